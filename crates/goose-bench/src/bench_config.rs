@@ -1,4 +1,5 @@
 use crate::bench_work_dir::BenchmarkWorkDir;
+use crate::eval_suites::load_suite_file;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::fs::read_to_string;
@@ -34,6 +35,12 @@ pub struct BenchRunConfig {
     pub eval_result_filename: String,
     pub run_summary_filename: String,
     pub env_file: Option<PathBuf>,
+    /// YAML suite files whose evals are registered under `yaml:<suite>:<eval>` selectors
+    #[serde(default)]
+    pub suite_files: Vec<PathBuf>,
+    /// Commit the results are recorded against; detected when the run starts if unset
+    #[serde(default)]
+    pub commit: Option<String>,
 }
 
 impl Default for BenchRunConfig {
@@ -68,6 +75,8 @@ impl Default for BenchRunConfig {
             eval_result_filename: "eval-results.json".to_string(),
             run_summary_filename: "run-results-summary.json".to_string(),
             env_file: None,
+            suite_files: vec![],
+            commit: None,
         }
     }
 }
@@ -77,6 +86,11 @@ impl BenchRunConfig {
         // update include_dirs to contain full-paths only
         config.include_dirs = BenchmarkWorkDir::canonical_dirs(config.include_dirs);
         Self::canonicalize_eval_post_proc_cmd(&mut config);
+        // every bench sub-process parses the config, so this makes suite evals visible to all
+        config.suite_files = BenchmarkWorkDir::canonical_dirs(config.suite_files);
+        for suite_file in &config.suite_files {
+            load_suite_file(suite_file)?;
+        }
         Ok(config)
     }
 
@@ -21,6 +21,10 @@ pub trait BenchBaseSession: Send + Sync {
     fn session_file(&self) -> Option<PathBuf>;
     fn message_history(&self) -> Conversation;
     fn get_total_token_usage(&self) -> anyhow::Result<Option<i32>>;
+    /// Accumulated (input, output) token counts, used to estimate run cost
+    fn get_token_breakdown(&self) -> anyhow::Result<Option<(i32, i32)>> {
+        Ok(None)
+    }
 }
 // struct for managing agent-session-access. to be passed to evals for benchmarking
 pub struct BenchAgent {
@@ -52,6 +56,9 @@ impl BenchAgent {
     pub(crate) async fn get_token_usage(&self) -> Option<i32> {
         self.session.get_total_token_usage().ok().flatten()
     }
+    pub(crate) fn get_token_breakdown(&self) -> Option<(i32, i32)> {
+        self.session.get_token_breakdown().ok().flatten()
+    }
     pub(crate) fn session_file(&self) -> Option<PathBuf> {
         self.session.session_file()
     }
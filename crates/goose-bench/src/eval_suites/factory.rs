@@ -4,7 +4,7 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::{OnceLock, RwLock};
 
-type EvaluationConstructor = fn() -> Box<dyn Evaluation>;
+type EvaluationConstructor = Box<dyn Fn() -> Box<dyn Evaluation> + Send + Sync>;
 type Registry = &'static RwLock<HashMap<&'static str, EvaluationConstructor>>;
 
 // Use std::sync::RwLock for interior mutability
@@ -18,9 +18,17 @@ fn eval_registry() -> Registry {
 
 /// Register a new evaluation version
 pub fn register_eval(selector: &'static str, constructor: fn() -> Box<dyn Evaluation>) {
+    register_eval_fn(selector, constructor);
+}
+
+/// Register an evaluation whose constructor captures state, e.g. one loaded from a suite file
+pub fn register_eval_fn<F>(selector: &'static str, constructor: F)
+where
+    F: Fn() -> Box<dyn Evaluation> + Send + Sync + 'static,
+{
     let registry = eval_registry();
     if let Ok(mut map) = registry.write() {
-        map.insert(selector, constructor);
+        map.insert(selector, Box::new(constructor));
     }
 }
 
@@ -58,6 +58,17 @@ pub async fn collect_baseline_metrics(
         );
     }
 
+    if let Some((input_tokens, output_tokens)) = agent.get_token_breakdown() {
+        metrics.insert(
+            "input_tokens".to_string(),
+            EvalMetricValue::Integer(input_tokens as i64),
+        );
+        metrics.insert(
+            "output_tokens".to_string(),
+            EvalMetricValue::Integer(output_tokens as i64),
+        );
+    }
+
     (messages, metrics)
 }
 
@@ -4,8 +4,10 @@ mod factory;
 mod metrics;
 mod utils;
 mod vibes;
+mod yaml_suite;
 
 pub use evaluation::*;
-pub use factory::{register_eval, EvaluationSuite};
+pub use factory::{register_eval, register_eval_fn, EvaluationSuite};
 pub use metrics::*;
 pub use utils::*;
+pub use yaml_suite::{load_suite_file, Grader, YamlEvalSpec, YamlSuite};
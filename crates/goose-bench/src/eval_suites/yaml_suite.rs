@@ -0,0 +1,278 @@
+// Evaluations defined declaratively in YAML suite files rather than in Rust.
+
+use crate::bench_session::BenchAgent;
+use crate::bench_work_dir::BenchmarkWorkDir;
+use crate::eval_suites::{
+    collect_baseline_metrics, metrics_hashmap_to_vec, register_eval_fn, used_tool, EvalMetricValue,
+    Evaluation, ExtensionRequirements,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use goose::conversation::message::Message;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A suite file: a named group of evaluations sharing a selector prefix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YamlSuite {
+    pub name: String,
+    pub evals: Vec<YamlEvalSpec>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct YamlExtensions {
+    #[serde(default)]
+    pub builtin: Vec<String>,
+    #[serde(default)]
+    pub external: Vec<String>,
+    #[serde(default)]
+    pub remote: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YamlEvalSpec {
+    pub name: String,
+    /// The task given to the agent. `{{ key }}` placeholders are filled from `inputs`.
+    pub prompt: String,
+    #[serde(default)]
+    pub inputs: HashMap<String, String>,
+    #[serde(default)]
+    pub extensions: YamlExtensions,
+    #[serde(default)]
+    pub assertions: Vec<Grader>,
+}
+
+/// A single check run against the outcome of an evaluation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Grader {
+    FileExists { path: PathBuf },
+    FileContains { path: PathBuf, pattern: String },
+    ResponseContains { text: String },
+    ResponseMatches { pattern: String },
+    ToolUsed { name: String },
+    CommandSucceeds { command: String },
+}
+
+impl Grader {
+    fn label(&self) -> String {
+        match self {
+            Grader::FileExists { path } => format!("file_exists {}", path.display()),
+            Grader::FileContains { path, .. } => format!("file_contains {}", path.display()),
+            Grader::ResponseContains { text } => format!("response_contains {}", text),
+            Grader::ResponseMatches { pattern } => format!("response_matches {}", pattern),
+            Grader::ToolUsed { name } => format!("tool_used {}", name),
+            Grader::CommandSucceeds { command } => format!("command_succeeds {}", command),
+        }
+    }
+
+    fn grade(&self, messages: &[Message]) -> bool {
+        let last_response = || {
+            messages
+                .last()
+                .map(|m| m.as_concat_text())
+                .unwrap_or_default()
+        };
+        match self {
+            Grader::FileExists { path } => path.exists(),
+            Grader::FileContains { path, pattern } => {
+                let Ok(content) = std::fs::read_to_string(path) else {
+                    return false;
+                };
+                Regex::new(pattern).is_ok_and(|re| re.is_match(&content))
+            }
+            Grader::ResponseContains { text } => last_response().contains(text.as_str()),
+            Grader::ResponseMatches { pattern } => {
+                Regex::new(pattern).is_ok_and(|re| re.is_match(&last_response()))
+            }
+            Grader::ToolUsed { name } => used_tool(messages, name),
+            Grader::CommandSucceeds { command } => shell_command(command)
+                .status()
+                .is_ok_and(|status| status.success()),
+        }
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+impl YamlEvalSpec {
+    fn render_prompt(&self) -> String {
+        self.inputs
+            .iter()
+            .fold(self.prompt.clone(), |prompt, (key, value)| {
+                prompt
+                    .replace(&format!("{{{{ {} }}}}", key), value)
+                    .replace(&format!("{{{{{}}}}}", key), value)
+            })
+    }
+}
+
+pub struct YamlEvaluation {
+    spec: YamlEvalSpec,
+}
+
+#[async_trait]
+impl Evaluation for YamlEvaluation {
+    async fn run(
+        &self,
+        agent: &mut BenchAgent,
+        _run_loc: &mut BenchmarkWorkDir,
+    ) -> Result<Vec<(String, EvalMetricValue)>> {
+        let (messages, perf_metrics) =
+            collect_baseline_metrics(agent, self.spec.render_prompt()).await;
+        let mut metrics = metrics_hashmap_to_vec(perf_metrics);
+
+        let mut passed = 0;
+        for grader in &self.spec.assertions {
+            let ok = grader.grade(messages.messages());
+            if ok {
+                passed += 1;
+            }
+            metrics.push((grader.label(), EvalMetricValue::Boolean(ok)));
+        }
+
+        let total = self.spec.assertions.len();
+        let score = if total == 0 {
+            1.0
+        } else {
+            passed as f64 / total as f64
+        };
+        metrics.push((
+            "passed".to_string(),
+            EvalMetricValue::Boolean(passed == total),
+        ));
+        metrics.push(("score".to_string(), EvalMetricValue::Float(score)));
+
+        Ok(metrics)
+    }
+
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn required_extensions(&self) -> ExtensionRequirements {
+        ExtensionRequirements {
+            builtin: self.spec.extensions.builtin.clone(),
+            external: self.spec.extensions.external.clone(),
+            remote: self.spec.extensions.remote.clone(),
+        }
+    }
+}
+
+// Selectors are split on ':' and matched with \w, so keep names to word characters
+fn selector_part(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl YamlSuite {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read eval suite {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse eval suite {}", path.display()))
+    }
+
+    /// Selector for each eval, of the form `yaml:<suite>:<eval>`
+    pub fn selectors(&self) -> Vec<(String, &YamlEvalSpec)> {
+        self.evals
+            .iter()
+            .map(|spec| {
+                (
+                    format!(
+                        "yaml:{}:{}",
+                        selector_part(&self.name),
+                        selector_part(&spec.name)
+                    ),
+                    spec,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Load a YAML suite file and register each of its evals with the evaluation registry
+pub fn load_suite_file(path: &Path) -> Result<Vec<String>> {
+    let suite = YamlSuite::from_file(path)?;
+    let mut registered = Vec::new();
+    for (selector, spec) in suite.selectors() {
+        let spec = spec.clone();
+        let selector: &'static str = Box::leak(selector.into_boxed_str());
+        register_eval_fn(selector, move || {
+            Box::new(YamlEvaluation { spec: spec.clone() })
+        });
+        registered.push(selector.to_string());
+    }
+    Ok(registered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITE: &str = r#"
+name: file-ops
+evals:
+  - name: write greeting
+    prompt: "Write {{ greeting }} to hello.txt"
+    inputs:
+      greeting: "Hello"
+    extensions:
+      builtin: [developer]
+    assertions:
+      - type: file_exists
+        path: hello.txt
+      - type: response_matches
+        pattern: "(?i)done"
+"#;
+
+    #[test]
+    fn test_parse_suite_and_selectors() {
+        let suite: YamlSuite = serde_yaml::from_str(SUITE).unwrap();
+        let selectors = suite.selectors();
+        assert_eq!(selectors.len(), 1);
+        assert_eq!(selectors[0].0, "yaml:file_ops:write_greeting");
+
+        let spec = selectors[0].1;
+        assert_eq!(spec.render_prompt(), "Write Hello to hello.txt");
+        assert_eq!(spec.extensions.builtin, vec!["developer".to_string()]);
+        assert_eq!(spec.assertions.len(), 2);
+    }
+
+    #[test]
+    fn test_response_graders() {
+        let messages = vec![Message::assistant().with_text("All done!")];
+        assert!(Grader::ResponseContains {
+            text: "done".to_string()
+        }
+        .grade(&messages));
+        assert!(!Grader::ResponseMatches {
+            pattern: "^failed".to_string()
+        }
+        .grade(&messages));
+        assert!(!Grader::ToolUsed {
+            name: "developer__shell".to_string()
+        }
+        .grade(&messages));
+    }
+}
@@ -131,3 +131,139 @@ impl fmt::Display for BenchmarkResults {
         Ok(())
     }
 }
+
+/// Aggregated results for one model across every run and evaluation
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ModelComparison {
+    pub provider: String,
+    pub model: String,
+    pub evaluations: usize,
+    pub passed: usize,
+    pub pass_rate: f64,
+    pub mean_score: f64,
+    pub total_tokens: i64,
+    pub cost_usd: Option<f64>,
+}
+
+/// Side-by-side comparison of every model in a benchmark run
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ComparisonReport {
+    pub generated_at: String,
+    pub models: Vec<ModelComparison>,
+}
+
+impl EvaluationResult {
+    fn metric(&self, name: &str) -> Option<&EvalMetricValue> {
+        self.metrics
+            .iter()
+            .find(|(metric, _)| metric == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn score(&self) -> Option<f64> {
+        match self.metric("score")? {
+            EvalMetricValue::Float(score) => Some(*score),
+            EvalMetricValue::Integer(score) => Some(*score as f64),
+            EvalMetricValue::Boolean(passed) => Some(if *passed { 1.0 } else { 0.0 }),
+            EvalMetricValue::String(_) => None,
+        }
+    }
+
+    /// An evaluation passes if it says so explicitly, otherwise if it scored full marks
+    pub fn passed(&self) -> bool {
+        match self.metric("passed") {
+            Some(EvalMetricValue::Boolean(passed)) => *passed,
+            _ => self.score().is_some_and(|score| score >= 1.0),
+        }
+    }
+}
+
+impl ComparisonReport {
+    pub fn new() -> Self {
+        Self {
+            generated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            models: Vec::new(),
+        }
+    }
+
+    pub fn add_model(&mut self, provider: String, model: String, runs: &[BenchmarkResults]) {
+        let evaluations: Vec<&EvaluationResult> = runs
+            .iter()
+            .flat_map(|run| &run.suites)
+            .flat_map(|suite| &suite.evaluations)
+            .collect();
+
+        let passed = evaluations.iter().filter(|e| e.passed()).count();
+        let scores: Vec<f64> = evaluations.iter().filter_map(|e| e.score()).collect();
+        let total_tokens = evaluations
+            .iter()
+            .filter_map(|e| match e.metric("total_tokens") {
+                Some(EvalMetricValue::Integer(tokens)) => Some(*tokens),
+                _ => None,
+            })
+            .sum();
+        let costs: Vec<f64> = evaluations
+            .iter()
+            .filter_map(|e| match e.metric("cost_usd") {
+                Some(EvalMetricValue::Float(cost)) => Some(*cost),
+                _ => None,
+            })
+            .collect();
+
+        let ratio = |n: f64, d: usize| if d == 0 { 0.0 } else { n / d as f64 };
+        self.models.push(ModelComparison {
+            provider,
+            model,
+            evaluations: evaluations.len(),
+            passed,
+            pass_rate: ratio(passed as f64, evaluations.len()),
+            mean_score: ratio(scores.iter().sum(), scores.len()),
+            total_tokens,
+            cost_usd: (!costs.is_empty()).then(|| costs.iter().sum()),
+        });
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str("<title>Goose Benchmark Comparison</title>\n");
+        html.push_str(
+            "<style>body{font-family:sans-serif}table{border-collapse:collapse}\
+             th,td{border:1px solid #ccc;padding:4px 8px;text-align:right}\
+             th:first-child,td:first-child{text-align:left}</style>\n",
+        );
+        html.push_str("</head>\n<body>\n");
+        html.push_str(&format!(
+            "<h1>Benchmark Comparison</h1>\n<p>Generated at {}</p>\n",
+            html_escape(&self.generated_at)
+        ));
+        html.push_str(
+            "<table>\n<tr><th>Model</th><th>Evaluations</th><th>Passed</th>\
+             <th>Pass rate</th><th>Mean score</th><th>Tokens</th><th>Cost (USD)</th></tr>\n",
+        );
+        for row in &self.models {
+            html.push_str(&format!(
+                "<tr><td>{}/{}</td><td>{}</td><td>{}</td><td>{:.1}%</td><td>{:.2}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&row.provider),
+                html_escape(&row.model),
+                row.evaluations,
+                row.passed,
+                row.pass_rate * 100.0,
+                row.mean_score,
+                row.total_tokens,
+                row.cost_usd
+                    .map(|cost| format!("{:.4}", cost))
+                    .unwrap_or_else(|| "-".to_string()),
+            ));
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
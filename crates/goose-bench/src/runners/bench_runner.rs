@@ -1,9 +1,12 @@
 use crate::bench_config::{BenchModel, BenchRunConfig};
 use crate::bench_work_dir::BenchmarkWorkDir;
 use crate::eval_suites::EvaluationSuite;
+use crate::reporting::{BenchmarkResults, ComparisonReport};
 use crate::runners::model_runner::ModelRunner;
 use crate::utilities::{await_process_exits, parallel_bench_cmd};
 use anyhow::Context;
+use goose::bench_results;
+use std::fs;
 use std::path::PathBuf;
 
 #[derive(Clone)]
@@ -13,7 +16,10 @@ pub struct BenchRunner {
 
 impl BenchRunner {
     pub fn new(config_path: PathBuf) -> anyhow::Result<BenchRunner> {
-        let config = BenchRunConfig::from(config_path.clone())?;
+        let mut config = BenchRunConfig::from(config_path.clone())?;
+        if config.commit.is_none() {
+            config.commit = bench_results::current_commit();
+        }
 
         let resolved_output_dir = match &config.output_dir {
             Some(path) => {
@@ -43,6 +49,8 @@ impl BenchRunner {
     }
 
     pub fn run(&mut self) -> anyhow::Result<()> {
+        let all_models = self.config.models.clone();
+
         // split models that must run serial from those that can be run in parallel
         let (parallel_models, serial_models): &(Vec<BenchModel>, Vec<BenchModel>) = &self
             .config
@@ -68,6 +76,35 @@ impl BenchRunner {
 
         await_process_exits(&mut parallel_models_handle, Vec::new());
 
+        self.config.models = all_models;
+        self.write_comparison_report()?;
+
+        Ok(())
+    }
+
+    /// Summarize every model's run results into comparison-report.{json,html}
+    fn write_comparison_report(&self) -> anyhow::Result<()> {
+        let mut report = ComparisonReport::new();
+        for model in &self.config.models {
+            let runs: Vec<BenchmarkResults> = (0..self.config.repeat.unwrap_or(1))
+                .filter_map(|run| {
+                    let summary = PathBuf::from(format!("{}-{}", model.provider, model.name))
+                        .join(format!("run-{}", run))
+                        .join(&self.config.run_summary_filename);
+                    let content = fs::read_to_string(&summary).ok()?;
+                    serde_json::from_str(&content).ok()
+                })
+                .collect();
+            report.add_model(model.provider.clone(), model.name.clone(), &runs);
+        }
+
+        fs::write(
+            "comparison-report.json",
+            serde_json::to_string_pretty(&report)?,
+        )
+        .context("Failed to write comparison-report.json")?;
+        fs::write("comparison-report.html", report.to_html())
+            .context("Failed to write comparison-report.html")?;
         Ok(())
     }
 
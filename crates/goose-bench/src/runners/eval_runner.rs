@@ -1,10 +1,13 @@
 use crate::bench_config::{BenchEval, BenchModel, BenchRunConfig};
 use crate::bench_session::BenchAgent;
 use crate::bench_work_dir::BenchmarkWorkDir;
-use crate::eval_suites::{EvaluationSuite, ExtensionRequirements};
+use crate::eval_suites::{EvalMetricValue, EvaluationSuite, ExtensionRequirements};
 use crate::reporting::EvaluationResult;
 use crate::utilities::await_process_exits;
 use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use goose::bench_results::{self, BenchRecord, BenchResultStore};
+use goose::providers::pricing::get_model_pricing;
 use std::env;
 use std::fs;
 use std::future::Future;
@@ -105,6 +108,12 @@ impl EvalRunner {
                 }
             }
 
+            if let Some(model) = self.config.models.first() {
+                if let Some(cost) = Self::estimate_cost(model, &result).await {
+                    result.add_metric("cost_usd".to_string(), EvalMetricValue::Float(cost));
+                }
+            }
+
             // Add any errors that occurred
             let errors = agent.get_errors().await;
             tracing::info!("Agent reported {} errors", errors.len());
@@ -132,6 +141,10 @@ impl EvalRunner {
                 eval_results_file.display()
             );
 
+            if let Err(e) = self.record_result(bench_eval, &result) {
+                tracing::warn!("Failed to record result in the benchmark history: {}", e);
+            }
+
             self.config.save("config.cfg".to_string());
             work_dir.save();
 
@@ -174,6 +187,49 @@ impl EvalRunner {
         Ok(())
     }
 
+    /// Keep the result in the benchmark history so trends can be followed across runs
+    fn record_result(&self, bench_eval: &BenchEval, result: &EvaluationResult) -> Result<()> {
+        let model = self
+            .config
+            .models
+            .first()
+            .context("No model specified in configuration")?;
+        let (suite, eval) = bench_results::split_selector(&bench_eval.selector);
+        let metrics: serde_json::Map<String, serde_json::Value> = result
+            .metrics
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), serde_json::to_value(value)?)))
+            .collect::<Result<_>>()?;
+        BenchResultStore::open_default()?.record(&BenchRecord {
+            recorded_at: Utc::now(),
+            provider: model.provider.clone(),
+            model: model.name.clone(),
+            suite,
+            eval,
+            commit: self.config.commit.clone(),
+            passed: result.passed(),
+            score: result.score(),
+            errors: result.errors.len(),
+            metrics: serde_json::Value::Object(metrics),
+        })
+    }
+
+    async fn estimate_cost(model: &BenchModel, result: &EvaluationResult) -> Option<f64> {
+        let token_metric = |name: &str| {
+            result
+                .metrics
+                .iter()
+                .find_map(|(metric, value)| match value {
+                    EvalMetricValue::Integer(count) if metric == name => Some(*count as f64),
+                    _ => None,
+                })
+        };
+        let input_tokens = token_metric("input_tokens")?;
+        let output_tokens = token_metric("output_tokens")?;
+        let pricing = get_model_pricing(&model.provider, &model.name).await?;
+        Some(input_tokens * pricing.input_cost + output_tokens * pricing.output_cost)
+    }
+
     pub fn path_for_eval(model: &BenchModel, eval: &BenchEval, run_id: String) -> PathBuf {
         let provider = model.provider.clone();
         let model = model.name.clone();
@@ -1,22 +1,42 @@
 use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
 
-use goose::config::{Config, ExtensionConfig};
+use goose::backup::BackupCategory;
+use goose::config::{Config, ConversationTemplate, ExtensionConfig};
+use goose::scheduler::JobEnvironment;
 
+use crate::ci::GithubContext;
+use crate::commands::backup::{handle_backup_create, handle_backup_restore};
 use crate::commands::bench::agent_generator;
+use crate::commands::config::{
+    handle_config_export, handle_config_import, handle_config_migrate_secrets, handle_config_sync,
+};
 use crate::commands::configure::handle_configure;
+use crate::commands::doctor::{handle_doctor, handle_doctor_bundle};
+use crate::commands::extension::{handle_extension_audit, handle_grant_plugin};
+use crate::commands::governance::{
+    handle_governance_export, handle_governance_holds, handle_governance_purge,
+    handle_governance_release,
+};
 use crate::commands::info::handle_info;
 use crate::commands::mcp::run_server;
-use crate::commands::recipe::{handle_deeplink, handle_list, handle_validate};
+use crate::commands::recipe::{handle_deeplink, handle_list, handle_test, handle_validate};
 // Import the new handlers from commands::schedule
 use crate::commands::schedule::{
     handle_schedule_add, handle_schedule_cron_help, handle_schedule_list, handle_schedule_remove,
     handle_schedule_run_now, handle_schedule_services_status, handle_schedule_services_stop,
     handle_schedule_sessions,
 };
+use crate::commands::self_update::{handle_self_rollback, handle_self_update, ReleaseChannel};
 use crate::commands::session::{handle_session_list, handle_session_remove};
+use crate::commands::template::{
+    handle_template_list, handle_template_remove, handle_template_save, handle_template_start,
+};
+use crate::commands::top::handle_top;
+use crate::commands::watch::handle_watch;
 use crate::recipes::extract_from_cli::extract_recipe_info_from_cli;
 use crate::recipes::recipe::{explain_recipe, render_recipe_as_yaml};
+use crate::recipes::recipe_test::RecipeTestOptions;
 use crate::session;
 use crate::session::{build_session, SessionBuilderConfig, SessionSettings};
 use goose_bench::bench_config::BenchRunConfig;
@@ -26,10 +46,15 @@ use goose_bench::runners::metric_aggregator::MetricAggregator;
 use goose_bench::runners::model_runner::ModelRunner;
 use std::io::Read;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(author, version, display_name = "", about, long_about = None)]
 struct Cli {
+    /// Plain output for screen readers and dumb terminals: no colors, spinners or box drawing
+    #[arg(long, global = true)]
+    plain: bool,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -121,6 +146,263 @@ enum SessionCommand {
         )]
         output: Option<PathBuf>,
     },
+    #[command(about = "Sync sessions with the cloud storage configured under GOOSE_SESSION_SYNC")]
+    Sync,
+    #[command(
+        about = "Continue a session handed off from another client",
+        long_about = "Continue a live session handed off from the desktop app or another client. The token comes from \"continue this session elsewhere\" and works once, for ten minutes. Tool calls that were waiting for approval are asked about again here."
+    )]
+    Attach {
+        #[arg(value_name = "TOKEN", help = "Handoff token, e.g. K3PQ-X2MA")]
+        token: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    #[command(about = "Export your config as YAML, leaving out credentials")]
+    Export {
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Write to a file instead of stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Import config from an exported YAML file")]
+    Import {
+        #[arg(value_name = "FILE", help = "YAML file to import")]
+        file: PathBuf,
+
+        #[arg(
+            long,
+            help = "Replace your config instead of merging into it",
+            long_help = "Replace your config with the file, keeping only settings about this machine such as the team config source, trusted projects, session roots and legal holds. By default imported values are merged over your existing config."
+        )]
+        replace: bool,
+    },
+    #[command(about = "Fetch the shared team config")]
+    Sync {
+        #[arg(
+            value_name = "SOURCE",
+            help = "https URL or git+<repo>[#<path>]; saved as GOOSE_TEAM_CONFIG",
+            long_help = "Where to fetch the team config from: an https URL of a YAML file, or git+<repo url>[#<path in repo>] (path defaults to config.yaml). The source is saved as GOOSE_TEAM_CONFIG so later syncs can omit it. Team settings apply beneath your own config, so local values always win."
+        )]
+        source: Option<String>,
+    },
+    #[command(about = "Move plaintext credentials from config.yaml into the system keychain")]
+    MigrateSecrets,
+}
+
+#[derive(Subcommand)]
+enum BackupCommand {
+    #[command(about = "Write sessions, config, recipes, memories and schedules to one archive")]
+    Create {
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Archive to write (default: goose-backup-<timestamp>.zip in goose's backups directory)"
+        )]
+        output: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "CATEGORIES",
+            value_delimiter = ',',
+            help = "Only back up these: sessions, config, recipes, memories, schedules"
+        )]
+        only: Vec<BackupCategory>,
+    },
+    #[command(about = "Restore from a backup archive")]
+    Restore {
+        #[arg(value_name = "FILE", help = "Archive created by `goose backup create`")]
+        archive: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "CATEGORIES",
+            value_delimiter = ',',
+            help = "Only restore these: sessions, config, recipes, memories, schedules"
+        )]
+        only: Vec<BackupCategory>,
+
+        #[arg(long, help = "Replace files that already exist")]
+        overwrite: bool,
+
+        #[arg(long, help = "List the archive contents without restoring anything")]
+        list: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum GovernanceCommand {
+    #[command(
+        about = "Export every session, audit record and memory of a project into one archive"
+    )]
+    Export {
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Working directory of the project to export (default: all sessions and global memories)"
+        )]
+        project: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Archive to write (default: goose-export-<timestamp>.zip)"
+        )]
+        output: Option<PathBuf>,
+
+        #[arg(long, help = "Also freeze the exported data against deletion")]
+        hold: bool,
+
+        #[arg(long, value_name = "TEXT", help = "Why the data is being held")]
+        reason: Option<String>,
+    },
+    #[command(
+        about = "Delete every session, attachment, audit record, memory, exemplar and alert of a project"
+    )]
+    Purge {
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Working directory of the project to purge (default: all data)"
+        )]
+        project: Option<PathBuf>,
+
+        #[arg(long, help = "Only report what would be removed")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Don't ask for confirmation")]
+        yes: bool,
+    },
+    #[command(about = "List legal holds in place")]
+    Holds,
+    #[command(about = "Release a legal hold")]
+    Release {
+        #[arg(value_name = "ID", help = "ID of the hold to release")]
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExtensionCommand {
+    #[command(
+        about = "Probe an extension's tools with adversarial inputs",
+        long_about = "Call each of the extension's tools with prompt-injection strings, invisible Unicode, bidirectional overrides and oversized input, and report what from their outputs would reach the model after goose's own handling of tool results. The tools really run, so audit untrusted extensions in a sandbox."
+    )]
+    Audit {
+        #[arg(value_name = "NAME", help = "Name of a configured extension")]
+        name: String,
+
+        #[arg(
+            long = "tool",
+            value_name = "TOOL",
+            help = "Only probe this tool (can be specified multiple times)",
+            action = clap::ArgAction::Append
+        )]
+        tools: Vec<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Don't ask for confirmation before calling the tools"
+        )]
+        yes: bool,
+    },
+    #[command(
+        name = "grant-plugin",
+        about = "Review and approve a native plugin library",
+        long_about = "Show the tools and capabilities a native plugin declares and, once you confirm, allow that exact library to load. A changed library has to be approved again."
+    )]
+    GrantPlugin {
+        #[arg(value_name = "PATH", help = "Path to the plugin's shared library")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum SelfCommand {
+    #[command(
+        about = "Update goose to a signed release",
+        long_about = "Download the release for this platform, verify its signature and replace the running binary. Follows GOOSE_UPDATE_CHANNEL (stable or beta) unless GOOSE_PINNED_VERSION is set."
+    )]
+    Update {
+        #[arg(
+            long,
+            value_name = "VERSION",
+            help = "Install this version instead of the latest"
+        )]
+        version: Option<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "Release channel to follow; defaults to GOOSE_UPDATE_CHANNEL or stable"
+        )]
+        channel: Option<ReleaseChannel>,
+    },
+    #[command(
+        about = "Go back to the goose that the last update replaced",
+        long_about = "Put back the binary kept by the last `goose self update`, without downloading anything. Running it again returns to the newer version."
+    )]
+    Rollback {},
+}
+
+#[derive(Subcommand)]
+enum TemplateCommand {
+    #[command(about = "List saved conversation templates")]
+    List {
+        #[arg(
+            short,
+            long,
+            help = "Show each template's prompt, context and extensions"
+        )]
+        verbose: bool,
+    },
+    #[command(about = "Save a conversation template, replacing any with the same name")]
+    Save {
+        #[arg(help = "Name of the template (letters, numbers, '-' and '_')")]
+        name: String,
+
+        #[arg(short, long, help = "The opening prompt of the conversation")]
+        prompt: String,
+
+        #[arg(short, long, help = "Short description of the template")]
+        description: Option<String>,
+
+        #[arg(
+            long = "context",
+            value_name = "FILE",
+            help = "File to attach to the prompt (can be specified multiple times)",
+            long_help = "File whose contents are attached to the prompt. PDF, docx, xlsx and pptx documents are converted to text with page, sheet and slide references, up to GOOSE_DOCUMENT_MAX_TOKENS tokens each.",
+            action = clap::ArgAction::Append
+        )]
+        context: Vec<String>,
+
+        #[arg(
+            long = "with-extension",
+            value_name = "NAME",
+            help = "Configured extension to enable (can be specified multiple times)",
+            long_help = "Name of a configured extension to enable when the template starts. If any are given, only these extensions are enabled.",
+            action = clap::ArgAction::Append
+        )]
+        extensions: Vec<String>,
+    },
+    #[command(about = "Remove a conversation template")]
+    Remove {
+        #[arg(help = "Name of the template to remove")]
+        name: String,
+    },
+    #[command(about = "Start a new session from a template")]
+    Start {
+        #[arg(help = "Name of the template to start")]
+        name: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -131,15 +413,53 @@ enum SchedulerCommand {
         id: String,
         #[arg(
             long,
+            required_unless_present_any = ["run_at", "after"],
             help = "Cron expression for the schedule",
             long_help = "Cron expression for when to run the job. Examples:\n  '0 * * * *'     - Every hour at minute 0\n  '0 */2 * * *'   - Every 2 hours\n  '@hourly'       - Every hour (shorthand)\n  '0 9 * * *'     - Every day at 9:00 AM\n  '0 9 * * 1'     - Every Monday at 9:00 AM\n  '0 0 1 * *'     - First day of every month at midnight"
         )]
-        cron: String,
+        cron: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["cron", "after"],
+            help = "Run once at this time (RFC 3339, e.g. 2025-07-01T09:00:00Z); needs GOOSE_SCHEDULER_TYPE=sqlite"
+        )]
+        run_at: Option<chrono::DateTime<chrono::Utc>>,
+        #[arg(
+            long,
+            value_name = "JOB_ID",
+            conflicts_with = "cron",
+            help = "Run once this job has succeeded; repeat to wait for several jobs. Needs GOOSE_SCHEDULER_TYPE=sqlite"
+        )]
+        after: Vec<String>,
         #[arg(
             long,
             help = "Recipe source (path to file, or base64 encoded recipe string)"
         )]
         recipe_source: String,
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Directory the job runs in, instead of the scheduler's"
+        )]
+        working_dir: Option<String>,
+        #[arg(
+            long,
+            value_name = "KEY=VALUE",
+            help = "Environment variable for the job's extensions; can be repeated",
+            action = clap::ArgAction::Append,
+            value_parser = parse_key_val,
+        )]
+        env: Vec<(String, String)>,
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Configured extension to enable for the job, besides the recipe's; can be repeated"
+        )]
+        extension: Vec<String>,
+        #[arg(long, help = "Provider for the job, instead of GOOSE_PROVIDER")]
+        provider: Option<String>,
+        #[arg(long, help = "Model for the job, instead of GOOSE_MODEL")]
+        model: Option<String>,
     },
     #[command(about = "List all scheduled jobs")]
     List {},
@@ -270,6 +590,62 @@ enum RecipeCommand {
         )]
         verbose: bool,
     },
+
+    /// Run a recipe's test cases and check their success criteria
+    #[command(about = "Test a recipe against recorded fixtures")]
+    Test {
+        /// Recipe name to get recipe file to test
+        #[arg(help = "recipe name to get recipe file or full path to the recipe file to test")]
+        recipe_name: String,
+
+        /// Path to the test spec
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Test spec file (defaults to <recipe>.test.yaml next to the recipe)"
+        )]
+        spec: Option<PathBuf>,
+
+        /// Directory holding recorded fixtures
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Fixtures directory (defaults to <recipe>.fixtures next to the recipe)"
+        )]
+        fixtures: Option<PathBuf>,
+
+        /// Run against the configured provider and record new fixtures
+        #[arg(
+            long,
+            help = "Run live against the configured provider, recording fixtures for passing cases"
+        )]
+        live: bool,
+
+        /// Maximum agent turns per case
+        #[arg(
+            long = "max-turns",
+            value_name = "NUMBER",
+            help = "Maximum agent turns per case",
+            default_value = "10"
+        )]
+        max_turns: u32,
+
+        /// Token budget per case
+        #[arg(
+            long = "max-tokens",
+            value_name = "NUMBER",
+            help = "Stop and fail a case once it has used this many tokens"
+        )]
+        max_tokens: Option<u32>,
+
+        /// Where to write a JUnit XML report
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write a JUnit XML report to this file"
+        )]
+        junit: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -286,6 +662,33 @@ enum Command {
         verbose: bool,
     },
 
+    /// Gather diagnostics for bug reports
+    #[command(
+        about = "Diagnose problems with your goose setup",
+        long_about = "Check provider credentials, configured MCP servers, disk space, clock and tokenizer, with suggested fixes."
+    )]
+    Doctor {
+        #[arg(
+            long,
+            help = "Write a zip of version info, redacted config and recent logs",
+            long_help = "Write a zip to attach to bug reports: version info, your config with credentials redacted, the most recent logs and, with --name or --path, that session's metadata."
+        )]
+        bundle: bool,
+
+        /// Session to include metadata for
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Where to write the bundle",
+            requires = "bundle"
+        )]
+        output: Option<PathBuf>,
+    },
+
     /// Manage system prompts and behaviors
     #[command(about = "Run one of the mcp servers bundled with goose")]
     Mcp { name: String },
@@ -583,6 +986,15 @@ enum Command {
         )]
         additional_sub_recipes: Vec<String>,
 
+        /// Report results to GitHub Actions
+        #[arg(
+            long = "ci",
+            help = "Run as a GitHub Actions step, reporting results as a check run and step summary",
+            long_help = "Run as a GitHub Actions step: the workflow event is described to the agent, findings in its reply become annotations on a `goose` check run (needs `checks: write`), the reply is added to the step summary, and the command fails if any error was reported.",
+            conflicts_with = "interactive"
+        )]
+        ci: bool,
+
         /// Provider to use for this run (overrides environment variable)
         #[arg(
             long = "provider",
@@ -602,6 +1014,105 @@ enum Command {
         model: Option<String>,
     },
 
+    /// Rerun a recipe whenever files in a directory change
+    #[command(
+        about = "Watch a directory and run a recipe each time files change",
+        long_about = "Watch a directory and run a recipe each time files in it change, telling the agent which files changed. Runs share one session, so the agent remembers earlier runs. Hidden and gitignored files are not watched."
+    )]
+    Watch {
+        /// Directory to watch
+        #[arg(value_name = "DIR", default_value = ".")]
+        dir: PathBuf,
+
+        /// Recipe name or full path to the recipe file
+        #[arg(
+            long = "recipe",
+            value_name = "RECIPE_NAME or FULL_PATH_TO_RECIPE_FILE",
+            help = "Recipe to run when files change"
+        )]
+        recipe: String,
+
+        #[arg(
+            long,
+            value_name = "KEY=VALUE",
+            help = "Dynamic parameters (e.g., --params username=alice --params channel_name=goose-channel)",
+            long_help = "Key-value parameters to pass to the recipe file. Can be specified multiple times.",
+            action = clap::ArgAction::Append,
+            value_parser = parse_key_val,
+        )]
+        params: Vec<(String, String)>,
+
+        /// Quiet period before a run starts
+        #[arg(
+            long = "debounce",
+            value_name = "MILLISECONDS",
+            default_value = "2000",
+            help = "How long files must stop changing before a run starts"
+        )]
+        debounce: u64,
+
+        /// Identifier for the watch session
+        #[command(flatten)]
+        identifier: Option<Identifier>,
+
+        /// Resume a previous watch session
+        #[arg(
+            short,
+            long,
+            action = clap::ArgAction::SetTrue,
+            help = "Resume from a previous session"
+        )]
+        resume: bool,
+
+        /// Maximum number of turns (iterations) allowed in a single run
+        #[arg(
+            long = "max-turns",
+            value_name = "NUMBER",
+            help = "Maximum number of turns allowed in each run (default: 1000)"
+        )]
+        max_turns: Option<u32>,
+
+        /// Enable debug output mode
+        #[arg(
+            long,
+            help = "Enable debug output mode with full content and no truncation"
+        )]
+        debug: bool,
+    },
+
+    /// Live dashboard for a running goosed
+    #[command(
+        about = "Show active runs, pending approvals and schedules on a goose server",
+        long_about = "A live terminal dashboard for a running goosed: active replies with their token burn rate, tool calls waiting for approval, and schedules. Select a row to cancel a run or answer an approval."
+    )]
+    Top {
+        /// Address of the goose server
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "http://127.0.0.1:3000",
+            help = "Address of the goose server"
+        )]
+        url: String,
+
+        /// Secret key of the goose server
+        #[arg(
+            long = "secret-key",
+            value_name = "KEY",
+            help = "The server's secret key (defaults to GOOSE_SERVER__SECRET_KEY)"
+        )]
+        secret_key: Option<String>,
+
+        /// Seconds between refreshes
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value = "2",
+            help = "Seconds between refreshes"
+        )]
+        interval: u64,
+    },
+
     /// Recipe utilities for validation and deeplinking
     #[command(about = "Recipe utilities for validation and deeplinking")]
     Recipe {
@@ -609,6 +1120,43 @@ enum Command {
         command: RecipeCommand,
     },
 
+    /// Share config between machines
+    #[command(about = "Export, import and sync configuration")]
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Back up and restore local goose data
+    #[command(
+        about = "Create or restore a backup of sessions, config, recipes, memories and schedules"
+    )]
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommand,
+    },
+
+    /// Answer data-governance requests
+    #[command(about = "Export a project's data and place or release legal holds on it")]
+    Governance {
+        #[command(subcommand)]
+        command: GovernanceCommand,
+    },
+
+    /// Vet extensions before enabling them
+    #[command(about = "Inspect configured extensions")]
+    Extension {
+        #[command(subcommand)]
+        command: ExtensionCommand,
+    },
+
+    /// Saved conversation starters
+    #[command(about = "Manage saved conversation templates")]
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommand,
+    },
+
     /// Manage scheduled jobs
     #[command(about = "Manage scheduled jobs", visible_alias = "sched")]
     Schedule {
@@ -633,6 +1181,13 @@ enum Command {
         reconfigure: bool,
     },
 
+    /// Manage the goose installation itself
+    #[command(name = "self", about = "Manage the goose installation")]
+    SelfCmd {
+        #[command(subcommand)]
+        command: SelfCommand,
+    },
+
     /// Evaluate system configuration across a range of practical tasks
     #[command(about = "Evaluate system configuration across a range of practical tasks")]
     Bench {
@@ -686,21 +1241,32 @@ pub struct RecipeInfo {
     pub sub_recipes: Option<Vec<goose::recipe::SubRecipe>>,
     pub final_output_response: Option<goose::recipe::Response>,
     pub retry_config: Option<goose::agents::types::RetryConfig>,
+    pub critique: Option<goose::agents::types::CritiqueConfig>,
 }
 
 pub async fn cli() -> Result<()> {
     let cli = Cli::parse();
+    session::init_plain_mode(cli.plain);
 
     let command_name = match &cli.command {
         Some(Command::Configure {}) => "configure",
         Some(Command::Info { .. }) => "info",
+        Some(Command::Doctor { .. }) => "doctor",
         Some(Command::Mcp { .. }) => "mcp",
         Some(Command::Session { .. }) => "session",
         Some(Command::Run { .. }) => "run",
+        Some(Command::Watch { .. }) => "watch",
+        Some(Command::Top { .. }) => "top",
         Some(Command::Schedule { .. }) => "schedule",
         Some(Command::Update { .. }) => "update",
+        Some(Command::SelfCmd { .. }) => "self",
         Some(Command::Bench { .. }) => "bench",
         Some(Command::Recipe { .. }) => "recipe",
+        Some(Command::Config { .. }) => "config",
+        Some(Command::Backup { .. }) => "backup",
+        Some(Command::Governance { .. }) => "governance",
+        Some(Command::Extension { .. }) => "extension",
+        Some(Command::Template { .. }) => "template",
         Some(Command::Web { .. }) => "web",
         None => "default_session",
     };
@@ -720,6 +1286,18 @@ pub async fn cli() -> Result<()> {
             handle_info(verbose)?;
             return Ok(());
         }
+        Some(Command::Doctor {
+            bundle,
+            identifier,
+            output,
+        }) => {
+            if bundle {
+                handle_doctor_bundle(identifier.map(extract_identifier), output)?;
+            } else {
+                handle_doctor().await?;
+            }
+            return Ok(());
+        }
         Some(Command::Mcp { name }) => {
             run_server(&name).await?;
         }
@@ -766,6 +1344,46 @@ pub async fn cli() -> Result<()> {
                     crate::commands::session::handle_session_export(session_identifier, output)?;
                     Ok(())
                 }
+                Some(SessionCommand::Sync) => {
+                    crate::commands::session::handle_session_sync().await?;
+                    Ok(())
+                }
+                Some(SessionCommand::Attach { token }) => {
+                    let handoff = crate::commands::session::redeem_session_handoff(&token)?;
+                    goose::analytics::record_feature("cli_session_attach");
+
+                    let mut session: crate::Session = build_session(SessionBuilderConfig {
+                        identifier: Some(session::Identifier::Path(handoff.session_path)),
+                        resume: true,
+                        no_session: false,
+                        extensions,
+                        remote_extensions,
+                        streamable_http_extensions,
+                        builtins,
+                        extensions_override: None,
+                        additional_system_prompt: None,
+                        settings: None,
+                        provider: None,
+                        model: None,
+                        debug,
+                        max_tool_repetitions,
+                        max_turns,
+                        scheduled_job_id: None,
+                        interactive: true,
+                        quiet: false,
+                        sub_recipes: None,
+                        final_output_response: None,
+                        retry_config: None,
+                        critique: None,
+                    })
+                    .await;
+
+                    session.render_message_history();
+                    session
+                        .resume_pending_approvals(&handoff.pending_approvals)
+                        .await?;
+                    session.interactive(None).await
+                }
                 None => {
                     let session_start = std::time::Instant::now();
                     let session_type = if resume { "resumed" } else { "new" };
@@ -776,6 +1394,7 @@ pub async fn cli() -> Result<()> {
                         interactive = true,
                         "Session started"
                     );
+                    goose::analytics::record_feature("cli_session");
 
                     // Run session command by default
                     let mut session: crate::Session = build_session(SessionBuilderConfig {
@@ -800,6 +1419,7 @@ pub async fn cli() -> Result<()> {
                         sub_recipes: None,
                         final_output_response: None,
                         retry_config: None,
+                        critique: None,
                     })
                     .await;
 
@@ -869,9 +1489,22 @@ pub async fn cli() -> Result<()> {
             scheduled_job_id,
             quiet,
             additional_sub_recipes,
+            ci,
             provider,
             model,
         }) => {
+            let ci_context = if ci {
+                let context = GithubContext::detect();
+                if context.is_none() {
+                    eprintln!(
+                        "Warning: --ci only supports GitHub Actions; running without CI reporting"
+                    );
+                }
+                context
+            } else {
+                None
+            };
+
             let (input_config, recipe_info) = match (instructions, input_text, recipe) {
                 (Some(file), _, _) if file == "-" => {
                     let mut input = String::new();
@@ -931,8 +1564,12 @@ pub async fn cli() -> Result<()> {
                         }
                         return Ok(());
                     }
-                    let (input_config, recipe_info) =
-                        extract_recipe_info_from_cli(recipe_name, params, additional_sub_recipes)?;
+                    let (input_config, recipe_info) = extract_recipe_info_from_cli(
+                        recipe_name,
+                        params,
+                        additional_sub_recipes,
+                        resume,
+                    )?;
                     (input_config, Some(recipe_info))
                 }
                 (None, None, None) => {
@@ -950,7 +1587,13 @@ pub async fn cli() -> Result<()> {
                 streamable_http_extensions,
                 builtins,
                 extensions_override: input_config.extensions_override,
-                additional_system_prompt: input_config.additional_system_prompt,
+                additional_system_prompt: match (
+                    input_config.additional_system_prompt,
+                    ci_context.as_ref().map(GithubContext::system_prompt),
+                ) {
+                    (Some(system), Some(ci)) => Some(format!("{}\n\n{}", system, ci)),
+                    (system, ci) => system.or(ci),
+                },
                 settings: recipe_info
                     .as_ref()
                     .and_then(|r| r.session_settings.clone()),
@@ -967,12 +1610,20 @@ pub async fn cli() -> Result<()> {
                     .as_ref()
                     .and_then(|r| r.final_output_response.clone()),
                 retry_config: recipe_info.as_ref().and_then(|r| r.retry_config.clone()),
+                critique: recipe_info.as_ref().and_then(|r| r.critique.clone()),
             })
             .await;
 
             if interactive {
                 let _ = session.interactive(input_config.contents).await;
             } else if let Some(contents) = input_config.contents {
+                let contents = match ci_context
+                    .as_ref()
+                    .and_then(GithubContext::pull_request_message)
+                {
+                    Some(pull_request) => format!("{}\n\n{}", pull_request, contents),
+                    None => contents,
+                };
                 let session_start = std::time::Instant::now();
                 let session_type = if recipe_info.is_some() {
                     "recipe"
@@ -1023,6 +1674,19 @@ pub async fn cli() -> Result<()> {
                 }
 
                 result?;
+
+                if let Some(context) = &ci_context {
+                    let reply = session
+                        .message_history()
+                        .iter()
+                        .rev()
+                        .find(|message| message.role == rmcp::model::Role::Assistant)
+                        .map(|message| message.as_concat_text())
+                        .unwrap_or_default();
+                    if crate::ci::report(context, &reply).await? {
+                        std::process::exit(1);
+                    }
+                }
             } else {
                 eprintln!("Error: no text provided for prompt in headless mode");
                 std::process::exit(1);
@@ -1035,9 +1699,24 @@ pub async fn cli() -> Result<()> {
                 SchedulerCommand::Add {
                     id,
                     cron,
+                    run_at,
+                    after,
                     recipe_source,
+                    working_dir,
+                    env,
+                    extension,
+                    provider,
+                    model,
                 } => {
-                    handle_schedule_add(id, cron, recipe_source).await?;
+                    let environment = JobEnvironment {
+                        working_dir,
+                        env: env.into_iter().collect(),
+                        extensions: extension,
+                        provider,
+                        model,
+                    };
+                    handle_schedule_add(id, cron, run_at, after, environment, recipe_source)
+                        .await?;
                 }
                 SchedulerCommand::List {} => {
                     handle_schedule_list().await?;
@@ -1072,6 +1751,15 @@ pub async fn cli() -> Result<()> {
             crate::commands::update::update(canary, reconfigure)?;
             return Ok(());
         }
+        Some(Command::SelfCmd { command }) => {
+            match command {
+                SelfCommand::Update { version, channel } => {
+                    handle_self_update(version, channel).await?
+                }
+                SelfCommand::Rollback {} => handle_self_rollback()?,
+            }
+            return Ok(());
+        }
         Some(Command::Bench { cmd }) => {
             match cmd {
                 BenchCommand::Selectors { config } => BenchRunner::list_selectors(config)?,
@@ -1104,6 +1792,93 @@ pub async fn cli() -> Result<()> {
                 RecipeCommand::List { format, verbose } => {
                     handle_list(&format, verbose)?;
                 }
+                RecipeCommand::Test {
+                    recipe_name,
+                    spec,
+                    fixtures,
+                    live,
+                    max_turns,
+                    max_tokens,
+                    junit,
+                } => {
+                    let options = RecipeTestOptions {
+                        spec,
+                        fixtures,
+                        live,
+                        max_turns,
+                        max_tokens,
+                    };
+                    handle_test(&recipe_name, &options, junit.as_deref()).await?;
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Config { command }) => {
+            match command {
+                ConfigCommand::Export { output } => handle_config_export(output.as_deref())?,
+                ConfigCommand::Import { file, replace } => handle_config_import(&file, replace)?,
+                ConfigCommand::Sync { source } => handle_config_sync(source.as_deref()).await?,
+                ConfigCommand::MigrateSecrets => handle_config_migrate_secrets()?,
+            }
+            return Ok(());
+        }
+        Some(Command::Extension { command }) => {
+            match command {
+                ExtensionCommand::Audit { name, tools, yes } => {
+                    handle_extension_audit(&name, &tools, yes).await?
+                }
+                ExtensionCommand::GrantPlugin { path } => handle_grant_plugin(&path)?,
+            }
+            return Ok(());
+        }
+        Some(Command::Backup { command }) => {
+            match command {
+                BackupCommand::Create { output, only } => handle_backup_create(output, &only)?,
+                BackupCommand::Restore {
+                    archive,
+                    only,
+                    overwrite,
+                    list,
+                } => handle_backup_restore(&archive, &only, overwrite, list)?,
+            }
+            return Ok(());
+        }
+        Some(Command::Governance { command }) => {
+            match command {
+                GovernanceCommand::Export {
+                    project,
+                    output,
+                    hold,
+                    reason,
+                } => handle_governance_export(project, output, hold, reason)?,
+                GovernanceCommand::Purge {
+                    project,
+                    dry_run,
+                    yes,
+                } => handle_governance_purge(project, dry_run, yes).await?,
+                GovernanceCommand::Holds => handle_governance_holds()?,
+                GovernanceCommand::Release { id } => handle_governance_release(&id)?,
+            }
+            return Ok(());
+        }
+        Some(Command::Template { command }) => {
+            match command {
+                TemplateCommand::List { verbose } => handle_template_list(verbose)?,
+                TemplateCommand::Save {
+                    name,
+                    prompt,
+                    description,
+                    context,
+                    extensions,
+                } => handle_template_save(ConversationTemplate {
+                    name,
+                    description,
+                    prompt,
+                    context,
+                    extensions,
+                })?,
+                TemplateCommand::Remove { name } => handle_template_remove(&name)?,
+                TemplateCommand::Start { name } => handle_template_start(&name).await?,
             }
             return Ok(());
         }
@@ -1111,6 +1886,48 @@ pub async fn cli() -> Result<()> {
             crate::commands::web::handle_web(port, host, open).await?;
             return Ok(());
         }
+        Some(Command::Top {
+            url,
+            secret_key,
+            interval,
+        }) => {
+            handle_top(url, secret_key, interval.max(1)).await?;
+            return Ok(());
+        }
+        Some(Command::Watch {
+            dir,
+            recipe,
+            params,
+            debounce,
+            identifier,
+            resume,
+            max_turns,
+            debug,
+        }) => {
+            let (input_config, recipe_info) =
+                extract_recipe_info_from_cli(recipe, params, Vec::new(), resume)?;
+            let session = build_session(SessionBuilderConfig {
+                identifier: identifier.map(extract_identifier),
+                resume,
+                extensions_override: input_config.extensions_override,
+                additional_system_prompt: input_config.additional_system_prompt,
+                settings: recipe_info.session_settings,
+                debug,
+                max_turns,
+                sub_recipes: recipe_info.sub_recipes,
+                final_output_response: recipe_info.final_output_response,
+                retry_config: recipe_info.retry_config,
+                critique: recipe_info.critique,
+                ..Default::default()
+            })
+            .await;
+            let prompt = input_config.contents.unwrap_or_else(|| {
+                "Files in the project changed. Follow your instructions for the new changes."
+                    .to_string()
+            });
+            handle_watch(session, dir, prompt, Duration::from_millis(debounce)).await?;
+            return Ok(());
+        }
         None => {
             return if !Config::global().exists() {
                 let _ = handle_configure().await;
@@ -1139,6 +1956,7 @@ pub async fn cli() -> Result<()> {
                     sub_recipes: None,
                     final_output_response: None,
                     retry_config: None,
+                    critique: None,
                 })
                 .await;
                 if let Err(e) = session.interactive(None).await {
@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use console::style;
+use goose::backup::{
+    backups_dir, create_backup, read_manifest, restore_backup, BackupCategory, BackupLocations,
+};
+use goose::config::Config;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+fn selected(only: &[BackupCategory]) -> &[BackupCategory] {
+    if only.is_empty() {
+        &BackupCategory::ALL
+    } else {
+        only
+    }
+}
+
+/// Default archive path in goose's backups directory, where purges find it, e.g.
+/// goose-backup-20250101-120000.zip
+fn default_output() -> Result<PathBuf> {
+    let dir = backups_dir()?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    Ok(dir.join(format!(
+        "goose-backup-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    )))
+}
+
+pub fn handle_backup_create(output: Option<PathBuf>, only: &[BackupCategory]) -> Result<()> {
+    let output = match output {
+        Some(output) => output,
+        None => default_output()?,
+    };
+    let file =
+        File::create(&output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let manifest = create_backup(
+        BufWriter::new(file),
+        Config::global(),
+        &BackupLocations::from_app_dirs()?,
+        selected(only),
+    )?;
+    println!(
+        "{} backed up {} files to {}",
+        style("✓").green().bold(),
+        manifest.entries.len(),
+        output.display()
+    );
+    println!(
+        "  Credentials are not included; re-enter them with `goose configure` after restoring."
+    );
+    Ok(())
+}
+
+pub fn handle_backup_restore(
+    archive: &Path,
+    only: &[BackupCategory],
+    overwrite: bool,
+    list: bool,
+) -> Result<()> {
+    let open =
+        || File::open(archive).with_context(|| format!("Failed to open {}", archive.display()));
+
+    if list {
+        let manifest = read_manifest(open()?)?;
+        println!(
+            "Backup from goose {} created {}",
+            manifest.goose_version, manifest.created_at
+        );
+        for entry in manifest.entries {
+            println!("  {} ({} bytes)", entry.path, entry.size);
+        }
+        return Ok(());
+    }
+
+    let summary = restore_backup(
+        open()?,
+        Config::global(),
+        &BackupLocations::from_app_dirs()?,
+        selected(only),
+        overwrite,
+    )?;
+    println!(
+        "{} restored {} files from {}",
+        style("✓").green().bold(),
+        summary.restored,
+        archive.display()
+    );
+    if summary.skipped > 0 {
+        println!(
+            "  {} existing files were kept; use --overwrite to replace them",
+            summary.skipped
+        );
+    }
+    Ok(())
+}
@@ -24,6 +24,12 @@ impl BenchBaseSession for Session {
     fn get_total_token_usage(&self) -> anyhow::Result<Option<i32>> {
         self.get_total_token_usage()
     }
+    fn get_token_breakdown(&self) -> anyhow::Result<Option<(i32, i32)>> {
+        let metadata = self.get_metadata()?;
+        Ok(metadata
+            .accumulated_input_tokens
+            .zip(metadata.accumulated_output_tokens))
+    }
 }
 pub async fn agent_generator(
     requirements: ExtensionRequirements,
@@ -53,6 +59,7 @@ pub async fn agent_generator(
         sub_recipes: None,
         final_output_response: None,
         retry_config: None,
+        critique: None,
     })
     .await;
 
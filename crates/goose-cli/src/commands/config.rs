@@ -0,0 +1,90 @@
+use anyhow::Result;
+use console::style;
+use goose::config::secrets::migrate_plaintext_secrets;
+use goose::config::team::{export_config, import_config_file, sync_team_config};
+use goose::config::Config;
+use std::path::Path;
+
+/// Write the user config (without secrets) to `output`, or stdout
+pub fn handle_config_export(output: Option<&Path>) -> Result<()> {
+    let yaml = export_config(Config::global())?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, yaml)?;
+            eprintln!(
+                "{} exported config to {}",
+                style("✓").green().bold(),
+                path.display()
+            );
+        }
+        None => print!("{}", yaml),
+    }
+    Ok(())
+}
+
+pub fn handle_config_import(file: &Path, replace: bool) -> Result<()> {
+    let summary = import_config_file(Config::global(), file, replace)?;
+    println!(
+        "{} imported {} values from {}",
+        style("✓").green().bold(),
+        summary.values,
+        file.display()
+    );
+    if summary.secrets > 0 {
+        println!(
+            "  {} credentials were stored in the secret store instead of config.yaml",
+            summary.secrets
+        );
+    }
+    Ok(())
+}
+
+pub async fn handle_config_sync(source: Option<&str>) -> Result<()> {
+    let result = sync_team_config(Config::global(), source).await?;
+    println!(
+        "{} synced {} team settings from {}",
+        style("✓").green().bold(),
+        result.keys,
+        result.source
+    );
+    println!("  Your own settings still take precedence; see `goose info -v`.");
+    Ok(())
+}
+
+pub fn handle_config_migrate_secrets() -> Result<()> {
+    let report = migrate_plaintext_secrets(Config::global())?;
+    let moved = report.config_keys.len() + report.extension_envs.len() + report.from_fallback_file;
+    if moved == 0 && report.conflicts.is_empty() {
+        println!(
+            "No plaintext credentials found; secrets are in the {}",
+            report.store
+        );
+        return Ok(());
+    }
+    println!(
+        "{} moved {} credentials into the {}",
+        style("✓").green().bold(),
+        moved,
+        report.store
+    );
+    for key in report.config_keys.iter().chain(&report.extension_envs) {
+        println!("  {}", key);
+    }
+    if report.from_fallback_file > 0 {
+        println!(
+            "  {} from the plaintext fallback file",
+            report.from_fallback_file
+        );
+    }
+    if !report.conflicts.is_empty() {
+        println!(
+            "{} left in config.yaml because the {} already holds a different value:",
+            style("!").yellow().bold(),
+            report.store
+        );
+        for key in &report.conflicts {
+            println!("  {}", key);
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use crate::recipes::github_recipe::GOOSE_RECIPE_GITHUB_REPO_CONFIG_KEY;
-use cliclack::spinner;
+use crate::session::{start_spinner, stop_spinner};
 use console::style;
 use goose::agents::extension::ToolInfo;
 use goose::agents::extension_manager::get_parameter_names;
@@ -553,14 +553,13 @@ pub async fn configure_provider_dialog() -> Result<bool, Box<dyn Error>> {
     }
 
     // Attempt to fetch supported models for this provider
-    let spin = spinner();
-    spin.start("Attempting to fetch supported models...");
+    let spin = start_spinner("Attempting to fetch supported models...");
     let models_res = {
         let temp_model_config = ModelConfig::new(&provider_meta.default_model)?;
         let temp_provider = create(provider_name, temp_model_config)?;
         temp_provider.fetch_supported_models().await
     };
-    spin.stop(style("Model fetch complete").green());
+    stop_spinner(spin, style("Model fetch complete").green());
 
     // Select a model: on fetch error show styled error and abort; if Some(models), show list; if None, free-text input
     let model: String = match models_res {
@@ -580,8 +579,7 @@ pub async fn configure_provider_dialog() -> Result<bool, Box<dyn Error>> {
     };
 
     // Test the configuration
-    let spin = spinner();
-    spin.start("Checking your configuration...");
+    let spin = start_spinner("Checking your configuration...");
 
     // Create model config with env var settings
     let toolshim_enabled = std::env::var("GOOSE_TOOLSHIM")
@@ -638,7 +636,7 @@ pub async fn configure_provider_dialog() -> Result<bool, Box<dyn Error>> {
             Ok(true)
         }
         Err(e) => {
-            spin.stop(style(e.to_string()).red());
+            stop_spinner(spin, style(e.to_string()).red());
             cliclack::outro(style("Failed to configure provider: init chat completion request with tool did not succeed.").on_red().white())?;
             Ok(false)
         }
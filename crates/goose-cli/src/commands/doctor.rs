@@ -0,0 +1,70 @@
+use anyhow::Result;
+use console::style;
+use goose::config::Config;
+use goose::diagnostics::{build_bundle, run_checks, BundleOptions, CheckStatus};
+use goose::session::{self, Identifier};
+use std::path::PathBuf;
+
+/// Write a diagnostics zip to attach to bug reports
+pub fn handle_doctor_bundle(identifier: Option<Identifier>, output: Option<PathBuf>) -> Result<()> {
+    let session_file = match identifier {
+        Some(identifier) => {
+            let path = session::get_path(identifier)?;
+            if !path.exists() {
+                anyhow::bail!("Session not found: {}", path.display());
+            }
+            Some(path)
+        }
+        None => None,
+    };
+
+    let bundle = build_bundle(
+        Config::global(),
+        &BundleOptions {
+            session_file,
+            ..Default::default()
+        },
+    )?;
+
+    let output = output.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "goose-diagnostics-{}.zip",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    std::fs::write(&output, bundle)?;
+    println!(
+        "{} wrote diagnostics to {}",
+        style("✓").green().bold(),
+        output.display()
+    );
+    println!("  Credentials are redacted, but please look it over before sharing.");
+    Ok(())
+}
+
+/// Check the environment and suggest fixes. Fails if any check failed.
+pub async fn handle_doctor() -> Result<()> {
+    println!("Checking your goose setup...\n");
+    let report = run_checks(Config::global()).await;
+    for check in &report.checks {
+        let marker = match check.status {
+            CheckStatus::Pass => style("✓").green().bold(),
+            CheckStatus::Warn => style("!").yellow().bold(),
+            CheckStatus::Fail => style("✗").red().bold(),
+            CheckStatus::Skip => style("-").dim(),
+        };
+        println!("{} {:<24} {}", marker, check.name, check.message);
+        if let Some(fix) = &check.fix {
+            println!("  {} {}", style("→").dim(), fix);
+        }
+    }
+
+    if !report.ok {
+        println!(
+            "\nIf a problem persists, run `goose doctor --bundle` and attach the zip to a bug report."
+        );
+        anyhow::bail!("Some checks failed");
+    }
+    println!("\n{} goose is ready to go", style("✓").green().bold());
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use anyhow::{anyhow, Result};
+use console::style;
+use goose::agents::extension_audit::{audit_extension, Verdict};
+use goose::agents::native_plugin::{grant_plugin, NativePluginClient};
+use goose::config::{Config, ExtensionConfigManager};
+use std::path::Path;
+
+/// Probe a configured extension's tools with adversarial inputs and print what goose lets through
+pub async fn handle_extension_audit(name: &str, tools: &[String], yes: bool) -> Result<()> {
+    let config = ExtensionConfigManager::get_config_by_name(name)?
+        .ok_or_else(|| anyhow!("No extension named '{}' is configured", name))?;
+
+    if !yes {
+        let proceed = cliclack::confirm(format!(
+            "This calls every tool of '{}' with hostile inputs. Only continue if they can't do harm here. Continue?",
+            name
+        ))
+        .initial_value(false)
+        .interact()?;
+        if !proceed {
+            return Ok(());
+        }
+    }
+
+    let report = audit_extension(config, tools).await?;
+    if report.findings.is_empty() {
+        println!("{} has no tools to audit", report.extension);
+        return Ok(());
+    }
+
+    let mut current_tool = None;
+    for finding in &report.findings {
+        if current_tool != Some(&finding.tool) {
+            println!("\n{}", style(&finding.tool).bold());
+            current_tool = Some(&finding.tool);
+        }
+        let label = |text: &str| style(format!("{:<10}", text));
+        let verdict = match finding.verdict {
+            Verdict::Clean => label("clean").green(),
+            Verdict::Rejected => label("rejected").green(),
+            Verdict::Contained => label("contained").cyan(),
+            Verdict::Exposed => label("exposed").red().bold(),
+            Verdict::Failed => label("failed").yellow(),
+        };
+        println!(
+            "  {:<16} {} {}",
+            finding.probe.to_string(),
+            verdict,
+            style(&finding.detail).dim()
+        );
+    }
+
+    let exposed = report.exposed();
+    println!();
+    if exposed == 0 {
+        println!(
+            "{} nothing from {} would reach the model unfiltered",
+            style("✓").green().bold(),
+            report.extension
+        );
+    } else {
+        println!(
+            "{} {} probe(s) got through to the model; review these tools before enabling {}",
+            style("!").red().bold(),
+            exposed,
+            report.extension
+        );
+    }
+    Ok(())
+}
+
+/// Show what a native plugin declares and record the user's approval of this exact library
+pub fn handle_grant_plugin(path: &Path) -> Result<()> {
+    let proceed = cliclack::confirm(format!(
+        "Reading the manifest of {} runs its code with your privileges. Only continue if you trust it. Continue?",
+        path.display()
+    ))
+    .initial_value(false)
+    .interact()?;
+    if !proceed {
+        return Ok(());
+    }
+
+    let manifest = NativePluginClient::inspect(path)?;
+    println!(
+        "\n{} {}",
+        style(&manifest.name).bold(),
+        style(&manifest.version).dim()
+    );
+    if let Some(description) = &manifest.description {
+        println!("  {}", description);
+    }
+    let tools: Vec<_> = manifest.tools.iter().map(|t| t.name.to_string()).collect();
+    println!("  tools: {}", tools.join(", "));
+    let capabilities: Vec<_> = manifest
+        .capabilities
+        .iter()
+        .map(|c| {
+            serde_json::to_string(c)
+                .unwrap_or_default()
+                .replace('"', "")
+        })
+        .collect();
+    if capabilities.is_empty() {
+        println!("  declares no capabilities");
+    } else {
+        println!(
+            "  declares: {}",
+            style(capabilities.join(", ")).yellow().bold()
+        );
+    }
+    println!(
+        "  {}",
+        style("Declared capabilities are not enforced; a plugin can do anything goose can.").dim()
+    );
+
+    let approve = cliclack::confirm(format!("Allow {} to load into goose?", manifest.name))
+        .initial_value(false)
+        .interact()?;
+    if !approve {
+        return Ok(());
+    }
+    grant_plugin(Config::global(), path, &manifest.capabilities)?;
+    println!(
+        "{} approved {}; it has to be approved again if the library changes",
+        style("✓").green().bold(),
+        path.display()
+    );
+    Ok(())
+}
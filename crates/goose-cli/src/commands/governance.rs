@@ -0,0 +1,157 @@
+use anyhow::{bail, Context, Result};
+use cliclack::confirm;
+use console::style;
+use goose::governance::{self, PurgeReport};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+/// Default archive name, e.g. goose-export-20250101-120000.zip
+fn default_output() -> PathBuf {
+    PathBuf::from(format!(
+        "goose-export-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+fn resolve(project: Option<PathBuf>) -> Result<Option<PathBuf>> {
+    project
+        .map(|project| {
+            std::path::absolute(&project)
+                .with_context(|| format!("Failed to resolve {}", project.display()))
+        })
+        .transpose()
+}
+
+pub fn handle_governance_export(
+    project: Option<PathBuf>,
+    output: Option<PathBuf>,
+    hold: bool,
+    reason: Option<String>,
+) -> Result<()> {
+    let project = resolve(project)?;
+    let output = output.unwrap_or_else(default_output);
+
+    let hold = if hold {
+        Some(governance::place_hold(project.as_deref(), reason)?)
+    } else {
+        None
+    };
+    let file =
+        File::create(&output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let manifest = governance::export_all(BufWriter::new(file), project.as_deref(), hold.as_ref())?;
+
+    println!(
+        "{} exported {} sessions ({} files) to {}",
+        style("✓").green().bold(),
+        manifest.sessions.len(),
+        manifest.entries.len(),
+        output.display()
+    );
+    if let Some(hold) = hold {
+        println!(
+            "  Placed legal hold {}; release it with `goose governance release {}`.",
+            hold.id, hold.id
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_governance_holds() -> Result<()> {
+    let holds = governance::holds();
+    if holds.is_empty() {
+        println!("No legal holds in place.");
+        return Ok(());
+    }
+    for hold in holds {
+        println!(
+            "{}  {}  {}",
+            style(&hold.id).bold(),
+            hold.created_at.format("%Y-%m-%d %H:%M"),
+            hold.project.as_deref().unwrap_or("all data")
+        );
+        if let Some(reason) = &hold.reason {
+            println!("  {}", reason);
+        }
+        println!(
+            "  {} sessions, memories in {}",
+            hold.sessions.len(),
+            hold.memory_dir
+        );
+    }
+    Ok(())
+}
+
+pub fn handle_governance_release(id: &str) -> Result<()> {
+    if !governance::release_hold(id)? {
+        bail!("No legal hold with id '{}'", id);
+    }
+    println!("{} released legal hold {}", style("✓").green().bold(), id);
+    Ok(())
+}
+
+fn print_purge_report(report: &PurgeReport) {
+    println!(
+        "  {} sessions, {} files ({} bytes), {} exemplars, {} alerts",
+        report.sessions.len(),
+        report.files.len(),
+        report.bytes(),
+        report.exemplars.len(),
+        report.alerts
+    );
+    if !report.backups.is_empty() {
+        println!("  Copies in {} backups", report.backups.len());
+    }
+    if !report.synced.is_empty() {
+        println!(
+            "  Synced copies of {} sessions in the sync backend",
+            report.synced.len()
+        );
+    }
+    for session in &report.held_sessions {
+        println!("  Keeping session {}: it is under a legal hold", session);
+    }
+    if report.memories_held {
+        println!("  Keeping memories: they are under a legal hold");
+    }
+}
+
+pub async fn handle_governance_purge(
+    project: Option<PathBuf>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let project = resolve(project)?;
+    if yes && !dry_run && project.is_none() {
+        bail!("Purging all data has to be confirmed; pass --project or leave out --yes");
+    }
+    let scope = project
+        .as_deref()
+        .map(|project| project.display().to_string())
+        .unwrap_or_else(|| "all projects".to_string());
+
+    let preview = governance::purge(project.as_deref(), true).await?;
+    println!("Purging data for {} would remove:", scope);
+    print_purge_report(&preview);
+    if dry_run {
+        return Ok(());
+    }
+    if !yes
+        && !confirm("Permanently delete this data?")
+            .initial_value(false)
+            .interact()?
+    {
+        println!("Nothing was deleted.");
+        return Ok(());
+    }
+
+    let report = governance::purge(project.as_deref(), false).await?;
+    println!(
+        "{} purged {} sessions and {} files for {}",
+        style("✓").green().bold(),
+        report.sessions.len(),
+        report.files.len(),
+        scope
+    );
+    Ok(())
+}
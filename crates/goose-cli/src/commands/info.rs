@@ -1,8 +1,10 @@
 use anyhow::Result;
 use console::style;
 use etcetera::{choose_app_strategy, AppStrategy};
+use goose::config::settings::SETTINGS_KEYS;
 use goose::config::Config;
-use serde_yaml;
+use serde_json::Value;
+use std::collections::HashMap;
 
 fn print_aligned(label: &str, value: &str, width: usize) {
     println!("  {:<width$} {}", label, value, width = width);
@@ -43,23 +45,41 @@ pub fn handle_info(verbose: bool) -> Result<()> {
     // Print verbose info if requested
     if verbose {
         println!("\n{}", style("Goose Configuration:").cyan().bold());
-        match config.load_values() {
+        for (source, path) in config.layer_paths() {
+            let status = if path.exists() { "" } else { " (not found)" };
+            print_aligned(
+                &format!("{:?} layer:", source),
+                &format!("{}{}", path.display(), status),
+                basic_padding,
+            );
+        }
+        if let Some(path) = config.untrusted_project_path() {
+            print_aligned(
+                "Project layer:",
+                &format!("{} (untrusted, ignored)", path.display()),
+                basic_padding,
+            );
+        }
+        match config.effective_values(&HashMap::new(), SETTINGS_KEYS) {
+            Ok(values) if values.is_empty() => {
+                println!("  No configuration values set");
+                println!(
+                    "  Run '{}' to configure goose",
+                    style("goose configure").cyan()
+                );
+            }
             Ok(values) => {
-                if values.is_empty() {
-                    println!("  No configuration values set");
+                for value in values {
+                    let rendered = match &value.value {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
                     println!(
-                        "  Run '{}' to configure goose",
-                        style("goose configure").cyan()
+                        "  {}: {} {}",
+                        value.key,
+                        rendered,
+                        style(format!("({:?})", value.source).to_lowercase()).dim()
                     );
-                } else {
-                    let sorted_values: std::collections::BTreeMap<_, _> =
-                        values.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
-
-                    if let Ok(yaml) = serde_yaml::to_string(&sorted_values) {
-                        for line in yaml.lines() {
-                            println!("  {}", line);
-                        }
-                    }
                 }
             }
             Err(e) => println!("  Error loading configuration: {}", e),
@@ -46,22 +46,27 @@ pub async fn run_server(name: &str) -> Result<()> {
 
     tracing::info!("Server initialized and ready to handle requests");
 
-    tokio::select! {
+    let shutdown_requested = tokio::select! {
         result = server.run(transport) => {
-            Ok(result?)
+            result?;
+            false
         }
-        _ = shutdown.notified() => {
-            // On Unix systems, kill the entire process group
-            #[cfg(unix)]
-            {
-                fn terminate_process_group() {
-                    let pgid = getpgrp();
-                    kill(Pid::from_raw(-pgid.as_raw()), Signal::SIGTERM)
-                        .expect("Failed to send SIGTERM to process group");
-                }
-                terminate_process_group();
+        _ = shutdown.notified() => true,
+    };
+
+    // The server and its router are dropped by now, which stops the background processes its
+    // tools started in process groups of their own
+    if shutdown_requested {
+        // On Unix systems, kill the entire process group
+        #[cfg(unix)]
+        {
+            fn terminate_process_group() {
+                let pgid = getpgrp();
+                kill(Pid::from_raw(-pgid.as_raw()), Signal::SIGTERM)
+                    .expect("Failed to send SIGTERM to process group");
             }
-            Ok(())
+            terminate_process_group();
         }
     }
+    Ok(())
 }
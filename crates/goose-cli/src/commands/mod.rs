@@ -1,9 +1,18 @@
+pub mod backup;
 pub mod bench;
+pub mod config;
 pub mod configure;
+pub mod doctor;
+pub mod extension;
+pub mod governance;
 pub mod info;
 pub mod mcp;
 pub mod recipe;
 pub mod schedule;
+pub mod self_update;
 pub mod session;
+pub mod template;
+pub mod top;
 pub mod update;
+pub mod watch;
 pub mod web;
@@ -1,8 +1,10 @@
 use anyhow::Result;
 use console::style;
+use std::path::Path;
 
 use crate::recipes::github_recipe::RecipeSource;
 use crate::recipes::recipe::load_recipe_for_validation;
+use crate::recipes::recipe_test::{run_recipe_tests, RecipeTestOptions};
 use crate::recipes::search_recipe::list_available_recipes;
 use goose::recipe_deeplink;
 
@@ -68,6 +70,52 @@ pub fn handle_deeplink(recipe_name: &str) -> Result<String> {
     }
 }
 
+/// Runs a recipe's test cases and reports the results
+///
+/// # Arguments
+///
+/// * `recipe_name` - Name or path of the recipe to test
+/// * `options` - Where to find the spec and fixtures, and run limits
+/// * `junit` - Optional path to write a JUnit XML report to
+///
+/// # Returns
+///
+/// Result indicating whether every case passed
+pub async fn handle_test(
+    recipe_name: &str,
+    options: &RecipeTestOptions,
+    junit: Option<&Path>,
+) -> Result<()> {
+    let report = run_recipe_tests(recipe_name, options).await?;
+
+    for case in &report.cases {
+        if case.failures.is_empty() {
+            println!("{} {}", style("✓").green().bold(), case.name);
+        } else {
+            println!("{} {}", style("✗").red().bold(), case.name);
+            for failure in &case.failures {
+                println!("    {}", style(failure).dim());
+            }
+        }
+    }
+
+    if let Some(path) = junit {
+        std::fs::write(path, report.to_junit_xml())?;
+        println!("JUnit report written to {}", path.display());
+    }
+
+    let failed = report.failed();
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "{} of {} test cases failed",
+            failed,
+            report.cases.len()
+        ));
+    }
+    println!("{} test cases passed", report.cases.len());
+    Ok(())
+}
+
 /// Lists all available recipes from local paths and GitHub repositories
 ///
 /// # Arguments
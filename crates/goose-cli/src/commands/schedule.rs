@@ -1,8 +1,9 @@
 use anyhow::{bail, Context, Result};
 use base64::engine::{general_purpose::STANDARD as BASE64_STANDARD, Engine};
+use chrono::{DateTime, Utc};
 use goose::scheduler::{
-    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, ScheduledJob,
-    SchedulerError,
+    get_default_scheduled_recipes_dir, get_default_scheduler_storage_path, JobEnvironment,
+    ScheduledJob, SchedulerError,
 };
 use goose::scheduler_factory::SchedulerFactory;
 use goose::temporal_scheduler::TemporalScheduler;
@@ -77,29 +78,47 @@ fn validate_cron_expression(cron: &str) -> Result<()> {
 
 pub async fn handle_schedule_add(
     id: String,
-    cron: String,
+    cron: Option<String>,
+    run_at: Option<DateTime<Utc>>,
+    depends_on: Vec<String>,
+    mut environment: JobEnvironment,
     recipe_source_arg: String, // This is expected to be a file path by the Scheduler
 ) -> Result<()> {
     println!(
-        "[CLI Debug] Scheduling job ID: {}, Cron: {}, Recipe Source Path: {}",
+        "[CLI Debug] Scheduling job ID: {}, Cron: {:?}, Recipe Source Path: {}",
         id, cron, recipe_source_arg
     );
 
     // Validate cron expression and provide helpful feedback
-    validate_cron_expression(&cron)?;
+    if let Some(cron) = &cron {
+        validate_cron_expression(cron)?;
+    }
+
+    // The job may run from another directory, so pin a relative working directory down now
+    if let Some(dir) = &environment.working_dir {
+        let dir = std::path::absolute(dir)
+            .with_context(|| format!("Invalid working directory '{}'", dir))?;
+        if !dir.is_dir() {
+            bail!("Working directory {} does not exist", dir.display());
+        }
+        environment.working_dir = Some(dir.to_string_lossy().into_owned());
+    }
 
     // The Scheduler's add_scheduled_job will handle copying the recipe from recipe_source_arg
     // to its internal storage and validating the path.
     let job = ScheduledJob {
         id: id.clone(),
         source: recipe_source_arg.clone(), // Pass the original user-provided path
-        cron,
+        cron: cron.unwrap_or_default(),
         last_run: None,
         currently_running: false,
         paused: false,
         current_session_id: None,
         process_start_time: None,
         execution_mode: Some("background".to_string()), // Default to background for CLI
+        run_at,
+        depends_on,
+        environment,
     };
 
     let scheduler_storage_path =
@@ -167,15 +186,43 @@ pub async fn handle_schedule_list() -> Result<()> {
                 "⏹️  IDLE"
             };
 
+            let trigger = match &job.run_at {
+                Some(run_at) => format!("Run At: {}", run_at.to_rfc3339()),
+                None if !job.depends_on.is_empty() => {
+                    format!("After: {}", job.depends_on.join(", "))
+                }
+                None => format!("Cron: {}", job.cron),
+            };
+
             println!(
-                "- ID: {}\n  Status: {}\n  Cron: {}\n  Recipe Source (in store): {}\n  Last Run: {}",
+                "- ID: {}\n  Status: {}\n  {}\n  Recipe Source (in store): {}\n  Last Run: {}",
                 job.id,
                 status,
-                job.cron,
+                trigger,
                 job.source, // This source is now the path within scheduled_recipes_dir
                 job.last_run
                     .map_or_else(|| "Never".to_string(), |dt| dt.to_rfc3339())
             );
+            let environment = job.environment.redacted();
+            if let Some(dir) = &environment.working_dir {
+                println!("  Working Dir: {}", dir);
+            }
+            if !environment.env_keys.is_empty() {
+                println!("  Env: {}", environment.env_keys.join(", "));
+            }
+            if !environment.extensions.is_empty() {
+                println!("  Extensions: {}", environment.extensions.join(", "));
+            }
+            if environment.provider.is_some() || environment.model.is_some() {
+                println!(
+                    "  Model: {} / {}",
+                    environment
+                        .provider
+                        .as_deref()
+                        .unwrap_or("default provider"),
+                    environment.model.as_deref().unwrap_or("default model")
+                );
+            }
         }
     }
     Ok(())
@@ -0,0 +1,371 @@
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use clap::ValueEnum;
+use console::style;
+use goose::config::Config;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const RELEASES_URL: &str = "https://github.com/block/goose/releases/download";
+/// Redirects to the tag of the newest stable release
+const LATEST_RELEASE_URL: &str = "https://github.com/block/goose/releases/latest";
+
+/// Config key that holds goose at one version; `goose self update` installs it and goes no further
+pub const PINNED_VERSION_KEY: &str = "GOOSE_PINNED_VERSION";
+/// Config key choosing the release channel when no version is pinned
+pub const UPDATE_CHANNEL_KEY: &str = "GOOSE_UPDATE_CHANNEL";
+
+/// Base64 Ed25519 public key that release archives are signed with, set by the release build.
+/// Builds without it cannot verify downloads and refuse to update themselves.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("GOOSE_RELEASE_PUBLIC_KEY");
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    /// Published under the canary release tag
+    Beta,
+}
+
+impl ReleaseChannel {
+    fn tag(self) -> &'static str {
+        match self {
+            ReleaseChannel::Stable => "stable",
+            ReleaseChannel::Beta => "canary",
+        }
+    }
+}
+
+/// An update setting from the environment or the user's own config. Project and team
+/// configs don't get to choose which binary is installed.
+fn user_setting<T: serde::de::DeserializeOwned>(config: &Config, key: &str) -> Option<T> {
+    match std::env::var(key) {
+        Ok(value) => serde_json::from_value(serde_json::Value::String(value)).ok(),
+        Err(_) => config.get_user_param(key).ok(),
+    }
+}
+
+/// A release tag: a channel or a specific version
+fn release_tag(version: Option<&str>, channel: Option<ReleaseChannel>, config: &Config) -> String {
+    let version = version
+        .map(str::to_string)
+        .or_else(|| user_setting::<String>(config, PINNED_VERSION_KEY));
+    match version {
+        Some(version) => format!("v{}", version.trim_start_matches('v')),
+        None => channel
+            .or_else(|| user_setting(config, UPDATE_CHANNEL_KEY))
+            .unwrap_or_default()
+            .tag()
+            .to_string(),
+    }
+}
+
+/// The version tag the stable channel points at, so an up-to-date install isn't downloaded
+/// again and the archive comes from a tag that can't move underneath us. Other tags are
+/// returned as they are; canary is rebuilt in place and has no version to resolve to.
+async fn resolve_tag(tag: String) -> Result<String> {
+    if tag != ReleaseChannel::Stable.tag() {
+        return Ok(tag);
+    }
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        .send()
+        .await
+        .context("Failed to look up the latest release")?;
+    response
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .and_then(|location| location.rsplit('/').next())
+        .filter(|tag| tag.starts_with('v'))
+        .map(str::to_string)
+        .context("Could not tell which version the stable release is")
+}
+
+/// Release archive for this platform, named the same way as download_cli.sh
+fn asset_name(os: &str, arch: &str) -> Result<String> {
+    let arch = match arch {
+        "x86_64" | "aarch64" => arch,
+        _ => bail!("No prebuilt goose for {} on {}", arch, os),
+    };
+    match os {
+        "macos" => Ok(format!("goose-{}-apple-darwin.tar.bz2", arch)),
+        "linux" => Ok(format!("goose-{}-unknown-linux-gnu.tar.bz2", arch)),
+        "windows" if arch == "x86_64" => Ok(format!("goose-{}-pc-windows-gnu.zip", arch)),
+        _ => bail!("No prebuilt goose for {} on {}", arch, os),
+    }
+}
+
+/// Check `signature` (base64 Ed25519, as published in `<asset>.sig`) over `archive`
+fn verify_signature(archive: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let public_key = engine
+        .decode(public_key.trim())
+        .context("Invalid release public key")?;
+    let signature = engine
+        .decode(signature.trim())
+        .context("Release signature is not valid base64")?;
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(archive, &signature)
+        .map_err(|_| anyhow::anyhow!("Release signature does not match; refusing to install"))
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Failed to download {}", url))?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+fn find_binary(dir: &Path, name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if let Some(found) = find_binary(&path, name) {
+                return Some(found);
+            }
+        } else if path.file_name().is_some_and(|n| n == name) {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Where the binary an update replaced is kept, e.g. `goose.previous` or `goose.previous.exe`
+fn previous_path(current: &Path) -> PathBuf {
+    let mut name = current.file_stem().unwrap_or_default().to_os_string();
+    name.push(".previous");
+    if let Some(extension) = current.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    current.with_file_name(name)
+}
+
+fn staging_dir(current: &Path) -> Result<tempfile::TempDir> {
+    let install_dir = current
+        .parent()
+        .context("Cannot locate the goose executable")?;
+    tempfile::Builder::new()
+        .prefix(".goose-update")
+        .tempdir_in(install_dir)
+        .with_context(|| format!("Cannot write to {}", install_dir.display()))
+}
+
+/// Replace the running executable with `new_binary`. Both must be on the same filesystem
+/// so the final rename is atomic.
+fn swap_binary(new_binary: &Path, current: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(new_binary, std::fs::Permissions::from_mode(0o755))?;
+        std::fs::rename(new_binary, current)?;
+    }
+    #[cfg(windows)]
+    {
+        // A running exe can be renamed but not overwritten
+        let old = current.with_extension("exe.old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(current, &old)?;
+        if let Err(e) = std::fs::rename(new_binary, current) {
+            std::fs::rename(&old, current)?;
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_self_update(
+    version: Option<String>,
+    channel: Option<ReleaseChannel>,
+) -> Result<()> {
+    let Some(public_key) = RELEASE_PUBLIC_KEY else {
+        bail!(
+            "This build of goose cannot verify release signatures; \
+             update with `goose update` or your package manager"
+        );
+    };
+    let config = Config::global();
+    let tag = resolve_tag(release_tag(version.as_deref(), channel, config)).await?;
+    if tag.trim_start_matches('v') == env!("CARGO_PKG_VERSION") {
+        println!("goose {} is already installed", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+
+    let asset = asset_name(std::env::consts::OS, std::env::consts::ARCH)?;
+    let url = format!("{}/{}/{}", RELEASES_URL, tag, asset);
+    println!("Downloading {} from {}...", asset, tag);
+    let client = reqwest::Client::new();
+    let archive = download(&client, &url).await?;
+    let signature = download(&client, &format!("{}.sig", url)).await?;
+    verify_signature(&archive, &String::from_utf8_lossy(&signature), public_key)?;
+    println!("{} signature verified", style("✓").green().bold());
+
+    let current = std::env::current_exe()?.canonicalize()?;
+    let staging = staging_dir(&current)?;
+    let archive_path = staging.path().join(&asset);
+    std::fs::write(&archive_path, archive)?;
+
+    // bsdtar on Windows extracts zips too
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive_path)
+        .arg("-C")
+        .arg(staging.path())
+        .status()
+        .context("Failed to run tar")?;
+    if !status.success() {
+        bail!("Failed to extract {}", asset);
+    }
+    let binary_name = if cfg!(windows) { "goose.exe" } else { "goose" };
+    let new_binary = find_binary(staging.path(), binary_name)
+        .with_context(|| format!("{} not found in {}", binary_name, asset))?;
+
+    std::fs::copy(&current, previous_path(&current))
+        .context("Failed to keep the current goose for `goose self rollback`")?;
+    swap_binary(&new_binary, &current)?;
+    println!(
+        "{} updated goose to {} at {}",
+        style("✓").green().bold(),
+        tag,
+        current.display()
+    );
+    println!(
+        "  `goose self rollback` goes back to {}",
+        env!("CARGO_PKG_VERSION")
+    );
+    if user_setting::<String>(config, PINNED_VERSION_KEY).is_some() && version.is_none() {
+        println!(
+            "  goose is pinned to this version; unset {} to follow a channel",
+            PINNED_VERSION_KEY
+        );
+    }
+    Ok(())
+}
+
+/// Put back the binary the last update replaced, without downloading anything. The
+/// version rolled back from becomes the previous one, so a second rollback undoes the first.
+pub fn handle_self_rollback() -> Result<()> {
+    let current = std::env::current_exe()?.canonicalize()?;
+    let previous = previous_path(&current);
+    if !previous.exists() {
+        bail!(
+            "No earlier version of goose is kept at {}",
+            previous.display()
+        );
+    }
+
+    let staging = staging_dir(&current)?;
+    let restored = staging.path().join("restored");
+    let replaced = staging.path().join("replaced");
+    std::fs::copy(&previous, &restored)?;
+    std::fs::copy(&current, &replaced)?;
+    swap_binary(&restored, &current)?;
+    std::fs::rename(&replaced, &previous)?;
+    println!(
+        "{} rolled back goose at {}; run `goose self rollback` again to return to {}",
+        style("✓").green().bold(),
+        current.display(),
+        env!("CARGO_PKG_VERSION")
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_asset_name() {
+        assert_eq!(
+            asset_name("linux", "aarch64").unwrap(),
+            "goose-aarch64-unknown-linux-gnu.tar.bz2"
+        );
+        assert_eq!(
+            asset_name("windows", "x86_64").unwrap(),
+            "goose-x86_64-pc-windows-gnu.zip"
+        );
+        assert!(asset_name("windows", "aarch64").is_err());
+        assert!(asset_name("freebsd", "x86_64").is_err());
+    }
+
+    #[test]
+    fn test_release_tag() {
+        let dir = tempdir().unwrap();
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )
+        .unwrap();
+        assert_eq!(release_tag(None, None, &config), "stable");
+        assert_eq!(
+            release_tag(None, Some(ReleaseChannel::Beta), &config),
+            "canary"
+        );
+
+        config
+            .set_param(UPDATE_CHANNEL_KEY, serde_json::json!("beta"))
+            .unwrap();
+        assert_eq!(release_tag(None, None, &config), "canary");
+
+        config
+            .set_param(PINNED_VERSION_KEY, serde_json::json!("1.4.0"))
+            .unwrap();
+        assert_eq!(release_tag(None, None, &config), "v1.4.0");
+        assert_eq!(release_tag(Some("v1.5.0"), None, &config), "v1.5.0");
+    }
+
+    #[test]
+    fn test_shared_config_cannot_pin() {
+        let dir = tempdir().unwrap();
+        let team = dir.path().join("team.yaml");
+        std::fs::write(&team, "GOOSE_PINNED_VERSION: 0.0.1\n").unwrap();
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )
+        .unwrap()
+        .with_team_config(Some(team));
+        assert_eq!(
+            config.get_param::<String>(PINNED_VERSION_KEY).unwrap(),
+            "0.0.1"
+        );
+        assert_eq!(release_tag(None, None, &config), "stable");
+    }
+
+    #[test]
+    fn test_previous_path() {
+        assert_eq!(
+            previous_path(Path::new("/usr/local/bin/goose")),
+            PathBuf::from("/usr/local/bin/goose.previous")
+        );
+        assert_eq!(
+            previous_path(Path::new("C:/goose/goose.exe")),
+            PathBuf::from("C:/goose/goose.previous.exe")
+        );
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = engine.encode(key_pair.public_key().as_ref());
+        let signature = engine.encode(key_pair.sign(b"release archive").as_ref());
+
+        assert!(verify_signature(b"release archive", &signature, &public_key).is_ok());
+        assert!(verify_signature(b"tampered archive", &signature, &public_key).is_err());
+    }
+}
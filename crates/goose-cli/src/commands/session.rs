@@ -1,7 +1,11 @@
 use crate::session::message_to_markdown;
 use anyhow::{Context, Result};
 use cliclack::{confirm, multiselect, select};
+use goose::governance;
+use goose::session::handoff::{self, Handoff};
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
+use goose::session::integrity::{self, IntegrityStatus};
+use goose::session::sync::{sync_sessions, SyncConfig, SESSION_SYNC_KEY};
 use goose::session::{self, Identifier};
 use goose::utils::safe_truncate;
 use regex::Regex;
@@ -11,6 +15,20 @@ use std::path::{Path, PathBuf};
 const TRUNCATED_DESC_LENGTH: usize = 60;
 
 pub fn remove_sessions(sessions: Vec<SessionInfo>) -> Result<()> {
+    let (held, sessions): (Vec<SessionInfo>, Vec<SessionInfo>) =
+        sessions.into_iter().partition(|session| {
+            governance::session_hold(&session.id, Path::new(&session.path)).is_some()
+        });
+    for session in &held {
+        println!(
+            "Session `{}` is under a legal hold and will be kept.",
+            session.id
+        );
+    }
+    if sessions.is_empty() {
+        return Ok(());
+    }
+
     println!("The following sessions will be removed:");
     for session in &sessions {
         println!("- {}", session.id);
@@ -166,6 +184,57 @@ pub fn handle_session_list(verbose: bool, format: String, ascending: bool) -> Re
     Ok(())
 }
 
+pub async fn handle_session_sync() -> Result<()> {
+    let config = goose::config::Config::global();
+    let Some(settings) = SyncConfig::load(config)? else {
+        return Err(anyhow::anyhow!(
+            "Session sync is not configured; set {} in your config",
+            SESSION_SYNC_KEY
+        ));
+    };
+    let backend = settings.backend(config)?;
+    let report = sync_sessions(backend.as_ref()).await?;
+
+    println!(
+        "Uploaded {} and downloaded {} sessions",
+        report.uploaded.len(),
+        report.downloaded.len()
+    );
+    for (name, kept_as) in &report.conflicts {
+        println!(
+            "  {} changed on both machines; the older copy was kept as {}",
+            name, kept_as
+        );
+    }
+    Ok(())
+}
+
+/// Redeem a handoff token issued by another client and move to the session's working
+/// directory, so the session continues where it was started
+pub fn redeem_session_handoff(token: &str) -> Result<Handoff> {
+    let handoff = handoff::redeem_handoff(token)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Handoff token {} is unknown, already used or expired; ask for a new one",
+            token
+        )
+    })?;
+    if !handoff.session_path.exists() {
+        return Err(anyhow::anyhow!(
+            "The handed-off session {} no longer exists",
+            handoff.session_id
+        ));
+    }
+    if handoff.working_dir.is_dir() {
+        std::env::set_current_dir(&handoff.working_dir).with_context(|| {
+            format!(
+                "Failed to change to the session's working directory {}",
+                handoff.working_dir.display()
+            )
+        })?;
+    }
+    Ok(handoff)
+}
+
 /// Export a session to Markdown without creating a full Session object
 ///
 /// This function directly reads messages from the session file and converts them to Markdown
@@ -195,9 +264,22 @@ pub fn handle_session_export(identifier: Identifier, output_path: Option<PathBuf
     };
 
     // Generate the markdown content using the export functionality
-    let markdown =
+    let mut markdown =
         export_session_to_markdown(messages.messages().clone(), &session_file_path, None);
 
+    // Record the checksum so the export can be matched to the session it came from
+    let report = integrity::verify_session(&session_file_path)?;
+    match report.status {
+        IntegrityStatus::Modified => {
+            eprintln!("Warning: this session was modified outside goose since it was last saved")
+        }
+        IntegrityStatus::Unsigned => {}
+        IntegrityStatus::Valid => markdown.push_str(&format!(
+            "\n*Session checksum (SHA-256 chain): {}*\n",
+            report.actual_checksum
+        )),
+    }
+
     // Output the markdown
     if let Some(output) = output_path {
         fs::write(&output, markdown)
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use console::style;
+use goose::config::conversation_templates::list_templates;
+use goose::config::ConversationTemplate;
+
+use crate::session::{build_session, SessionBuilderConfig};
+
+pub fn handle_template_list(verbose: bool) -> Result<()> {
+    let templates = list_templates()?;
+    if templates.is_empty() {
+        println!("No saved templates. Create one with `goose template save`.");
+        return Ok(());
+    }
+
+    for template in templates {
+        match (&template.description, verbose) {
+            (Some(description), true) => println!("{} - {}", template.name, description),
+            _ => println!("{}", template.name),
+        }
+        if verbose {
+            println!("    prompt: {}", template.prompt);
+            if !template.context.is_empty() {
+                println!("    context: {}", template.context.join(", "));
+            }
+            if !template.extensions.is_empty() {
+                println!("    extensions: {}", template.extensions.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_template_save(template: ConversationTemplate) -> Result<()> {
+    template.save()?;
+    println!(
+        "{} saved template '{}'",
+        style("✓").green().bold(),
+        template.name
+    );
+    Ok(())
+}
+
+pub fn handle_template_remove(name: &str) -> Result<()> {
+    ConversationTemplate::remove(name)?;
+    println!("{} removed template '{}'", style("✓").green().bold(), name);
+    Ok(())
+}
+
+/// Start an interactive session from a template, sending its prompt as the first message
+pub async fn handle_template_start(name: &str) -> Result<()> {
+    let template = ConversationTemplate::load(name)?;
+    let prompt =
+        template.render_prompt_with(goose_mcp::computercontroller::document::read_attachment)?;
+    let extensions_override = if template.extensions.is_empty() {
+        None
+    } else {
+        Some(template.extension_configs()?)
+    };
+
+    let mut session = build_session(SessionBuilderConfig {
+        identifier: None,
+        resume: false,
+        no_session: false,
+        extensions: Vec::new(),
+        remote_extensions: Vec::new(),
+        streamable_http_extensions: Vec::new(),
+        builtins: Vec::new(),
+        extensions_override,
+        additional_system_prompt: None,
+        settings: None,
+        provider: None,
+        model: None,
+        debug: false,
+        max_tool_repetitions: None,
+        max_turns: None,
+        scheduled_job_id: None,
+        interactive: true,
+        quiet: false,
+        sub_recipes: None,
+        final_output_response: None,
+        retry_config: None,
+        critique: None,
+    })
+    .await;
+
+    session.interactive(Some(prompt)).await
+}
@@ -0,0 +1,469 @@
+use anyhow::{anyhow, Result};
+use console::{style, Key, Term};
+use goose::agents::PendingApproval;
+use goose::scheduler::ScheduledJob;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+use crate::session::plain_mode;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RunInfo {
+    id: String,
+    session_id: String,
+    working_dir: String,
+    started_at: i64,
+    tokens: i32,
+    cancelling: bool,
+}
+
+#[derive(Deserialize)]
+struct RunList {
+    runs: Vec<RunInfo>,
+}
+
+#[derive(Deserialize)]
+struct ScheduleList {
+    jobs: Vec<ScheduledJob>,
+}
+
+#[derive(Deserialize)]
+struct ApprovalList {
+    approvals: Vec<PendingApproval>,
+}
+
+struct Client {
+    base: String,
+    secret_key: String,
+    http: reqwest::Client,
+}
+
+impl Client {
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Option<T>> {
+        let mut request = self
+            .http
+            .request(method, format!("{}/v1{}", self.base, path))
+            .header("X-Secret-Key", &self.secret_key);
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(serde_json::to_vec(&body)?);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err(anyhow!(
+                "goosed rejected the secret key; pass --secret-key or set GOOSE_SERVER__SECRET_KEY"
+            ));
+        }
+        if !status.is_success() {
+            return Err(anyhow!("goosed returned {} for {}", status, path));
+        }
+        let bytes = response.bytes().await?;
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&bytes)?))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.request(reqwest::Method::GET, path, None)
+            .await?
+            .ok_or_else(|| anyhow!("goosed returned an empty response for {}", path))
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<()> {
+        self.request::<Value>(reqwest::Method::POST, path, Some(body))
+            .await
+            .map(|_| ())
+    }
+}
+
+#[derive(Default)]
+struct Dashboard {
+    runs: Vec<RunInfo>,
+    schedules: Vec<ScheduledJob>,
+    approvals: Vec<PendingApproval>,
+    /// Tokens per minute for each run, from the change since the previous refresh
+    burn: HashMap<String, f64>,
+    error: Option<String>,
+    status: Option<String>,
+}
+
+/// The row the cursor is on: runs first, then approvals
+enum Selected<'a> {
+    Run(&'a RunInfo),
+    Approval(&'a PendingApproval),
+}
+
+impl Dashboard {
+    fn selectable(&self) -> usize {
+        self.runs.len() + self.approvals.len()
+    }
+
+    fn selected(&self, cursor: usize) -> Option<Selected<'_>> {
+        match self.runs.get(cursor) {
+            Some(run) => Some(Selected::Run(run)),
+            None => self
+                .approvals
+                .get(cursor - self.runs.len())
+                .map(Selected::Approval),
+        }
+    }
+
+    fn total_burn(&self) -> f64 {
+        self.burn.values().sum()
+    }
+}
+
+/// Tokens per minute between two readings `elapsed` apart
+fn burn_rate(previous: i32, current: i32, elapsed: Duration) -> f64 {
+    let minutes = elapsed.as_secs_f64() / 60.0;
+    if minutes <= 0.0 || current <= previous {
+        return 0.0;
+    }
+    (current - previous) as f64 / minutes
+}
+
+fn format_age(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m{:02}s", s / 60, s % 60),
+        s => format!("{}h{:02}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+fn schedule_state(job: &ScheduledJob) -> &'static str {
+    if job.currently_running {
+        "running"
+    } else if job.paused {
+        "paused"
+    } else {
+        "waiting"
+    }
+}
+
+fn render(dashboard: &Dashboard, cursor: usize, url: &str) -> Vec<String> {
+    let now = chrono::Utc::now().timestamp();
+    let marker = |row: usize| if row == cursor { ">" } else { " " };
+    let mut lines = vec![
+        format!(
+            "{} {}   {} runs, {} approvals, {:.0} tokens/min",
+            style("goose top").cyan().bold(),
+            style(url).dim(),
+            dashboard.runs.len(),
+            dashboard.approvals.len(),
+            dashboard.total_burn()
+        ),
+        String::new(),
+        style("ACTIVE RUNS").bold().to_string(),
+    ];
+
+    if dashboard.runs.is_empty() {
+        lines.push(style("  none").dim().to_string());
+    }
+    for (row, run) in dashboard.runs.iter().enumerate() {
+        // Pad before styling so the escape codes don't count towards the width
+        let state = if run.cancelling {
+            style(format!("{:<10}", "cancelling")).yellow()
+        } else {
+            style(format!("{:<10}", "running")).green()
+        };
+        lines.push(format!(
+            "{} {:<24} {} {:>8} {:>8} tok {:>7.0}/min  {}",
+            marker(row),
+            run.session_id,
+            state,
+            format_age(now - run.started_at),
+            run.tokens,
+            dashboard.burn.get(&run.id).copied().unwrap_or(0.0),
+            style(&run.working_dir).dim()
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(style("PENDING APPROVALS").bold().to_string());
+    if dashboard.approvals.is_empty() {
+        lines.push(style("  none").dim().to_string());
+    }
+    for (index, approval) in dashboard.approvals.iter().enumerate() {
+        let arguments = approval.arguments.to_string();
+        lines.push(format!(
+            "{} {:<32} {:<16} expires in {:<8} {}",
+            marker(dashboard.runs.len() + index),
+            approval.tool_name,
+            format!("{:?}", approval.risk),
+            format_age(approval.expires_at - now),
+            style(goose::utils::safe_truncate(&arguments, 60)).dim()
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push(style("SCHEDULES").bold().to_string());
+    if dashboard.schedules.is_empty() {
+        lines.push(style("  none").dim().to_string());
+    }
+    for job in &dashboard.schedules {
+        let last_run = job
+            .last_run
+            .map(|t| format!("last run {} ago", format_age(now - t.timestamp())))
+            .unwrap_or_else(|| "never run".to_string());
+        lines.push(format!(
+            "  {:<24} {:<8} {:<20} {}",
+            job.id,
+            schedule_state(job),
+            job.cron,
+            style(last_run).dim()
+        ));
+    }
+
+    lines.push(String::new());
+    if let Some(error) = &dashboard.error {
+        lines.push(style(error).red().to_string());
+    } else if let Some(status) = &dashboard.status {
+        lines.push(style(status).yellow().to_string());
+    }
+    lines.push(
+        style("↑/↓ select   c cancel run   a allow   A always allow   d deny   r refresh   q quit")
+            .dim()
+            .to_string(),
+    );
+    lines
+}
+
+async fn refresh(client: &Client, dashboard: &mut Dashboard, last_refresh: &mut Instant) {
+    let fetched = async {
+        let runs: RunList = client.get("/runs").await?;
+        let approvals: ApprovalList = client.get("/approvals").await?;
+        // The scheduler is optional; a server without one still has runs to show
+        let schedules = client
+            .get::<ScheduleList>("/schedule/list")
+            .await
+            .map(|list| list.jobs)
+            .unwrap_or_default();
+        anyhow::Ok((runs.runs, approvals.approvals, schedules))
+    }
+    .await;
+
+    match fetched {
+        Ok((runs, approvals, schedules)) => {
+            let elapsed = last_refresh.elapsed();
+            let now = chrono::Utc::now().timestamp();
+            let previous: HashMap<&str, i32> = dashboard
+                .runs
+                .iter()
+                .map(|run| (run.id.as_str(), run.tokens))
+                .collect();
+            dashboard.burn = runs
+                .iter()
+                .map(|run| {
+                    // A run seen for the first time is averaged over its whole life so far
+                    let rate = match previous.get(run.id.as_str()) {
+                        Some(&before) => burn_rate(before, run.tokens, elapsed),
+                        None => burn_rate(
+                            0,
+                            run.tokens,
+                            Duration::from_secs((now - run.started_at).max(0) as u64),
+                        ),
+                    };
+                    (run.id.clone(), rate)
+                })
+                .collect();
+            dashboard.runs = runs;
+            dashboard.approvals = approvals;
+            dashboard.schedules = schedules;
+            dashboard.error = None;
+        }
+        Err(e) => dashboard.error = Some(format!("Failed to reach goosed: {}", e)),
+    }
+    *last_refresh = Instant::now();
+}
+
+/// Act on the selected row; returns a status line describing what happened
+async fn act(client: &Client, selected: Selected<'_>, key: char) -> Option<Result<String>> {
+    match (selected, key) {
+        (Selected::Run(run), 'c') => Some(
+            client
+                .post(&format!("/runs/{}/cancel", run.id), json!({}))
+                .await
+                .map(|_| format!("Cancelling run in session {}", run.session_id)),
+        ),
+        (Selected::Approval(approval), 'a' | 'A' | 'd') => {
+            let (action, verb) = match key {
+                'a' => ("allow_once", "Allowed"),
+                'A' => ("always_allow", "Always allowed"),
+                _ => ("deny", "Denied"),
+            };
+            Some(
+                client
+                    .post(
+                        &format!("/approvals/{}", approval.id),
+                        json!({ "action": action }),
+                    )
+                    .await
+                    .map(|_| format!("{} {}", verb, approval.tool_name)),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// A live view of a running goosed: active replies, pending approvals and schedules, with
+/// keys to cancel runs and answer approvals
+pub async fn handle_top(url: String, secret_key: Option<String>, interval: u64) -> Result<()> {
+    let secret_key = secret_key
+        .or_else(|| std::env::var("GOOSE_SERVER__SECRET_KEY").ok())
+        .ok_or_else(|| {
+            anyhow!("goose top needs the server's secret key; pass --secret-key or set GOOSE_SERVER__SECRET_KEY")
+        })?;
+    let client = Client {
+        base: url.trim_end_matches('/').to_string(),
+        secret_key,
+        http: reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?,
+    };
+
+    let term = Term::stdout();
+    if !term.is_term() {
+        return Err(anyhow!("goose top needs an interactive terminal"));
+    }
+
+    // console reads keys with blocking calls, so read them on their own thread
+    let (key_tx, mut key_rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let term = Term::stdout();
+        while let Ok(key) = term.read_key() {
+            if key_tx.send(key).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Plain mode prints the dashboard again only when it changes, instead of redrawing
+    let plain = plain_mode();
+    let mut last_printed = Vec::new();
+    if !plain {
+        term.hide_cursor()?;
+    }
+    let mut dashboard = Dashboard::default();
+    let mut cursor = 0;
+    let mut last_refresh = Instant::now();
+    refresh(&client, &mut dashboard, &mut last_refresh).await;
+
+    loop {
+        cursor = cursor.min(dashboard.selectable().saturating_sub(1));
+        let lines = render(&dashboard, cursor, &client.base);
+        if plain {
+            if lines != last_printed {
+                term.write_line("")?;
+                for line in &lines {
+                    term.write_line(line)?;
+                }
+                last_printed = lines;
+            }
+        } else {
+            term.clear_screen()?;
+            let (_, width) = term.size();
+            for line in lines {
+                term.write_line(&console::truncate_str(&line, width as usize, "…"))?;
+            }
+        }
+
+        let key = tokio::select! {
+            key = key_rx.recv() => key,
+            _ = tokio::time::sleep(Duration::from_secs(interval)) => {
+                refresh(&client, &mut dashboard, &mut last_refresh).await;
+                continue;
+            }
+        };
+        match key {
+            None | Some(Key::Escape | Key::Char('q')) => break,
+            Some(Key::ArrowUp | Key::Char('k')) => cursor = cursor.saturating_sub(1),
+            Some(Key::ArrowDown | Key::Char('j')) => cursor += 1,
+            Some(Key::Char('r')) => refresh(&client, &mut dashboard, &mut last_refresh).await,
+            Some(Key::Char(c)) => {
+                let result = match dashboard.selected(cursor) {
+                    Some(selected) => act(&client, selected, c).await,
+                    None => None,
+                };
+                if let Some(result) = result {
+                    dashboard.status = Some(result.unwrap_or_else(|e| format!("Failed: {}", e)));
+                    refresh(&client, &mut dashboard, &mut last_refresh).await;
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    if !plain {
+        term.clear_screen()?;
+        term.show_cursor()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_rate() {
+        assert_eq!(burn_rate(1000, 4000, Duration::from_secs(30)), 6000.0);
+        assert_eq!(burn_rate(4000, 4000, Duration::from_secs(30)), 0.0);
+        // A run that was compacted or restarted doesn't burn negative tokens
+        assert_eq!(burn_rate(4000, 100, Duration::from_secs(30)), 0.0);
+        assert_eq!(burn_rate(0, 100, Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(-5), "0s");
+        assert_eq!(format_age(42), "42s");
+        assert_eq!(format_age(125), "2m05s");
+        assert_eq!(format_age(7260), "2h01m");
+    }
+
+    #[test]
+    fn test_cursor_covers_runs_then_approvals() {
+        let dashboard = Dashboard {
+            runs: vec![RunInfo {
+                id: "run".to_string(),
+                session_id: "s".to_string(),
+                working_dir: "/tmp".to_string(),
+                started_at: 0,
+                tokens: 0,
+                cancelling: false,
+            }],
+            approvals: vec![serde_json::from_value(json!({
+                "id": "tool-1",
+                "toolName": "developer__shell",
+                "arguments": {"command": "rm -rf build"},
+                "risk": "destructive",
+                "prompt": null,
+                "requestedAt": 0,
+                "expiresAt": 600
+            }))
+            .unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(dashboard.selectable(), 2);
+        assert!(matches!(dashboard.selected(0), Some(Selected::Run(_))));
+        assert!(matches!(
+            dashboard.selected(1),
+            Some(Selected::Approval(a)) if a.id == "tool-1"
+        ));
+        assert!(dashboard.selected(2).is_none());
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use console::style;
+use goose::conversation::message::Message;
+use goose::i18n;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tokio_util::sync::CancellationToken;
+
+use crate::session::Session;
+
+/// How often the directory is rescanned for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+type Snapshot = HashMap<PathBuf, SystemTime>;
+
+/// Modification times of every file under `dir`, skipping hidden and gitignored files
+fn snapshot(dir: &Path) -> Snapshot {
+    ignore::WalkBuilder::new(dir)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.into_path(), modified))
+        })
+        .collect()
+}
+
+/// Files added, modified or removed between two snapshots
+fn changed_files(before: &Snapshot, after: &Snapshot) -> BTreeSet<PathBuf> {
+    let mut changed: BTreeSet<PathBuf> = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+fn watch_prompt(prompt: &str, changed: &BTreeSet<PathBuf>) -> String {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let files = changed
+        .iter()
+        .map(|path| {
+            let shown = path.strip_prefix(&cwd).unwrap_or(path);
+            let note = if path.exists() { "" } else { " (deleted)" };
+            format!("- {}{}", shown.display(), note)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "{}\n\nThese files changed since the last run:\n{}",
+        prompt, files
+    )
+}
+
+/// Run `prompt` in `session` each time files under `dir` change, once they have been quiet
+/// for `debounce`. Every run goes into the same session so the agent remembers earlier
+/// ones. Changes made while a run is in progress, including the agent's own edits, don't
+/// trigger another run. Ctrl+C stops watching, also while a run is in progress.
+pub async fn handle_watch(
+    mut session: Session,
+    dir: PathBuf,
+    prompt: String,
+    debounce: Duration,
+) -> Result<()> {
+    let dir = dir
+        .canonicalize()
+        .with_context(|| format!("Cannot watch {}", dir.display()))?;
+    if !dir.is_dir() {
+        anyhow::bail!("{} is not a directory", dir.display());
+    }
+
+    // One listener for the whole watch, so a Ctrl+C pressed while files are scanned or a run
+    // is in progress isn't lost between polls
+    let stop = CancellationToken::new();
+    let stop_on_ctrl_c = stop.clone();
+    let ctrl_c = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            stop_on_ctrl_c.cancel();
+        }
+    });
+
+    let mut baseline = snapshot(&dir);
+    let mut pending = BTreeSet::new();
+    let mut last_change = Instant::now();
+    println!(
+        "{} Watching {} ({} files). Press Ctrl+C to stop.",
+        style("✓").green().bold(),
+        dir.display(),
+        baseline.len()
+    );
+
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => break,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+
+        let current = snapshot(&dir);
+        let changed = changed_files(&baseline, &current);
+        if !changed.is_empty() {
+            pending.extend(changed);
+            last_change = Instant::now();
+            baseline = current;
+        }
+        if pending.is_empty() || last_change.elapsed() < debounce {
+            continue;
+        }
+
+        let changed = std::mem::take(&mut pending);
+        println!(
+            "\n{} {} file(s) changed, running",
+            style("→").cyan().bold(),
+            changed.len()
+        );
+        let message = Message::user().with_text(watch_prompt(&prompt, &changed));
+        if let Err(e) = session.process_message(message, stop.child_token()).await {
+            eprintln!("{} {}", style("✗").red().bold(), e);
+        }
+        if stop.is_cancelled() {
+            break;
+        }
+        baseline = snapshot(&dir);
+        println!("\n{} Waiting for changes", style("✓").green().bold());
+    }
+
+    ctrl_c.abort();
+    println!(
+        "\nStopped watching.{}",
+        session
+            .session_file()
+            .map(|p| format!(
+                " {}",
+                i18n::t_with("cli.recorded_to", &[("path", &p.display().to_string())])
+            ))
+            .unwrap_or_default()
+    );
+    session.end_session().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_files_reports_edits_additions_and_removals() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.rs");
+        let edited = dir.path().join("edited.rs");
+        let removed = dir.path().join("removed.rs");
+        for path in [&kept, &edited, &removed] {
+            std::fs::write(path, "fn main() {}").unwrap();
+        }
+        let before = snapshot(dir.path());
+        assert_eq!(before.len(), 3);
+
+        let mut after = before.clone();
+        after.insert(edited.clone(), SystemTime::now() + Duration::from_secs(5));
+        after.remove(&removed);
+        let added = dir.path().join("added.rs");
+        after.insert(added.clone(), SystemTime::now());
+
+        let changed = changed_files(&before, &after);
+        assert_eq!(changed, BTreeSet::from([added, edited, removed]));
+    }
+
+    #[test]
+    fn test_snapshot_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join("target/out.o"), "").unwrap();
+        std::fs::write(dir.path().join("lib.rs"), "").unwrap();
+
+        let files: Vec<_> = snapshot(dir.path()).into_keys().collect();
+        assert_eq!(files, vec![dir.path().join("lib.rs")]);
+    }
+}
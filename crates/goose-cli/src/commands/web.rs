@@ -491,6 +491,8 @@ async fn process_message_streaming(
         schedule_id: None,
         execution_mode: None,
         max_turns: None,
+        run_limits: Default::default(),
+        critique: None,
         retry_config: None,
     };
 
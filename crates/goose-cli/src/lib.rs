@@ -1,5 +1,6 @@
 use etcetera::AppStrategyArgs;
 use once_cell::sync::Lazy;
+pub mod ci;
 pub mod cli;
 pub mod commands;
 pub mod logging;
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use anyhow::{anyhow, Result};
+use goose::recipe::scaffold;
 use goose::recipe::SubRecipe;
 
 use crate::recipes::print_recipe::print_recipe_info;
@@ -11,10 +12,13 @@ use crate::{
     session::SessionSettings,
 };
 
+/// Load the recipe to run. Its scaffold is created unless `resume` is set, in which case the
+/// run that started the session already created it.
 pub fn extract_recipe_info_from_cli(
     recipe_name: String,
     params: Vec<(String, String)>,
     additional_sub_recipes: Vec<String>,
+    resume: bool,
 ) -> Result<(InputConfig, RecipeInfo)> {
     let recipe = load_recipe(&recipe_name, params.clone()).unwrap_or_else(|err| {
         eprintln!("{}: {}", console::style("Error").red().bold(), err);
@@ -47,10 +51,31 @@ pub fn extract_recipe_info_from_cli(
             }
         }
     }
+    let mut instructions = recipe.instructions;
+    if let Some(scaffold) = recipe.scaffold.as_ref().filter(|_| !resume) {
+        let report = scaffold::materialize(scaffold, &std::env::current_dir()?)?;
+        println!(
+            "{} {} files in {}{}",
+            console::style("Scaffolded").green().bold(),
+            report.created.len() + report.overwritten.len(),
+            report.root.display(),
+            if report.skipped.is_empty() {
+                String::new()
+            } else {
+                format!(", kept {} existing", report.skipped.len())
+            }
+        );
+        let description = report.describe();
+        instructions = Some(match instructions {
+            Some(instructions) => format!("{}\n\n{}", instructions, description),
+            None => description,
+        });
+    }
+
     let input_config = InputConfig {
         contents: recipe.prompt.filter(|s| !s.trim().is_empty()),
         extensions_override: recipe.extensions,
-        additional_system_prompt: recipe.instructions,
+        additional_system_prompt: instructions,
     };
 
     let recipe_info = RecipeInfo {
@@ -58,10 +83,14 @@ pub fn extract_recipe_info_from_cli(
             goose_provider: s.goose_provider,
             goose_model: s.goose_model,
             temperature: s.temperature,
+            max_turns: s.max_turns,
+            run_limits: s.run_limits(),
+            style: s.style,
         }),
         sub_recipes: Some(all_sub_recipes),
         final_output_response: recipe.response,
         retry_config: recipe.retry,
+        critique: recipe.critique,
     };
 
     Ok((input_config, recipe_info))
@@ -96,7 +125,7 @@ mod tests {
         let recipe_name = recipe_path.to_str().unwrap().to_string();
 
         let (input_config, recipe_info) =
-            extract_recipe_info_from_cli(recipe_name, params, Vec::new()).unwrap();
+            extract_recipe_info_from_cli(recipe_name, params, Vec::new(), false).unwrap();
         let settings = recipe_info.session_settings;
         let sub_recipes = recipe_info.sub_recipes;
         let response = recipe_info.final_output_response;
@@ -160,7 +189,8 @@ mod tests {
         ];
 
         let (input_config, recipe_info) =
-            extract_recipe_info_from_cli(recipe_name, params, additional_sub_recipes).unwrap();
+            extract_recipe_info_from_cli(recipe_name, params, additional_sub_recipes, false)
+                .unwrap();
         let settings = recipe_info.session_settings;
         let sub_recipes = recipe_info.sub_recipes;
         let response = recipe_info.final_output_response;
@@ -2,5 +2,6 @@ pub mod extract_from_cli;
 pub mod github_recipe;
 pub mod print_recipe;
 pub mod recipe;
+pub mod recipe_test;
 pub mod search_recipe;
 pub mod secret_discovery;
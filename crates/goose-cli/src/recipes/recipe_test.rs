@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use goose::agents::{Agent, RunLimits, ToolRecorder};
+use goose::config::{Config, ExtensionConfigManager};
+use goose::model::ModelConfig;
+use goose::providers::base::Provider;
+use goose::providers::create;
+use goose::providers::testprovider::TestProvider;
+use goose::recipe::build_recipe::build_recipe_from_template;
+use goose::recipe::Recipe;
+use goose::session;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::recipes::search_recipe::retrieve_recipe_file;
+use crate::session::Session;
+
+/// Test cases for a recipe, read from `<recipe>.test.yaml` next to the recipe by default
+#[derive(Debug, Deserialize)]
+pub struct RecipeTestSpec {
+    pub cases: Vec<RecipeTestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecipeTestCase {
+    pub name: String,
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+    #[serde(default)]
+    pub criteria: Vec<SuccessCriterion>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SuccessCriterion {
+    /// A file exists after the run
+    FileExists { path: PathBuf },
+    /// A shell command exits 0 after the run
+    Command { command: String },
+    /// The final assistant message matches a regex
+    OutputMatches { pattern: String },
+}
+
+impl SuccessCriterion {
+    fn check(&self, output: &str) -> Result<(), String> {
+        match self {
+            SuccessCriterion::FileExists { path } => {
+                if path.exists() {
+                    Ok(())
+                } else {
+                    Err(format!("file {} does not exist", path.display()))
+                }
+            }
+            SuccessCriterion::Command { command } => {
+                let status = if cfg!(windows) {
+                    Command::new("cmd").arg("/C").arg(command).status()
+                } else {
+                    Command::new("sh").arg("-c").arg(command).status()
+                };
+                match status {
+                    Ok(status) if status.success() => Ok(()),
+                    Ok(status) => Err(format!("`{}` exited with {}", command, status)),
+                    Err(e) => Err(format!("`{}` could not be run: {}", command, e)),
+                }
+            }
+            SuccessCriterion::OutputMatches { pattern } => {
+                let re = Regex::new(pattern).map_err(|e| format!("invalid pattern: {}", e))?;
+                if re.is_match(output) {
+                    Ok(())
+                } else {
+                    Err(format!("output did not match /{}/", pattern))
+                }
+            }
+        }
+    }
+}
+
+pub struct RecipeTestOptions {
+    pub spec: Option<PathBuf>,
+    pub fixtures: Option<PathBuf>,
+    pub live: bool,
+    pub max_turns: u32,
+    pub max_tokens: Option<u32>,
+}
+
+pub struct CaseResult {
+    pub name: String,
+    pub duration_secs: f64,
+    pub failures: Vec<String>,
+}
+
+pub struct RecipeTestReport {
+    pub recipe_title: String,
+    pub cases: Vec<CaseResult>,
+}
+
+impl RecipeTestReport {
+    pub fn failed(&self) -> usize {
+        self.cases.iter().filter(|c| !c.failures.is_empty()).count()
+    }
+
+    pub fn to_junit_xml(&self) -> String {
+        let total_time: f64 = self.cases.iter().map(|c| c.duration_secs).sum();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuites tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            self.cases.len(),
+            self.failed(),
+            total_time
+        ));
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" time=\"{:.3}\">\n",
+            xml_escape(&self.recipe_title),
+            self.cases.len(),
+            self.failed(),
+            total_time
+        ));
+        for case in &self.cases {
+            let open = format!(
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                xml_escape(&case.name),
+                xml_escape(&self.recipe_title),
+                case.duration_secs
+            );
+            if case.failures.is_empty() {
+                xml.push_str(&open);
+                xml.push_str("/>\n");
+            } else {
+                xml.push_str(&open);
+                xml.push_str(">\n");
+                xml.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    xml_escape(&case.failures[0]),
+                    xml_escape(&case.failures.join("\n"))
+                ));
+                xml.push_str("    </testcase>\n");
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn default_sidecar(recipe_path: &Path, suffix: &str) -> PathBuf {
+    let stem = recipe_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("recipe");
+    recipe_path.with_file_name(format!("{}{}", stem, suffix))
+}
+
+async fn run_case(
+    recipe_name: &str,
+    case: &RecipeTestCase,
+    options: &RecipeTestOptions,
+    fixtures_dir: &Path,
+) -> Result<Vec<String>> {
+    let params: Vec<(String, String)> = case
+        .params
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    let recipe_file = retrieve_recipe_file(recipe_name)?;
+    let recipe: Recipe = build_recipe_from_template(
+        recipe_file,
+        params,
+        None::<fn(&str, &str) -> Result<String>>,
+    )
+    .map_err(|e| anyhow!(e.to_string()))?;
+    let prompt = recipe
+        .prompt
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| anyhow!("Recipe has no prompt to test"))?;
+
+    let provider_file = fixtures_dir.join(format!("{}.json", case.name));
+    let tools_file = fixtures_dir.join(format!("{}.tools.json", case.name));
+
+    let agent = Agent::new();
+    let recording = if options.live {
+        let config = Config::global();
+        let provider_name = recipe
+            .settings
+            .as_ref()
+            .and_then(|s| s.goose_provider.clone())
+            .or_else(|| config.get_param("GOOSE_PROVIDER").ok())
+            .ok_or_else(|| anyhow!("No provider configured. Run 'goose configure' first"))?;
+        let model_name = recipe
+            .settings
+            .as_ref()
+            .and_then(|s| s.goose_model.clone())
+            .or_else(|| config.get_param("GOOSE_MODEL").ok())
+            .ok_or_else(|| anyhow!("No model configured. Run 'goose configure' first"))?;
+        let inner = create(&provider_name, ModelConfig::new(&model_name)?)?;
+        std::fs::create_dir_all(fixtures_dir)?;
+        let provider = Arc::new(TestProvider::new_recording(
+            inner,
+            provider_file.to_string_lossy(),
+        ));
+        agent
+            .update_provider(provider.clone() as Arc<dyn Provider>)
+            .await?;
+        let tool_recorder = Arc::new(ToolRecorder::new_recording(&tools_file));
+        agent.set_tool_recorder(Some(tool_recorder.clone())).await;
+        Some((provider, tool_recorder))
+    } else {
+        if !provider_file.exists() {
+            return Err(anyhow!(
+                "No fixture at {}. Run with --live to record one.",
+                provider_file.display()
+            ));
+        }
+        let provider = TestProvider::new_replaying(provider_file.to_string_lossy())?;
+        agent.update_provider(Arc::new(provider)).await?;
+        if tools_file.exists() {
+            agent
+                .set_tool_recorder(Some(Arc::new(ToolRecorder::new_replaying(&tools_file)?)))
+                .await;
+        }
+        None
+    };
+
+    let extensions = match recipe.extensions.clone() {
+        Some(extensions) => {
+            agent.disable_router_for_recipe().await;
+            extensions
+        }
+        None => ExtensionConfigManager::get_all()?
+            .into_iter()
+            .filter(|ext| ext.enabled)
+            .map(|ext| ext.config)
+            .collect(),
+    };
+    for extension in extensions {
+        agent.add_extension(extension).await?;
+    }
+    if let Some(instructions) = recipe.instructions.clone() {
+        agent.extend_system_prompt(instructions).await;
+    }
+    if let Some(sub_recipes) = recipe.sub_recipes.clone() {
+        agent.add_sub_recipes(sub_recipes).await;
+    }
+    if let Some(response) = recipe.response.clone() {
+        agent.add_final_output_tool(response).await;
+    }
+
+    let session_file = session::get_path(session::Identifier::Name(format!(
+        "recipe_test_{}",
+        session::generate_session_id()
+    )))?;
+    let mut session = Session::new(
+        agent,
+        Some(session_file),
+        false,
+        None,
+        Some(options.max_turns),
+        None,
+        recipe.retry.clone(),
+    );
+    // The budget ends the run once it is spent, rather than only failing the case afterwards
+    session.set_run_limits(RunLimits {
+        max_tokens: options.max_tokens,
+        ..recipe
+            .settings
+            .as_ref()
+            .map(|s| s.run_limits())
+            .unwrap_or_default()
+    });
+
+    let mut failures = Vec::new();
+    if let Err(e) = session.headless(prompt).await {
+        failures.push(format!("run failed: {}", e));
+    }
+
+    if let Some(max_tokens) = options.max_tokens {
+        if let Ok(Some(used)) = session.get_total_token_usage() {
+            if i64::from(used) >= i64::from(max_tokens) {
+                failures.push(format!(
+                    "token budget exceeded: used {} of {}",
+                    used, max_tokens
+                ));
+            }
+        }
+    }
+
+    let output = session
+        .message_history()
+        .last()
+        .map(|m| m.as_concat_text())
+        .unwrap_or_default();
+
+    // Checks declared for retries are success criteria too
+    let retry_checks = recipe.retry.iter().flat_map(|retry| {
+        retry.checks.iter().map(|check| match check {
+            goose::agents::SuccessCheck::Shell { command } => SuccessCriterion::Command {
+                command: command.clone(),
+            },
+        })
+    });
+    for criterion in case.criteria.iter().cloned().chain(retry_checks) {
+        if let Err(failure) = criterion.check(&output) {
+            failures.push(failure);
+        }
+    }
+
+    drop(session);
+    // Only a passing live run becomes the new fixture
+    if let Some((provider, tool_recorder)) = recording {
+        if failures.is_empty() {
+            provider.save_records()?;
+            tool_recorder.save_records()?;
+        }
+    }
+
+    Ok(failures)
+}
+
+pub async fn run_recipe_tests(
+    recipe_name: &str,
+    options: &RecipeTestOptions,
+) -> Result<RecipeTestReport> {
+    let recipe_file = retrieve_recipe_file(recipe_name)?;
+    let recipe_path = recipe_file.file_path.clone();
+    let recipe_title = Recipe::from_content(&recipe_file.content)
+        .map(|r| r.title)
+        .unwrap_or_else(|_| recipe_name.to_string());
+
+    let spec_path = options
+        .spec
+        .clone()
+        .unwrap_or_else(|| default_sidecar(&recipe_path, ".test.yaml"));
+    let spec: RecipeTestSpec = serde_yaml::from_str(
+        &std::fs::read_to_string(&spec_path)
+            .map_err(|e| anyhow!("Failed to read test spec {}: {}", spec_path.display(), e))?,
+    )?;
+    let fixtures_dir = options
+        .fixtures
+        .clone()
+        .unwrap_or_else(|| default_sidecar(&recipe_path, ".fixtures"));
+
+    let mut cases = Vec::new();
+    for case in &spec.cases {
+        let start = Instant::now();
+        let failures = match run_case(recipe_name, case, options, &fixtures_dir).await {
+            Ok(failures) => failures,
+            Err(e) => vec![e.to_string()],
+        };
+        cases.push(CaseResult {
+            name: case.name.clone(),
+            duration_secs: start.elapsed().as_secs_f64(),
+            failures,
+        });
+    }
+
+    Ok(RecipeTestReport {
+        recipe_title,
+        cases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let spec: RecipeTestSpec = serde_yaml::from_str(
+            r#"
+cases:
+  - name: basic
+    params:
+      language: rust
+    criteria:
+      - type: file_exists
+        path: Cargo.toml
+      - type: command
+        command: "true"
+      - type: output_matches
+        pattern: "(?i)done"
+"#,
+        )
+        .unwrap();
+        assert_eq!(spec.cases.len(), 1);
+        assert_eq!(spec.cases[0].params["language"], "rust");
+        assert_eq!(spec.cases[0].criteria.len(), 3);
+    }
+
+    #[test]
+    fn test_output_matches() {
+        let criterion = SuccessCriterion::OutputMatches {
+            pattern: "^All done".to_string(),
+        };
+        assert!(criterion.check("All done here").is_ok());
+        assert!(criterion.check("Not finished").is_err());
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let report = RecipeTestReport {
+            recipe_title: "My <Recipe>".to_string(),
+            cases: vec![
+                CaseResult {
+                    name: "passes".to_string(),
+                    duration_secs: 1.0,
+                    failures: vec![],
+                },
+                CaseResult {
+                    name: "fails".to_string(),
+                    duration_secs: 0.5,
+                    failures: vec!["file out.txt does not exist".to_string()],
+                },
+            ],
+        };
+        let xml = report.to_junit_xml();
+        assert!(xml.contains("<testsuite name=\"My &lt;Recipe&gt;\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"passes\""));
+        assert!(xml.contains("<failure message=\"file out.txt does not exist\">"));
+    }
+}
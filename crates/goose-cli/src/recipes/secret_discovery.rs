@@ -57,6 +57,8 @@ fn extract_secrets_from_extensions(
             ExtensionConfig::Builtin { name, .. } => (name, &Vec::new()),
             ExtensionConfig::Frontend { name, .. } => (name, &Vec::new()),
             ExtensionConfig::InlinePython { name, .. } => (name, &Vec::new()),
+            ExtensionConfig::Wasm { name, env_keys, .. } => (name, env_keys),
+            ExtensionConfig::Plugin { name, .. } => (name, &Vec::new()),
         };
 
         for key in env_keys {
@@ -173,6 +175,9 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            critique: None,
+            artifacts: None,
+            scaffold: None,
         }
     }
 
@@ -217,6 +222,9 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            critique: None,
+            artifacts: None,
+            scaffold: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -262,6 +270,9 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            critique: None,
+            artifacts: None,
+            scaffold: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -315,6 +326,9 @@ mod tests {
             parameters: None,
             response: None,
             retry: None,
+            critique: None,
+            artifacts: None,
+            scaffold: None,
         };
 
         let secrets = discover_recipe_secrets(&recipe);
@@ -63,6 +63,22 @@ impl McpClientTrait for MockClient {
         Err(Error::UnexpectedResponse)
     }
 
+    async fn subscribe_resource(
+        &self,
+        _uri: &str,
+        _cancel_token: CancellationToken,
+    ) -> Result<(), Error> {
+        Err(Error::UnexpectedResponse)
+    }
+
+    async fn unsubscribe_resource(
+        &self,
+        _uri: &str,
+        _cancel_token: CancellationToken,
+    ) -> Result<(), Error> {
+        Err(Error::UnexpectedResponse)
+    }
+
     async fn list_tools(
         &self,
         _: Option<String>,
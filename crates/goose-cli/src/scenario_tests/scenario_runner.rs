@@ -6,7 +6,7 @@ use crate::scenario_tests::mock_client::weather_client;
 use crate::scenario_tests::provider_configs::{get_provider_configs, ProviderConfig};
 use crate::session::Session;
 use anyhow::Result;
-use goose::agents::Agent;
+use goose::agents::{Agent, ToolRecorder};
 use goose::model::ModelConfig;
 use goose::providers::{create, testprovider::TestProvider};
 use std::collections::{HashMap, HashSet};
@@ -154,6 +154,8 @@ where
         std::fs::create_dir_all(parent)?;
     }
 
+    let tools_file_path = file_path.replace(".json", ".tools.json");
+
     let replay_mode = Path::new(&file_path).exists();
     let (provider_arc, provider_for_saving, original_env) = if replay_mode {
         match TestProvider::new_replaying(&file_path) {
@@ -198,6 +200,16 @@ where
         extension_manager.add_client("weather_extension".to_string(), Box::new(mock_client));
     }
 
+    // Older recordings only captured provider traffic; fall back to the live mock tools for those
+    let tool_recorder = if !replay_mode {
+        Some(Arc::new(ToolRecorder::new_recording(&tools_file_path)))
+    } else if Path::new(&tools_file_path).exists() {
+        Some(Arc::new(ToolRecorder::new_replaying(&tools_file_path)?))
+    } else {
+        None
+    };
+    agent.set_tool_recorder(tool_recorder.clone()).await;
+
     agent
         .update_provider(provider_arc as Arc<dyn goose::providers::base::Provider>)
         .await?;
@@ -240,6 +252,9 @@ where
             Arc::try_unwrap(provider)
                 .map_err(|_| anyhow::anyhow!("Failed to unwrap provider for recording"))?
                 .finish_recording()?;
+            if let Some(recorder) = &tool_recorder {
+                recorder.save_records()?;
+            }
         }
     }
 
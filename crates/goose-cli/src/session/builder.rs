@@ -1,6 +1,7 @@
 use console::style;
-use goose::agents::types::RetryConfig;
-use goose::agents::Agent;
+use goose::agents::types::{CritiqueConfig, RetryConfig};
+use goose::agents::{Agent, RunLimits};
+use goose::config::base::PROJECT_CONFIG_KEYS;
 use goose::config::{Config, ExtensionConfig, ExtensionConfigManager};
 use goose::providers::create;
 use goose::recipe::{Response, SubRecipe};
@@ -61,6 +62,42 @@ pub struct SessionBuilderConfig {
     pub final_output_response: Option<Response>,
     /// Retry configuration for automated validation and recovery
     pub retry_config: Option<RetryConfig>,
+    /// Review of the final answer of each run against recipe-defined criteria
+    pub critique: Option<CritiqueConfig>,
+}
+
+/// Asks whether to load the repository's .goose/config.yaml, which is ignored until its directory
+/// is trusted. Only the keys in `PROJECT_CONFIG_KEYS` are ever taken from it.
+fn offer_project_config_trust(config: &Config, interactive: bool) {
+    let Some(path) = config.untrusted_project_path() else {
+        return;
+    };
+    if !interactive {
+        output::render_text(
+            &format!(
+                "Ignoring untrusted project config {}; run goose interactively in this directory to trust it.",
+                path.display()
+            ),
+            Some(console::Color::Yellow),
+            true,
+        );
+        return;
+    }
+
+    let prompt = format!(
+        "This directory has a project config at {}. Trust it? It can only set {}.",
+        path.display(),
+        PROJECT_CONFIG_KEYS.join(", ")
+    );
+    match cliclack::confirm(prompt).initial_value(false).interact() {
+        Ok(true) => {
+            if let Err(e) = config.trust_project() {
+                output::render_error(&format!("Failed to trust project config: {}", e));
+            }
+        }
+        Ok(false) => {}
+        Err(e) => tracing::warn!("Could not ask about project config: {}", e),
+    }
 }
 
 /// Offers to help debug an extension failure by creating a minimal debugging session
@@ -170,11 +207,15 @@ pub struct SessionSettings {
     pub goose_model: Option<String>,
     pub goose_provider: Option<String>,
     pub temperature: Option<f32>,
+    pub max_turns: Option<u32>,
+    pub run_limits: RunLimits,
+    pub style: Option<String>,
 }
 
 pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
     // Load config and get provider/model
     let config = Config::global();
+    offer_project_config_trust(config, session_config.interactive);
 
     let provider_name = session_config
         .provider
@@ -218,6 +259,14 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         agent.add_final_output_tool(final_output_response).await;
     }
 
+    if let Some(style) = session_config
+        .settings
+        .as_ref()
+        .and_then(|s| s.style.clone())
+    {
+        agent.set_style(Some(style)).await;
+    }
+
     let new_provider = match create(&provider_name, model_config) {
         Ok(provider) => provider,
         Err(e) => {
@@ -409,10 +458,16 @@ pub async fn build_session(session_config: SessionBuilderConfig) -> Session {
         session_file.clone(),
         session_config.debug,
         session_config.scheduled_job_id.clone(),
-        session_config.max_turns,
+        session_config
+            .max_turns
+            .or_else(|| session_config.settings.as_ref().and_then(|s| s.max_turns)),
         edit_mode,
         session_config.retry_config.clone(),
     );
+    if let Some(settings) = &session_config.settings {
+        session.set_run_limits(settings.run_limits);
+    }
+    session.set_critique(session_config.critique);
 
     // Add extensions if provided
     for extension_str in session_config.extensions {
@@ -608,6 +663,7 @@ mod tests {
             sub_recipes: None,
             final_output_response: None,
             retry_config: None,
+            critique: None,
         };
 
         assert_eq!(config.extensions.len(), 1);
@@ -134,6 +134,15 @@ impl GooseCompleter {
             "/prompt",
             "/mode",
             "/recipe",
+            "/elevate",
+            "/unelevate",
+            "/summarize",
+            "/compact",
+            "/model",
+            "/style",
+            "/extensions",
+            "/tokens",
+            "/fork",
         ];
 
         // Find commands that match the prefix
@@ -424,9 +433,13 @@ impl Highlighter for GooseCompleter {
 impl Validator for GooseCompleter {
     fn validate(
         &self,
-        _ctx: &mut rustyline::validate::ValidationContext,
+        ctx: &mut rustyline::validate::ValidationContext,
     ) -> Result<rustyline::validate::ValidationResult> {
-        Ok(rustyline::validate::ValidationResult::Valid(None))
+        if super::input::is_input_complete(ctx.input()) {
+            Ok(rustyline::validate::ValidationResult::Valid(None))
+        } else {
+            Ok(rustyline::validate::ValidationResult::Incomplete)
+        }
     }
 }
 
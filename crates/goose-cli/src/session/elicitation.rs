@@ -0,0 +1,130 @@
+//! Asking the user for the input an extension requests in the middle of a tool call. The
+//! request's schema is a flat object, so each of its properties is one prompt.
+
+use anyhow::Result;
+use goose::agents::PendingApproval;
+use goose::i18n;
+use rmcp::model::JsonObject;
+use serde_json::Value;
+use std::io::ErrorKind;
+
+/// Treat an interrupted prompt (Ctrl+C, Escape) as the user declining to answer
+fn unless_interrupted<T>(result: std::io::Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.kind() == ErrorKind::Interrupted => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Ask for `property` of the requested schema. The inner option is empty when the user left an
+/// optional property blank, the outer one when they interrupted.
+fn ask_property(name: &str, property: &Value, required: bool) -> Result<Option<Option<Value>>> {
+    let label = property
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or(name)
+        .to_string();
+    let description = property
+        .get("description")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let kind = property
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("string");
+
+    if kind == "boolean" {
+        let default = property.get("default").and_then(Value::as_bool);
+        let answer = cliclack::confirm(&label)
+            .initial_value(default.unwrap_or(false))
+            .interact();
+        return Ok(unless_interrupted(answer)?.map(|answer| Some(Value::Bool(answer))));
+    }
+
+    if let Some(options) = property.get("enum").and_then(Value::as_array) {
+        let names = property.get("enumNames").and_then(Value::as_array);
+        let mut select = cliclack::select(&label);
+        for (i, option) in options.iter().filter_map(Value::as_str).enumerate() {
+            let name = names
+                .and_then(|names| names.get(i))
+                .and_then(Value::as_str)
+                .unwrap_or(option);
+            select = select.item(option.to_string(), name, "");
+        }
+        let answer = select.interact();
+        return Ok(unless_interrupted(answer)?.map(|answer| Some(Value::String(answer))));
+    }
+
+    let invalid_number = i18n::t("cli.elicitation_invalid_number");
+    let integer = kind == "integer";
+    let mut input = cliclack::input(&label)
+        .placeholder(description)
+        .required(required);
+    if integer || kind == "number" {
+        input = input.validate(move |input: &String| {
+            let valid = input.is_empty()
+                || if integer {
+                    input.parse::<i64>().is_ok()
+                } else {
+                    input.parse::<f64>().is_ok()
+                };
+            if valid {
+                Ok(())
+            } else {
+                Err(invalid_number.clone())
+            }
+        });
+    }
+    let Some(answer) = unless_interrupted(input.interact::<String>())? else {
+        return Ok(None);
+    };
+    if answer.is_empty() {
+        return Ok(Some(None));
+    }
+    let value = match kind {
+        "integer" => answer.parse::<i64>().map(Value::from)?,
+        "number" => answer.parse::<f64>().map(Value::from)?,
+        _ => Value::String(answer),
+    };
+    Ok(Some(Some(value)))
+}
+
+/// Ask the user for what `request` is after. Returns their answer, or none when they decline.
+pub fn ask(request: &PendingApproval) -> Result<Option<JsonObject>> {
+    let extension = request
+        .tool_name
+        .strip_suffix("__elicitation")
+        .unwrap_or(&request.tool_name);
+    let question = i18n::t_with(
+        "cli.elicitation_request",
+        &[
+            ("extension", extension),
+            ("message", request.prompt.as_deref().unwrap_or_default()),
+        ],
+    );
+    let answering = cliclack::confirm(question).initial_value(true).interact();
+    if unless_interrupted(answering)? != Some(true) {
+        return Ok(None);
+    }
+
+    let schema = &request.arguments;
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let mut content = JsonObject::new();
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (name, property) in properties {
+            match ask_property(name, property, required.contains(&name.as_str()))? {
+                Some(Some(value)) => {
+                    content.insert(name.clone(), value);
+                }
+                Some(None) => {}
+                None => return Ok(None),
+            }
+        }
+    }
+    Ok(Some(content))
+}
@@ -1,8 +1,10 @@
 use super::completion::GooseCompleter;
 use anyhow::Result;
+use goose::i18n;
 use rustyline::Editor;
 use shlex;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub enum InputResult {
@@ -21,6 +23,13 @@ pub enum InputResult {
     Clear,
     Recipe(Option<String>),
     Summarize,
+    Elevate(Option<u64>),
+    Unelevate,
+    Model(Option<String>),
+    Style(Option<String>),
+    ListExtensions,
+    Tokens,
+    Fork(Option<String>),
 }
 
 #[derive(Debug)]
@@ -62,6 +71,10 @@ pub fn get_input(
         rustyline::KeyEvent(rustyline::KeyCode::Char('j'), rustyline::Modifiers::CTRL),
         rustyline::EventHandler::Simple(rustyline::Cmd::Newline),
     );
+    editor.bind_sequence(
+        rustyline::KeyEvent(rustyline::KeyCode::Enter, rustyline::Modifiers::ALT),
+        rustyline::EventHandler::Simple(rustyline::Cmd::Newline),
+    );
 
     editor.bind_sequence(
         rustyline::KeyEvent(rustyline::KeyCode::Char('c'), rustyline::Modifiers::CTRL),
@@ -82,6 +95,7 @@ pub fn get_input(
     if !input.trim().is_empty() {
         editor.add_history_entry(input.as_str())?;
     }
+    let input = join_continued_lines(&input);
 
     // Handle non-slash commands first
     if !input.starts_with('/') {
@@ -121,6 +135,11 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_CLEAR: &str = "/clear";
     const CMD_RECIPE: &str = "/recipe";
     const CMD_SUMMARIZE: &str = "/summarize";
+    const CMD_ELEVATE: &str = "/elevate";
+    const CMD_UNELEVATE: &str = "/unelevate";
+    const CMD_MODEL: &str = "/model";
+    const CMD_STYLE: &str = "/style";
+    const CMD_FORK: &str = "/fork";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -180,11 +199,73 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s == CMD_ENDPLAN => Some(InputResult::EndPlan),
         s if s == CMD_CLEAR => Some(InputResult::Clear),
         s if s.starts_with(CMD_RECIPE) => parse_recipe_command(s),
-        s if s == CMD_SUMMARIZE => Some(InputResult::Summarize),
+        s if s == CMD_SUMMARIZE || s == "/compact" => Some(InputResult::Summarize),
+        "/extensions" => Some(InputResult::ListExtensions),
+        "/tokens" => Some(InputResult::Tokens),
+        s if s == CMD_MODEL => Some(InputResult::Model(None)),
+        s if s.starts_with(&format!("{} ", CMD_MODEL)) => Some(InputResult::Model(Some(
+            s[CMD_MODEL.len()..].trim().to_string(),
+        ))),
+        s if s == CMD_STYLE => Some(InputResult::Style(None)),
+        s if s.starts_with(&format!("{} ", CMD_STYLE)) => Some(InputResult::Style(Some(
+            s[CMD_STYLE.len()..].trim().to_string(),
+        ))),
+        s if s == CMD_FORK => Some(InputResult::Fork(None)),
+        s if s.starts_with(&format!("{} ", CMD_FORK)) => Some(InputResult::Fork(Some(
+            s[CMD_FORK.len()..].trim().to_string(),
+        ))),
+        s if s == CMD_UNELEVATE => Some(InputResult::Unelevate),
+        s if s == CMD_ELEVATE => Some(InputResult::Elevate(None)),
+        s if s.starts_with(&format!("{} ", CMD_ELEVATE)) => {
+            parse_elevate_command(s[CMD_ELEVATE.len()..].trim())
+        }
         _ => None,
     }
 }
 
+/// A line ending in a backslash continues on the next one; drop the backslashes
+fn join_continued_lines(input: &str) -> String {
+    input.replace("\\\n", "\n")
+}
+
+/// Whether Enter should submit `input` or start a new line: it starts one after a trailing
+/// backslash or inside an unclosed ``` code fence
+pub fn is_input_complete(input: &str) -> bool {
+    if input.ends_with('\\') {
+        return false;
+    }
+    let fences = input
+        .lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count();
+    fences % 2 == 0
+}
+
+/// Input history is kept per project, so the up arrow recalls what was typed in this
+/// repository. A project is the enclosing git repository, or the directory itself.
+pub fn history_file(history_dir: &Path, working_dir: &Path) -> PathBuf {
+    let project = working_dir
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .unwrap_or(working_dir);
+    let name: String = project
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    history_dir.join(format!("{}.txt", name.trim_matches('_')))
+}
+
+fn parse_elevate_command(minutes: &str) -> Option<InputResult> {
+    match minutes.parse::<u64>() {
+        Ok(minutes) if minutes > 0 => Some(InputResult::Elevate(Some(minutes))),
+        _ => {
+            println!("Usage: /elevate [minutes] - minutes must be a positive number");
+            Some(InputResult::Retry)
+        }
+    }
+}
+
 fn parse_recipe_command(s: &str) -> Option<InputResult> {
     const CMD_RECIPE: &str = "/recipe";
 
@@ -272,33 +353,7 @@ fn parse_plan_command(input: String) -> Option<InputResult> {
 }
 
 fn print_help() {
-    println!(
-        "Available commands:
-/exit or /quit - Exit the session
-/t - Toggle Light/Dark/Ansi theme
-/t <name> - Set theme directly (light, dark, ansi)
-/extension <command> - Add a stdio extension (format: ENV1=val1 command args...)
-/builtin <names> - Add builtin extensions by name (comma-separated)
-/prompts [--extension <name>] - List all available prompts, optionally filtered by extension
-/prompt <n> [--info] [key=value...] - Get prompt info or execute a prompt
-/mode <name> - Set the goose mode to use ('auto', 'approve', 'chat', 'smart_approve')
-/plan <message_text> -  Enters 'plan' mode with optional message. Create a plan based on the current messages and asks user if they want to act on it.
-                        If user acts on the plan, goose mode is set to 'auto' and returns to 'normal' goose mode.
-                        To warm up goose before using '/plan', we recommend setting '/mode approve' & putting appropriate context into goose.
-                        The model is used based on $GOOSE_PLANNER_PROVIDER and $GOOSE_PLANNER_MODEL environment variables.
-                        If no model is set, the default model is used.
-/endplan - Exit plan mode and return to 'normal' goose mode.
-/recipe [filepath] - Generate a recipe from the current conversation and save it to the specified filepath (must end with .yaml).
-                       If no filepath is provided, it will be saved to ./recipe.yaml.
-/summarize - Summarize the current conversation to reduce context length while preserving key information.
-/? or /help - Display this help message
-/clear - Clears the current chat history
-
-Navigation:
-Ctrl+C - Clear current line if text is entered, otherwise exit the session
-Ctrl+J - Add a newline
-Up/Down arrows - Navigate through command history"
-    );
+    println!("{}", i18n::t("cli.help"));
 }
 
 #[cfg(test)]
@@ -536,4 +591,109 @@ mod tests {
         let result = handle_slash_command("  /summarize  ");
         assert!(matches!(result, Some(InputResult::Summarize)));
     }
+
+    #[test]
+    fn test_elevate_command() {
+        assert!(matches!(
+            handle_slash_command("/elevate"),
+            Some(InputResult::Elevate(None))
+        ));
+        assert!(matches!(
+            handle_slash_command("/elevate 45"),
+            Some(InputResult::Elevate(Some(45)))
+        ));
+        assert!(matches!(
+            handle_slash_command("/elevate soon"),
+            Some(InputResult::Retry)
+        ));
+        assert!(matches!(
+            handle_slash_command("/unelevate"),
+            Some(InputResult::Unelevate)
+        ));
+        assert!(handle_slash_command("/elevated").is_none());
+    }
+
+    #[test]
+    fn test_session_commands() {
+        assert!(matches!(
+            handle_slash_command("/compact"),
+            Some(InputResult::Summarize)
+        ));
+        assert!(matches!(
+            handle_slash_command("/extensions"),
+            Some(InputResult::ListExtensions)
+        ));
+        assert!(matches!(
+            handle_slash_command("/tokens"),
+            Some(InputResult::Tokens)
+        ));
+        assert!(matches!(
+            handle_slash_command("/model"),
+            Some(InputResult::Model(None))
+        ));
+        if let Some(InputResult::Model(Some(model))) = handle_slash_command("/model gpt-4o") {
+            assert_eq!(model, "gpt-4o");
+        } else {
+            panic!("Expected Model");
+        }
+        assert!(matches!(
+            handle_slash_command("/style"),
+            Some(InputResult::Style(None))
+        ));
+        if let Some(InputResult::Style(Some(name))) = handle_slash_command("/style terse") {
+            assert_eq!(name, "terse");
+        } else {
+            panic!("Expected Style");
+        }
+        assert!(matches!(
+            handle_slash_command("/fork"),
+            Some(InputResult::Fork(None))
+        ));
+        if let Some(InputResult::Fork(Some(name))) = handle_slash_command("/fork experiment") {
+            assert_eq!(name, "experiment");
+        } else {
+            panic!("Expected Fork");
+        }
+        // /mode and /extension still parse as before
+        assert!(matches!(
+            handle_slash_command("/mode auto"),
+            Some(InputResult::GooseMode(_))
+        ));
+        assert!(matches!(
+            handle_slash_command("/extension foo"),
+            Some(InputResult::AddExtension(_))
+        ));
+    }
+
+    #[test]
+    fn test_multi_line_input() {
+        assert!(is_input_complete("fix the tests"));
+        assert!(!is_input_complete("first line \\"));
+        assert!(!is_input_complete("look at this:\n```rust\nfn main() {}"));
+        assert!(is_input_complete(
+            "look at this:\n```rust\nfn main() {}\n```"
+        ));
+        assert_eq!(join_continued_lines("one \\\ntwo"), "one \ntwo");
+    }
+
+    #[test]
+    fn test_history_file_is_per_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join("src/nested")).unwrap();
+        let other = dir.path().join("other");
+        std::fs::create_dir_all(&other).unwrap();
+
+        let history_dir = Path::new("/history");
+        assert_eq!(
+            history_file(history_dir, &repo.join("src/nested")),
+            history_file(history_dir, &repo)
+        );
+        assert_ne!(
+            history_file(history_dir, &repo),
+            history_file(history_dir, &other)
+        );
+        assert_eq!(history_file(history_dir, &repo).parent(), Some(history_dir));
+    }
 }
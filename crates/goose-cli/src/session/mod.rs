@@ -1,5 +1,6 @@
 mod builder;
 mod completion;
+mod elicitation;
 mod export;
 mod input;
 mod output;
@@ -23,17 +24,23 @@ use goose::permission::PermissionConfirmation;
 use goose::providers::base::Provider;
 pub use goose::session::Identifier;
 use goose::utils::safe_truncate;
+pub use output::{init_plain_mode, plain_mode, start_spinner, stop_spinner};
 
 use anyhow::{Context, Result};
 use completion::GooseCompleter;
 use etcetera::{choose_app_strategy, AppStrategy};
 use goose::agents::extension::{Envs, ExtensionConfig};
+use goose::agents::hooks::{self, HookEvent, HookKind};
 use goose::agents::types::RetryConfig;
-use goose::agents::{Agent, SessionConfig};
+use goose::agents::{Agent, CritiqueConfig, PendingApproval, RunLimits, SessionConfig};
 use goose::config::Config;
+use goose::context_mgmt::truncate::truncated_tool_results;
+use goose::governance;
+use goose::i18n;
 use goose::providers::pricing::initialize_pricing_cache;
 use goose::session;
 use input::InputResult;
+use mcp_core::tool::ToolCall;
 use rmcp::model::PromptMessage;
 use rmcp::model::ServerNotification;
 use rmcp::model::{ErrorCode, ErrorData};
@@ -43,7 +50,7 @@ use rand::{distributions::Alphanumeric, Rng};
 use rustyline::EditMode;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio;
@@ -66,6 +73,8 @@ pub struct Session {
     max_turns: Option<u32>,
     edit_mode: Option<EditMode>,
     retry_config: Option<RetryConfig>,
+    run_limits: RunLimits,
+    critique: Option<CritiqueConfig>,
 }
 
 // Cache structure for completion data
@@ -152,6 +161,51 @@ impl Session {
             max_turns,
             edit_mode,
             retry_config,
+            run_limits: RunLimits::default(),
+            critique: None,
+        }
+    }
+
+    /// Limit how long each run may take and how many tool calls it may make
+    pub fn set_run_limits(&mut self, run_limits: RunLimits) {
+        self.run_limits = run_limits;
+    }
+
+    /// Have the model review its final answer of each run against the recipe's criteria
+    pub fn set_critique(&mut self, critique: Option<CritiqueConfig>) {
+        self.critique = critique;
+    }
+
+    /// Keep the full output of tool results that truncation shortened with the session, so
+    /// it can still be fetched after the model only sees the shortened copy
+    fn keep_truncated_tool_results(&self, truncated: &Conversation) {
+        let Some(session_file) = &self.session_file else {
+            return;
+        };
+        let outputs = truncated_tool_results(self.messages.messages(), truncated.messages());
+        if outputs.is_empty() {
+            return;
+        }
+        match session::attachments::save_tool_results(session_file, &outputs) {
+            Ok(()) => output::render_text(
+                &format!(
+                    "Kept the full output of {} truncated tool result(s) with the session.",
+                    outputs.len()
+                ),
+                Some(Color::Yellow),
+                true,
+            ),
+            Err(e) => tracing::warn!("Failed to keep truncated tool results: {}", e),
+        }
+    }
+
+    /// Items pinned to the session, added back to messages that were just summarized
+    fn with_pins(session_file: Option<&Path>, messages: Conversation) -> Conversation {
+        match (session_file, std::env::current_dir()) {
+            (Some(session_file), Ok(working_dir)) => {
+                session::pins::reinclude_pins(session_file, &working_dir, messages)
+            }
+            _ => messages,
         }
     }
 
@@ -159,13 +213,14 @@ impl Session {
     async fn summarize_context_messages(
         messages: &mut Conversation,
         agent: &Agent,
+        session_file: Option<&Path>,
         message_suffix: &str,
     ) -> Result<()> {
         // Summarize messages to fit within context length
         let (summarized_messages, _, _) = agent.summarize_context(messages.messages()).await?;
         let msg = format!("Context maxed out\n{}\n{}", "-".repeat(50), message_suffix);
         output::render_text(&msg, Some(Color::Yellow), true);
-        *messages = summarized_messages;
+        *messages = Self::with_pins(session_file, summarized_messages);
 
         Ok(())
     }
@@ -399,6 +454,44 @@ impl Session {
         Ok(())
     }
 
+    /// Ask again about the tool calls a handed-off session was waiting on, then let the agent
+    /// carry on from their results as if they had been answered where they were asked
+    pub async fn resume_pending_approvals(&mut self, approvals: &[PendingApproval]) -> Result<()> {
+        if approvals.is_empty() {
+            return Ok(());
+        }
+
+        let mut response_message = Message::user();
+        for approval in approvals {
+            let request = Message::assistant().with_tool_request(
+                approval.id.clone(),
+                Ok(ToolCall::new(
+                    &approval.tool_name,
+                    approval.arguments.clone(),
+                )),
+            );
+            output::render_message(&request, self.debug);
+            let allowed = cliclack::confirm(i18n::t("cli.handoff_approval"))
+                .initial_value(false)
+                .interact()
+                .or_else(|e| {
+                    if e.kind() == std::io::ErrorKind::Interrupted {
+                        Ok(false)
+                    } else {
+                        Err(e)
+                    }
+                })?;
+            response_message.content.push(
+                self.agent
+                    .resolve_handed_off_approval(approval, allowed)
+                    .await,
+            );
+        }
+
+        self.process_message(response_message, CancellationToken::default())
+            .await
+    }
+
     /// Start an interactive session, optionally with an initial message
     pub async fn interactive(&mut self, prompt: Option<String>) -> Result<()> {
         // Process initial message if provided
@@ -430,24 +523,26 @@ impl Session {
         let completer = GooseCompleter::new(self.completion_cache.clone());
         editor.set_helper(Some(completer));
 
-        // Create and use a global history file in ~/.config/goose directory
+        // Keep command history per project in ~/.config/goose/history
         // This allows command history to persist across different chat sessions
         // instead of being tied to each individual session's messages
         let strategy =
             choose_app_strategy(crate::APP_STRATEGY.clone()).expect("goose requires a home dir");
         let config_dir = strategy.config_dir();
-        let history_file = config_dir.join("history.txt");
-
-        // Ensure config directory exists
-        if let Some(parent) = history_file.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)?;
-            }
-        }
-
-        // Load history from the global file
-        if history_file.exists() {
-            if let Err(err) = editor.load_history(&history_file) {
+        let history_dir = config_dir.join("history");
+        let history_file =
+            input::history_file(&history_dir, &std::env::current_dir().unwrap_or_default());
+        std::fs::create_dir_all(&history_dir)?;
+
+        // A project without history yet starts from the global file used by older versions
+        let legacy_history_file = config_dir.join("history.txt");
+        let load_from = if history_file.exists() {
+            &history_file
+        } else {
+            &legacy_history_file
+        };
+        if load_from.exists() {
+            if let Err(err) = editor.load_history(load_from) {
                 eprintln!("Warning: Failed to load command history: {}", err);
             }
         }
@@ -526,15 +621,24 @@ impl Session {
                     let current = output::get_theme();
                     let new_theme = match current {
                         output::Theme::Light => {
-                            println!("Switching to Dark theme");
+                            println!(
+                                "{}",
+                                i18n::t_with("cli.theme_switched", &[("theme", "Dark")])
+                            );
                             output::Theme::Dark
                         }
                         output::Theme::Dark => {
-                            println!("Switching to Ansi theme");
+                            println!(
+                                "{}",
+                                i18n::t_with("cli.theme_switched", &[("theme", "Ansi")])
+                            );
                             output::Theme::Ansi
                         }
                         output::Theme::Ansi => {
-                            println!("Switching to Light theme");
+                            println!(
+                                "{}",
+                                i18n::t_with("cli.theme_switched", &[("theme", "Light")])
+                            );
                             output::Theme::Light
                         }
                     };
@@ -547,15 +651,24 @@ impl Session {
 
                     let new_theme = match theme_name.as_str() {
                         "light" => {
-                            println!("Switching to Light theme");
+                            println!(
+                                "{}",
+                                i18n::t_with("cli.theme_switched", &[("theme", "Light")])
+                            );
                             output::Theme::Light
                         }
                         "dark" => {
-                            println!("Switching to Dark theme");
+                            println!(
+                                "{}",
+                                i18n::t_with("cli.theme_switched", &[("theme", "Dark")])
+                            );
                             output::Theme::Dark
                         }
                         "ansi" => {
-                            println!("Switching to Ansi theme");
+                            println!(
+                                "{}",
+                                i18n::t_with("cli.theme_switched", &[("theme", "Ansi")])
+                            );
                             output::Theme::Ansi
                         }
                         _ => output::Theme::Dark,
@@ -580,17 +693,137 @@ impl Session {
 
                     // Check if mode is valid
                     if !["auto", "approve", "chat", "smart_approve"].contains(&mode.as_str()) {
-                        output::render_error(&format!(
-                            "Invalid mode '{}'. Mode must be one of: auto, approve, chat, smart_approve",
-                            mode
-                        ));
+                        output::render_error(&i18n::t_with("cli.invalid_mode", &[("mode", &mode)]));
                         continue;
                     }
 
                     config
                         .set_param("GOOSE_MODE", Value::String(mode.to_string()))
                         .unwrap();
-                    output::goose_mode_message(&format!("Goose mode set to '{}'", mode));
+                    output::goose_mode_message(&i18n::t_with("cli.mode_set", &[("mode", &mode)]));
+                    continue;
+                }
+                input::InputResult::Elevate(minutes) => {
+                    save_history(&mut editor);
+
+                    let Some(session_file) = self.session_file.as_ref() else {
+                        output::render_error(&i18n::t("cli.elevation_needs_session"));
+                        continue;
+                    };
+                    let minutes = minutes.unwrap_or(30);
+                    match session::elevation::grant_elevation(
+                        session_file,
+                        std::time::Duration::from_secs(minutes.saturating_mul(60)),
+                        None,
+                    ) {
+                        Ok(elevation) => {
+                            let minutes = (elevation.expires_at - elevation.granted_at) / 60;
+                            output::goose_mode_message(&i18n::t_with(
+                                "cli.elevated",
+                                &[("minutes", &minutes.to_string())],
+                            ));
+                        }
+                        Err(e) => output::render_error(&i18n::t_with(
+                            "cli.elevate_failed",
+                            &[("error", &e.to_string())],
+                        )),
+                    }
+                    continue;
+                }
+                input::InputResult::Unelevate => {
+                    save_history(&mut editor);
+
+                    let revoked = self
+                        .session_file
+                        .as_ref()
+                        .map(|file| session::elevation::revoke_elevation(file))
+                        .transpose();
+                    match revoked {
+                        Ok(Some(true)) => {
+                            output::goose_mode_message(&i18n::t("cli.elevation_ended"))
+                        }
+                        Ok(_) => output::goose_mode_message(&i18n::t("cli.not_elevated")),
+                        Err(e) => output::render_error(&i18n::t_with(
+                            "cli.unelevate_failed",
+                            &[("error", &e.to_string())],
+                        )),
+                    }
+                    continue;
+                }
+                input::InputResult::Model(model) => {
+                    save_history(&mut editor);
+
+                    match model {
+                        None => {
+                            let provider = self.agent.provider().await?;
+                            output::goose_mode_message(&i18n::t_with(
+                                "cli.using_model",
+                                &[("model", &provider.get_model_config().model_name)],
+                            ));
+                        }
+                        Some(model) => match self.switch_model(&model).await {
+                            Ok(()) => output::goose_mode_message(&i18n::t_with(
+                                "cli.switched_model",
+                                &[("model", &model)],
+                            )),
+                            Err(e) => output::render_error(&i18n::t_with(
+                                "cli.switch_model_failed",
+                                &[("error", &e.to_string())],
+                            )),
+                        },
+                    }
+                    continue;
+                }
+                input::InputResult::Style(name) => {
+                    save_history(&mut editor);
+
+                    match name {
+                        None => output::render_styles(
+                            &goose::style::presets(),
+                            self.current_style().as_deref(),
+                        ),
+                        Some(name) => match self.switch_style(&name).await {
+                            Ok(()) => output::goose_mode_message(&i18n::t_with(
+                                "cli.switched_style",
+                                &[("style", &name)],
+                            )),
+                            Err(e) => output::render_error(&i18n::t_with(
+                                "cli.switch_style_failed",
+                                &[("error", &e.to_string())],
+                            )),
+                        },
+                    }
+                    continue;
+                }
+                input::InputResult::ListExtensions => {
+                    save_history(&mut editor);
+                    output::render_extensions(&self.agent.list_extensions().await);
+                    continue;
+                }
+                input::InputResult::Tokens => {
+                    save_history(&mut editor);
+
+                    let provider = self.agent.provider().await?;
+                    let context_limit = provider.get_model_config().context_limit();
+                    match self.get_metadata() {
+                        Ok(metadata) => output::render_token_usage(&metadata, context_limit),
+                        Err(_) => output::render_error(&i18n::t("cli.tokens_need_session")),
+                    }
+                    continue;
+                }
+                input::InputResult::Fork(name) => {
+                    save_history(&mut editor);
+
+                    match self.fork(name).await {
+                        Ok(path) => output::goose_mode_message(&i18n::t_with(
+                            "cli.forked",
+                            &[("path", &path.display().to_string())],
+                        )),
+                        Err(e) => output::render_error(&i18n::t_with(
+                            "cli.fork_failed",
+                            &[("error", &e.to_string())],
+                        )),
+                    }
                     continue;
                 }
                 input::InputResult::Plan(options) => {
@@ -619,10 +852,11 @@ impl Session {
                     self.messages.clear();
                     tracing::info!("Chat context cleared by user.");
                     output::render_message(
-                        &Message::assistant().with_text("Chat context cleared."),
+                        &Message::assistant().with_text(i18n::t("cli.chat_cleared")),
                         self.debug,
                     );
                     if let Some(file) = self.session_file.as_ref().filter(|f| f.exists()) {
+                        governance::preserve_held_history(file, &[])?;
                         std::fs::remove_file(file)?;
                         std::fs::File::create(file)?;
                     }
@@ -633,7 +867,10 @@ impl Session {
                     self.handle_prompt_command(opts).await?;
                 }
                 InputResult::Recipe(filepath_opt) => {
-                    println!("{}", console::style("Generating Recipe").green());
+                    println!(
+                        "{}",
+                        console::style(i18n::t("cli.generating_recipe")).green()
+                    );
 
                     output::show_thinking();
                     let recipe = self.agent.create_recipe(self.messages.clone()).await;
@@ -646,8 +883,11 @@ impl Session {
                             match self.save_recipe(&recipe, filepath_str) {
                                 Ok(path) => println!(
                                     "{}",
-                                    console::style(format!("Saved recipe to {}", path.display()))
-                                        .green()
+                                    console::style(i18n::t_with(
+                                        "cli.recipe_saved",
+                                        &[("path", &path.display().to_string())]
+                                    ))
+                                    .green()
                                 ),
                                 Err(e) => {
                                     println!("{}", console::style(e).red());
@@ -657,7 +897,7 @@ impl Session {
                         Err(e) => {
                             println!(
                                 "{}: {:?}",
-                                console::style("Failed to generate recipe").red(),
+                                console::style(i18n::t("cli.recipe_failed")).red(),
                                 e
                             );
                         }
@@ -668,7 +908,7 @@ impl Session {
                 InputResult::Summarize => {
                     save_history(&mut editor);
 
-                    let prompt = "Are you sure you want to summarize this conversation? This will condense the message history.";
+                    let prompt = i18n::t("cli.summarize_confirm");
                     let should_summarize =
                         match cliclack::confirm(prompt).initial_value(true).interact() {
                             Ok(choice) => choice,
@@ -682,7 +922,7 @@ impl Session {
                         };
 
                     if should_summarize {
-                        println!("{}", console::style("Summarizing conversation...").yellow());
+                        println!("{}", console::style(i18n::t("cli.summarizing")).yellow());
                         output::show_thinking();
 
                         // Get the provider for summarization
@@ -695,7 +935,8 @@ impl Session {
                             .await?;
 
                         // Update the session messages with the summarized ones
-                        self.messages = summarized_messages;
+                        self.messages =
+                            Self::with_pins(self.session_file.as_deref(), summarized_messages);
 
                         // Persist the summarized messages and update session metadata with new token counts
                         if let Some(session_file) = &self.session_file {
@@ -751,19 +992,16 @@ impl Session {
                         }
 
                         output::hide_thinking();
+                        println!("{}", console::style(i18n::t("cli.summarized")).green());
                         println!(
                             "{}",
-                            console::style("Conversation has been summarized.").green()
+                            console::style(i18n::t("cli.summarized_detail")).green()
                         );
+                    } else {
                         println!(
                             "{}",
-                            console::style(
-                                "Key information has been preserved while reducing context length."
-                            )
-                            .green()
+                            console::style(i18n::t("cli.summarize_cancelled")).yellow()
                         );
-                    } else {
-                        println!("{}", console::style("Summarization cancelled.").yellow());
                     }
 
                     continue;
@@ -772,15 +1010,103 @@ impl Session {
         }
 
         println!(
-            "\nClosing session.{}",
+            "\n{}{}",
+            i18n::t("cli.closing_session"),
             self.session_file
                 .as_ref()
-                .map(|p| format!(" Recorded to {}", p.display()))
+                .map(|p| format!(
+                    " {}",
+                    i18n::t_with("cli.recorded_to", &[("path", &p.display().to_string())])
+                ))
                 .unwrap_or_default()
         );
+        self.end_session().await;
+        Ok(())
+    }
+
+    /// Switch to another model from the current provider, keeping the conversation
+    async fn switch_model(&mut self, model: &str) -> Result<()> {
+        let current = self.agent.provider().await?.get_model_config();
+        let provider_name: String = Config::global()
+            .get_param("GOOSE_PROVIDER")
+            .context("No provider configured")?;
+        let model_config =
+            goose::model::ModelConfig::new(model)?.with_temperature(current.temperature);
+        let provider = goose::providers::create(&provider_name, model_config)?;
+        self.agent.update_provider(provider).await?;
         Ok(())
     }
 
+    /// The style preset in use: the session's choice, or the configured default
+    fn current_style(&self) -> Option<String> {
+        self.session_file
+            .as_ref()
+            .and_then(|file| session::read_metadata(file).ok())
+            .and_then(|metadata| metadata.style)
+            .or_else(goose::style::default_style)
+    }
+
+    /// Switch the session to the style preset `name`; `default` goes back to the configured one
+    async fn switch_style(&mut self, name: &str) -> Result<()> {
+        let style = if name == "default" {
+            None
+        } else if goose::style::find_preset(name).is_some() {
+            Some(name.to_string())
+        } else {
+            anyhow::bail!("Unknown style '{}'; /style lists the presets", name);
+        };
+        match self.session_file.as_ref().filter(|file| file.exists()) {
+            // Kept in the session so it also applies when the session is resumed
+            Some(file) => {
+                let mut metadata = session::read_metadata(file)?;
+                metadata.style = style;
+                session::update_metadata(file, &metadata).await?;
+            }
+            None => self.agent.set_style(style).await,
+        }
+        Ok(())
+    }
+
+    /// Copy the conversation into a new session and continue in it, so the original can be
+    /// resumed from where the fork happened
+    async fn fork(&mut self, name: Option<String>) -> Result<PathBuf> {
+        let Some(current) = self.session_file.clone() else {
+            anyhow::bail!("Forking needs a saved session");
+        };
+        let name = name.unwrap_or_else(session::generate_session_id);
+        let forked = session::get_path(session::Identifier::Name(name.clone()))?;
+        if forked.exists() {
+            anyhow::bail!("A session named '{}' already exists", name);
+        }
+
+        let mut metadata = session::read_metadata(&current).unwrap_or_default();
+        metadata.description = if metadata.description.is_empty() {
+            "Fork".to_string()
+        } else {
+            format!("{} (fork)", metadata.description)
+        };
+        metadata.message_count = self.messages.len();
+        session::storage::save_messages_with_metadata(&forked, &metadata, &self.messages)?;
+
+        self.session_file = Some(forked.clone());
+        Ok(forked)
+    }
+
+    /// End the session, which fires its end hooks, and wait for any hooks still running,
+    /// since the process is about to exit
+    pub(crate) async fn end_session(&self) {
+        match self.session_file.as_ref().and_then(|path| path.file_stem()) {
+            Some(name) => self.agent.end_session(&name.to_string_lossy()).await,
+            None => hooks::fire(HookEvent::new(
+                HookKind::OnSessionEnd,
+                None,
+                std::env::current_dir().ok().as_deref(),
+                serde_json::json!({ "message_count": self.messages.len() }),
+            )),
+        }
+        hooks::flush().await;
+    }
+
     async fn plan_with_reasoner_model(
         &mut self,
         plan_messages: Conversation,
@@ -800,11 +1126,9 @@ impl Session {
         match planner_response_type {
             PlannerResponseType::Plan => {
                 println!();
-                let should_act = match cliclack::confirm(
-                    "Do you want to clear message history & act on this plan?",
-                )
-                .initial_value(true)
-                .interact()
+                let should_act = match cliclack::confirm(i18n::t("cli.plan_act_confirm"))
+                    .initial_value(true)
+                    .interact()
                 {
                     Ok(choice) => choice,
                     Err(e) => {
@@ -866,6 +1190,7 @@ impl Session {
         let message = Message::user().with_text(&prompt);
         self.process_message(message, CancellationToken::default())
             .await?;
+        self.end_session().await;
         Ok(())
     }
 
@@ -884,6 +1209,8 @@ impl Session {
                 schedule_id: self.scheduled_job_id.clone(),
                 execution_mode: None,
                 max_turns: self.max_turns,
+                run_limits: self.run_limits,
+                critique: self.critique.clone(),
                 retry_config: self.retry_config.clone(),
             }
         });
@@ -897,6 +1224,7 @@ impl Session {
             .await?;
 
         let mut progress_bars = output::McpSpinners::new();
+        let mut elicitations = self.agent.elicitation_requests();
 
         use futures::StreamExt;
         loop {
@@ -909,14 +1237,14 @@ impl Session {
                                 output::hide_thinking();
 
                                 // Format the confirmation prompt
-                                let prompt = "Goose would like to call the above tool, do you allow?".to_string();
+                                let prompt = i18n::t("cli.tool_permission");
 
                                 // Get confirmation from user
                                 let permission_result = cliclack::select(prompt)
-                                    .item(Permission::AllowOnce, "Allow", "Allow the tool call once")
-                                    .item(Permission::AlwaysAllow, "Always Allow", "Always allow the tool call")
-                                    .item(Permission::DenyOnce, "Deny", "Deny the tool call")
-                                    .item(Permission::Cancel, "Cancel", "Cancel the AI response and tool call")
+                                    .item(Permission::AllowOnce, i18n::t("cli.permission_allow"), i18n::t("cli.permission_allow_hint"))
+                                    .item(Permission::AlwaysAllow, i18n::t("cli.permission_always"), i18n::t("cli.permission_always_hint"))
+                                    .item(Permission::DenyOnce, i18n::t("cli.permission_deny"), i18n::t("cli.permission_deny_hint"))
+                                    .item(Permission::Cancel, i18n::t("cli.permission_cancel"), i18n::t("cli.permission_cancel_hint"))
                                     .interact();
 
                                 let permission = match permission_result {
@@ -932,7 +1260,7 @@ impl Session {
                                 };
 
                                 if permission == Permission::Cancel {
-                                    output::render_text("Tool call cancelled. Returning to chat...", Some(Color::Yellow), true);
+                                    output::render_text(&i18n::t("cli.tool_cancelled"), Some(Color::Yellow), true);
 
                                     let mut response_message = Message::user();
                                     response_message.content.push(MessageContent::tool_response(
@@ -975,11 +1303,11 @@ impl Session {
                                     _ => {
                                         if interactive {
                                             // In interactive mode with no default, ask the user what to do
-                                            let prompt = "The model's context length is maxed out. You will need to reduce the # msgs. Do you want to?".to_string();
+                                            let prompt = i18n::t("cli.context_full");
                                             cliclack::select(prompt)
-                                                .item("clear", "Clear Session", "Removes all messages from Goose's memory")
-                                                .item("truncate", "Truncate Messages", "Removes old messages till context is within limits")
-                                                .item("summarize", "Summarize Session", "Summarize the session to reduce context length")
+                                                .item("clear", i18n::t("cli.context_clear"), i18n::t("cli.context_clear_hint"))
+                                                .item("truncate", i18n::t("cli.context_truncate"), i18n::t("cli.context_truncate_hint"))
+                                                .item("summarize", i18n::t("cli.context_summarize"), i18n::t("cli.context_summarize_hint"))
                                                 .interact()?
                                         } else {
                                             // In headless mode, default to summarize
@@ -992,9 +1320,9 @@ impl Session {
                                     "clear" => {
                                         self.messages.clear();
                                         let msg = if context_strategy == "clear" {
-                                            format!("Context maxed out - automatically cleared session.\n{}", "-".repeat(50))
+                                            format!("{}\n{}", i18n::t("cli.context_auto_cleared"), "-".repeat(50))
                                         } else {
-                                            format!("Session cleared.\n{}", "-".repeat(50))
+                                            format!("{}\n{}", i18n::t("cli.session_cleared"), "-".repeat(50))
                                         };
                                         output::render_text(&msg, Some(Color::Yellow), true);
                                         break;  // exit the loop to hand back control to the user
@@ -1003,12 +1331,13 @@ impl Session {
                                         // Truncate messages to fit within context length
                                         let (truncated_messages, _) = self.agent.truncate_context(self.messages.messages()).await?;
                                         let msg = if context_strategy == "truncate" {
-                                            format!("Context maxed out - automatically truncated messages.\n{}\nGoose tried its best to truncate messages for you.", "-".repeat(50))
+                                            format!("{}\n{}\n{}", i18n::t("cli.context_auto_truncated"), "-".repeat(50), i18n::t("cli.truncated_detail"))
                                         } else {
-                                            format!("Context maxed out\n{}\nGoose tried its best to truncate messages for you.", "-".repeat(50))
+                                            format!("{}\n{}\n{}", i18n::t("cli.context_maxed"), "-".repeat(50), i18n::t("cli.truncated_detail"))
                                         };
                                         output::render_text("", Some(Color::Yellow), true);
                                         output::render_text(&msg, Some(Color::Yellow), true);
+                                        self.keep_truncated_tool_results(&truncated_messages);
                                         self.messages = truncated_messages;
                                     }
                                     "summarize" => {
@@ -1020,7 +1349,7 @@ impl Session {
                                         } else {
                                             "Goose automatically summarized messages to continue processing."
                                         };
-                                        Self::summarize_context_messages(&mut self.messages, &self.agent, message_suffix).await?;
+                                        Self::summarize_context_messages(&mut self.messages, &self.agent, self.session_file.as_deref(), message_suffix).await?;
                                     }
                                     _ => {
                                         unreachable!()
@@ -1253,6 +1582,16 @@ impl Session {
                         None => break,
                     }
                 }
+                Ok(request) = elicitations.recv() => {
+                    output::hide_thinking();
+                    let _ = progress_bars.hide();
+                    let content = if interactive { elicitation::ask(&request)? } else { None };
+                    if content.is_none() {
+                        let declined = i18n::t("cli.elicitation_declined");
+                        output::render_text(&declined, Some(Color::Yellow), true);
+                    }
+                    self.agent.answer_elicitation(&request.id, content);
+                }
                 _ = tokio::signal::ctrl_c() => {
                     cancel_token_clone.cancel();
                     drop(stream);
@@ -1448,9 +1787,17 @@ impl Session {
 
         // Print session restored message
         println!(
-            "\n{} {} messages loaded into context.",
-            console::style("Session restored:").green().bold(),
-            console::style(self.messages.len()).green()
+            "\n{} {}",
+            console::style(i18n::t("cli.session_restored"))
+                .green()
+                .bold(),
+            i18n::t_with(
+                "cli.messages_loaded",
+                &[(
+                    "count",
+                    &console::style(self.messages.len()).green().to_string()
+                )]
+            )
         );
 
         // Render each message
@@ -1461,7 +1808,7 @@ impl Session {
         // Add a visual separator after restored messages
         println!(
             "\n{}\n",
-            console::style("──────── New Messages ────────").dim()
+            console::style(format!("──────── {} ────────", i18n::t("cli.new_messages"))).dim()
         );
     }
 
@@ -3,8 +3,11 @@ use bat::WrappingMode;
 use console::{style, Color};
 use goose::config::Config;
 use goose::conversation::message::{Message, MessageContent, ToolRequest, ToolResponse};
+use goose::i18n;
 use goose::providers::pricing::get_model_pricing;
 use goose::providers::pricing::parse_model_id;
+use goose::session::SessionMetadata;
+use goose::style::StylePreset;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use mcp_core::tool::ToolCall;
 use regex::Regex;
@@ -14,7 +17,7 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::io::{Error, IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
 // Re-export theme for use in main
@@ -88,14 +91,66 @@ pub fn get_theme() -> Theme {
     CURRENT_THEME.with(|t| *t.borrow())
 }
 
+static PLAIN_MODE: OnceLock<bool> = OnceLock::new();
+
+fn plain_mode_configured() -> bool {
+    std::env::var("TERM").is_ok_and(|term| term == "dumb")
+        || Config::global()
+            .get_param::<bool>("GOOSE_CLI_PLAIN")
+            .unwrap_or(false)
+}
+
+/// Decide once, at startup, whether output is plain: no colors, spinners, syntax
+/// highlighting or box drawing, and progress reported as whole lines. This suits screen
+/// readers and dumb terminals. `forced` comes from the `--plain` flag; otherwise
+/// `GOOSE_CLI_PLAIN` or `TERM=dumb` turns it on.
+pub fn init_plain_mode(forced: bool) {
+    let plain = *PLAIN_MODE.get_or_init(|| forced || plain_mode_configured());
+    if plain {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+}
+
+pub fn plain_mode() -> bool {
+    *PLAIN_MODE.get_or_init(plain_mode_configured)
+}
+
+/// Start a cliclack spinner, or print `message` as a line in plain mode
+pub fn start_spinner(message: &str) -> Option<cliclack::ProgressBar> {
+    if plain_mode() {
+        println!("{}", message);
+        return None;
+    }
+    let spinner = cliclack::spinner();
+    spinner.start(message);
+    Some(spinner)
+}
+
+pub fn stop_spinner(spinner: Option<cliclack::ProgressBar>, message: impl std::fmt::Display) {
+    match spinner {
+        Some(spinner) => spinner.stop(message),
+        None => println!("{}", message),
+    }
+}
+
 // Simple wrapper around spinner to manage its state
 #[derive(Default)]
 pub struct ThinkingIndicator {
     spinner: Option<cliclack::ProgressBar>,
+    plain_shown: bool,
 }
 
 impl ThinkingIndicator {
     pub fn show(&mut self) {
+        if plain_mode() {
+            // One line per turn rather than an animation a screen reader would re-announce
+            if !self.plain_shown {
+                eprintln!("{}", i18n::t("cli.thinking"));
+                self.plain_shown = true;
+            }
+            return;
+        }
         let spinner = cliclack::spinner();
         if Config::global()
             .get_param("RANDOM_THINKING_MESSAGES")
@@ -106,19 +161,20 @@ impl ThinkingIndicator {
                 super::thinking::get_random_thinking_message()
             ));
         } else {
-            spinner.start("Thinking...");
+            spinner.start(i18n::t("cli.thinking"));
         }
         self.spinner = Some(spinner);
     }
 
     pub fn hide(&mut self) {
+        self.plain_shown = false;
         if let Some(spinner) = self.spinner.take() {
             spinner.stop("");
         }
     }
 
     pub fn is_shown(&self) -> bool {
-        self.spinner.is_some()
+        self.spinner.is_some() || self.plain_shown
     }
 }
 
@@ -152,7 +208,9 @@ pub fn is_showing_thinking() -> bool {
 }
 
 pub fn set_thinking_message(s: &String) {
-    if std::io::stdout().is_terminal() {
+    if plain_mode() {
+        eprintln!("{}", s);
+    } else if std::io::stdout().is_terminal() {
         THINKING.with(|t| {
             if let Some(spinner) = t.borrow_mut().spinner.as_mut() {
                 spinner.set_message(s);
@@ -176,14 +234,14 @@ pub fn render_message(message: &Message, debug: bool) {
                 if std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok()
                     && std::io::stdout().is_terminal()
                 {
-                    println!("\n{}", style("Thinking:").dim().italic());
+                    println!("\n{}", style(i18n::t("cli.thinking_label")).dim().italic());
                     print_markdown(&thinking.thinking, theme);
                 }
             }
             MessageContent::RedactedThinking(_) => {
                 // For redacted thinking, print thinking was redacted
-                println!("\n{}", style("Thinking:").dim().italic());
-                print_markdown("Thinking was redacted", theme);
+                println!("\n{}", style(i18n::t("cli.thinking_label")).dim().italic());
+                print_markdown(&i18n::t("cli.thinking_redacted"), theme);
             }
             _ => {
                 println!("WARNING: Message content type could not be rendered");
@@ -218,24 +276,20 @@ pub fn render_text_no_newlines(text: &str, color: Option<Color>, dim: bool) {
 pub fn render_enter_plan_mode() {
     println!(
         "\n{} {}\n",
-        style("Entering plan mode.").green().bold(),
-        style("You can provide instructions to create a plan and then act on it. To exit early, type /endplan")
-            .green()
-            .dim()
+        style(i18n::t("cli.plan_mode_entered")).green().bold(),
+        style(i18n::t("cli.plan_mode_hint")).green().dim()
     );
 }
 
 pub fn render_act_on_plan() {
-    println!(
-        "\n{}\n",
-        style("Exiting plan mode and acting on the above plan")
-            .green()
-            .bold(),
-    );
+    println!("\n{}\n", style(i18n::t("cli.plan_acting")).green().bold(),);
 }
 
 pub fn render_exit_plan_mode() {
-    println!("\n{}\n", style("Exiting plan mode.").green().bold());
+    println!(
+        "\n{}\n",
+        style(i18n::t("cli.plan_mode_exited")).green().bold()
+    );
 }
 
 pub fn goose_mode_message(text: &str) {
@@ -292,7 +346,11 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
 }
 
 pub fn render_error(message: &str) {
-    println!("\n  {} {}\n", style("error:").red().bold(), message);
+    println!(
+        "\n  {} {}\n",
+        style(i18n::t("cli.error")).red().bold(),
+        message
+    );
 }
 
 pub fn render_prompts(prompts: &HashMap<String, Vec<String>>) {
@@ -309,9 +367,17 @@ pub fn render_prompts(prompts: &HashMap<String, Vec<String>>) {
 pub fn render_prompt_info(info: &PromptInfo) {
     println!();
     if let Some(ext) = &info.extension {
-        println!(" {}: {}", style("Extension").green(), ext);
+        println!(
+            " {}: {}",
+            style(i18n::t("cli.prompt_extension")).green(),
+            ext
+        );
     }
-    println!(" Prompt: {}", style(&info.name).cyan().bold());
+    println!(
+        " {}: {}",
+        i18n::t("cli.prompt_name"),
+        style(&info.name).cyan().bold()
+    );
     if let Some(desc) = &info.description {
         println!("\n {}", desc);
     }
@@ -321,13 +387,13 @@ pub fn render_prompt_info(info: &PromptInfo) {
 
 fn render_arguments(info: &PromptInfo) {
     if let Some(args) = &info.arguments {
-        println!("\n Arguments:");
+        println!("\n {}", i18n::t("cli.prompt_arguments"));
         for arg in args {
             let required = arg.required.unwrap_or(false);
             let req_str = if required {
-                style("(required)").red()
+                style(i18n::t("cli.prompt_required")).red()
             } else {
-                style("(optional)").dim()
+                style(i18n::t("cli.prompt_optional")).dim()
             };
 
             println!(
@@ -343,9 +409,11 @@ fn render_arguments(info: &PromptInfo) {
 pub fn render_extension_success(name: &str) {
     println!();
     println!(
-        "  {} extension `{}`",
-        style("added").green(),
-        style(name).cyan(),
+        "  {}",
+        i18n::t_with(
+            "cli.extension_added",
+            &[("name", &style(name).cyan().to_string())]
+        )
     );
     println!();
 }
@@ -353,9 +421,11 @@ pub fn render_extension_success(name: &str) {
 pub fn render_extension_error(name: &str, error: &str) {
     println!();
     println!(
-        "  {} to add extension {}",
-        style("failed").red(),
-        style(name).red()
+        "  {}",
+        i18n::t_with(
+            "cli.extension_failed",
+            &[("name", &style(name).red().to_string())]
+        )
     );
     println!();
     println!("{}", style(error).dim());
@@ -365,10 +435,11 @@ pub fn render_extension_error(name: &str, error: &str) {
 pub fn render_builtin_success(names: &str) {
     println!();
     println!(
-        "  {} builtin{}: {}",
-        style("added").green(),
-        if names.contains(',') { "s" } else { "" },
-        style(names).cyan()
+        "  {}",
+        i18n::t_with(
+            "cli.builtin_added",
+            &[("names", &style(names).cyan().to_string())]
+        )
     );
     println!();
 }
@@ -376,10 +447,11 @@ pub fn render_builtin_success(names: &str) {
 pub fn render_builtin_error(names: &str, error: &str) {
     println!();
     println!(
-        "  {} to add builtin{}: {}",
-        style("failed").red(),
-        if names.contains(',') { "s" } else { "" },
-        style(names).red()
+        "  {}",
+        i18n::t_with(
+            "cli.builtin_failed",
+            &[("names", &style(names).red().to_string())]
+        )
     );
     println!();
     println!("{}", style(error).dim());
@@ -476,6 +548,19 @@ fn render_default_request(call: &ToolCall, debug: bool) {
 
 fn print_tool_header(call: &ToolCall) {
     let parts: Vec<_> = call.name.rsplit("__").collect();
+    if plain_mode() {
+        let extension = parts
+            .split_first()
+            .map(|(_, s)| s.iter().rev().copied().collect::<Vec<_>>().join("__"))
+            .unwrap_or_else(|| "unknown".to_string());
+        println!();
+        println!(
+            "Tool: {} from {}",
+            parts.first().unwrap_or(&"unknown"),
+            extension
+        );
+        return;
+    }
     let tool_header = format!(
         "─── {} | {} ──────────────────────────",
         style(parts.first().unwrap_or(&"unknown")),
@@ -498,18 +583,85 @@ pub fn env_no_color() -> bool {
     std::env::var_os("NO_COLOR").is_none()
 }
 
+/// Part of a markdown message: prose, or a fenced code block with its language tag
+#[derive(Debug, PartialEq)]
+enum MarkdownBlock<'a> {
+    Text(&'a str),
+    Code { language: &'a str, code: &'a str },
+}
+
+fn split_code_blocks(content: &str) -> Vec<MarkdownBlock<'_>> {
+    let mut blocks = Vec::new();
+    let mut text_start = 0;
+    let mut open_fence: Option<(usize, &str)> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let Some(tag) = line.trim_start().strip_prefix("```") else {
+            continue;
+        };
+        match open_fence {
+            None => {
+                if line_start > text_start {
+                    blocks.push(MarkdownBlock::Text(&content[text_start..line_start]));
+                }
+                text_start = line_start;
+                open_fence = Some((offset, tag.trim()));
+            }
+            Some((code_start, language)) if tag.trim().is_empty() => {
+                blocks.push(MarkdownBlock::Code {
+                    language,
+                    code: &content[code_start..line_start],
+                });
+                open_fence = None;
+                text_start = offset;
+            }
+            Some(_) => {}
+        }
+    }
+
+    // Anything after the last block, including an unclosed fence, is plain markdown
+    if text_start < content.len() {
+        blocks.push(MarkdownBlock::Text(&content[text_start..]));
+    }
+    blocks
+}
+
+fn print_highlighted(content: &str, language: &str, theme: Theme) -> bool {
+    bat::PrettyPrinter::new()
+        .input(bat::Input::from_bytes(content.as_bytes()))
+        .theme(theme.as_str())
+        .colored_output(env_no_color())
+        .language(language)
+        .wrapping_mode(WrappingMode::NoWrapping(true))
+        .print()
+        .is_ok()
+}
+
+/// Print markdown, highlighting fenced code blocks with their own language's syntax
 fn print_markdown(content: &str, theme: Theme) {
-    if std::io::stdout().is_terminal() {
-        bat::PrettyPrinter::new()
-            .input(bat::Input::from_bytes(content.as_bytes()))
-            .theme(theme.as_str())
-            .colored_output(env_no_color())
-            .language("Markdown")
-            .wrapping_mode(WrappingMode::NoWrapping(true))
-            .print()
-            .unwrap();
-    } else {
+    if plain_mode() || !std::io::stdout().is_terminal() {
         print!("{}", content);
+        return;
+    }
+
+    for block in split_code_blocks(content) {
+        match block {
+            MarkdownBlock::Text(text) => {
+                if !print_highlighted(text, "Markdown", theme) {
+                    print!("{}", text);
+                }
+            }
+            MarkdownBlock::Code { language, code } => {
+                println!("{}", style(format!("```{}", language)).dim());
+                if language.is_empty() || !print_highlighted(code, language, theme) {
+                    print!("{}", code);
+                }
+                println!("{}", style("```").dim());
+            }
+        }
     }
 }
 
@@ -719,8 +871,69 @@ pub fn display_session_info(
     );
 }
 
+pub fn render_extensions(extensions: &[String]) {
+    println!();
+    if extensions.is_empty() {
+        println!("  {}", style(i18n::t("cli.no_extensions")).dim());
+    }
+    for extension in extensions {
+        println!("  - {}", style(extension).cyan());
+    }
+    println!();
+}
+
+pub fn render_styles(presets: &[StylePreset], current: Option<&str>) {
+    println!();
+    for preset in presets {
+        let marker = if current == Some(preset.name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{} {}  {}",
+            marker,
+            style(&preset.name).cyan(),
+            style(&preset.instructions).dim()
+        );
+    }
+    if current.is_none() {
+        println!("  {}", style(i18n::t("cli.no_style")).dim());
+    }
+    println!();
+}
+
+pub fn render_token_usage(metadata: &SessionMetadata, context_limit: usize) {
+    let tokens =
+        |count: Option<i32>| count.map_or_else(|| "-".to_string(), |count| count.to_string());
+    println!();
+    display_context_usage(metadata.total_tokens.unwrap_or(0) as usize, context_limit);
+    println!(
+        "{}",
+        i18n::t_with(
+            "cli.tokens_last_turn",
+            &[
+                ("input", &tokens(metadata.input_tokens)),
+                ("output", &tokens(metadata.output_tokens)),
+            ]
+        )
+    );
+    println!(
+        "{}",
+        i18n::t_with(
+            "cli.tokens_session",
+            &[
+                ("total", &tokens(metadata.accumulated_total_tokens)),
+                ("input", &tokens(metadata.accumulated_input_tokens)),
+                ("output", &tokens(metadata.accumulated_output_tokens)),
+            ]
+        )
+    );
+    println!();
+}
+
 pub fn display_greeting() {
-    println!("\nGoose is running! Enter your instructions, or try asking what goose can do.\n");
+    println!("\n{}\n", i18n::t("cli.greeting"));
 }
 
 /// Display context window usage with both current and session totals
@@ -728,7 +941,11 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     use console::style;
 
     if context_limit == 0 {
-        println!("Context: Error - context limit is zero");
+        println!(
+            "{}: {}",
+            i18n::t("cli.context"),
+            i18n::t("cli.context_limit_zero")
+        );
         return;
     }
 
@@ -736,6 +953,18 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
     let percentage =
         (((total_tokens as f64 / context_limit as f64) * 100.0).round() as usize).min(100);
 
+    if plain_mode() {
+        println!(
+            "{}: {}% ({}/{} {})",
+            i18n::t("cli.context"),
+            percentage,
+            total_tokens,
+            context_limit,
+            i18n::t("cli.context_tokens")
+        );
+        return;
+    }
+
     // Create dot visualization with safety bounds
     let dot_count = 10;
     let filled_dots =
@@ -757,8 +986,13 @@ pub fn display_context_usage(total_tokens: usize, context_limit: usize) {
 
     // Print the status line
     println!(
-        "Context: {} {}% ({}/{} tokens)",
-        colored_dots, percentage, total_tokens, context_limit
+        "{}: {} {}% ({}/{} {})",
+        i18n::t("cli.context"),
+        colored_dots,
+        percentage,
+        total_tokens,
+        context_limit,
+        i18n::t("cli.context_tokens")
     );
 }
 
@@ -839,6 +1073,8 @@ pub async fn display_cost_usage(
 pub struct McpSpinners {
     bars: HashMap<String, ProgressBar>,
     log_spinner: Option<ProgressBar>,
+    /// Last whole percentage printed for each progress token in plain mode
+    plain_progress: HashMap<String, u64>,
 
     multi_bar: MultiProgress,
 }
@@ -848,11 +1084,16 @@ impl McpSpinners {
         McpSpinners {
             bars: HashMap::new(),
             log_spinner: None,
+            plain_progress: HashMap::new(),
             multi_bar: MultiProgress::new(),
         }
     }
 
     pub fn log(&mut self, message: &str) {
+        if plain_mode() {
+            eprintln!("{}", message);
+            return;
+        }
         let spinner = self.log_spinner.get_or_insert_with(|| {
             let bar = self.multi_bar.add(
                 ProgressBar::new_spinner()
@@ -871,6 +1112,12 @@ impl McpSpinners {
     }
 
     pub fn update(&mut self, token: &str, value: f64, total: Option<f64>, message: Option<&str>) {
+        if plain_mode() {
+            if let Some(line) = self.plain_progress_line(token, value, total, message) {
+                eprintln!("{}", line);
+            }
+            return;
+        }
         let bar = self.bars.entry(token.to_string()).or_insert_with(|| {
             if let Some(total) = total {
                 self.multi_bar.add(
@@ -889,7 +1136,32 @@ impl McpSpinners {
         }
     }
 
+    /// A progress line for plain mode, or None when the whole percentage hasn't moved
+    fn plain_progress_line(
+        &mut self,
+        token: &str,
+        value: f64,
+        total: Option<f64>,
+        message: Option<&str>,
+    ) -> Option<String> {
+        let progress = match total {
+            Some(total) if total > 0.0 => {
+                let percent = ((value / total) * 100.0).clamp(0.0, 100.0) as u64;
+                if self.plain_progress.insert(token.to_string(), percent) == Some(percent) {
+                    return None;
+                }
+                format!("{}%", percent)
+            }
+            _ => format!("{}", value),
+        };
+        Some(match message {
+            Some(message) => format!("Progress: {} {}", progress, message),
+            None => format!("Progress: {}", progress),
+        })
+    }
+
     pub fn hide(&mut self) -> Result<(), Error> {
+        self.plain_progress.clear();
         self.bars.iter_mut().for_each(|(_, bar)| {
             bar.disable_steady_tick();
         });
@@ -947,6 +1219,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_plain_progress_only_prints_when_percentage_moves() {
+        let mut spinners = McpSpinners::new();
+        assert_eq!(
+            spinners.plain_progress_line("t", 1.0, Some(4.0), Some("indexing")),
+            Some("Progress: 25% indexing".to_string())
+        );
+        assert_eq!(
+            spinners.plain_progress_line("t", 1.001, Some(4.0), None),
+            None
+        );
+        assert_eq!(
+            spinners.plain_progress_line("t", 2.0, Some(4.0), None),
+            Some("Progress: 50%".to_string())
+        );
+        assert_eq!(
+            spinners.plain_progress_line("other", 3.0, None, None),
+            Some("Progress: 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_split_code_blocks() {
+        let content = "Run this:\n```rust\nfn main() {}\n```\nThen:\n```\nplain\n```\n";
+        assert_eq!(
+            split_code_blocks(content),
+            vec![
+                MarkdownBlock::Text("Run this:\n"),
+                MarkdownBlock::Code {
+                    language: "rust",
+                    code: "fn main() {}\n"
+                },
+                MarkdownBlock::Text("Then:\n"),
+                MarkdownBlock::Code {
+                    language: "",
+                    code: "plain\n"
+                },
+            ]
+        );
+
+        // An unclosed fence stays part of the text
+        assert_eq!(
+            split_code_blocks("see\n```sh\nls"),
+            vec![
+                MarkdownBlock::Text("see\n"),
+                MarkdownBlock::Text("```sh\nls")
+            ]
+        );
+    }
+
     #[test]
     fn test_long_path_shortening() {
         assert_eq!(
@@ -5,6 +5,9 @@ use goose::agents::subagent_execution_tool::notification_events::{
 use goose::utils::safe_truncate;
 use serde_json::Value;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use super::output::plain_mode;
 
 #[cfg(test)]
 mod tests;
@@ -16,6 +19,8 @@ const CLEAR_BELOW: &str = "\x1b[J";
 pub const TASK_EXECUTION_NOTIFICATION_TYPE: &str = "task_execution";
 
 static INITIAL_SHOWN: AtomicBool = AtomicBool::new(false);
+/// The last plain-mode update, so an unchanged one isn't printed again
+static LAST_PLAIN_UPDATE: Mutex<String> = Mutex::new(String::new());
 
 fn format_result_data_for_display(result_data: &Value) -> String {
     match result_data {
@@ -59,6 +64,21 @@ pub fn format_task_execution_notification(
                 None,
                 Some(TASK_EXECUTION_NOTIFICATION_TYPE.to_string()),
             ),
+            TaskExecutionNotificationEvent::TasksUpdate { .. } if plain_mode() => {
+                let update = format_tasks_update_plain(&event);
+                let mut last = LAST_PLAIN_UPDATE.lock().unwrap();
+                let formatted_display = if *last == update {
+                    String::new()
+                } else {
+                    *last = update.clone();
+                    update
+                };
+                (
+                    formatted_display,
+                    None,
+                    Some(TASK_EXECUTION_NOTIFICATION_TYPE.to_string()),
+                )
+            }
             TaskExecutionNotificationEvent::TasksUpdate { .. } => {
                 let formatted_display = format_tasks_update_from_event(&event);
                 (
@@ -68,7 +88,11 @@ pub fn format_task_execution_notification(
                 )
             }
             TaskExecutionNotificationEvent::TasksComplete { .. } => {
-                let formatted_summary = format_tasks_complete_from_event(&event);
+                let formatted_summary = if plain_mode() {
+                    format_tasks_complete_plain(&event)
+                } else {
+                    format_tasks_complete_from_event(&event)
+                };
                 (
                     formatted_summary,
                     None,
@@ -112,6 +136,52 @@ fn format_tasks_update_from_event(event: &TaskExecutionNotificationEvent) -> Str
     }
 }
 
+/// Line-based task progress for plain mode: no cursor movement, screen clearing or emoji,
+/// and no live output, so the text only changes when a task does
+fn format_tasks_update_plain(event: &TaskExecutionNotificationEvent) -> String {
+    let TaskExecutionNotificationEvent::TasksUpdate { stats, tasks } = event else {
+        return String::new();
+    };
+    let mut display = format!(
+        "Tasks: {} total, {} pending, {} running, {} completed, {} failed\n",
+        stats.total, stats.pending, stats.running, stats.completed, stats.failed
+    );
+    let mut sorted_tasks = tasks.clone();
+    sorted_tasks.sort_by(|a, b| a.id.cmp(&b.id));
+    for task in sorted_tasks {
+        let status = match task.status {
+            TaskStatus::Pending => "pending",
+            TaskStatus::Running => "running",
+            TaskStatus::Completed => "completed",
+            TaskStatus::Failed => "failed",
+        };
+        display.push_str(&format!("  {}: {}\n", task.task_name, status));
+    }
+    display
+}
+
+fn format_tasks_complete_plain(event: &TaskExecutionNotificationEvent) -> String {
+    let TaskExecutionNotificationEvent::TasksComplete {
+        stats,
+        failed_tasks,
+    } = event
+    else {
+        return String::new();
+    };
+    let mut summary = format!(
+        "Execution complete: {} total, {} completed, {} failed, {:.1}% success rate\n",
+        stats.total, stats.completed, stats.failed, stats.success_rate
+    );
+    for task in failed_tasks {
+        summary.push_str(&format!("  Failed: {}\n", task.name));
+        if let Some(error) = &task.error {
+            summary.push_str(&format!("    Error: {}\n", error));
+        }
+    }
+    summary.push_str("Generating summary...\n");
+    summary
+}
+
 fn format_tasks_complete_from_event(event: &TaskExecutionNotificationEvent) -> String {
     if let TaskExecutionNotificationEvent::TasksComplete {
         stats,
@@ -191,6 +191,53 @@ fn test_format_tasks_complete_from_event_no_failures() {
     assert!(result.contains("❌ Failed: 0"));
 }
 
+#[test]
+fn test_plain_formats_have_no_escape_codes_or_emoji() {
+    let stats = TaskExecutionStats::new(2, 1, 0, 0, 1);
+    let tasks = vec![
+        TaskInfo {
+            id: "task-2".to_string(),
+            status: TaskStatus::Failed,
+            duration_secs: None,
+            current_output: "".to_string(),
+            task_type: "text_instruction".to_string(),
+            task_name: "second".to_string(),
+            task_metadata: "".to_string(),
+            error: Some("boom".to_string()),
+            result_data: None,
+        },
+        TaskInfo {
+            id: "task-1".to_string(),
+            status: TaskStatus::Pending,
+            duration_secs: None,
+            current_output: "".to_string(),
+            task_type: "sub_recipe".to_string(),
+            task_name: "first".to_string(),
+            task_metadata: "".to_string(),
+            error: None,
+            result_data: None,
+        },
+    ];
+    let update =
+        format_tasks_update_plain(&TaskExecutionNotificationEvent::TasksUpdate { stats, tasks });
+    assert_eq!(
+        update,
+        "Tasks: 2 total, 1 pending, 0 running, 0 completed, 1 failed\n  first: pending\n  second: failed\n"
+    );
+
+    let complete = format_tasks_complete_plain(&TaskExecutionNotificationEvent::TasksComplete {
+        stats: TaskCompletionStats::new(2, 1, 1),
+        failed_tasks: vec![FailedTaskInfo {
+            id: "task-2".to_string(),
+            name: "second".to_string(),
+            error: Some("boom".to_string()),
+        }],
+    });
+    assert!(complete.starts_with("Execution complete: 2 total, 1 completed, 1 failed, 50.0%"));
+    assert!(complete.contains("  Failed: second\n    Error: boom\n"));
+    assert!(complete.is_ascii());
+}
+
 #[test]
 fn test_format_task_display_running() {
     let task = TaskInfo {
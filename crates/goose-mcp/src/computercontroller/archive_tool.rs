@@ -0,0 +1,282 @@
+use flate2::read::GzDecoder;
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Extraction is refused when the archive's entries add up to more than this, which keeps a
+/// small archive from filling the disk
+const MAX_EXTRACTED_BYTES: u64 = 1024 * 1024 * 1024; // 1GB
+/// Entries listed before the rest are only counted
+const MAX_LISTED_ENTRIES: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    fn of_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") || name.ends_with(".jar") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    size: u64,
+    is_dir: bool,
+}
+
+fn error(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None)
+}
+
+fn open(path: &Path) -> Result<File, ErrorData> {
+    File::open(path).map_err(|e| error(format!("Failed to open archive: {}", e)))
+}
+
+fn tar_reader(path: &Path, format: ArchiveFormat) -> Result<Box<dyn Read>, ErrorData> {
+    let file = open(path)?;
+    Ok(match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        _ => Box::new(file),
+    })
+}
+
+fn list_entries(path: &Path, format: ArchiveFormat) -> Result<Vec<Entry>, ErrorData> {
+    let read_error = |e: std::io::Error| error(format!("Failed to read archive: {}", e));
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(open(path)?)
+                .map_err(|e| error(format!("Failed to read archive: {}", e)))?;
+            (0..archive.len())
+                .map(|i| {
+                    let file = archive
+                        .by_index(i)
+                        .map_err(|e| error(format!("Failed to read archive: {}", e)))?;
+                    Ok(Entry {
+                        name: file.name().to_string(),
+                        size: file.size(),
+                        is_dir: file.is_dir(),
+                    })
+                })
+                .collect()
+        }
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => {
+            let mut archive = tar::Archive::new(tar_reader(path, format)?);
+            let mut entries = Vec::new();
+            for entry in archive.entries().map_err(read_error)? {
+                let entry = entry.map_err(read_error)?;
+                let header = entry.header();
+                entries.push(Entry {
+                    name: entry.path().map_err(read_error)?.display().to_string(),
+                    size: header.size().map_err(read_error)?,
+                    is_dir: header.entry_type().is_dir(),
+                });
+            }
+            Ok(entries)
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1}MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1}KB", b as f64 / 1024.0),
+        b => format!("{}B", b),
+    }
+}
+
+fn extract(
+    path: &Path,
+    format: ArchiveFormat,
+    destination: &Path,
+    entries: &[Entry],
+) -> Result<(), ErrorData> {
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    if total > MAX_EXTRACTED_BYTES {
+        return Err(error(format!(
+            "The archive would extract to {}, more than the limit of {}",
+            format_size(total),
+            format_size(MAX_EXTRACTED_BYTES)
+        )));
+    }
+    fs::create_dir_all(destination)
+        .map_err(|e| error(format!("Failed to create {}: {}", destination.display(), e)))?;
+
+    // Both extractors refuse entries that would land outside of the destination
+    let extract_error = |e: String| error(format!("Failed to extract archive: {}", e));
+    match format {
+        ArchiveFormat::Zip => zip::ZipArchive::new(open(path)?)
+            .and_then(|mut archive| archive.extract(destination))
+            .map_err(|e| extract_error(e.to_string())),
+        ArchiveFormat::Tar | ArchiveFormat::TarGz => tar::Archive::new(tar_reader(path, format)?)
+            .unpack(destination)
+            .map_err(|e| extract_error(e.to_string())),
+    }
+}
+
+pub async fn archive_tool(
+    path: &str,
+    operation: &str,
+    destination: Option<&str>,
+    cache_dir: &Path,
+) -> Result<Vec<Content>, ErrorData> {
+    let path = Path::new(path);
+    let format = ArchiveFormat::of_path(path).ok_or_else(|| {
+        ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            "Unsupported archive, expected a .zip, .jar, .tar, .tar.gz or .tgz file".to_string(),
+            None,
+        )
+    })?;
+    let entries = list_entries(path, format)?;
+
+    match operation {
+        "list" => {
+            let total: u64 = entries.iter().map(|entry| entry.size).sum();
+            let mut listing = format!(
+                "{} entries, {} uncompressed:\n",
+                entries.len(),
+                format_size(total)
+            );
+            for entry in entries.iter().take(MAX_LISTED_ENTRIES) {
+                if entry.is_dir {
+                    listing.push_str(&format!("{}\n", entry.name));
+                } else {
+                    listing.push_str(&format!("{} ({})\n", entry.name, format_size(entry.size)));
+                }
+            }
+            if entries.len() > MAX_LISTED_ENTRIES {
+                listing.push_str(&format!(
+                    "... and {} more entries\n",
+                    entries.len() - MAX_LISTED_ENTRIES
+                ));
+            }
+            Ok(vec![Content::text(listing)])
+        }
+        "extract" => {
+            let destination = match destination {
+                Some(destination) => PathBuf::from(destination),
+                None => {
+                    let stem = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    let stem = [".tar.gz", ".tgz", ".tar", ".zip", ".jar"]
+                        .iter()
+                        .find_map(|ext| stem.strip_suffix(ext))
+                        .unwrap_or(&stem)
+                        .to_string();
+                    cache_dir.join("archives").join(stem)
+                }
+            };
+            extract(path, format, &destination, &entries)?;
+            let files = entries.iter().filter(|entry| !entry.is_dir).count();
+            Ok(vec![Content::text(format!(
+                "Extracted {} files to {}",
+                files,
+                destination.display()
+            ))])
+        }
+        _ => Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("Invalid operation: {}", operation),
+            None,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_zip(path: &Path) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("docs/readme.txt", options).unwrap();
+        writer.write_all(b"hello").unwrap();
+        writer.start_file("data.csv", options).unwrap();
+        writer.write_all(b"a,b\n1,2\n").unwrap();
+        writer.finish().unwrap();
+    }
+
+    fn write_tar_gz(path: &Path) {
+        let encoder =
+            flate2::write::GzEncoder::new(File::create(path).unwrap(), Default::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "notes.txt", &b"notes"[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zip_list_and_extract() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("bundle.zip");
+        write_zip(&archive);
+
+        let listing = archive_tool(archive.to_str().unwrap(), "list", None, dir.path())
+            .await
+            .unwrap();
+        let text = &listing[0].as_text().unwrap().text;
+        assert!(text.starts_with("2 entries, 13B uncompressed"));
+        assert!(text.contains("docs/readme.txt (5B)"));
+
+        let destination = dir.path().join("out");
+        archive_tool(
+            archive.to_str().unwrap(),
+            "extract",
+            destination.to_str(),
+            dir.path(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(destination.join("docs/readme.txt")).unwrap(),
+            "hello"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tar_gz_extracts_to_the_cache_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("notes.tar.gz");
+        write_tar_gz(&archive);
+        let cache_dir = dir.path().join("cache");
+
+        archive_tool(archive.to_str().unwrap(), "extract", None, &cache_dir)
+            .await
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(cache_dir.join("archives/notes/notes.txt")).unwrap(),
+            "notes"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_archives_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = archive_tool("archive.rar", "list", None, dir.path()).await;
+        assert_eq!(result.unwrap_err().code, ErrorCode::INVALID_PARAMS);
+    }
+}
@@ -0,0 +1,246 @@
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::error::DataFusionError;
+use datafusion::execution::context::SQLOptions;
+use datafusion::execution::options::{CsvReadOptions, ParquetReadOptions};
+use datafusion::prelude::{DataFrame, SessionContext};
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use std::path::Path;
+
+/// The name queries use for the loaded file
+const TABLE_NAME: &str = "data";
+/// Rows of a query result returned; the model gets a compact answer, not the raw data
+const MAX_RESULT_ROWS: usize = 100;
+
+fn error(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None)
+}
+
+fn query_error(e: DataFusionError) -> ErrorData {
+    ErrorData::new(
+        ErrorCode::INVALID_PARAMS,
+        format!("Query failed: {}", e),
+        None,
+    )
+}
+
+/// Load the file at `path` as the table `data`
+async fn load(path: &str) -> Result<SessionContext, ErrorData> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    // Files are only picked up when the extension given matches theirs
+    let file_extension = format!(".{}", extension);
+    let ctx = SessionContext::new();
+    let registered = match extension.as_str() {
+        "csv" => {
+            ctx.register_csv(
+                TABLE_NAME,
+                path,
+                CsvReadOptions::new().file_extension(&file_extension),
+            )
+            .await
+        }
+        "tsv" => {
+            ctx.register_csv(
+                TABLE_NAME,
+                path,
+                CsvReadOptions::new()
+                    .delimiter(b'\t')
+                    .file_extension(&file_extension),
+            )
+            .await
+        }
+        "parquet" => {
+            ctx.register_parquet(
+                TABLE_NAME,
+                path,
+                ParquetReadOptions {
+                    file_extension: &file_extension,
+                    ..Default::default()
+                },
+            )
+            .await
+        }
+        _ => {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                "Unsupported file, expected a .csv, .tsv or .parquet file".to_string(),
+                None,
+            ))
+        }
+    };
+    registered.map_err(|e| error(format!("Failed to load {}: {}", path, e)))?;
+    Ok(ctx)
+}
+
+/// Up to `MAX_RESULT_ROWS` rows of `df` as a table, noting when there were more
+async fn format_rows(df: DataFrame) -> Result<String, ErrorData> {
+    let batches = df
+        .limit(0, Some(MAX_RESULT_ROWS + 1))
+        .map_err(query_error)?
+        .collect()
+        .await
+        .map_err(query_error)?;
+    let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+
+    let mut kept = Vec::new();
+    let mut remaining = MAX_RESULT_ROWS;
+    for batch in batches {
+        if remaining == 0 {
+            break;
+        }
+        let take = batch.num_rows().min(remaining);
+        remaining -= take;
+        kept.push(batch.slice(0, take));
+    }
+    let mut text = pretty_format_batches(&kept)
+        .map_err(|e| error(format!("Failed to format the result: {}", e)))?
+        .to_string();
+    if rows > MAX_RESULT_ROWS {
+        text.push_str(&format!(
+            "\nOnly the first {} rows are shown; aggregate or filter to narrow the result",
+            MAX_RESULT_ROWS
+        ));
+    }
+    Ok(text)
+}
+
+pub async fn data_tool(
+    path: &str,
+    operation: &str,
+    query: Option<&str>,
+) -> Result<Vec<Content>, ErrorData> {
+    let ctx = load(path).await?;
+    let table = ctx.table(TABLE_NAME).await.map_err(query_error)?;
+
+    let result = match operation {
+        "schema" => {
+            let rows = table.clone().count().await.map_err(query_error)?;
+            let mut text = format!("{} rows, columns:\n", rows);
+            for field in table.schema().fields() {
+                text.push_str(&format!(
+                    "- {}: {}{}\n",
+                    field.name(),
+                    field.data_type(),
+                    if field.is_nullable() {
+                        " (nullable)"
+                    } else {
+                        ""
+                    }
+                ));
+            }
+            text
+        }
+        "describe" => {
+            let summary = table.describe().await.map_err(query_error)?;
+            format_rows(summary).await?
+        }
+        "query" => {
+            let query = query.ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "Missing 'query' parameter for the query operation".to_string(),
+                    None,
+                )
+            })?;
+            // Queries only read the loaded file; they may not create tables or write files
+            let options = SQLOptions::new()
+                .with_allow_ddl(false)
+                .with_allow_dml(false)
+                .with_allow_statements(false);
+            let df = ctx
+                .sql_with_options(query, options)
+                .await
+                .map_err(query_error)?;
+            format_rows(df).await?
+        }
+        _ => {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Invalid operation: {}. Valid operations are: 'schema', 'describe', 'query'",
+                    operation
+                ),
+                None,
+            ))
+        }
+    };
+    Ok(vec![Content::text(result)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sales_csv(dir: &Path) -> String {
+        let path = dir.join("sales.csv");
+        std::fs::write(
+            &path,
+            "region,product,amount\nnorth,apples,10\nsouth,apples,5\nnorth,pears,7\n",
+        )
+        .unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn text(result: Vec<Content>) -> String {
+        result[0].as_text().unwrap().text.clone()
+    }
+
+    #[tokio::test]
+    async fn test_schema_reports_columns_and_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = text(
+            data_tool(&sales_csv(dir.path()), "schema", None)
+                .await
+                .unwrap(),
+        );
+        assert!(result.starts_with("3 rows"));
+        assert!(result.contains("- region: Utf8"));
+        assert!(result.contains("- amount: Int64"));
+    }
+
+    #[tokio::test]
+    async fn test_aggregation_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = text(
+            data_tool(
+                &sales_csv(dir.path()),
+                "query",
+                Some(
+                    "SELECT region, SUM(amount) AS total FROM data GROUP BY region ORDER BY region",
+                ),
+            )
+            .await
+            .unwrap(),
+        );
+        assert!(result.contains("| north  | 17    |"));
+        assert!(result.contains("| south  | 5     |"));
+    }
+
+    #[tokio::test]
+    async fn test_describe_summarizes_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = text(
+            data_tool(&sales_csv(dir.path()), "describe", None)
+                .await
+                .unwrap(),
+        );
+        assert!(result.contains("mean"));
+        assert!(result.contains("amount"));
+    }
+
+    #[tokio::test]
+    async fn test_queries_cannot_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("copy.csv");
+        let result = data_tool(
+            &sales_csv(dir.path()),
+            "query",
+            Some(&format!("COPY data TO '{}'", out.display())),
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(!out.exists());
+    }
+}
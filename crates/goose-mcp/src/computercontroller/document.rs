@@ -0,0 +1,474 @@
+//! Turning PDF and Office documents into plain text the model can read, split into sections
+//! that keep their page, sheet or slide, so answers can point back into the document. Every
+//! document is held to a budget, both on the size of the file and on the text it yields.
+
+use anyhow::{anyhow, bail, Context, Result};
+use docx_rs::{read_docx, DocumentChild, ParagraphChild, RunChild};
+use lopdf::Document;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Overrides the default number of tokens a document attached as context may take
+pub const MAX_TOKENS_ENV_VAR: &str = "GOOSE_DOCUMENT_MAX_TOKENS";
+const DEFAULT_MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+const DEFAULT_MAX_TOKENS: usize = 20_000;
+/// Slides are compressed in the file, so what they unpack to is limited separately, per slide
+/// and across the presentation
+const MAX_SLIDE_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_SLIDES_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Text runs and paragraph ends in slide XML
+static SLIDE_TEXT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<a:t(?:\s[^>]*)?>(.*?)</a:t>|</a:p>").expect("valid regex"));
+static SLIDE_NUMBER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^ppt/slides/slide(\d+)\.xml$").expect("valid regex"));
+
+/// How much of a document is read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DocumentBudget {
+    /// Larger files are refused without being opened
+    pub max_file_bytes: u64,
+    /// Text past this many tokens is left out
+    pub max_tokens: usize,
+}
+
+impl Default for DocumentBudget {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_tokens: DEFAULT_MAX_TOKENS,
+        }
+    }
+}
+
+impl DocumentBudget {
+    /// The default budget, with its tokens taken from `GOOSE_DOCUMENT_MAX_TOKENS` when set
+    pub fn from_env() -> Self {
+        let max_tokens = std::env::var(MAX_TOKENS_ENV_VAR)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MAX_TOKENS);
+        Self {
+            max_tokens,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Pdf,
+    Docx,
+    Xlsx,
+    Pptx,
+}
+
+impl DocumentKind {
+    pub fn of_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "pdf" => Some(DocumentKind::Pdf),
+            "docx" => Some(DocumentKind::Docx),
+            "xlsx" => Some(DocumentKind::Xlsx),
+            "pptx" => Some(DocumentKind::Pptx),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            DocumentKind::Pdf => "PDF",
+            DocumentKind::Docx => "Word document",
+            DocumentKind::Xlsx => "Excel workbook",
+            DocumentKind::Pptx => "PowerPoint presentation",
+        }
+    }
+
+    /// What the sections of this kind of document are
+    fn sections_name(&self) -> &'static str {
+        match self {
+            DocumentKind::Pdf => "pages",
+            DocumentKind::Docx => "sections",
+            DocumentKind::Xlsx => "sheets",
+            DocumentKind::Pptx => "slides",
+        }
+    }
+}
+
+/// A part of a document and where it came from, such as `page 3` or `sheet "Q1"`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub reference: String,
+    pub text: String,
+}
+
+#[derive(Debug)]
+pub struct IngestedDocument {
+    pub kind: DocumentKind,
+    pub sections: Vec<Section>,
+    /// Sections in the document, including any left out for the budget
+    pub total_sections: usize,
+    /// Whether text was left out to stay within the budget
+    pub truncated: bool,
+}
+
+/// Rough token count; documents are cut well before precision matters
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Collects sections until the token budget runs out, cutting the one that crosses it
+struct Collector {
+    sections: Vec<Section>,
+    remaining_tokens: usize,
+    truncated: bool,
+}
+
+impl Collector {
+    fn new(budget: &DocumentBudget) -> Self {
+        Self {
+            sections: Vec::new(),
+            remaining_tokens: budget.max_tokens,
+            truncated: false,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.truncated
+    }
+
+    /// Whether `text` already holds more than the budget has room for
+    fn overflows(&self, text: &str) -> bool {
+        estimate_tokens(text) > self.remaining_tokens
+    }
+
+    fn push(&mut self, reference: String, text: String) {
+        let text = text.trim().to_string();
+        if text.is_empty() || self.truncated {
+            return;
+        }
+        let tokens = estimate_tokens(&text);
+        if tokens <= self.remaining_tokens {
+            self.remaining_tokens -= tokens;
+            self.sections.push(Section { reference, text });
+            return;
+        }
+        let kept: String = text.chars().take(self.remaining_tokens * 4).collect();
+        if !kept.is_empty() {
+            self.sections.push(Section {
+                reference,
+                text: kept,
+            });
+        }
+        self.remaining_tokens = 0;
+        self.truncated = true;
+    }
+}
+
+fn pdf_sections(path: &Path, collector: &mut Collector) -> Result<usize> {
+    let doc = Document::load(path).context("Failed to open PDF file")?;
+    let pages = doc.get_pages();
+    for &page in pages.keys() {
+        if collector.is_full() {
+            break;
+        }
+        let text = doc.extract_text(&[page]).unwrap_or_default();
+        collector.push(format!("page {}", page), text);
+    }
+    Ok(pages.len())
+}
+
+fn docx_sections(path: &Path, collector: &mut Collector) -> Result<usize> {
+    let bytes = fs::read(path).context("Failed to read DOCX file")?;
+    let docx = read_docx(&bytes).map_err(|e| anyhow!("Failed to parse DOCX file: {}", e))?;
+
+    // Word has no fixed pages, so headings divide the document instead
+    let mut reference = "start".to_string();
+    let mut text = String::new();
+    let mut total = 0;
+    for element in docx.document.children.iter() {
+        let DocumentChild::Paragraph(paragraph) = element else {
+            continue;
+        };
+        let paragraph_text: String = paragraph
+            .children
+            .iter()
+            .filter_map(|child| match child {
+                ParagraphChild::Run(run) => Some(run.children.iter().filter_map(|rc| match rc {
+                    RunChild::Text(t) => Some(t.text.as_str()),
+                    _ => None,
+                })),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        let is_heading = paragraph
+            .property
+            .style
+            .as_ref()
+            .is_some_and(|style| style.val.starts_with("Heading"));
+        if is_heading && !paragraph_text.trim().is_empty() {
+            if !text.trim().is_empty() {
+                total += 1;
+                collector.push(reference, std::mem::take(&mut text));
+            }
+            reference = format!("section \"{}\"", paragraph_text.trim());
+        }
+        text.push_str(&paragraph_text);
+        text.push('\n');
+    }
+    if !text.trim().is_empty() {
+        total += 1;
+        collector.push(reference, text);
+    }
+    Ok(total)
+}
+
+fn xlsx_sections(path: &Path, collector: &mut Collector) -> Result<usize> {
+    let workbook =
+        umya_spreadsheet::reader::xlsx::read(path).context("Failed to read Excel file")?;
+    let sheets = workbook.get_sheet_collection();
+    for sheet in sheets {
+        if collector.is_full() {
+            break;
+        }
+        let mut text = String::new();
+        for row in 1..=sheet.get_highest_row() {
+            let values: Vec<String> = (1..=sheet.get_highest_column())
+                .map(|col| {
+                    sheet
+                        .get_cell((col, row))
+                        .map(|cell| cell.get_value().into_owned())
+                        .unwrap_or_default()
+                })
+                .collect();
+            if values.iter().any(|value| !value.is_empty()) {
+                text.push_str(&format!("row {}: {}\n", row, values.join(" | ")));
+            }
+            // Rows past the budget would only be cut off again
+            if collector.overflows(&text) {
+                break;
+            }
+        }
+        collector.push(format!("sheet \"{}\"", sheet.get_name()), text);
+    }
+    Ok(sheets.len())
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn slide_text(xml: &str) -> String {
+    let mut text = String::new();
+    for capture in SLIDE_TEXT.captures_iter(xml) {
+        match capture.get(1) {
+            Some(run) => text.push_str(&unescape_xml(run.as_str())),
+            None => text.push('\n'),
+        }
+    }
+    text
+}
+
+fn pptx_sections(path: &Path, collector: &mut Collector) -> Result<usize> {
+    let mut archive =
+        zip::ZipArchive::new(File::open(path)?).context("Failed to read PowerPoint file")?;
+    let mut slides: Vec<(usize, String)> = archive
+        .file_names()
+        .filter_map(|name| {
+            let number = SLIDE_NUMBER.captures(name)?.get(1)?.as_str().parse().ok()?;
+            Some((number, name.to_string()))
+        })
+        .collect();
+    slides.sort();
+    let mut unpacked = 0;
+    for (number, name) in &slides {
+        if collector.is_full() {
+            break;
+        }
+        let limit = MAX_SLIDE_BYTES.min(MAX_SLIDES_BYTES - unpacked);
+        let mut xml = String::new();
+        archive
+            .by_name(name)?
+            .take(limit + 1)
+            .read_to_string(&mut xml)
+            .with_context(|| format!("Failed to read slide {}", number))?;
+        if xml.len() as u64 > limit {
+            bail!(
+                "slide {} unpacks to more than {} bytes, which is more than a presentation may",
+                number,
+                limit
+            );
+        }
+        unpacked += xml.len() as u64;
+        collector.push(format!("slide {}", number), slide_text(&xml));
+    }
+    Ok(slides.len())
+}
+
+/// Read the document at `path` into sections of text, within `budget`
+pub fn ingest(path: &Path, budget: &DocumentBudget) -> Result<IngestedDocument> {
+    let kind = DocumentKind::of_path(path)
+        .ok_or_else(|| anyhow!("{} is not a PDF, docx, xlsx or pptx file", path.display()))?;
+    let size = fs::metadata(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .len();
+    if size > budget.max_file_bytes {
+        bail!(
+            "{} is {} bytes, more than the limit of {} bytes",
+            path.display(),
+            size,
+            budget.max_file_bytes
+        );
+    }
+
+    let mut collector = Collector::new(budget);
+    let total_sections = match kind {
+        DocumentKind::Pdf => pdf_sections(path, &mut collector)?,
+        DocumentKind::Docx => docx_sections(path, &mut collector)?,
+        DocumentKind::Xlsx => xlsx_sections(path, &mut collector)?,
+        DocumentKind::Pptx => pptx_sections(path, &mut collector)?,
+    };
+    Ok(IngestedDocument {
+        kind,
+        sections: collector.sections,
+        total_sections,
+        truncated: collector.truncated,
+    })
+}
+
+impl IngestedDocument {
+    /// The document as text, each section headed by its reference
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}, {} {}\n",
+            self.kind.name(),
+            self.total_sections,
+            self.kind.sections_name()
+        );
+        for section in &self.sections {
+            text.push_str(&format!("\n[{}]\n{}\n", section.reference, section.text));
+        }
+        if self.truncated {
+            text.push_str(&format!(
+                "\n[Truncated to fit the token budget after {}]\n",
+                self.sections
+                    .last()
+                    .map(|section| section.reference.as_str())
+                    .unwrap_or("the start")
+            ));
+        }
+        text
+    }
+}
+
+/// Contents of a file attached as context: documents are converted to text within the budget
+/// from `GOOSE_DOCUMENT_MAX_TOKENS`, anything else is read as is
+pub fn read_attachment(path: &Path) -> Result<String> {
+    if DocumentKind::of_path(path).is_some() {
+        return Ok(ingest(path, &DocumentBudget::from_env())?.to_text());
+    }
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn test_data(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/computercontroller/tests/data")
+            .join(name)
+    }
+
+    #[test]
+    fn test_pdf_pages_are_referenced() {
+        let document = ingest(&test_data("test.pdf"), &DocumentBudget::default()).unwrap();
+        assert_eq!(document.kind, DocumentKind::Pdf);
+        assert!(!document.truncated);
+        assert_eq!(document.sections[0].reference, "page 1");
+        assert!(document.to_text().contains("[page 1]"));
+    }
+
+    #[test]
+    fn test_xlsx_sheets_are_referenced() {
+        let document = ingest(
+            &test_data("FinancialSample.xlsx"),
+            &DocumentBudget::default(),
+        )
+        .unwrap();
+        assert!(document.sections[0].reference.starts_with("sheet \""));
+        assert!(document.sections[0].text.starts_with("row 1: "));
+    }
+
+    #[test]
+    fn test_budget_truncates_the_text() {
+        let budget = DocumentBudget {
+            max_tokens: 50,
+            ..DocumentBudget::default()
+        };
+        let document = ingest(&test_data("FinancialSample.xlsx"), &budget).unwrap();
+        assert!(document.truncated);
+        let kept: usize = document
+            .sections
+            .iter()
+            .map(|section| estimate_tokens(&section.text))
+            .sum();
+        assert!(kept <= 50);
+        assert!(document.to_text().contains("Truncated to fit"));
+    }
+
+    #[test]
+    fn test_xlsx_rows_stop_at_the_budget() {
+        let budget = DocumentBudget {
+            max_tokens: 50,
+            ..DocumentBudget::default()
+        };
+        let mut collector = Collector::new(&budget);
+        xlsx_sections(&test_data("FinancialSample.xlsx"), &mut collector).unwrap();
+        assert!(collector.truncated);
+        assert_eq!(collector.sections.len(), 1);
+        assert!(!collector.sections[0].text.contains("row 100:"));
+    }
+
+    #[test]
+    fn test_slides_that_unpack_too_far_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bomb.pptx");
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        zip.start_file(
+            "ppt/slides/slide1.xml",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+        let padding = vec![b' '; MAX_SLIDE_BYTES as usize + 1];
+        std::io::Write::write_all(&mut zip, &padding).unwrap();
+        zip.finish().unwrap();
+
+        let error = ingest(&path, &DocumentBudget::default()).unwrap_err();
+        assert!(error.to_string().contains("unpacks to more than"));
+    }
+
+    #[test]
+    fn test_large_files_are_refused() {
+        let budget = DocumentBudget {
+            max_file_bytes: 10,
+            ..DocumentBudget::default()
+        };
+        assert!(ingest(&test_data("sample.docx"), &budget).is_err());
+    }
+
+    #[test]
+    fn test_slide_text_keeps_paragraphs() {
+        let xml = r#"<p:sld><a:p><a:r><a:t>Q3 &amp; Q4</a:t></a:r></a:p><a:p><a:r><a:t xml:space="preserve">Revenue up</a:t></a:r></a:p></p:sld>"#;
+        assert_eq!(slide_text(xml), "Q3 & Q4\nRevenue up\n");
+    }
+}
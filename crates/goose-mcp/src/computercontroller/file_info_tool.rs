@@ -0,0 +1,201 @@
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::Path,
+};
+
+/// Bytes read from the start of a file to recognize its format; tar keeps its signature the
+/// furthest in, at offset 257
+const HEADER_BYTES: u64 = 512;
+
+/// Formats told apart by the bytes a file starts with
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "PNG image"),
+    (b"\xff\xd8\xff", "JPEG image"),
+    (b"GIF87a", "GIF image"),
+    (b"GIF89a", "GIF image"),
+    (b"BM", "BMP image"),
+    (b"%PDF-", "PDF document"),
+    (b"PK\x03\x04", "ZIP archive (also docx, xlsx, jar)"),
+    (b"\x1f\x8b", "gzip compressed data"),
+    (b"BZh", "bzip2 compressed data"),
+    (b"\xfd7zXZ\x00", "xz compressed data"),
+    (b"7z\xbc\xaf\x27\x1c", "7-Zip archive"),
+    (b"\x7fELF", "ELF executable"),
+    (b"MZ", "PE executable (Windows)"),
+    (b"\xcf\xfa\xed\xfe", "Mach-O executable"),
+    (b"\xce\xfa\xed\xfe", "Mach-O executable"),
+    (b"\xca\xfe\xba\xbe", "Mach-O universal binary or Java class"),
+    (b"\x00asm", "WebAssembly module"),
+    (b"SQLite format 3\x00", "SQLite database"),
+];
+
+fn detect_format(header: &[u8]) -> Option<&'static str> {
+    if header.len() >= 12 && &header[..4] == b"RIFF" && &header[8..12] == b"WEBP" {
+        return Some("WebP image");
+    }
+    if header.get(257..262) == Some(b"ustar".as_slice()) {
+        return Some("tar archive");
+    }
+    SIGNATURES
+        .iter()
+        .find(|(magic, _)| header.starts_with(magic))
+        .map(|(_, name)| *name)
+}
+
+fn elf_details(header: &[u8]) -> Option<String> {
+    let bits = match header.get(4)? {
+        1 => "32-bit",
+        2 => "64-bit",
+        _ => return None,
+    };
+    let little_endian = *header.get(5)? == 1;
+    let machine_bytes = [*header.get(18)?, *header.get(19)?];
+    let machine = if little_endian {
+        u16::from_le_bytes(machine_bytes)
+    } else {
+        u16::from_be_bytes(machine_bytes)
+    };
+    let arch = match machine {
+        0x03 => "x86",
+        0x28 => "ARM",
+        0x3e => "x86-64",
+        0xb7 => "AArch64",
+        0xf3 => "RISC-V",
+        _ => "unknown architecture",
+    };
+    Some(format!("{}, {}", bits, arch))
+}
+
+fn pe_details(header: &[u8]) -> Option<String> {
+    let pe_offset = u32::from_le_bytes(header.get(0x3c..0x40)?.try_into().ok()?) as usize;
+    if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let machine = u16::from_le_bytes(header.get(pe_offset + 4..pe_offset + 6)?.try_into().ok()?);
+    let arch = match machine {
+        0x014c => "x86",
+        0x8664 => "x86-64",
+        0xaa64 => "ARM64",
+        _ => "unknown architecture",
+    };
+    Some(arch.to_string())
+}
+
+fn mach_o_details(header: &[u8]) -> Option<String> {
+    let cpu_type = u32::from_le_bytes(header.get(4..8)?.try_into().ok()?);
+    let arch = match cpu_type {
+        0x0100_0007 => "x86-64",
+        0x0100_000c => "arm64",
+        0x7 => "x86",
+        0xc => "ARM",
+        _ => "unknown architecture",
+    };
+    Some(arch.to_string())
+}
+
+/// Whether `header` is valid UTF-8, allowing for a character cut off at its end
+fn is_text(header: &[u8]) -> bool {
+    match std::str::from_utf8(header) {
+        Ok(text) => !text.contains('\0'),
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+fn image_details(path: &Path) -> Option<String> {
+    image::image_dimensions(path)
+        .ok()
+        .map(|(width, height)| format!("{}x{} pixels", width, height))
+}
+
+pub async fn file_info_tool(path: &str) -> Result<Vec<Content>, ErrorData> {
+    let path = Path::new(path);
+    let metadata = fs::metadata(path).map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("Failed to read {}: {}", path.display(), e),
+            None,
+        )
+    })?;
+    if metadata.is_dir() {
+        return Err(ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            format!("{} is a directory", path.display()),
+            None,
+        ));
+    }
+
+    let mut header = Vec::new();
+    File::open(path)
+        .and_then(|file| file.take(HEADER_BYTES).read_to_end(&mut header))
+        .map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to read {}: {}", path.display(), e),
+                None,
+            )
+        })?;
+
+    let format = detect_format(&header);
+    let details = match format {
+        Some("ELF executable") => elf_details(&header),
+        Some("PE executable (Windows)") => pe_details(&header),
+        Some("Mach-O executable") => mach_o_details(&header),
+        Some(name) if name.ends_with("image") => image_details(path),
+        _ => None,
+    };
+
+    let mut info = format!("Path: {}\nSize: {} bytes\n", path.display(), metadata.len());
+    if let Ok(modified) = metadata.modified() {
+        let modified: chrono::DateTime<chrono::Local> = modified.into();
+        info.push_str(&format!("Modified: {}\n", modified.to_rfc3339()));
+    }
+    match format {
+        Some(format) => info.push_str(&format!("Type: {}\n", format)),
+        None if is_text(&header) => info.push_str("Type: text\n"),
+        None => info.push_str("Type: unknown binary data\n"),
+    }
+    if let Some(details) = details {
+        info.push_str(&format!("Details: {}\n", details));
+    }
+    Ok(vec![Content::text(info)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_are_detected_by_signature() {
+        assert_eq!(detect_format(b"%PDF-1.7\n"), Some("PDF document"));
+        assert_eq!(detect_format(b"\x89PNG\r\n\x1a\n...."), Some("PNG image"));
+        assert_eq!(detect_format(b"plain text"), None);
+
+        let mut tar = vec![0u8; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(detect_format(&tar), Some("tar archive"));
+    }
+
+    #[test]
+    fn test_elf_header_details() {
+        let mut header = vec![0u8; 64];
+        header[..4].copy_from_slice(b"\x7fELF");
+        header[4] = 2;
+        header[5] = 1;
+        header[18] = 0x3e;
+        assert_eq!(elf_details(&header).unwrap(), "64-bit, x86-64");
+    }
+
+    #[tokio::test]
+    async fn test_image_dimensions_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pixel.png");
+        image::RgbImage::new(3, 2).save(&path).unwrap();
+
+        let result = file_info_tool(path.to_str().unwrap()).await.unwrap();
+        let text = &result[0].as_text().unwrap().text;
+        assert!(text.contains("Type: PNG image"));
+        assert!(text.contains("Details: 3x2 pixels"));
+    }
+}
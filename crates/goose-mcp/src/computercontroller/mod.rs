@@ -5,7 +5,13 @@ use reqwest::{Client, Url};
 use serde_json::Value;
 use std::borrow::Cow;
 use std::{
-    collections::HashMap, fs, future::Future, path::PathBuf, pin::Pin, sync::Arc, sync::Mutex,
+    collections::HashMap,
+    fs,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    sync::Mutex,
 };
 use tokio::{process::Command, sync::mpsc};
 
@@ -24,7 +30,11 @@ use rmcp::model::{
 };
 use rmcp::object;
 
+mod archive_tool;
+mod data_tool;
+pub mod document;
 mod docx_tool;
+mod file_info_tool;
 mod pdf_tool;
 mod xlsx_tool;
 
@@ -421,6 +431,148 @@ impl ComputerControllerRouter {
             }),
         );
 
+        let archive_tool = Tool::new(
+            "archive_tool",
+            indoc! {r#"
+                List or extract zip and tar archives (.zip, .jar, .tar, .tar.gz, .tgz) locally.
+                Supports operations:
+                - list: List the entries of the archive with their uncompressed sizes
+                - extract: Extract the archive into a directory (returns where the files were put)
+
+                Archives are extracted into the cache directory unless a destination is given.
+                Use pdf_tool and docx_tool on the extracted files to get text out of documents.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path", "operation"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the archive"
+                    },
+                    "operation": {
+                        "type": "string",
+                        "enum": ["list", "extract"],
+                        "description": "Operation to perform on the archive"
+                    },
+                    "destination": {
+                        "type": "string",
+                        "description": "Directory to extract into for the extract operation"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Archive list and extract".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        let file_info_tool = Tool::new(
+            "file_info",
+            indoc! {r#"
+                Show basic metadata of a file without opening it in another program: its size,
+                when it was modified, its type worked out from its contents rather than its name,
+                the dimensions of images, and the architecture of executables (ELF, PE, Mach-O).
+
+                Use this to find out what an unfamiliar or binary file is before processing it.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the file"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("File info".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        let read_document_tool = Tool::new(
+            "read_document",
+            indoc! {r#"
+                Read a PDF, Word (.docx), Excel (.xlsx) or PowerPoint (.pptx) document as text,
+                split into sections headed by where they come from: [page 3], [sheet "Q1"],
+                [slide 2], or [section "Heading"] for Word documents. Use these references when
+                quoting or summarizing the document.
+
+                Long documents are cut off once they reach max_tokens, and the output says where.
+                Use pdf_tool, docx_tool or xlsx_tool to work on a specific part in more detail.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the document"
+                    },
+                    "max_tokens": {
+                        "type": "integer",
+                        "description": "Most tokens of text to return (default 20000)"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Read document".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        let data_tool = Tool::new(
+            "data_tool",
+            indoc! {r#"
+                Analyze CSV, TSV and Parquet files without reading their rows, using an embedded
+                SQL engine. Supports operations:
+                - schema: Column names and types, and the number of rows
+                - describe: Summary statistics of every column (count, nulls, mean, std, min, max, median)
+                - query: Run a read-only SQL query against the file, which is the table `data`
+                  (e.g. "SELECT region, SUM(amount) FROM data GROUP BY region")
+
+                Query results are cut off at 100 rows, so aggregate rather than select raw rows.
+                Use this instead of reading large data files into the conversation.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path", "operation"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the .csv, .tsv or .parquet file"
+                    },
+                    "operation": {
+                        "type": "string",
+                        "enum": ["schema", "describe", "query"],
+                        "description": "Operation to perform on the data"
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "SQL query for the query operation, reading from the table `data`"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Data analysis".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
         // choose_app_strategy().cache_dir()
         // - macOS/Linux: ~/.cache/goose/computer_controller/
         // - Windows:     ~\AppData\Local\Block\goose\cache\computer_controller\
@@ -527,6 +679,13 @@ impl ComputerControllerRouter {
               - Save as text, JSON, or binary files
               - Content is cached locally for later use
               - This is not optimised for complex websites, so don't use this as the first tool.
+            archive_tool and file_info
+              - Inspect and unpack archives, binaries and images locally, without external services
+            data_tool
+              - Schema, summary statistics and SQL aggregations over CSV and Parquet files
+              - Prefer this over reading or scripting through large data files
+            read_document
+              - Read PDF, docx, xlsx and pptx files as text with page, sheet and slide references
             cache
               - Manage your cached files
               - List, view, delete files
@@ -548,6 +707,10 @@ impl ComputerControllerRouter {
                 pdf_tool,
                 docx_tool,
                 xlsx_tool,
+                archive_tool,
+                file_info_tool,
+                read_document_tool,
+                data_tool,
             ],
             cache_dir,
             active_resources: Arc::new(Mutex::new(HashMap::new())),
@@ -1181,6 +1344,97 @@ impl ComputerControllerRouter {
         crate::computercontroller::pdf_tool::pdf_tool(path, operation, &self.cache_dir).await
     }
 
+    async fn archive_tool(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from("Missing 'path' parameter"),
+                data: None,
+            })?;
+
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from("Missing 'operation' parameter"),
+                data: None,
+            })?;
+
+        crate::computercontroller::archive_tool::archive_tool(
+            path,
+            operation,
+            params.get("destination").and_then(|v| v.as_str()),
+            &self.cache_dir,
+        )
+        .await
+    }
+
+    async fn file_info(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from("Missing 'path' parameter"),
+                data: None,
+            })?;
+
+        crate::computercontroller::file_info_tool::file_info_tool(path).await
+    }
+
+    async fn data_tool(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from("Missing 'path' parameter"),
+                data: None,
+            })?;
+
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from("Missing 'operation' parameter"),
+                data: None,
+            })?;
+
+        crate::computercontroller::data_tool::data_tool(
+            path,
+            operation,
+            params.get("query").and_then(|v| v.as_str()),
+        )
+        .await
+    }
+
+    async fn read_document(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ErrorData {
+                code: ErrorCode::INVALID_PARAMS,
+                message: Cow::from("Missing 'path' parameter"),
+                data: None,
+            })?;
+
+        let mut budget = document::DocumentBudget::default();
+        if let Some(max_tokens) = params.get("max_tokens").and_then(|v| v.as_u64()) {
+            budget.max_tokens = max_tokens as usize;
+        }
+
+        let document = document::ingest(Path::new(path), &budget).map_err(|e| ErrorData {
+            code: ErrorCode::INTERNAL_ERROR,
+            message: Cow::from(e.to_string()),
+            data: None,
+        })?;
+        Ok(vec![Content::text(document.to_text())])
+    }
+
     async fn cache(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
         let command = params
             .get("command")
@@ -1324,6 +1578,10 @@ impl Router for ComputerControllerRouter {
                 "pdf_tool" => this.pdf_tool(arguments).await,
                 "docx_tool" => this.docx_tool(arguments).await,
                 "xlsx_tool" => this.xlsx_tool(arguments).await,
+                "archive_tool" => this.archive_tool(arguments).await,
+                "file_info" => this.file_info(arguments).await,
+                "read_document" => this.read_document(arguments).await,
+                "data_tool" => this.data_tool(arguments).await,
                 _ => Err(ErrorData {
                     code: ErrorCode::INVALID_REQUEST,
                     message: Cow::from(format!("Tool {} not found", tool_name)),
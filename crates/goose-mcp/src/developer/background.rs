@@ -0,0 +1,464 @@
+//! Long-running commands such as dev servers and file watchers, started in the background so
+//! the agent can keep working and check on their output later. Every process belongs to the
+//! session that started it, which is the only one that sees it, and is stopped when that
+//! session ends or the router is dropped. On Linux a process is also stopped when the extension
+//! dies without getting to stop it.
+
+use rmcp::model::{ErrorCode, ErrorData};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+
+use super::shell::{get_shell_config, ShellKind};
+
+pub const MAX_PROCESSES: usize = 8;
+/// Unread lines kept per process; the oldest are dropped once a process prints more
+const MAX_UNREAD_LINES: usize = 2_000;
+/// How long a process gets to exit after being asked to before it is killed
+const STOP_GRACE_PERIOD: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Default)]
+struct Output {
+    unread: VecDeque<String>,
+    /// Lines dropped since the output was last read
+    dropped: usize,
+    /// Streams still being read, so output printed just before exiting is not missed
+    open_streams: usize,
+}
+
+impl Output {
+    fn push(&mut self, line: String) {
+        if self.unread.len() >= MAX_UNREAD_LINES {
+            self.unread.pop_front();
+            self.dropped += 1;
+        }
+        self.unread.push_back(line);
+    }
+}
+
+struct BackgroundProcess {
+    /// The session that started the process
+    session: String,
+    command: String,
+    child: Child,
+    started: Instant,
+    output: Arc<Mutex<Output>>,
+}
+
+impl BackgroundProcess {
+    fn exit_code(&mut self) -> Option<Option<i32>> {
+        self.child
+            .try_wait()
+            .ok()
+            .flatten()
+            .map(|status| status.code())
+    }
+
+    /// Ask the process and everything it started to exit
+    fn terminate(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.child.id() {
+            use nix::sys::signal::{killpg, Signal};
+            use nix::unistd::Pid;
+            let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGTERM);
+            return;
+        }
+        let _ = self.child.start_kill();
+    }
+
+    /// Kill the process and everything it started
+    fn kill(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.child.id() {
+            use nix::sys::signal::{killpg, Signal};
+            use nix::unistd::Pid;
+            let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL);
+        }
+        let _ = self.child.start_kill();
+    }
+
+    /// Stop the process, killing it if it doesn't exit within the grace period
+    async fn shut_down(&mut self) {
+        if self.exit_code().is_none() {
+            self.terminate();
+            if tokio::time::timeout(STOP_GRACE_PERIOD, self.child.wait())
+                .await
+                .is_err()
+            {
+                self.kill();
+                let _ = self.child.wait().await;
+            }
+        }
+    }
+
+    fn take_output(&mut self) -> ProcessOutput {
+        let exit_code = self.exit_code();
+        let mut output = self.output.lock().unwrap();
+        ProcessOutput {
+            lines: output.unread.drain(..).collect(),
+            dropped: std::mem::take(&mut output.dropped),
+            exit_code,
+        }
+    }
+}
+
+impl Drop for BackgroundProcess {
+    fn drop(&mut self) {
+        if self.exit_code().is_none() {
+            self.kill();
+        }
+    }
+}
+
+/// A process as listed for the agent
+#[derive(Debug)]
+pub struct ProcessSummary {
+    pub id: String,
+    pub command: String,
+    pub pid: Option<u32>,
+    pub running_for: Duration,
+    /// `Some` once the process exited, holding its exit code unless a signal ended it
+    pub exit_code: Option<Option<i32>>,
+}
+
+/// What a process printed since its output was last read
+#[derive(Debug)]
+pub struct ProcessOutput {
+    pub lines: Vec<String>,
+    /// Lines that were dropped before they could be read
+    pub dropped: usize,
+    /// `Some` once the process exited, after which it is no longer tracked
+    pub exit_code: Option<Option<i32>>,
+}
+
+fn collect_lines(stream: impl AsyncRead + Unpin + Send + 'static, output: Arc<Mutex<Output>>) {
+    output.lock().unwrap().open_streams += 1;
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stream).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            output.lock().unwrap().push(line);
+        }
+        output.lock().unwrap().open_streams -= 1;
+    });
+}
+
+/// `command` run so that the whole process group is taken down when the shell is asked to exit,
+/// which is what the parent death signal sends it
+#[cfg(target_os = "linux")]
+fn supervised(command: &str) -> String {
+    format!(
+        "trap 'trap - TERM; kill -TERM 0' TERM\n{{\n{}\n}} &\nwait $!",
+        command
+    )
+}
+
+#[derive(Default)]
+pub struct BackgroundProcesses {
+    processes: Mutex<HashMap<String, BackgroundProcess>>,
+    next_id: AtomicUsize,
+}
+
+impl BackgroundProcesses {
+    /// Start `command` in `cwd` for `session` and return its id and process id
+    pub fn start(
+        &self,
+        command: &str,
+        cwd: &Path,
+        session: &str,
+    ) -> Result<(String, Option<u32>), ErrorData> {
+        let running = self
+            .processes
+            .lock()
+            .unwrap()
+            .values_mut()
+            .filter(|process| process.exit_code().is_none())
+            .count();
+        if running >= MAX_PROCESSES {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_REQUEST,
+                format!(
+                    "There are already {} background processes running, stop one before starting another",
+                    MAX_PROCESSES
+                ),
+                None,
+            ));
+        }
+
+        let shell = get_shell_config();
+        let mut process = Command::new(&shell.executable);
+        process
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .env("GOOSE_TERMINAL", "1");
+        // A process group of its own lets stopping it also stop whatever it started, such as
+        // the server behind `npm run dev`
+        #[cfg(unix)]
+        process.process_group(0);
+        // Tie the process to this one, so it doesn't outlive an extension that is killed
+        // before it can stop it. The signal only reaches the shell, which passes it on to
+        // the rest of the group.
+        #[cfg(target_os = "linux")]
+        let command = if shell.kind == ShellKind::Posix {
+            let parent = nix::unistd::getpid();
+            // Safety: the closure only makes system calls that are safe after fork
+            unsafe {
+                process.pre_exec(move || {
+                    nix::sys::prctl::set_pdeathsig(nix::sys::signal::Signal::SIGTERM)?;
+                    // The parent may have died before the signal was set up
+                    if nix::unistd::getppid() != parent {
+                        return Err(std::io::Error::other("goose exited"));
+                    }
+                    Ok(())
+                });
+            }
+            supervised(command)
+        } else {
+            command.to_string()
+        };
+        shell.apply(&mut process, &command);
+        let mut child = process.spawn().map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to start '{}': {}", command, e),
+                None,
+            )
+        })?;
+
+        let output = Arc::new(Mutex::new(Output::default()));
+        if let Some(stdout) = child.stdout.take() {
+            collect_lines(stdout, output.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            collect_lines(stderr, output.clone());
+        }
+
+        let id = format!("proc-{}", self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+        let pid = child.id();
+        self.processes.lock().unwrap().insert(
+            id.clone(),
+            BackgroundProcess {
+                session: session.to_string(),
+                command: command.to_string(),
+                child,
+                started: Instant::now(),
+                output,
+            },
+        );
+        Ok((id, pid))
+    }
+
+    fn not_found(&self, id: &str, session: &str) -> ErrorData {
+        let mut ids: Vec<String> = self
+            .processes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, process)| process.session == session)
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort();
+        ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            if ids.is_empty() {
+                format!("No background process '{}', and none are running", id)
+            } else {
+                format!(
+                    "No background process '{}', known processes are: {}",
+                    id,
+                    ids.join(", ")
+                )
+            },
+            None,
+        )
+    }
+
+    /// Every tracked process of `session`, oldest first
+    pub fn list(&self, session: &str) -> Vec<ProcessSummary> {
+        let mut processes = self.processes.lock().unwrap();
+        let mut summaries: Vec<ProcessSummary> = processes
+            .iter_mut()
+            .filter(|(_, process)| process.session == session)
+            .map(|(id, process)| ProcessSummary {
+                id: id.clone(),
+                command: process.command.clone(),
+                pid: process.child.id(),
+                running_for: process.started.elapsed(),
+                exit_code: process.exit_code(),
+            })
+            .collect();
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.running_for));
+        summaries
+    }
+
+    /// Output of process `id` of `session` since it was last read, after waiting up to `wait`
+    /// for more of it unless the process exits first
+    pub async fn output(
+        &self,
+        id: &str,
+        session: &str,
+        wait: Duration,
+    ) -> Result<ProcessOutput, ErrorData> {
+        let deadline = Instant::now() + wait;
+        loop {
+            let state = self
+                .processes
+                .lock()
+                .unwrap()
+                .get_mut(id)
+                .filter(|process| process.session == session)
+                .map(|process| (process.exit_code(), process.output.clone()));
+            let Some((exit_code, output)) = state else {
+                return Err(self.not_found(id, session));
+            };
+            let finished = exit_code.is_some() && output.lock().unwrap().open_streams == 0;
+            if finished || Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        let mut processes = self.processes.lock().unwrap();
+        let read = processes.get_mut(id).map(BackgroundProcess::take_output);
+        match read {
+            Some(output) => {
+                if output.exit_code.is_some() {
+                    processes.remove(id);
+                }
+                Ok(output)
+            }
+            None => {
+                drop(processes);
+                Err(self.not_found(id, session))
+            }
+        }
+    }
+
+    /// Stop process `id` of `session` and everything it started, returning its unread output
+    pub async fn stop(&self, id: &str, session: &str) -> Result<ProcessOutput, ErrorData> {
+        let process = {
+            let mut processes = self.processes.lock().unwrap();
+            match processes.get(id) {
+                Some(process) if process.session == session => processes.remove(id),
+                _ => None,
+            }
+        };
+        let Some(mut process) = process else {
+            return Err(self.not_found(id, session));
+        };
+        process.shut_down().await;
+        Ok(process.take_output())
+    }
+
+    /// Stop every process `session` started, returning how many there were
+    pub async fn end_session(&self, session: &str) -> usize {
+        let mut ended: Vec<BackgroundProcess> = {
+            let mut processes = self.processes.lock().unwrap();
+            let ids: Vec<String> = processes
+                .iter()
+                .filter(|(_, process)| process.session == session)
+                .map(|(id, _)| id.clone())
+                .collect();
+            ids.iter().filter_map(|id| processes.remove(id)).collect()
+        };
+        // Ask them all first, so the grace periods run side by side
+        for process in &mut ended {
+            if process.exit_code().is_none() {
+                process.terminate();
+            }
+        }
+        for process in &mut ended {
+            process.shut_down().await;
+        }
+        ended.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oldest_output_is_dropped_first() {
+        let mut output = Output::default();
+        for i in 0..MAX_UNREAD_LINES + 3 {
+            output.push(i.to_string());
+        }
+        assert_eq!(output.dropped, 3);
+        assert_eq!(output.unread.front().unwrap(), "3");
+        assert_eq!(output.unread.len(), MAX_UNREAD_LINES);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_processes_run_until_stopped() {
+        let processes = BackgroundProcesses::default();
+        let dir = tempfile::tempdir().unwrap();
+        let (id, pid) = processes
+            .start("echo started; sleep 30", dir.path(), "session")
+            .unwrap();
+        assert!(pid.is_some());
+
+        let output = processes
+            .output(&id, "session", Duration::from_millis(500))
+            .await
+            .unwrap();
+        assert_eq!(output.lines, vec!["started"]);
+        assert_eq!(output.exit_code, None);
+        assert_eq!(processes.list("session").len(), 1);
+
+        let stopped = processes.stop(&id, "session").await.unwrap();
+        assert!(stopped.exit_code.is_some());
+        assert!(processes.list("session").is_empty());
+        assert!(processes
+            .output(&id, "session", Duration::ZERO)
+            .await
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_exited_processes_report_their_exit_code() {
+        let processes = BackgroundProcesses::default();
+        let dir = tempfile::tempdir().unwrap();
+        let (id, _) = processes
+            .start("echo done; exit 3", dir.path(), "session")
+            .unwrap();
+
+        let output = processes
+            .output(&id, "session", Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(output.exit_code, Some(Some(3)));
+        assert!(processes.list("session").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_processes_belong_to_their_session() {
+        let processes = BackgroundProcesses::default();
+        let dir = tempfile::tempdir().unwrap();
+        let (id, _) = processes.start("sleep 30", dir.path(), "first").unwrap();
+        processes.start("sleep 30", dir.path(), "second").unwrap();
+
+        assert_eq!(processes.list("first").len(), 1);
+        assert!(processes
+            .output(&id, "second", Duration::ZERO)
+            .await
+            .is_err());
+        assert!(processes.stop(&id, "second").await.is_err());
+
+        assert_eq!(processes.end_session("first").await, 1);
+        assert!(processes.list("first").is_empty());
+        assert_eq!(processes.list("second").len(), 1);
+        assert_eq!(processes.end_session("second").await, 1);
+    }
+}
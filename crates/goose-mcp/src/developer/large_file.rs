@@ -0,0 +1,234 @@
+//! Reading parts of files too large to view whole, such as multi-gigabyte logs. Files are only
+//! ever streamed, and every line is cut to a bounded length, so neither the process's memory
+//! nor the model's context grows with the size of the file.
+
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+
+/// Lines shown from each end of a file in its summary
+pub const SUMMARY_LINES: usize = 20;
+/// Characters of a line that are shown; the rest is replaced by a note of how much was cut
+pub const MAX_LINE_CHARS: usize = 1_000;
+/// Most output a single ranged read returns
+pub const MAX_READ_BYTES: usize = 400 * 1024;
+/// How far back from the end of a file its last lines are looked for
+const TAIL_WINDOW_BYTES: u64 = 256 * 1024;
+
+/// A line of a file, cut to `MAX_LINE_CHARS`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub number: usize,
+    pub text: String,
+}
+
+fn shorten(bytes: &[u8], cut_bytes: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_end_matches(['\n', '\r']);
+    let mut shown: String = text.chars().take(MAX_LINE_CHARS).collect();
+    let cut = text[shown.len()..].len() + cut_bytes;
+    if cut > 0 {
+        shown.push_str(&format!(" [... {} more bytes]", cut));
+    }
+    shown
+}
+
+/// Read the next line, keeping at most `MAX_LINE_CHARS * 4` bytes of it (enough for that many
+/// characters of UTF-8) and skipping over the rest. Returns the kept bytes and how many were
+/// skipped, or `None` at the end of the input.
+fn next_line(reader: &mut impl BufRead) -> io::Result<Option<(Vec<u8>, usize)>> {
+    let keep = MAX_LINE_CHARS * 4;
+    let mut kept = Vec::new();
+    let mut skipped = 0;
+    let mut read_any = false;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(read_any.then_some((kept, skipped)));
+        }
+        read_any = true;
+        let (line, consumed, done) = match buffer.iter().position(|&b| b == b'\n') {
+            Some(end) => (&buffer[..end], end + 1, true),
+            None => (buffer, buffer.len(), false),
+        };
+        let room = keep.saturating_sub(kept.len()).min(line.len());
+        kept.extend_from_slice(&line[..room]);
+        skipped += line.len() - room;
+        reader.consume(consumed);
+        if done {
+            return Ok(Some((kept, skipped)));
+        }
+    }
+}
+
+/// Number of lines in `reader`, counted without holding more than a buffer of it
+pub fn count_lines(reader: impl Read) -> io::Result<usize> {
+    let mut reader = BufReader::new(reader);
+    let mut count = 0;
+    let mut ends_with_newline = true;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            break;
+        }
+        count += buffer.iter().filter(|&&b| b == b'\n').count();
+        ends_with_newline = buffer.last() == Some(&b'\n');
+        let consumed = buffer.len();
+        reader.consume(consumed);
+    }
+    Ok(if ends_with_newline { count } else { count + 1 })
+}
+
+/// The lines `start..=end` (1-indexed; `None` reads to the end), stopping early once they
+/// add up to `MAX_READ_BYTES`. Returns the lines and whether they were stopped early.
+pub fn read_lines(
+    reader: impl Read,
+    start: usize,
+    end: Option<usize>,
+) -> io::Result<(Vec<Line>, bool)> {
+    let mut reader = BufReader::new(reader);
+    let mut lines = Vec::new();
+    let mut size = 0;
+    let mut number = 0;
+    while let Some((bytes, skipped)) = next_line(&mut reader)? {
+        number += 1;
+        if number < start.max(1) {
+            continue;
+        }
+        if end.is_some_and(|end| number > end) {
+            break;
+        }
+        let text = shorten(&bytes, skipped);
+        size += text.len() + 1;
+        if size > MAX_READ_BYTES && !lines.is_empty() {
+            return Ok((lines, true));
+        }
+        lines.push(Line { number, text });
+    }
+    Ok((lines, false))
+}
+
+/// Up to `SUMMARY_LINES` lines from the end of a file of `total_lines` lines
+pub fn tail_lines(mut file: impl Read + Seek, total_lines: usize) -> io::Result<Vec<Line>> {
+    let size = file.seek(SeekFrom::End(0))?;
+    let offset = size.saturating_sub(TAIL_WINDOW_BYTES);
+    file.seek(SeekFrom::Start(offset))?;
+    let mut window = Vec::new();
+    file.take(TAIL_WINDOW_BYTES).read_to_end(&mut window)?;
+
+    let mut pieces: Vec<&[u8]> = window.split(|&b| b == b'\n').collect();
+    if window.last() == Some(&b'\n') {
+        pieces.pop();
+    }
+    // The window most likely starts in the middle of a line
+    if offset > 0 && pieces.len() > 1 {
+        pieces.remove(0);
+    }
+    let shown = pieces.len().min(SUMMARY_LINES);
+    let first_number = total_lines + 1 - shown;
+    Ok(pieces[pieces.len() - shown..]
+        .iter()
+        .enumerate()
+        .map(|(i, piece)| {
+            let keep = piece.len().min(MAX_LINE_CHARS * 4);
+            Line {
+                number: first_number + i,
+                text: shorten(&piece[..keep], piece.len() - keep),
+            }
+        })
+        .collect())
+}
+
+/// Up to `MAX_READ_BYTES` bytes from `offset`, as text
+pub fn read_bytes(mut file: impl Read + Seek, offset: u64, length: usize) -> io::Result<String> {
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::new();
+    file.take(length.min(MAX_READ_BYTES) as u64)
+        .read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+pub fn format_lines(lines: &[Line]) -> String {
+    lines
+        .iter()
+        .map(|line| format!("{}: {}", line.number, line.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn numbered(count: usize) -> String {
+        (1..=count).map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn test_count_lines() {
+        assert_eq!(count_lines(Cursor::new(numbered(5))).unwrap(), 5);
+        assert_eq!(count_lines(Cursor::new("a\nb")).unwrap(), 2);
+        assert_eq!(count_lines(Cursor::new("")).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_lines_in_range() {
+        let (lines, stopped) = read_lines(Cursor::new(numbered(100)), 10, Some(12)).unwrap();
+        assert!(!stopped);
+        assert_eq!(
+            lines,
+            vec![
+                Line {
+                    number: 10,
+                    text: "line 10".to_string()
+                },
+                Line {
+                    number: 11,
+                    text: "line 11".to_string()
+                },
+                Line {
+                    number: 12,
+                    text: "line 12".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_lines_are_cut() {
+        let content = format!("{}\nshort\n", "x".repeat(10_000));
+        let (lines, _) = read_lines(Cursor::new(content), 1, None).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].text.starts_with(&"x".repeat(MAX_LINE_CHARS)));
+        assert!(lines[0].text.ends_with("[... 9000 more bytes]"));
+        assert_eq!(lines[1].text, "short");
+    }
+
+    #[test]
+    fn test_reads_stop_at_the_output_limit() {
+        let line = "y".repeat(MAX_LINE_CHARS);
+        let content = format!("{}\n", line).repeat(1_000);
+        let (lines, stopped) = read_lines(Cursor::new(content), 1, None).unwrap();
+        assert!(stopped);
+        assert!(lines.len() < 1_000);
+    }
+
+    #[test]
+    fn test_tail_lines_are_numbered_from_the_end() {
+        let content = numbered(1_000);
+        let lines = tail_lines(Cursor::new(content.as_bytes()), 1_000).unwrap();
+        assert_eq!(lines.len(), SUMMARY_LINES);
+        assert_eq!(lines[0].number, 1_000 - SUMMARY_LINES + 1);
+        assert_eq!(lines.last().unwrap().text, "line 1000");
+        assert_eq!(
+            lines.last().unwrap().number,
+            1_000,
+            "numbers line up with the content"
+        );
+    }
+
+    #[test]
+    fn test_read_bytes_from_offset() {
+        let text = read_bytes(Cursor::new("0123456789"), 3, 4).unwrap();
+        assert_eq!(text, "3456");
+    }
+}
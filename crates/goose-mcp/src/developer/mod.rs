@@ -1,7 +1,14 @@
+mod background;
 mod editor_models;
 
 mod lang;
+mod large_file;
+mod ocr;
 mod shell;
+mod terminal;
+mod test_runner;
+mod watch;
+mod workspace;
 
 use anyhow::Result;
 use base64::Engine;
@@ -30,7 +37,7 @@ use mcp_core::{
     handler::{require_str_parameter, PromptError, ResourceError},
     protocol::ServerCapabilities,
 };
-use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::router::{request_session_id, CapabilitiesBuilder};
 use mcp_server::Router;
 use once_cell::sync::Lazy;
 
@@ -40,11 +47,19 @@ use rmcp::model::{
 };
 use rmcp::object;
 
+use self::background::BackgroundProcesses;
 use self::editor_models::{create_editor_model, EditorModel};
-use self::shell::{expand_path, get_shell_config, is_absolute_path, normalize_line_endings};
+use self::shell::{
+    expand_path, get_shell_config, is_absolute_path, normalize_line_endings, ShellKind,
+};
+use self::terminal::TerminalSessions;
+use self::test_runner::TestFramework;
+use self::watch::FileWatches;
+use self::workspace::PackageScope;
 use indoc::indoc;
 use std::process::Stdio;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use xcap::{Monitor, Window};
 
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
@@ -287,6 +302,30 @@ fn read_referenced_files(
     result
 }
 
+/// Longest a terminal or background process tool call waits for output
+const MAX_TERMINAL_WAIT_SECONDS: u64 = 60;
+/// Longest a file watch tool call waits for changes
+const MAX_WATCH_TIMEOUT_SECONDS: u64 = 120;
+
+/// Command output streamed to the client while the command runs
+fn shell_output_notification(stream: &str, output: &str) -> JsonRpcMessage {
+    JsonRpcMessage::Notification(JsonRpcNotification {
+        jsonrpc: JsonRpcVersion2_0,
+        notification: Notification {
+            method: "notifications/message".to_string(),
+            params: object!({
+                "level": "info",
+                "data": {
+                    "type": "shell",
+                    "stream": stream,
+                    "output": output,
+                }
+            }),
+            extensions: Default::default(),
+        },
+    })
+}
+
 pub struct DeveloperRouter {
     tools: Vec<Tool>,
     prompts: Arc<HashMap<String, Prompt>>,
@@ -294,6 +333,10 @@ pub struct DeveloperRouter {
     file_history: Arc<Mutex<HashMap<PathBuf, Vec<String>>>>,
     ignore_patterns: Arc<Gitignore>,
     editor_model: Option<EditorModel>,
+    terminals: Arc<TerminalSessions>,
+    background_processes: Arc<BackgroundProcesses>,
+    file_watches: Arc<FileWatches>,
+    package_scope: Option<PackageScope>,
 }
 
 impl Default for DeveloperRouter {
@@ -314,10 +357,35 @@ impl DeveloperRouter {
         // a load off the main LLM making the tool calls and you get faster more correct applies
         let editor_model = create_editor_model();
 
-        // Get OS-specific shell tool description
-        let shell_tool_desc = match std::env::consts::OS {
-            "windows" => indoc! {r#"
-                Execute a command in the shell.
+        // Get a shell tool description for the shell commands run in
+        let shell_tool_desc = match get_shell_config().kind {
+            ShellKind::PowerShell => indoc! {r#"
+                Execute a command in PowerShell.
+
+                This will return the output and error concatenated into a single string, as
+                you would see from running on the command line. There will also be an indication
+                of if the command succeeded or failed.
+
+                Avoid commands that produce a large amount of output, and consider piping those outputs to files.
+
+                **Important**: For searching files and code:
+
+                Preferred: Use ripgrep (`rg`) when available - it respects .gitignore and is fast:
+                  - To locate a file by name: `rg --files | rg example.py`
+                  - To locate content inside files: `rg 'class Example'`
+
+                Alternative PowerShell commands (if ripgrep is not installed):
+                  - To locate a file by name: `Get-ChildItem -Recurse -Filter example.py`
+                  - To locate content inside files: `Get-ChildItem -Recurse -Filter *.py | Select-String 'class Example'`
+
+                Note: Alternative commands may show ignored/hidden files that should be excluded.
+
+                **Important**: Each shell command runs in its own process, so directory changes do not persist.
+                  - Multiple commands: Use ; to chain commands, avoid newlines
+                  - Pathnames: Use absolute paths and quote paths that contain spaces
+            "#},
+            ShellKind::Cmd => indoc! {r#"
+                Execute a command in the Windows command prompt (cmd.exe).
 
                 This will return the output and error concatenated into a single string, as
                 you would see from running on the command line. There will also be an indication
@@ -336,8 +404,12 @@ impl DeveloperRouter {
                   - To locate content inside files: `findstr /s /i "class Example" *.py`
 
                 Note: Alternative commands may show ignored/hidden files that should be excluded.
+
+                **Important**: Each shell command runs in its own process, so directory changes do not persist.
+                  - Multiple commands: Use && to chain commands, avoid newlines
+                  - Pathnames: Use absolute paths and double quote paths that contain spaces
             "#},
-            _ => indoc! {r#"
+            ShellKind::Posix => indoc! {r#"
                 Execute a command in the shell.
 
                 This will return the output and error concatenated into a single string, as
@@ -373,6 +445,148 @@ impl DeveloperRouter {
             }),
         );
 
+        let terminal_tool = Tool::new(
+            "terminal".to_string(),
+            indoc! {r#"
+                Run an interactive command in a terminal session that stays open across tool calls.
+
+                Use this instead of the shell tool for commands that need a terminal or ask for input,
+                such as interactive installers, REPLs and ssh. Prefer the shell tool for everything else.
+
+                The `action` parameter specifies the operation to perform. Allowed options are:
+                - `start`: Run `command` in a new session. Returns its `session_id` and first output.
+                - `send`: Type `input` into the session and return the output that follows. A newline
+                  presses Enter and control characters are sent as typed, e.g. "\u0003" for Ctrl-C.
+                - `read`: Return output the session printed since it was last read.
+                - `stop`: End the session.
+
+                Each call waits up to `wait_seconds` (default 2) for the output to settle. Output is plain
+                text with terminal colors removed. Sessions end when their command exits; stop the ones
+                you no longer need.
+            "#}
+            .to_string(),
+            object!({
+                "type": "object",
+                "required": ["action"],
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["start", "send", "read", "stop"]
+                    },
+                    "command": {"type": "string", "description": "Command to run, for start"},
+                    "session_id": {"type": "string", "description": "Session to use, for send, read and stop"},
+                    "input": {"type": "string", "description": "Text to type, for send"},
+                    "wait_seconds": {"type": "integer", "minimum": 0, "maximum": MAX_TERMINAL_WAIT_SECONDS}
+                }
+            }),
+        );
+
+        let background_process_tool = Tool::new(
+            "background_process".to_string(),
+            indoc! {r#"
+                Run long-lived commands in the background, such as dev servers and file watchers, and check
+                on them later in the session.
+
+                The `action` parameter specifies the operation to perform. Allowed options are:
+                - `start`: Start `command` and return its `process_id` right away.
+                - `output`: Return what the process printed since its output was last read, waiting up to
+                  `wait_seconds` (default 0) unless it exits first. Useful to wait for a server to be ready.
+                - `stop`: Stop the process and everything it started.
+                - `list`: List the processes started in this session and whether they still run.
+
+                Use the shell tool for commands that finish on their own. Background processes are stopped
+                when the session ends; stop them earlier once you no longer need them.
+            "#}
+            .to_string(),
+            object!({
+                "type": "object",
+                "required": ["action"],
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["start", "output", "stop", "list"]
+                    },
+                    "command": {"type": "string", "description": "Command to run, for start"},
+                    "process_id": {"type": "string", "description": "Process to use, for output and stop"},
+                    "wait_seconds": {"type": "integer", "minimum": 0, "maximum": MAX_TERMINAL_WAIT_SECONDS}
+                }
+            }),
+        );
+
+        let watch_files_tool = Tool::new(
+            "watch_files".to_string(),
+            indoc! {r#"
+                Watch files for changes and wait for them, e.g. for a build artifact to appear or a
+                generated file to be rewritten.
+
+                The `action` parameter specifies the operation to perform. Allowed options are:
+                - `watch`: Watch `path` (absolute, a file or a directory and everything below it) and return
+                  a `watch_id`. With `pattern`, only changes to paths matching that glob relative to `path`
+                  are reported, e.g. `dist/*.js`.
+                - `wait`: Return the changes seen since the last call, waiting up to `timeout_seconds`
+                  (default 30) for one when there are none yet. Changes that happened between turns are
+                  reported too.
+                - `stop`: Stop watching.
+
+                Files ignored by .gooseignore are never reported.
+            "#}
+            .to_string(),
+            object!({
+                "type": "object",
+                "required": ["action"],
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["watch", "wait", "stop"]
+                    },
+                    "path": {"type": "string", "description": "Absolute path to watch, for watch"},
+                    "pattern": {"type": "string", "description": "Glob the changed paths must match, for watch"},
+                    "watch_id": {"type": "string", "description": "Watch to use, for wait and stop"},
+                    "timeout_seconds": {"type": "integer", "minimum": 0, "maximum": MAX_WATCH_TIMEOUT_SECONDS}
+                }
+            }),
+        );
+
+        let run_tests_tool = Tool::new(
+            "run_tests".to_string(),
+            indoc! {r#"
+                Run a project's tests and get the results as JSON: how many passed, failed and were
+                skipped, each failing test with the message it failed with, or the build error when the
+                tests could not run at all.
+
+                Supports cargo, jest, vitest, pytest and go test, picked from the project's manifest.
+                Prefer this over running the tests with the shell tool. Use `filter` to rerun only the
+                tests you are fixing, then run everything once they pass. A run that takes longer than
+                270 seconds is stopped; run large suites with the background_process tool instead.
+            "#}
+            .to_string(),
+            object!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path of the project or a directory in it (default the current directory)"
+                    },
+                    "framework": {
+                        "type": "string",
+                        "enum": TestFramework::NAMES,
+                        "description": "Test runner to use when it isn't found from the project's manifest"
+                    },
+                    "filter": {
+                        "type": "string",
+                        "description": "Only run tests whose names match this, as the runner understands it"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Run tests".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
         // Create text editor tool with different descriptions based on editor API configuration
         let (text_editor_desc, str_replace_command) = if let Some(ref editor) = editor_model {
             (
@@ -380,7 +594,8 @@ impl DeveloperRouter {
                 Perform text editing operations on files.
 
                 The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
+                - `view`: View the content of a file. Files over 400KB are summarized by their first and last lines;
+                  view parts of them with `view_range`, or `byte_range` when their lines are very long.
                 - `write`: Create or overwrite a file with the given content
                 - `edit_file`: Edit the file with the new content.
                 - `insert`: Insert text at a specific line location in the file.
@@ -403,7 +618,8 @@ impl DeveloperRouter {
                 Perform text editing operations on files.
 
                 The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
+                - `view`: View the content of a file. Files over 400KB are summarized by their first and last lines;
+                  view parts of them with `view_range`, or `byte_range` when their lines are very long.
                 - `write`: Create or overwrite a file with the given content
                 - `str_replace`: Replace a string in a file with a new string.
                 - `insert`: Insert text at a specific line location in the file.
@@ -444,6 +660,13 @@ impl DeveloperRouter {
                         "maxItems": 2,
                         "description": "Optional array of two integers specifying the start and end line numbers to view. Line numbers are 1-indexed, and -1 for the end line means read to the end of the file. This parameter only applies when viewing files, not directories."
                     },
+                    "byte_range": {
+                        "type": "array",
+                        "items": {"type": "integer"},
+                        "minItems": 2,
+                        "maxItems": 2,
+                        "description": "Optional array of a byte offset and a length to view instead of lines, at most 400KB. Useful for files with very long lines."
+                    },
                     "insert_line": {
                         "type": "integer",
                         "description": "The line number after which to insert the text (0 for beginning of file, -1 for end of file). This parameter is required when using the insert command."
@@ -485,6 +708,7 @@ impl DeveloperRouter {
                 2. A specific window by its title using the window_title parameter
 
                 Only one of display or window_title should be specified.
+                When local OCR is preferred, the text on screen is returned instead of the image.
             "#},
             object!({
                 "type": "object",
@@ -539,9 +763,43 @@ impl DeveloperRouter {
             open_world_hint: Some(false),
         });
 
+        let ocr_tool = Tool::new(
+            "ocr",
+            indoc! {r#"
+                Extract the text from an image file, such as a screenshot or a scanned document,
+                using OCR on this machine. The image is never sent anywhere, which makes this the
+                private choice for images holding sensitive text, and it is cheaper than looking
+                at the image when only its text matters.
+
+                Requires tesseract to be installed.
+            "#},
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path to the image file"
+                    },
+                    "language": {
+                        "type": "string",
+                        "description": "Tesseract language code(s) of the text, e.g. 'eng', 'deu' or 'eng+fra' (default 'eng')"
+                    }
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Extract text from an image".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
         // Get base instructions and working directory
         let cwd = std::env::current_dir().expect("should have a current working dir");
         let os = std::env::consts::OS;
+        let package_scope = PackageScope::from_env(&cwd);
 
         let base_instructions = match os {
             "windows" => formatdoc! {r#"
@@ -709,25 +967,37 @@ impl DeveloperRouter {
         }
 
         // Return base instructions directly when no hints are found
-        let instructions = if hints.is_empty() {
+        let mut instructions = if hints.is_empty() {
             base_instructions
         } else {
             format!("{base_instructions}\n{hints}")
         };
+        if let Some(scope) = &package_scope {
+            instructions.push_str(&format!("\n### Package Scope\n{}\n", scope.instructions()));
+        }
 
         Self {
             tools: vec![
                 bash_tool,
+                terminal_tool,
+                background_process_tool,
+                watch_files_tool,
+                run_tests_tool,
                 text_editor_tool,
                 list_windows_tool,
                 screen_capture_tool,
                 image_processor_tool,
+                ocr_tool,
             ],
             prompts: Arc::new(load_prompt_files()),
             instructions,
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model,
+            terminals: Arc::new(TerminalSessions::default()),
+            background_processes: Arc::new(BackgroundProcesses::default()),
+            file_watches: Arc::new(FileWatches::default()),
+            package_scope,
         }
     }
 
@@ -736,6 +1006,35 @@ impl DeveloperRouter {
         self.ignore_patterns.matched(path, false).is_ignore()
     }
 
+    // Refuse a command that names a file restricted by .gooseignore
+    fn check_command_paths(&self, command: &str) -> Result<(), ErrorData> {
+        for arg in command.split_whitespace().skip(1) {
+            // Skip command flags
+            if arg.starts_with('-') {
+                continue;
+            }
+            // Paths may be quoted, e.g. "C:\Users\goose\notes.txt"
+            let arg = arg.trim_matches(|c| c == '"' || c == '\'');
+            // Skip invalid paths
+            let path = Path::new(arg);
+            if !path.exists() {
+                continue;
+            }
+
+            if self.is_ignored(path) {
+                return Err(ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!(
+                        "The command attempts to access '{}' which is restricted by .gooseignore",
+                        arg
+                    ),
+                    None,
+                ));
+            }
+        }
+        Ok(())
+    }
+
     // shell output can be large, this will help manage that
     fn process_shell_output(&self, output_str: &str) -> Result<(String, String), ErrorData> {
         let lines: Vec<&str> = output_str.lines().collect();
@@ -827,43 +1126,21 @@ impl DeveloperRouter {
                 )
             })?;
 
-        // Check if command might access ignored files and return early if it does
-        let cmd_parts: Vec<&str> = command.split_whitespace().collect();
-        for arg in &cmd_parts[1..] {
-            // Skip command flags
-            if arg.starts_with('-') {
-                continue;
-            }
-            // Skip invalid paths
-            let path = Path::new(arg);
-            if !path.exists() {
-                continue;
-            }
-
-            if self.is_ignored(path) {
-                return Err(ErrorData::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!(
-                        "The command attempts to access '{}' which is restricted by .gooseignore",
-                        arg
-                    ),
-                    None,
-                ));
-            }
-        }
+        self.check_command_paths(command)?;
 
         // Get platform-specific shell configuration
         let shell_config = get_shell_config();
 
         // Execute the command using platform-specific shell
-        let mut child = Command::new(&shell_config.executable)
+        let mut process = Command::new(&shell_config.executable);
+        process
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .stdin(Stdio::null())
             .kill_on_drop(true)
-            .env("GOOSE_TERMINAL", "1")
-            .args(&shell_config.args)
-            .arg(command)
+            .env("GOOSE_TERMINAL", "1");
+        shell_config.apply(&mut process, command);
+        let mut child = process
             .spawn()
             .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
 
@@ -889,21 +1166,7 @@ impl DeveloperRouter {
                 combined_output.push_str(&line);
 
                 notifier
-                    .try_send(JsonRpcMessage::Notification(JsonRpcNotification {
-                        jsonrpc: JsonRpcVersion2_0,
-                        notification: Notification {
-                            method: "notifications/message".to_string(),
-                            params: object!({
-                                "level": "info",
-                                "data": {
-                                    "type": "shell",
-                                    "stream": key,
-                                    "output": line,
-                                }
-                            }),
-                            extensions: Default::default(),
-                        },
-                    }))
+                    .try_send(shell_output_notification(key, &line))
                     .ok();
             }
             Ok::<_, std::io::Error>(combined_output)
@@ -949,6 +1212,294 @@ impl DeveloperRouter {
         ])
     }
 
+    // Interactive commands in terminal sessions that persist across tool calls
+    async fn terminal(
+        &self,
+        params: Value,
+        notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Result<Vec<Content>, ErrorData> {
+        let action = require_str_parameter(&params, "action")?;
+        let wait = Duration::from_secs(
+            params
+                .get("wait_seconds")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(2)
+                .min(MAX_TERMINAL_WAIT_SECONDS),
+        );
+        let on_output = |chunk: &str| {
+            notifier
+                .try_send(shell_output_notification("stdout", chunk))
+                .ok();
+        };
+
+        let (session_id, output) = match action {
+            "start" => {
+                let command = require_str_parameter(&params, "command")?;
+                self.check_command_paths(command)?;
+                let cwd = std::env::current_dir().expect("should have a current working dir");
+                let session_id = self.terminals.start(command, &cwd)?;
+                let output = self.terminals.read(&session_id, wait, on_output).await?;
+                (session_id, output)
+            }
+            "send" => {
+                let session_id = require_str_parameter(&params, "session_id")?;
+                let input = require_str_parameter(&params, "input")?;
+                // What is typed into a shell is a command like any other
+                for line in input.lines() {
+                    self.check_command_paths(&format!("input {}", line))?;
+                }
+                self.terminals.send(session_id, input)?;
+                let output = self.terminals.read(session_id, wait, on_output).await?;
+                (session_id.to_string(), output)
+            }
+            "read" => {
+                let session_id = require_str_parameter(&params, "session_id")?;
+                let output = self.terminals.read(session_id, wait, on_output).await?;
+                (session_id.to_string(), output)
+            }
+            "stop" => {
+                let session_id = require_str_parameter(&params, "session_id")?;
+                (session_id.to_string(), self.terminals.stop(session_id)?)
+            }
+            _ => {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Unknown terminal action '{}'", action),
+                    None,
+                ))
+            }
+        };
+
+        let status = match (action, output.exit_code) {
+            ("stop", _) => format!("Session {} was stopped.", session_id),
+            (_, Some(code)) => format!("Session {} exited with code {}.", session_id, code),
+            (_, None) => format!(
+                "Session {} is still running; use `send` to type input or `read` for more output.",
+                session_id
+            ),
+        };
+        let (final_output, user_output) = if output.text.is_empty() {
+            ("(no new output)".to_string(), String::new())
+        } else {
+            self.process_shell_output(&output.text)?
+        };
+
+        Ok(vec![
+            Content::text(format!("{}\n\n{}", status, final_output))
+                .with_audience(vec![Role::Assistant]),
+            Content::text(user_output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    // Long-running commands that keep running between tool calls
+    async fn background_process(
+        &self,
+        params: Value,
+        session: &str,
+    ) -> Result<Vec<Content>, ErrorData> {
+        let action = require_str_parameter(&params, "action")?;
+        let processes = &self.background_processes;
+
+        let (process_id, output) = match action {
+            "start" => {
+                let command = require_str_parameter(&params, "command")?;
+                self.check_command_paths(command)?;
+                let cwd = std::env::current_dir().expect("should have a current working dir");
+                let (process_id, pid) = processes.start(command, &cwd, session)?;
+                let pid = pid.map(|pid| format!(" (pid {})", pid)).unwrap_or_default();
+                return Ok(vec![Content::text(format!(
+                    "Started {}{}. Use the `output` action to check on it.",
+                    process_id, pid
+                ))]);
+            }
+            "list" => {
+                let summaries = processes.list(session);
+                if summaries.is_empty() {
+                    return Ok(vec![Content::text("No background processes")]);
+                }
+                let lines: Vec<String> = summaries
+                    .iter()
+                    .map(|summary| {
+                        let state = match summary.exit_code {
+                            None => format!("running for {}s", summary.running_for.as_secs()),
+                            Some(Some(code)) => format!("exited with code {}", code),
+                            Some(None) => "ended by a signal".to_string(),
+                        };
+                        format!("{}: {} ({})", summary.id, summary.command, state)
+                    })
+                    .collect();
+                return Ok(vec![Content::text(lines.join("\n"))]);
+            }
+            "output" => {
+                let process_id = require_str_parameter(&params, "process_id")?;
+                let wait = Duration::from_secs(
+                    params
+                        .get("wait_seconds")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0)
+                        .min(MAX_TERMINAL_WAIT_SECONDS),
+                );
+                (
+                    process_id,
+                    processes.output(process_id, session, wait).await?,
+                )
+            }
+            "stop" => {
+                let process_id = require_str_parameter(&params, "process_id")?;
+                (process_id, processes.stop(process_id, session).await?)
+            }
+            _ => {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!("Unknown background process action '{}'", action),
+                    None,
+                ))
+            }
+        };
+
+        let status = match (action, output.exit_code) {
+            ("stop", _) => format!("{} was stopped.", process_id),
+            (_, Some(Some(code))) => format!("{} exited with code {}.", process_id, code),
+            (_, Some(None)) => format!("{} was ended by a signal.", process_id),
+            (_, None) => format!("{} is still running.", process_id),
+        };
+        let mut text = output.lines.join("\n");
+        if output.dropped > 0 {
+            text = format!("({} earlier lines were dropped)\n{}", output.dropped, text);
+        }
+        let (final_output, user_output) = if text.is_empty() {
+            ("(no new output)".to_string(), String::new())
+        } else {
+            self.process_shell_output(&text)?
+        };
+
+        Ok(vec![
+            Content::text(format!("{}\n\n{}", status, final_output))
+                .with_audience(vec![Role::Assistant]),
+            Content::text(user_output)
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    // Tests run with their language's runner, reported as structured results
+    async fn run_tests(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let dir = match params.get("path").and_then(|v| v.as_str()) {
+            Some(path) => self.resolve_path(path)?,
+            None => match &self.package_scope {
+                Some(scope) => scope.package_root.clone(),
+                None => std::env::current_dir().expect("should have a current working dir"),
+            },
+        };
+        let framework = match params.get("framework").and_then(|v| v.as_str()) {
+            Some(name) => Some(TestFramework::from_name(name).ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!(
+                        "Unknown framework '{}', expected one of: {}",
+                        name,
+                        TestFramework::NAMES.join(", ")
+                    ),
+                    None,
+                )
+            })?),
+            None => None,
+        };
+        let filter = params.get("filter").and_then(|v| v.as_str());
+
+        let report = test_runner::run_tests(&dir, framework, filter).await?;
+        let json = serde_json::to_string_pretty(&report).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to serialize the test results: {}", e),
+                None,
+            )
+        })?;
+        Ok(vec![
+            Content::text(json).with_audience(vec![Role::Assistant]),
+            Content::text(format!("{}: {}", report.command, report.summary()))
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
+        ])
+    }
+
+    // File change subscriptions that collect changes across turns
+    async fn watch_files(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let action = require_str_parameter(&params, "action")?;
+        match action {
+            "watch" => {
+                let path = self.resolve_path(require_str_parameter(&params, "path")?)?;
+                if !path.exists() {
+                    return Err(ErrorData::new(
+                        ErrorCode::INVALID_PARAMS,
+                        format!("The path '{}' does not exist", path.display()),
+                        None,
+                    ));
+                }
+                if self.is_ignored(&path) {
+                    return Err(ErrorData::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!(
+                            "Access to '{}' is restricted by .gooseignore",
+                            path.display()
+                        ),
+                        None,
+                    ));
+                }
+                let pattern = params.get("pattern").and_then(|v| v.as_str());
+                let watch_id =
+                    self.file_watches
+                        .watch(&path, pattern, Arc::clone(&self.ignore_patterns))?;
+                Ok(vec![Content::text(format!(
+                    "Watching {} as {}. Use the `wait` action to get its changes.",
+                    path.display(),
+                    watch_id
+                ))])
+            }
+            "wait" => {
+                let watch_id = require_str_parameter(&params, "watch_id")?;
+                let timeout = Duration::from_secs(
+                    params
+                        .get("timeout_seconds")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(30)
+                        .min(MAX_WATCH_TIMEOUT_SECONDS),
+                );
+                let watched = self.file_watches.wait(watch_id, timeout).await?;
+                if watched.changes.is_empty() {
+                    return Ok(vec![Content::text(format!(
+                        "No changes within {} seconds.",
+                        timeout.as_secs()
+                    ))]);
+                }
+                let mut lines: Vec<String> = watched
+                    .changes
+                    .iter()
+                    .map(|change| format!("{}: {}", change.kind, change.path.display()))
+                    .collect();
+                if watched.dropped > 0 {
+                    lines.insert(
+                        0,
+                        format!("({} earlier changes were dropped)", watched.dropped),
+                    );
+                }
+                Ok(vec![Content::text(lines.join("\n"))])
+            }
+            "stop" => {
+                let watch_id = require_str_parameter(&params, "watch_id")?;
+                self.file_watches.stop(watch_id)?;
+                Ok(vec![Content::text(format!("Stopped {}", watch_id))])
+            }
+            _ => Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Unknown watch action '{}'", action),
+                None,
+            )),
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     async fn text_editor(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
         let command = params
@@ -984,6 +1535,23 @@ impl DeveloperRouter {
             ));
         }
 
+        // Edits stay inside the package the session is scoped to, which only the user can widen
+        if let Some(scope) = &self.package_scope {
+            if command != "view" && !scope.contains(&path) {
+                return Err(ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    format!(
+                        "'{}' is outside the `{}` package this session works in ({}). If the task \
+                         needs changes there, stop and ask the user to widen the package scope.",
+                        path.display(),
+                        scope.package_name(),
+                        scope.package_root.display()
+                    ),
+                    None,
+                ));
+            }
+        }
+
         match command {
             "view" => {
                 let view_range = params
@@ -998,7 +1566,14 @@ impl DeveloperRouter {
                             None
                         }
                     });
-                self.text_editor_view(&path, view_range).await
+                let byte_range = params
+                    .get("byte_range")
+                    .and_then(|v| v.as_array())
+                    .and_then(|arr| match arr.as_slice() {
+                        [offset, length] => Some((offset.as_u64()?, length.as_u64()? as usize)),
+                        _ => None,
+                    });
+                self.text_editor_view(&path, view_range, byte_range).await
             }
             "write" => {
                 let file_text = require_str_parameter(&params, "file_text")?;
@@ -1158,6 +1733,7 @@ impl DeveloperRouter {
         &self,
         path: &PathBuf,
         view_range: Option<(usize, i64)>,
+        byte_range: Option<(u64, usize)>,
     ) -> Result<Vec<Content>, ErrorData> {
         if !path.is_file() {
             return Err(ErrorData::new(
@@ -1191,16 +1767,12 @@ impl DeveloperRouter {
             })?
             .len();
 
+        if let Some((offset, length)) = byte_range {
+            return self.text_editor_view_bytes(path, file_size, offset, length);
+        }
         if file_size > MAX_FILE_SIZE {
-            return Err(ErrorData::new(
-                ErrorCode::INTERNAL_ERROR,
-                format!(
-                "File '{}' is too large ({:.2}KB). Maximum size is 400KB to prevent memory issues.",
-                path.display(),
-                file_size as f64 / 1024.0
-            ),
-                None,
-            ));
+            // Streamed in parts rather than read whole, to not run out of memory or context
+            return self.text_editor_view_large(path, file_size, view_range);
         }
 
         // Ensure we never read over that limit even if the file is being concurrently mutated
@@ -1247,6 +1819,139 @@ impl DeveloperRouter {
         ])
     }
 
+    fn text_editor_view_large(
+        &self,
+        path: &Path,
+        file_size: u64,
+        view_range: Option<(usize, i64)>,
+    ) -> Result<Vec<Content>, ErrorData> {
+        let read_error = |e: std::io::Error| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to read file: {}", e),
+                None,
+            )
+        };
+        let open = || File::open(path).map_err(read_error);
+        let language = lang::get_language_identifier(path);
+
+        let Some((start, end)) = view_range else {
+            let total_lines = large_file::count_lines(open()?).map_err(read_error)?;
+            let summary = if total_lines <= 2 * large_file::SUMMARY_LINES {
+                let (lines, _) = large_file::read_lines(open()?, 1, None).map_err(read_error)?;
+                large_file::format_lines(&lines)
+            } else {
+                let (head, _) = large_file::read_lines(open()?, 1, Some(large_file::SUMMARY_LINES))
+                    .map_err(read_error)?;
+                let tail = large_file::tail_lines(open()?, total_lines).map_err(read_error)?;
+                format!(
+                    "{}\n...\n{}",
+                    large_file::format_lines(&head),
+                    large_file::format_lines(&tail)
+                )
+            };
+            return Ok(vec![Content::text(formatdoc! {"
+                File '{path}' is too large to view whole ({size:.1}MB, {total_lines} lines). These are its first
+                and last lines; view other parts with view_range, with byte_range if its lines are very long,
+                or search it with rg.
+
+                ### {path} (summary)
+                ```{language}
+                {summary}
+                ```
+                ",
+                path=path.display(),
+                size=file_size as f64 / (1024.0 * 1024.0),
+                total_lines=total_lines,
+                language=language,
+                summary=summary,
+            })]);
+        };
+
+        let end = (end != -1).then_some(end as usize);
+        if end.is_some_and(|end| end < start) {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Start line {} must be less than end line {}",
+                    start,
+                    end.unwrap_or_default()
+                ),
+                None,
+            ));
+        }
+        let (lines, stopped) = large_file::read_lines(open()?, start, end).map_err(read_error)?;
+        let (Some(first), Some(last)) = (lines.first(), lines.last()) else {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!("Start line {} is beyond the end of the file", start),
+                None,
+            ));
+        };
+        let mut text = formatdoc! {"
+            ### {path} (lines {first}-{last})
+            ```{language}
+            {content}
+            ```
+            ",
+            path=path.display(),
+            first=first.number,
+            last=last.number,
+            language=language,
+            content=large_file::format_lines(&lines),
+        };
+        if stopped {
+            text.push_str(&format!(
+                "Stopped at line {} after {}KB of output; continue with view_range [{}, ...].\n",
+                last.number,
+                large_file::MAX_READ_BYTES / 1024,
+                last.number + 1
+            ));
+        }
+        Ok(vec![Content::text(text)])
+    }
+
+    fn text_editor_view_bytes(
+        &self,
+        path: &Path,
+        file_size: u64,
+        offset: u64,
+        length: usize,
+    ) -> Result<Vec<Content>, ErrorData> {
+        if offset >= file_size {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "Offset {} is beyond the end of the file ({} bytes)",
+                    offset, file_size
+                ),
+                None,
+            ));
+        }
+        let text = File::open(path)
+            .and_then(|file| large_file::read_bytes(file, offset, length))
+            .map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to read file: {}", e),
+                    None,
+                )
+            })?;
+        let end = (offset + length.min(large_file::MAX_READ_BYTES) as u64).min(file_size);
+        Ok(vec![Content::text(formatdoc! {"
+            ### {path} (bytes {offset}-{end} of {size})
+            ```
+            {text}
+            ```
+            ",
+            path=path.display(),
+            offset=offset,
+            end=end,
+            size=file_size,
+            text=text,
+        })])
+    }
+
     async fn text_editor_write(
         &self,
         path: &PathBuf,
@@ -1673,7 +2378,9 @@ impl DeveloperRouter {
         path.to_path_buf()
     }
 
-    async fn image_processor(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+    /// Resolve the image file named by the `path` parameter, checking it may be read and is
+    /// not too large
+    fn image_path(&self, params: &Value) -> Result<PathBuf, ErrorData> {
         let path_str = params.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
             ErrorData::new(
                 ErrorCode::INVALID_PARAMS,
@@ -1735,6 +2442,50 @@ impl DeveloperRouter {
                 None,
             ));
         }
+        Ok(path)
+    }
+
+    /// The text in an encoded image, recognized locally, as the result of a tool. `source` says
+    /// where the image came from, e.g. `the screenshot`.
+    async fn local_image_text(
+        image: &[u8],
+        language: Option<&str>,
+        source: &str,
+    ) -> Result<Vec<Content>, ErrorData> {
+        let text = ocr::recognize_text(image, language).await?;
+        Ok(vec![Content::text(if text.is_empty() {
+            format!("No text was found in {}", source)
+        } else {
+            format!("Text recognized locally in {}:\n\n{}", source, text)
+        })])
+    }
+
+    async fn ocr(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let path = self.image_path(&params)?;
+        let image = std::fs::read(&path).map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to read image file: {}", e),
+                None,
+            )
+        })?;
+        let language = params.get("language").and_then(|v| v.as_str());
+        Self::local_image_text(&image, language, &path.display().to_string()).await
+    }
+
+    async fn image_processor(&self, params: Value) -> Result<Vec<Content>, ErrorData> {
+        let path = self.image_path(&params)?;
+
+        if ocr::prefer_local_ocr() {
+            let image = std::fs::read(&path).map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to read image file: {}", e),
+                    None,
+                )
+            })?;
+            return Self::local_image_text(&image, None, "the image").await;
+        }
 
         // Open and decode the image
         let image = xcap::image::open(&path).map_err(|e| {
@@ -1845,6 +2596,21 @@ impl DeveloperRouter {
                 })?
             };
 
+        // Recognized at full size, where small text is still legible
+        if ocr::prefer_local_ocr() {
+            let mut bytes: Vec<u8> = Vec::new();
+            image
+                .write_to(&mut Cursor::new(&mut bytes), xcap::image::ImageFormat::Png)
+                .map_err(|e| {
+                    ErrorData::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to write image buffer {}", e),
+                        None,
+                    )
+                })?;
+            return Self::local_image_text(&bytes, None, "the screenshot").await;
+        }
+
         // Resize the image to a reasonable width while maintaining aspect ratio
         let max_width = 768;
         if image.width() > max_width {
@@ -1917,12 +2683,19 @@ impl Router for DeveloperRouter {
         let this = self.clone();
         let tool_name = tool_name.to_string();
         Box::pin(async move {
+            // Calls from clients that don't name a session share one
+            let session = request_session_id().unwrap_or_default();
             match tool_name.as_str() {
                 "shell" => this.bash(arguments, notifier).await,
+                "terminal" => this.terminal(arguments, notifier).await,
+                "background_process" => this.background_process(arguments, &session).await,
+                "watch_files" => this.watch_files(arguments).await,
+                "run_tests" => this.run_tests(arguments).await,
                 "text_editor" => this.text_editor(arguments).await,
                 "list_windows" => this.list_windows(arguments).await,
                 "screen_capture" => this.screen_capture(arguments).await,
                 "image_processor" => this.image_processor(arguments).await,
+                "ocr" => this.ocr(arguments).await,
                 _ => Err(ErrorData::new(
                     ErrorCode::METHOD_NOT_FOUND,
                     format!("Tool {} not found", tool_name),
@@ -1974,6 +2747,24 @@ impl Router for DeveloperRouter {
             }
         })
     }
+
+    fn session_ended(
+        &self,
+        session_id: &str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        let processes = Arc::clone(&self.background_processes);
+        let session_id = session_id.to_string();
+        Box::pin(async move {
+            let stopped = processes.end_session(&session_id).await;
+            if stopped > 0 {
+                tracing::info!(
+                    "Stopped {} background processes of session {}",
+                    stopped,
+                    session_id
+                );
+            }
+        })
+    }
 }
 
 impl Clone for DeveloperRouter {
@@ -1985,6 +2776,10 @@ impl Clone for DeveloperRouter {
             file_history: Arc::clone(&self.file_history),
             ignore_patterns: Arc::clone(&self.ignore_patterns),
             editor_model: create_editor_model(),
+            terminals: Arc::clone(&self.terminals),
+            background_processes: Arc::clone(&self.background_processes),
+            file_watches: Arc::clone(&self.file_watches),
+            package_scope: self.package_scope.clone(),
         }
     }
 }
@@ -2156,13 +2951,13 @@ mod tests {
         // Get router after setting current directory
         let router = get_router().await;
 
-        // Test file size limit
+        // Files above MAX_FILE_SIZE are summarized instead of read whole
         {
             let large_file_path = temp_dir.path().join("large.txt");
             let large_file_str = large_file_path.to_str().unwrap();
 
-            // Create a file larger than 2MB
-            let content = "x".repeat(3 * 1024 * 1024); // 3MB
+            // Create a 3MB file that is a single line
+            let content = "x".repeat(3 * 1024 * 1024);
             std::fs::write(&large_file_path, content).unwrap();
 
             let result = router
@@ -2174,38 +2969,61 @@ mod tests {
                     }),
                     dummy_sender(),
                 )
-                .await;
+                .await
+                .unwrap();
 
-            assert!(result.is_err());
-            let err = result.err().unwrap();
-            assert_eq!(err.code, ErrorCode::INTERNAL_ERROR);
-            assert!(err.to_string().contains("too large"));
+            let text = &result[0].as_text().unwrap().text;
+            assert!(text.contains("too large to view whole (3.0MB, 1 lines)"));
+            assert!(text.contains("more bytes]"));
+            assert!(text.len() < 10_000);
         }
 
-        // Test character count limit
+        // Parts of large files can be viewed by lines or bytes
         {
-            let many_chars_path = temp_dir.path().join("many_chars.txt");
-            let many_chars_str = many_chars_path.to_str().unwrap();
+            let log_path = temp_dir.path().join("large.log");
+            let log_str = log_path.to_str().unwrap();
 
-            // This is above MAX_FILE_SIZE
-            let content = "x".repeat(500_000);
-            std::fs::write(&many_chars_path, content).unwrap();
+            let content: String = (1..=50_000)
+                .map(|i| format!("event {} {}\n", i, "-".repeat(20)))
+                .collect();
+            std::fs::write(&log_path, content).unwrap();
 
-            let result = router
+            let summary = router
                 .call_tool(
                     "text_editor",
-                    json!({
-                        "command": "view",
-                        "path": many_chars_str
-                    }),
+                    json!({"command": "view", "path": log_str}),
                     dummy_sender(),
                 )
-                .await;
-
-            assert!(result.is_err());
-            let err = result.err().unwrap();
-            assert_eq!(err.code, ErrorCode::INTERNAL_ERROR);
-            assert!(err.to_string().contains("is too large"));
+                .await
+                .unwrap();
+            let text = &summary[0].as_text().unwrap().text;
+            assert!(text.contains("50000 lines"));
+            assert!(text.contains("1: event 1 "));
+            assert!(text.contains("50000: event 50000 "));
+            assert!(!text.contains("event 25000 "));
+
+            let lines = router
+                .call_tool(
+                    "text_editor",
+                    json!({"command": "view", "path": log_str, "view_range": [25000, 25001]}),
+                    dummy_sender(),
+                )
+                .await
+                .unwrap();
+            let text = &lines[0].as_text().unwrap().text;
+            assert!(text.contains("25000: event 25000 "));
+            assert!(text.contains("25001: event 25001 "));
+            assert!(!text.contains("event 25002 "));
+
+            let bytes = router
+                .call_tool(
+                    "text_editor",
+                    json!({"command": "view", "path": log_str, "byte_range": [0, 8]}),
+                    dummy_sender(),
+                )
+                .await
+                .unwrap();
+            assert!(bytes[0].as_text().unwrap().text.contains("event 1 "));
         }
 
         // Let temp_dir drop naturally at end of scope
@@ -2452,6 +3270,10 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            terminals: Arc::new(TerminalSessions::default()),
+            background_processes: Arc::new(BackgroundProcesses::default()),
+            file_watches: Arc::new(FileWatches::default()),
+            package_scope: None,
         };
 
         // Test basic file matching
@@ -2503,6 +3325,10 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            terminals: Arc::new(TerminalSessions::default()),
+            background_processes: Arc::new(BackgroundProcesses::default()),
+            file_watches: Arc::new(FileWatches::default()),
+            package_scope: None,
         };
 
         // Try to write to an ignored file
@@ -2545,6 +3371,59 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_text_editor_stays_in_package_scope() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\n").unwrap();
+        for package in ["billing", "ledger"] {
+            std::fs::create_dir(root.join(package)).unwrap();
+            std::fs::write(root.join(package).join("Cargo.toml"), "[package]\n").unwrap();
+        }
+        std::env::set_current_dir(root.join("billing")).unwrap();
+
+        let router = DeveloperRouter {
+            tools: vec![],
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(GitignoreBuilder::new(&root).build().unwrap()),
+            editor_model: None,
+            terminals: Arc::new(TerminalSessions::default()),
+            background_processes: Arc::new(BackgroundProcesses::default()),
+            file_watches: Arc::new(FileWatches::default()),
+            package_scope: PackageScope::detect(&root.join("billing"), None),
+        };
+        let write = |path: PathBuf, extra: Value| {
+            let mut params = json!({
+                "command": "write",
+                "path": path.to_str().unwrap(),
+                "file_text": "pub fn total() {}",
+            });
+            params
+                .as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            router.call_tool("text_editor", params, dummy_sender())
+        };
+
+        assert!(write(root.join("billing/lib.rs"), json!({})).await.is_ok());
+        let err = write(root.join("ledger/lib.rs"), json!({}))
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("outside the `billing` package"));
+        // The model can't opt out of the scope on its own
+        assert!(
+            write(root.join("ledger/lib.rs"), json!({"outside_package": true}))
+                .await
+                .is_err()
+        );
+        assert!(!root.join("ledger/lib.rs").exists());
+
+        std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_bash_respects_ignore_patterns() {
@@ -2563,6 +3442,10 @@ mod tests {
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
             editor_model: None,
+            terminals: Arc::new(TerminalSessions::default()),
+            background_processes: Arc::new(BackgroundProcesses::default()),
+            file_watches: Arc::new(FileWatches::default()),
+            package_scope: None,
         };
 
         // Create an ignored file
@@ -2602,6 +3485,60 @@ mod tests {
         temp_dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_terminal_respects_ignore_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::env::set_current_dir(&temp_dir).unwrap();
+
+        let mut builder = GitignoreBuilder::new(temp_dir.path());
+        builder.add_line(None, "secret.txt").unwrap();
+        let ignore_patterns = builder.build().unwrap();
+
+        let router = DeveloperRouter {
+            tools: DeveloperRouter::new().tools,
+            prompts: Arc::new(HashMap::new()),
+            instructions: String::new(),
+            file_history: Arc::new(Mutex::new(HashMap::new())),
+            ignore_patterns: Arc::new(ignore_patterns),
+            editor_model: None,
+            terminals: Arc::new(TerminalSessions::default()),
+            background_processes: Arc::new(BackgroundProcesses::default()),
+            file_watches: Arc::new(FileWatches::default()),
+            package_scope: None,
+        };
+
+        let secret_file_path = temp_dir.path().join("secret.txt");
+        std::fs::write(&secret_file_path, "secret content").unwrap();
+
+        let result = router
+            .call_tool(
+                "terminal",
+                json!({
+                    "action": "start",
+                    "command": format!("less {}", secret_file_path.to_str().unwrap())
+                }),
+                dummy_sender(),
+            )
+            .await;
+        assert_eq!(result.unwrap_err().code, ErrorCode::INTERNAL_ERROR);
+
+        let result = router
+            .call_tool(
+                "terminal",
+                json!({
+                    "action": "send",
+                    "session_id": "1",
+                    "input": format!("cat {}\n", secret_file_path.to_str().unwrap())
+                }),
+                dummy_sender(),
+            )
+            .await;
+        assert_eq!(result.unwrap_err().code, ErrorCode::INTERNAL_ERROR);
+
+        temp_dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_gitignore_fallback_when_no_gooseignore() {
@@ -0,0 +1,144 @@
+//! Reading the text in screenshots and scanned documents with a local tesseract install, so
+//! the images never have to be sent to a vision model. The `GOOSE_PREFER_LOCAL_OCR` setting,
+//! which goose passes on from its config, makes the screenshot and image tools return the
+//! recognized text in place of the image itself.
+
+use rmcp::model::{ErrorCode, ErrorData};
+use std::env;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+pub const PREFER_LOCAL_OCR_ENV_VAR: &str = "GOOSE_PREFER_LOCAL_OCR";
+/// Path to the tesseract binary, for installs that are not on the PATH
+pub const TESSERACT_PATH_ENV_VAR: &str = "GOOSE_TESSERACT_PATH";
+
+/// Whether images should be turned into text locally rather than returned to the model
+pub fn prefer_local_ocr() -> bool {
+    env::var(PREFER_LOCAL_OCR_ENV_VAR)
+        .map(|value| matches!(value.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+fn tesseract_path() -> Result<std::path::PathBuf, ErrorData> {
+    if let Ok(path) = env::var(TESSERACT_PATH_ENV_VAR) {
+        if !path.trim().is_empty() {
+            return Ok(path.trim().into());
+        }
+    }
+    which::which("tesseract").map_err(|_| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!(
+                "tesseract is not installed, which is needed to read text from images locally. \
+                 Install it (brew install tesseract, apt install tesseract-ocr or winget install \
+                 UB-Mannheim.TesseractOCR) or point {} at it.",
+                TESSERACT_PATH_ENV_VAR
+            ),
+            None,
+        )
+    })
+}
+
+/// Tesseract's output without the trailing whitespace on its lines, the runs of blank lines
+/// between blocks, or the form feed it ends every page with
+fn clean(raw: &str) -> String {
+    let mut text = String::new();
+    let mut blank_lines = 0;
+    for line in raw.replace('\x0c', "").lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !text.is_empty() {
+            text.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        blank_lines = 0;
+        text.push_str(line);
+    }
+    text
+}
+
+/// The text in `image`, an encoded image such as a PNG, in `language` (tesseract's code, such
+/// as `eng` or `deu+eng`) or English when none is given
+pub async fn recognize_text(image: &[u8], language: Option<&str>) -> Result<String, ErrorData> {
+    let mut command = Command::new(tesseract_path()?);
+    command
+        .arg("stdin")
+        .arg("stdout")
+        .args(["-l", language.unwrap_or("eng")])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command.spawn().map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to run tesseract: {}", e),
+            None,
+        )
+    })?;
+
+    // Written from a task of its own so a full output pipe can't stall the write
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let image = image.to_vec();
+    let writer = tokio::spawn(async move {
+        let _ = stdin.write_all(&image).await;
+    });
+    let output = child.wait_with_output().await.map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to run tesseract: {}", e),
+            None,
+        )
+    })?;
+    let _ = writer.await;
+
+    if !output.status.success() {
+        return Err(ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!(
+                "tesseract failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            None,
+        ));
+    }
+    Ok(clean(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn test_clean_collapses_blank_lines() {
+        let raw = "Invoice 42  \n\n\n\nTotal: $10.00\nDue: May 1\n\n\x0c";
+        assert_eq!(clean(raw), "Invoice 42\n\nTotal: $10.00\nDue: May 1");
+    }
+
+    #[test]
+    #[serial]
+    fn test_prefer_local_ocr_setting() {
+        env::set_var(PREFER_LOCAL_OCR_ENV_VAR, "true");
+        assert!(prefer_local_ocr());
+        env::set_var(PREFER_LOCAL_OCR_ENV_VAR, "0");
+        assert!(!prefer_local_ocr());
+        env::remove_var(PREFER_LOCAL_OCR_ENV_VAR);
+        assert!(!prefer_local_ocr());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_missing_tesseract_is_explained() {
+        env::set_var(TESSERACT_PATH_ENV_VAR, "/nonexistent/tesseract");
+        let result = recognize_text(b"not an image", None).await;
+        env::remove_var(TESSERACT_PATH_ENV_VAR);
+        assert!(result
+            .unwrap_err()
+            .message
+            .contains("Failed to run tesseract"));
+    }
+}
@@ -1,9 +1,41 @@
+use base64::Engine;
 use std::env;
+use tokio::process::Command;
+
+/// Chooses the shell commands run in: `bash`, `sh`, `zsh`, `pwsh`, `powershell`, `cmd` or the
+/// path to one of them. Detected from the platform when unset.
+pub const SHELL_ENV_VAR: &str = "GOOSE_SHELL";
+
+/// How a shell parses the command it is given, which decides how commands are passed to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    Posix,
+    PowerShell,
+    Cmd,
+}
+
+impl ShellKind {
+    /// The kind of the shell at `executable`, by its file name
+    pub fn of_executable(executable: &str) -> Self {
+        // Split on both kinds of separator, so Windows paths are understood on any platform
+        let file_name = executable
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(executable)
+            .to_lowercase();
+        match file_name.strip_suffix(".exe").unwrap_or(&file_name) {
+            "pwsh" | "powershell" => ShellKind::PowerShell,
+            "cmd" => ShellKind::Cmd,
+            _ => ShellKind::Posix,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ShellConfig {
     pub executable: String,
     pub args: Vec<String>,
+    pub kind: ShellKind,
 }
 
 impl Default for ShellConfig {
@@ -18,57 +50,86 @@ impl Default for ShellConfig {
             {
                 // This branch should never be taken on non-Windows
                 // but we need it for compilation
-                Self {
-                    executable: "cmd".to_string(),
-                    args: vec!["/c".to_string()],
-                }
+                Self::for_executable("cmd")
             }
         } else {
             // Use bash on Unix/macOS (keep existing behavior)
-            Self {
-                executable: "bash".to_string(),
-                args: vec!["-c".to_string()],
-            }
+            Self::for_executable("bash")
         }
     }
 }
 
 impl ShellConfig {
+    /// The shell at `executable` with the arguments its kind needs to run a single command
+    pub fn for_executable(executable: &str) -> Self {
+        let kind = ShellKind::of_executable(executable);
+        let args = match kind {
+            ShellKind::Posix => vec!["-c"],
+            ShellKind::PowerShell => vec!["-NoProfile", "-NonInteractive", "-EncodedCommand"],
+            // /d skips AutoRun commands from the registry, /s keeps the quoting of the command
+            ShellKind::Cmd => vec!["/d", "/s", "/c"],
+        };
+        Self {
+            executable: executable.to_string(),
+            args: args.into_iter().map(String::from).collect(),
+            kind,
+        }
+    }
+
+    /// The shell named by `setting`, found on the PATH when it is not a path itself
+    pub fn from_setting(setting: &str) -> Self {
+        let executable = which::which(setting)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| setting.to_string());
+        Self::for_executable(&executable)
+    }
+
     #[cfg(windows)]
     fn detect_windows_shell() -> Self {
-        // Check for PowerShell first (more modern)
-        if let Ok(ps_path) = which::which("pwsh") {
-            // PowerShell 7+ (cross-platform PowerShell)
-            Self {
-                executable: ps_path.to_string_lossy().to_string(),
-                args: vec![
-                    "-NoProfile".to_string(),
-                    "-NonInteractive".to_string(),
-                    "-Command".to_string(),
-                ],
-            }
-        } else if let Ok(ps_path) = which::which("powershell") {
-            // Windows PowerShell 5.1
-            Self {
-                executable: ps_path.to_string_lossy().to_string(),
-                args: vec![
-                    "-NoProfile".to_string(),
-                    "-NonInteractive".to_string(),
-                    "-Command".to_string(),
-                ],
-            }
-        } else {
-            // Fall back to cmd.exe
-            Self {
-                executable: "cmd".to_string(),
-                args: vec!["/c".to_string()],
+        // Check for PowerShell 7+ first, then Windows PowerShell 5.1, then fall back to cmd.exe
+        ["pwsh", "powershell"]
+            .iter()
+            .find_map(|name| which::which(name).ok())
+            .map(|path| Self::for_executable(&path.to_string_lossy()))
+            .unwrap_or_else(|| Self::for_executable("cmd"))
+    }
+
+    /// The last argument, which holds `command` the way this shell expects it
+    pub fn command_arg(&self, command: &str) -> String {
+        match self.kind {
+            ShellKind::Posix => command.to_string(),
+            ShellKind::PowerShell => {
+                // An encoded command needs no quoting at all. Progress bars are turned off
+                // since PowerShell writes them to stderr as CLIXML when output is redirected.
+                let script = format!("$ProgressPreference = 'SilentlyContinue'; {}", command);
+                let utf16: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+                base64::prelude::BASE64_STANDARD.encode(utf16)
             }
+            // With /s, cmd strips the outer quotes and runs everything between them unchanged
+            ShellKind::Cmd => format!("\"{}\"", command),
+        }
+    }
+
+    /// Add the arguments that make this shell run `command` to `process`
+    pub fn apply(&self, process: &mut Command, command: &str) {
+        process.args(&self.args);
+        let arg = self.command_arg(command);
+        #[cfg(windows)]
+        if self.kind == ShellKind::Cmd {
+            // cmd parses its command line itself, so it must not get the quoting other
+            // Windows programs expect
+            process.raw_arg(arg);
+            return;
         }
+        process.arg(arg);
     }
 }
 
 pub fn get_shell_config() -> ShellConfig {
-    ShellConfig::default()
+    match env::var(SHELL_ENV_VAR) {
+        Ok(setting) if !setting.trim().is_empty() => ShellConfig::from_setting(setting.trim()),
+        _ => ShellConfig::default(),
+    }
 }
 
 pub fn expand_path(path_str: &str) -> String {
@@ -79,17 +140,29 @@ pub fn expand_path(path_str: &str) -> String {
             &env::var("USERPROFILE").unwrap_or_default(),
         );
         // Add more Windows environment variables as needed
-        with_userprofile.replace("%APPDATA%", &env::var("APPDATA").unwrap_or_default())
+        let expanded =
+            with_userprofile.replace("%APPDATA%", &env::var("APPDATA").unwrap_or_default());
+        shellexpand::tilde(&expanded).into_owned()
     } else {
         // Unix-style expansion
         shellexpand::tilde(path_str).into_owned()
     }
 }
 
+/// Whether `path_str` is a Windows absolute path: a drive letter followed by either kind of
+/// slash, or a UNC path
+fn is_windows_absolute_path(path_str: &str) -> bool {
+    let bytes = path_str.as_bytes();
+    let has_drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    has_drive || path_str.starts_with("\\\\") || path_str.starts_with("//")
+}
+
 pub fn is_absolute_path(path_str: &str) -> bool {
     if cfg!(windows) {
-        // Check for Windows absolute paths (drive letters and UNC)
-        path_str.contains(":\\") || path_str.starts_with("\\\\")
+        is_windows_absolute_path(path_str)
     } else {
         // Unix absolute paths start with /
         path_str.starts_with('/')
@@ -105,3 +178,71 @@ pub fn normalize_line_endings(text: &str) -> String {
         text.replace("\r\n", "\n")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_kind_from_executable() {
+        assert_eq!(ShellKind::of_executable("bash"), ShellKind::Posix);
+        assert_eq!(ShellKind::of_executable("/usr/bin/zsh"), ShellKind::Posix);
+        assert_eq!(
+            ShellKind::of_executable("C:\\Program Files\\PowerShell\\7\\pwsh.exe"),
+            ShellKind::PowerShell
+        );
+        assert_eq!(
+            ShellKind::of_executable("powershell.exe"),
+            ShellKind::PowerShell
+        );
+        assert_eq!(
+            ShellKind::of_executable("C:\\Windows\\System32\\CMD.EXE"),
+            ShellKind::Cmd
+        );
+    }
+
+    #[test]
+    fn test_powershell_commands_are_encoded() {
+        let shell = ShellConfig::for_executable("pwsh");
+        assert_eq!(shell.args.last().unwrap(), "-EncodedCommand");
+
+        let encoded = shell.command_arg(r#"Write-Output "it's here""#);
+        let bytes = base64::prelude::BASE64_STANDARD.decode(encoded).unwrap();
+        let utf16: Vec<u16> = bytes
+            .chunks(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        let script = String::from_utf16(&utf16).unwrap();
+        assert!(script.ends_with(r#"; Write-Output "it's here""#));
+    }
+
+    #[test]
+    fn test_cmd_commands_keep_their_quoting() {
+        let shell = ShellConfig::for_executable("cmd");
+        assert_eq!(shell.args, vec!["/d", "/s", "/c"]);
+        assert_eq!(
+            shell.command_arg(r#"dir "C:\Program Files" && echo done"#),
+            r#""dir "C:\Program Files" && echo done""#
+        );
+    }
+
+    #[test]
+    fn test_posix_commands_are_passed_unchanged() {
+        let shell = ShellConfig::for_executable("sh");
+        assert_eq!(shell.args, vec!["-c"]);
+        assert_eq!(
+            shell.command_arg("echo 'a b' | wc -c"),
+            "echo 'a b' | wc -c"
+        );
+    }
+
+    #[test]
+    fn test_windows_absolute_paths() {
+        assert!(is_windows_absolute_path("C:\\Users\\goose"));
+        assert!(is_windows_absolute_path("d:/projects/goose"));
+        assert!(is_windows_absolute_path("\\\\server\\share"));
+        assert!(!is_windows_absolute_path("src\\main.rs"));
+        assert!(!is_windows_absolute_path("C:relative"));
+        assert!(!is_windows_absolute_path("notes:\\"));
+    }
+}
@@ -0,0 +1,340 @@
+//! Interactive commands run in a pseudo-terminal, for programs that need a TTY such as
+//! installers that prompt, REPLs and ssh. A session outlives the tool call that started it, so
+//! the agent can read its output and send it input across turns.
+
+use once_cell::sync::Lazy;
+use portable_pty::{native_pty_system, Child, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use regex::Regex;
+use rmcp::model::{ErrorCode, ErrorData};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use super::shell::get_shell_config;
+
+pub const MAX_SESSIONS: usize = 8;
+/// Unread output kept per session; the oldest is dropped once a session prints more
+const MAX_UNREAD_BYTES: usize = 400_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Output counts as complete once a session has been quiet for this long
+const QUIET_PERIOD: Duration = Duration::from_millis(500);
+const TERMINAL_SIZE: PtySize = PtySize {
+    rows: 40,
+    cols: 120,
+    pixel_width: 0,
+    pixel_height: 0,
+};
+
+/// Colors, cursor movement and window titles, which mean nothing to the agent
+static ANSI_ESCAPE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-Z\\-_]")
+        .expect("valid regex")
+});
+
+#[derive(Default)]
+struct Output {
+    unread: Vec<u8>,
+    closed: bool,
+}
+
+struct TerminalSession {
+    command: String,
+    child: Box<dyn Child + Send + Sync>,
+    writer: Box<dyn Write + Send>,
+    output: Arc<Mutex<Output>>,
+    // Dropping the master hangs up the terminal, so it lives as long as the session
+    _master: Box<dyn MasterPty + Send>,
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+/// What a session printed since it was last read
+#[derive(Debug)]
+pub struct TerminalOutput {
+    pub text: String,
+    /// Set once the command exited, after which the session is gone
+    pub exit_code: Option<u32>,
+}
+
+fn internal_error(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message, None)
+}
+
+/// Take the bytes of `unread` that form complete UTF-8, leaving a character split across
+/// reads for the next one
+fn take_complete(unread: &mut Vec<u8>) -> Vec<u8> {
+    let complete = match std::str::from_utf8(unread) {
+        Ok(_) => unread.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => unread.len(),
+    };
+    let rest = unread.split_off(complete);
+    std::mem::replace(unread, rest)
+}
+
+/// Terminal output as plain text
+fn clean(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    ANSI_ESCAPE
+        .replace_all(&text, "")
+        .replace("\r\n", "\n")
+        .replace('\r', "")
+}
+
+/// Keystrokes for `input`: a terminal sends carriage return for Enter
+fn keystrokes(input: &str) -> String {
+    input.replace("\r\n", "\r").replace('\n', "\r")
+}
+
+#[derive(Default)]
+pub struct TerminalSessions {
+    sessions: Mutex<HashMap<String, TerminalSession>>,
+    next_id: AtomicUsize,
+}
+
+impl TerminalSessions {
+    /// Run `command` in a new terminal in `cwd` and return the id of its session
+    pub fn start(&self, command: &str, cwd: &Path) -> Result<String, ErrorData> {
+        if self.sessions.lock().unwrap().len() >= MAX_SESSIONS {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_REQUEST,
+                format!(
+                    "There are already {} terminal sessions open, stop one before starting another",
+                    MAX_SESSIONS
+                ),
+                None,
+            ));
+        }
+
+        let pair = native_pty_system()
+            .openpty(TERMINAL_SIZE)
+            .map_err(|e| internal_error(format!("Failed to open a terminal: {}", e)))?;
+        let shell = get_shell_config();
+        let mut builder = CommandBuilder::new(&shell.executable);
+        builder.args(&shell.args);
+        builder.arg(shell.command_arg(command));
+        builder.cwd(cwd);
+        builder.env("GOOSE_TERMINAL", "1");
+        let child = pair
+            .slave
+            .spawn_command(builder)
+            .map_err(|e| internal_error(format!("Failed to run '{}': {}", command, e)))?;
+        // The child holds its own handle; ours would keep the terminal open after it exits
+        drop(pair.slave);
+
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| internal_error(e.to_string()))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| internal_error(e.to_string()))?;
+        let output = Arc::new(Mutex::new(Output::default()));
+        let reader_output = output.clone();
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 8192];
+            loop {
+                let read = reader.read(&mut buffer);
+                let mut output = reader_output.lock().unwrap();
+                match read {
+                    Ok(0) | Err(_) => {
+                        output.closed = true;
+                        break;
+                    }
+                    Ok(n) => {
+                        output.unread.extend_from_slice(&buffer[..n]);
+                        let excess = output.unread.len().saturating_sub(MAX_UNREAD_BYTES);
+                        output.unread.drain(..excess);
+                    }
+                }
+            }
+        });
+
+        let id = format!("term-{}", self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+        self.sessions.lock().unwrap().insert(
+            id.clone(),
+            TerminalSession {
+                command: command.to_string(),
+                child,
+                writer,
+                output,
+                _master: pair.master,
+            },
+        );
+        Ok(id)
+    }
+
+    fn not_found(&self, id: &str) -> ErrorData {
+        let sessions = self.sessions.lock().unwrap();
+        let mut open: Vec<String> = sessions
+            .iter()
+            .map(|(id, session)| format!("{} ({})", id, session.command))
+            .collect();
+        open.sort();
+        ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            if open.is_empty() {
+                format!("No terminal session '{}', and none are open", id)
+            } else {
+                format!(
+                    "No terminal session '{}', open sessions are: {}",
+                    id,
+                    open.join(", ")
+                )
+            },
+            None,
+        )
+    }
+
+    /// Type `input` into the terminal of session `id`
+    pub fn send(&self, id: &str, input: &str) -> Result<(), ErrorData> {
+        let written = self.sessions.lock().unwrap().get_mut(id).map(|session| {
+            session
+                .writer
+                .write_all(keystrokes(input).as_bytes())
+                .and_then(|_| session.writer.flush())
+        });
+        match written {
+            Some(written) => written
+                .map_err(|e| internal_error(format!("Failed to send input to {}: {}", id, e))),
+            None => Err(self.not_found(id)),
+        }
+    }
+
+    /// Output of session `id`, waiting up to `wait` for it to print something and go quiet.
+    /// Output is handed to `on_output` as it arrives.
+    pub async fn read(
+        &self,
+        id: &str,
+        wait: Duration,
+        mut on_output: impl FnMut(&str),
+    ) -> Result<TerminalOutput, ErrorData> {
+        let deadline = Instant::now() + wait;
+        let mut text = String::new();
+        let mut last_output: Option<Instant> = None;
+        loop {
+            let output = self
+                .sessions
+                .lock()
+                .unwrap()
+                .get(id)
+                .map(|session| session.output.clone());
+            let Some(output) = output else {
+                return Err(self.not_found(id));
+            };
+            let (chunk, closed) = {
+                let mut output = output.lock().unwrap();
+                (take_complete(&mut output.unread), output.closed)
+            };
+            if !chunk.is_empty() {
+                let chunk = clean(&chunk);
+                on_output(&chunk);
+                text.push_str(&chunk);
+                last_output = Some(Instant::now());
+            }
+
+            let now = Instant::now();
+            let quiet = last_output.is_some_and(|at| now - at >= QUIET_PERIOD);
+            if closed || quiet || now >= deadline {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        let mut sessions = self.sessions.lock().unwrap();
+        let exit_code = match sessions.get_mut(id) {
+            Some(session) => session
+                .child
+                .try_wait()
+                .ok()
+                .flatten()
+                .map(|status| status.exit_code()),
+            None => None,
+        };
+        if exit_code.is_some() {
+            sessions.remove(id);
+        }
+        Ok(TerminalOutput { text, exit_code })
+    }
+
+    /// End session `id`, returning what it printed that was not read yet
+    pub fn stop(&self, id: &str) -> Result<TerminalOutput, ErrorData> {
+        let session = self.sessions.lock().unwrap().remove(id);
+        let Some(mut session) = session else {
+            return Err(self.not_found(id));
+        };
+        let _ = session.child.kill();
+        let exit_code = session.child.wait().ok().map(|status| status.exit_code());
+        let text = clean(&std::mem::take(&mut session.output.lock().unwrap().unread));
+        Ok(TerminalOutput { text, exit_code })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_strips_terminal_escapes() {
+        let raw = b"\x1b[1;32mok\x1b[0m\r\n\x1b]0;title\x07done\r\n";
+        assert_eq!(clean(raw), "ok\ndone\n");
+    }
+
+    #[test]
+    fn test_split_characters_wait_for_the_next_read() {
+        let mut unread = "héllo".as_bytes()[..2].to_vec();
+        assert_eq!(take_complete(&mut unread), b"h");
+        assert_eq!(unread, vec![0xC3]);
+    }
+
+    #[test]
+    fn test_enter_is_sent_as_carriage_return() {
+        assert_eq!(keystrokes("yes\n"), "yes\r");
+        assert_eq!(keystrokes("a\r\nb"), "a\rb");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_input_reaches_an_interactive_command() {
+        let sessions = TerminalSessions::default();
+        let dir = tempfile::tempdir().unwrap();
+        let id = sessions
+            .start("read -p 'name? ' name && echo \"hi $name\"", dir.path())
+            .unwrap();
+
+        let prompt = sessions
+            .read(&id, Duration::from_secs(5), |_| {})
+            .await
+            .unwrap();
+        assert!(prompt.text.contains("name?"));
+        assert_eq!(prompt.exit_code, None);
+
+        sessions.send(&id, "goose\n").unwrap();
+        let mut streamed = String::new();
+        let mut exit_code = None;
+        for _ in 0..5 {
+            let reply = sessions
+                .read(&id, Duration::from_secs(5), |chunk| {
+                    streamed.push_str(chunk)
+                })
+                .await
+                .unwrap();
+            exit_code = reply.exit_code;
+            if exit_code.is_some() {
+                break;
+            }
+        }
+        assert!(streamed.contains("hi goose"));
+        assert_eq!(exit_code, Some(0));
+        assert!(sessions.send(&id, "again\n").is_err());
+    }
+}
@@ -0,0 +1,736 @@
+//! Running a project's tests with the runner its language uses and reading the results back as
+//! data, so the agent sees which tests failed and why rather than pages of logs.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rmcp::model::{ErrorCode, ErrorData};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::process::Command;
+
+/// Kept under goose's default 300 second extension timeout, so a run that takes too long is
+/// stopped and reported here rather than given up on by goose while it keeps running
+const TEST_TIMEOUT: Duration = Duration::from_secs(270);
+/// Failures reported in full; the rest are only counted
+const MAX_FAILURES: usize = 20;
+const MAX_MESSAGE_LINES: usize = 30;
+const MAX_BUILD_ERROR_LINES: usize = 60;
+
+static ANSI_ESCAPE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestFramework {
+    Cargo,
+    Jest,
+    Vitest,
+    Pytest,
+    Go,
+}
+
+impl TestFramework {
+    pub const NAMES: &'static [&'static str] = &["cargo", "jest", "vitest", "pytest", "go"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "cargo" => Some(Self::Cargo),
+            "jest" => Some(Self::Jest),
+            "vitest" => Some(Self::Vitest),
+            "pytest" => Some(Self::Pytest),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// The framework of the project `dir` belongs to, from the closest manifest at or above it,
+    /// along with the directory to run the tests in. A project whose runner isn't supported is
+    /// an error naming it, rather than a reason to look further up for another project.
+    pub fn detect(dir: &Path) -> Result<(Self, PathBuf), String> {
+        for ancestor in dir.ancestors() {
+            if ancestor.join("Cargo.toml").is_file() {
+                return Ok((Self::Cargo, ancestor.to_path_buf()));
+            }
+            if ancestor.join("go.mod").is_file() {
+                return Ok((Self::Go, ancestor.to_path_buf()));
+            }
+            let package_json = ancestor.join("package.json");
+            if let Ok(manifest) = std::fs::read_to_string(&package_json) {
+                let manifest: Value = serde_json::from_str(&manifest).unwrap_or_default();
+                let depends_on = |name: &str| {
+                    ["dependencies", "devDependencies"]
+                        .iter()
+                        .any(|key| manifest[key].get(name).is_some())
+                };
+                if depends_on("vitest") {
+                    return Ok((Self::Vitest, ancestor.to_path_buf()));
+                }
+                if depends_on("jest") {
+                    return Ok((Self::Jest, ancestor.to_path_buf()));
+                }
+                let runner = match manifest["scripts"]["test"].as_str() {
+                    Some(script) => format!("`{}`", script),
+                    None => "no test script".to_string(),
+                };
+                return Err(format!(
+                    "{} uses neither jest nor vitest (it has {}); run its tests with the shell \
+                     tool instead",
+                    package_json.display(),
+                    runner
+                ));
+            }
+            if [
+                "pytest.ini",
+                "pyproject.toml",
+                "setup.cfg",
+                "tox.ini",
+                "conftest.py",
+            ]
+            .iter()
+            .any(|file| ancestor.join(file).is_file())
+            {
+                return Ok((Self::Pytest, ancestor.to_path_buf()));
+            }
+            if let Some((manifest, runner)) = UNSUPPORTED_MANIFESTS
+                .iter()
+                .find(|(manifest, _)| ancestor.join(manifest).is_file())
+            {
+                return Err(format!(
+                    "{} is a {} project, which run_tests doesn't support; run its tests with the \
+                     shell tool instead",
+                    ancestor.join(manifest).display(),
+                    runner
+                ));
+            }
+        }
+        Err(format!(
+            "No supported test setup found at or above {}. Pass `framework` as one of: {}",
+            dir.display(),
+            TestFramework::NAMES.join(", ")
+        ))
+    }
+}
+
+/// Manifests of projects whose tests run with a runner that isn't supported, and that runner
+const UNSUPPORTED_MANIFESTS: &[(&str, &str)] = &[
+    ("pom.xml", "Maven"),
+    ("build.gradle", "Gradle"),
+    ("build.gradle.kts", "Gradle"),
+    ("Gemfile", "Ruby"),
+    ("mix.exs", "Elixir"),
+    ("composer.json", "PHP Composer"),
+    ("Package.swift", "Swift"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestFailure {
+    pub name: String,
+    pub message: String,
+}
+
+/// The outcome of a test run
+#[derive(Debug, Default, Serialize)]
+pub struct TestReport {
+    pub command: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// The first failures, with what they printed
+    pub failures: Vec<TestFailure>,
+    /// Why the tests could not be run, such as a compile error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_error: Option<String>,
+}
+
+impl TestReport {
+    pub fn summary(&self) -> String {
+        if self.build_error.is_some() {
+            return "The tests could not be built or run".to_string();
+        }
+        format!(
+            "{} passed, {} failed, {} skipped",
+            self.passed, self.failed, self.skipped
+        )
+    }
+
+    fn add_failure(&mut self, name: String, message: &str) {
+        self.failed += 1;
+        if self.failures.len() < MAX_FAILURES {
+            self.failures.push(TestFailure {
+                name,
+                message: first_lines(message, MAX_MESSAGE_LINES),
+            });
+        }
+    }
+}
+
+/// The first `max` lines of `text` without color codes, noting how many were left out
+fn first_lines(text: &str, max: usize) -> String {
+    let text = ANSI_ESCAPE.replace_all(text, "");
+    let lines: Vec<&str> = text.trim().lines().collect();
+    if lines.len() <= max {
+        return lines.join("\n");
+    }
+    format!(
+        "{}\n... ({} more lines)",
+        lines[..max].join("\n"),
+        lines.len() - max
+    )
+}
+
+/// Parse the output of `cargo test`, which has no stable machine-readable format
+pub fn parse_cargo(stdout: &str, stderr: &str) -> TestReport {
+    static RESULT: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^test (.+?) \.\.\. (ok|FAILED|ignored)").unwrap());
+    static OUTPUT_HEADER: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^---- (.+?) stdout ----$").unwrap());
+
+    let mut report = TestReport::default();
+    let mut failed = Vec::new();
+    for line in stdout.lines() {
+        if let Some(captures) = RESULT.captures(line) {
+            match &captures[2] {
+                "ok" => report.passed += 1,
+                "ignored" => report.skipped += 1,
+                _ => failed.push(captures[1].to_string()),
+            }
+        }
+    }
+
+    // What each failing test printed, from its `---- name stdout ----` section
+    let mut outputs: HashMap<String, Vec<&str>> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in stdout.lines() {
+        if let Some(captures) = OUTPUT_HEADER.captures(line) {
+            current = Some(captures[1].to_string());
+        } else if line == "failures:" || line.starts_with("test result:") {
+            current = None;
+        } else if let Some(name) = &current {
+            outputs.entry(name.clone()).or_default().push(line);
+        }
+    }
+    for name in failed {
+        let message = outputs.get(&name).map(|lines| lines.join("\n"));
+        report.add_failure(name, message.as_deref().unwrap_or(""));
+    }
+
+    if report.passed + report.failed + report.skipped == 0 {
+        let errors: Vec<&str> = stderr
+            .lines()
+            .skip_while(|line| !line.starts_with("error"))
+            .collect();
+        if !errors.is_empty() {
+            report.build_error = Some(first_lines(&errors.join("\n"), MAX_BUILD_ERROR_LINES));
+        }
+    }
+    report
+}
+
+/// Parse the JSON report jest and vitest write with `--json`/`--reporter=json`
+pub fn parse_jest_json(json: &str) -> Result<TestReport, serde_json::Error> {
+    let results: Value = serde_json::from_str(json)?;
+    let mut report = TestReport::default();
+    for file in results["testResults"].as_array().into_iter().flatten() {
+        let assertions = file["assertionResults"].as_array();
+        // A file that failed without running anything, e.g. one that doesn't compile
+        if assertions.is_none_or(|a| a.is_empty()) && file["status"] == "failed" {
+            report.add_failure(
+                file["name"].as_str().unwrap_or("test file").to_string(),
+                file["message"].as_str().unwrap_or(""),
+            );
+            continue;
+        }
+        for assertion in assertions.into_iter().flatten() {
+            match assertion["status"].as_str() {
+                Some("passed") => report.passed += 1,
+                Some("failed") => {
+                    let messages: Vec<&str> = assertion["failureMessages"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|m| m.as_str())
+                        .collect();
+                    report.add_failure(
+                        assertion["fullName"].as_str().unwrap_or("test").to_string(),
+                        &messages.join("\n"),
+                    );
+                }
+                _ => report.skipped += 1,
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Parse pytest's output when run with `-rA`, which ends in a line per test
+pub fn parse_pytest(stdout: &str) -> TestReport {
+    // Parametrized node ids may hold spaces inside their brackets, e.g. `test_sum[1 + 1]`
+    static SUMMARY: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^(PASSED|FAILED|ERROR|XFAIL|XPASS) ([^\s\[]+(?:\[.*?\])?)(?: (?:- )?(.*))?$")
+            .unwrap()
+    });
+    // Skips are grouped by reason, with how many tests each line stands for
+    static SKIPPED: Lazy<Regex> = Lazy::new(|| Regex::new(r"^SKIPPED \[(\d+)\] ").unwrap());
+    static SECTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^_{3,} (.+?) _{3,}$").unwrap());
+
+    // The tracebacks of the FAILURES and ERRORS sections, by the test's short name
+    let mut details: HashMap<String, Vec<&str>> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in stdout.lines() {
+        if let Some(captures) = SECTION.captures(line) {
+            current = Some(captures[1].to_string());
+        } else if line.starts_with("====") {
+            current = None;
+        } else if let Some(name) = &current {
+            details.entry(name.clone()).or_default().push(line);
+        }
+    }
+
+    let mut report = TestReport::default();
+    for line in stdout.lines() {
+        if let Some(captures) = SKIPPED.captures(line) {
+            report.skipped += captures[1].parse::<usize>().unwrap_or(1);
+            continue;
+        }
+        let Some(captures) = SUMMARY.captures(line) else {
+            continue;
+        };
+        let node_id = &captures[2];
+        match &captures[1] {
+            // An unexpected pass is only a failure under `xfail_strict`, which pytest then
+            // reports as FAILED
+            "PASSED" | "XFAIL" | "XPASS" => report.passed += 1,
+            _ => {
+                // Sections are headed `test_name` or `Class.test_name`
+                let short_name = node_id.split("::").skip(1).collect::<Vec<_>>().join(".");
+                let section = details
+                    .get(&short_name)
+                    .or_else(|| details.get(&format!("ERROR collecting {}", node_id)))
+                    .map(|lines| lines.join("\n"));
+                let message = section
+                    .or_else(|| captures.get(3).map(|m| m.as_str().to_string()))
+                    .unwrap_or_default();
+                report.add_failure(node_id.to_string(), &message);
+            }
+        }
+    }
+    report
+}
+
+/// Parse the events `go test -json` prints, a JSON object per line
+pub fn parse_go_json(stdout: &str, stderr: &str) -> TestReport {
+    let mut report = TestReport::default();
+    let mut outputs: HashMap<(String, String), String> = HashMap::new();
+    let mut failed_packages = Vec::new();
+    let mut packages_with_failed_tests = Vec::new();
+    let mut other_output = Vec::new();
+
+    for line in stdout.lines() {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            other_output.push(line);
+            continue;
+        };
+        let package = event["Package"].as_str().unwrap_or_default().to_string();
+        let test = event["Test"].as_str().map(String::from);
+        match (event["Action"].as_str(), test) {
+            (Some("output"), Some(test)) => {
+                outputs
+                    .entry((package, test))
+                    .or_default()
+                    .push_str(event["Output"].as_str().unwrap_or_default());
+            }
+            (Some("pass"), Some(_)) => report.passed += 1,
+            (Some("skip"), Some(_)) => report.skipped += 1,
+            (Some("fail"), Some(test)) => {
+                let output = outputs
+                    .get(&(package.clone(), test.clone()))
+                    .cloned()
+                    .unwrap_or_default();
+                report.add_failure(format!("{}.{}", package, test), &output);
+                packages_with_failed_tests.push(package);
+            }
+            (Some("fail"), None) => failed_packages.push(package),
+            _ => {}
+        }
+    }
+
+    // A package that failed without a failing test did not build
+    if failed_packages
+        .iter()
+        .any(|package| !packages_with_failed_tests.contains(package))
+    {
+        let errors = format!("{}\n{}", other_output.join("\n"), stderr);
+        report.build_error = Some(first_lines(&errors, MAX_BUILD_ERROR_LINES));
+    }
+    report
+}
+
+/// Kill a test run and everything it started
+fn kill_process_group(pid: Option<u32>) {
+    #[cfg(unix)]
+    if let Some(pid) = pid {
+        use nix::sys::signal::{killpg, Signal};
+        use nix::unistd::Pid;
+        let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// Run the tests under `dir` with `framework`, or the framework of the project `dir` is in,
+/// limited to the tests matching `filter`
+pub async fn run_tests(
+    dir: &Path,
+    framework: Option<TestFramework>,
+    filter: Option<&str>,
+) -> Result<TestReport, ErrorData> {
+    let (framework, project_dir) = match framework {
+        Some(framework) => (framework, dir.to_path_buf()),
+        None => TestFramework::detect(dir)
+            .map_err(|message| ErrorData::new(ErrorCode::INVALID_PARAMS, message, None))?,
+    };
+
+    let json_file = tempfile::NamedTempFile::new().map_err(|e| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to create a file for the test results: {}", e),
+            None,
+        )
+    })?;
+    let json_path = json_file.path().to_string_lossy().to_string();
+
+    let mut args: Vec<String> = Vec::new();
+    let program = match framework {
+        TestFramework::Cargo => {
+            args.extend(["test".into(), "--no-fail-fast".into()]);
+            args.extend(filter.map(String::from));
+            "cargo".to_string()
+        }
+        TestFramework::Jest | TestFramework::Vitest => {
+            args.push("--no-install".into());
+            if framework == TestFramework::Jest {
+                args.extend(["jest".into(), "--json".into(), "--outputFile".into()]);
+                args.push(json_path.clone());
+            } else {
+                args.extend(["vitest".into(), "run".into(), "--reporter=json".into()]);
+                args.push(format!("--outputFile={}", json_path));
+            }
+            if let Some(filter) = filter {
+                args.extend(["-t".into(), filter.into()]);
+            }
+            "npx".to_string()
+        }
+        TestFramework::Pytest => {
+            args.extend(
+                [
+                    "-m",
+                    "pytest",
+                    "-rA",
+                    "--tb=short",
+                    "-p",
+                    "no:cacheprovider",
+                ]
+                .map(String::from),
+            );
+            if let Some(filter) = filter {
+                args.extend(["-k".into(), filter.into()]);
+            }
+            if which::which("python3").is_ok() {
+                "python3".to_string()
+            } else {
+                "python".to_string()
+            }
+        }
+        TestFramework::Go => {
+            args.extend(["test".into(), "-json".into()]);
+            if let Some(filter) = filter {
+                args.extend(["-run".into(), filter.into()]);
+            }
+            args.push("./...".into());
+            "go".to_string()
+        }
+    };
+    let command = format!("{} {}", program, args.join(" "));
+
+    let mut process = Command::new(&program);
+    process
+        .args(&args)
+        .current_dir(&project_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // A process group of its own, so a run that times out is stopped along with the test
+    // processes, servers and browsers it started
+    #[cfg(unix)]
+    process.process_group(0);
+    let failed_to_run = |e: std::io::Error| {
+        ErrorData::new(
+            ErrorCode::INTERNAL_ERROR,
+            format!("Failed to run `{}`: {}", command, e),
+            None,
+        )
+    };
+    let child = process.spawn().map_err(failed_to_run)?;
+    let pid = child.id();
+    let output = match tokio::time::timeout(TEST_TIMEOUT, child.wait_with_output()).await {
+        Ok(output) => output.map_err(failed_to_run)?,
+        Err(_) => {
+            kill_process_group(pid);
+            return Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "`{}` did not finish within {} seconds and was stopped. Run fewer tests at \
+                     a time with `filter`, or run them with the background_process tool.",
+                    command,
+                    TEST_TIMEOUT.as_secs()
+                ),
+                None,
+            ));
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let mut report = match framework {
+        TestFramework::Cargo => parse_cargo(&stdout, &stderr),
+        TestFramework::Jest | TestFramework::Vitest => {
+            // Without the file there is only the log, shown below as why nothing ran
+            std::fs::read_to_string(json_file.path())
+                .ok()
+                .and_then(|json| parse_jest_json(&json).ok())
+                .unwrap_or_default()
+        }
+        TestFramework::Pytest => parse_pytest(&stdout),
+        TestFramework::Go => parse_go_json(&stdout, &stderr),
+    };
+    report.command = command;
+
+    // Nothing ran, yet the run failed: show why in place of the results
+    let ran = report.passed + report.failed + report.skipped > 0;
+    if !ran && !output.status.success() && report.build_error.is_none() {
+        let log = if stderr.trim().is_empty() {
+            stdout
+        } else {
+            stderr
+        };
+        let lines: Vec<&str> = log.lines().collect();
+        let tail = lines[lines.len().saturating_sub(MAX_BUILD_ERROR_LINES)..].join("\n");
+        report.build_error = Some(first_lines(&tail, MAX_BUILD_ERROR_LINES));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo() {
+        let stdout = "\
+running 3 tests
+test math::adds ... ok
+test math::divides ... FAILED
+test math::slow ... ignored, takes a minute
+
+failures:
+
+---- math::divides stdout ----
+
+thread 'math::divides' panicked at src/math.rs:12:9:
+assertion `left == right` failed
+  left: 2
+ right: 3
+
+failures:
+    math::divides
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured; 0 filtered out
+";
+        let report = parse_cargo(stdout, "");
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 1, 1));
+        assert_eq!(report.failures[0].name, "math::divides");
+        assert!(report.failures[0].message.contains("left: 2"));
+        assert!(report.build_error.is_none());
+    }
+
+    #[test]
+    fn test_parse_cargo_compile_error() {
+        let stderr = "   Compiling math v0.1.0\nerror[E0425]: cannot find value `x` in this scope\n --> src/math.rs:3:5\n";
+        let report = parse_cargo("", stderr);
+        assert!(report
+            .build_error
+            .unwrap()
+            .starts_with("error[E0425]: cannot find value `x`"));
+    }
+
+    #[test]
+    fn test_parse_jest_json() {
+        let json = r#"{
+            "testResults": [
+                {
+                    "name": "/app/sum.test.js",
+                    "status": "failed",
+                    "assertionResults": [
+                        {"fullName": "sum adds", "status": "passed", "failureMessages": []},
+                        {"fullName": "sum carries", "status": "failed",
+                         "failureMessages": ["\u001b[31mExpected: 10\nReceived: 1\u001b[39m"]},
+                        {"fullName": "sum later", "status": "pending", "failureMessages": []}
+                    ]
+                },
+                {"name": "/app/broken.test.js", "status": "failed", "message": "SyntaxError: Unexpected token", "assertionResults": []}
+            ]
+        }"#;
+        let report = parse_jest_json(json).unwrap();
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 2, 1));
+        assert_eq!(
+            report.failures[0],
+            TestFailure {
+                name: "sum carries".to_string(),
+                message: "Expected: 10\nReceived: 1".to_string()
+            }
+        );
+        assert_eq!(report.failures[1].name, "/app/broken.test.js");
+    }
+
+    #[test]
+    fn test_parse_pytest() {
+        let stdout = "\
+============================= test session starts ==============================
+collected 3 items
+
+test_cart.py .Fs                                                         [100%]
+
+=================================== FAILURES ===================================
+____________________________ TestCart.test_total _____________________________
+test_cart.py:9: in test_total
+    assert cart.total() == 30
+E   assert 25 == 30
+=========================== short test summary info ============================
+PASSED test_cart.py::test_empty
+SKIPPED [1] test_cart.py:14: needs network
+FAILED test_cart.py::TestCart::test_total - assert 25 == 30
+=================== 1 failed, 1 passed, 1 skipped in 0.05s ====================
+";
+        let report = parse_pytest(stdout);
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 1, 1));
+        assert_eq!(
+            report.failures[0].name,
+            "test_cart.py::TestCart::test_total"
+        );
+        assert!(report.failures[0].message.contains("E   assert 25 == 30"));
+    }
+
+    #[test]
+    fn test_parse_pytest_counts_unexpected_passes_as_passed() {
+        let stdout = "\
+=========================== short test summary info ============================
+XFAIL test_cart.py::test_discount - rounding is off
+XPASS test_cart.py::test_tax known issue
+=================== 1 xfailed, 1 xpassed in 0.02s ====================
+";
+        let report = parse_pytest(stdout);
+        assert_eq!((report.passed, report.failed, report.skipped), (2, 0, 0));
+    }
+
+    #[test]
+    fn test_parse_pytest_counts_grouped_skips() {
+        let stdout = "\
+=========================== short test summary info ============================
+PASSED test_cart.py::test_empty
+SKIPPED [3] test_cart.py:14: needs network
+SKIPPED [1] test_cart.py:30: slow
+=================== 1 passed, 4 skipped in 0.02s ====================
+";
+        let report = parse_pytest(stdout);
+        assert_eq!((report.passed, report.failed, report.skipped), (1, 0, 4));
+    }
+
+    #[test]
+    fn test_parse_pytest_node_ids_with_spaces() {
+        let stdout = "\
+=================================== FAILURES ===================================
+_____________________________ test_sum[1 + 2-4] ______________________________
+E   assert 3 == 4
+=========================== short test summary info ============================
+PASSED test_math.py::test_sum[1 + 1-2]
+FAILED test_math.py::test_sum[1 + 2-4] - assert [3] == [4]
+=================== 1 failed, 1 passed in 0.02s ====================
+";
+        let report = parse_pytest(stdout);
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(report.failures[0].name, "test_math.py::test_sum[1 + 2-4]");
+        assert!(report.failures[0].message.contains("E   assert 3 == 4"));
+    }
+
+    #[test]
+    fn test_parse_go_json() {
+        let stdout = r#"{"Action":"run","Package":"shop/cart","Test":"TestTotal"}
+{"Action":"output","Package":"shop/cart","Test":"TestTotal","Output":"    cart_test.go:9: got 25, want 30\n"}
+{"Action":"fail","Package":"shop/cart","Test":"TestTotal","Elapsed":0}
+{"Action":"pass","Package":"shop/cart","Test":"TestEmpty","Elapsed":0}
+{"Action":"fail","Package":"shop/cart","Elapsed":0.1}
+"#;
+        let report = parse_go_json(stdout, "");
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(report.failures[0].name, "shop/cart.TestTotal");
+        assert_eq!(
+            report.failures[0].message,
+            "cart_test.go:9: got 25, want 30"
+        );
+        assert!(report.build_error.is_none());
+
+        let stdout = r#"{"Action":"fail","Package":"shop/api","Elapsed":0}"#;
+        let stderr = "# shop/api\napi/server.go:4:2: undefined: Router\n";
+        let report = parse_go_json(stdout, stderr);
+        assert!(report.build_error.unwrap().contains("undefined: Router"));
+    }
+
+    #[test]
+    fn test_detect() {
+        let dir = tempfile::tempdir().unwrap();
+        let web = dir.path().join("web");
+        std::fs::create_dir_all(web.join("src")).unwrap();
+        std::fs::write(
+            web.join("package.json"),
+            r#"{"devDependencies": {"vitest": "^2.0.0"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            TestFramework::detect(&web.join("src")),
+            Ok((TestFramework::Vitest, web.clone()))
+        );
+
+        std::fs::write(dir.path().join("go.mod"), "module shop\n").unwrap();
+        assert_eq!(
+            TestFramework::detect(dir.path()),
+            Ok((TestFramework::Go, dir.path().to_path_buf()))
+        );
+    }
+
+    #[test]
+    fn test_detect_stops_at_the_closest_project() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[workspace]\n").unwrap();
+
+        let web = dir.path().join("web");
+        std::fs::create_dir_all(&web).unwrap();
+        std::fs::write(
+            web.join("package.json"),
+            r#"{"scripts": {"test": "mocha"}, "devDependencies": {"mocha": "^10.0.0"}}"#,
+        )
+        .unwrap();
+        let err = TestFramework::detect(&web).unwrap_err();
+        assert!(err.contains("`mocha`"));
+
+        let api = dir.path().join("api");
+        std::fs::create_dir_all(&api).unwrap();
+        std::fs::write(api.join("pom.xml"), "<project/>").unwrap();
+        let err = TestFramework::detect(&api).unwrap_err();
+        assert!(err.contains("Maven"));
+    }
+}
@@ -0,0 +1,346 @@
+//! Watching the workspace for file changes, so the agent can wait for something to happen on
+//! disk such as a build artifact appearing. Changes are collected from the moment a watch
+//! starts, so the ones that happen between turns are reported by the next call.
+
+use glob::Pattern;
+use ignore::gitignore::Gitignore;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rmcp::model::{ErrorCode, ErrorData};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+pub const MAX_WATCHES: usize = 8;
+/// Changes kept per watch; the oldest are dropped once more happen before they are read
+const MAX_PENDING_CHANGES: usize = 1_000;
+/// Changes tend to come in bursts, like a build writing many files; after the first one the
+/// rest of the burst is waited for this long so it is reported together
+const BURST_WINDOW: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl ChangeKind {
+    fn from_event(kind: &EventKind) -> Option<Self> {
+        match kind {
+            EventKind::Create(_) => Some(ChangeKind::Created),
+            EventKind::Modify(_) => Some(ChangeKind::Modified),
+            EventKind::Remove(_) => Some(ChangeKind::Removed),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Modified => "modified",
+            ChangeKind::Removed => "removed",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    pub path: PathBuf,
+}
+
+#[derive(Default)]
+struct Pending {
+    changes: VecDeque<FileChange>,
+    dropped: usize,
+}
+
+impl Pending {
+    fn push(&mut self, change: FileChange) {
+        // Writing a file often shows up as several modifications in a row
+        if self.changes.back() == Some(&change) {
+            return;
+        }
+        if self.changes.len() >= MAX_PENDING_CHANGES {
+            self.changes.pop_front();
+            self.dropped += 1;
+        }
+        self.changes.push_back(change);
+    }
+}
+
+/// Which changes under `root` a watch reports
+struct Filter {
+    root: PathBuf,
+    pattern: Option<Pattern>,
+    ignore_patterns: Arc<Gitignore>,
+}
+
+impl Filter {
+    fn matches(&self, path: &Path) -> bool {
+        if self
+            .ignore_patterns
+            .matched(path, path.is_dir())
+            .is_ignore()
+        {
+            return false;
+        }
+        match &self.pattern {
+            Some(pattern) => path
+                .strip_prefix(&self.root)
+                .is_ok_and(|relative| pattern.matches_path(relative)),
+            None => true,
+        }
+    }
+}
+
+struct FileWatch {
+    path: PathBuf,
+    pattern: Option<String>,
+    pending: Arc<Mutex<Pending>>,
+    changed: Arc<Notify>,
+    // Dropping the watcher ends the watch
+    _watcher: RecommendedWatcher,
+}
+
+/// Changes a watch saw since they were last read
+#[derive(Debug)]
+pub struct WatchedChanges {
+    pub changes: Vec<FileChange>,
+    /// Changes that were dropped before they could be read
+    pub dropped: usize,
+}
+
+#[derive(Default)]
+pub struct FileWatches {
+    watches: Mutex<HashMap<String, FileWatch>>,
+    next_id: AtomicUsize,
+}
+
+impl FileWatches {
+    /// Start watching `path` and everything below it, reporting only changes to paths that
+    /// match `pattern` relative to `path` when one is given. Returns the id of the watch.
+    pub fn watch(
+        &self,
+        path: &Path,
+        pattern: Option<&str>,
+        ignore_patterns: Arc<Gitignore>,
+    ) -> Result<String, ErrorData> {
+        if self.watches.lock().unwrap().len() >= MAX_WATCHES {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_REQUEST,
+                format!(
+                    "There are already {} watches, stop one before starting another",
+                    MAX_WATCHES
+                ),
+                None,
+            ));
+        }
+        let compiled = pattern
+            .map(Pattern::new)
+            .transpose()
+            .map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, e.to_string(), None))?;
+        let filter = Filter {
+            root: path.to_path_buf(),
+            pattern: compiled,
+            ignore_patterns,
+        };
+
+        let pending = Arc::new(Mutex::new(Pending::default()));
+        let changed = Arc::new(Notify::new());
+        let (handler_pending, handler_changed) = (pending.clone(), changed.clone());
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let Some(kind) = ChangeKind::from_event(&event.kind) else {
+                    return;
+                };
+                let mut pending = handler_pending.lock().unwrap();
+                for path in event.paths.into_iter().filter(|path| filter.matches(path)) {
+                    pending.push(FileChange { kind, path });
+                }
+                if !pending.changes.is_empty() {
+                    handler_changed.notify_one();
+                }
+            })
+            .map_err(|e| watch_error(path, e))?;
+        watcher
+            .watch(path, RecursiveMode::Recursive)
+            .map_err(|e| watch_error(path, e))?;
+
+        let id = format!("watch-{}", self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+        self.watches.lock().unwrap().insert(
+            id.clone(),
+            FileWatch {
+                path: path.to_path_buf(),
+                pattern: pattern.map(String::from),
+                pending,
+                changed,
+                _watcher: watcher,
+            },
+        );
+        Ok(id)
+    }
+
+    fn not_found(&self, id: &str) -> ErrorData {
+        let watches = self.watches.lock().unwrap();
+        let mut active: Vec<String> = watches
+            .iter()
+            .map(|(id, watch)| match &watch.pattern {
+                Some(pattern) => format!("{} ({}, {})", id, watch.path.display(), pattern),
+                None => format!("{} ({})", id, watch.path.display()),
+            })
+            .collect();
+        active.sort();
+        ErrorData::new(
+            ErrorCode::INVALID_PARAMS,
+            if active.is_empty() {
+                format!("No watch '{}', and none are active", id)
+            } else {
+                format!(
+                    "No watch '{}', active watches are: {}",
+                    id,
+                    active.join(", ")
+                )
+            },
+            None,
+        )
+    }
+
+    /// Changes seen by watch `id` since they were last read, waiting up to `timeout` for one
+    /// when there are none yet
+    pub async fn wait(&self, id: &str, timeout: Duration) -> Result<WatchedChanges, ErrorData> {
+        let watch = self
+            .watches
+            .lock()
+            .unwrap()
+            .get(id)
+            .map(|watch| (watch.pending.clone(), watch.changed.clone()));
+        let Some((pending, changed)) = watch else {
+            return Err(self.not_found(id));
+        };
+
+        let deadline = Instant::now() + timeout;
+        let mut waited = false;
+        loop {
+            if !pending.lock().unwrap().changes.is_empty() {
+                if waited {
+                    tokio::time::sleep(BURST_WINDOW).await;
+                }
+                break;
+            }
+            // A notification can be left over from changes an earlier call already read
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero()
+                || tokio::time::timeout(remaining, changed.notified())
+                    .await
+                    .is_err()
+            {
+                break;
+            }
+            waited = true;
+        }
+
+        let mut pending = pending.lock().unwrap();
+        Ok(WatchedChanges {
+            changes: pending.changes.drain(..).collect(),
+            dropped: std::mem::take(&mut pending.dropped),
+        })
+    }
+
+    /// Stop watch `id`
+    pub fn stop(&self, id: &str) -> Result<(), ErrorData> {
+        let removed = self.watches.lock().unwrap().remove(id);
+        match removed {
+            Some(_) => Ok(()),
+            None => Err(self.not_found(id)),
+        }
+    }
+}
+
+fn watch_error(path: &Path, error: notify::Error) -> ErrorData {
+    ErrorData::new(
+        ErrorCode::INTERNAL_ERROR,
+        format!("Failed to watch {}: {}", path.display(), error),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ignore::gitignore::GitignoreBuilder;
+
+    fn no_ignores(root: &Path) -> Arc<Gitignore> {
+        Arc::new(GitignoreBuilder::new(root).build().unwrap())
+    }
+
+    #[test]
+    fn test_repeated_changes_are_reported_once() {
+        let mut pending = Pending::default();
+        let change = FileChange {
+            kind: ChangeKind::Modified,
+            path: PathBuf::from("/workspace/main.rs"),
+        };
+        pending.push(change.clone());
+        pending.push(change.clone());
+        pending.push(FileChange {
+            kind: ChangeKind::Removed,
+            ..change
+        });
+        assert_eq!(pending.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_matches_pattern_and_respects_ignores() {
+        let root = PathBuf::from("/workspace");
+        let mut builder = GitignoreBuilder::new(&root);
+        builder.add_line(None, "*.map").unwrap();
+        let filter = Filter {
+            root: root.clone(),
+            pattern: Some(Pattern::new("dist/*").unwrap()),
+            ignore_patterns: Arc::new(builder.build().unwrap()),
+        };
+
+        assert!(filter.matches(&root.join("dist/app.js")));
+        assert!(!filter.matches(&root.join("dist/app.js.map")));
+        assert!(!filter.matches(&root.join("src/app.js")));
+    }
+
+    #[tokio::test]
+    async fn test_waiting_reports_new_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let watches = FileWatches::default();
+        let id = watches
+            .watch(&root, Some("*.txt"), no_ignores(&root))
+            .unwrap();
+
+        let quiet = watches.wait(&id, Duration::from_millis(100)).await.unwrap();
+        assert!(quiet.changes.is_empty());
+
+        std::fs::write(root.join("ignored.log"), "log").unwrap();
+        std::fs::write(root.join("artifact.txt"), "built").unwrap();
+        let changes = watches.wait(&id, Duration::from_secs(5)).await.unwrap();
+        assert!(changes
+            .changes
+            .iter()
+            .all(|change| change.path == root.join("artifact.txt")));
+        assert!(changes
+            .changes
+            .iter()
+            .any(|change| change.kind == ChangeKind::Created));
+
+        watches.stop(&id).unwrap();
+        assert!(watches.wait(&id, Duration::ZERO).await.is_err());
+    }
+}
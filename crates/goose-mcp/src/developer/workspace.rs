@@ -0,0 +1,247 @@
+//! Finding the package of a monorepo goose was started in, so searches stay inside it and the
+//! text editor refuses to change other packages until the user widens the scope.
+
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+/// `auto` (the default) to scope to the package of the working directory, `off` to work across
+/// the whole repository, or the path of the package to scope to
+pub const PACKAGE_SCOPE_ENV_VAR: &str = "GOOSE_PACKAGE_SCOPE";
+
+/// Files that make a directory a package of its own
+const PACKAGE_MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "go.mod",
+    "pyproject.toml",
+    "setup.py",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+];
+
+/// Files that only the root of a workspace has
+const WORKSPACE_MARKERS: &[(&str, &str)] = &[
+    ("pnpm-workspace.yaml", "pnpm workspace"),
+    ("lerna.json", "Lerna monorepo"),
+    ("nx.json", "Nx workspace"),
+    ("turbo.json", "Turborepo"),
+    ("rush.json", "Rush monorepo"),
+    ("go.work", "Go workspace"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageScope {
+    pub workspace_root: PathBuf,
+    /// What kind of workspace it is, e.g. `Cargo workspace`
+    pub workspace_kind: String,
+    pub package_root: PathBuf,
+}
+
+/// What kind of workspace `dir` is the root of, if any
+fn workspace_kind(dir: &Path) -> Option<String> {
+    if let Ok(manifest) = std::fs::read_to_string(dir.join("Cargo.toml")) {
+        if manifest.lines().any(|line| line.trim() == "[workspace]") {
+            return Some("Cargo workspace".to_string());
+        }
+    }
+    if let Ok(manifest) = std::fs::read_to_string(dir.join("package.json")) {
+        let workspaces = serde_json::from_str::<serde_json::Value>(&manifest)
+            .map(|manifest| manifest.get("workspaces").is_some())
+            .unwrap_or(false);
+        if workspaces {
+            return Some("npm workspace".to_string());
+        }
+    }
+    WORKSPACE_MARKERS
+        .iter()
+        .find(|(marker, _)| dir.join(marker).is_file())
+        .map(|(_, kind)| kind.to_string())
+}
+
+/// The closest workspace root at or above `dir`, without leaving its git repository
+fn find_workspace(dir: &Path) -> Option<(PathBuf, String)> {
+    for ancestor in dir.ancestors() {
+        if let Some(kind) = workspace_kind(ancestor) {
+            return Some((ancestor.to_path_buf(), kind));
+        }
+        if ancestor.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+impl PackageScope {
+    /// The scope for a session started in `cwd`, as set by `GOOSE_PACKAGE_SCOPE`
+    pub fn from_env(cwd: &Path) -> Option<Self> {
+        Self::detect(cwd, env::var(PACKAGE_SCOPE_ENV_VAR).ok().as_deref())
+    }
+
+    /// The scope for a session started in `cwd`, with `setting` as described for
+    /// `PACKAGE_SCOPE_ENV_VAR`. There is none outside of a workspace or at its root.
+    pub fn detect(cwd: &Path, setting: Option<&str>) -> Option<Self> {
+        let cwd = cwd.canonicalize().ok()?;
+        match setting.map(str::trim).unwrap_or("auto") {
+            "" | "auto" => {
+                let (workspace_root, workspace_kind) = find_workspace(&cwd)?;
+                let package_root = cwd
+                    .ancestors()
+                    .take_while(|dir| *dir != workspace_root)
+                    .find(|dir| {
+                        PACKAGE_MANIFESTS
+                            .iter()
+                            .any(|manifest| dir.join(manifest).is_file())
+                    })?
+                    .to_path_buf();
+                Some(Self {
+                    workspace_root,
+                    workspace_kind,
+                    package_root,
+                })
+            }
+            "off" | "none" => None,
+            package => {
+                let package_root = cwd.join(package).canonicalize().ok()?;
+                let (workspace_root, workspace_kind) = package_root
+                    .parent()
+                    .and_then(find_workspace)
+                    .unwrap_or_else(|| (package_root.clone(), "repository".to_string()));
+                Some(Self {
+                    workspace_root,
+                    workspace_kind,
+                    package_root,
+                })
+            }
+        }
+    }
+
+    /// Whether `path`, an absolute path, is inside the package
+    pub fn contains(&self, path: &Path) -> bool {
+        // The file may not exist yet, so `..` is resolved without touching the disk
+        let mut normalized = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::ParentDir => {
+                    normalized.pop();
+                }
+                Component::CurDir => {}
+                other => normalized.push(other),
+            }
+        }
+        let normalized = match normalized
+            .parent()
+            .and_then(|parent| parent.canonicalize().ok())
+        {
+            Some(parent) => parent.join(normalized.file_name().unwrap_or_default()),
+            None => normalized,
+        };
+        normalized.starts_with(&self.package_root)
+    }
+
+    pub fn package_name(&self) -> String {
+        self.package_root
+            .strip_prefix(&self.workspace_root)
+            .unwrap_or(&self.package_root)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn instructions(&self) -> String {
+        format!(
+            "This directory is the `{package}` package of a {kind} rooted at {root}. Keep \
+             searches and file listings to {dir} (e.g. `rg --files {dir}`) rather than the whole \
+             repository, and only look at other packages when the task depends on them. The text \
+             editor refuses to change files outside the package. Shell commands, terminals and \
+             background processes start in the package but are not confined to it, so don't use \
+             them to change other packages either. If the task needs changes elsewhere, stop and \
+             ask the user to widen the package scope.",
+            package = self.package_name(),
+            kind = self.workspace_kind,
+            root = self.workspace_root.display(),
+            dir = self.package_root.display(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn cargo_workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        for package in ["billing", "ledger"] {
+            fs::create_dir_all(root.join("crates").join(package).join("src")).unwrap();
+            fs::write(
+                root.join("crates").join(package).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n", package),
+            )
+            .unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_detects_package_of_working_directory() {
+        let dir = cargo_workspace();
+        let root = dir.path().canonicalize().unwrap();
+        let scope =
+            PackageScope::detect(&root.join("crates/billing/src"), None).expect("a package scope");
+        assert_eq!(scope.workspace_root, root);
+        assert_eq!(scope.workspace_kind, "Cargo workspace");
+        assert_eq!(scope.package_root, root.join("crates/billing"));
+        assert_eq!(scope.package_name(), "crates/billing");
+    }
+
+    #[test]
+    fn test_no_scope_at_workspace_root_or_when_off() {
+        let dir = cargo_workspace();
+        assert!(PackageScope::detect(dir.path(), None).is_none());
+        assert!(PackageScope::detect(&dir.path().join("crates/billing"), Some("off")).is_none());
+    }
+
+    #[test]
+    fn test_npm_workspaces_and_explicit_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{"name": "shop", "workspaces": ["apps/*"]}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("apps/web")).unwrap();
+        fs::write(root.join("apps/web/package.json"), r#"{"name": "web"}"#).unwrap();
+
+        let scope = PackageScope::detect(&root, Some("apps/web")).expect("a package scope");
+        assert_eq!(scope.workspace_kind, "npm workspace");
+        assert_eq!(scope.package_root, root.join("apps/web"));
+    }
+
+    #[test]
+    fn test_instructions_leave_the_scope_to_the_user() {
+        let dir = cargo_workspace();
+        let scope = PackageScope::detect(&dir.path().join("crates/billing"), None).unwrap();
+        let instructions = scope.instructions();
+        assert!(instructions.contains("ask the user"));
+        assert!(!instructions.contains(PACKAGE_SCOPE_ENV_VAR));
+    }
+
+    #[test]
+    fn test_contains() {
+        let dir = cargo_workspace();
+        let root = dir.path().canonicalize().unwrap();
+        let scope = PackageScope::detect(&root.join("crates/billing"), None).unwrap();
+        assert!(scope.contains(&root.join("crates/billing/src/new_file.rs")));
+        assert!(!scope.contains(&root.join("crates/ledger/src/lib.rs")));
+        assert!(!scope.contains(&root.join("crates/billing/../ledger/Cargo.toml")));
+        assert!(!scope.contains(&root.join("Cargo.toml")));
+    }
+}
@@ -317,6 +317,24 @@ impl MemoryRouter {
         base_dir.join(format!("{}.txt", category))
     }
 
+    /// Refuse to delete memories that are under a legal hold. goose marks held memories
+    /// with a `memory.hold` file next to the memory directory rather than inside it, so the
+    /// marker is never read back as a category.
+    fn ensure_not_held(&self, is_global: bool) -> io::Result<()> {
+        let base_dir = if is_global {
+            &self.global_memory_dir
+        } else {
+            &self.local_memory_dir
+        };
+        if base_dir.with_extension("hold").exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "These memories are under a legal hold and can't be removed until it is released",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn retrieve_all(&self, is_global: bool) -> io::Result<HashMap<String, Vec<String>>> {
         let base_dir = if is_global {
             &self.global_memory_dir
@@ -411,6 +429,7 @@ impl MemoryRouter {
         memory_content: &str,
         is_global: bool,
     ) -> io::Result<()> {
+        self.ensure_not_held(is_global)?;
         let memory_file_path = self.get_memory_file(category, is_global);
         if !memory_file_path.exists() {
             return Ok(());
@@ -433,6 +452,7 @@ impl MemoryRouter {
     }
 
     pub fn clear_memory(&self, category: &str, is_global: bool) -> io::Result<()> {
+        self.ensure_not_held(is_global)?;
         let memory_file_path = self.get_memory_file(category, is_global);
         if memory_file_path.exists() {
             fs::remove_file(memory_file_path)?;
@@ -442,6 +462,7 @@ impl MemoryRouter {
     }
 
     pub fn clear_all_global_or_local_memories(&self, is_global: bool) -> io::Result<()> {
+        self.ensure_not_held(is_global)?;
         let base_dir = if is_global {
             &self.global_memory_dir
         } else {
@@ -687,6 +708,38 @@ mod tests {
         assert!(router.clear_all_global_or_local_memories(true).is_ok());
     }
 
+    #[test]
+    fn test_held_memories_cannot_be_removed() {
+        let temp_dir = tempdir().unwrap();
+        let memory_base = temp_dir.path().join("held_memory");
+
+        let router = MemoryRouter {
+            tools: vec![],
+            instructions: String::new(),
+            global_memory_dir: memory_base.join("global"),
+            local_memory_dir: memory_base.join("local"),
+        };
+        router
+            .remember("context", "project", "keep me", &[], false)
+            .unwrap();
+        fs::write(memory_base.join("local.hold"), "hold-1\n").unwrap();
+
+        let err = router.clear_memory("project", false).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(router
+            .remove_specific_memory("project", "keep me", false)
+            .is_err());
+        assert!(router.clear_all_global_or_local_memories(false).is_err());
+        assert!(router.local_memory_dir.join("project.txt").exists());
+        assert_eq!(router.retrieve_all(false).unwrap().len(), 1);
+
+        // global memories aren't covered by the local hold
+        router
+            .remember("context", "global", "forget me", &[], true)
+            .unwrap();
+        assert!(router.clear_memory("global", true).is_ok());
+    }
+
     #[test]
     fn test_remember_retrieve_clear_workflow() {
         let temp_dir = tempdir().unwrap();
@@ -1,4 +1,9 @@
 // We'll generate the schema at runtime since we need access to the complete application context
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=src/");
+    println!("cargo:rerun-if-changed=proto/");
+    tonic_build::configure()
+        .build_client(true)
+        .compile_protos(&["proto/goose.proto"], &["proto"])?;
+    Ok(())
 }
@@ -7,7 +7,6 @@ use etcetera::{choose_app_strategy, AppStrategy};
 use goose::agents::Agent;
 use goose::config::APP_STRATEGY;
 use goose::scheduler_factory::SchedulerFactory;
-use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 
 use goose::providers::pricing::initialize_pricing_cache;
@@ -27,8 +26,18 @@ pub async fn run() -> Result<()> {
         );
     }
 
-    let secret_key =
-        std::env::var("GOOSE_SERVER__SECRET_KEY").unwrap_or_else(|_| "test".to_string());
+    let secret_key = match std::env::var("GOOSE_SERVER__SECRET_KEY") {
+        Ok(key) => key,
+        // The built-in key is fine for the desktop app talking over loopback, but would leave
+        // a server reachable from other machines (e.g. in a container) wide open
+        Err(_) if settings.socket_path.is_none() && !settings.socket_addr().ip().is_loopback() => {
+            anyhow::bail!(
+                "GOOSE_SERVER__SECRET_KEY must be set when listening on {}",
+                settings.socket_addr()
+            );
+        }
+        Err(_) => "test".to_string(),
+    };
 
     let new_agent = Agent::new();
     let agent_ref = Arc::new(new_agent);
@@ -45,12 +54,74 @@ pub async fn run() -> Result<()> {
     // NEW: Provide scheduler access to the agent
     agent_ref.set_scheduler(scheduler_instance).await;
 
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    let run_queue_path = choose_app_strategy(APP_STRATEGY.clone())?
+        .data_dir()
+        .join("run_queue.json");
+    crate::routes::a2a::resume_queued(app_state.clone(), run_queue_path).await;
+
+    // Session digests, if configured, go out for as long as the server runs
+    tokio::spawn(goose::digest::run_scheduled());
+
+    let cors = crate::proxy::cors_layer(&settings.cors_origins);
+
+    if let Some(grpc_addr) = settings.grpc_socket_addr() {
+        let service = crate::grpc::GooseService::server(app_state.clone());
+        if !settings.socket_addr().ip().is_loopback() {
+            tracing::warn!(
+                "gRPC has no TLS, so it listens on {} only; tunnel to it for remote access",
+                grpc_addr
+            );
+        }
+        info!("gRPC listening on {}", grpc_addr);
+        tokio::spawn(async move {
+            if let Err(e) = tonic::transport::Server::builder()
+                .add_service(service)
+                .serve(grpc_addr)
+                .await
+            {
+                tracing::error!("gRPC server stopped: {}", e);
+            }
+        });
+    }
+
+    let proxy = settings.proxy_settings();
+    let mut app = crate::routes::configure(app_state);
+    if !proxy.base_path.is_empty() {
+        info!("serving under {}", proxy.base_path);
+        app = axum::Router::new().nest(&proxy.base_path, app);
+    }
+    let app = app
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(proxy),
+            crate::proxy::forwarded_headers,
+        ))
+        .layer(cors);
+
+    if let Some(path) = &settings.socket_path {
+        if settings.tls_cert.is_some() {
+            tracing::warn!("TLS settings are ignored when serving on a local socket");
+        }
+        return crate::local_socket::serve(path, app, &secret_key).await;
+    }
 
-    let app = crate::routes::configure(app_state).layer(cors);
+    let app = app.into_make_service_with_connect_info::<std::net::SocketAddr>();
+
+    if let Some(tls) = settings.tls_settings()? {
+        let config = tls.rustls_config()?;
+        info!(
+            "listening on https://{}{}",
+            settings.socket_addr(),
+            if tls.client_ca.is_some() {
+                " (client certificates required)"
+            } else {
+                ""
+            }
+        );
+        axum_server::bind_rustls(settings.socket_addr(), config)
+            .serve(app)
+            .await?;
+        return Ok(());
+    }
 
     let listener = tokio::net::TcpListener::bind(settings.socket_addr()).await?;
     info!("listening on {}", listener.local_addr()?);
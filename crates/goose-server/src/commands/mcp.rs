@@ -1,3 +1,4 @@
+use crate::mcp_router::GooseRouter;
 use anyhow::{anyhow, Result};
 use goose_mcp::{ComputerControllerRouter, DeveloperRouter, MemoryRouter, TutorialRouter};
 use mcp_server::router::RouterService;
@@ -19,6 +20,7 @@ pub async fn run(name: &str) -> Result<()> {
         "computercontroller" => Some(Box::new(RouterService(ComputerControllerRouter::new()))),
         "memory" => Some(Box::new(RouterService(MemoryRouter::new()))),
         "tutorial" => Some(Box::new(RouterService(TutorialRouter::new()))),
+        "goose" => Some(Box::new(RouterService(GooseRouter::new()))),
         _ => None,
     };
 
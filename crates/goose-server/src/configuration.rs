@@ -1,7 +1,10 @@
 use crate::error::{to_env_var, ConfigError};
+use crate::proxy::ProxySettings;
+use crate::tls::TlsSettings;
 use config::{Config, Environment};
 use serde::Deserialize;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::PathBuf;
 
 #[derive(Debug, Default, Deserialize)]
 pub struct Settings {
@@ -9,6 +12,35 @@ pub struct Settings {
     pub host: String,
     #[serde(default = "default_port")]
     pub port: u16,
+    /// Serve the gRPC API on this loopback port as well, set with GOOSE_GRPC_PORT
+    #[serde(default)]
+    pub grpc_port: Option<u16>,
+    /// Listen on this Unix socket (or `\\.\pipe\name` on Windows) instead of host and
+    /// port, set with GOOSE_SOCKET_PATH
+    #[serde(default)]
+    pub socket_path: Option<PathBuf>,
+    /// Origins allowed to call the API from a browser, comma separated in GOOSE_CORS_ORIGINS.
+    /// Any origin is allowed when empty.
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    /// Reverse proxies whose X-Forwarded-* headers are trusted, set with GOOSE_TRUSTED_PROXIES
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Serve every route under this prefix, e.g. `/goose`, set with GOOSE_BASE_PATH
+    #[serde(default)]
+    pub base_path: String,
+    /// A proxy in front terminates TLS, set with GOOSE_TLS_TERMINATED
+    #[serde(default)]
+    pub tls_terminated: bool,
+    /// Serve HTTPS with this PEM certificate chain, set with GOOSE_TLS_CERT
+    #[serde(default)]
+    pub tls_cert: Option<PathBuf>,
+    /// Private key for tls_cert, set with GOOSE_TLS_KEY
+    #[serde(default)]
+    pub tls_key: Option<PathBuf>,
+    /// Require client certificates signed by this CA bundle, set with GOOSE_TLS_CLIENT_CA
+    #[serde(default)]
+    pub tls_client_ca: Option<PathBuf>,
 }
 
 impl Settings {
@@ -18,6 +50,44 @@ impl Settings {
             .expect("Failed to parse socket address")
     }
 
+    /// The gRPC API has no TLS, so it only listens on loopback whatever the host is; the
+    /// secret key it carries must not cross the network in the clear
+    pub fn grpc_socket_addr(&self) -> Option<SocketAddr> {
+        let ip = if self.socket_addr().is_ipv6() {
+            IpAddr::V6(Ipv6Addr::LOCALHOST)
+        } else {
+            IpAddr::V4(Ipv4Addr::LOCALHOST)
+        };
+        self.grpc_port.map(|port| SocketAddr::new(ip, port))
+    }
+
+    pub fn proxy_settings(&self) -> ProxySettings {
+        ProxySettings {
+            trusted_proxies: self.trusted_proxies.clone(),
+            base_path: normalize_base_path(&self.base_path),
+            // Serving TLS natively means clients reach us over https just the same
+            tls_terminated: self.tls_terminated || self.tls_cert.is_some(),
+        }
+    }
+
+    /// Native TLS settings, or None to serve plain HTTP
+    pub fn tls_settings(&self) -> Result<Option<TlsSettings>, ConfigError> {
+        match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => Ok(Some(TlsSettings {
+                cert: cert.clone(),
+                key: key.clone(),
+                client_ca: self.tls_client_ca.clone(),
+            })),
+            (None, None) if self.tls_client_ca.is_none() => Ok(None),
+            (_, None) => Err(ConfigError::MissingEnvVar {
+                env_var: "GOOSE_TLS_KEY".to_string(),
+            }),
+            (None, _) => Err(ConfigError::MissingEnvVar {
+                env_var: "GOOSE_TLS_CERT".to_string(),
+            }),
+        }
+    }
+
     pub fn new() -> Result<Self, ConfigError> {
         Self::load_and_validate()
     }
@@ -33,7 +103,10 @@ impl Settings {
                 Environment::with_prefix("GOOSE")
                     .prefix_separator("_")
                     .separator("__")
-                    .try_parsing(true),
+                    .try_parsing(true)
+                    .list_separator(",")
+                    .with_list_parse_key("cors_origins")
+                    .with_list_parse_key("trusted_proxies"),
             )
             .build()?;
 
@@ -74,6 +147,16 @@ fn default_port() -> u16 {
     3000
 }
 
+/// Turns `goose/` or `/goose/` into `/goose`, and `/` into no prefix at all
+fn normalize_base_path(path: &str) -> String {
+    let trimmed = path.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", trimmed)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,8 +166,57 @@ mod tests {
         let server_settings = Settings {
             host: "127.0.0.1".to_string(),
             port: 3000,
+            grpc_port: Some(3001),
+            ..Default::default()
         };
         let addr = server_settings.socket_addr();
         assert_eq!(addr.to_string(), "127.0.0.1:3000");
+        let grpc_addr = server_settings.grpc_socket_addr().unwrap();
+        assert_eq!(grpc_addr.to_string(), "127.0.0.1:3001");
+    }
+
+    #[test]
+    fn test_grpc_stays_on_loopback() {
+        let mut settings = Settings {
+            host: "0.0.0.0".to_string(),
+            port: 3000,
+            grpc_port: Some(3001),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.grpc_socket_addr().unwrap().to_string(),
+            "127.0.0.1:3001"
+        );
+
+        settings.host = "[::]".to_string();
+        assert_eq!(
+            settings.grpc_socket_addr().unwrap().to_string(),
+            "[::1]:3001"
+        );
+    }
+
+    #[test]
+    fn test_tls_settings() {
+        let mut settings = Settings::default();
+        assert!(settings.tls_settings().unwrap().is_none());
+
+        settings.tls_client_ca = Some(PathBuf::from("ca.pem"));
+        assert!(matches!(
+            settings.tls_settings(),
+            Err(ConfigError::MissingEnvVar { env_var }) if env_var == "GOOSE_TLS_KEY"
+        ));
+
+        settings.tls_cert = Some(PathBuf::from("cert.pem"));
+        settings.tls_key = Some(PathBuf::from("key.pem"));
+        let tls = settings.tls_settings().unwrap().unwrap();
+        assert_eq!(tls.client_ca, Some(PathBuf::from("ca.pem")));
+    }
+
+    #[test]
+    fn test_normalize_base_path() {
+        assert_eq!(normalize_base_path(""), "");
+        assert_eq!(normalize_base_path("/"), "");
+        assert_eq!(normalize_base_path("goose/"), "/goose");
+        assert_eq!(normalize_base_path("/team/goose"), "/team/goose");
     }
 }
@@ -0,0 +1,359 @@
+//! gRPC service mirroring the session and agent REST routes, for callers that prefer
+//! protobuf contracts. Enabled by setting GOOSE_GRPC_PORT.
+
+use crate::state::AppState;
+use futures::StreamExt;
+use goose::agents::{AgentEvent, SessionConfig};
+use goose::conversation::message::{Message, MessageContent};
+use goose::conversation::Conversation;
+use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::{Permission, PermissionConfirmation};
+use goose::session::{self, info::SortOrder, SessionInfo};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tonic::{Request, Response, Status};
+
+pub mod proto {
+    tonic::include_proto!("goose.v1");
+}
+
+use proto::goose_server::{Goose, GooseServer};
+
+const SECRET_KEY_METADATA: &str = "x-secret-key";
+
+/// Same check as `verify_secret_key` for REST, against request metadata
+fn check_secret_key(secret_key: &str, request: &Request<()>) -> Result<(), Status> {
+    match request.metadata().get(SECRET_KEY_METADATA) {
+        Some(value) if value.to_str().is_ok_and(|v| v == secret_key) => Ok(()),
+        _ => Err(Status::unauthenticated("invalid or missing x-secret-key")),
+    }
+}
+
+fn to_proto_message(message: &Message) -> proto::Message {
+    let content = message
+        .content
+        .iter()
+        .map(|content| {
+            let kind = match content {
+                MessageContent::Text(text) if text.annotations.is_none() => {
+                    proto::content::Kind::Text(text.text.clone())
+                }
+                other => {
+                    proto::content::Kind::Json(serde_json::to_string(other).unwrap_or_default())
+                }
+            };
+            proto::Content { kind: Some(kind) }
+        })
+        .collect();
+    let role = match message.role {
+        rmcp::model::Role::User => proto::Role::User,
+        rmcp::model::Role::Assistant => proto::Role::Assistant,
+    };
+    proto::Message {
+        id: message.id.clone().unwrap_or_default(),
+        role: role as i32,
+        created: message.created,
+        content,
+    }
+}
+
+fn from_proto_message(message: proto::Message) -> Result<Message, Status> {
+    let role = match proto::Role::try_from(message.role) {
+        Ok(proto::Role::User) => rmcp::model::Role::User,
+        Ok(proto::Role::Assistant) => rmcp::model::Role::Assistant,
+        _ => return Err(Status::invalid_argument("message role must be set")),
+    };
+    let content = message
+        .content
+        .into_iter()
+        .filter_map(|content| content.kind)
+        .map(|kind| match kind {
+            proto::content::Kind::Text(text) => Ok(MessageContent::text(text)),
+            proto::content::Kind::Json(json) => serde_json::from_str(&json)
+                .map_err(|e| Status::invalid_argument(format!("invalid message content: {}", e))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let converted = Message::new(role, message.created, content);
+    Ok(if message.id.is_empty() {
+        converted
+    } else {
+        converted.with_id(message.id)
+    })
+}
+
+fn to_proto_summary(info: &SessionInfo) -> proto::SessionSummary {
+    proto::SessionSummary {
+        id: info.id.clone(),
+        description: info.metadata.description.clone(),
+        working_dir: info.metadata.working_dir.to_string_lossy().into_owned(),
+        modified: info.modified.clone(),
+        message_count: info.metadata.message_count as u64,
+        total_tokens: info.metadata.total_tokens,
+    }
+}
+
+pub struct GooseService {
+    state: Arc<AppState>,
+}
+
+impl GooseService {
+    /// The service with the secret key check applied to every call
+    pub fn server(
+        state: Arc<AppState>,
+    ) -> tonic::service::interceptor::InterceptedService<
+        GooseServer<GooseService>,
+        impl tonic::service::Interceptor + Clone,
+    > {
+        let secret_key = state.secret_key.clone();
+        GooseServer::with_interceptor(GooseService { state }, move |request: Request<()>| {
+            check_secret_key(&secret_key, &request)?;
+            Ok(request)
+        })
+    }
+}
+
+async fn send_event(
+    tx: &mpsc::Sender<Result<proto::ReplyEvent, Status>>,
+    event: proto::reply_event::Event,
+    cancel_token: &CancellationToken,
+) {
+    let event = proto::ReplyEvent { event: Some(event) };
+    if tx.send(Ok(event)).await.is_err() {
+        tracing::info!("gRPC client hung up");
+        cancel_token.cancel();
+    }
+}
+
+#[tonic::async_trait]
+impl Goose for GooseService {
+    type ReplyStream = ReceiverStream<Result<proto::ReplyEvent, Status>>;
+
+    async fn reply(
+        &self,
+        request: Request<proto::ReplyRequest>,
+    ) -> Result<Response<Self::ReplyStream>, Status> {
+        use proto::reply_event::Event;
+
+        let request = request.into_inner();
+        let messages = request
+            .messages
+            .into_iter()
+            .map(from_proto_message)
+            .collect::<Result<Vec<_>, _>>()?;
+        let agent = self
+            .state
+            .get_agent()
+            .await
+            .map_err(|_| Status::failed_precondition("no agent configured"))?;
+        let session_id = request
+            .session_id
+            .unwrap_or_else(session::generate_session_id);
+        let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let working_dir = PathBuf::from(request.working_dir);
+
+        let (tx, rx) = mpsc::channel(100);
+        let cancel_token = CancellationToken::new();
+        let mut conversation = Conversation::new_unvalidated(messages);
+        let session_config = SessionConfig {
+            id: session::Identifier::Name(session_id),
+            working_dir: working_dir.clone(),
+            schedule_id: None,
+            execution_mode: None,
+            max_turns: None,
+            run_limits: Default::default(),
+            critique: None,
+            retry_config: None,
+        };
+
+        tokio::spawn(async move {
+            let mut stream = match agent
+                .reply(
+                    conversation.clone(),
+                    Some(session_config),
+                    Some(cancel_token.clone()),
+                )
+                .await
+            {
+                Ok(stream) => stream,
+                Err(e) => {
+                    send_event(&tx, Event::Error(e.to_string()), &cancel_token).await;
+                    return;
+                }
+            };
+            let saved_message_count = conversation.len();
+
+            loop {
+                let event = tokio::select! {
+                    _ = cancel_token.cancelled() => break,
+                    event = stream.next() => event,
+                };
+                let event = match event {
+                    Some(Ok(AgentEvent::Message(message))) => {
+                        conversation.push(message.clone());
+                        Event::Message(to_proto_message(&message))
+                    }
+                    Some(Ok(AgentEvent::HistoryReplaced(messages))) => {
+                        conversation = Conversation::new_unvalidated(messages);
+                        continue;
+                    }
+                    Some(Ok(AgentEvent::ModelChange { model, mode })) => {
+                        Event::ModelChange(proto::ModelChange { model, mode })
+                    }
+                    Some(Ok(AgentEvent::McpNotification((request_id, notification)))) => {
+                        Event::Notification(proto::Notification {
+                            request_id,
+                            json: serde_json::to_string(&notification).unwrap_or_default(),
+                        })
+                    }
+                    Some(Err(e)) => {
+                        send_event(&tx, Event::Error(e.to_string()), &cancel_token).await;
+                        break;
+                    }
+                    None => break,
+                };
+                send_event(&tx, event, &cancel_token).await;
+            }
+
+            if conversation.len() > saved_message_count {
+                let provider = agent.provider().await.ok();
+                if let Err(e) = session::persist_messages(
+                    &session_path,
+                    &conversation,
+                    provider,
+                    Some(working_dir),
+                )
+                .await
+                {
+                    tracing::error!("Failed to store session history: {:?}", e);
+                }
+            }
+            send_event(&tx, Event::Finish("stop".to_string()), &cancel_token).await;
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn confirm_permission(
+        &self,
+        request: Request<proto::ConfirmPermissionRequest>,
+    ) -> Result<Response<proto::ConfirmPermissionResponse>, Status> {
+        let request = request.into_inner();
+        let agent = self
+            .state
+            .get_agent()
+            .await
+            .map_err(|_| Status::failed_precondition("no agent configured"))?;
+        let permission = match proto::Permission::try_from(request.permission) {
+            Ok(proto::Permission::AlwaysAllow) => Permission::AlwaysAllow,
+            Ok(proto::Permission::AllowOnce) => Permission::AllowOnce,
+            _ => Permission::DenyOnce,
+        };
+        let principal_type = match proto::PrincipalType::try_from(request.principal_type) {
+            Ok(proto::PrincipalType::Extension) => PrincipalType::Extension,
+            _ => PrincipalType::Tool,
+        };
+        agent
+            .handle_confirmation(
+                request.id,
+                PermissionConfirmation {
+                    principal_type,
+                    permission,
+                },
+            )
+            .await;
+        Ok(Response::new(proto::ConfirmPermissionResponse {}))
+    }
+
+    async fn list_sessions(
+        &self,
+        _request: Request<proto::ListSessionsRequest>,
+    ) -> Result<Response<proto::ListSessionsResponse>, Status> {
+        let sessions = session::get_valid_sorted_sessions(SortOrder::Descending)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(proto::ListSessionsResponse {
+            sessions: sessions.iter().map(to_proto_summary).collect(),
+        }))
+    }
+
+    async fn get_session(
+        &self,
+        request: Request<proto::GetSessionRequest>,
+    ) -> Result<Response<proto::Session>, Status> {
+        let session_id = request.into_inner().session_id;
+        let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let metadata = session::read_metadata(&session_path)
+            .map_err(|_| Status::not_found("session not found"))?;
+        let messages =
+            session::read_messages(&session_path).map_err(|e| Status::internal(e.to_string()))?;
+        let modified = session_path
+            .metadata()
+            .and_then(|m| m.modified())
+            .map(|time| {
+                chrono::DateTime::<chrono::Utc>::from(time)
+                    .format("%Y-%m-%d %H:%M:%S UTC")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let info = SessionInfo {
+            id: session_id,
+            path: session_path.to_string_lossy().into_owned(),
+            modified,
+            metadata,
+        };
+        Ok(Response::new(proto::Session {
+            summary: Some(to_proto_summary(&info)),
+            messages: messages.messages().iter().map(to_proto_message).collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_round_trip() {
+        let message = Message::assistant()
+            .with_text("Listing files")
+            .with_tool_request(
+                "call_1",
+                Ok(mcp_core::ToolCall::new(
+                    "developer__shell",
+                    serde_json::json!({"command": "ls"}),
+                )),
+            )
+            .with_id("msg_1");
+
+        let proto_message = to_proto_message(&message);
+        assert_eq!(proto_message.role, proto::Role::Assistant as i32);
+        assert_eq!(
+            proto_message.content[0].kind,
+            Some(proto::content::Kind::Text("Listing files".to_string()))
+        );
+        assert!(matches!(
+            proto_message.content[1].kind,
+            Some(proto::content::Kind::Json(_))
+        ));
+
+        let round_trip = from_proto_message(proto_message).unwrap();
+        assert_eq!(round_trip, message);
+    }
+
+    #[test]
+    fn test_secret_key_required() {
+        let mut request = Request::new(());
+        assert_eq!(
+            check_secret_key("secret", &request).unwrap_err().code(),
+            tonic::Code::Unauthenticated
+        );
+        request
+            .metadata_mut()
+            .insert(SECRET_KEY_METADATA, "secret".parse().unwrap());
+        assert!(check_secret_key("secret", &request).is_ok());
+    }
+}
@@ -1,6 +1,9 @@
+pub mod local_socket;
 pub mod openapi;
+pub mod proxy;
 pub mod routes;
 pub mod state;
+pub mod tls;
 
 // Re-export commonly used items
 pub use openapi::*;
@@ -0,0 +1,251 @@
+//! Serving goosed over a Unix domain socket, or a named pipe on Windows, instead of TCP.
+//!
+//! Access is controlled by the operating system rather than the secret key. The Unix socket
+//! is created readable and writable by its owner only, and connections are accepted only
+//! from processes running as the same user, checked with the peer's credentials. Windows
+//! pipes reject remote clients and only grant write access to the creating user. Requests
+//! from an accepted peer are treated as carrying the server's secret key.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use http::HeaderValue;
+use std::path::Path;
+use std::sync::Arc;
+
+async fn trust_local_peer(
+    State(secret_key): State<Arc<str>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Ok(value) = HeaderValue::from_str(&secret_key) {
+        request.headers_mut().insert("X-Secret-Key", value);
+    }
+    next.run(request).await
+}
+
+#[cfg(unix)]
+pub async fn serve(path: &Path, app: Router, secret_key: &str) -> anyhow::Result<()> {
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        Arc::<str>::from(secret_key),
+        trust_local_peer,
+    ));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    remove_stale_socket(path)?;
+    let listener = unix::SameUserListener::bind(path)?;
+
+    tracing::info!("listening on unix:{}", path.display());
+    let result = axum::serve(listener, app).await;
+    let _ = std::fs::remove_file(path);
+    Ok(result?)
+}
+
+/// A socket left behind by a previous run would make bind fail. Only a socket nobody is
+/// listening on is removed; anything else at the path is an error.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> anyhow::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    if !metadata.file_type().is_socket() {
+        anyhow::bail!("{} exists and is not a socket", path.display());
+    }
+    match std::os::unix::net::UnixStream::connect(path) {
+        Ok(_) => anyhow::bail!("another server is listening on {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+            std::fs::remove_file(path)?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::path::Path;
+    use tokio::net::{unix::SocketAddr, UnixListener, UnixStream};
+
+    /// Accepts clients on a Unix socket that run as the same user as the server
+    pub struct SameUserListener {
+        listener: UnixListener,
+        uid: libc::uid_t,
+    }
+
+    impl SameUserListener {
+        pub fn bind(path: &Path) -> std::io::Result<Self> {
+            // Created with owner-only permissions from the start, so there is no moment
+            // where others could connect. The umask is process wide, so restore it at once.
+            let previous = unsafe { libc::umask(0o177) };
+            let listener = UnixListener::bind(path);
+            unsafe { libc::umask(previous) };
+            Ok(Self {
+                listener: listener?,
+                uid: unsafe { libc::geteuid() },
+            })
+        }
+    }
+
+    impl axum::serve::Listener for SameUserListener {
+        type Io = UnixStream;
+        type Addr = SocketAddr;
+
+        async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+            loop {
+                let (stream, addr) = match self.listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        tracing::error!("unix socket accept failed: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                match stream.peer_cred() {
+                    Ok(cred) if cred.uid() == self.uid => return (stream, addr),
+                    Ok(cred) => {
+                        tracing::warn!("refused unix socket client running as uid {}", cred.uid())
+                    }
+                    Err(e) => tracing::warn!("refused unix socket client: {}", e),
+                }
+            }
+        }
+
+        fn local_addr(&self) -> std::io::Result<Self::Addr> {
+            self.listener.local_addr()
+        }
+    }
+}
+
+#[cfg(windows)]
+pub async fn serve(path: &Path, app: Router, secret_key: &str) -> anyhow::Result<()> {
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        Arc::<str>::from(secret_key),
+        trust_local_peer,
+    ));
+
+    let listener = pipe::NamedPipeListener::bind(path.to_string_lossy().into_owned())?;
+    tracing::info!("listening on {}", path.display());
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(windows)]
+mod pipe {
+    use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+
+    /// Accepts clients on a named pipe, keeping one idle instance ready for the next one
+    pub struct NamedPipeListener {
+        name: String,
+        next: NamedPipeServer,
+    }
+
+    impl NamedPipeListener {
+        pub fn bind(name: String) -> std::io::Result<Self> {
+            let next = ServerOptions::new()
+                .first_pipe_instance(true)
+                .reject_remote_clients(true)
+                .create(&name)?;
+            Ok(Self { name, next })
+        }
+    }
+
+    impl axum::serve::Listener for NamedPipeListener {
+        type Io = NamedPipeServer;
+        type Addr = String;
+
+        async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+            loop {
+                if let Err(e) = self.next.connect().await {
+                    tracing::error!("named pipe connect failed: {}", e);
+                    continue;
+                }
+                match ServerOptions::new()
+                    .reject_remote_clients(true)
+                    .create(&self.name)
+                {
+                    Ok(next) => {
+                        let connected = std::mem::replace(&mut self.next, next);
+                        return (connected, self.name.clone());
+                    }
+                    Err(e) => {
+                        tracing::error!("failed to create named pipe instance: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        }
+
+        fn local_addr(&self) -> std::io::Result<Self::Addr> {
+            Ok(self.name.clone())
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_serves_over_owner_only_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("goosed.sock");
+        let app = Router::new().route(
+            "/whoami",
+            axum::routing::get(|headers: http::HeaderMap| async move {
+                headers["X-Secret-Key"].to_str().unwrap().to_string()
+            }),
+        );
+
+        let server_path = path.clone();
+        tokio::spawn(async move { serve(&server_path, app, "sekret").await });
+        while !path.exists() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(b"GET /whoami HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with("sekret"));
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_only_stale_sockets_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "keep me").unwrap();
+        assert!(remove_stale_socket(&file).is_err());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep me");
+
+        let live = dir.path().join("live.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&live).unwrap();
+        assert!(remove_stale_socket(&live).is_err());
+        assert!(live.exists());
+
+        let stale = dir.path().join("stale.sock");
+        drop(std::os::unix::net::UnixListener::bind(&stale).unwrap());
+        remove_stale_socket(&stale).unwrap();
+        assert!(!stale.exists());
+
+        remove_stale_socket(&dir.path().join("missing.sock")).unwrap();
+    }
+}
@@ -1,10 +1,15 @@
 mod commands;
 mod configuration;
 mod error;
+mod grpc;
+mod local_socket;
 mod logging;
+mod mcp_router;
 mod openapi;
+mod proxy;
 mod routes;
 mod state;
+mod tls;
 
 use clap::{Parser, Subcommand};
 
@@ -0,0 +1,446 @@
+//! goose itself as an MCP server, so IDEs and other MCP clients can drive it.
+//! Clients launch it over stdio with `goosed mcp goose`.
+
+use futures::StreamExt;
+use goose::agents::{Agent, AgentEvent, SessionConfig};
+use goose::config::Config;
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use goose::model::ModelConfig;
+use goose::providers::create;
+use goose::recipe::build_recipe::build_recipe_from_template;
+use goose::recipe::read_recipe_file_content::read_recipe_file;
+use goose::recipe::scaffold;
+use goose::session::{self, info::get_valid_sorted_sessions, info::SortOrder};
+use goose_mcp::MemoryRouter;
+use indoc::indoc;
+use mcp_core::{
+    handler::{PromptError, ResourceError},
+    protocol::ServerCapabilities,
+};
+use mcp_server::router::CapabilitiesBuilder;
+use mcp_server::Router;
+use rmcp::model::{
+    Content, ErrorCode, ErrorData, JsonRpcMessage, Prompt, Resource, Role, Tool, ToolAnnotations,
+};
+use rmcp::object;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::{future::Future, pin::Pin};
+use tokio::sync::mpsc;
+
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+/// Characters of context either side of a search match
+const SNIPPET_RADIUS: usize = 80;
+
+fn invalid_params(message: impl Into<String>) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_PARAMS, message.into(), None)
+}
+
+fn internal_error(message: impl std::fmt::Display) -> ErrorData {
+    ErrorData::new(ErrorCode::INTERNAL_ERROR, message.to_string(), None)
+}
+
+#[derive(Clone)]
+pub struct GooseRouter {
+    tools: Vec<Tool>,
+}
+
+impl Default for GooseRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GooseRouter {
+    pub fn new() -> Self {
+        let run_recipe = Tool::new(
+            "run_recipe",
+            "Run a goose recipe to completion with the configured provider and return goose's final response.",
+            object!({
+                "type": "object",
+                "required": ["path"],
+                "properties": {
+                    "path": {"type": "string", "description": "Path to the recipe yaml or json file"},
+                    "params": {
+                        "type": "object",
+                        "description": "Values for the recipe's parameters",
+                        "additionalProperties": {"type": "string"}
+                    },
+                    "working_dir": {"type": "string", "description": "Directory to run in; defaults to goose's current directory"}
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Run Recipe".to_string()),
+            read_only_hint: Some(false),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(false),
+            open_world_hint: Some(true),
+        });
+
+        let search_sessions = Tool::new(
+            "search_sessions",
+            "Search past goose sessions by description and message text, newest first.",
+            object!({
+                "type": "object",
+                "required": ["query"],
+                "properties": {
+                    "query": {"type": "string", "description": "Case-insensitive text to look for"},
+                    "limit": {"type": "integer", "description": "Maximum sessions to return"}
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Search Sessions".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        let query_memory = Tool::new(
+            "query_memory",
+            "Read what goose has remembered, optionally limited to one category or entries containing some text.",
+            object!({
+                "type": "object",
+                "properties": {
+                    "category": {"type": "string"},
+                    "query": {"type": "string", "description": "Case-insensitive text the memory must contain"},
+                    "scope": {"type": "string", "enum": ["global", "local", "all"], "default": "all"}
+                }
+            }),
+        )
+        .annotate(ToolAnnotations {
+            title: Some("Query Memory".to_string()),
+            read_only_hint: Some(true),
+            destructive_hint: Some(false),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+        });
+
+        Self {
+            tools: vec![run_recipe, search_sessions, query_memory],
+        }
+    }
+
+    async fn run_recipe(&self, arguments: Value) -> Result<String, ErrorData> {
+        let path = arguments
+            .get("path")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid_params("Missing 'path' parameter"))?;
+        let params: Vec<(String, String)> = arguments
+            .get("params")
+            .and_then(Value::as_object)
+            .map(|params| {
+                params
+                    .iter()
+                    .map(|(k, v)| {
+                        (
+                            k.clone(),
+                            v.as_str().map_or_else(|| v.to_string(), str::to_string),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let working_dir = match arguments.get("working_dir").and_then(Value::as_str) {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::current_dir().map_err(internal_error)?,
+        };
+
+        let recipe_file = read_recipe_file(path).map_err(|e| invalid_params(e.to_string()))?;
+        let recipe = build_recipe_from_template(
+            recipe_file,
+            params,
+            None::<fn(&str, &str) -> anyhow::Result<String>>,
+        )
+        .map_err(|e| invalid_params(e.to_string()))?;
+        let prompt = recipe
+            .prompt
+            .clone()
+            .ok_or_else(|| invalid_params("Recipe has no prompt to run"))?;
+
+        let config = Config::global();
+        let provider_name: String = config
+            .get_param("GOOSE_PROVIDER")
+            .map_err(|_| internal_error("GOOSE_PROVIDER is not configured"))?;
+        let model_name: String = config
+            .get_param("GOOSE_MODEL")
+            .map_err(|_| internal_error("GOOSE_MODEL is not configured"))?;
+        let model_config = ModelConfig::new(&model_name).map_err(internal_error)?;
+        let provider = create(&provider_name, model_config).map_err(internal_error)?;
+
+        let agent = Agent::new();
+        for extension in recipe.extensions.iter().flatten() {
+            agent.add_extension(extension.clone()).await.map_err(|e| {
+                internal_error(format!(
+                    "Failed to add extension '{}': {}",
+                    extension.name(),
+                    e
+                ))
+            })?;
+        }
+        agent
+            .update_provider(provider.clone())
+            .await
+            .map_err(internal_error)?;
+        if let Some(instructions) = &recipe.instructions {
+            agent.extend_system_prompt(instructions.clone()).await;
+        }
+        if let Some(scaffold) = &recipe.scaffold {
+            let report = scaffold::materialize(scaffold, &working_dir)
+                .map_err(|e| invalid_params(format!("Failed to create the scaffold: {}", e)))?;
+            agent.extend_system_prompt(report.describe()).await;
+        }
+
+        let session_id = session::generate_session_id();
+        let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+            .map_err(internal_error)?;
+        let mut conversation =
+            Conversation::new_unvalidated(vec![Message::user().with_text(prompt)]);
+        let session_config = SessionConfig {
+            id: session::Identifier::Name(session_id.clone()),
+            working_dir: working_dir.clone(),
+            schedule_id: None,
+            execution_mode: None,
+            max_turns: None,
+            run_limits: Default::default(),
+            critique: None,
+            retry_config: recipe.retry.clone(),
+        };
+        let mut stream = agent
+            .reply(conversation.clone(), Some(session_config), None)
+            .await
+            .map_err(internal_error)?;
+
+        let mut answer = String::new();
+        while let Some(event) = stream.next().await {
+            match event.map_err(internal_error)? {
+                AgentEvent::Message(message) => {
+                    let text = message.as_concat_text();
+                    if message.role == Role::Assistant && !text.trim().is_empty() {
+                        answer = text;
+                    }
+                    conversation.push(message);
+                }
+                AgentEvent::HistoryReplaced(messages) => {
+                    conversation = Conversation::new_unvalidated(messages);
+                }
+                _ => {}
+            }
+        }
+
+        if let Err(e) = session::persist_messages(
+            &session_path,
+            &conversation,
+            Some(provider),
+            Some(working_dir),
+        )
+        .await
+        {
+            tracing::error!("Failed to store recipe session: {:?}", e);
+        }
+        Ok(format!("{}\n\n(session: {})", answer, session_id))
+    }
+
+    fn search_sessions(&self, arguments: Value) -> Result<Value, ErrorData> {
+        let query = arguments
+            .get("query")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid_params("Missing 'query' parameter"))?
+            .to_lowercase();
+        let limit = arguments
+            .get("limit")
+            .and_then(Value::as_u64)
+            .map_or(DEFAULT_SEARCH_LIMIT, |l| l as usize);
+
+        let sessions = get_valid_sorted_sessions(SortOrder::Descending).map_err(internal_error)?;
+        let mut matches = Vec::new();
+        for info in sessions {
+            if matches.len() >= limit {
+                break;
+            }
+            let snippet = if info.metadata.description.to_lowercase().contains(&query) {
+                Some(info.metadata.description.clone())
+            } else {
+                session::read_messages(&PathBuf::from(&info.path))
+                    .ok()
+                    .and_then(|conversation| {
+                        conversation
+                            .messages()
+                            .iter()
+                            .find_map(|m| snippet(&m.as_concat_text(), &query))
+                    })
+            };
+            if let Some(snippet) = snippet {
+                matches.push(json!({
+                    "id": info.id,
+                    "description": info.metadata.description,
+                    "modified": info.modified,
+                    "working_dir": info.metadata.working_dir,
+                    "match": snippet,
+                }));
+            }
+        }
+        Ok(Value::Array(matches))
+    }
+
+    fn query_memory(&self, arguments: Value) -> Result<Value, ErrorData> {
+        let category = arguments.get("category").and_then(Value::as_str);
+        let query = arguments
+            .get("query")
+            .and_then(Value::as_str)
+            .map(str::to_lowercase);
+        let scopes: &[bool] = match arguments.get("scope").and_then(Value::as_str) {
+            Some("global") => &[true],
+            Some("local") => &[false],
+            None | Some("all") => &[true, false],
+            Some(other) => return Err(invalid_params(format!("Unknown scope '{}'", other))),
+        };
+
+        let memory = MemoryRouter::new();
+        let mut results = serde_json::Map::new();
+        for &is_global in scopes {
+            let memories = memory.retrieve_all(is_global).map_err(internal_error)?;
+            let mut found = serde_json::Map::new();
+            for (name, entries) in memories {
+                if category.is_some_and(|c| c != name) {
+                    continue;
+                }
+                let entries: Vec<String> = entries
+                    .into_iter()
+                    .filter(|e| {
+                        !e.is_empty() && query.as_ref().is_none_or(|q| e.to_lowercase().contains(q))
+                    })
+                    .collect();
+                if !entries.is_empty() {
+                    found.insert(name, json!(entries));
+                }
+            }
+            let scope = if is_global { "global" } else { "local" };
+            results.insert(scope.to_string(), Value::Object(found));
+        }
+        Ok(Value::Object(results))
+    }
+}
+
+/// The text around the first case-insensitive occurrence of `query`
+fn snippet(text: &str, query: &str) -> Option<String> {
+    let start = text.to_lowercase().find(query)?;
+    // Lowercasing can change byte lengths, so clamp to char boundaries of the original
+    let floor = |mut i: usize| {
+        i = i.min(text.len());
+        while !text.is_char_boundary(i) {
+            i -= 1;
+        }
+        i
+    };
+    let from = floor(start.saturating_sub(SNIPPET_RADIUS));
+    let to = floor(start + query.len() + SNIPPET_RADIUS);
+    Some(text[from..to].replace('\n', " "))
+}
+
+impl Router for GooseRouter {
+    fn name(&self) -> String {
+        "goose".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        indoc! {r#"
+            goose is a local AI agent. Use run_recipe to hand goose a whole task described by a
+            recipe file; it runs to completion with goose's own provider and extensions. Use
+            search_sessions and query_memory to look up what goose has done and remembered before.
+        "#}
+        .to_string()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        self.tools.clone()
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        _notifier: mpsc::Sender<JsonRpcMessage>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ErrorData>> + Send + 'static>> {
+        let this = self.clone();
+        let tool_name = tool_name.to_string();
+
+        Box::pin(async move {
+            let text = match tool_name.as_str() {
+                "run_recipe" => this.run_recipe(arguments).await?,
+                "search_sessions" => this.search_sessions(arguments)?.to_string(),
+                "query_memory" => this.query_memory(arguments)?.to_string(),
+                _ => {
+                    return Err(ErrorData::new(
+                        ErrorCode::RESOURCE_NOT_FOUND,
+                        format!("Tool {} not found", tool_name),
+                        None,
+                    ))
+                }
+            };
+            Ok(vec![Content::text(text)])
+        })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        Vec::new()
+    }
+
+    fn read_resource(
+        &self,
+        _uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        Box::pin(async move { Ok("".to_string()) })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move {
+            Err(PromptError::NotFound(format!(
+                "Prompt {} not found",
+                prompt_name
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet() {
+        let text = format!(
+            "{}Needle in the haystack{}",
+            "a".repeat(200),
+            "é".repeat(200)
+        );
+        let found = snippet(&text, "needle").unwrap();
+        assert!(found.starts_with(&"a".repeat(SNIPPET_RADIUS)));
+        assert!(found.contains("Needle in the haystack"));
+        assert!(snippet(&text, "missing").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tool() {
+        let router = GooseRouter::new();
+        let (tx, _rx) = mpsc::channel(1);
+        let err = router.call_tool("rm_rf", json!({}), tx).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::RESOURCE_NOT_FOUND);
+        assert_eq!(router.list_tools().len(), 3);
+    }
+}
@@ -1,26 +1,57 @@
 use goose::agents::extension::Envs;
 use goose::agents::extension::ToolInfo;
+use goose::agents::extension_manager::{
+    ExtensionDetails, ExtensionResource, ExtensionStatus, ExtensionTool, ToolCollision, ToolRoute,
+    ToolRoutingTable,
+};
+use goose::agents::prompt_analysis::{PromptAnalysis, PromptSource, PromptSourceKind};
+use goose::agents::sampling::SamplingUsage;
 use goose::agents::ExtensionConfig;
+use goose::agents::PendingApproval;
+use goose::analytics::UsageStats;
+use goose::anomaly::{Alert, AlertKind};
+use goose::backup::{BackupCategory, RestoreSummary};
+use goose::bench_results::{TrendBucket, TrendPoint};
 use goose::config::permission::PermissionLevel;
-use goose::config::ExtensionEntry;
+use goose::config::settings::SettingsValidationError;
+use goose::config::team::TeamSyncResult;
+use goose::config::{
+    ConfigSource, ConversationTemplate, EffectiveValue, ExtensionEntry, GooseSettings,
+    ToolCollisionPolicy,
+};
+use goose::diagnostics::{CheckResult, CheckStatus, DoctorReport};
+use goose::exemplars::Exemplar;
+use goose::governance::{LegalHold, PurgeReport, PurgedFile, RecordKind};
+use goose::offline::Connectivity;
 use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::RiskCategory;
 use goose::providers::base::{ConfigKey, ModelInfo, ProviderMetadata};
 use goose::session::info::SessionInfo;
-use goose::session::SessionMetadata;
+use goose::session::{
+    Annotation, AuditEvent, AuditEventKind, BranchReason, DiscardedBranch, Elevation,
+    ExtensionVersion, Feedback, FeedbackRating, IntegrityReport, IntegrityStatus, Pin, PinnedItem,
+    ResourceAttachment, RunManifest, SessionEvent, SessionEventKind, SessionMetadata, TokenUsage,
+};
+use goose::style::StylePreset;
+use goose::tool_stats::{ExtensionUsage, ToolStatsReport, ToolUsage};
 use rmcp::model::{
     Annotations, Content, EmbeddedResource, ImageContent, RawEmbeddedResource, RawImageContent,
     RawTextContent, ResourceContents, Role, TextContent, Tool, ToolAnnotations,
 };
-use utoipa::{OpenApi, ToSchema};
+use utoipa::{Modify, OpenApi, ToSchema};
+
+use super::routes::{API_PREFIX, UNVERSIONED_PATHS};
 
 use goose::conversation::message::{
-    ContextLengthExceeded, FrontendToolRequest, Message, MessageContent, RedactedThinkingContent,
-    SummarizationRequested, ThinkingContent, ToolConfirmationRequest, ToolRequest, ToolResponse,
+    ContextLengthExceeded, FrontendToolRequest, Message, MessageContent, MessageUsage,
+    RedactedThinkingContent, SummarizationRequested, ThinkingContent, ToolConfirmationRequest,
+    ToolRequest, ToolResponse,
 };
 use utoipa::openapi::schema::{
     AdditionalProperties, AnyOfBuilder, ArrayBuilder, ObjectBuilder, OneOfBuilder, Schema,
     SchemaFormat, SchemaType,
 };
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
 use utoipa::openapi::{AllOfBuilder, Ref, RefOr};
 
 macro_rules! derive_utoipa {
@@ -350,9 +381,47 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
     }
 }
 
+/// Declares the `api_key` scheme the routes refer to, so generated clients send X-Secret-Key,
+/// and the `observer_token` scheme that read-only observers use for live sessions
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Secret-Key"))),
+        );
+        components.add_security_scheme(
+            "observer_token",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Observer-Token"))),
+        );
+    }
+}
+
+/// Documents the versioned paths clients should call, e.g. `/v1/reply` rather than `/reply`
+struct VersionPrefix;
+
+impl Modify for VersionPrefix {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let paths = std::mem::take(&mut openapi.paths.paths);
+        openapi.paths.paths = paths
+            .into_iter()
+            .map(|(path, item)| {
+                if UNVERSIONED_PATHS.contains(&path.as_str()) {
+                    (path, item)
+                } else {
+                    (format!("{}{}", API_PREFIX, path), item)
+                }
+            })
+            .collect();
+    }
+}
+
 #[allow(dead_code)] // Used by utoipa for OpenAPI generation
 #[derive(OpenApi)]
 #[openapi(
+    modifiers(&SecurityAddon, &VersionPrefix),
     paths(
         super::routes::config_management::backup_config,
         super::routes::config_management::recover_config,
@@ -365,22 +434,100 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::config_management::remove_extension,
         super::routes::config_management::get_extensions,
         super::routes::config_management::read_all_config,
+        super::routes::config_management::update_config,
+        super::routes::config_management::get_config_schema,
+        super::routes::config_management::effective_config,
+        super::routes::config_management::sync_team,
         super::routes::config_management::providers,
         super::routes::config_management::upsert_permissions,
         super::routes::config_management::create_custom_provider,
         super::routes::config_management::remove_custom_provider,
+        super::routes::config_management::get_pricing,
+        super::routes::config_management::get_current_model,
         super::routes::agent::get_tools,
         super::routes::agent::add_sub_recipes,
         super::routes::agent::extend_prompt,
+        super::routes::agent::analyze_prompt,
         super::routes::agent::update_agent_provider,
         super::routes::agent::update_router_tool_selector,
         super::routes::agent::update_session_config,
+        super::routes::reply::reply_handler,
         super::routes::reply::confirm_permission,
+        super::routes::reply::submit_tool_result,
+        super::routes::extension::list_extensions,
+        super::routes::extension::list_extension_tools,
+        super::routes::extension::add_extension,
+        super::routes::extension::remove_extension,
+        super::routes::extension::get_disabled_tools,
+        super::routes::extension::set_disabled_tools,
+        super::routes::extension::get_tool_routing,
+        super::routes::extension::get_sampling,
+        super::routes::extension::set_sampling,
+        super::routes::extension::get_extension_secrets,
+        super::routes::extension::set_extension_secrets,
+        super::routes::extension::list_extension_resources,
+        super::routes::extension::attach_extension_resource,
+        super::routes::extension::detach_extension_resource,
+        super::routes::extension::get_extension_stats,
+        super::routes::extension::clear_extension_stats,
+        super::routes::audio::transcribe_handler,
+        super::routes::audio::transcribe_elevenlabs_handler,
+        super::routes::audio::check_dictation_config,
+        super::routes::health::status,
+        super::routes::health::ready,
+        super::routes::health::connectivity,
+        super::routes::a2a::get_agent_card,
+        super::routes::a2a::handle_rpc,
+        super::routes::approvals::list_approvals,
+        super::routes::approvals::decide_approval,
+        super::routes::approvals::get_auto_approve,
+        super::routes::approvals::set_auto_approve,
+        super::routes::run_queue::list_queue,
+        super::routes::run_queue::reorder_queue,
+        super::routes::run_queue::remove_queued_run,
+        super::routes::runs::list_runs,
+        super::routes::runs::cancel_run,
+        super::routes::runs::list_artifacts,
+        super::routes::runs::download_artifact,
+        super::routes::compare::compare,
         super::routes::context::manage_context,
         super::routes::session::list_sessions,
         super::routes::session::get_session_history,
+        super::routes::session::get_session_insights,
+        super::routes::session::update_session_metadata,
+        super::routes::session::add_message_annotation,
+        super::routes::session::get_message_annotations,
+        super::routes::session::get_session_annotations,
+        super::routes::session::delete_session_annotation,
+        super::routes::session::edit_message,
+        super::routes::session::get_session_branches,
+        super::routes::session::retry_last_turn,
+        super::routes::session::verify_session_integrity,
+        super::routes::session::get_session_manifests,
+        super::routes::session::replay_run,
+        super::routes::session::get_full_tool_result,
+        super::routes::session::attach_document,
+        super::routes::session::get_session_events,
+        super::routes::live::attach_session,
+        super::routes::live::grant_observer,
+        super::routes::live::revoke_observer,
+        super::routes::session::handoff_session,
+        super::routes::session::end_session,
+        super::routes::elevation::grant_elevation,
+        super::routes::elevation::get_elevation,
+        super::routes::elevation::revoke_elevation,
+        super::routes::elevation::get_audit_log,
+        super::routes::feedback::session_feedback,
+        super::routes::feedback::message_feedback,
+        super::routes::feedback::get_feedback,
+        super::routes::feedback::export_feedback,
         super::routes::schedule::create_schedule,
         super::routes::schedule::list_schedules,
+        super::routes::schedule::schedule_graph,
+        super::routes::schedule::schedule_calendar_feed,
+        super::routes::schedule::list_blackouts,
+        super::routes::schedule::create_blackout,
+        super::routes::schedule::delete_blackout,
         super::routes::schedule::delete_schedule,
         super::routes::schedule::update_schedule,
         super::routes::schedule::run_now_handler,
@@ -392,12 +539,55 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::recipe::create_recipe,
         super::routes::recipe::encode_recipe,
         super::routes::recipe::decode_recipe,
-        super::routes::recipe::scan_recipe
+        super::routes::recipe::scan_recipe,
+        super::routes::diagnostics::get_diagnostics,
+        super::routes::diagnostics::get_checks,
+        super::routes::backup::create_backup_handler,
+        super::routes::backup::restore_backup_handler,
+        super::routes::governance::export_data,
+        super::routes::governance::purge_data,
+        super::routes::governance::list_holds,
+        super::routes::governance::release_hold,
+        super::routes::setup::start_openrouter_setup,
+        super::routes::setup::get_setup_status,
+        super::routes::setup::detect_providers,
+        super::routes::setup::test_connectivity,
+        super::routes::setup::choose_model,
+        super::routes::setup::enable_starter_extensions,
+        super::routes::stats::get_stats,
+        super::routes::stats::clear_stats,
+        super::routes::style::list_styles,
+        super::routes::style::set_session_style,
+        super::routes::pins::list_pins,
+        super::routes::pins::add_pin,
+        super::routes::pins::remove_pin,
+        super::routes::prompts::list_prompts,
+        super::routes::prompts::use_prompt,
+        super::routes::bench::bench_results,
+        super::routes::alerts::list_alerts,
+        super::routes::exemplars::mark_exemplar,
+        super::routes::exemplars::list_exemplars,
+        super::routes::exemplars::delete_exemplar,
+        super::routes::templates::get_templates,
+        super::routes::templates::save_template,
+        super::routes::templates::remove_template,
+        super::routes::templates::start_template
     ),
     components(schemas(
         super::routes::config_management::UpsertConfigQuery,
         super::routes::config_management::ConfigKeyQuery,
         super::routes::config_management::ConfigResponse,
+        super::routes::config_management::UpdateConfigRequest,
+        super::routes::config_management::ConfigValidationResponse,
+        super::routes::config_management::EffectiveConfigRequest,
+        super::routes::config_management::EffectiveConfigResponse,
+        super::routes::config_management::ConfigLayer,
+        super::routes::config_management::TeamSyncRequest,
+        TeamSyncResult,
+        ConfigSource,
+        EffectiveValue,
+        GooseSettings,
+        SettingsValidationError,
         super::routes::config_management::ProvidersResponse,
         super::routes::config_management::ProviderDetails,
         super::routes::config_management::ExtensionResponse,
@@ -405,13 +595,142 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         super::routes::config_management::ToolPermission,
         super::routes::config_management::UpsertPermissionsQuery,
         super::routes::config_management::CreateCustomProviderRequest,
+        super::routes::config_management::PricingQuery,
+        super::routes::config_management::PricingResponse,
+        super::routes::config_management::PricingData,
+        super::routes::reply::ChatRequest,
+        super::routes::reply::MessageEvent,
         super::routes::reply::PermissionConfirmationRequest,
+        super::routes::reply::ToolResultRequest,
+        super::routes::extension::ExtensionConfigRequest,
+        super::routes::extension::ExtensionResponse,
+        super::routes::extension::DisabledToolsResponse,
+        super::routes::extension::SetDisabledToolsRequest,
+        super::routes::extension::ExtensionListResponse,
+        super::routes::extension::ExtensionToolsResponse,
+        ExtensionDetails,
+        ExtensionStatus,
+        ExtensionTool,
+        super::routes::extension::SamplingResponse,
+        super::routes::extension::SetSamplingRequest,
+        super::routes::extension::ExtensionSecretsResponse,
+        super::routes::extension::SetExtensionSecretsRequest,
+        SamplingUsage,
+        super::routes::extension::ResourceListResponse,
+        super::routes::extension::ResourceRequest,
+        ExtensionResource,
+        ResourceAttachment,
+        ToolRoutingTable,
+        ToolRoute,
+        ToolCollision,
+        ToolCollisionPolicy,
+        ToolStatsReport,
+        ExtensionUsage,
+        ToolUsage,
+        super::routes::audio::TranscribeRequest,
+        super::routes::audio::TranscribeElevenLabsRequest,
+        super::routes::audio::TranscribeResponse,
+        super::routes::health::StatusResponse,
+        super::routes::health::ReadinessResponse,
+        Connectivity,
+        super::routes::approvals::ApprovalListResponse,
+        super::routes::approvals::ApprovalAction,
+        super::routes::approvals::ApprovalDecision,
+        super::routes::approvals::AutoApproveCategories,
+        super::routes::run_queue::QueuedRunInfo,
+        super::routes::run_queue::QueuedRunListResponse,
+        super::routes::run_queue::ReorderQueueRequest,
+        super::routes::runs::RunInfo,
+        super::routes::runs::RunListResponse,
+        super::routes::runs::ArtifactListResponse,
+        goose::session::attachments::ArtifactInfo,
+        RiskCategory,
+        PendingApproval,
+        super::routes::compare::CompareModel,
+        super::routes::compare::CompareRequest,
+        super::routes::compare::CompareResult,
+        super::routes::compare::CompareResponse,
         super::routes::context::ContextManageRequest,
         super::routes::context::ContextManageResponse,
         super::routes::session::SessionListResponse,
         super::routes::session::SessionHistoryResponse,
+        super::routes::session::SessionInsights,
+        super::routes::session::UpdateSessionMetadataRequest,
+        super::routes::session::CreateAnnotationRequest,
+        super::routes::session::AnnotationListResponse,
+        super::routes::session::EditMessageRequest,
+        super::routes::session::BranchListResponse,
+        super::routes::session::RetryRequest,
+        super::routes::session::ReplayRequest,
+        super::routes::session::ManifestListResponse,
+        super::routes::session::RunManifestEntry,
+        RunManifest,
+        ExtensionVersion,
+        IntegrityReport,
+        IntegrityStatus,
+        super::routes::session::FullToolResultResponse,
+        super::routes::session::AttachDocumentRequest,
+        super::routes::session::AttachDocumentResponse,
+        super::routes::session::EventListResponse,
+        super::routes::live::LiveEvent,
+        super::routes::live::ObserverRequest,
+        super::routes::live::ObserverResponse,
+        super::routes::session::HandoffRequest,
+        super::routes::session::HandoffResponse,
+        super::routes::elevation::GrantElevationRequest,
+        super::routes::elevation::ElevationStatusResponse,
+        super::routes::elevation::AuditLogResponse,
+        super::routes::feedback::FeedbackRequest,
+        super::routes::feedback::FeedbackListResponse,
+        super::routes::setup::SetupResponse,
+        super::routes::setup::SetupStepKind,
+        super::routes::setup::SetupStep,
+        super::routes::setup::SetupStatus,
+        super::routes::setup::DetectedProvider,
+        super::routes::setup::DetectedProvidersResponse,
+        super::routes::setup::SetupProviderRequest,
+        super::routes::setup::ConnectivityResult,
+        super::routes::setup::SetupExtensionsRequest,
+        UsageStats,
+        super::routes::bench::BenchResultsResponse,
+        TrendBucket,
+        TrendPoint,
+        Alert,
+        AlertKind,
+        super::routes::alerts::AlertListResponse,
+        Exemplar,
+        super::routes::exemplars::MarkExemplarRequest,
+        super::routes::exemplars::ExemplarListResponse,
+        CheckStatus,
+        CheckResult,
+        DoctorReport,
+        BackupCategory,
+        RestoreSummary,
+        super::routes::governance::ExportRequest,
+        super::routes::governance::LegalHoldListResponse,
+        super::routes::governance::PurgeRequest,
+        super::routes::style::StyleListResponse,
+        super::routes::style::SetSessionStyleRequest,
+        super::routes::style::SessionStyleResponse,
+        StylePreset,
+        super::routes::pins::PinListResponse,
+        Pin,
+        PinnedItem,
+        super::routes::prompts::PromptListResponse,
+        super::routes::prompts::ExtensionPrompt,
+        super::routes::prompts::PromptArgumentInfo,
+        super::routes::prompts::UsePromptRequest,
+        super::routes::prompts::UsePromptResponse,
+        LegalHold,
+        PurgeReport,
+        PurgedFile,
+        RecordKind,
+        super::routes::templates::TemplateListResponse,
+        super::routes::templates::StartTemplateResponse,
+        ConversationTemplate,
         Message,
         MessageContent,
+        MessageUsage,
         ContentSchema,
         EmbeddedResourceSchema,
         ImageContentSchema,
@@ -444,13 +763,30 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         ModelInfo,
         SessionInfo,
         SessionMetadata,
+        Annotation,
+        DiscardedBranch,
+        BranchReason,
+        AuditEvent,
+        AuditEventKind,
+        SessionEvent,
+        SessionEventKind,
+        TokenUsage,
+        Elevation,
+        Feedback,
+        FeedbackRating,
         super::routes::schedule::CreateScheduleRequest,
         super::routes::schedule::UpdateScheduleRequest,
         super::routes::schedule::KillJobResponse,
         super::routes::schedule::InspectJobResponse,
         goose::scheduler::ScheduledJob,
+        goose::scheduler::JobEnvironment,
         super::routes::schedule::RunNowResponse,
         super::routes::schedule::ListSchedulesResponse,
+        super::routes::schedule::ScheduleGraphResponse,
+        goose::scheduler::JobNode,
+        goose::scheduler::JobOutcome,
+        super::routes::schedule::ListBlackoutsResponse,
+        goose::schedule_calendar::BlackoutWindow,
         super::routes::schedule::SessionsQuery,
         super::routes::schedule::SessionDisplayInfo,
         super::routes::recipe::CreateRecipeRequest,
@@ -470,12 +806,21 @@ impl<'__s> ToSchema<'__s> for AnnotatedSchema {
         goose::recipe::RecipeParameterRequirement,
         goose::recipe::Response,
         goose::recipe::SubRecipe,
+        goose::recipe::Artifact,
+        goose::recipe::Scaffold,
+        goose::recipe::ScaffoldFile,
+        goose::recipe::ScaffoldConflict,
         goose::agents::types::RetryConfig,
+        goose::agents::types::CritiqueConfig,
+        goose::agents::types::RunLimits,
         goose::agents::types::SuccessCheck,
         super::routes::agent::AddSubRecipesRequest,
         super::routes::agent::AddSubRecipesResponse,
         super::routes::agent::ExtendPromptRequest,
         super::routes::agent::ExtendPromptResponse,
+        PromptAnalysis,
+        PromptSource,
+        PromptSourceKind,
         super::routes::agent::UpdateProviderRequest,
         super::routes::agent::SessionConfigRequest,
         super::routes::agent::GetToolsQuery,
@@ -489,3 +834,22 @@ pub fn generate_schema() -> String {
     let api_doc = ApiDoc::openapi();
     serde_json::to_string_pretty(&api_doc).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_documents_versioned_paths_streaming_and_auth() {
+        let schema: serde_json::Value = serde_json::from_str(&generate_schema()).unwrap();
+
+        let reply = &schema["paths"]["/v1/reply"]["post"]["responses"]["200"]["content"];
+        assert!(reply.get("text/event-stream").is_some());
+        assert!(schema["paths"].get("/reply").is_none());
+        assert!(schema["paths"].get("/status").is_some());
+
+        let api_key = &schema["components"]["securitySchemes"]["api_key"];
+        assert_eq!(api_key["in"], "header");
+        assert_eq!(api_key["name"], "X-Secret-Key");
+    }
+}
@@ -0,0 +1,175 @@
+//! Support for running goosed behind a reverse proxy such as nginx or Caddy.
+//!
+//! X-Forwarded-* headers are only believed when the connection comes from a trusted proxy;
+//! from anyone else they are stripped before the routes see them. Handlers that need the
+//! address clients used to reach the server call [`external_base_url`].
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+pub const X_FORWARDED_FOR: &str = "x-forwarded-for";
+pub const X_FORWARDED_HOST: &str = "x-forwarded-host";
+pub const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+pub const X_FORWARDED_PREFIX: &str = "x-forwarded-prefix";
+
+const FORWARDED_HEADERS: &[&str] = &[
+    "forwarded",
+    X_FORWARDED_FOR,
+    X_FORWARDED_HOST,
+    X_FORWARDED_PROTO,
+    X_FORWARDED_PREFIX,
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct ProxySettings {
+    /// Peers whose X-Forwarded-* headers are honoured
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Path prefix goosed is mounted under, e.g. `/goose`
+    pub base_path: String,
+    /// The proxy terminates TLS, so clients reach us over https even without X-Forwarded-Proto
+    pub tls_terminated: bool,
+}
+
+impl ProxySettings {
+    fn is_trusted(&self, peer: Option<IpAddr>) -> bool {
+        match peer {
+            Some(ip) => self.trusted_proxies.contains(&ip),
+            None => false,
+        }
+    }
+}
+
+/// Strips forwarded headers from untrusted peers and fills in what the settings already know
+pub async fn forwarded_headers(
+    State(settings): State<Arc<ProxySettings>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip());
+    let headers = request.headers_mut();
+
+    if !settings.is_trusted(peer) {
+        for name in FORWARDED_HEADERS {
+            if headers.remove(*name).is_some() {
+                tracing::debug!("Ignoring {} from untrusted peer {:?}", name, peer);
+            }
+        }
+    }
+    if settings.tls_terminated && !headers.contains_key(X_FORWARDED_PROTO) {
+        headers.insert(X_FORWARDED_PROTO, HeaderValue::from_static("https"));
+    }
+    if !settings.base_path.is_empty() && !headers.contains_key(X_FORWARDED_PREFIX) {
+        if let Ok(prefix) = HeaderValue::from_str(&settings.base_path) {
+            headers.insert(X_FORWARDED_PREFIX, prefix);
+        }
+    }
+
+    next.run(request).await
+}
+
+fn first_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// The scheme, host and path prefix clients use to reach this server, without a trailing slash
+pub fn external_base_url(headers: &HeaderMap) -> String {
+    let proto = first_value(headers, X_FORWARDED_PROTO).unwrap_or("http");
+    let host = first_value(headers, X_FORWARDED_HOST)
+        .or_else(|| first_value(headers, "host"))
+        .unwrap_or("localhost");
+    let prefix = first_value(headers, X_FORWARDED_PREFIX).unwrap_or("");
+    format!("{}://{}{}", proto, host, prefix.trim_end_matches('/'))
+}
+
+/// Allows any origin when none are configured, which is what the desktop app relies on
+pub fn cors_layer(origins: &[String]) -> CorsLayer {
+    if origins.is_empty() {
+        return CorsLayer::new()
+            .allow_origin(Any)
+            .allow_methods(Any)
+            .allow_headers(Any);
+    }
+
+    let origins: Vec<HeaderValue> = origins
+        .iter()
+        .filter_map(
+            |origin| match HeaderValue::from_str(origin.trim_end_matches('/')) {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    tracing::warn!("Ignoring invalid CORS origin: {}", origin);
+                    None
+                }
+            },
+        )
+        .collect();
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins))
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([
+            http::header::CONTENT_TYPE,
+            http::header::ACCEPT,
+            HeaderName::from_static("x-secret-key"),
+        ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_external_base_url() {
+        assert_eq!(
+            external_base_url(&headers(&[("host", "127.0.0.1:3000")])),
+            "http://127.0.0.1:3000"
+        );
+        assert_eq!(
+            external_base_url(&headers(&[
+                ("host", "127.0.0.1:3000"),
+                (X_FORWARDED_HOST, "goose.example.com, 10.0.0.2"),
+                (X_FORWARDED_PROTO, "https"),
+                (X_FORWARDED_PREFIX, "/goose/"),
+            ])),
+            "https://goose.example.com/goose"
+        );
+    }
+
+    #[test]
+    fn test_trusted_peer() {
+        let settings = ProxySettings {
+            trusted_proxies: vec!["10.0.0.2".parse().unwrap()],
+            ..Default::default()
+        };
+        assert!(settings.is_trusted(Some("10.0.0.2".parse().unwrap())));
+        assert!(!settings.is_trusted(Some("10.0.0.3".parse().unwrap())));
+        assert!(!settings.is_trusted(None));
+    }
+}
@@ -0,0 +1,963 @@
+//! Agent-to-agent (A2A) protocol support, so other orchestrators can discover this goose
+//! through its agent card and delegate tasks to it over JSON-RPC.
+//!
+//! Each A2A context maps to a goose session named `a2a-<contextId>`, so follow-up messages
+//! in the same context continue the conversation; a task waits while another one in its
+//! context is still running. Delegated tasks run with the configured GOOSE_MODE. When a
+//! tool call needs confirmation the task becomes `input-required`, and the client answers
+//! with a message for the same task: "approve" allows the call and any other reply denies
+//! it.
+//!
+//! With `GOOSE_SERVER_MAX_CONCURRENT_RUNS` set, tasks beyond the cap wait their turn in the
+//! [run queue](super::run_queue), which survives a restart of the server.
+
+use super::reply::SseResponse;
+use super::run_queue::QueuedRun;
+use super::utils::verify_secret_key;
+use crate::proxy::external_base_url;
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use futures::StreamExt;
+use goose::agents::{AgentEvent, SessionConfig};
+use goose::conversation::message::{Message, MessageContent};
+use goose::conversation::Conversation;
+use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::{Permission, PermissionConfirmation};
+use goose::session;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch, Mutex};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+
+pub const A2A_PROTOCOL_VERSION: &str = "0.2.6";
+/// Tasks held at once. Finished tasks are forgotten to make room, and new tasks are refused
+/// while this many are still open.
+const MAX_TASKS: usize = 1000;
+/// Replies to an input-required task that allow the pending tool call
+const APPROVALS: &[&str] = &["approve", "approved", "allow", "yes", "y"];
+
+const PARSE_ERROR: i32 = -32700;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const INTERNAL_ERROR: i32 = -32603;
+const TASK_NOT_FOUND: i32 = -32001;
+const TASK_NOT_CANCELABLE: i32 = -32002;
+const TOO_MANY_TASKS: i32 = -32000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum A2aRole {
+    User,
+    Agent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Part {
+    Text { text: String },
+    Data { data: Value },
+    File { file: Value },
+}
+
+fn message_kind() -> String {
+    "message".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct A2aMessage {
+    pub role: A2aRole,
+    pub parts: Vec<Part>,
+    pub message_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<String>,
+    #[serde(default = "message_kind")]
+    pub kind: String,
+}
+
+impl A2aMessage {
+    fn agent(text: String, task_id: &str, context_id: &str) -> Self {
+        Self {
+            role: A2aRole::Agent,
+            parts: vec![Part::Text { text }],
+            message_id: uuid::Uuid::new_v4().to_string(),
+            task_id: Some(task_id.to_string()),
+            context_id: Some(context_id.to_string()),
+            kind: message_kind(),
+        }
+    }
+
+    /// The goose message for an incoming A2A message. Only text and data parts are understood.
+    fn to_goose(&self) -> Option<Message> {
+        let text = self
+            .parts
+            .iter()
+            .filter_map(|part| match part {
+                Part::Text { text } => Some(text.clone()),
+                Part::Data { data } => Some(data.to_string()),
+                Part::File { .. } => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        (!text.is_empty()).then(|| Message::user().with_text(text))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskState {
+    Submitted,
+    Working,
+    InputRequired,
+    Completed,
+    Canceled,
+    Failed,
+}
+
+impl TaskState {
+    fn is_final(self) -> bool {
+        matches!(
+            self,
+            TaskState::Completed | TaskState::Canceled | TaskState::Failed
+        )
+    }
+
+    /// Whether the client's request for the task is answered: it finished, or it waits on
+    /// the client
+    fn ends_request(self) -> bool {
+        self.is_final() || self == TaskState::InputRequired
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub state: TaskState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<A2aMessage>,
+    pub timestamp: String,
+}
+
+impl TaskStatus {
+    fn new(state: TaskState, message: Option<A2aMessage>) -> Self {
+        Self {
+            state,
+            message,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub artifact_id: String,
+    pub name: Option<String>,
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub context_id: String,
+    pub status: TaskStatus,
+    pub history: Vec<A2aMessage>,
+    pub artifacts: Vec<Artifact>,
+    pub kind: String,
+}
+
+/// What `message/stream` sends, one per SSE event
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+enum StreamResult {
+    Task(Task),
+    StatusUpdate(TaskStatusUpdateEvent),
+    ArtifactUpdate(TaskArtifactUpdateEvent),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskStatusUpdateEvent {
+    task_id: String,
+    context_id: String,
+    kind: &'static str,
+    status: TaskStatus,
+    #[serde(rename = "final")]
+    is_final: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskArtifactUpdateEvent {
+    task_id: String,
+    context_id: String,
+    kind: &'static str,
+    artifact: Artifact,
+    last_chunk: bool,
+}
+
+struct TaskEntry {
+    task: Task,
+    cancel: CancellationToken,
+    /// The `message/stream` request following the task, until its request is answered
+    events: Option<EventSink>,
+    /// The tool confirmation an input-required task is waiting on
+    pending_confirmation: Option<String>,
+    state: watch::Sender<TaskState>,
+}
+
+/// Tasks delegated to this server, kept in memory
+#[derive(Default)]
+pub struct A2aTasks {
+    tasks: Mutex<HashMap<String, TaskEntry>>,
+}
+
+impl A2aTasks {
+    async fn insert(&self, task: Task, cancel: CancellationToken) -> Result<(), RpcError> {
+        let mut tasks = self.tasks.lock().await;
+        if tasks.len() >= MAX_TASKS {
+            tasks.retain(|_, entry| !entry.task.status.state.is_final());
+        }
+        if tasks.len() >= MAX_TASKS {
+            return Err(RpcError::new(
+                TOO_MANY_TASKS,
+                "Too many tasks in progress; try again later",
+            ));
+        }
+        let (state, _) = watch::channel(task.status.state);
+        tasks.insert(
+            task.id.clone(),
+            TaskEntry {
+                task,
+                cancel,
+                events: None,
+                pending_confirmation: None,
+                state,
+            },
+        );
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Option<Task> {
+        self.tasks.lock().await.get(id).map(|e| e.task.clone())
+    }
+
+    async fn update(&self, id: &str, f: impl FnOnce(&mut Task)) -> Option<Task> {
+        let mut tasks = self.tasks.lock().await;
+        let entry = tasks.get_mut(id)?;
+        f(&mut entry.task);
+        Some(entry.task.clone())
+    }
+
+    /// Send updates for the task to a `message/stream` request
+    async fn follow(&self, id: &str, events: EventSink) {
+        if let Some(entry) = self.tasks.lock().await.get_mut(id) {
+            entry.events = Some(events);
+        }
+    }
+
+    /// Send an event to the stream following the task, if there is one
+    async fn publish(&self, id: &str, result: StreamResult) {
+        let events = self
+            .tasks
+            .lock()
+            .await
+            .get(id)
+            .and_then(|entry| entry.events.clone());
+        if let Some(events) = events {
+            events.send(result).await;
+        }
+    }
+
+    /// Move the task to `status` and tell whoever is waiting on it. A stream following the
+    /// task ends once its request is answered.
+    async fn set_status(&self, id: &str, status: TaskStatus) {
+        let (events, update) = {
+            let mut tasks = self.tasks.lock().await;
+            let Some(entry) = tasks.get_mut(id) else {
+                return;
+            };
+            entry.task.status = status.clone();
+            if status.state != TaskState::InputRequired {
+                entry.pending_confirmation = None;
+            }
+            entry.state.send_replace(status.state);
+            let events = if status.state.ends_request() {
+                entry.events.take()
+            } else {
+                entry.events.clone()
+            };
+            let update = StreamResult::StatusUpdate(TaskStatusUpdateEvent {
+                task_id: entry.task.id.clone(),
+                context_id: entry.task.context_id.clone(),
+                kind: "status-update",
+                is_final: status.state.ends_request(),
+                status,
+            });
+            (events, update)
+        };
+        if let Some(events) = events {
+            events.send(update).await;
+        }
+    }
+
+    /// Ask the client to approve or deny a tool call
+    async fn request_input(&self, id: &str, confirmation_id: String, question: A2aMessage) {
+        let status = TaskStatus::new(TaskState::InputRequired, Some(question.clone()));
+        if let Some(entry) = self.tasks.lock().await.get_mut(id) {
+            entry.task.history.push(question);
+            entry.pending_confirmation = Some(confirmation_id);
+        }
+        self.set_status(id, status).await;
+    }
+
+    /// Take the client's answer to an input-required task, returning the tool confirmation
+    /// it answers
+    async fn answer(&self, id: &str, message: A2aMessage) -> Result<String, RpcError> {
+        let mut tasks = self.tasks.lock().await;
+        let entry = tasks
+            .get_mut(id)
+            .ok_or_else(|| RpcError::new(TASK_NOT_FOUND, "Task not found"))?;
+        let confirmation_id = entry
+            .pending_confirmation
+            .take()
+            .filter(|_| entry.task.status.state == TaskState::InputRequired)
+            .ok_or_else(|| {
+                RpcError::new(
+                    INVALID_PARAMS,
+                    "Task is not waiting for input; send a new message with the same contextId",
+                )
+            })?;
+        entry.task.history.push(message);
+        entry.task.status = TaskStatus::new(TaskState::Working, None);
+        entry.state.send_replace(TaskState::Working);
+        Ok(confirmation_id)
+    }
+
+    /// The task once the client's request for it is answered
+    async fn wait_for_answer(&self, id: &str) -> Option<Task> {
+        let mut state = self.tasks.lock().await.get(id)?.state.subscribe();
+        // An error means the task was forgotten, and `get` then finds nothing
+        let _ = state.wait_for(|state| state.ends_request()).await;
+        self.get(id).await
+    }
+
+    async fn cancel(&self, id: &str) -> Result<Task, RpcError> {
+        {
+            let tasks = self.tasks.lock().await;
+            let entry = tasks
+                .get(id)
+                .ok_or_else(|| RpcError::new(TASK_NOT_FOUND, "Task not found"))?;
+            if entry.task.status.state.is_final() {
+                return Err(RpcError::new(
+                    TASK_NOT_CANCELABLE,
+                    "Task is already finished",
+                ));
+            }
+            entry.cancel.cancel();
+        }
+        self.set_status(id, TaskStatus::new(TaskState::Canceled, None))
+            .await;
+        self.get(id)
+            .await
+            .ok_or_else(|| RpcError::new(TASK_NOT_FOUND, "Task not found"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn rpc_response(id: &Value, result: Result<impl Serialize, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageSendParams {
+    message: A2aMessage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskQueryParams {
+    id: String,
+    history_length: Option<usize>,
+}
+
+fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+/// Sends stream events for one `message/stream` request
+#[derive(Clone)]
+struct EventSink {
+    tx: mpsc::Sender<String>,
+    request_id: Value,
+}
+
+impl EventSink {
+    async fn send(&self, result: StreamResult) {
+        let response = rpc_response(&self.request_id, Ok::<_, RpcError>(result));
+        let _ = self.tx.send(format!("data: {}\n\n", response)).await;
+    }
+}
+
+fn agent_card(base_url: &str) -> Value {
+    json!({
+        "protocolVersion": A2A_PROTOCOL_VERSION,
+        "name": "goose",
+        "description": "A local, extensible AI agent that can run commands, edit files and use MCP extensions",
+        "url": format!("{}/a2a", base_url),
+        "preferredTransport": "JSONRPC",
+        "version": env!("CARGO_PKG_VERSION"),
+        "capabilities": {
+            "streaming": true,
+            "pushNotifications": false,
+            "stateTransitionHistory": false
+        },
+        "securitySchemes": {
+            "secretKey": {"type": "apiKey", "in": "header", "name": "X-Secret-Key"}
+        },
+        "security": [{"secretKey": []}],
+        "defaultInputModes": ["text/plain", "application/json"],
+        "defaultOutputModes": ["text/plain"],
+        "skills": [{
+            "id": "general",
+            "name": "General agent",
+            "description": "Carries out a task using goose's enabled extensions",
+            "tags": ["coding", "automation"]
+        }]
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/agent-card.json",
+    responses(
+        (status = 200, description = "A2A agent card, also served at /.well-known/agent.json", body = Value)
+    ),
+    tag = "A2A"
+)]
+async fn get_agent_card(headers: HeaderMap) -> Json<Value> {
+    Json(agent_card(&external_base_url(&headers)))
+}
+
+/// Start a task for the incoming message, or pass it to the task it answers. Updates go to
+/// `events` when the client is streaming. Returns the task as it stands.
+async fn submit(
+    state: &Arc<AppState>,
+    params: MessageSendParams,
+    events: Option<EventSink>,
+) -> Result<Task, RpcError> {
+    let mut message = params.message;
+    let goose_message = message
+        .to_goose()
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Message has no text or data parts"))?;
+    if let Some(task_id) = message.task_id.clone() {
+        if state.a2a_tasks.get(&task_id).await.is_some() {
+            return resume(state, &task_id, message, &goose_message, events).await;
+        }
+    }
+    let context_id = match message.context_id.clone() {
+        // Used in a session name, so it must not be able to form a path
+        Some(id)
+            if id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            id
+        }
+        Some(_) => return Err(RpcError::new(INVALID_PARAMS, "Invalid contextId")),
+        None => uuid::Uuid::new_v4().to_string(),
+    };
+    let task_id = uuid::Uuid::new_v4().to_string();
+    message.task_id = Some(task_id.clone());
+    message.context_id = Some(context_id.clone());
+
+    let task = Task {
+        id: task_id,
+        context_id,
+        status: TaskStatus::new(TaskState::Submitted, None),
+        history: vec![message],
+        artifacts: Vec::new(),
+        kind: "task".to_string(),
+    };
+    let cancel = CancellationToken::new();
+    state.a2a_tasks.insert(task.clone(), cancel.clone()).await?;
+    if let Some(events) = events {
+        events.send(StreamResult::Task(task.clone())).await;
+        state.a2a_tasks.follow(&task.id, events).await;
+    }
+    tokio::spawn(run_task(state.clone(), task.clone(), goose_message, cancel));
+    Ok(task)
+}
+
+/// Answer the tool confirmation an input-required task is waiting on
+async fn resume(
+    state: &AppState,
+    task_id: &str,
+    mut message: A2aMessage,
+    goose_message: &Message,
+    events: Option<EventSink>,
+) -> Result<Task, RpcError> {
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))?;
+    message.task_id = Some(task_id.to_string());
+    let confirmation_id = state.a2a_tasks.answer(task_id, message).await?;
+    let reply = goose_message.as_concat_text().trim().to_lowercase();
+    let permission = if APPROVALS.contains(&reply.as_str()) {
+        Permission::AllowOnce
+    } else {
+        Permission::DenyOnce
+    };
+
+    let task = state
+        .a2a_tasks
+        .get(task_id)
+        .await
+        .ok_or_else(|| RpcError::new(TASK_NOT_FOUND, "Task not found"))?;
+    if let Some(events) = events {
+        events.send(StreamResult::Task(task.clone())).await;
+        state.a2a_tasks.follow(task_id, events).await;
+    }
+    agent
+        .handle_confirmation(
+            confirmation_id,
+            PermissionConfirmation {
+                principal_type: PrincipalType::Tool,
+                permission,
+            },
+        )
+        .await;
+    Ok(task)
+}
+
+/// Drive the agent for one task, recording progress in the task store, which passes each
+/// update on to a stream following the task
+async fn run_task(state: Arc<AppState>, task: Task, message: Message, cancel: CancellationToken) {
+    let tasks = &state.a2a_tasks;
+    let task_id = task.id.clone();
+
+    let queued = QueuedRun::new(task.clone(), message.clone());
+    let Some(_slot) = state.run_queue.wait_turn(queued, &cancel).await else {
+        tasks
+            .set_status(&task_id, TaskStatus::new(TaskState::Canceled, None))
+            .await;
+        return;
+    };
+
+    let result = execute(&state, &task, message, &cancel).await;
+
+    let status = match result {
+        _ if cancel.is_cancelled() => TaskStatus::new(TaskState::Canceled, None),
+        Ok(Some(answer)) => {
+            let artifact = Artifact {
+                artifact_id: uuid::Uuid::new_v4().to_string(),
+                name: Some("response".to_string()),
+                parts: vec![Part::Text { text: answer }],
+            };
+            tasks
+                .update(&task_id, |t| t.artifacts.push(artifact.clone()))
+                .await;
+            tasks
+                .publish(
+                    &task_id,
+                    StreamResult::ArtifactUpdate(TaskArtifactUpdateEvent {
+                        task_id: task_id.clone(),
+                        context_id: task.context_id.clone(),
+                        kind: "artifact-update",
+                        artifact,
+                        last_chunk: true,
+                    }),
+                )
+                .await;
+            TaskStatus::new(TaskState::Completed, None)
+        }
+        Ok(None) => TaskStatus::new(TaskState::Completed, None),
+        Err(error) => {
+            tracing::error!("A2A task {} failed: {}", task_id, error);
+            TaskStatus::new(
+                TaskState::Failed,
+                Some(A2aMessage::agent(error, &task_id, &task.context_id)),
+            )
+        }
+    };
+    tasks.set_status(&task_id, status).await;
+}
+
+/// Returns the agent's final answer, if it gave one
+async fn execute(
+    state: &AppState,
+    task: &Task,
+    message: Message,
+    cancel: &CancellationToken,
+) -> Result<Option<String>, String> {
+    let tasks = &state.a2a_tasks;
+    let agent = state.get_agent().await.map_err(|e| e.to_string())?;
+    let session_id = format!("a2a-{}", task.context_id);
+    let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+        .map_err(|e| e.to_string())?;
+    // Tasks in one context take turns, so each builds on the history the previous one saved
+    let lock_path = session_path.clone();
+    let _lock = tokio::task::spawn_blocking(move || session::lock_session(&lock_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let mut conversation = session::read_messages(&session_path).map_err(|e| e.to_string())?;
+    let saved_message_count = conversation.len();
+    conversation.push(message);
+    let working_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+
+    tasks
+        .set_status(&task.id, TaskStatus::new(TaskState::Working, None))
+        .await;
+
+    let session_config = SessionConfig {
+        id: session::Identifier::Name(session_id),
+        working_dir: working_dir.clone(),
+        schedule_id: None,
+        execution_mode: None,
+        max_turns: None,
+        run_limits: Default::default(),
+        critique: None,
+        retry_config: None,
+    };
+    let mut stream = agent
+        .reply(
+            conversation.clone(),
+            Some(session_config),
+            Some(cancel.clone()),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut answer = None;
+    let mut result = Ok(());
+    loop {
+        let event = tokio::select! {
+            _ = cancel.cancelled() => break,
+            event = stream.next() => event,
+        };
+        match event {
+            Some(Ok(AgentEvent::Message(message))) => {
+                conversation.push(message.clone());
+                let confirmation = message.content.iter().find_map(|content| match content {
+                    MessageContent::ToolConfirmationRequest(request) => Some(request.clone()),
+                    _ => None,
+                });
+                if let Some(request) = confirmation {
+                    let question = format!(
+                        "goose wants to run {} with {}. Reply \"approve\" to allow it; any \
+                         other reply denies it.",
+                        request.tool_name, request.arguments
+                    );
+                    tasks
+                        .request_input(
+                            &task.id,
+                            request.id,
+                            A2aMessage::agent(question, &task.id, &task.context_id),
+                        )
+                        .await;
+                    continue;
+                }
+                let text = message.as_concat_text();
+                if message.role != Role::Assistant || text.trim().is_empty() {
+                    continue;
+                }
+                answer = Some(text.clone());
+                let update = A2aMessage::agent(text, &task.id, &task.context_id);
+                let status = TaskStatus::new(TaskState::Working, Some(update.clone()));
+                tasks.update(&task.id, |t| t.history.push(update)).await;
+                tasks.set_status(&task.id, status).await;
+            }
+            Some(Ok(AgentEvent::HistoryReplaced(messages))) => {
+                conversation = Conversation::new_unvalidated(messages);
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => {
+                result = Err(e.to_string());
+                break;
+            }
+            None => break,
+        }
+    }
+
+    if conversation.len() > saved_message_count {
+        let provider = agent.provider().await.ok();
+        if let Err(e) =
+            session::persist_messages(&session_path, &conversation, provider, Some(working_dir))
+                .await
+        {
+            tracing::error!("Failed to store A2A session history: {:?}", e);
+        }
+    }
+    result.map(|_| answer)
+}
+
+/// Pick up the tasks that were still queued when the server last stopped
+pub async fn resume_queued(state: Arc<AppState>, queue_path: PathBuf) {
+    let restored = state.run_queue.restore(queue_path);
+    if !restored.is_empty() {
+        tracing::info!("Resuming {} queued A2A task(s)", restored.len());
+    }
+    for run in restored {
+        let cancel = CancellationToken::new();
+        if let Err(e) = state
+            .a2a_tasks
+            .insert(run.task.clone(), cancel.clone())
+            .await
+        {
+            tracing::error!("Failed to resume A2A task {}: {}", run.task.id, e.message);
+            continue;
+        }
+        tokio::spawn(run_task(state.clone(), run.task, run.message, cancel));
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/a2a",
+    request_body(content = Value, description = "A2A JSON-RPC 2.0 request"),
+    responses(
+        (status = 200, description = "JSON-RPC response; message/stream answers with server-sent events instead", content(
+            (Value = "application/json"),
+            (Value = "text/event-stream")
+        )),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "A2A"
+)]
+async fn handle_rpc(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Response, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let request: RpcRequest = match serde_json::from_str(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = RpcError::new(PARSE_ERROR, e.to_string());
+            return Ok(Json(rpc_response(&Value::Null, Err::<(), _>(error))).into_response());
+        }
+    };
+    let id = request.id;
+
+    let result = match request.method.as_str() {
+        "message/send" => match params(request.params) {
+            Ok(p) => match submit(&state, p, None).await {
+                Ok(task) => state
+                    .a2a_tasks
+                    .wait_for_answer(&task.id)
+                    .await
+                    .ok_or_else(|| RpcError::new(INTERNAL_ERROR, "Task was dropped")),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        },
+        "message/stream" => {
+            let (tx, rx) = mpsc::channel(100);
+            let events = EventSink {
+                tx,
+                request_id: id.clone(),
+            };
+            let submitted = match params(request.params) {
+                Ok(p) => submit(&state, p, Some(events)).await,
+                Err(e) => Err(e),
+            };
+            return Ok(match submitted {
+                Ok(_) => SseResponse::new(ReceiverStream::new(rx)).into_response(),
+                Err(e) => Json(rpc_response(&id, Err::<(), _>(e))).into_response(),
+            });
+        }
+        "tasks/get" => match params::<TaskQueryParams>(request.params) {
+            Ok(p) => match state.a2a_tasks.get(&p.id).await {
+                Some(mut task) => {
+                    if let Some(length) = p.history_length {
+                        let skip = task.history.len().saturating_sub(length);
+                        task.history.drain(..skip);
+                    }
+                    Ok(task)
+                }
+                None => Err(RpcError::new(TASK_NOT_FOUND, "Task not found")),
+            },
+            Err(e) => Err(e),
+        },
+        "tasks/cancel" => match params::<TaskQueryParams>(request.params) {
+            Ok(p) => state.a2a_tasks.cancel(&p.id).await,
+            Err(e) => Err(e),
+        },
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    };
+    Ok(Json(rpc_response(&id, result)).into_response())
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/.well-known/agent.json", get(get_agent_card))
+        .route("/.well-known/agent-card.json", get(get_agent_card))
+        .route("/a2a", post(handle_rpc))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use goose::agents::Agent;
+    use tower::ServiceExt;
+
+    async fn rpc(app: Router, body: Value) -> Value {
+        let request = Request::builder()
+            .uri("/a2a")
+            .method("POST")
+            .header("x-secret-key", "test-secret")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_agent_card() {
+        let state = AppState::new(Arc::new(Agent::new()), "test-secret".to_string()).await;
+        let request = Request::builder()
+            .uri("/.well-known/agent.json")
+            .header("host", "127.0.0.1:3000")
+            .body(Body::empty())
+            .unwrap();
+        let response = routes(state).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let card: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(card["url"], "http://127.0.0.1:3000/a2a");
+        assert_eq!(card["capabilities"]["streaming"], true);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_errors() {
+        let state = AppState::new(Arc::new(Agent::new()), "test-secret".to_string()).await;
+
+        let response = rpc(
+            routes(state.clone()),
+            json!({"jsonrpc": "2.0", "id": 1, "method": "tasks/resubscribe", "params": {}}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = rpc(
+            routes(state.clone()),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tasks/get", "params": {"id": "nope"}}),
+        )
+        .await;
+        assert_eq!(response["id"], 2);
+        assert_eq!(response["error"]["code"], TASK_NOT_FOUND);
+
+        let response = rpc(
+            routes(state),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "message/send", "params": {
+                "message": {
+                    "role": "user",
+                    "parts": [{"kind": "text", "text": "hi"}],
+                    "messageId": "m1",
+                    "contextId": "../../etc"
+                }
+            }}),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_open_tasks_are_capped() {
+        let task = |id: usize, state| Task {
+            id: id.to_string(),
+            context_id: "ctx".to_string(),
+            status: TaskStatus::new(state, None),
+            history: Vec::new(),
+            artifacts: Vec::new(),
+            kind: "task".to_string(),
+        };
+        let tasks = A2aTasks::default();
+        for id in 0..MAX_TASKS {
+            let state = if id == 0 {
+                TaskState::Completed
+            } else {
+                TaskState::Working
+            };
+            tasks
+                .insert(task(id, state), CancellationToken::new())
+                .await
+                .unwrap();
+        }
+
+        // The finished task makes room for one more
+        tasks
+            .insert(
+                task(MAX_TASKS, TaskState::Submitted),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+        assert!(tasks.get("0").await.is_none());
+
+        let error = tasks
+            .insert(
+                task(MAX_TASKS + 1, TaskState::Submitted),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, TOO_MANY_TASKS);
+    }
+}
@@ -1,4 +1,4 @@
-use super::utils::verify_secret_key;
+use super::utils::{request_locale, verify_secret_key};
 use crate::state::AppState;
 use axum::{
     extract::{Query, State},
@@ -6,10 +6,14 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use goose::agents::prompt_analysis::PromptAnalysis;
 use goose::config::PermissionManager;
+use goose::i18n;
 use goose::model::ModelConfig;
 use goose::providers::create;
 use goose::recipe::Response;
+use goose::session;
+use goose::style;
 use goose::{
     agents::{extension::ToolInfo, extension_manager::get_parameter_names},
     config::permission::PermissionLevel,
@@ -47,6 +51,12 @@ pub struct UpdateProviderRequest {
 #[derive(Deserialize, utoipa::ToSchema)]
 pub struct SessionConfigRequest {
     response: Option<Response>,
+    /// Session the recipe runs in, which the settings below are saved on
+    #[serde(default)]
+    session_id: Option<String>,
+    /// Style preset from the recipe, kept with the session so others keep their own
+    #[serde(default)]
+    style: Option<String>,
 }
 
 #[derive(Deserialize, utoipa::ToSchema)]
@@ -84,6 +94,37 @@ async fn add_sub_recipes(
     Ok(Json(AddSubRecipesResponse { success: true }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/agent/prompt/analysis",
+    responses(
+        (status = 200, description = "Tokens the system prompt and tools take up, by source, with suggestions for what to disable", body = PromptAnalysis),
+        (status = 401, description = "Unauthorized - invalid secret key"),
+        (status = 424, description = "Agent not initialized"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Context Management"
+)]
+async fn analyze_prompt(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<PromptAnalysis>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let analysis = agent.analyze_prompt().await.map_err(|e| {
+        tracing::error!("Failed to analyze the system prompt: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(analysis))
+}
+
 #[utoipa::path(
     post,
     path = "/agent/prompt",
@@ -229,16 +270,21 @@ async fn update_router_tool_selector(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
 ) -> Result<Json<String>, Json<ErrorResponse>> {
+    let locale = request_locale(&headers);
     verify_secret_key(&headers, &state).map_err(|_| {
         Json(ErrorResponse {
-            error: "Unauthorized - Invalid or missing API key".to_string(),
+            error: i18n::translate(locale, "api.unauthorized", &[]),
         })
     })?;
 
     let agent = state.get_agent().await.map_err(|e| {
         tracing::error!("Failed to get agent: {}", e);
         Json(ErrorResponse {
-            error: format!("Failed to get agent: {}", e),
+            error: i18n::translate(
+                locale,
+                "api.agent_unavailable",
+                &[("error", &e.to_string())],
+            ),
         })
     })?;
 
@@ -248,7 +294,11 @@ async fn update_router_tool_selector(
         .map_err(|e| {
             tracing::error!("Failed to update tool selection strategy: {}", e);
             Json(ErrorResponse {
-                error: format!("Failed to update tool selection strategy: {}", e),
+                error: i18n::translate(
+                    locale,
+                    "api.tool_selection_failed",
+                    &[("error", &e.to_string())],
+                ),
             })
         })?;
 
@@ -273,19 +323,52 @@ async fn update_session_config(
     headers: HeaderMap,
     Json(payload): Json<SessionConfigRequest>,
 ) -> Result<Json<String>, Json<ErrorResponse>> {
+    let locale = request_locale(&headers);
     verify_secret_key(&headers, &state).map_err(|_| {
         Json(ErrorResponse {
-            error: "Unauthorized - Invalid or missing API key".to_string(),
+            error: i18n::translate(locale, "api.unauthorized", &[]),
         })
     })?;
 
     let agent = state.get_agent().await.map_err(|e| {
         tracing::error!("Failed to get agent: {}", e);
         Json(ErrorResponse {
-            error: format!("Failed to get agent: {}", e),
+            error: i18n::translate(
+                locale,
+                "api.agent_unavailable",
+                &[("error", &e.to_string())],
+            ),
         })
     })?;
 
+    if let Some(style) = payload.style {
+        let error = |key: &str, args: &[(&str, &str)]| {
+            Json(ErrorResponse {
+                error: i18n::translate(locale, key, args),
+            })
+        };
+        let Some(session_id) = payload.session_id else {
+            return Err(error("api.session_required", &[]));
+        };
+        if style::find_preset(&style).is_none() {
+            return Err(error("api.unknown_style", &[("style", &style)]));
+        }
+        let saved = async {
+            let path = session::get_path(session::Identifier::Name(session_id))?;
+            let mut metadata = session::read_metadata(&path)?;
+            metadata.style = Some(style);
+            session::update_metadata(&path, &metadata).await
+        };
+        saved.await.map_err(|e| {
+            tracing::error!("Failed to save the recipe's style: {}", e);
+            error("api.session_config_failed", &[("error", &e.to_string())])
+        })?;
+        tracing::info!("Set the session's style from the recipe");
+        if payload.response.is_none() {
+            return Ok(Json("Session config updated with style".to_string()));
+        }
+    }
+
     if let Some(response) = payload.response {
         agent.add_final_output_tool(response).await;
 
@@ -301,6 +384,7 @@ async fn update_session_config(
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/agent/prompt", post(extend_prompt))
+        .route("/agent/prompt/analysis", get(analyze_prompt))
         .route("/agent/tools", get(get_tools))
         .route("/agent/update_provider", post(update_agent_provider))
         .route(
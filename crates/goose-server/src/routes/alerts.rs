@@ -0,0 +1,61 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use goose::anomaly::{self, Alert};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct AlertsQuery {
+    /// Only alerts raised at or after this Unix timestamp (seconds)
+    since: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertListResponse {
+    /// Alerts, most recent first
+    alerts: Vec<Alert>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/alerts",
+    params(
+        AlertsQuery
+    ),
+    responses(
+        (status = 200, description = "Token spikes, repeated tool failures and looping runs noticed so far", body = AlertListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Analytics"
+)]
+async fn list_alerts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<AlertsQuery>,
+) -> Result<Json<AlertListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let alerts = anomaly::read_alerts(query.since).map_err(|e| {
+        tracing::error!("Failed to read alerts: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(AlertListResponse { alerts }))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/alerts", get(list_alerts))
+        .with_state(state)
+}
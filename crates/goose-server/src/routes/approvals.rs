@@ -0,0 +1,230 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use goose::agents::PendingApproval;
+use goose::permission::permission_confirmation::PrincipalType;
+use goose::permission::risk::{auto_approved_categories, set_auto_approved_categories};
+use goose::permission::{Permission, PermissionConfirmation, RiskCategory};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, IntoParams)]
+pub struct ApprovalsQuery {
+    /// Only approvals the reply of this session is waiting on
+    session_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalListResponse {
+    approvals: Vec<PendingApproval>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalAction {
+    AllowOnce,
+    AlwaysAllow,
+    Deny,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ApprovalDecision {
+    action: ApprovalAction,
+    /// What the user entered for an extension's request for input, shaped by the schema in
+    /// the request's `arguments`; ignored when the action is `deny`
+    #[serde(default)]
+    content: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoApproveCategories {
+    /// Risk categories that smart approve runs without asking
+    categories: Vec<RiskCategory>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/approvals",
+    params(
+        ApprovalsQuery
+    ),
+    responses(
+        (status = 200, description = "Tool calls waiting for approval", body = ApprovalListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "Precondition failed - Agent not available")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Approvals"
+)]
+async fn list_approvals(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ApprovalsQuery>,
+) -> Result<Json<ApprovalListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    Ok(Json(ApprovalListResponse {
+        approvals: agent.list_pending_approvals(query.session_id.as_deref()),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/approvals/{id}",
+    request_body = ApprovalDecision,
+    params(
+        ("id" = String, Path, description = "Id of the pending tool request")
+    ),
+    responses(
+        (status = 204, description = "Decision delivered to the agent"),
+        (status = 400, description = "Bad request - The content is not a JSON object"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "No pending approval with this id, or it has expired"),
+        (status = 412, description = "Precondition failed - Agent not available")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Approvals"
+)]
+async fn decide_approval(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(decision): Json<ApprovalDecision>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    if let Some(content) = decision.content {
+        let serde_json::Value::Object(content) = content else {
+            return Err(StatusCode::BAD_REQUEST);
+        };
+        let content = match decision.action {
+            ApprovalAction::Deny => None,
+            ApprovalAction::AllowOnce | ApprovalAction::AlwaysAllow => Some(content),
+        };
+        return if agent.answer_elicitation(&id, content) {
+            Ok(StatusCode::NO_CONTENT)
+        } else {
+            Err(StatusCode::NOT_FOUND)
+        };
+    }
+
+    let permission = match decision.action {
+        ApprovalAction::AllowOnce => Permission::AllowOnce,
+        ApprovalAction::AlwaysAllow => Permission::AlwaysAllow,
+        ApprovalAction::Deny => Permission::DenyOnce,
+    };
+    let confirmation = PermissionConfirmation {
+        principal_type: PrincipalType::Tool,
+        permission,
+    };
+
+    if agent.answer_approval(id, confirmation).await {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/approvals/auto-approve",
+    responses(
+        (status = 200, description = "Risk categories approved without asking", body = AutoApproveCategories),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Approvals"
+)]
+async fn get_auto_approve(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<AutoApproveCategories>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(Json(AutoApproveCategories {
+        categories: auto_approved_categories(),
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/approvals/auto-approve",
+    request_body = AutoApproveCategories,
+    responses(
+        (status = 200, description = "Auto-approved categories saved", body = AutoApproveCategories),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Approvals"
+)]
+async fn set_auto_approve(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<AutoApproveCategories>,
+) -> Result<Json<AutoApproveCategories>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    set_auto_approved_categories(&request.categories)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(request))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/approvals", get(list_approvals))
+        .route(
+            "/approvals/auto-approve",
+            get(get_auto_approve).put(set_auto_approve),
+        )
+        .route("/approvals/{id}", post(decide_approval))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approval_decision_deserialization() {
+        let decision: ApprovalDecision =
+            serde_json::from_str(r#"{"action": "always_allow"}"#).unwrap();
+        assert!(matches!(decision.action, ApprovalAction::AlwaysAllow));
+        assert!(decision.content.is_none());
+
+        let answer: ApprovalDecision =
+            serde_json::from_str(r#"{"action": "allow_once", "content": {"profile": "staging"}}"#)
+                .unwrap();
+        assert_eq!(
+            answer.content,
+            Some(serde_json::json!({"profile": "staging"}))
+        );
+        assert!(serde_json::from_str::<ApprovalDecision>(r#"{"action": "maybe"}"#).is_err());
+    }
+}
@@ -15,25 +15,26 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
+use utoipa::ToSchema;
 
 // Constants
 const MAX_AUDIO_SIZE_BYTES: usize = 25 * 1024 * 1024; // 25MB
 const OPENAI_TIMEOUT_SECONDS: u64 = 30;
 
-#[derive(Debug, Deserialize)]
-struct TranscribeRequest {
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TranscribeRequest {
     audio: String, // Base64 encoded audio data
     mime_type: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct TranscribeElevenLabsRequest {
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TranscribeElevenLabsRequest {
     audio: String, // Base64 encoded audio data
     mime_type: String,
 }
 
-#[derive(Debug, Serialize)]
-struct TranscribeResponse {
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TranscribeResponse {
     text: String,
 }
 
@@ -59,6 +60,25 @@ struct WhisperResponse {
 /// - 415: Unsupported Media Type (unsupported audio format)
 /// - 502: Bad Gateway (OpenAI API error)
 /// - 503: Service Unavailable (network error)
+#[utoipa::path(
+    post,
+    path = "/audio/transcribe",
+    request_body = TranscribeRequest,
+    responses(
+        (status = 200, description = "Transcribed text", body = TranscribeResponse),
+        (status = 400, description = "Invalid base64 audio data"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "OpenAI API key not configured"),
+        (status = 413, description = "Audio exceeds 25MB"),
+        (status = 415, description = "Unsupported audio format"),
+        (status = 502, description = "OpenAI API error"),
+        (status = 503, description = "Network error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Audio"
+)]
 async fn transcribe_handler(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -170,6 +190,25 @@ async fn transcribe_handler(
 ///
 /// Uses ElevenLabs' speech-to-text endpoint for transcription.
 /// Requires an ElevenLabs API key with speech-to-text access.
+#[utoipa::path(
+    post,
+    path = "/audio/transcribe/elevenlabs",
+    request_body = TranscribeElevenLabsRequest,
+    responses(
+        (status = 200, description = "Transcribed text", body = TranscribeResponse),
+        (status = 400, description = "Invalid base64 audio data"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "ElevenLabs API key not configured"),
+        (status = 413, description = "Audio exceeds 25MB"),
+        (status = 415, description = "Unsupported audio format"),
+        (status = 502, description = "ElevenLabs API error"),
+        (status = 503, description = "Network error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Audio"
+)]
 async fn transcribe_elevenlabs_handler(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -321,6 +360,18 @@ async fn transcribe_elevenlabs_handler(
 /// Check if dictation providers are configured
 ///
 /// Returns configuration status for dictation providers
+#[utoipa::path(
+    get,
+    path = "/audio/config",
+    responses(
+        (status = 200, description = "Which dictation providers are configured, e.g. {\"elevenlabs\": true}", body = serde_json::Value),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Audio"
+)]
 async fn check_dictation_config(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
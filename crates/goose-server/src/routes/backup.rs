@@ -0,0 +1,164 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use goose::backup::{
+    create_backup, restore_backup, BackupCategory, BackupLocations, RestoreSummary,
+};
+use goose::config::Config;
+use serde::Deserialize;
+use std::io::Cursor;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+pub struct BackupQuery {
+    /// Comma-separated categories (sessions, config, recipes, memories, schedules); all when omitted
+    only: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct RestoreQuery {
+    /// Comma-separated categories to restore; all when omitted
+    only: Option<String>,
+    /// Replace files that already exist
+    #[serde(default)]
+    overwrite: bool,
+}
+
+fn parse_categories(only: Option<&str>) -> Result<Vec<BackupCategory>, StatusCode> {
+    match only {
+        None => Ok(BackupCategory::ALL.to_vec()),
+        Some(list) => list
+            .split(',')
+            .map(|c| c.trim().parse().map_err(|_| StatusCode::BAD_REQUEST))
+            .collect(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/backup",
+    params(BackupQuery),
+    responses(
+        (status = 200, description = "Zip of the selected data with a checksummed manifest; credentials are never included", content_type = "application/zip", body = Vec<u8>),
+        (status = 400, description = "Unknown category"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Backup"
+)]
+async fn create_backup_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<BackupQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let categories = parse_categories(query.only.as_deref())?;
+
+    let mut archive = Cursor::new(Vec::new());
+    BackupLocations::from_app_dirs()
+        .and_then(|locations| {
+            create_backup(&mut archive, Config::global(), &locations, &categories)
+        })
+        .map_err(|e| {
+            tracing::error!("Failed to create backup: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let filename = format!(
+        "goose-backup-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        archive.into_inner(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/backup/restore",
+    params(RestoreQuery),
+    request_body(content = Vec<u8>, description = "Archive from GET /backup", content_type = "application/zip"),
+    responses(
+        (status = 200, description = "Backup restored", body = RestoreSummary),
+        (status = 400, description = "Unknown category, or the archive is damaged or not a backup"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Backup"
+)]
+async fn restore_backup_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<RestoreQuery>,
+    body: Bytes,
+) -> Result<Json<RestoreSummary>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let categories = parse_categories(query.only.as_deref())?;
+
+    let summary = BackupLocations::from_app_dirs()
+        .and_then(|locations| {
+            restore_backup(
+                Cursor::new(body),
+                Config::global(),
+                &locations,
+                &categories,
+                query.overwrite,
+            )
+        })
+        .map_err(|e| {
+            tracing::warn!("Failed to restore backup: {:?}", e);
+            StatusCode::BAD_REQUEST
+        })?;
+    Ok(Json(summary))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/backup", get(create_backup_handler))
+        .route(
+            "/backup/restore",
+            post(restore_backup_handler).layer(DefaultBodyLimit::max(1024 * 1024 * 1024)),
+        )
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_categories() {
+        assert_eq!(
+            parse_categories(None).unwrap(),
+            BackupCategory::ALL.to_vec()
+        );
+        assert_eq!(
+            parse_categories(Some("sessions, memories")).unwrap(),
+            vec![BackupCategory::Sessions, BackupCategory::Memories]
+        );
+        assert_eq!(
+            parse_categories(Some("sessions,logs")).unwrap_err(),
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
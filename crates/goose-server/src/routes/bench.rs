@@ -0,0 +1,80 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use goose::bench_results::{BenchResultStore, TrendBucket, TrendPoint, TrendQuery};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct BenchResultsQuery {
+    /// Only results from this provider
+    provider: Option<String>,
+    /// Only results from this model
+    model: Option<String>,
+    /// Only results from this suite, e.g. `core:developer`
+    suite: Option<String>,
+    /// Only results recorded at or after this time (RFC 3339)
+    since: Option<DateTime<Utc>>,
+    /// Group results by `day` (the default), `week` or `commit`
+    #[param(value_type = Option<TrendBucket>)]
+    bucket: Option<TrendBucket>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchResultsResponse {
+    bucket: TrendBucket,
+    points: Vec<TrendPoint>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/bench/results",
+    params(BenchResultsQuery),
+    responses(
+        (status = 200, description = "Benchmark results aggregated per bucket, model and suite, oldest first", body = BenchResultsResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Bench"
+)]
+async fn bench_results(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<BenchResultsQuery>,
+) -> Result<Json<BenchResultsResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let bucket = query.bucket.unwrap_or_default();
+    let points = BenchResultStore::open_default()
+        .and_then(|store| {
+            store.trend(&TrendQuery {
+                provider: query.provider,
+                model: query.model,
+                suite: query.suite,
+                since: query.since,
+                bucket,
+            })
+        })
+        .map_err(|e| {
+            tracing::error!("Failed to read benchmark results: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(BenchResultsResponse { bucket, points }))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/bench/results", get(bench_results))
+        .with_state(state)
+}
@@ -0,0 +1,322 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use futures::future::join_all;
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use goose::model::ModelConfig;
+use goose::providers::base::Provider;
+use goose::providers::create;
+use goose::session;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+const MAX_COMPARE_MODELS: usize = 5;
+
+/// Creates the provider for a model under comparison, from its provider name
+type CreateProvider = fn(&str, ModelConfig) -> anyhow::Result<Arc<dyn Provider>>;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareModel {
+    /// Provider name, e.g. "openai"
+    pub provider: String,
+    /// Model name for that provider
+    pub model: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareRequest {
+    /// Conversation to send to every model
+    pub messages: Vec<Message>,
+    /// Two or more models to compare
+    pub models: Vec<CompareModel>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareResult {
+    pub provider: String,
+    pub model: String,
+    /// Session the run was stored in
+    pub session_id: String,
+    /// The model's reply, absent if the request failed
+    pub message: Option<Message>,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareResponse {
+    /// Recorded as the `comparison_id` of every run's session
+    pub comparison_id: String,
+    pub results: Vec<CompareResult>,
+}
+
+async fn run_model(
+    create_provider: CreateProvider,
+    comparison_id: &str,
+    index: usize,
+    target: CompareModel,
+    system_prompt: &str,
+    conversation: &Conversation,
+    tools: &[rmcp::model::Tool],
+) -> CompareResult {
+    let session_id = format!("{}_compare{}", comparison_id, index);
+    let mut result = CompareResult {
+        provider: target.provider.clone(),
+        model: target.model.clone(),
+        session_id: session_id.clone(),
+        message: None,
+        error: None,
+        latency_ms: 0,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+    };
+
+    let provider = match ModelConfig::new(&target.model)
+        .map_err(anyhow::Error::from)
+        .and_then(|config| create_provider(&target.provider, config))
+    {
+        Ok(provider) => provider,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+
+    let start = Instant::now();
+    let completion = provider
+        .complete(system_prompt, conversation.messages(), tools)
+        .await;
+    result.latency_ms = start.elapsed().as_millis() as u64;
+
+    let (message, usage) = match completion {
+        Ok(completion) => completion,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return result;
+        }
+    };
+    result.input_tokens = usage.usage.input_tokens;
+    result.output_tokens = usage.usage.output_tokens;
+    result.total_tokens = usage.usage.total_tokens;
+
+    let mut messages = conversation.clone();
+    messages.push(message.clone());
+    let stored = session::get_path(session::Identifier::Name(session_id)).and_then(|path| {
+        let metadata = session::SessionMetadata {
+            description: format!(
+                "Comparison {}: {}/{}",
+                comparison_id, target.provider, target.model
+            ),
+            message_count: messages.len(),
+            total_tokens: usage.usage.total_tokens,
+            input_tokens: usage.usage.input_tokens,
+            output_tokens: usage.usage.output_tokens,
+            accumulated_total_tokens: usage.usage.total_tokens,
+            accumulated_input_tokens: usage.usage.input_tokens,
+            accumulated_output_tokens: usage.usage.output_tokens,
+            comparison_id: Some(comparison_id.to_string()),
+            ..Default::default()
+        };
+        session::storage::save_messages_with_metadata(&path, &metadata, &messages)
+    });
+    if let Err(e) = stored {
+        tracing::warn!("Failed to store comparison run: {}", e);
+    }
+
+    result.message = Some(message);
+    result
+}
+
+#[utoipa::path(
+    post,
+    path = "/compare",
+    request_body = CompareRequest,
+    responses(
+        (status = 200, description = "All models were run; per-model failures are reported inline", body = CompareResponse),
+        (status = 400, description = "Bad request - fewer than two or too many models"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "Precondition failed - Agent not available"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Model Comparison"
+)]
+async fn compare(
+    state: State<Arc<AppState>>,
+    headers: HeaderMap,
+    request: Json<CompareRequest>,
+) -> Result<Json<CompareResponse>, StatusCode> {
+    compare_with(create, state, headers, request).await
+}
+
+async fn compare_with(
+    create_provider: CreateProvider,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<CompareRequest>,
+) -> Result<Json<CompareResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    if request.models.len() < 2 || request.models.len() > MAX_COMPARE_MODELS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    // Every model sees the same system prompt and tools the agent would use
+    let (tools, _toolshim_tools, system_prompt) = agent
+        .prepare_tools_and_prompt(None)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let conversation = Conversation::new_unvalidated(request.messages);
+    let comparison_id = session::generate_session_id();
+
+    let results = join_all(request.models.into_iter().enumerate().map(|(i, target)| {
+        run_model(
+            create_provider,
+            &comparison_id,
+            i,
+            target,
+            &system_prompt,
+            &conversation,
+            &tools,
+        )
+    }))
+    .await;
+
+    Ok(Json(CompareResponse {
+        comparison_id,
+        results,
+    }))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/compare", post(compare))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use goose::agents::Agent;
+    use goose::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use goose::providers::errors::ProviderError;
+
+    struct MockProvider {
+        model_config: ModelConfig,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MockProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            _messages: &[Message],
+            _tools: &[rmcp::model::Tool],
+        ) -> anyhow::Result<(Message, ProviderUsage), ProviderError> {
+            let reply = format!("Hello from {}", self.model_config.model_name);
+            Ok((
+                Message::assistant().with_text(reply),
+                ProviderUsage::new(self.model_config.model_name.clone(), Usage::default()),
+            ))
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+    }
+
+    fn create_mock(_name: &str, model_config: ModelConfig) -> anyhow::Result<Arc<dyn Provider>> {
+        Ok(Arc::new(MockProvider { model_config }))
+    }
+
+    #[test]
+    fn test_compare_request_deserialization() {
+        let json = r#"{
+            "messages": [],
+            "models": [
+                {"provider": "openai", "model": "gpt-4o"},
+                {"provider": "anthropic", "model": "claude-sonnet-4"}
+            ]
+        }"#;
+        let request: CompareRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.models.len(), 2);
+        assert_eq!(request.models[1].provider, "anthropic");
+    }
+
+    #[tokio::test]
+    async fn test_compare_stores_linked_sessions() {
+        let agent = Agent::new();
+        let _ = agent
+            .update_provider(create_mock("mock", ModelConfig::new("mock-model").unwrap()).unwrap())
+            .await;
+        let state = AppState::new(Arc::new(agent), "test".to_string()).await;
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Secret-Key", "test".parse().unwrap());
+        let request = CompareRequest {
+            messages: vec![Message::user().with_text("Say hello")],
+            models: vec![
+                CompareModel {
+                    provider: "openai".to_string(),
+                    model: "gpt-4o".to_string(),
+                },
+                CompareModel {
+                    provider: "anthropic".to_string(),
+                    model: "claude-sonnet-4".to_string(),
+                },
+            ],
+        };
+
+        let Json(response) = compare_with(create_mock, State(state), headers, Json(request))
+            .await
+            .unwrap();
+
+        assert_eq!(response.results.len(), 2);
+        for (result, model) in response.results.iter().zip(["gpt-4o", "claude-sonnet-4"]) {
+            assert_eq!(result.error, None);
+            assert_eq!(result.model, model);
+            let path =
+                session::get_path(session::Identifier::Name(result.session_id.clone())).unwrap();
+            let metadata = session::read_metadata(&path).unwrap();
+            let messages = session::read_messages(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+
+            assert_eq!(
+                metadata.comparison_id.as_ref(),
+                Some(&response.comparison_id)
+            );
+            assert_eq!(messages.len(), 2);
+            assert_eq!(
+                messages.messages()[1].as_concat_text(),
+                format!("Hello from {}", model)
+            );
+        }
+    }
+}
@@ -7,8 +7,13 @@ use axum::{
     Json, Router,
 };
 use etcetera::{choose_app_strategy, AppStrategy};
+use goose::config::settings::{
+    store_secrets, validate_secrets, SettingsValidationError, SETTINGS_KEYS,
+};
+use goose::config::team::{sync_team_config, TeamConfigSource, TeamSyncResult, TEAM_CONFIG_KEY};
+use goose::config::GooseSettings;
 use goose::config::APP_STRATEGY;
-use goose::config::{Config, ConfigError};
+use goose::config::{Config, ConfigError, ConfigSource, EffectiveValue};
 use goose::config::{ExtensionConfigManager, ExtensionEntry};
 use goose::model::ModelConfig;
 use goose::providers::base::ProviderMetadata;
@@ -52,6 +57,51 @@ pub struct ConfigKeyQuery {
 #[derive(Serialize, ToSchema)]
 pub struct ConfigResponse {
     pub config: HashMap<String, Value>,
+    /// The typed settings, see GET /config/schema
+    pub settings: GooseSettings,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateConfigRequest {
+    #[serde(default)]
+    pub settings: GooseSettings,
+    /// Secrets by name, e.g. OPENAI_API_KEY. Stored in the keyring, never in config.yaml.
+    #[serde(default)]
+    pub secrets: HashMap<String, String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ConfigValidationResponse {
+    pub errors: Vec<SettingsValidationError>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct EffectiveConfigRequest {
+    /// Values that take precedence over every config layer, as a request would apply them
+    #[serde(default)]
+    pub overrides: HashMap<String, Value>,
+    /// Only report these keys; all known keys when omitted
+    pub keys: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TeamSyncRequest {
+    /// New source to sync from and save as GOOSE_TEAM_CONFIG; the saved source when omitted
+    pub source: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ConfigLayer {
+    pub source: ConfigSource,
+    pub path: String,
+    pub exists: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EffectiveConfigResponse {
+    /// Read-only config files consulted besides the user config
+    pub layers: Vec<ConfigLayer>,
+    pub values: Vec<EffectiveValue>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -296,8 +346,161 @@ pub async fn read_all_config(
     let values = config
         .load_values()
         .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    let settings = GooseSettings::load(config).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(ConfigResponse {
+        config: values,
+        settings,
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/config",
+    request_body = UpdateConfigRequest,
+    responses(
+        (status = 200, description = "Settings and secrets saved; returns the current settings", body = GooseSettings),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 422, description = "Validation failed; nothing was saved", body = ConfigValidationResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Configuration"
+)]
+pub async fn update_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<UpdateConfigRequest>,
+) -> Result<Json<GooseSettings>, (StatusCode, Json<ConfigValidationResponse>)> {
+    let fail = |status: StatusCode| {
+        (
+            status,
+            Json(ConfigValidationResponse { errors: Vec::new() }),
+        )
+    };
+    verify_secret_key(&headers, &state).map_err(fail)?;
+
+    let mut errors = request.settings.validate();
+    errors.extend(validate_secrets(&request.secrets));
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ConfigValidationResponse { errors }),
+        ));
+    }
 
-    Ok(Json(ConfigResponse { config: values }))
+    let config = Config::global();
+    request
+        .settings
+        .apply(config)
+        .map_err(|_| fail(StatusCode::INTERNAL_SERVER_ERROR))?;
+    store_secrets(config, &request.secrets).map_err(|e| {
+        tracing::error!("Failed to store secrets: {}", e);
+        fail(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    GooseSettings::load(config)
+        .map(Json)
+        .map_err(|_| fail(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/effective",
+    request_body = EffectiveConfigRequest,
+    responses(
+        (status = 200, description = "Effective value of each key and the layer it came from", body = EffectiveConfigResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn effective_config(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<EffectiveConfigRequest>,
+) -> Result<Json<EffectiveConfigResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let config = Config::global();
+    let mut values = config
+        .effective_values(&request.overrides, SETTINGS_KEYS)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(keys) = &request.keys {
+        values.retain(|v| keys.contains(&v.key));
+    }
+
+    let layers = config
+        .layer_paths()
+        .into_iter()
+        .map(|(source, path)| ConfigLayer {
+            source,
+            exists: path.exists(),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect();
+
+    Ok(Json(EffectiveConfigResponse { layers, values }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/team/sync",
+    request_body = TeamSyncRequest,
+    responses(
+        (status = 200, description = "Team config fetched and cached", body = TeamSyncResult),
+        (status = 400, description = "No source configured, or the source is invalid"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 502, description = "The team config could not be fetched or parsed")
+    )
+)]
+pub async fn sync_team(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<TeamSyncRequest>,
+) -> Result<Json<TeamSyncResult>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let config = Config::global();
+    if request.source.is_none() && config.get_param::<String>(TEAM_CONFIG_KEY).is_err() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if let Some(source) = &request.source {
+        TeamConfigSource::parse(source).map_err(|_| StatusCode::BAD_REQUEST)?;
+    }
+
+    sync_team_config(config, request.source.as_deref())
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!("Team config sync failed: {}", e);
+            StatusCode::BAD_GATEWAY
+        })
+}
+
+#[utoipa::path(
+    get,
+    path = "/config/schema",
+    responses(
+        (status = 200, description = "JSON schema for the typed settings", body = Value),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Configuration"
+)]
+pub async fn get_config_schema(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let (_, schema) = GooseSettings::schema();
+    serde_json::to_value(schema)
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
 #[utoipa::path(
@@ -763,7 +966,10 @@ pub async fn remove_custom_provider(
 
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
-        .route("/config", get(read_all_config))
+        .route("/config", get(read_all_config).put(update_config))
+        .route("/config/schema", get(get_config_schema))
+        .route("/config/effective", post(effective_config))
+        .route("/config/team/sync", post(sync_team))
         .route("/config/upsert", post(upsert_config))
         .route("/config/remove", post(remove_config))
         .route("/config/read", post(read_config))
@@ -829,4 +1035,28 @@ mod tests {
         assert!(gpt4_limit.is_some());
         assert_eq!(gpt4_limit.unwrap().context_limit, 128_000);
     }
+
+    #[tokio::test]
+    async fn test_update_config_rejects_invalid_settings() {
+        let test_state = AppState::new(
+            Arc::new(goose::agents::Agent::default()),
+            "test".to_string(),
+        )
+        .await;
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Secret-Key", "test".parse().unwrap());
+
+        let request: UpdateConfigRequest = serde_json::from_value(serde_json::json!({
+            "settings": { "GOOSE_MODE": "yolo" },
+            "secrets": { "openai key": "sk-test" }
+        }))
+        .unwrap();
+        let (status, Json(body)) = update_config(State(test_state), headers, Json(request))
+            .await
+            .unwrap_err();
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let fields: Vec<_> = body.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["GOOSE_MODE", "openai key"]);
+    }
 }
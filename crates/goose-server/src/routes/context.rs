@@ -6,9 +6,12 @@ use axum::{
     routing::post,
     Json, Router,
 };
+use goose::context_mgmt::truncate::truncated_tool_results;
 use goose::conversation::{message::Message, Conversation};
+use goose::session::{self, attachments};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tracing::error;
 use utoipa::ToSchema;
 
 /// Request payload for context management operations
@@ -19,6 +22,11 @@ pub struct ContextManageRequest {
     pub messages: Vec<Message>,
     /// Operation to perform: "truncation" or "summarize"
     pub manage_action: String,
+    /// Session the messages belong to. When given, the full output of tool results shortened
+    /// by truncation is kept with the session, and items pinned to the session are added back
+    /// after summarizing.
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 /// Response from context management operations
@@ -29,6 +37,9 @@ pub struct ContextManageResponse {
     pub messages: Vec<Message>,
     /// Token counts for each processed message
     pub token_counts: Vec<usize>,
+    /// Tool call ids whose results were shortened and whose full output can be fetched from
+    /// /sessions/{session_id}/tool-results/{call_id}/full
+    pub truncated_tool_results: Vec<String>,
 }
 
 #[utoipa::path(
@@ -38,6 +49,7 @@ pub struct ContextManageResponse {
     responses(
         (status = 200, description = "Context managed successfully", body = ContextManageResponse),
         (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
         (status = 412, description = "Precondition failed - Agent not available"),
         (status = 500, description = "Internal server error")
     ),
@@ -60,25 +72,77 @@ async fn manage_context(
 
     let mut processed_messages = Conversation::new_unvalidated(vec![]);
     let mut token_counts: Vec<usize> = vec![];
+    let mut truncated_call_ids: Vec<String> = vec![];
 
     if request.manage_action == "truncation" {
         (processed_messages, token_counts) = agent
             .truncate_context(&request.messages)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if let Some(session_id) = request.session_id {
+            truncated_call_ids =
+                keep_truncated_tool_results(session_id, &request.messages, &processed_messages)?;
+        }
     } else if request.manage_action == "summarize" {
         (processed_messages, token_counts, _) = agent
             .summarize_context(&request.messages)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        if let Some(session_id) = request.session_id {
+            processed_messages = with_pins(session_id, processed_messages)?;
+        }
     }
 
     Ok(Json(ContextManageResponse {
         messages: processed_messages.messages().clone(),
         token_counts,
+        truncated_tool_results: truncated_call_ids,
     }))
 }
 
+/// Keep the full output of tool results shortened by truncation with the session, returning
+/// the ids of the tool calls that were kept
+fn keep_truncated_tool_results(
+    session_id: String,
+    original: &[Message],
+    truncated: &Conversation,
+) -> Result<Vec<String>, StatusCode> {
+    let outputs = truncated_tool_results(original, truncated.messages());
+    if outputs.is_empty() {
+        return Ok(vec![]);
+    }
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    attachments::save_tool_results(&session_path, &outputs).map_err(|e| {
+        error!("Failed to keep truncated tool results: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(outputs.into_iter().map(|(call_id, _)| call_id).collect())
+}
+
+/// Add the items pinned to the session back to messages that were just summarized
+fn with_pins(session_id: String, summarized: Conversation) -> Result<Conversation, StatusCode> {
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let working_dir = session::read_metadata(&session_path)
+        .map(|metadata| metadata.working_dir)
+        .map_err(|e| {
+            error!("Failed to read session metadata: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(session::pins::reinclude_pins(
+        &session_path,
+        &working_dir,
+        summarized,
+    ))
+}
+
 // Configure routes for this module
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
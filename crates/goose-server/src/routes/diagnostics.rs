@@ -0,0 +1,109 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use goose::config::Config;
+use goose::diagnostics::{build_bundle, run_checks, BundleOptions, DoctorReport};
+use goose::session;
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsQuery {
+    /// Session to include metadata for, usually the one that failed
+    session_id: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/diagnostics",
+    params(DiagnosticsQuery),
+    responses(
+        (status = 200, description = "Zip of version info, redacted config, recent logs and session metadata", content_type = "application/zip", body = Vec<u8>),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Diagnostics"
+)]
+async fn get_diagnostics(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<DiagnosticsQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_file = match query.session_id {
+        Some(session_id) => {
+            let path = session::get_path(session::Identifier::Name(session_id))
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            if !path.exists() {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            Some(path)
+        }
+        None => None,
+    };
+
+    let options = BundleOptions {
+        session_file,
+        ..Default::default()
+    };
+    let bundle = build_bundle(Config::global(), &options).map_err(|e| {
+        tracing::error!("Failed to build diagnostics bundle: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let filename = format!(
+        "goose-diagnostics-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        bundle,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/diagnostics/checks",
+    responses(
+        (status = 200, description = "Environment checks with suggested fixes", body = DoctorReport),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Diagnostics"
+)]
+async fn get_checks(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<DoctorReport>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(Json(run_checks(Config::global()).await))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/diagnostics", get(get_diagnostics))
+        .route("/diagnostics/checks", get(get_checks))
+        .with_state(state)
+}
@@ -0,0 +1,213 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use goose::session;
+use goose::session::elevation::{self, AuditEvent, Elevation};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+const DEFAULT_ELEVATION_MINUTES: u64 = 30;
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantElevationRequest {
+    /// How long the session may act without asking; defaults to 30, capped at 240
+    duration_minutes: Option<u64>,
+    /// Why the elevation was granted, kept in the audit log
+    reason: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ElevationStatusResponse {
+    /// The active elevation, or null when the session asks before acting
+    elevation: Option<Elevation>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogResponse {
+    events: Vec<AuditEvent>,
+}
+
+fn existing_session_path(session_id: String) -> Result<PathBuf, StatusCode> {
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(session_path)
+}
+
+fn internal_error(e: anyhow::Error) -> StatusCode {
+    tracing::error!("Session elevation error: {:?}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/elevation",
+    request_body = GrantElevationRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Session elevated", body = Elevation),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn grant_elevation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<GrantElevationRequest>,
+) -> Result<Json<Elevation>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = existing_session_path(session_id)?;
+    let minutes = request
+        .duration_minutes
+        .unwrap_or(DEFAULT_ELEVATION_MINUTES);
+    let elevation = elevation::grant_elevation(
+        &session_path,
+        Duration::from_secs(minutes.saturating_mul(60)),
+        request.reason,
+    )
+    .map_err(internal_error)?;
+    goose::analytics::record_feature("elevation");
+    Ok(Json(elevation))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/elevation",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Current elevation status", body = ElevationStatusResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn get_elevation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<ElevationStatusResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = existing_session_path(session_id)?;
+    let elevation = elevation::active_elevation(&session_path).map_err(internal_error)?;
+    Ok(Json(ElevationStatusResponse { elevation }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}/elevation",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 204, description = "Elevation revoked"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found or not elevated"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn revoke_elevation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = existing_session_path(session_id)?;
+    if elevation::revoke_elevation(&session_path).map_err(internal_error)? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/audit",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Audit log for the session, oldest first", body = AuditLogResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<AuditLogResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = existing_session_path(session_id)?;
+    // Reading the elevation logs its expiry if it has lapsed since the last check
+    elevation::active_elevation(&session_path).map_err(internal_error)?;
+    let events = elevation::read_audit_log(&session_path).map_err(internal_error)?;
+    Ok(Json(AuditLogResponse { events }))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/sessions/{session_id}/elevation",
+            get(get_elevation)
+                .post(grant_elevation)
+                .delete(revoke_elevation),
+        )
+        .route("/sessions/{session_id}/audit", get(get_audit_log))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grant_request_defaults() {
+        let request: GrantElevationRequest = serde_json::from_str("{}").unwrap();
+        assert!(request.duration_minutes.is_none());
+
+        let request: GrantElevationRequest =
+            serde_json::from_str(r#"{"durationMinutes": 15, "reason": "migration"}"#).unwrap();
+        assert_eq!(request.duration_minutes, Some(15));
+        assert_eq!(request.reason.as_deref(), Some("migration"));
+    }
+}
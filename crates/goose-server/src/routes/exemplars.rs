@@ -0,0 +1,162 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use goose::exemplars::{self, Exemplar};
+use goose::session;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkExemplarRequest {
+    /// Who is marking the exchange
+    author: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExemplarListResponse {
+    /// Exemplars, in the order they were marked
+    exemplars: Vec<Exemplar>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/messages/{message_index}/exemplar",
+    request_body = MarkExemplarRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("message_index" = usize, Path, description = "Index of the user prompt that starts the exchange")
+    ),
+    responses(
+        (status = 200, description = "Exchange kept as an exemplar", body = Exemplar),
+        (status = 400, description = "Bad request - The message is not a prompt that got an answer"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 412, description = "Precondition failed - Agent not initialized"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Keep a prompt and its answer as an example for similar tasks
+async fn mark_exemplar(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, message_index)): Path<(String, usize)>,
+    request: Option<Json<MarkExemplarRequest>>,
+) -> Result<Json<Exemplar>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let messages = session::read_messages(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    let (prompt, response) = exemplars::exchange_at(messages.messages(), message_index)
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let provider = agent
+        .provider()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    let exemplar = exemplars::add_exemplar(
+        &provider,
+        prompt,
+        response,
+        Some(session_id),
+        request.author,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to save exemplar: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(exemplar))
+}
+
+#[utoipa::path(
+    get,
+    path = "/exemplars",
+    responses(
+        (status = 200, description = "Exchanges marked as exemplars", body = ExemplarListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn list_exemplars(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ExemplarListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let exemplars = exemplars::list_exemplars().map_err(|e| {
+        tracing::error!("Failed to read exemplars: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(ExemplarListResponse { exemplars }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/exemplars/{id}",
+    params(
+        ("id" = String, Path, description = "Exemplar to remove")
+    ),
+    responses(
+        (status = 204, description = "Exemplar removed"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Exemplar not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn delete_exemplar(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    match exemplars::remove_exemplar(&id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Failed to remove exemplar: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/sessions/{session_id}/messages/{message_index}/exemplar",
+            post(mark_exemplar),
+        )
+        .route("/exemplars", get(list_exemplars))
+        .route("/exemplars/{id}", delete(delete_exemplar))
+        .with_state(state)
+}
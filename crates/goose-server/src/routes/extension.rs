@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::env;
 use std::path::Path;
 use std::sync::Arc;
@@ -5,17 +6,30 @@ use std::sync::OnceLock;
 
 use super::utils::verify_secret_key;
 use crate::state::AppState;
-use axum::{extract::State, routing::post, Json, Router};
+use axum::{
+    extract::{Path as AxumPath, Query, State},
+    routing::{get, post, put},
+    Json, Router,
+};
+use goose::agents::extension_manager::{
+    ExtensionDetails, ExtensionResource, ExtensionTool, ToolRoutingTable,
+};
+use goose::agents::sampling::SamplingUsage;
 use goose::agents::{extension::Envs, ExtensionConfig};
+use goose::config::{is_extension_secret, Config, ExtensionConfigManager};
+use goose::session::{self, ResourceAttachment, SessionMetadata};
+use goose::tool_stats::{ToolStats, ToolStatsReport};
 use http::{HeaderMap, StatusCode};
-use rmcp::model::Tool;
+use rmcp::model::{ErrorCode, ErrorData, Tool};
 use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 use tracing;
+use utoipa::{IntoParams, ToSchema};
 
 /// Enum representing the different types of extension configuration requests.
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(tag = "type")]
-enum ExtensionConfigRequest {
+pub enum ExtensionConfigRequest {
     /// Server-Sent Events (SSE) extension.
     #[serde(rename = "sse")]
     Sse {
@@ -91,13 +105,29 @@ enum ExtensionConfigRequest {
 ///
 /// - `error`: Indicates whether an error occurred (`true`) or not (`false`).
 /// - `message`: Provides detailed error information when `error` is `true`.
-#[derive(Serialize)]
-struct ExtensionResponse {
+#[derive(Serialize, ToSchema)]
+#[schema(as = AgentExtensionResponse)]
+pub struct ExtensionResponse {
     error: bool,
     message: Option<String>,
 }
 
 /// Handler for adding a new extension configuration.
+#[utoipa::path(
+    post,
+    path = "/extensions/add",
+    request_body = ExtensionConfigRequest,
+    responses(
+        (status = 200, description = "Whether the extension was added to the running agent", body = AgentExtensionResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "No agent configured"),
+        (status = 422, description = "Invalid extension configuration")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
 async fn add_extension(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -298,6 +328,20 @@ async fn add_extension(
 }
 
 /// Handler for removing an extension by name
+#[utoipa::path(
+    post,
+    path = "/extensions/remove",
+    request_body(content = String, description = "Name of the extension to remove"),
+    responses(
+        (status = 200, description = "Whether the extension was removed from the running agent", body = AgentExtensionResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "No agent configured")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
 async fn remove_extension(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -322,11 +366,693 @@ async fn remove_extension(
     }
 }
 
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DisabledToolsResponse {
+    /// Disabled tools by extension; tool names are without the extension prefix
+    disabled_tools: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetDisabledToolsRequest {
+    /// Tools to disable, with or without the extension prefix; empty enables them all again
+    tools: Vec<String>,
+}
+
+/// Handler for listing the tools disabled within extensions
+#[utoipa::path(
+    get,
+    path = "/extensions/disabled_tools",
+    responses(
+        (status = 200, description = "Tools disabled within each extension", body = DisabledToolsResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn get_disabled_tools(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<DisabledToolsResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    Ok(Json(DisabledToolsResponse {
+        disabled_tools: ExtensionConfigManager::get_disabled_tools(),
+    }))
+}
+
+/// Handler for choosing which tools of an extension are disabled
+#[utoipa::path(
+    put,
+    path = "/extensions/{name}/disabled_tools",
+    request_body = SetDisabledToolsRequest,
+    params(
+        ("name" = String, Path, description = "Extension name, as it prefixes its tools")
+    ),
+    responses(
+        (status = 200, description = "Disabled tools updated; they are left out from the next request on", body = DisabledToolsResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn set_disabled_tools(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+    Json(request): Json<SetDisabledToolsRequest>,
+) -> Result<Json<DisabledToolsResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let prefix = format!("{}__", name);
+    let tools = request
+        .tools
+        .into_iter()
+        .map(|tool| {
+            tool.strip_prefix(&prefix)
+                .map(str::to_string)
+                .unwrap_or(tool)
+        })
+        .collect();
+    ExtensionConfigManager::set_disabled_tools(&name, tools).map_err(|e| {
+        tracing::error!("Failed to save disabled tools: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(DisabledToolsResponse {
+        disabled_tools: ExtensionConfigManager::get_disabled_tools(),
+    }))
+}
+
+/// Handler for the tools offered to the model and the extension each one is routed to
+#[utoipa::path(
+    get,
+    path = "/extensions/routing",
+    responses(
+        (status = 200, description = "Effective routing table, with tool names more than one extension or tool claims and how GOOSE_TOOL_COLLISION_POLICY resolved them", body = ToolRoutingTable),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "No agent configured"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn get_tool_routing(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ToolRoutingTable>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let extension_manager = agent.extension_manager.read().await;
+    let table = extension_manager.tool_routing_table().await.map_err(|e| {
+        tracing::error!("Failed to build the tool routing table: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(table))
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ExtensionListResponse {
+    /// Extensions the running agent has loaded, sorted by name
+    extensions: Vec<ExtensionDetails>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ExtensionToolsResponse {
+    extension: String,
+    tools: Vec<ExtensionTool>,
+}
+
+/// Handler for the extensions the running agent has loaded
+#[utoipa::path(
+    get,
+    path = "/extensions",
+    responses(
+        (status = 200, description = "Loaded extensions with their instructions, server info, capabilities and connection status", body = ExtensionListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "No agent configured")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn list_extensions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ExtensionListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let extensions = agent
+        .extension_manager
+        .read()
+        .await
+        .extension_details()
+        .await;
+    Ok(Json(ExtensionListResponse { extensions }))
+}
+
+/// Handler for the live tool schemas of a loaded extension
+#[utoipa::path(
+    get,
+    path = "/extensions/{name}/tools",
+    params(
+        ("name" = String, Path, description = "Extension name")
+    ),
+    responses(
+        (status = 200, description = "Tools as they are offered to the model, with overrides applied and disabled tools left out", body = ExtensionToolsResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "The extension is not loaded"),
+        (status = 412, description = "No agent configured"),
+        (status = 502, description = "The extension failed to list its tools")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn list_extension_tools(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+) -> Result<Json<ExtensionToolsResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let tools = agent
+        .extension_manager
+        .read()
+        .await
+        .extension_tools(&name)
+        .await
+        .ok_or(StatusCode::NOT_FOUND)?
+        .map_err(|e| {
+            tracing::error!("Failed to list tools of extension '{}': {}", name, e);
+            StatusCode::BAD_GATEWAY
+        })?;
+    Ok(Json(ExtensionToolsResponse {
+        extension: name,
+        tools,
+    }))
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SamplingResponse {
+    /// Extensions with a sampling budget or that requested completions, by name
+    extensions: Vec<SamplingUsage>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetSamplingRequest {
+    /// Tokens the extension may spend on completions; unlimited when omitted. Every request is
+    /// still approved by the user.
+    token_budget: Option<i32>,
+}
+
+/// Handler for what extensions may spend on completions and what they spent
+#[utoipa::path(
+    get,
+    path = "/extensions/sampling",
+    responses(
+        (status = 200, description = "Sampling budgets and tokens spent per extension", body = SamplingResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "No agent configured")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn get_sampling(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<SamplingResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let extensions = agent
+        .extension_manager
+        .read()
+        .await
+        .sampling()
+        .usage()
+        .await;
+    Ok(Json(SamplingResponse { extensions }))
+}
+
+/// Handler for setting or lifting an extension's sampling budget
+#[utoipa::path(
+    put,
+    path = "/extensions/{name}/sampling",
+    request_body = SetSamplingRequest,
+    params(
+        ("name" = String, Path, description = "Extension name")
+    ),
+    responses(
+        (status = 204, description = "Budget saved; it applies to the extension's next sampling request"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn set_sampling(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+    Json(request): Json<SetSamplingRequest>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    ExtensionConfigManager::set_sampling_budget(&name, request.token_budget).map_err(|e| {
+        tracing::error!("Failed to save sampling budget: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ExtensionSecretsResponse {
+    /// Names of the keyring secrets passed to each extension, by extension and then by the
+    /// environment variable they are passed in; the secrets themselves are never returned
+    secrets: HashMap<String, HashMap<String, String>>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetExtensionSecretsRequest {
+    /// Keyring secret names by the environment variable they are passed in; empty to pass none
+    env: HashMap<String, String>,
+}
+
+/// Handler for which secrets are passed to which extensions
+#[utoipa::path(
+    get,
+    path = "/extensions/secrets",
+    responses(
+        (status = 200, description = "Secret names passed to each extension's environment", body = ExtensionSecretsResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn get_extension_secrets(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ExtensionSecretsResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(Json(ExtensionSecretsResponse {
+        secrets: ExtensionConfigManager::get_extension_secrets(),
+    }))
+}
+
+/// Handler for choosing the secrets passed to an extension's process
+#[utoipa::path(
+    put,
+    path = "/extensions/{name}/secrets",
+    request_body = SetExtensionSecretsRequest,
+    params(
+        ("name" = String, Path, description = "Extension name")
+    ),
+    responses(
+        (status = 204, description = "Secrets saved; they are passed to the extension when it is next started"),
+        (status = 400, description = "A secret is not in the keyring, or its name doesn't start with the extension's key and an underscore"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn set_extension_secrets(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+    Json(request): Json<SetExtensionSecretsRequest>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let config = Config::global();
+    for secret in request.env.values() {
+        if !is_extension_secret(&name, secret) {
+            tracing::warn!(
+                "Secret '{}' is outside the namespace of extension '{}'",
+                secret,
+                name
+            );
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        if config.get_secret::<String>(secret).is_err() {
+            tracing::warn!("Secret '{}' for extension '{}' is not set", secret, name);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+    ExtensionConfigManager::set_extension_secrets(&name, request.env).map_err(|e| {
+        tracing::error!("Failed to save extension secrets: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ResourceListResponse {
+    resources: Vec<ExtensionResource>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ResourceListQuery {
+    /// Session to mark the attached resources of
+    session_id: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ResourceRequest {
+    /// Session to attach the resource to or detach it from
+    session_id: String,
+    /// URI of the resource, as the extension lists it
+    uri: String,
+}
+
+/// The session's file and metadata, as a status code the handlers can return when it is missing
+fn session_metadata(session_id: &str) -> Result<(std::path::PathBuf, SessionMetadata), StatusCode> {
+    let session_path = session::get_path(session::Identifier::Name(session_id.to_string()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let metadata = session::read_metadata(&session_path).map_err(|e| {
+        tracing::error!("Failed to read session metadata: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok((session_path, metadata))
+}
+
+async fn save_session_metadata(
+    session_path: &Path,
+    metadata: &SessionMetadata,
+) -> Result<(), StatusCode> {
+    session::update_metadata(session_path, metadata)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update session metadata: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+fn resource_error_status(error: ErrorData) -> StatusCode {
+    match error.code {
+        ErrorCode::INVALID_PARAMS | ErrorCode::RESOURCE_NOT_FOUND => StatusCode::NOT_FOUND,
+        _ => {
+            tracing::error!("Resource request failed: {}", error.message);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Handler for listing the resources an extension exposes
+#[utoipa::path(
+    get,
+    path = "/extensions/{name}/resources",
+    params(
+        ("name" = String, Path, description = "Extension name"),
+        ResourceListQuery
+    ),
+    responses(
+        (status = 200, description = "Resources the extension exposes, and whether each one is attached to the session", body = ResourceListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Extension not found"),
+        (status = 412, description = "No agent configured"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn list_extension_resources(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+    Query(query): Query<ResourceListQuery>,
+) -> Result<Json<ResourceListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let attached = match &query.session_id {
+        Some(session_id) => session_metadata(session_id)?.1.attached_resources,
+        None => Vec::new(),
+    };
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let resources = agent
+        .extension_manager
+        .read()
+        .await
+        .list_extension_resources(&name, &attached, CancellationToken::default())
+        .await
+        .map_err(resource_error_status)?;
+    Ok(Json(ResourceListResponse { resources }))
+}
+
+/// Handler for attaching a resource to a session's context
+#[utoipa::path(
+    post,
+    path = "/extensions/{name}/resources/attach",
+    request_body = ResourceRequest,
+    params(
+        ("name" = String, Path, description = "Extension name")
+    ),
+    responses(
+        (status = 204, description = "Resource attached; its current content is part of every request of the session until it is detached, and the extension is subscribed to for changes when it supports that"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session, extension or resource not found"),
+        (status = 412, description = "No agent configured"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn attach_extension_resource(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+    Json(request): Json<ResourceRequest>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let (session_path, mut metadata) = session_metadata(&request.session_id)?;
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    agent
+        .extension_manager
+        .write()
+        .await
+        .attach_resource(
+            &request.session_id,
+            &name,
+            &request.uri,
+            CancellationToken::default(),
+        )
+        .await
+        .map_err(resource_error_status)?;
+
+    let attachment = ResourceAttachment {
+        extension: name,
+        uri: request.uri,
+    };
+    if !metadata.attached_resources.contains(&attachment) {
+        metadata.attached_resources.push(attachment);
+        save_session_metadata(&session_path, &metadata).await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Handler for detaching a resource from a session's context
+#[utoipa::path(
+    post,
+    path = "/extensions/{name}/resources/detach",
+    request_body = ResourceRequest,
+    params(
+        ("name" = String, Path, description = "Extension name")
+    ),
+    responses(
+        (status = 204, description = "Resource detached"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "The session is not found or the resource is not attached to it"),
+        (status = 412, description = "No agent configured")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn detach_extension_resource(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    AxumPath(name): AxumPath<String>,
+    Json(request): Json<ResourceRequest>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let (session_path, mut metadata) = session_metadata(&request.session_id)?;
+    let attached_count = metadata.attached_resources.len();
+    metadata
+        .attached_resources
+        .retain(|resource| !(resource.extension == name && resource.uri == request.uri));
+    if metadata.attached_resources.len() == attached_count {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    save_session_metadata(&session_path, &metadata).await?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    agent
+        .extension_manager
+        .read()
+        .await
+        .detach_resource(
+            &request.session_id,
+            &name,
+            &request.uri,
+            CancellationToken::default(),
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn tool_stats() -> Result<ToolStats, StatusCode> {
+    ToolStats::from_default_path().map_err(|e| {
+        tracing::error!("Failed to load tool stats: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// Handler for tool usage across sessions, per extension
+#[utoipa::path(
+    get,
+    path = "/extensions/stats",
+    responses(
+        (status = 200, description = "Call counts, failure rates and latency per extension and tool; tools the running agent offers but never called are listed as unused", body = ToolStatsReport),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn get_extension_stats(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ToolStatsReport>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    // Without an agent there is nothing to compare against, so only called tools are listed
+    let available_tools: Vec<String> = match state.get_agent().await {
+        Ok(agent) => agent
+            .list_tools(None)
+            .await
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    let report = tool_stats()?.report(&available_tools).map_err(|e| {
+        tracing::error!("Failed to read tool stats: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(report))
+}
+
+/// Handler for deleting the recorded tool usage
+#[utoipa::path(
+    delete,
+    path = "/extensions/stats",
+    responses(
+        (status = 204, description = "Recorded tool usage deleted"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Extension Management"
+)]
+async fn clear_extension_stats(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    tool_stats()?.clear().map_err(|e| {
+        tracing::error!("Failed to clear tool stats: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Registers the extension management routes with the Axum router.
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
+        .route("/extensions", get(list_extensions))
         .route("/extensions/add", post(add_extension))
         .route("/extensions/remove", post(remove_extension))
+        .route("/extensions/disabled_tools", get(get_disabled_tools))
+        .route("/extensions/routing", get(get_tool_routing))
+        .route("/extensions/sampling", get(get_sampling))
+        .route("/extensions/{name}/sampling", put(set_sampling))
+        .route("/extensions/secrets", get(get_extension_secrets))
+        .route("/extensions/{name}/secrets", put(set_extension_secrets))
+        .route("/extensions/{name}/disabled_tools", put(set_disabled_tools))
+        .route("/extensions/{name}/tools", get(list_extension_tools))
+        .route(
+            "/extensions/{name}/resources",
+            get(list_extension_resources),
+        )
+        .route(
+            "/extensions/{name}/resources/attach",
+            post(attach_extension_resource),
+        )
+        .route(
+            "/extensions/{name}/resources/detach",
+            post(detach_extension_resource),
+        )
+        .route(
+            "/extensions/stats",
+            get(get_extension_stats).delete(clear_extension_stats),
+        )
         .with_state(state)
 }
 
@@ -350,10 +1076,11 @@ struct ExtensionAllowlistEntry {
 #[allow(dead_code)]
 static ALLOWED_EXTENSIONS: OnceLock<Option<AllowedExtensions>> = OnceLock::new();
 
-/// Fetches and parses the allowed extensions from the URL specified in GOOSE_ALLOWLIST env var
+/// Fetches and parses the allowed extensions from the URL in GOOSE_ALLOWLIST, which can
+/// come from the environment or any config layer (e.g. a synced team config)
 #[allow(dead_code)]
 fn fetch_allowed_extensions() -> Option<AllowedExtensions> {
-    match env::var("GOOSE_ALLOWLIST") {
+    match goose::config::Config::global().get_param::<String>("GOOSE_ALLOWLIST") {
         Err(_) => {
             // Environment variable not set, no allowlist to enforce
             None
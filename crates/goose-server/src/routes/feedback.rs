@@ -0,0 +1,216 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use goose::session;
+use goose::session::feedback::{self, Feedback, FeedbackRating};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackRequest {
+    /// Thumbs up or down
+    rating: FeedbackRating,
+    /// Optional free-text explanation
+    comment: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackListResponse {
+    feedback: Vec<Feedback>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// Only export sessions rated this way as a whole
+    rating: Option<FeedbackRating>,
+}
+
+fn save_feedback(
+    session_id: String,
+    message_index: Option<usize>,
+    request: FeedbackRequest,
+) -> Result<Json<Feedback>, StatusCode> {
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    if let Some(index) = message_index {
+        let messages = session::read_messages(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+        if index >= messages.len() {
+            return Err(StatusCode::NOT_FOUND);
+        }
+    }
+
+    let entry = Feedback {
+        message_index,
+        rating: request.rating,
+        comment: request.comment,
+        created: chrono::Utc::now().timestamp(),
+    };
+    feedback::record_feedback(&session_path, entry.clone()).map_err(|e| {
+        tracing::error!("Failed to record feedback: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/feedback",
+    request_body = FeedbackRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Feedback recorded", body = Feedback),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Feedback"
+)]
+async fn session_feedback(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<FeedbackRequest>,
+) -> Result<Json<Feedback>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    save_feedback(session_id, None, request)
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/messages/{message_index}/feedback",
+    request_body = FeedbackRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("message_index" = usize, Path, description = "Index of the message being rated")
+    ),
+    responses(
+        (status = 200, description = "Feedback recorded", body = Feedback),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session or message not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Feedback"
+)]
+async fn message_feedback(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, message_index)): Path<(String, usize)>,
+    Json(request): Json<FeedbackRequest>,
+) -> Result<Json<Feedback>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    save_feedback(session_id, Some(message_index), request)
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/feedback",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "All feedback recorded for the session", body = FeedbackListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Feedback"
+)]
+async fn get_feedback(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<FeedbackListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let feedback =
+        feedback::read_feedback(&session_path).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(FeedbackListResponse { feedback }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/feedback/export",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "Rated sessions as JSONL, one session per line", content_type = "application/x-ndjson", body = String),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Feedback"
+)]
+async fn export_feedback(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ExportQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let body = feedback::export_feedback_jsonl(query.rating).map_err(|e| {
+        tracing::error!("Failed to export feedback: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(([(header::CONTENT_TYPE, "application/x-ndjson")], body))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/sessions/{session_id}/feedback",
+            get(get_feedback).post(session_feedback),
+        )
+        .route(
+            "/sessions/{session_id}/messages/{message_index}/feedback",
+            post(message_feedback),
+        )
+        .route("/feedback/export", get(export_feedback))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feedback_request_deserialization() {
+        let request: FeedbackRequest =
+            serde_json::from_str(r#"{"rating": "down", "comment": "ignored my file"}"#).unwrap();
+        assert_eq!(request.rating, FeedbackRating::Down);
+        assert_eq!(request.comment.as_deref(), Some("ignored my file"));
+
+        assert!(serde_json::from_str::<FeedbackRequest>(r#"{"rating": "meh"}"#).is_err());
+    }
+}
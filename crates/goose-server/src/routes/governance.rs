@@ -0,0 +1,254 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderName, StatusCode},
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use goose::governance::{self, LegalHold, PurgeReport};
+use serde::{Deserialize, Serialize};
+use std::io::{Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio_util::io::ReaderStream;
+use utoipa::ToSchema;
+
+/// Header carrying the id of the hold placed by an export
+const HOLD_ID_HEADER: HeaderName = HeaderName::from_static("x-goose-hold-id");
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRequest {
+    /// Working directory of the project to export; everything when omitted
+    project: Option<String>,
+    /// Also freeze the exported sessions and memories against deletion
+    #[serde(default)]
+    hold: bool,
+    /// Why the data is being exported or held, kept with the hold
+    reason: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeRequest {
+    /// Working directory of the project to purge; all of the user's data when omitted
+    project: Option<String>,
+    /// Only report what would be removed; set to false to actually delete
+    #[serde(default = "default_dry_run")]
+    dry_run: bool,
+    /// The confirmationToken of a dry run, required to purge all of the user's data
+    confirmation_token: Option<String>,
+}
+
+fn default_dry_run() -> bool {
+    true
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LegalHoldListResponse {
+    holds: Vec<LegalHold>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/governance/export",
+    request_body = ExportRequest,
+    responses(
+        (status = 200, description = "Zip of every session, audit record and memory in scope with a checksummed manifest; the id of any hold placed is in the x-goose-hold-id header", content_type = "application/zip", body = Vec<u8>),
+        (status = 400, description = "The project is not an absolute path"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Governance"
+)]
+async fn export_data(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ExportRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let project = request.project.map(PathBuf::from);
+    if project
+        .as_ref()
+        .is_some_and(|project| !project.is_absolute())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let hold = if request.hold {
+        let hold = governance::place_hold(project.as_deref(), request.reason).map_err(|e| {
+            tracing::error!("Failed to place legal hold: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        Some(hold)
+    } else {
+        None
+    };
+
+    // The archive is built in an anonymous temp file and streamed from there, so an export
+    // never has to fit in memory
+    let export_hold = hold.clone();
+    let archive = tokio::task::spawn_blocking(move || -> anyhow::Result<std::fs::File> {
+        let mut file = tempfile::tempfile()?;
+        governance::export_all(&mut file, project.as_deref(), export_hold.as_ref())?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file)
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|result| result)
+    .map_err(|e| {
+        tracing::error!("Failed to export data: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let length = archive.metadata().map(|metadata| metadata.len()).ok();
+
+    let filename = format!(
+        "goose-export-{}.zip",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        "application/zip".parse().expect("valid header value"),
+    );
+    response_headers.insert(
+        header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", filename)
+            .parse()
+            .expect("valid header value"),
+    );
+    if let Some(length) = length {
+        response_headers.insert(header::CONTENT_LENGTH, length.into());
+    }
+    if let Some(hold) = hold {
+        response_headers.insert(
+            HOLD_ID_HEADER,
+            hold.id.parse().expect("uuids are valid header values"),
+        );
+    }
+    let body = Body::from_stream(ReaderStream::new(tokio::fs::File::from_std(archive)));
+    Ok((response_headers, body))
+}
+
+#[utoipa::path(
+    post,
+    path = "/governance/purge",
+    request_body = PurgeRequest,
+    responses(
+        (status = 200, description = "What was removed, or would be on a dry run; data under a legal hold is kept and listed", body = PurgeReport),
+        (status = 400, description = "The project is not an absolute path"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 428, description = "Purging all data needs the confirmationToken of a dry run covering the same data"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Governance"
+)]
+async fn purge_data(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<PurgeRequest>,
+) -> Result<Json<PurgeReport>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let project = request.project.map(PathBuf::from);
+    if project
+        .as_ref()
+        .is_some_and(|project| !project.is_absolute())
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let purge_error = |e: anyhow::Error| {
+        tracing::error!("Failed to purge data: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    if !request.dry_run && project.is_none() {
+        let confirmed = match &request.confirmation_token {
+            Some(token) => governance::confirms_purge(None, token)
+                .await
+                .map_err(purge_error)?,
+            None => false,
+        };
+        if !confirmed {
+            return Err(StatusCode::PRECONDITION_REQUIRED);
+        }
+    }
+
+    let report = governance::purge(project.as_deref(), request.dry_run)
+        .await
+        .map_err(purge_error)?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/governance/holds",
+    responses(
+        (status = 200, description = "Legal holds in place", body = LegalHoldListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Governance"
+)]
+async fn list_holds(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<LegalHoldListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    Ok(Json(LegalHoldListResponse {
+        holds: governance::holds(),
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/governance/holds/{id}",
+    params(
+        ("id" = String, Path, description = "ID of the hold to release")
+    ),
+    responses(
+        (status = 204, description = "Hold released"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Hold not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Governance"
+)]
+async fn release_hold(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    match governance::release_hold(&id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Error releasing legal hold '{}': {:?}", id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/governance/export", post(export_data))
+        .route("/governance/purge", post(purge_data))
+        .route("/governance/holds", get(list_holds))
+        .route("/governance/holds/{id}", delete(release_hold))
+        .with_state(state)
+}
@@ -1,17 +1,122 @@
-use axum::{routing::get, Json, Router};
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use goose::config::Config;
+use goose::offline::{self, Connectivity};
+use goose::session;
 use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
-struct StatusResponse {
+#[derive(Serialize, ToSchema)]
+pub struct StatusResponse {
     status: &'static str,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    ready: bool,
+    /// What needs fixing before the server can take work
+    problems: Vec<String>,
+}
+
 /// Simple status endpoint that returns 200 OK when the server is running
+#[utoipa::path(
+    get,
+    path = "/status",
+    responses(
+        (status = 200, description = "The server is running", body = StatusResponse)
+    ),
+    tag = "Health"
+)]
 async fn status() -> Json<StatusResponse> {
     Json(StatusResponse { status: "ok" })
 }
 
-/// Configure health check routes
+/// Problems are reported to unauthenticated callers, so details like paths only go to the log
+fn readiness_problems() -> Vec<String> {
+    let mut problems = Vec::new();
+    if Config::global()
+        .get_param::<String>("GOOSE_PROVIDER")
+        .is_err()
+    {
+        problems.push("No provider configured; set GOOSE_PROVIDER".to_string());
+    }
+    let writable = session::ensure_session_dir().and_then(|dir| {
+        let probe = dir.join(".ready");
+        std::fs::write(&probe, b"")
+            .and_then(|_| std::fs::remove_file(&probe))
+            .map_err(|e| anyhow::anyhow!("{} is not writable: {}", dir.display(), e))
+    });
+    if let Err(e) = writable {
+        tracing::warn!("Session storage check failed: {}", e);
+        problems.push("Session storage not writable".to_string());
+    }
+    problems
+}
+
+/// Readiness probe: 200 once a provider is configured and sessions can be saved
+#[utoipa::path(
+    get,
+    path = "/ready",
+    responses(
+        (status = 200, description = "The server can take work", body = ReadinessResponse),
+        (status = 503, description = "The server is running but misconfigured", body = ReadinessResponse)
+    ),
+    tag = "Health"
+)]
+async fn ready() -> (StatusCode, Json<ReadinessResponse>) {
+    let problems = readiness_problems();
+    let status = if problems.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(ReadinessResponse {
+            ready: problems.is_empty(),
+            problems,
+        }),
+    )
+}
+
+/// Whether the configured provider can be reached, so clients can show when goose is offline
+#[utoipa::path(
+    get,
+    path = "/connectivity",
+    responses(
+        (status = 200, description = "Online, local, or offline with the reason", body = Connectivity),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Health"
+)]
+async fn connectivity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Connectivity>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    Ok(Json(offline::check_configured_provider().await))
+}
+
+/// Configure the health probes, which are served unversioned and without authentication
 pub fn routes() -> Router {
-    Router::new().route("/status", get(status))
+    Router::new()
+        .route("/status", get(status))
+        .route("/ready", get(ready))
+}
+
+/// Configure the health routes that belong to the versioned API
+pub fn api_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/connectivity", get(connectivity))
+        .with_state(state)
 }
@@ -0,0 +1,591 @@
+use super::reply::{sse_frame, MessageEvent, SseResponse};
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use goose::session;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use utoipa::{IntoParams, ToSchema};
+
+/// Frames buffered per session before a slow client starts missing them
+const LIVE_BUFFER: usize = 256;
+
+/// Turn holder for prompts sent without a client id
+pub const ANONYMOUS_CLIENT: &str = "anonymous";
+
+/// Header carrying an observer token in place of the secret key
+pub const OBSERVER_TOKEN_HEADER: &str = "X-Observer-Token";
+
+/// How long an observer token is valid for when the request doesn't say
+const DEFAULT_OBSERVER_TTL: Duration = Duration::from_secs(8 * 60 * 60);
+
+/// Longest an observer token can be valid for
+const MAX_OBSERVER_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// What an attached client may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveRole {
+    /// Holds the secret key, so can prompt and answer approvals as well as watch
+    Collaborator,
+    /// Holds an observer token for the session, which only lets it watch
+    Observer,
+}
+
+/// Read-only access to one session, given to someone without the secret key
+struct ObserverGrant {
+    session_id: String,
+    label: String,
+    expires_at: DateTime<Utc>,
+}
+
+struct LiveSession {
+    events: broadcast::Sender<String>,
+    /// Attached client ids, with how many connections each has open
+    clients: HashMap<String, usize>,
+    /// Attached observers by label, with how many connections each has open
+    observers: HashMap<String, usize>,
+    /// Client whose prompt is being answered
+    turn: Option<String>,
+}
+
+impl LiveSession {
+    fn new() -> Self {
+        Self {
+            events: broadcast::channel(LIVE_BUFFER).0,
+            clients: HashMap::new(),
+            observers: HashMap::new(),
+            turn: None,
+        }
+    }
+
+    fn connections(&mut self, role: LiveRole) -> &mut HashMap<String, usize> {
+        match role {
+            LiveRole::Collaborator => &mut self.clients,
+            LiveRole::Observer => &mut self.observers,
+        }
+    }
+
+    fn publish_presence(&self) {
+        let sorted = |ids: &HashMap<String, usize>| {
+            let mut ids: Vec<String> = ids.keys().cloned().collect();
+            ids.sort();
+            ids
+        };
+        let _ = self.events.send(sse_frame(&LiveEvent::Presence {
+            clients: sorted(&self.clients),
+            observers: sorted(&self.observers),
+            turn: self.turn.clone(),
+        }));
+    }
+}
+
+/// Clients attached to live sessions, and the turn lock that keeps their prompts from
+/// interleaving. Kept in memory; a session's entry goes away once nobody uses it.
+#[derive(Default)]
+pub struct LiveSessions {
+    sessions: Mutex<HashMap<String, LiveSession>>,
+    observer_grants: Mutex<HashMap<String, ObserverGrant>>,
+}
+
+impl LiveSessions {
+    fn update<T>(&self, session_id: &str, f: impl FnOnce(&mut LiveSession) -> T) -> T {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(LiveSession::new);
+        let result = f(session);
+        if session.clients.is_empty() && session.observers.is_empty() && session.turn.is_none() {
+            sessions.remove(session_id);
+        }
+        result
+    }
+
+    /// Subscribe `client_id` to everything streamed for the session until the returned
+    /// attachment is dropped
+    pub fn attach(
+        self: &Arc<Self>,
+        session_id: &str,
+        client_id: &str,
+        role: LiveRole,
+    ) -> (broadcast::Receiver<String>, Attachment) {
+        let events = self.update(session_id, |session| {
+            *session
+                .connections(role)
+                .entry(client_id.to_string())
+                .or_default() += 1;
+            let events = session.events.subscribe();
+            session.publish_presence();
+            events
+        });
+        let attachment = Attachment {
+            live: self.clone(),
+            session_id: session_id.to_string(),
+            client_id: client_id.to_string(),
+            role,
+        };
+        (events, attachment)
+    }
+
+    /// Let `client_id` prompt the session until the returned turn is dropped. Fails with the
+    /// current holder if another prompt is still being answered.
+    pub fn take_turn(self: &Arc<Self>, session_id: &str, client_id: &str) -> Result<Turn, String> {
+        self.update(session_id, |session| match &session.turn {
+            Some(holder) => Err(holder.clone()),
+            None => {
+                session.turn = Some(client_id.to_string());
+                session.publish_presence();
+                Ok(())
+            }
+        })?;
+        Ok(Turn {
+            live: self.clone(),
+            session_id: session_id.to_string(),
+        })
+    }
+
+    /// Send an SSE frame to every client attached to the session
+    pub fn publish(&self, session_id: &str, frame: &str) {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(session) = sessions.get(session_id) {
+            let _ = session.events.send(frame.to_string());
+        }
+    }
+
+    /// Whether a client that could prompt the session, or stop a reply, is following it.
+    /// Observers don't count, as they can do neither.
+    pub fn has_collaborators(&self, session_id: &str) -> bool {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        sessions
+            .get(session_id)
+            .is_some_and(|session| !session.clients.is_empty())
+    }
+
+    /// Issue a token that lets its holder watch the session and nothing else until
+    /// `expires_at`. Expired tokens are forgotten as new ones are issued.
+    pub fn grant_observer(
+        &self,
+        session_id: &str,
+        label: String,
+        expires_at: DateTime<Utc>,
+    ) -> String {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let mut grants = self
+            .observer_grants
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        grants.retain(|_, grant| grant.expires_at > now);
+        grants.insert(
+            token.clone(),
+            ObserverGrant {
+                session_id: session_id.to_string(),
+                label,
+                expires_at,
+            },
+        );
+        token
+    }
+
+    /// Withdraw an observer token; streams opened with it close shortly after
+    pub fn revoke_observer(&self, session_id: &str, token: &str) -> bool {
+        let mut grants = self
+            .observer_grants
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if grants
+            .get(token)
+            .is_some_and(|grant| grant.session_id == session_id)
+        {
+            grants.remove(token);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The label of the observer `token` was issued to, if it is valid for the session and
+    /// has not expired
+    pub fn observer_label(&self, session_id: &str, token: &str) -> Option<String> {
+        self.observer_grants
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(token)
+            .filter(|grant| grant.session_id == session_id && grant.expires_at > Utc::now())
+            .map(|grant| grant.label.clone())
+    }
+}
+
+/// A client's subscription to a live session; detaches when dropped
+pub struct Attachment {
+    live: Arc<LiveSessions>,
+    session_id: String,
+    client_id: String,
+    role: LiveRole,
+}
+
+impl Drop for Attachment {
+    fn drop(&mut self) {
+        self.live.update(&self.session_id, |session| {
+            let connections = session.connections(self.role);
+            if let Some(count) = connections.get_mut(&self.client_id) {
+                *count -= 1;
+                if *count == 0 {
+                    connections.remove(&self.client_id);
+                }
+            }
+            session.publish_presence();
+        });
+    }
+}
+
+/// The right to prompt a session; the next prompt can be sent once it is dropped
+pub struct Turn {
+    live: Arc<LiveSessions>,
+    session_id: String,
+}
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        self.live.update(&self.session_id, |session| {
+            session.turn = None;
+            session.publish_presence();
+        });
+    }
+}
+
+/// Frames of the live stream that are not reply events
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(tag = "type")]
+pub enum LiveEvent {
+    /// Sent whenever a client attaches or detaches, or the turn changes hands
+    Presence {
+        clients: Vec<String>,
+        /// Labels of read-only observers
+        observers: Vec<String>,
+        /// Client whose prompt is being answered, if any
+        turn: Option<String>,
+    },
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct AttachQuery {
+    /// Identifies this client to the others, and as the turn holder when it sends a prompt.
+    /// Ignored for observers, who are shown by the label of their token.
+    #[serde(default)]
+    client_id: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ObserverRequest {
+    /// Who the token is for, shown to the other clients while they watch
+    label: Option<String>,
+    /// How many seconds the token is valid for; 8 hours if not given, at most 7 days
+    ttl_seconds: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ObserverResponse {
+    /// Send as the X-Observer-Token header to /sessions/{session_id}/live
+    token: String,
+    label: String,
+    /// When the token stops working, closing any stream opened with it
+    expires_at: DateTime<Utc>,
+}
+
+/// Who is attaching: a secret key holder, or an observer with a token for this session
+fn attach_role(
+    headers: &HeaderMap,
+    state: &AppState,
+    session_id: &str,
+) -> Result<(LiveRole, Option<String>), StatusCode> {
+    if verify_secret_key(headers, state).is_ok() {
+        return Ok((LiveRole::Collaborator, None));
+    }
+    headers
+        .get(OBSERVER_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|token| state.live.observer_label(session_id, token))
+        .map(|label| (LiveRole::Observer, Some(label)))
+        .ok_or(StatusCode::UNAUTHORIZED)
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/live",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        AttachQuery
+    ),
+    responses(
+        (status = 200, description = "Server-sent events: every MessageEvent streamed for the session by any client's /reply, plus LiveEvent frames. Observers first get the saved conversation as Message events.", body = LiveEvent, content_type = "text/event-stream"),
+        (status = 400, description = "Bad request - Empty client id"),
+        (status = 401, description = "Unauthorized - Neither a valid API key nor an observer token for this session")
+    ),
+    security(
+        ("api_key" = []),
+        ("observer_token" = [])
+    ),
+    tag = "Session Management"
+)]
+// Attach to a session to follow replies to prompts sent by any client
+async fn attach_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Query(query): Query<AttachQuery>,
+) -> Result<SseResponse, StatusCode> {
+    let (role, observer_label) = attach_role(&headers, &state, &session_id)?;
+    let client_id = match observer_label {
+        Some(label) => label,
+        None if query.client_id.trim().is_empty() => return Err(StatusCode::BAD_REQUEST),
+        None => query.client_id,
+    };
+
+    // Observers have no other way to read the session, so they start from what is saved
+    let history = match role {
+        LiveRole::Observer => session::get_path(session::Identifier::Name(session_id.clone()))
+            .ok()
+            .filter(|path| path.exists())
+            .and_then(|path| session::read_messages(&path).ok())
+            .map(|conversation| conversation.messages().clone())
+            .unwrap_or_default(),
+        LiveRole::Collaborator => Vec::new(),
+    };
+    let token = headers
+        .get(OBSERVER_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let live = state.live.clone();
+    let (mut events, attachment) = live.attach(&session_id, &client_id, role);
+    let (tx, rx) = mpsc::channel(100);
+    tokio::spawn(async move {
+        let _attachment = attachment;
+        for message in history {
+            if tx
+                .send(sse_frame(&MessageEvent::Message { message }))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+        let mut heartbeat = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            let frame = tokio::select! {
+                _ = heartbeat.tick() => {
+                    // A revoked or expired observer is cut off at the next heartbeat
+                    if role == LiveRole::Observer
+                        && token.as_deref().and_then(|t| live.observer_label(&session_id, t)).is_none()
+                    {
+                        break;
+                    }
+                    sse_frame(&MessageEvent::Ping)
+                }
+                event = events.recv() => match event {
+                    Ok(frame) => frame,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Live client fell behind and missed {} events", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            if tx.send(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+    Ok(SseResponse::new(ReceiverStream::new(rx)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/observers",
+    request_body = ObserverRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Observer token issued", body = ObserverResponse),
+        (status = 400, description = "Bad request - ttl_seconds is zero or longer than 7 days"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Let someone watch the session without being able to prompt or approve tools
+async fn grant_observer(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<ObserverRequest>,
+) -> Result<Json<ObserverResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let label = request
+        .label
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .unwrap_or_else(|| "observer".to_string());
+    let ttl = match request.ttl_seconds {
+        Some(seconds) => Duration::from_secs(seconds),
+        None => DEFAULT_OBSERVER_TTL,
+    };
+    if ttl.is_zero() || ttl > MAX_OBSERVER_TTL {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let expires_at = Utc::now() + ttl;
+    let token = state
+        .live
+        .grant_observer(&session_id, label.clone(), expires_at);
+    Ok(Json(ObserverResponse {
+        token,
+        label,
+        expires_at,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}/observers/{token}",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("token" = String, Path, description = "Observer token to revoke")
+    ),
+    responses(
+        (status = 204, description = "Observer token revoked"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "No such observer token for this session")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn revoke_observer(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, token)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    if state.live.revoke_observer(&session_id, &token) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/sessions/{session_id}/live", get(attach_session))
+        .route("/sessions/{session_id}/observers", post(grant_observer))
+        .route(
+            "/sessions/{session_id}/observers/{token}",
+            delete(revoke_observer),
+        )
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turn_lock() {
+        let live = Arc::new(LiveSessions::default());
+        let turn = live.take_turn("shared", "alice").unwrap();
+        assert_eq!(
+            live.take_turn("shared", "bob").err().as_deref(),
+            Some("alice")
+        );
+        assert!(live.take_turn("other", "bob").is_ok());
+
+        drop(turn);
+        assert!(live.take_turn("shared", "bob").is_ok());
+        assert!(live.sessions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_attached_clients_see_published_frames() {
+        let live = Arc::new(LiveSessions::default());
+        let (mut alice, alice_attachment) = live.attach("shared", "alice", LiveRole::Collaborator);
+        let (mut bob, bob_attachment) = live.attach("shared", "bob", LiveRole::Collaborator);
+        assert!(live.has_collaborators("shared"));
+
+        // Alice sees bob arrive
+        assert!(alice.recv().await.unwrap().contains("\"alice\""));
+        assert!(alice.recv().await.unwrap().contains("\"bob\""));
+
+        live.publish("shared", "data: {\"type\":\"Ping\"}\n\n");
+        assert!(bob.recv().await.unwrap().contains("Presence"));
+        assert_eq!(bob.recv().await.unwrap(), "data: {\"type\":\"Ping\"}\n\n");
+
+        drop(alice_attachment);
+        drop(bob_attachment);
+        assert!(!live.has_collaborators("shared"));
+    }
+
+    #[tokio::test]
+    async fn test_observers_watch_without_taking_part() {
+        let live = Arc::new(LiveSessions::default());
+        let token = live.grant_observer(
+            "shared",
+            "dana".to_string(),
+            Utc::now() + Duration::from_secs(60),
+        );
+        assert_eq!(
+            live.observer_label("shared", &token).as_deref(),
+            Some("dana")
+        );
+        assert_eq!(live.observer_label("other", &token), None);
+
+        let (mut dana, attachment) = live.attach("shared", "dana", LiveRole::Observer);
+        let presence = dana.recv().await.unwrap();
+        assert!(presence.contains("\"clients\":[]"));
+        assert!(presence.contains("\"observers\":[\"dana\"]"));
+        assert!(!live.has_collaborators("shared"));
+
+        assert!(!live.revoke_observer("other", &token));
+        assert!(live.revoke_observer("shared", &token));
+        assert_eq!(live.observer_label("shared", &token), None);
+        drop(attachment);
+        assert!(live.sessions.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_observer_tokens_expire() {
+        let live = LiveSessions::default();
+        let expired = live.grant_observer(
+            "shared",
+            "dana".to_string(),
+            Utc::now() - Duration::from_secs(1),
+        );
+        assert_eq!(live.observer_label("shared", &expired), None);
+
+        let valid = live.grant_observer(
+            "shared",
+            "erin".to_string(),
+            Utc::now() + Duration::from_secs(60),
+        );
+        assert_eq!(
+            live.observer_label("shared", &valid).as_deref(),
+            Some("erin")
+        );
+        // Issuing a token forgets the expired ones
+        assert_eq!(live.observer_grants.lock().unwrap().len(), 1);
+    }
+}
@@ -1,32 +1,166 @@
+//! HTTP routes for goosed.
+//!
+//! The API is versioned by path prefix: every route below is served under `/v1`. The same
+//! routes are still answered at their old unversioned paths so existing clients keep
+//! working, but those responses carry `Deprecation` and a `Link` to the `/v1` successor.
+//!
+//! Policy: within a version, changes are additive only (new routes, new optional fields).
+//! Removing or changing the meaning of anything requires a new prefix, and the previous
+//! version stays mounted for at least one release after its successor ships, with
+//! deprecation headers announcing it. `/status`, `/ready` and the A2A endpoints are exempt,
+//! since health probes and the A2A protocol pin their own paths.
+
 // Export route modules
+pub mod a2a;
 pub mod agent;
+pub mod alerts;
+pub mod approvals;
 pub mod audio;
+pub mod backup;
+pub mod bench;
+pub mod compare;
 pub mod config_management;
 pub mod context;
+pub mod diagnostics;
+pub mod elevation;
+pub mod exemplars;
 pub mod extension;
+pub mod feedback;
+pub mod governance;
 pub mod health;
+pub mod live;
+pub mod pins;
+pub mod prompts;
 pub mod recipe;
 pub mod reply;
+pub mod run_queue;
+pub mod runs;
 pub mod schedule;
 pub mod session;
 pub mod setup;
+pub mod stats;
+pub mod style;
+pub mod templates;
 pub mod utils;
 use std::sync::Arc;
 
-use axum::Router;
+use axum::{extract::Request, middleware::Next, response::Response, Router};
+use http::{header, HeaderValue};
+
+/// Path prefix of the current API version
+pub const API_PREFIX: &str = "/v1";
+
+/// Paths that are served as-is rather than under the version prefix
+pub const UNVERSIONED_PATHS: &[&str] = &[
+    "/status",
+    "/ready",
+    "/a2a",
+    "/.well-known/agent.json",
+    "/.well-known/agent-card.json",
+];
 
 // Function to configure all routes
 pub fn configure(state: Arc<crate::state::AppState>) -> Router {
-    Router::new()
-        .merge(health::routes())
+    let api = Router::new()
         .merge(reply::routes(state.clone()))
         .merge(agent::routes(state.clone()))
+        .merge(alerts::routes(state.clone()))
+        .merge(approvals::routes(state.clone()))
         .merge(audio::routes(state.clone()))
+        .merge(backup::routes(state.clone()))
+        .merge(bench::routes(state.clone()))
+        .merge(compare::routes(state.clone()))
         .merge(context::routes(state.clone()))
+        .merge(diagnostics::routes(state.clone()))
+        .merge(elevation::routes(state.clone()))
+        .merge(exemplars::routes(state.clone()))
         .merge(extension::routes(state.clone()))
+        .merge(feedback::routes(state.clone()))
+        .merge(governance::routes(state.clone()))
+        .merge(health::api_routes(state.clone()))
+        .merge(live::routes(state.clone()))
+        .merge(pins::routes(state.clone()))
+        .merge(prompts::routes(state.clone()))
         .merge(config_management::routes(state.clone()))
         .merge(recipe::routes(state.clone()))
+        .merge(run_queue::routes(state.clone()))
+        .merge(runs::routes(state.clone()))
         .merge(session::routes(state.clone()))
         .merge(schedule::routes(state.clone()))
         .merge(setup::routes(state.clone()))
+        .merge(stats::routes(state.clone()))
+        .merge(style::routes(state.clone()))
+        .merge(templates::routes(state.clone()));
+
+    Router::new()
+        .merge(health::routes())
+        .merge(a2a::routes(state))
+        .nest(API_PREFIX, api.clone())
+        .merge(api.layer(axum::middleware::from_fn(deprecate_unversioned)))
+}
+
+/// Marks responses to the legacy unversioned paths as deprecated, pointing at the /v1 path
+async fn deprecate_unversioned(request: Request, next: Next) -> Response {
+    let successor = format!(
+        "<{}{}>; rel=\"successor-version\"",
+        API_PREFIX,
+        request.uri().path()
+    );
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert("Deprecation", HeaderValue::from_static("true"));
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.insert(header::LINK, link);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use goose::agents::Agent;
+    use tower::ServiceExt;
+
+    async fn get(app: Router, uri: &str) -> Response {
+        let request = Request::builder()
+            .uri(uri)
+            .header("X-Secret-Key", "test")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    async fn get_without_key(app: Router, uri: &str) -> Response {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_versioned_and_legacy_paths() {
+        let state =
+            crate::state::AppState::new(Arc::new(Agent::default()), "test".to_string()).await;
+        let app = configure(state);
+
+        let response = get(app.clone(), "/v1/config/current-model").await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(response.headers().get("Deprecation").is_none());
+
+        let response = get(app.clone(), "/config/current-model").await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()["Deprecation"], "true");
+        assert_eq!(
+            response.headers()[header::LINK],
+            "</v1/config/current-model>; rel=\"successor-version\""
+        );
+
+        let response = get(app.clone(), "/status").await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert!(response.headers().get("Deprecation").is_none());
+
+        let response = get_without_key(app.clone(), "/status").await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        let response = get_without_key(app, "/v1/connectivity").await;
+        assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+    }
 }
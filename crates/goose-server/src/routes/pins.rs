@@ -0,0 +1,142 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get},
+    Json, Router,
+};
+use goose::session::{self, pins, Pin, PinnedItem};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct PinListResponse {
+    /// Pinned items, oldest first
+    pins: Vec<Pin>,
+}
+
+fn existing_session_path(session_id: String) -> Result<PathBuf, StatusCode> {
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(session_path)
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/pins",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Items pinned to the session", body = PinListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn list_pins(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<PinListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let session_path = existing_session_path(session_id)?;
+    let pins = pins::read_pins(&session_path).map_err(|e| {
+        tracing::error!("Failed to read pinned items: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(PinListResponse { pins }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/pins",
+    request_body = PinnedItem,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Item pinned; it is re-included whenever the session is compacted", body = Pin),
+        (status = 400, description = "The note is empty, or the file path is empty or not inside the session's working directory"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn add_pin(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(item): Json<PinnedItem>,
+) -> Result<Json<Pin>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let invalid = match &item {
+        PinnedItem::File { path } => path.trim().is_empty() || !pins::is_relative_inside(path),
+        PinnedItem::Note { text } => text.trim().is_empty(),
+    };
+    if invalid {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let session_path = existing_session_path(session_id)?;
+    let pin = pins::add_pin(&session_path, item).map_err(|e| {
+        tracing::error!("Failed to pin item: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(pin))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}/pins/{pin_id}",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("pin_id" = String, Path, description = "ID of the pin to remove")
+    ),
+    responses(
+        (status = 204, description = "Item unpinned"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session or pin not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn remove_pin(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, pin_id)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let session_path = existing_session_path(session_id)?;
+    match pins::remove_pin(&session_path, &pin_id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Error removing pin '{}': {:?}", pin_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/sessions/{session_id}/pins", get(list_pins).post(add_pin))
+        .route("/sessions/{session_id}/pins/{pin_id}", delete(remove_pin))
+        .with_state(state)
+}
@@ -0,0 +1,328 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use goose::session;
+use rmcp::model::Prompt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct PromptArgumentInfo {
+    name: String,
+    description: Option<String>,
+    required: bool,
+}
+
+/// A prompt template an extension advertises
+#[derive(Serialize, ToSchema)]
+pub struct ExtensionPrompt {
+    extension: String,
+    name: String,
+    description: Option<String>,
+    arguments: Vec<PromptArgumentInfo>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PromptListResponse {
+    /// Prompts of every enabled extension, by extension and then by name
+    prompts: Vec<ExtensionPrompt>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsePromptRequest {
+    /// Values of the prompt's arguments
+    #[serde(default)]
+    arguments: HashMap<String, String>,
+    /// Session to add the prompt's messages to; a new session is started when omitted
+    session_id: Option<String>,
+    /// Working directory of the new session; required when no session id is given
+    working_dir: Option<PathBuf>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsePromptResponse {
+    session_id: String,
+    /// The messages the prompt produced, as they were added to the session
+    messages: Vec<Message>,
+}
+
+/// The prompt `name` of `extension`. Prompts are looked up by both, since extensions are free
+/// to give their prompts the same names.
+fn find_prompt<'a>(
+    prompts: &'a HashMap<String, Vec<Prompt>>,
+    extension: &str,
+    name: &str,
+) -> Option<&'a Prompt> {
+    prompts
+        .get(extension)?
+        .iter()
+        .find(|prompt| prompt.name == name)
+}
+
+/// The first required argument of `prompt` that `arguments` lacks
+fn missing_argument<'a>(
+    prompt: &'a Prompt,
+    arguments: &HashMap<String, String>,
+) -> Option<&'a str> {
+    prompt
+        .arguments
+        .iter()
+        .flatten()
+        .find(|argument| {
+            argument.required.unwrap_or(false) && !arguments.contains_key(&argument.name)
+        })
+        .map(|argument| argument.name.as_str())
+}
+
+fn extension_prompt(extension: &str, prompt: &Prompt) -> ExtensionPrompt {
+    ExtensionPrompt {
+        extension: extension.to_string(),
+        name: prompt.name.clone(),
+        description: prompt.description.clone(),
+        arguments: prompt
+            .arguments
+            .iter()
+            .flatten()
+            .map(|argument| PromptArgumentInfo {
+                name: argument.name.clone(),
+                description: argument.description.clone(),
+                required: argument.required.unwrap_or(false),
+            })
+            .collect(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/prompts",
+    responses(
+        (status = 200, description = "Prompt templates the enabled extensions advertise", body = PromptListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 412, description = "No agent configured"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Prompts"
+)]
+async fn list_prompts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<PromptListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let by_extension = agent
+        .extension_manager
+        .read()
+        .await
+        .list_prompts(CancellationToken::default())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to list prompts: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut prompts: Vec<ExtensionPrompt> = by_extension
+        .iter()
+        .flat_map(|(extension, prompts)| {
+            prompts
+                .iter()
+                .map(|prompt| extension_prompt(extension, prompt))
+        })
+        .collect();
+    prompts.sort_by(|a, b| (&a.extension, &a.name).cmp(&(&b.extension, &b.name)));
+    Ok(Json(PromptListResponse { prompts }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/prompts/{extension}/{name}",
+    request_body = UsePromptRequest,
+    params(
+        ("extension" = String, Path, description = "Extension the prompt belongs to"),
+        ("name" = String, Path, description = "Name of the prompt")
+    ),
+    responses(
+        (status = 200, description = "The prompt's messages were added to the session, which is ready to reply to", body = UsePromptResponse),
+        (status = 400, description = "A required argument or the new session's working directory is missing"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Prompt or session not found"),
+        (status = 412, description = "No agent configured"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Prompts"
+)]
+async fn use_prompt(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((extension, name)): Path<(String, String)>,
+    Json(request): Json<UsePromptRequest>,
+) -> Result<Json<UsePromptResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    let extension_manager = agent.extension_manager.read().await;
+    let prompts = extension_manager
+        .list_prompts(CancellationToken::default())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to list prompts: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let prompt = find_prompt(&prompts, &extension, &name).ok_or(StatusCode::NOT_FOUND)?;
+    if let Some(argument) = missing_argument(prompt, &request.arguments) {
+        tracing::warn!("Prompt '{}' needs the argument '{}'", name, argument);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // A new session runs where the client says, not wherever goosed happens to be
+    let (session_id, session_path, working_dir) = match (&request.session_id, &request.working_dir)
+    {
+        (Some(session_id), _) => {
+            let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            if !session_path.exists() {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            (session_id.clone(), session_path, None)
+        }
+        (None, Some(working_dir)) => {
+            let session_id = session::generate_session_id();
+            let session_path = session::get_path_for_working_dir(&session_id, working_dir)
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            (session_id, session_path, Some(working_dir.clone()))
+        }
+        (None, None) => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let arguments =
+        serde_json::to_value(&request.arguments).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let result = extension_manager
+        .get_prompt(&extension, &name, arguments, CancellationToken::default())
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get prompt '{}': {:?}", name, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let prompt_messages: Vec<Message> = result.messages.into_iter().map(Message::from).collect();
+
+    // Held until the session is saved, so a reply finishing meanwhile can't be overwritten
+    let lock_path = session_path.clone();
+    let _lock = tokio::task::spawn_blocking(move || session::lock_session(&lock_path))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| {
+            tracing::error!("Failed to lock session: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let (mut metadata, mut messages) = if let Some(working_dir) = working_dir {
+        let metadata = session::SessionMetadata {
+            description: result.description.unwrap_or_else(|| name.clone()),
+            ..session::SessionMetadata::new(working_dir)
+        };
+        (metadata, Vec::new())
+    } else {
+        let metadata = session::read_metadata(&session_path).map_err(|e| {
+            tracing::error!("Failed to read session metadata: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        let messages = session::read_messages(&session_path).map_err(|e| {
+            tracing::error!("Failed to read session messages: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        (metadata, messages.messages().clone())
+    };
+    messages.extend(prompt_messages.iter().cloned());
+    metadata.message_count = messages.len();
+    session::storage::save_messages_with_metadata(
+        &session_path,
+        &metadata,
+        &Conversation::new_unvalidated(messages),
+    )
+    .map_err(|e| {
+        tracing::error!("Failed to save prompt messages: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(UsePromptResponse {
+        session_id,
+        messages: prompt_messages,
+    }))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/prompts", get(list_prompts))
+        .route("/prompts/{extension}/{name}", post(use_prompt))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::PromptArgument;
+
+    fn prompt(name: &str, required: &[&str]) -> Prompt {
+        Prompt::new(
+            name,
+            None::<String>,
+            Some(
+                required
+                    .iter()
+                    .map(|argument| PromptArgument {
+                        name: argument.to_string(),
+                        description: None,
+                        required: Some(true),
+                    })
+                    .collect(),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_prompts_are_found_by_extension_and_name() {
+        let prompts = HashMap::from([
+            ("github".to_string(), vec![prompt("review", &[])]),
+            ("gitlab".to_string(), vec![prompt("review", &["mr"])]),
+        ]);
+        let found = find_prompt(&prompts, "gitlab", "review").unwrap();
+        assert_eq!(missing_argument(found, &HashMap::new()), Some("mr"));
+        let found = find_prompt(&prompts, "github", "review").unwrap();
+        assert_eq!(missing_argument(found, &HashMap::new()), None);
+        assert!(find_prompt(&prompts, "jira", "review").is_none());
+        assert!(find_prompt(&prompts, "github", "triage").is_none());
+    }
+
+    #[test]
+    fn test_new_sessions_need_a_working_dir() {
+        let request: UsePromptRequest =
+            serde_json::from_str(r#"{"arguments": {"mr": "12"}, "workingDir": "/work/shop"}"#)
+                .unwrap();
+        assert_eq!(request.working_dir, Some(PathBuf::from("/work/shop")));
+        assert!(request.session_id.is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use super::utils::verify_secret_key;
+use super::live::ANONYMOUS_CLIENT;
+use super::utils::{request_locale, verify_secret_key};
 use crate::state::AppState;
 use axum::{
     extract::{DefaultBodyLimit, State},
@@ -12,12 +13,14 @@ use futures::{stream::StreamExt, Stream};
 use goose::conversation::message::{Message, MessageContent};
 use goose::conversation::Conversation;
 use goose::{
-    agents::{AgentEvent, SessionConfig},
+    agents::{with_reply_provider, AgentEvent, CritiqueConfig, RunLimits, SessionConfig},
     permission::permission_confirmation::PrincipalType,
+    providers::base::Provider,
 };
 use goose::{
     permission::{Permission, PermissionConfirmation},
     session,
+    session::manifest::{self, RunManifest},
 };
 use mcp_core::ToolResult;
 use rmcp::model::{Content, ServerNotification};
@@ -85,12 +88,24 @@ fn track_tool_telemetry(content: &MessageContent, all_messages: &[Message]) {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct ChatRequest {
-    messages: Vec<Message>,
-    session_id: Option<String>,
-    session_working_dir: String,
-    scheduled_job_id: Option<String>,
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct ChatRequest {
+    pub(crate) messages: Vec<Message>,
+    pub(crate) session_id: Option<String>,
+    pub(crate) session_working_dir: String,
+    pub(crate) scheduled_job_id: Option<String>,
+    /// Identifies the client sending the prompt to others attached to the session
+    #[serde(default)]
+    pub(crate) client_id: Option<String>,
+    /// Time and tool call limits for this run; unset limits fall back to the config
+    #[serde(default)]
+    pub(crate) run_limits: RunLimits,
+    /// Review of the final answer of this run, from the session's recipe
+    #[serde(default)]
+    pub(crate) critique: Option<CritiqueConfig>,
+    /// The run this one replays, recorded in its manifest
+    #[serde(skip)]
+    pub(crate) replay_of: Option<String>,
 }
 
 pub struct SseResponse {
@@ -98,7 +113,7 @@ pub struct SseResponse {
 }
 
 impl SseResponse {
-    fn new(rx: ReceiverStream<String>) -> Self {
+    pub(crate) fn new(rx: ReceiverStream<String>) -> Self {
         Self { rx }
     }
 }
@@ -127,9 +142,10 @@ impl IntoResponse for SseResponse {
     }
 }
 
-#[derive(Debug, Serialize)]
+/// A single `data:` frame of the `/reply` event stream
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(tag = "type")]
-enum MessageEvent {
+pub enum MessageEvent {
     Message {
         message: Message,
     },
@@ -145,28 +161,49 @@ enum MessageEvent {
     },
     Notification {
         request_id: String,
+        #[schema(value_type = Object)]
         message: ServerNotification,
     },
     Ping,
 }
 
-async fn stream_event(
-    event: MessageEvent,
-    tx: &mpsc::Sender<String>,
-    cancel_token: &CancellationToken,
-) {
-    let json = serde_json::to_string(&event).unwrap_or_else(|e| {
+/// `event` as a `data:` frame of an event stream
+pub(crate) fn sse_frame(event: &impl Serialize) -> String {
+    let json = serde_json::to_string(event).unwrap_or_else(|e| {
         format!(
             r#"{{"type":"Error","error":"Failed to serialize event: {}"}}"#,
             e
         )
     });
-    if tx.send(format!("data: {}\n\n", json)).await.is_err() {
+    format!("data: {}\n\n", json)
+}
+
+async fn stream_event(
+    event: MessageEvent,
+    tx: &mpsc::Sender<String>,
+    cancel_token: &CancellationToken,
+) {
+    if tx.send(sse_frame(&event)).await.is_err() {
         tracing::info!("client hung up");
         cancel_token.cancel();
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/reply",
+    request_body = ChatRequest,
+    responses(
+        (status = 200, description = "Server-sent events, one MessageEvent per data frame", body = MessageEvent, content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized - invalid secret key"),
+        (status = 409, description = "Another prompt is still being answered in this session"),
+        (status = 412, description = "No agent configured")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Reply"
+)]
 async fn reply_handler(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -174,6 +211,32 @@ async fn reply_handler(
 ) -> Result<SseResponse, StatusCode> {
     verify_secret_key(&headers, &state)?;
 
+    stream_reply(state, request, request_locale(&headers), None)
+}
+
+/// Run the agent on `request` in the background and stream its events, saving the
+/// conversation to the session when the reply finishes. A `provider` given here is used for
+/// this reply only, leaving the agent's own provider to the replies of other sessions.
+///
+/// Events also go to every client attached to the session, and the request's client holds
+/// the session's turn until the reply ends, so a second prompt meanwhile is a conflict.
+pub(crate) fn stream_reply(
+    state: Arc<AppState>,
+    request: ChatRequest,
+    locale: &'static str,
+    provider: Option<Arc<dyn Provider>>,
+) -> Result<SseResponse, StatusCode> {
+    let session_id = request
+        .session_id
+        .unwrap_or_else(session::generate_session_id);
+    let client_id = request
+        .client_id
+        .unwrap_or_else(|| ANONYMOUS_CLIENT.to_string());
+    let turn = state
+        .live
+        .take_turn(&session_id, &client_id)
+        .map_err(|_| StatusCode::CONFLICT)?;
+
     let session_start = std::time::Instant::now();
 
     tracing::info!(
@@ -182,28 +245,60 @@ async fn reply_handler(
         interface = "ui",
         "Session started"
     );
+    goose::analytics::record_feature("desktop_session");
 
-    let (tx, rx) = mpsc::channel(100);
-    let stream = ReceiverStream::new(rx);
+    let (tx, mut frames) = mpsc::channel::<String>(100);
+    let (client_tx, client_rx) = mpsc::channel(100);
+    let stream = ReceiverStream::new(client_rx);
     let cancel_token = CancellationToken::new();
 
     let messages = Conversation::new_unvalidated(request.messages);
     let session_working_dir = request.session_working_dir.clone();
 
-    let session_id = request
-        .session_id
-        .unwrap_or_else(session::generate_session_id);
+    // The other clients see the prompt, then everything the requesting client sees except
+    // heartbeats. The reply keeps going if the requesting client hangs up while another
+    // collaborator is attached, as they can stop it; observers alone can't, so it is cancelled.
+    if let Some(prompt) = messages.last() {
+        state.live.publish(
+            &session_id,
+            &sse_frame(&MessageEvent::Message {
+                message: prompt.clone(),
+            }),
+        );
+    }
+    let live = state.live.clone();
+    let live_session_id = session_id.clone();
+    let live_cancel = cancel_token.clone();
+    tokio::spawn(async move {
+        let ping = sse_frame(&MessageEvent::Ping);
+        let mut client_tx = Some(client_tx);
+        while let Some(frame) = frames.recv().await {
+            if frame != ping {
+                live.publish(&live_session_id, &frame);
+            }
+            if let Some(tx) = &client_tx {
+                if tx.send(frame).await.is_err() {
+                    client_tx = None;
+                }
+            }
+            if client_tx.is_none() && !live.has_collaborators(&live_session_id) {
+                tracing::info!("client hung up");
+                live_cancel.cancel();
+                break;
+            }
+        }
+    });
 
     let task_cancel = cancel_token.clone();
     let task_tx = tx.clone();
 
-    std::mem::drop(tokio::spawn(async move {
+    let reply = async move {
         let agent = match state.get_agent().await {
             Ok(agent) => agent,
             Err(_) => {
                 let _ = stream_event(
                     MessageEvent::Error {
-                        error: "No agent configured".to_string(),
+                        error: goose::i18n::translate(locale, "api.no_agent", &[]),
                     },
                     &task_tx,
                     &cancel_token,
@@ -219,6 +314,8 @@ async fn reply_handler(
             schedule_id: request.scheduled_job_id.clone(),
             execution_mode: None,
             max_turns: None,
+            run_limits: request.run_limits,
+            critique: request.critique.clone(),
             retry_config: None,
         };
 
@@ -246,7 +343,10 @@ async fn reply_handler(
         };
 
         let mut all_messages = messages.clone();
-        let session_path = match session::get_path(session::Identifier::Name(session_id.clone())) {
+        let session_path = match session::get_path_for_working_dir(
+            &session_id,
+            &PathBuf::from(&session_working_dir),
+        ) {
             Ok(path) => path,
             Err(e) => {
                 tracing::error!("Failed to get session path: {}", e);
@@ -262,6 +362,16 @@ async fn reply_handler(
             }
         };
         let saved_message_count = all_messages.len();
+        let run_id = state
+            .runs
+            .start(
+                &session_id,
+                &session_working_dir,
+                session_path.clone(),
+                task_cancel.clone(),
+            )
+            .await;
+        let run_created = chrono::Utc::now().timestamp();
 
         let mut heartbeat_interval = tokio::time::interval(Duration::from_millis(500));
         loop {
@@ -324,26 +434,44 @@ async fn reply_handler(
             }
         }
 
+        match RunManifest::capture(
+            &agent,
+            &session::Identifier::Name(session_id.clone()),
+            &run_id,
+            run_created,
+            messages.messages(),
+        )
+        .await
+        {
+            Ok(mut manifest) => {
+                manifest.replay_of = request.replay_of.clone();
+                if let Err(e) = manifest::save_manifest(&session_path, manifest) {
+                    tracing::error!("Failed to save run manifest: {:?}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to capture run manifest: {:?}", e),
+        }
+
+        // Saved before the run is marked finished, so a client taking the session over (which
+        // waits for the run to finish) reads every message of it
         if all_messages.len() > saved_message_count {
             if let Ok(provider) = agent.provider().await {
-                let provider = Arc::clone(&provider);
-                let session_path_clone = session_path.to_path_buf();
-                let all_messages_clone = all_messages.clone();
-                tokio::spawn(async move {
-                    if let Err(e) = session::persist_messages(
-                        &session_path_clone,
-                        &all_messages_clone,
-                        Some(provider),
-                        Some(PathBuf::from(&session_working_dir)),
-                    )
-                    .await
-                    {
-                        tracing::error!("Failed to store session history: {:?}", e);
-                    }
-                });
+                if let Err(e) = session::persist_messages(
+                    &session_path,
+                    &all_messages,
+                    Some(Arc::clone(&provider)),
+                    Some(PathBuf::from(&session_working_dir)),
+                )
+                .await
+                {
+                    tracing::error!("Failed to store session history: {:?}", e);
+                }
             }
         }
 
+        state.runs.finish(&run_id).await;
+        drop(turn);
+
         let session_duration = session_start.elapsed();
 
         if let Ok(metadata) = session::read_metadata(&session_path) {
@@ -404,6 +532,12 @@ async fn reply_handler(
             &cancel_token,
         )
         .await;
+    };
+    std::mem::drop(tokio::spawn(async move {
+        match provider {
+            Some(provider) => with_reply_provider(provider, reply).await,
+            None => reply.await,
+        }
     }));
     Ok(SseResponse::new(stream))
 }
@@ -461,12 +595,28 @@ pub async fn confirm_permission(
     Ok(Json(Value::Object(serde_json::Map::new())))
 }
 
-#[derive(Debug, Deserialize)]
-struct ToolResultRequest {
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ToolResultRequest {
     id: String,
+    #[schema(value_type = Object)]
     result: ToolResult<Vec<Content>>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/tool_result",
+    request_body = ToolResultRequest,
+    responses(
+        (status = 200, description = "Tool result was handed to the agent", body = Value),
+        (status = 401, description = "Unauthorized - invalid secret key"),
+        (status = 412, description = "No agent configured"),
+        (status = 422, description = "Malformed tool result")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Reply"
+)]
 async fn submit_tool_result(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
@@ -584,6 +734,10 @@ mod tests {
                         session_id: Some("test-session".to_string()),
                         session_working_dir: "test-working-dir".to_string(),
                         scheduled_job_id: None,
+                        client_id: None,
+                        run_limits: Default::default(),
+                        critique: None,
+                        replay_of: None,
                     })
                     .unwrap(),
                 ))
@@ -0,0 +1,418 @@
+//! Delegated runs waiting for their turn. With `GOOSE_SERVER_MAX_CONCURRENT_RUNS` set, A2A
+//! tasks beyond the cap wait here in order. The queue is written to disk whenever it changes,
+//! so tasks still waiting when goosed stops are picked up again when it starts.
+
+use super::a2a::Task;
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get},
+    Json, Router,
+};
+use goose::config::Config;
+use goose::conversation::message::Message;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+/// How many runs may go at once; 0, the default, means no limit
+pub const MAX_CONCURRENT_RUNS_KEY: &str = "GOOSE_SERVER_MAX_CONCURRENT_RUNS";
+
+/// A task waiting to run, with everything needed to start it after a restart
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRun {
+    pub task: Task,
+    pub message: Message,
+    pub queued_at: i64,
+}
+
+impl QueuedRun {
+    pub fn new(task: Task, message: Message) -> Self {
+        Self {
+            task,
+            message,
+            queued_at: chrono::Utc::now().timestamp(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    pending: Vec<QueuedRun>,
+    running: usize,
+    /// Where the queue is saved; unset until [`RunQueue::restore`] is called
+    path: Option<PathBuf>,
+}
+
+impl QueueState {
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_string(&self.pending)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                Ok(std::fs::write(path, json)?)
+            });
+        if let Err(e) = result {
+            tracing::error!("Failed to save the run queue to {}: {}", path.display(), e);
+        }
+    }
+
+    fn position(&self, id: &str) -> Option<usize> {
+        self.pending.iter().position(|run| run.task.id == id)
+    }
+}
+
+/// Runs waiting for one of a limited number of slots, started in queue order
+#[derive(Default)]
+pub struct RunQueue {
+    state: Mutex<QueueState>,
+    max_concurrent: usize,
+    changed: Notify,
+}
+
+/// One of the queue's slots, freed when dropped
+pub struct RunSlot<'a> {
+    queue: &'a RunQueue,
+}
+
+impl Drop for RunSlot<'_> {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().running -= 1;
+        self.queue.changed.notify_waiters();
+    }
+}
+
+impl RunQueue {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            max_concurrent,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_config() -> Self {
+        Self::new(
+            Config::global()
+                .get_param(MAX_CONCURRENT_RUNS_KEY)
+                .unwrap_or(0),
+        )
+    }
+
+    /// Save the queue to `path` from now on, returning the runs left there by a previous server
+    pub fn restore(&self, path: PathBuf) -> Vec<QueuedRun> {
+        let saved: Vec<QueuedRun> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let mut state = self.state.lock().unwrap();
+        state.path = Some(path);
+        state.pending.splice(0..0, saved.iter().cloned());
+        state.save();
+        saved
+    }
+
+    /// Queue `run` and wait until it reaches the front and a slot is free. Returns None if the
+    /// run was cancelled or taken off the queue before that.
+    pub async fn wait_turn(
+        &self,
+        run: QueuedRun,
+        cancel: &CancellationToken,
+    ) -> Option<RunSlot<'_>> {
+        let id = run.task.id.clone();
+        {
+            let mut state = self.state.lock().unwrap();
+            // Runs restored at startup are already queued
+            if state.position(&id).is_none() {
+                state.pending.push(run);
+                state.save();
+            }
+        }
+        loop {
+            let changed = self.changed.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                match state.position(&id) {
+                    None => return None,
+                    Some(0) if self.max_concurrent == 0 || state.running < self.max_concurrent => {
+                        state.pending.remove(0);
+                        state.running += 1;
+                        state.save();
+                        drop(state);
+                        self.changed.notify_waiters();
+                        return Some(RunSlot { queue: self });
+                    }
+                    Some(_) => {}
+                }
+            }
+            tokio::select! {
+                _ = changed => {}
+                _ = cancel.cancelled() => {
+                    self.remove(&id);
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Take a waiting run off the queue
+    pub fn remove(&self, id: &str) -> Option<QueuedRun> {
+        let mut state = self.state.lock().unwrap();
+        let run = state.pending.remove(state.position(id)?);
+        state.save();
+        drop(state);
+        self.changed.notify_waiters();
+        Some(run)
+    }
+
+    /// Move the runs in `ids` to the front, in that order; the rest keep their order behind
+    /// them. Fails without changing anything if one of them isn't waiting.
+    fn reorder(&self, ids: &[String]) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(missing) = ids.iter().find(|id| state.position(id).is_none()) {
+            return Err(missing.clone());
+        }
+        let mut rest = std::mem::take(&mut state.pending);
+        let mut front = Vec::with_capacity(rest.len());
+        for id in ids {
+            if let Some(index) = rest.iter().position(|run| &run.task.id == id) {
+                front.push(rest.remove(index));
+            }
+        }
+        front.append(&mut rest);
+        state.pending = front;
+        state.save();
+        drop(state);
+        self.changed.notify_waiters();
+        Ok(())
+    }
+
+    fn list(&self) -> QueuedRunListResponse {
+        let state = self.state.lock().unwrap();
+        QueuedRunListResponse {
+            runs: state
+                .pending
+                .iter()
+                .map(|run| QueuedRunInfo {
+                    id: run.task.id.clone(),
+                    session_id: format!("a2a-{}", run.task.context_id),
+                    queued_at: run.queued_at,
+                    prompt: run.message.as_concat_text(),
+                })
+                .collect(),
+            running: state.running,
+            max_concurrent: (self.max_concurrent > 0).then_some(self.max_concurrent),
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRunInfo {
+    /// Id of the A2A task
+    id: String,
+    session_id: String,
+    /// Unix timestamp (seconds) when the run was queued
+    queued_at: i64,
+    prompt: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedRunListResponse {
+    /// Waiting runs, next to start first
+    runs: Vec<QueuedRunInfo>,
+    /// Runs holding a slot right now
+    running: usize,
+    /// The cap on concurrent runs, if there is one
+    max_concurrent: Option<usize>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ReorderQueueRequest {
+    /// Ids of waiting runs to move to the front of the queue, in the order they should start
+    ids: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/runs/queue",
+    responses(
+        (status = 200, description = "Runs waiting to start", body = QueuedRunListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Runs"
+)]
+async fn list_queue(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<QueuedRunListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(Json(state.run_queue.list()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/runs/queue",
+    request_body = ReorderQueueRequest,
+    responses(
+        (status = 200, description = "The queue in its new order", body = QueuedRunListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "One of the ids is not waiting in the queue")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Runs"
+)]
+async fn reorder_queue(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<ReorderQueueRequest>,
+) -> Result<Json<QueuedRunListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    state.run_queue.reorder(&request.ids).map_err(|id| {
+        tracing::warn!("Can't reorder the run queue: {} is not waiting", id);
+        StatusCode::NOT_FOUND
+    })?;
+    Ok(Json(state.run_queue.list()))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/runs/queue/{id}",
+    params(
+        ("id" = String, Path, description = "Id of the waiting run")
+    ),
+    responses(
+        (status = 204, description = "The run was taken off the queue and its task canceled"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "No waiting run with this id")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Runs"
+)]
+async fn remove_queued_run(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    state.run_queue.remove(&id).ok_or(StatusCode::NOT_FOUND)?;
+    let _ = state.a2a_tasks.cancel(&id).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/runs/queue", get(list_queue).put(reorder_queue))
+        .route("/runs/queue/{id}", delete(remove_queued_run))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::a2a::{TaskState, TaskStatus};
+
+    fn run(id: &str) -> QueuedRun {
+        QueuedRun::new(
+            Task {
+                id: id.to_string(),
+                context_id: "ctx".to_string(),
+                status: TaskStatus {
+                    state: TaskState::Submitted,
+                    message: None,
+                    timestamp: String::new(),
+                },
+                history: Vec::new(),
+                artifacts: Vec::new(),
+                kind: "task".to_string(),
+            },
+            Message::user().with_text(id),
+        )
+    }
+
+    fn pending(queue: &RunQueue) -> Vec<String> {
+        queue.list().runs.into_iter().map(|run| run.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_runs_wait_for_a_slot_in_order() {
+        let queue = Arc::new(RunQueue::new(1));
+        let cancel = CancellationToken::new();
+        let first = queue.wait_turn(run("a"), &cancel).await.unwrap();
+
+        let mut waiters = Vec::new();
+        for id in ["b", "c"] {
+            let queue = queue.clone();
+            waiters.push(tokio::spawn(async move {
+                let cancel = CancellationToken::new();
+                queue.wait_turn(run(id), &cancel).await.is_some()
+            }));
+            while !pending(&queue).contains(&id.to_string()) {
+                tokio::task::yield_now().await;
+            }
+        }
+        assert_eq!(pending(&queue), vec!["b", "c"]);
+
+        queue.reorder(&["c".to_string()]).unwrap();
+        assert_eq!(pending(&queue), vec!["c", "b"]);
+        assert!(queue.reorder(&["a".to_string()]).is_err());
+
+        assert!(queue.remove("b").is_some());
+        drop(first);
+        assert!(waiters.remove(1).await.unwrap());
+        assert!(!waiters.remove(0).await.unwrap());
+        assert!(pending(&queue).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_queue_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run_queue.json");
+
+        let queue = Arc::new(RunQueue::new(1));
+        assert!(queue.restore(path.clone()).is_empty());
+        let cancel = CancellationToken::new();
+        let _running = queue.wait_turn(run("a"), &cancel).await.unwrap();
+        let waiting = {
+            let queue = queue.clone();
+            tokio::spawn(async move {
+                queue
+                    .wait_turn(run("b"), &CancellationToken::new())
+                    .await
+                    .is_some()
+            })
+        };
+        while pending(&queue).is_empty() {
+            tokio::task::yield_now().await;
+        }
+        waiting.abort();
+
+        let restarted = RunQueue::new(1);
+        let restored = restarted.restore(path);
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].task.id, "b");
+        assert_eq!(restored[0].message.as_concat_text(), "b");
+        assert_eq!(pending(&restarted), vec!["b"]);
+    }
+}
@@ -0,0 +1,330 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use goose::session;
+use goose::session::attachments::{self, ArtifactInfo};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+struct RunEntry {
+    session_id: String,
+    working_dir: String,
+    session_path: PathBuf,
+    started_at: i64,
+    /// Session token total when the run started, so usage is counted for this run only
+    start_tokens: i32,
+    cancel: CancellationToken,
+}
+
+/// `/reply` streams currently in progress, kept in memory
+#[derive(Default)]
+pub struct ActiveRuns {
+    runs: Mutex<HashMap<String, RunEntry>>,
+}
+
+impl ActiveRuns {
+    pub async fn start(
+        &self,
+        session_id: &str,
+        working_dir: &str,
+        session_path: PathBuf,
+        cancel: CancellationToken,
+    ) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        let start_tokens = accumulated_tokens(&session_path);
+        self.runs.lock().await.insert(
+            id.clone(),
+            RunEntry {
+                session_id: session_id.to_string(),
+                working_dir: working_dir.to_string(),
+                session_path,
+                started_at: chrono::Utc::now().timestamp(),
+                start_tokens,
+                cancel,
+            },
+        );
+        id
+    }
+
+    pub async fn finish(&self, id: &str) {
+        self.runs.lock().await.remove(id);
+    }
+
+    async fn list(&self) -> Vec<RunInfo> {
+        let runs = self.runs.lock().await;
+        let mut list: Vec<RunInfo> = runs
+            .iter()
+            .map(|(id, run)| RunInfo {
+                id: id.clone(),
+                session_id: run.session_id.clone(),
+                working_dir: run.working_dir.clone(),
+                started_at: run.started_at,
+                tokens: (accumulated_tokens(&run.session_path) - run.start_tokens).max(0),
+                cancelling: run.cancel.is_cancelled(),
+            })
+            .collect();
+        list.sort_by_key(|run| run.started_at);
+        list
+    }
+
+    /// Whether a reply is being generated for `session_id`
+    pub async fn is_running(&self, session_id: &str) -> bool {
+        self.runs
+            .lock()
+            .await
+            .values()
+            .any(|run| run.session_id == session_id)
+    }
+
+    /// Cancel any reply being generated for `session_id` and wait, up to `wait`, for it to
+    /// wind down. Returns false if it is still running after that.
+    pub async fn cancel_session(&self, session_id: &str, wait: std::time::Duration) -> bool {
+        for run in self.runs.lock().await.values() {
+            if run.session_id == session_id {
+                run.cancel.cancel();
+            }
+        }
+        let deadline = tokio::time::Instant::now() + wait;
+        while self.is_running(session_id).await {
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        true
+    }
+
+    async fn cancel(&self, id: &str) -> bool {
+        match self.runs.lock().await.get(id) {
+            Some(run) => {
+                run.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn accumulated_tokens(session_path: &std::path::Path) -> i32 {
+    session::read_metadata(session_path)
+        .ok()
+        .and_then(|metadata| metadata.accumulated_total_tokens)
+        .unwrap_or(0)
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInfo {
+    id: String,
+    session_id: String,
+    working_dir: String,
+    /// Unix timestamp (seconds) when the run started
+    started_at: i64,
+    /// Tokens used by this run so far
+    tokens: i32,
+    /// Cancellation was requested and the run is winding down
+    cancelling: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunListResponse {
+    runs: Vec<RunInfo>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/runs",
+    responses(
+        (status = 200, description = "Replies currently being generated", body = RunListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Runs"
+)]
+async fn list_runs(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<RunListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    Ok(Json(RunListResponse {
+        runs: state.runs.list().await,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/runs/{id}/cancel",
+    params(
+        ("id" = String, Path, description = "Id of the run")
+    ),
+    responses(
+        (status = 204, description = "The run was asked to stop"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "No active run with this id")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Runs"
+)]
+async fn cancel_run(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    if state.runs.cancel(&id).await {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactListResponse {
+    artifacts: Vec<ArtifactInfo>,
+}
+
+/// The file of the finished run's session; runs are looked up by their session id
+fn run_session_file(id: &str) -> Result<PathBuf, StatusCode> {
+    let path = session::get_path(session::Identifier::Name(id.to_string()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(path)
+}
+
+#[utoipa::path(
+    get,
+    path = "/runs/{id}/artifacts",
+    params(
+        ("id" = String, Path, description = "Session id of the run")
+    ),
+    responses(
+        (status = 200, description = "Files collected from the run", body = ArtifactListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "No session with this id")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Runs"
+)]
+async fn list_artifacts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<ArtifactListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let artifacts = attachments::list_artifacts(&run_session_file(&id)?).map_err(|e| {
+        tracing::error!("Failed to list artifacts of run {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(ArtifactListResponse { artifacts }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/runs/{id}/artifacts/{name}",
+    params(
+        ("id" = String, Path, description = "Session id of the run"),
+        ("name" = String, Path, description = "Name of the artifact")
+    ),
+    responses(
+        (status = 200, description = "The artifact's contents", content_type = "application/octet-stream"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "No such run or artifact")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Runs"
+)]
+async fn download_artifact(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((id, name)): Path<(String, String)>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let (info, content) = attachments::read_artifact(&run_session_file(&id)?, &name)
+        .map_err(|e| {
+            tracing::error!("Failed to read artifact {} of run {}: {}", name, id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", info.name),
+            ),
+        ],
+        content,
+    ))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/runs", get(list_runs))
+        .route("/runs/{id}/cancel", post(cancel_run))
+        .route("/runs/{id}/artifacts", get(list_artifacts))
+        .route("/runs/{id}/artifacts/{name}", get(download_artifact))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_runs_are_listed_until_finished() {
+        let runs = ActiveRuns::default();
+        let cancel = CancellationToken::new();
+        let id = runs
+            .start(
+                "session",
+                "/tmp",
+                PathBuf::from("/tmp/does-not-exist.jsonl"),
+                cancel.clone(),
+            )
+            .await;
+
+        let listed = runs.list().await;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].session_id, "session");
+        assert_eq!(listed[0].tokens, 0);
+
+        assert!(runs.is_running("session").await);
+        assert!(!runs.is_running("other").await);
+
+        assert!(runs.cancel(&id).await);
+        assert!(cancel.is_cancelled());
+        assert!(runs.list().await[0].cancelling);
+
+        runs.finish(&id).await;
+        assert!(runs.list().await.is_empty());
+        assert!(!runs.is_running("session").await);
+        assert!(!runs.cancel(&id).await);
+    }
+}
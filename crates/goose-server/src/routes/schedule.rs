@@ -2,25 +2,38 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
     routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, Utc};
 
 use crate::routes::utils::verify_secret_key;
 use crate::state::AppState;
-use goose::scheduler::ScheduledJob;
+use goose::schedule_calendar::{self, BlackoutWindow};
+use goose::scheduler::{JobEnvironment, JobNode, ScheduledJob};
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
 pub struct CreateScheduleRequest {
     id: String,
     recipe_source: String,
+    /// Left empty for one-shot and chained jobs
+    #[serde(default)]
     cron: String,
     #[serde(default)]
     execution_mode: Option<String>, // "foreground" or "background"
+    /// Run once at this time; needs the sqlite scheduler
+    #[serde(default)]
+    run_at: Option<DateTime<Utc>>,
+    /// Run once these jobs have all succeeded; needs the sqlite scheduler
+    #[serde(default)]
+    depends_on: Vec<String>,
+    /// Working directory, environment, extensions and provider for the job's runs
+    #[serde(default)]
+    environment: JobEnvironment,
 }
 
 #[derive(Deserialize, Serialize, utoipa::ToSchema)]
@@ -33,6 +46,23 @@ pub struct ListSchedulesResponse {
     jobs: Vec<ScheduledJob>,
 }
 
+// Response for the graph endpoint: every job after the jobs it depends on
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ScheduleGraphResponse {
+    jobs: Vec<JobNode>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct ListBlackoutsResponse {
+    windows: Vec<BlackoutWindow>,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct CalendarQuery {
+    /// The GOOSE_SCHEDULE_ICAL_TOKEN, for calendar apps that can't send the secret key
+    token: Option<String>,
+}
+
 // Response for the kill endpoint
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct KillJobResponse {
@@ -127,6 +157,9 @@ async fn create_schedule(
         current_session_id: None,
         process_start_time: None,
         execution_mode: req.execution_mode.or(Some("background".to_string())), // Default to background
+        run_at: req.run_at,
+        depends_on: req.depends_on,
+        environment: req.environment,
     };
     scheduler
         .add_scheduled_job(job.clone())
@@ -136,12 +169,19 @@ async fn create_schedule(
             match e {
                 goose::scheduler::SchedulerError::JobNotFound(_) => StatusCode::NOT_FOUND,
                 goose::scheduler::SchedulerError::CronParseError(_) => StatusCode::BAD_REQUEST,
+                goose::scheduler::SchedulerError::InvalidJob(_) => StatusCode::BAD_REQUEST,
                 goose::scheduler::SchedulerError::RecipeLoadError(_) => StatusCode::BAD_REQUEST,
                 goose::scheduler::SchedulerError::JobIdExists(_) => StatusCode::CONFLICT,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             }
         })?;
-    Ok(Json(job))
+    Ok(Json(redacted(job)))
+}
+
+/// `job` as clients see it, with only the names of its environment variables
+fn redacted(mut job: ScheduledJob) -> ScheduledJob {
+    job.environment = job.environment.redacted();
+    job
 }
 
 #[utoipa::path(
@@ -169,7 +209,153 @@ async fn list_schedules(
         eprintln!("Error listing schedules: {:?}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
-    Ok(Json(ListSchedulesResponse { jobs }))
+    Ok(Json(ListSchedulesResponse {
+        jobs: jobs.into_iter().map(redacted).collect(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/schedule/graph",
+    responses(
+        (status = 200, description = "Scheduled jobs and their dependencies, in the order they run", body = ScheduleGraphResponse),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+async fn schedule_graph(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ScheduleGraphResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    let scheduler = state
+        .scheduler()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let jobs = scheduler.dependency_graph().await.map_err(|e| {
+        tracing::error!("Error building the schedule graph: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(ScheduleGraphResponse { jobs }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/schedule/calendar.ics",
+    params(CalendarQuery),
+    responses(
+        (status = 200, description = "iCalendar feed of upcoming runs, recent runs and blackout windows", content_type = "text/calendar"),
+        (status = 401, description = "Neither the secret key nor the feed token was given"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+async fn schedule_calendar_feed(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<CalendarQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if verify_secret_key(&headers, &state).is_err() {
+        let token = schedule_calendar::feed_token().ok_or(StatusCode::UNAUTHORIZED)?;
+        if query.token.as_deref() != Some(token.as_str()) {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+    let scheduler = state
+        .scheduler()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let ical = schedule_calendar::export_ical(scheduler.as_ref())
+        .await
+        .map_err(|e| {
+            tracing::error!("Error exporting the schedule calendar: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        ical,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/schedule/blackouts",
+    responses(
+        (status = 200, description = "Windows during which schedules don't run", body = ListBlackoutsResponse),
+    ),
+    tag = "schedule"
+)]
+async fn list_blackouts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ListBlackoutsResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    Ok(Json(ListBlackoutsResponse {
+        windows: schedule_calendar::blackouts(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/schedule/blackouts",
+    request_body = BlackoutWindow,
+    responses(
+        (status = 200, description = "Blackout window added", body = BlackoutWindow),
+        (status = 400, description = "The window ends before it starts or has no id"),
+        (status = 409, description = "A window with this id already exists"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+async fn create_blackout(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(window): Json<BlackoutWindow>,
+) -> Result<Json<BlackoutWindow>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    window.validate().map_err(|_| StatusCode::BAD_REQUEST)?;
+    if schedule_calendar::blackouts()
+        .iter()
+        .any(|existing| existing.id == window.id)
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+    schedule_calendar::add_blackout(window.clone()).map_err(|e| {
+        tracing::error!("Error adding blackout window: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(window))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/schedule/blackouts/{id}",
+    params(
+        ("id" = String, Path, description = "ID of the blackout window to remove")
+    ),
+    responses(
+        (status = 204, description = "Blackout window removed"),
+        (status = 404, description = "Blackout window not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "schedule"
+)]
+async fn delete_blackout(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    match schedule_calendar::remove_blackout(&id) {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            tracing::error!("Error removing blackout window '{}': {:?}", id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
 }
 
 #[utoipa::path(
@@ -440,7 +626,7 @@ async fn update_schedule(
         .find(|job| job.id == id)
         .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(Json(updated_job))
+    Ok(Json(redacted(updated_job)))
 }
 
 #[utoipa::path(
@@ -533,6 +719,13 @@ pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/schedule/create", post(create_schedule))
         .route("/schedule/list", get(list_schedules))
+        .route("/schedule/graph", get(schedule_graph))
+        .route("/schedule/calendar.ics", get(schedule_calendar_feed))
+        .route(
+            "/schedule/blackouts",
+            get(list_blackouts).post(create_blackout),
+        )
+        .route("/schedule/blackouts/{id}", delete(delete_blackout))
         .route("/schedule/delete/{id}", delete(delete_schedule)) // Corrected
         .route("/schedule/{id}", put(update_schedule))
         .route("/schedule/{id}/run_now", post(run_now_handler)) // Corrected
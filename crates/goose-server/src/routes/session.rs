@@ -1,22 +1,39 @@
-use super::utils::verify_secret_key;
+use super::reply::{stream_reply, ChatRequest, SseResponse};
+use super::utils::{request_locale, verify_secret_key};
 use chrono::DateTime;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::state::AppState;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
-    routing::{get, put},
+    routing::{delete, get, post, put},
     Json, Router,
 };
-use goose::conversation::message::Message;
+use goose::agents::PendingApproval;
+use goose::config::Config;
+use goose::conversation::message::{Message, MessageContent};
+use goose::conversation::Conversation;
+use goose::model::ModelConfig;
+use goose::providers;
 use goose::session;
+use goose::session::annotations;
+use goose::session::attachments;
+use goose::session::branches::{self, BranchReason, DiscardedBranch};
+use goose::session::events::{self, EventFilter, SessionEvent, SessionEventKind};
+use goose::session::handoff;
 use goose::session::info::{get_valid_sorted_sessions, SessionInfo, SortOrder};
-use goose::session::SessionMetadata;
+use goose::session::integrity::{self, IntegrityReport};
+use goose::session::manifest::{self, RunManifest};
+use goose::session::{Annotation, SessionLock, SessionMetadata};
+use goose::token_counter::create_async_token_counter;
+use rmcp::model::Role;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 #[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -32,8 +49,12 @@ pub struct SessionHistoryResponse {
     session_id: String,
     /// Session metadata containing creation time and other details
     metadata: SessionMetadata,
-    /// List of messages in the session conversation
+    /// List of messages in the session conversation. Assistant messages carry the provider
+    /// usage for the request that produced them.
     messages: Vec<Message>,
+    /// Estimated tokens each message takes up in the context window, in message order. Empty
+    /// if the tokenizer is unavailable.
+    message_tokens: Vec<usize>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -45,6 +66,134 @@ pub struct UpdateSessionMetadataRequest {
 
 const MAX_DESCRIPTION_LENGTH: usize = 200;
 
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAnnotationRequest {
+    /// Who is writing the annotation
+    author: Option<String>,
+    /// Free-text comment on the message
+    comment: Option<String>,
+    /// Numeric rating for the message
+    rating: Option<i32>,
+    /// Labels to attach to the message
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationListResponse {
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EditMessageRequest {
+    /// New text for the user message
+    content: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryRequest {
+    /// Model to use for this attempt instead of the current one
+    model: Option<String>,
+    /// Sampling temperature to use for this attempt
+    temperature: Option<f32>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestListResponse {
+    /// Manifest of each run in the session, oldest first
+    manifests: Vec<RunManifestEntry>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunManifestEntry {
+    manifest: RunManifest,
+    /// What changed since the run it replayed, or else since the run before it, one line per
+    /// difference
+    changes: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayRequest {
+    /// Run to replay; the session's most recent run if unset
+    run_id: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HandoffRequest {
+    /// The conversation as this client has it, including any reply still streaming
+    messages: Vec<Message>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HandoffResponse {
+    /// Single-use code for `goose session attach`
+    token: String,
+    /// Command that continues the session in the CLI
+    command: String,
+    /// Unix timestamp (seconds) after which the token no longer works
+    expires_at: i64,
+    /// Tool calls that will be asked about again where the session continues
+    pending_approvals: Vec<PendingApproval>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct EventsQuery {
+    /// Only events of this kind
+    kind: Option<SessionEventKind>,
+    /// Case-insensitive text to find in the tool call id, tool name or detail
+    q: Option<String>,
+    /// Only events at or after this Unix timestamp (milliseconds)
+    since: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EventListResponse {
+    /// Matching events, oldest first
+    events: Vec<SessionEvent>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachDocumentRequest {
+    /// The document to attach; relative paths are taken from the session's working directory
+    path: PathBuf,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachDocumentResponse {
+    /// Name the document's text is kept under with the session
+    name: String,
+    /// The message that hands the document to the model, as added to the session
+    message: Message,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FullToolResultResponse {
+    /// Id of the tool call
+    call_id: String,
+    /// The tool's complete output, before it was truncated for the context window
+    output: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchListResponse {
+    /// Branches discarded by edits, oldest first
+    branches: Vec<DiscardedBranch>,
+}
+
 #[derive(Serialize, ToSchema, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionInsights {
@@ -134,10 +283,22 @@ async fn get_session_history(
         }
     };
 
+    let message_tokens = match create_async_token_counter().await {
+        Ok(counter) => messages
+            .iter()
+            .map(|message| counter.count_message_tokens(message))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to count message tokens: {}", e);
+            Vec::new()
+        }
+    };
+
     Ok(Json(SessionHistoryResponse {
         session_id,
         metadata,
         messages: messages.messages().clone(),
+        message_tokens,
     }))
 }
 
@@ -310,6 +471,921 @@ async fn update_session_metadata(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/messages/{message_index}/annotations",
+    request_body = CreateAnnotationRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("message_index" = usize, Path, description = "Index of the message to annotate")
+    ),
+    responses(
+        (status = 200, description = "Annotation added successfully", body = Annotation),
+        (status = 400, description = "Bad request - Annotation has no comment, rating, or labels"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session or message not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Attach a comment, rating, or labels to a message
+async fn add_message_annotation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, message_index)): Path<(String, usize)>,
+    Json(request): Json<CreateAnnotationRequest>,
+) -> Result<Json<Annotation>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    if request.comment.is_none() && request.rating.is_none() && request.labels.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let messages = session::read_messages(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    if message_index >= messages.len() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let annotation = Annotation {
+        id: uuid::Uuid::new_v4().to_string(),
+        message_index,
+        author: request.author,
+        comment: request.comment,
+        rating: request.rating,
+        labels: request.labels,
+        created: chrono::Utc::now().timestamp(),
+    };
+    let saved = annotation.clone();
+    tokio::task::spawn_blocking(move || annotations::add_annotation(&session_path, saved))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| {
+            error!("Failed to save annotation: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(annotation))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/messages/{message_index}/annotations",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("message_index" = usize, Path, description = "Index of the message")
+    ),
+    responses(
+        (status = 200, description = "Annotations for the message", body = AnnotationListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// List annotations on a single message
+async fn get_message_annotations(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, message_index)): Path<(String, usize)>,
+) -> Result<Json<AnnotationListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let annotations = read_session_annotations(session_id)?
+        .into_iter()
+        .filter(|a| a.message_index == message_index)
+        .collect();
+
+    Ok(Json(AnnotationListResponse { annotations }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/annotations",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "All annotations in the session", body = AnnotationListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// List every annotation in a session
+async fn get_session_annotations(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<AnnotationListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let annotations = read_session_annotations(session_id)?;
+    Ok(Json(AnnotationListResponse { annotations }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/sessions/{session_id}/annotations/{annotation_id}",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("annotation_id" = String, Path, description = "Identifier of the annotation to remove")
+    ),
+    responses(
+        (status = 204, description = "Annotation removed"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session or annotation not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Remove an annotation
+async fn delete_session_annotation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, annotation_id)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let deleted = tokio::task::spawn_blocking(move || {
+        annotations::delete_annotation(&session_path, &annotation_id)
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    match deleted {
+        Ok(true) => Ok(StatusCode::NO_CONTENT),
+        Ok(false) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("Failed to delete annotation: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/messages/{message_index}/edit",
+    request_body = EditMessageRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("message_index" = usize, Path, description = "Index of the user message to replace")
+    ),
+    responses(
+        (status = 200, description = "Server-sent events for the regenerated reply, as from /reply", content_type = "text/event-stream"),
+        (status = 400, description = "Bad request - Empty content, or the message is not a user prompt"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session or message not found"),
+        (status = 409, description = "A reply is already being generated for this session"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Replace a user message, drop everything after it and generate a new reply
+async fn edit_message(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, message_index)): Path<(String, usize)>,
+    Json(request): Json<EditMessageRequest>,
+) -> Result<SseResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    if request.content.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let (_lock, session_path, metadata, conversation) =
+        load_idle_session(&state, &session_id).await?;
+    let messages = conversation.messages();
+    let original = messages.get(message_index).ok_or(StatusCode::NOT_FOUND)?;
+    if !is_prompt(original) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Only the text is edited; images and other content the prompt carried stay with it
+    let mut edited = messages[..message_index].to_vec();
+    let mut prompt = Message::user().with_text(request.content);
+    prompt.content.extend(
+        original
+            .content
+            .iter()
+            .filter(|content| !matches!(content, MessageContent::Text(_)))
+            .cloned(),
+    );
+    edited.push(prompt);
+    replace_tail(
+        &session_path,
+        &metadata,
+        messages,
+        message_index,
+        BranchReason::Edit,
+        &edited,
+    )?;
+
+    let request = ChatRequest {
+        messages: edited,
+        session_id: Some(session_id),
+        session_working_dir: metadata.working_dir.to_string_lossy().into_owned(),
+        scheduled_job_id: metadata.schedule_id,
+        client_id: None,
+        run_limits: Default::default(),
+        critique: None,
+        replay_of: None,
+    };
+    stream_reply(state, request, request_locale(&headers), None)
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/retry",
+    request_body = RetryRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Server-sent events for the regenerated reply, as from /reply", content_type = "text/event-stream"),
+        (status = 400, description = "Bad request - The session has no prompt to retry, or the overrides are invalid"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 409, description = "A reply is already being generated for this session"),
+        (status = 412, description = "No agent configured"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Generate the last assistant turn again, optionally with another model or temperature
+async fn retry_last_turn(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<RetryRequest>,
+) -> Result<SseResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let provider = if request.model.is_some() || request.temperature.is_some() {
+        let agent = state
+            .get_agent()
+            .await
+            .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+        let current = agent
+            .provider()
+            .await
+            .map_err(|_| StatusCode::PRECONDITION_FAILED)?
+            .get_model_config();
+        let provider_name: String = Config::global()
+            .get_param("GOOSE_PROVIDER")
+            .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+        let model_config =
+            ModelConfig::new(request.model.as_deref().unwrap_or(&current.model_name))
+                .map_err(|_| StatusCode::BAD_REQUEST)?
+                .with_temperature(request.temperature.or(current.temperature));
+        Some(providers::create(&provider_name, model_config).map_err(|_| StatusCode::BAD_REQUEST)?)
+    } else {
+        None
+    };
+
+    let (_lock, session_path, metadata, conversation) =
+        load_idle_session(&state, &session_id).await?;
+    let messages = conversation.messages();
+    let prompt_index = last_prompt_index(messages).ok_or(StatusCode::BAD_REQUEST)?;
+
+    // The earlier attempt is kept as a branch, the new one goes into the session
+    let kept = messages[..=prompt_index].to_vec();
+    replace_tail(
+        &session_path,
+        &metadata,
+        messages,
+        prompt_index + 1,
+        BranchReason::Retry,
+        &kept,
+    )?;
+
+    let request = ChatRequest {
+        messages: kept,
+        session_id: Some(session_id),
+        session_working_dir: metadata.working_dir.to_string_lossy().into_owned(),
+        scheduled_job_id: metadata.schedule_id,
+        client_id: None,
+        run_limits: Default::default(),
+        critique: None,
+        replay_of: None,
+    };
+    stream_reply(state, request, request_locale(&headers), provider)
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/integrity",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Whether the session's messages still match the checksum saved with them", body = IntegrityReport),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn verify_session_integrity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<IntegrityReport>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let report = integrity::verify_session(&session_path).map_err(|e| {
+        error!("Failed to verify session integrity: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/manifests",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Reproducibility manifest of each run in the session", body = ManifestListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn get_session_manifests(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<ManifestListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let manifests = manifest::read_manifests(&session_path).map_err(|e| {
+        error!("Failed to read run manifests: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let entries = manifests
+        .iter()
+        .enumerate()
+        .map(|(index, run)| {
+            let compared_to = run
+                .replay_of
+                .as_ref()
+                .and_then(|replayed| manifests.iter().find(|m| &m.run_id == replayed))
+                .or_else(|| index.checked_sub(1).map(|previous| &manifests[previous]));
+            RunManifestEntry {
+                manifest: run.clone(),
+                changes: compared_to
+                    .map(|earlier| earlier.differences(run))
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+    Ok(Json(ManifestListResponse { manifests: entries }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/replay",
+    request_body = ReplayRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Server-sent events for the replayed run, as from /reply", content_type = "text/event-stream"),
+        (status = 400, description = "Bad request - The manifest's provider or model can't be used"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session or run manifest not found"),
+        (status = 409, description = "A reply is in progress, or the prompts the run started from have since been edited"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Run a recorded run again from the same messages, with the provider, model and sampling
+// settings in its manifest. Everything after those messages is kept as a branch.
+async fn replay_run(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<ReplayRequest>,
+) -> Result<SseResponse, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let (_lock, session_path, metadata, conversation) =
+        load_idle_session(&state, &session_id).await?;
+    let manifests = manifest::read_manifests(&session_path).map_err(|e| {
+        error!("Failed to read run manifests: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let recorded = match &request.run_id {
+        Some(run_id) => manifests.into_iter().find(|m| &m.run_id == run_id),
+        None => manifests.into_iter().last(),
+    }
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    let messages = conversation.messages();
+    if recorded.message_count > messages.len()
+        || manifest::prompt_hashes(&messages[..recorded.message_count]) != recorded.prompt_hashes
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let model_config = ModelConfig::new(&recorded.model)
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .with_temperature(recorded.temperature)
+        .with_max_tokens(recorded.max_tokens)
+        .with_context_limit(recorded.context_limit)
+        .with_toolshim(recorded.toolshim)
+        .with_toolshim_model(recorded.toolshim_model.clone())
+        .with_seed(recorded.seed);
+    let provider =
+        providers::create(&recorded.provider, model_config).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let kept = messages[..recorded.message_count].to_vec();
+    replace_tail(
+        &session_path,
+        &metadata,
+        messages,
+        recorded.message_count,
+        BranchReason::Replay,
+        &kept,
+    )?;
+
+    let request = ChatRequest {
+        messages: kept,
+        session_id: Some(session_id),
+        session_working_dir: metadata.working_dir.to_string_lossy().into_owned(),
+        scheduled_job_id: metadata.schedule_id,
+        client_id: None,
+        run_limits: Default::default(),
+        critique: None,
+        replay_of: Some(recorded.run_id),
+    };
+    stream_reply(state, request, request_locale(&headers), Some(provider))
+}
+
+/// A message the user typed, as opposed to tool results sent back in the user role
+fn is_prompt(message: &Message) -> bool {
+    message.role == Role::User && !message.is_tool_response()
+}
+
+fn last_prompt_index(messages: &[Message]) -> Option<usize> {
+    messages.iter().rposition(is_prompt)
+}
+
+/// A lock on a session that exists and has no reply in progress, with its path, metadata
+/// and messages. Callers hold the lock until they have saved, so two edits can't both read
+/// the same messages and the later save silently drop the earlier one.
+async fn load_idle_session(
+    state: &AppState,
+    session_id: &str,
+) -> Result<(SessionLock, PathBuf, SessionMetadata, Conversation), StatusCode> {
+    let session_path = session::get_path(session::Identifier::Name(session_id.to_string()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let lock_path = session_path.clone();
+    let lock = tokio::task::spawn_blocking(move || session::lock_session(&lock_path))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| {
+            error!("Failed to lock session: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if state.runs.is_running(session_id).await {
+        return Err(StatusCode::CONFLICT);
+    }
+    let metadata = session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    let conversation = session::read_messages(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    Ok((lock, session_path, metadata, conversation))
+}
+
+/// Keep `messages[index..]` as a discarded branch and save `kept` as the session. The
+/// session is saved before regenerating, so an edit survives even if the reply fails.
+fn replace_tail(
+    session_path: &std::path::Path,
+    metadata: &SessionMetadata,
+    messages: &[Message],
+    index: usize,
+    reason: BranchReason,
+    kept: &[Message],
+) -> Result<(), StatusCode> {
+    if index < messages.len() {
+        let branch = DiscardedBranch {
+            id: uuid::Uuid::new_v4().to_string(),
+            message_index: index,
+            messages: messages[index..].to_vec(),
+            reason,
+            created: chrono::Utc::now().timestamp(),
+        };
+        branches::save_branch(session_path, branch).map_err(|e| {
+            error!("Failed to save discarded branch: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+
+    session::storage::save_messages_with_metadata(
+        session_path,
+        metadata,
+        &Conversation::new_unvalidated(kept.to_vec()),
+    )
+    .map_err(|e| {
+        error!("Failed to save session: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/branches",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Messages discarded by edits and retries in the session", body = BranchListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// List the branches discarded by edits and retries, for recovery
+async fn get_session_branches(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<Json<BranchListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let branches = branches::read_branches(&session_path).map_err(|e| {
+        error!("Failed to read discarded branches: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(BranchListResponse { branches }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/tool-results/{call_id}/full",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        ("call_id" = String, Path, description = "Id of the tool call whose result was truncated")
+    ),
+    responses(
+        (status = 200, description = "Full output of the tool call", body = FullToolResultResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found, or no full output was kept for the tool call"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Get the complete output of a tool call whose result was truncated in the conversation
+async fn get_full_tool_result(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path((session_id, call_id)): Path<(String, String)>,
+) -> Result<Json<FullToolResultResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let output = attachments::read_tool_result(&session_path, &call_id)
+        .map_err(|e| {
+            error!("Failed to read tool result: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(FullToolResultResponse { call_id, output }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/documents",
+    request_body = AttachDocumentRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "The document was converted to text, kept with the session and added to its conversation", body = AttachDocumentResponse),
+        (status = 400, description = "The file can't be read, or is too large or not a supported document"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Attach a document to a session: PDF and Office files are converted to text with page, sheet
+// and slide references within the document budget, anything else is read as text
+async fn attach_document(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<AttachDocumentRequest>,
+) -> Result<Json<AttachDocumentResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let lock_path = session_path.clone();
+    let _lock = tokio::task::spawn_blocking(move || session::lock_session(&lock_path))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(|e| {
+            error!("Failed to lock session: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let mut metadata = session::read_metadata(&session_path).map_err(|e| {
+        error!("Failed to read session metadata: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let document_path = metadata.working_dir.join(&request.path);
+
+    let read_path = document_path.clone();
+    let text = tokio::task::spawn_blocking(move || {
+        goose_mcp::computercontroller::document::read_attachment(&read_path)
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|e| {
+        info!("Failed to read document {}: {}", document_path.display(), e);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let name = attachments::save_document(&session_path, &document_path, &text).map_err(|e| {
+        error!("Failed to save document: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let message = Message::user().with_text(attachments::document_message(&request.path, &text));
+    let mut messages = session::read_messages(&session_path)
+        .map_err(|e| {
+            error!("Failed to read session messages: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .messages()
+        .clone();
+    messages.push(message.clone());
+    metadata.message_count = messages.len();
+    session::storage::save_messages_with_metadata(
+        &session_path,
+        &metadata,
+        &Conversation::new_unvalidated(messages),
+    )
+    .map_err(|e| {
+        error!("Failed to save session: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(AttachDocumentResponse { name, message }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/sessions/{session_id}/events",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session"),
+        EventsQuery
+    ),
+    responses(
+        (status = 200, description = "Session events retrieved successfully", body = EventListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Search the structured event log recorded alongside a session's messages
+async fn get_session_events(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Json<EventListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let filter = EventFilter {
+        kind: query.kind,
+        text: query.q.filter(|q| !q.is_empty()),
+        since: query.since,
+    };
+    let events = events::read_events(&session_path, &filter).map_err(|e| {
+        error!("Failed to read session events: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(EventListResponse { events }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/handoff",
+    request_body = HandoffRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Session saved and ready to continue elsewhere", body = HandoffResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 409, description = "The reply in progress could not be stopped"),
+        (status = 412, description = "Precondition failed - Agent not available"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Stop the session here and issue a short-lived token to continue it in another client
+async fn handoff_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<HandoffRequest>,
+) -> Result<Json<HandoffResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+
+    // Approvals belong to the reply in progress, so collect them before stopping it. The
+    // other client runs or denies them once the user answers there.
+    let pending_approvals = if state.runs.is_running(&session_id).await {
+        agent.list_pending_approvals(Some(&session_id))
+    } else {
+        Vec::new()
+    };
+    if !state
+        .runs
+        .cancel_session(&session_id, Duration::from_secs(10))
+        .await
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let metadata = session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    session::storage::save_messages_with_metadata(
+        &session_path,
+        &metadata,
+        &Conversation::new_unvalidated(request.messages),
+    )
+    .map_err(|e| {
+        error!("Failed to save session before handoff: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let handoff = handoff::create_handoff(
+        session_id,
+        session_path,
+        metadata.working_dir,
+        pending_approvals,
+    )
+    .map_err(|e| {
+        error!("Failed to create handoff: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let token = handoff::display_token(&handoff.token);
+    Ok(Json(HandoffResponse {
+        command: format!("goose session attach {}", token),
+        token,
+        expires_at: handoff.expires_at,
+        pending_approvals: handoff.pending_approvals,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{session_id}/end",
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 204, description = "Session ended"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 409, description = "The reply in progress could not be stopped"),
+        (status = 412, description = "Precondition failed - Agent not available")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+// Stop the session's reply and let its extensions stop the processes started in it, for when
+// the client closes the session
+async fn end_session(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let agent = state
+        .get_agent()
+        .await
+        .map_err(|_| StatusCode::PRECONDITION_FAILED)?;
+    if !state
+        .runs
+        .cancel_session(&session_id, Duration::from_secs(10))
+        .await
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+    agent.end_session(&session_id).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn read_session_annotations(session_id: String) -> Result<Vec<Annotation>, StatusCode> {
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    annotations::read_annotations(&session_path).map_err(|e| {
+        error!("Failed to read annotations: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
 // Configure routes for this module
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
@@ -320,6 +1396,41 @@ pub fn routes(state: Arc<AppState>) -> Router {
             "/sessions/{session_id}/metadata",
             put(update_session_metadata),
         )
+        .route(
+            "/sessions/{session_id}/messages/{message_index}/annotations",
+            get(get_message_annotations).post(add_message_annotation),
+        )
+        .route(
+            "/sessions/{session_id}/messages/{message_index}/edit",
+            post(edit_message),
+        )
+        .route("/sessions/{session_id}/retry", post(retry_last_turn))
+        .route(
+            "/sessions/{session_id}/integrity",
+            get(verify_session_integrity),
+        )
+        .route(
+            "/sessions/{session_id}/manifests",
+            get(get_session_manifests),
+        )
+        .route("/sessions/{session_id}/replay", post(replay_run))
+        .route("/sessions/{session_id}/branches", get(get_session_branches))
+        .route("/sessions/{session_id}/events", get(get_session_events))
+        .route("/sessions/{session_id}/handoff", post(handoff_session))
+        .route("/sessions/{session_id}/end", post(end_session))
+        .route("/sessions/{session_id}/documents", post(attach_document))
+        .route(
+            "/sessions/{session_id}/tool-results/{call_id}/full",
+            get(get_full_tool_result),
+        )
+        .route(
+            "/sessions/{session_id}/annotations",
+            get(get_session_annotations),
+        )
+        .route(
+            "/sessions/{session_id}/annotations/{annotation_id}",
+            delete(delete_session_annotation),
+        )
         .with_state(state)
 }
 
@@ -379,4 +1490,33 @@ mod tests {
         assert!(String::new().len() <= MAX_DESCRIPTION_LENGTH); // Empty string
         assert!("Short".len() <= MAX_DESCRIPTION_LENGTH); // Short string
     }
+
+    #[test]
+    fn test_create_annotation_request_deserialization() {
+        let json = r#"{"comment": "wrong file", "rating": 1, "labels": ["bug"]}"#;
+        let request: CreateAnnotationRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.comment.as_deref(), Some("wrong file"));
+        assert_eq!(request.rating, Some(1));
+        assert_eq!(request.labels, vec!["bug".to_string()]);
+        assert!(request.author.is_none());
+
+        let request: CreateAnnotationRequest = serde_json::from_str("{}").unwrap();
+        assert!(request.labels.is_empty());
+    }
+
+    #[test]
+    fn test_last_prompt_skips_tool_results() {
+        let messages = vec![
+            Message::user().with_text("list the files"),
+            Message::assistant().with_text("Listing them"),
+            Message::user().with_tool_response("call-1", Ok(vec![])),
+            Message::assistant().with_text("Here they are"),
+        ];
+        assert_eq!(last_prompt_index(&messages), Some(0));
+        assert_eq!(last_prompt_index(&messages[1..]), None);
+
+        let request: RetryRequest = serde_json::from_str(r#"{"temperature": 0.2}"#).unwrap();
+        assert!(request.model.is_none());
+        assert_eq!(request.temperature, Some(0.2));
+    }
 }
@@ -1,22 +1,402 @@
+use super::utils::{check_provider_configured, verify_secret_key};
 use crate::state::AppState;
-use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use goose::agents::ExtensionConfig;
 use goose::config::signup_openrouter::OpenRouterAuth;
-use goose::config::{configure_openrouter, Config};
-use serde::Serialize;
+use goose::config::{configure_openrouter, Config, ExtensionConfigManager, ExtensionEntry};
+use goose::conversation::message::Message;
+use goose::model::ModelConfig;
+use goose::providers::{create, providers};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Instant;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+/// Config key holding onboarding progress that cannot be derived from other config
+const SETUP_STATE_KEY: &str = "GOOSE_SETUP_STATE";
+
+/// Builtin extensions offered during onboarding, with their display names
+const STARTER_EXTENSIONS: &[(&str, &str)] = &[
+    ("developer", "Developer Tools"),
+    ("computercontroller", "Computer Controller"),
+    ("memory", "Memory"),
+    ("tutorial", "Tutorial"),
+];
+
+#[derive(Serialize, ToSchema)]
 pub struct SetupResponse {
     pub success: bool,
     pub message: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupStepKind {
+    DetectProvider,
+    TestConnectivity,
+    ChooseModel,
+    EnableExtensions,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupStep {
+    step: SetupStepKind,
+    done: bool,
+    detail: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupStatus {
+    /// Steps in the order onboarding walks through them
+    steps: Vec<SetupStep>,
+    complete: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedProvider {
+    name: String,
+    display_name: String,
+    /// Whether the provider's keys were found in the environment, config or keyring
+    configured: bool,
+    default_model: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedProvidersResponse {
+    providers: Vec<DetectedProvider>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupProviderRequest {
+    provider: String,
+    /// Defaults to the provider's recommended model
+    model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityResult {
+    provider: String,
+    model: String,
+    ok: bool,
+    error: Option<String>,
+    latency_ms: u64,
+    /// Unix timestamp (seconds)
+    tested_at: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupExtensionsRequest {
+    /// Starter extensions to enable; all of them when omitted
+    extensions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetupState {
+    connectivity: Option<ConnectivityResult>,
+}
+
+fn load_setup_state(config: &Config) -> SetupState {
+    config.get_param(SETUP_STATE_KEY).unwrap_or_default()
+}
+
+fn resolve_model(request: &SetupProviderRequest) -> Result<String, StatusCode> {
+    if let Some(model) = &request.model {
+        return Ok(model.clone());
+    }
+    providers()
+        .into_iter()
+        .find(|p| p.name == request.provider)
+        .map(|p| p.default_model)
+        .ok_or(StatusCode::BAD_REQUEST)
+}
+
+fn setup_status(config: &Config) -> SetupStatus {
+    let configured: Vec<_> = providers()
+        .into_iter()
+        .filter(check_provider_configured)
+        .map(|p| p.name)
+        .collect();
+    let provider = config.get_param::<String>("GOOSE_PROVIDER").ok();
+    let model = config.get_param::<String>("GOOSE_MODEL").ok();
+    let connectivity = load_setup_state(config).connectivity;
+    let enabled: Vec<_> = ExtensionConfigManager::get_all()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.enabled)
+        .map(|e| e.config.name())
+        .collect();
+
+    let steps = vec![
+        SetupStep {
+            step: SetupStepKind::DetectProvider,
+            done: !configured.is_empty(),
+            detail: (!configured.is_empty()).then(|| configured.join(", ")),
+        },
+        SetupStep {
+            step: SetupStepKind::TestConnectivity,
+            done: connectivity.as_ref().is_some_and(|c| c.ok),
+            detail: connectivity.map(|c| match c.error {
+                Some(error) => format!("{}/{}: {}", c.provider, c.model, error),
+                None => format!("{}/{}", c.provider, c.model),
+            }),
+        },
+        SetupStep {
+            step: SetupStepKind::ChooseModel,
+            done: provider.is_some() && model.is_some(),
+            detail: provider.zip(model).map(|(p, m)| format!("{}/{}", p, m)),
+        },
+        SetupStep {
+            step: SetupStepKind::EnableExtensions,
+            done: !enabled.is_empty(),
+            detail: (!enabled.is_empty()).then(|| enabled.join(", ")),
+        },
+    ];
+    let complete = steps.iter().all(|s| s.done);
+    SetupStatus { steps, complete }
+}
+
 pub fn routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/handle_openrouter", post(start_openrouter_setup))
+        .route("/setup/status", get(get_setup_status))
+        .route("/setup/providers", get(detect_providers))
+        .route("/setup/test", post(test_connectivity))
+        .route("/setup/model", post(choose_model))
+        .route("/setup/extensions", post(enable_starter_extensions))
         .with_state(state)
 }
 
+#[utoipa::path(
+    get,
+    path = "/setup/status",
+    responses(
+        (status = 200, description = "Progress through onboarding", body = SetupStatus),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Setup"
+)]
+async fn get_setup_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<SetupStatus>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    Ok(Json(setup_status(Config::global())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/setup/providers",
+    responses(
+        (status = 200, description = "Providers, with whether their keys were found", body = DetectedProvidersResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Setup"
+)]
+async fn detect_providers(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<DetectedProvidersResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let mut providers: Vec<_> = providers()
+        .into_iter()
+        .map(|p| DetectedProvider {
+            configured: check_provider_configured(&p),
+            name: p.name,
+            display_name: p.display_name,
+            default_model: p.default_model,
+        })
+        .collect();
+    // Configured providers first so onboarding can preselect one
+    providers.sort_by_key(|p| !p.configured);
+    Ok(Json(DetectedProvidersResponse { providers }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/setup/test",
+    request_body = SetupProviderRequest,
+    responses(
+        (status = 200, description = "Result of a one-message round trip to the provider", body = ConnectivityResult),
+        (status = 400, description = "Unknown provider"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Setup"
+)]
+async fn test_connectivity(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<SetupProviderRequest>,
+) -> Result<Json<ConnectivityResult>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let model = resolve_model(&request)?;
+    let start = Instant::now();
+    let outcome = match ModelConfig::new(&model)
+        .map_err(anyhow::Error::from)
+        .and_then(|config| create(&request.provider, config))
+    {
+        Ok(provider) => provider
+            .complete(
+                "You are a connectivity check.",
+                &[Message::user().with_text("Reply with OK.")],
+                &[],
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    let result = ConnectivityResult {
+        provider: request.provider,
+        model,
+        ok: outcome.is_ok(),
+        error: outcome.err(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        tested_at: chrono::Utc::now().timestamp(),
+    };
+
+    let config = Config::global();
+    let mut setup_state = load_setup_state(config);
+    setup_state.connectivity = Some(result.clone());
+    let value =
+        serde_json::to_value(&setup_state).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    config
+        .set_param(SETUP_STATE_KEY, value)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+#[utoipa::path(
+    post,
+    path = "/setup/model",
+    request_body = SetupProviderRequest,
+    responses(
+        (status = 200, description = "Default provider and model saved", body = SetupStatus),
+        (status = 400, description = "Unknown provider"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Setup"
+)]
+async fn choose_model(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<SetupProviderRequest>,
+) -> Result<Json<SetupStatus>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    if !providers().iter().any(|p| p.name == request.provider) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let model = resolve_model(&request)?;
+
+    let config = Config::global();
+    config
+        .set_param("GOOSE_PROVIDER", request.provider.into())
+        .and_then(|_| config.set_param("GOOSE_MODEL", model.into()))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(setup_status(config)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/setup/extensions",
+    request_body = SetupExtensionsRequest,
+    responses(
+        (status = 200, description = "Starter extensions enabled", body = SetupStatus),
+        (status = 400, description = "Not a starter extension"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Setup"
+)]
+async fn enable_starter_extensions(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<SetupExtensionsRequest>,
+) -> Result<Json<SetupStatus>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let requested = request.extensions.unwrap_or_else(|| {
+        STARTER_EXTENSIONS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    });
+    let mut entries = Vec::new();
+    for name in &requested {
+        let (name, display_name) = STARTER_EXTENSIONS
+            .iter()
+            .find(|(starter, _)| starter == name)
+            .ok_or(StatusCode::BAD_REQUEST)?;
+        entries.push(ExtensionEntry {
+            enabled: true,
+            config: ExtensionConfig::Builtin {
+                name: name.to_string(),
+                display_name: Some(display_name.to_string()),
+                description: None,
+                timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
+                bundled: Some(true),
+                available_tools: Vec::new(),
+            },
+        });
+    }
+
+    for entry in entries {
+        // Keep any existing configuration of the extension, just turn it on
+        let existing = ExtensionConfigManager::get_config_by_name(&entry.config.name())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let result = match existing {
+            Some(config) => ExtensionConfigManager::set_enabled(&config.key(), true),
+            None => ExtensionConfigManager::set(entry),
+        };
+        result.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+
+    Ok(Json(setup_status(Config::global())))
+}
+
+#[utoipa::path(
+    post,
+    path = "/handle_openrouter",
+    responses(
+        (status = 200, description = "Outcome of the OpenRouter sign-in flow", body = SetupResponse),
+        (status = 500, description = "Could not start the sign-in flow")
+    ),
+    tag = "Setup"
+)]
 async fn start_openrouter_setup(
     State(_state): State<Arc<AppState>>,
 ) -> Result<Json<SetupResponse>, StatusCode> {
@@ -58,3 +438,29 @@ async fn start_openrouter_setup(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_state_round_trip() {
+        let state = SetupState {
+            connectivity: Some(ConnectivityResult {
+                provider: "openai".to_string(),
+                model: "gpt-4o".to_string(),
+                ok: false,
+                error: Some("401 Unauthorized".to_string()),
+                latency_ms: 120,
+                tested_at: 0,
+            }),
+        };
+        let value = serde_json::to_value(&state).unwrap();
+        assert_eq!(value["connectivity"]["latencyMs"], 120);
+        let restored: SetupState = serde_json::from_value(value).unwrap();
+        assert!(!restored.connectivity.unwrap().ok);
+
+        let empty: SetupState = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(empty.connectivity.is_none());
+    }
+}
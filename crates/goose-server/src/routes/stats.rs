@@ -0,0 +1,76 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use goose::analytics::{Analytics, UsageStats};
+use goose::config::Config;
+use std::sync::Arc;
+
+fn analytics() -> Result<Analytics, StatusCode> {
+    Analytics::from_config(Config::global()).map_err(|e| {
+        tracing::error!("Failed to load analytics: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats",
+    responses(
+        (status = 200, description = "Usage recorded on this machine; empty unless GOOSE_ANALYTICS_ENABLED is set", body = UsageStats),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Analytics"
+)]
+async fn get_stats(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<UsageStats>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let stats = analytics()?.stats().map_err(|e| {
+        tracing::error!("Failed to read analytics: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(stats))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/stats",
+    responses(
+        (status = 204, description = "Locally recorded usage deleted"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Analytics"
+)]
+async fn clear_stats(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    analytics()?.clear().map_err(|e| {
+        tracing::error!("Failed to clear analytics: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/stats", get(get_stats).delete(clear_stats))
+        .with_state(state)
+}
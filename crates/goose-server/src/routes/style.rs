@@ -0,0 +1,116 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, put},
+    Json, Router,
+};
+use goose::session;
+use goose::style::{self, StylePreset};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleListResponse {
+    presets: Vec<StylePreset>,
+    /// The preset sessions use when they don't choose one
+    default_style: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetSessionStyleRequest {
+    /// Name of the preset, or null to go back to the configured default
+    style: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionStyleResponse {
+    /// The preset the session picked, or null when it uses the configured default
+    style: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/styles",
+    responses(
+        (status = 200, description = "Style presets available to sessions", body = StyleListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Style"
+)]
+async fn list_styles(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<StyleListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    Ok(Json(StyleListResponse {
+        presets: style::presets(),
+        default_style: style::default_style(),
+    }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/sessions/{session_id}/style",
+    request_body = SetSessionStyleRequest,
+    params(
+        ("session_id" = String, Path, description = "Unique identifier for the session")
+    ),
+    responses(
+        (status = 200, description = "Style switched; it applies from the session's next reply", body = SessionStyleResponse),
+        (status = 400, description = "Unknown style preset"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Session not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Session Management"
+)]
+async fn set_session_style(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<SetSessionStyleRequest>,
+) -> Result<Json<SessionStyleResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+    if let Some(name) = &request.style {
+        if style::find_preset(name).is_none() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let session_path = session::get_path(session::Identifier::Name(session_id))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !session_path.exists() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let mut metadata = session::read_metadata(&session_path).map_err(|e| {
+        tracing::error!("Failed to read session metadata: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    metadata.style = request.style;
+    session::update_metadata(&session_path, &metadata)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to update session style: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(SessionStyleResponse {
+        style: metadata.style,
+    }))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/styles", get(list_styles))
+        .route("/sessions/{session_id}/style", put(set_session_style))
+        .with_state(state)
+}
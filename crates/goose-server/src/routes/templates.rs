@@ -0,0 +1,179 @@
+use super::utils::verify_secret_key;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use goose::config::conversation_templates::list_templates;
+use goose::config::ConversationTemplate;
+use goose::conversation::message::Message;
+use goose::conversation::Conversation;
+use goose::session;
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateListResponse {
+    templates: Vec<ConversationTemplate>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StartTemplateResponse {
+    /// The newly created session
+    session_id: String,
+    /// The opening user message stored in the session, with context attached
+    prompt: String,
+    /// Extensions the template asks for. Starting a template leaves the running agent as it
+    /// is; the client enables these for the session it opens.
+    extensions: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/templates",
+    responses(
+        (status = 200, description = "Saved conversation templates", body = TemplateListResponse),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Conversation Templates"
+)]
+async fn get_templates(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<TemplateListResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let templates = list_templates().map_err(|e| {
+        tracing::error!("Failed to list templates: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(TemplateListResponse { templates }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/templates",
+    request_body = ConversationTemplate,
+    responses(
+        (status = 200, description = "Template saved", body = ConversationTemplate),
+        (status = 400, description = "Bad request - Invalid template name"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Conversation Templates"
+)]
+async fn save_template(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(template): Json<ConversationTemplate>,
+) -> Result<Json<ConversationTemplate>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    template.save().map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(Json(template))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/templates/{name}",
+    params(
+        ("name" = String, Path, description = "Name of the template to remove")
+    ),
+    responses(
+        (status = 204, description = "Template removed"),
+        (status = 400, description = "Bad request - Invalid template name"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Conversation Templates"
+)]
+async fn remove_template(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    ConversationTemplate::remove(&name).map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/templates/{name}/start",
+    params(
+        ("name" = String, Path, description = "Name of the template to start")
+    ),
+    responses(
+        (status = 200, description = "Session created from the template", body = StartTemplateResponse),
+        (status = 400, description = "Bad request - Missing context file or unconfigured extension"),
+        (status = 401, description = "Unauthorized - Invalid or missing API key"),
+        (status = 404, description = "Template not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("api_key" = [])
+    ),
+    tag = "Conversation Templates"
+)]
+async fn start_template(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<StartTemplateResponse>, StatusCode> {
+    verify_secret_key(&headers, &state)?;
+
+    let template = ConversationTemplate::load(&name).map_err(|_| StatusCode::NOT_FOUND)?;
+    let prompt = template
+        .render_prompt_with(goose_mcp::computercontroller::document::read_attachment)
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let extensions = template
+        .extension_configs()
+        .map_err(|_| StatusCode::BAD_REQUEST)?
+        .iter()
+        .map(|extension| extension.name())
+        .collect();
+
+    let session_id = session::generate_session_id();
+    let session_path = session::get_path(session::Identifier::Name(session_id.clone()))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let messages = Conversation::new_unvalidated(vec![Message::user().with_text(prompt.clone())]);
+    let metadata = session::SessionMetadata {
+        description: template.description.clone().unwrap_or(template.name),
+        message_count: messages.len(),
+        ..Default::default()
+    };
+    session::storage::save_messages_with_metadata(&session_path, &metadata, &messages).map_err(
+        |e| {
+            tracing::error!("Failed to create session from template: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        },
+    )?;
+
+    Ok(Json(StartTemplateResponse {
+        session_id,
+        prompt,
+        extensions,
+    }))
+}
+
+pub fn routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/templates", get(get_templates).post(save_template))
+        .route("/templates/{name}", delete(remove_template))
+        .route("/templates/{name}/start", post(start_template))
+        .with_state(state)
+}
@@ -1,5 +1,6 @@
 use crate::state::AppState;
 use goose::config::Config;
+use goose::i18n;
 use goose::providers::base::{ConfigKey, ProviderMetadata};
 use http::{HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -37,6 +38,16 @@ pub fn verify_secret_key(headers: &HeaderMap, state: &AppState) -> Result<Status
     }
 }
 
+/// Locale for error messages in a response: the request's `Accept-Language` if goose has a
+/// matching translation, otherwise the server's own locale
+pub fn request_locale(headers: &HeaderMap) -> &'static str {
+    headers
+        .get(http::header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(i18n::negotiate_locale)
+        .unwrap_or_else(i18n::current_locale)
+}
+
 /// Inspects a configuration key to determine if it's set, its location, and value (for non-secret keys)
 #[allow(dead_code)]
 pub fn inspect_key(key_name: &str, is_secret: bool) -> Result<KeyInfo, Box<dyn Error>> {
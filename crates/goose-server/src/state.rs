@@ -1,3 +1,7 @@
+use crate::routes::a2a::A2aTasks;
+use crate::routes::live::LiveSessions;
+use crate::routes::run_queue::RunQueue;
+use crate::routes::runs::ActiveRuns;
 use goose::agents::Agent;
 use goose::scheduler_trait::SchedulerTrait;
 use std::sync::Arc;
@@ -10,6 +14,10 @@ pub struct AppState {
     agent: Option<AgentRef>,
     pub secret_key: String,
     pub scheduler: Arc<Mutex<Option<Arc<dyn SchedulerTrait>>>>,
+    pub a2a_tasks: Arc<A2aTasks>,
+    pub runs: Arc<ActiveRuns>,
+    pub run_queue: Arc<RunQueue>,
+    pub live: Arc<LiveSessions>,
 }
 
 impl AppState {
@@ -18,6 +26,10 @@ impl AppState {
             agent: Some(agent.clone()),
             secret_key,
             scheduler: Arc::new(Mutex::new(None)),
+            a2a_tasks: Arc::new(A2aTasks::default()),
+            runs: Arc::new(ActiveRuns::default()),
+            run_queue: Arc::new(RunQueue::from_config()),
+            live: Arc::new(LiveSessions::default()),
         })
     }
 
@@ -0,0 +1,98 @@
+//! Built-in HTTPS for deployments without a reverse proxy in front of goosed.
+//!
+//! With a certificate and key configured the server speaks TLS directly. Adding a client CA
+//! turns on mutual TLS: connections must present a certificate signed by that CA before any
+//! request reaches the routes (the X-Secret-Key check still applies on top).
+
+use anyhow::{anyhow, Context, Result};
+use axum_server::tls_rustls::RustlsConfig;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct TlsSettings {
+    /// PEM certificate chain presented to clients
+    pub cert: PathBuf,
+    /// PEM private key for the certificate
+    pub key: PathBuf,
+    /// PEM CA bundle used to verify client certificates; enables mutual TLS when set
+    pub client_ca: Option<PathBuf>,
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("reading certificates from {}", path.display()))?;
+    if certs.is_empty() {
+        return Err(anyhow!("no certificates found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .with_context(|| format!("reading private key from {}", path.display()))?
+        .ok_or_else(|| anyhow!("no private key found in {}", path.display()))
+}
+
+impl TlsSettings {
+    pub fn server_config(&self) -> Result<ServerConfig> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?;
+
+        let builder = match &self.client_ca {
+            Some(ca) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(ca)? {
+                    roots.add(cert)?;
+                }
+                let verifier =
+                    WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                        .build()?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        let mut config = builder.with_single_cert(load_certs(&self.cert)?, load_key(&self.key)?)?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
+
+    pub fn rustls_config(&self) -> Result<RustlsConfig> {
+        Ok(RustlsConfig::from_config(Arc::new(self.server_config()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_missing_files_are_reported() {
+        let settings = TlsSettings {
+            cert: PathBuf::from("/nonexistent/cert.pem"),
+            key: PathBuf::from("/nonexistent/key.pem"),
+            client_ca: None,
+        };
+        let err = settings.server_config().unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/cert.pem"));
+    }
+
+    #[test]
+    fn test_empty_pem_is_rejected() {
+        let mut cert = tempfile::NamedTempFile::new().unwrap();
+        writeln!(cert, "not a certificate").unwrap();
+        let err = load_certs(cert.path()).unwrap_err();
+        assert!(err.to_string().contains("no certificates found"));
+    }
+}
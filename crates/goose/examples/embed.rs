@@ -0,0 +1,52 @@
+use futures::StreamExt;
+use goose::agents::{AgentEvent, ExtensionConfig};
+use goose::config::{DEFAULT_EXTENSION_DESCRIPTION, DEFAULT_EXTENSION_TIMEOUT};
+use goose::conversation::message::Message;
+use goose::embed::{GooseBuilder, SessionStorage};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Uses the provider and model from `goose configure`; call .provider_name() or
+    // .provider() to choose one here instead
+    let goose = GooseBuilder::new()
+        .extension(
+            ExtensionConfig::stdio(
+                "developer",
+                "./target/debug/goose",
+                DEFAULT_EXTENSION_DESCRIPTION,
+                DEFAULT_EXTENSION_TIMEOUT,
+            )
+            .with_args(vec!["mcp", "developer"]),
+        )
+        .instructions("Keep answers short.")
+        .storage(SessionStorage::Named("embed-example".to_string()))
+        .build()
+        .await?;
+
+    // Wait for the whole reply
+    let reply = goose.run("What files are in this directory?").await?;
+    println!("{}\n", reply.as_concat_text());
+
+    // Or stream it; the conversation continues from the previous message
+    let mut events = goose
+        .stream(
+            Message::user().with_text("Which of them is the largest?"),
+            None,
+        )
+        .await?;
+    while let Some(event) = events.next().await {
+        if let AgentEvent::Message(message) = event? {
+            for content in &message.content {
+                if let Some(text) = content.as_text() {
+                    print!("{}", text);
+                } else if let Some(request) = content.as_tool_request() {
+                    if let Ok(call) = &request.tool_call {
+                        println!("\n[calling {}]", call.name);
+                    }
+                }
+            }
+        }
+    }
+    println!();
+    Ok(())
+}
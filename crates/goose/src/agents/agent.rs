@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -8,13 +9,19 @@ use futures::stream::BoxStream;
 use futures::{stream, FutureExt, Stream, StreamExt, TryStreamExt};
 use uuid::Uuid;
 
+use crate::agents::approvals::{ApprovalRegistry, PendingApproval};
+use crate::agents::critique;
+use crate::agents::elicitation::ElicitationContext;
 use crate::agents::extension::{ExtensionConfig, ExtensionError, ExtensionResult, ToolInfo};
 use crate::agents::extension_manager::{get_parameter_names, ExtensionManager};
 use crate::agents::final_output_tool::{FINAL_OUTPUT_CONTINUATION_MESSAGE, FINAL_OUTPUT_TOOL_NAME};
+use crate::agents::hooks::{self, HookEvent, HookKind};
+use crate::agents::interceptor::{Interceptor, ProviderRequest};
+use crate::agents::loop_guard::{LoopAction, LoopGuard, LoopGuardConfig};
 use crate::agents::platform_tools::{
-    PLATFORM_LIST_RESOURCES_TOOL_NAME, PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME,
-    PLATFORM_MANAGE_SCHEDULE_TOOL_NAME, PLATFORM_READ_RESOURCE_TOOL_NAME,
-    PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME,
+    PLATFORM_GENERATE_IMAGE_TOOL_NAME, PLATFORM_LIST_RESOURCES_TOOL_NAME,
+    PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME, PLATFORM_MANAGE_SCHEDULE_TOOL_NAME,
+    PLATFORM_READ_RESOURCE_TOOL_NAME, PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME,
 };
 use crate::agents::prompt_manager::PromptManager;
 use crate::agents::recipe_tools::dynamic_task_tools::{
@@ -22,36 +29,47 @@ use crate::agents::recipe_tools::dynamic_task_tools::{
 };
 use crate::agents::retry::{RetryManager, RetryResult};
 use crate::agents::router_tools::ROUTER_LLM_SEARCH_TOOL_NAME;
+use crate::agents::sampling::SamplingContext;
 use crate::agents::sub_recipe_manager::SubRecipeManager;
 use crate::agents::subagent_execution_tool::subagent_execute_task_tool::{
     self, SUBAGENT_EXECUTE_TASK_TOOL_NAME,
 };
 use crate::agents::subagent_execution_tool::tasks_manager::TasksManager;
+use crate::agents::tool_recorder::ToolRecorder;
 use crate::agents::tool_route_manager::ToolRouteManager;
 use crate::agents::tool_router_index_manager::ToolRouterIndexManager;
-use crate::agents::types::SessionConfig;
 use crate::agents::types::{FrontendTool, ToolResultReceiver};
+use crate::agents::types::{RunLimits, SessionConfig, ToolCallContext};
+use crate::analytics::{self, AnalyticsEvent};
+use crate::anomaly::{self, AnomalyConfig, AnomalyMonitor};
 use crate::config::{Config, ExtensionConfigManager, PermissionManager};
 use crate::context_mgmt::auto_compact;
 use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
+use crate::exemplars;
+use crate::notifications::{self, NotificationKind};
+use crate::offline;
 use crate::permission::permission_judge::{check_tool_permissions, PermissionCheckResult};
-use crate::permission::PermissionConfirmation;
+use crate::permission::{Permission, PermissionConfirmation};
 use crate::providers::base::Provider;
 use crate::providers::errors::ProviderError;
+use crate::providers::image_generation::ImageGenerationProvider;
 use crate::recipe::{Author, Recipe, Response, Settings, SubRecipe};
 use crate::scheduler_trait::SchedulerTrait;
 use crate::session;
+use crate::session::events::{SessionEvent, SessionEventKind, TokenUsage};
 use crate::tool_monitor::{ToolCall, ToolMonitor};
-use crate::utils::is_token_cancelled;
+use crate::tool_stats;
+use crate::utils::{is_token_cancelled, safe_truncate};
 use mcp_core::ToolResult;
 use regex::Regex;
 use rmcp::model::{
-    Content, ErrorCode, ErrorData, GetPromptResult, Prompt, ServerNotification, Tool,
+    Content, ErrorCode, ErrorData, GetPromptResult, JsonObject, Prompt, Role, ServerNotification,
+    Tool,
 };
 use serde_json::Value;
-use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, warn};
 
 use super::final_output_tool::FinalOutputTool;
 use super::platform_tools;
@@ -62,9 +80,12 @@ use crate::agents::todo_tools::{
     TODO_READ_TOOL_NAME,
     TODO_WRITE_TOOL_NAME,
 };
-use crate::conversation::message::{Message, ToolRequest};
+use crate::conversation::message::{Message, MessageContent, ToolRequest};
 
 const DEFAULT_MAX_TURNS: u32 = 1000;
+/// Config keys for the run limits used when neither the session nor its recipe sets them
+const MAX_RUN_SECONDS_KEY: &str = "GOOSE_MAX_RUN_SECONDS";
+const MAX_TOOL_CALLS_KEY: &str = "GOOSE_MAX_TOOL_CALLS";
 
 /// Context needed for the reply function
 pub struct ReplyContext {
@@ -72,6 +93,8 @@ pub struct ReplyContext {
     pub tools: Vec<Tool>,
     pub toolshim_tools: Vec<Tool>,
     pub system_prompt: String,
+    /// Style preset the session picked, if any
+    pub style: Option<String>,
     pub goose_mode: String,
     pub initial_messages: Vec<Message>,
     pub config: &'static Config,
@@ -85,6 +108,18 @@ pub struct ToolCategorizeResult {
     pub regular_tools: HashSet<String>,
 }
 
+tokio::task_local! {
+    /// Provider of the reply running in this task, in place of the agent's own
+    static REPLY_PROVIDER: Arc<dyn Provider>;
+}
+
+/// Run `future` with `provider` answering every request the agent makes from this task, so one
+/// reply can use another model without changing the provider other sessions of the agent use.
+/// Work the reply hands to other tasks, such as subagents, uses the agent's own provider.
+pub async fn with_reply_provider<F: Future>(provider: Arc<dyn Provider>, future: F) -> F::Output {
+    REPLY_PROVIDER.scope(provider, future).await
+}
+
 /// The main goose Agent
 pub struct Agent {
     pub(super) provider: Mutex<Option<Arc<dyn Provider>>>,
@@ -104,6 +139,21 @@ pub struct Agent {
     pub(super) scheduler_service: Mutex<Option<Arc<dyn SchedulerTrait>>>,
     pub(super) retry_manager: RetryManager,
     pub(super) todo_list: Arc<Mutex<String>>,
+    pub(super) tool_recorder: Mutex<Option<Arc<ToolRecorder>>>,
+    pub(super) approvals: ApprovalRegistry,
+    /// Sampling requests of the extensions, which wait for approval alongside tool calls
+    pub(super) sampling: Arc<SamplingContext>,
+    /// Extensions' requests for input from the user, which wait alongside tool calls too
+    pub(super) elicitation: Arc<ElicitationContext>,
+    /// Working directory of each session that has replied, until it ends
+    pub(super) started_sessions: Mutex<HashMap<String, PathBuf>>,
+    /// The system prompt each session's latest reply started with, until it is taken
+    pub(super) reply_system_prompts: Mutex<HashMap<String, String>>,
+    /// The exemplars section of each session's system prompt, matched once against its task
+    /// and kept until the session ends
+    pub(super) exemplar_sections: Mutex<HashMap<String, String>>,
+    pub(super) interceptors: Mutex<Vec<Arc<dyn Interceptor>>>,
+    pub(super) image_provider: Mutex<Option<Arc<dyn ImageGenerationProvider>>>,
 }
 
 #[derive(Clone, Debug)]
@@ -172,9 +222,13 @@ impl Agent {
         let tool_monitor = Arc::new(Mutex::new(None));
         let retry_manager = RetryManager::with_tool_monitor(tool_monitor.clone());
 
+        let extension_manager = ExtensionManager::new();
+        let sampling = Arc::clone(extension_manager.sampling());
+        let elicitation = Arc::clone(extension_manager.elicitation());
+
         Self {
             provider: Mutex::new(None),
-            extension_manager: Arc::new(RwLock::new(ExtensionManager::new())),
+            extension_manager: Arc::new(RwLock::new(extension_manager)),
             sub_recipe_manager: Mutex::new(SubRecipeManager::new()),
             tasks_manager: TasksManager::new(),
             final_output_tool: Arc::new(Mutex::new(None)),
@@ -190,6 +244,15 @@ impl Agent {
             scheduler_service: Mutex::new(None),
             retry_manager,
             todo_list: Arc::new(Mutex::new(String::new())),
+            tool_recorder: Mutex::new(None),
+            approvals: ApprovalRegistry::new(),
+            sampling,
+            elicitation,
+            started_sessions: Mutex::new(HashMap::new()),
+            reply_system_prompts: Mutex::new(HashMap::new()),
+            exemplar_sections: Mutex::new(HashMap::new()),
+            interceptors: Mutex::new(Vec::new()),
+            image_provider: Mutex::new(None),
         }
     }
 
@@ -198,6 +261,45 @@ impl Agent {
         *tool_monitor = Some(ToolMonitor::new(max_repetitions));
     }
 
+    /// Record extension tool calls to, or replay them from, a recording file
+    pub async fn set_tool_recorder(&self, recorder: Option<Arc<ToolRecorder>>) {
+        *self.tool_recorder.lock().await = recorder;
+    }
+
+    /// Register an interceptor; it runs after any already registered
+    pub async fn add_interceptor(&self, interceptor: Arc<dyn Interceptor>) {
+        self.interceptors.lock().await.push(interceptor);
+    }
+
+    pub(super) async fn intercept_provider_request(
+        &self,
+        system_prompt: &mut String,
+        messages: &mut Vec<Message>,
+    ) -> Result<()> {
+        let interceptors = self.interceptors.lock().await.clone();
+        for interceptor in interceptors {
+            interceptor
+                .before_provider_call(ProviderRequest {
+                    system_prompt: &mut *system_prompt,
+                    messages: &mut *messages,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn intercept_tool_result(
+        &self,
+        request: &ToolRequest,
+        result: &mut ToolResult<Vec<Content>>,
+    ) -> Result<()> {
+        let interceptors = self.interceptors.lock().await.clone();
+        for interceptor in interceptors {
+            interceptor.after_tool_result(request, result).await?;
+        }
+        Ok(())
+    }
+
     /// Reset the retry attempts counter to 0
     pub async fn reset_retry_attempts(&self) {
         self.retry_manager.reset_attempts().await;
@@ -253,7 +355,15 @@ impl Agent {
         let initial_messages = conversation.messages().clone();
         let config = Config::global();
 
-        let (tools, toolshim_tools, system_prompt) = self.prepare_tools_and_prompt().await?;
+        // Read on every reply so a style switched through the API applies right away
+        let style = session.as_ref().and_then(|session_config| {
+            session::storage::get_path(session_config.id.clone())
+                .and_then(|path| session::storage::read_metadata(&path))
+                .ok()
+                .and_then(|metadata| metadata.style)
+        });
+        let (tools, toolshim_tools, system_prompt) =
+            self.prepare_tools_and_prompt(style.as_deref()).await?;
         let goose_mode = Self::determine_goose_mode(session.as_ref(), config);
 
         Ok(ReplyContext {
@@ -261,6 +371,7 @@ impl Agent {
             tools,
             toolshim_tools,
             system_prompt,
+            style,
             goose_mode,
             initial_messages,
             config,
@@ -292,6 +403,7 @@ impl Agent {
         permission_check_result: &PermissionCheckResult,
         message_tool_response: Arc<Mutex<Message>>,
         cancel_token: Option<tokio_util::sync::CancellationToken>,
+        context: &ToolCallContext,
     ) -> Result<Vec<(String, ToolStream)>> {
         let mut tool_futures: Vec<(String, ToolStream)> = Vec::new();
 
@@ -299,7 +411,12 @@ impl Agent {
         for request in &permission_check_result.approved {
             if let Ok(tool_call) = request.tool_call.clone() {
                 let (req_id, tool_result) = self
-                    .dispatch_tool_call(tool_call, request.id.clone(), cancel_token.clone())
+                    .dispatch_tool_call_in_session(
+                        tool_call,
+                        request.id.clone(),
+                        cancel_token.clone(),
+                        context,
+                    )
                     .await;
 
                 tool_futures.push((
@@ -341,8 +458,12 @@ impl Agent {
         self.tool_route_manager.disable_router_for_recipe().await;
     }
 
-    /// Get a reference count clone to the provider
+    /// Get a reference count clone to the provider, or to the one the current reply runs with
+    /// when it was given its own through `with_reply_provider`
     pub async fn provider(&self) -> Result<Arc<dyn Provider>, anyhow::Error> {
+        if let Ok(provider) = REPLY_PROVIDER.try_with(Arc::clone) {
+            return Ok(provider);
+        }
         match &*self.provider.lock().await {
             Some(provider) => Ok(Arc::clone(provider)),
             None => Err(anyhow!("Provider not set")),
@@ -373,12 +494,29 @@ impl Agent {
     }
 
     /// Dispatch a single tool call to the appropriate client
-    #[instrument(skip(self, tool_call, request_id), fields(input, output))]
     pub async fn dispatch_tool_call(
         &self,
         tool_call: mcp_core::tool::ToolCall,
         request_id: String,
         cancellation_token: Option<CancellationToken>,
+    ) -> (String, Result<ToolCallResult, ErrorData>) {
+        self.dispatch_tool_call_in_session(
+            tool_call,
+            request_id,
+            cancellation_token,
+            &ToolCallContext::default(),
+        )
+        .await
+    }
+
+    /// Dispatch a single tool call made in the session `context` describes
+    #[instrument(skip(self, tool_call, request_id, context), fields(input, output))]
+    pub async fn dispatch_tool_call_in_session(
+        &self,
+        tool_call: mcp_core::tool::ToolCall,
+        request_id: String,
+        cancellation_token: Option<CancellationToken>,
+        context: &ToolCallContext,
     ) -> (String, Result<ToolCallResult, ErrorData>) {
         // Check if this tool call should be allowed based on repetition monitoring
         if let Some(monitor) = self.tool_monitor.lock().await.as_mut() {
@@ -403,6 +541,13 @@ impl Agent {
             return (request_id, Ok(ToolCallResult::from(result)));
         }
 
+        if tool_call.name == PLATFORM_GENERATE_IMAGE_TOOL_NAME {
+            let result = self
+                .handle_generate_image(tool_call.arguments, context)
+                .await;
+            return (request_id, Ok(ToolCallResult::from(result)));
+        }
+
         if tool_call.name == PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME {
             let extension_name = tool_call
                 .arguments
@@ -439,6 +584,8 @@ impl Agent {
             };
         }
 
+        let started = std::time::Instant::now();
+        let tool_name = tool_call.name.clone();
         let extension_manager = self.extension_manager.read().await;
         let sub_recipe_manager = self.sub_recipe_manager.lock().await;
         let result: ToolCallResult = if sub_recipe_manager.is_sub_recipe_tool(&tool_call.name) {
@@ -541,27 +688,45 @@ impl Agent {
                 Err(e) => return (request_id, Err(e)),
             }
         } else {
-            // Clone the result to ensure no references to extension_manager are returned
-            let result = extension_manager
-                .dispatch_tool_call(tool_call.clone(), cancellation_token.unwrap_or_default())
-                .await;
-            result.unwrap_or_else(|e| {
-                ToolCallResult::from(Err(ErrorData::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    e.to_string(),
-                    None,
-                )))
-            })
+            let recorder = self.tool_recorder.lock().await.clone();
+            match recorder {
+                Some(recorder) if recorder.is_replaying() => {
+                    ToolCallResult::from(recorder.replay(&tool_call))
+                }
+                recorder => {
+                    // Clone the result to ensure no references to extension_manager are returned
+                    let result = extension_manager
+                        .dispatch_tool_call_in_session(
+                            tool_call.clone(),
+                            context.session_id.as_deref(),
+                            cancellation_token.unwrap_or_default(),
+                        )
+                        .await
+                        .unwrap_or_else(|e| {
+                            ToolCallResult::from(Err(ErrorData::new(
+                                ErrorCode::INTERNAL_ERROR,
+                                e.to_string(),
+                                None,
+                            )))
+                        });
+                    match recorder {
+                        Some(recorder) => recorder.record(&tool_call, result),
+                        None => result,
+                    }
+                }
+            }
         };
 
+        // Latency runs until the tool's result is in, which may be long after dispatch
+        let timed_result = result.result.inspect(move |output| {
+            tool_stats::record(&tool_name, started.elapsed(), output.is_ok());
+        });
         (
             request_id,
             Ok(ToolCallResult {
                 notification_stream: result.notification_stream,
                 result: Box::new(
-                    result
-                        .result
-                        .map(super::large_response_handler::process_tool_response),
+                    timed_result.map(super::large_response_handler::process_tool_response),
                 ),
             }),
         )
@@ -757,6 +922,10 @@ impl Agent {
                 platform_tools::manage_schedule_tool(),
             ]);
 
+            if self.image_generation_available().await {
+                prefixed_tools.push(platform_tools::generate_image_tool());
+            }
+
             // Add task planner tools
             // TODO: Re-enable after next release
             // prefixed_tools.extend([todo_read_tool(), todo_write_tool()]);
@@ -823,6 +992,19 @@ impl Agent {
             .expect("Failed to list extensions")
     }
 
+    /// Each extension with the version its server reported
+    pub async fn extension_versions(&self) -> Vec<(String, Option<String>)> {
+        let extension_manager = self.extension_manager.read().await;
+        extension_manager.extension_versions().await
+    }
+
+    /// The system prompt the latest reply in `session` started with, if it got that far and
+    /// nothing has taken it since
+    pub async fn take_system_prompt(&self, session: &session::Identifier) -> Option<String> {
+        let label = serde_json::to_string(session).unwrap_or_default();
+        self.reply_system_prompts.lock().await.remove(&label)
+    }
+
     /// Handle a confirmation response for a tool request
     pub async fn handle_confirmation(
         &self,
@@ -834,6 +1016,182 @@ impl Agent {
         }
     }
 
+    /// Tool confirmations this agent is waiting on in `session_id`, or in any session. Sampling
+    /// and elicitation requests from extensions belong to no session, so only the unfiltered
+    /// list has them.
+    pub fn list_pending_approvals(&self, session_id: Option<&str>) -> Vec<PendingApproval> {
+        let mut pending = self.approvals.list(session_id);
+        if session_id.is_none() {
+            pending.extend(self.sampling.pending_approvals());
+            pending.extend(self.elicitation.pending_approvals());
+            pending.sort_by_key(|approval| approval.requested_at);
+        }
+        pending
+    }
+
+    /// Extensions' requests for input as they come in, for clients that ask the user right away
+    pub fn elicitation_requests(&self) -> broadcast::Receiver<PendingApproval> {
+        self.elicitation.subscribe()
+    }
+
+    /// Answer an extension's request for input with what the user entered, or decline it when
+    /// `content` is none. Returns false if there is no such request.
+    pub fn answer_elicitation(&self, request_id: &str, content: Option<JsonObject>) -> bool {
+        self.elicitation.answer(request_id, content)
+    }
+
+    /// The exemplars for the session's task, matched on its first reply only since the task
+    /// doesn't change
+    async fn exemplar_section(
+        &self,
+        session: Option<&SessionConfig>,
+        messages: &[Message],
+    ) -> Result<String> {
+        let session_id = session.map(|session| session.id.session_id());
+        if let Some(id) = &session_id {
+            if let Some(section) = self.exemplar_sections.lock().await.get(id) {
+                return Ok(section.clone());
+            }
+        }
+        let section = exemplars::prompt_section(&self.provider().await?, messages).await;
+        if let Some(id) = session_id {
+            self.exemplar_sections
+                .lock()
+                .await
+                .insert(id, section.clone());
+        }
+        Ok(section)
+    }
+
+    /// Let the extensions release what they kept for a session that has ended, such as the
+    /// background processes started in it, and fire its session end hooks
+    pub async fn end_session(&self, session_id: &str) {
+        self.exemplar_sections.lock().await.remove(session_id);
+        let working_dir = self.started_sessions.lock().await.remove(session_id);
+        self.extension_manager
+            .read()
+            .await
+            .end_session(session_id)
+            .await;
+
+        let id = session::Identifier::Name(session_id.to_string());
+        let message_count = session::storage::get_path(id.clone())
+            .and_then(|path| session::storage::read_metadata(&path))
+            .map(|metadata| metadata.message_count)
+            .ok();
+        hooks::fire(HookEvent::new(
+            HookKind::OnSessionEnd,
+            Some(&id),
+            working_dir.as_deref(),
+            serde_json::json!({ "message_count": message_count }),
+        ));
+    }
+
+    /// Answer a pending tool confirmation. Returns false if there is no unexpired
+    /// confirmation with this id.
+    pub async fn answer_approval(
+        &self,
+        request_id: String,
+        confirmation: PermissionConfirmation,
+    ) -> bool {
+        let allowed = matches!(
+            confirmation.permission,
+            Permission::AllowOnce | Permission::AlwaysAllow
+        );
+        if self.sampling.answer(&request_id, allowed) {
+            return true;
+        }
+        // Allowing a request for input without giving any sends the extension an empty answer
+        if self
+            .elicitation
+            .answer(&request_id, allowed.then(JsonObject::new))
+        {
+            return true;
+        }
+        if self.approvals.get(&request_id).is_none() {
+            return false;
+        }
+        self.handle_confirmation(request_id, confirmation).await;
+        true
+    }
+
+    /// Settle a tool call that another client was waiting to approve when the session was
+    /// handed off: run it if `allowed`, otherwise answer it the way a denial would
+    pub async fn resolve_handed_off_approval(
+        &self,
+        approval: &PendingApproval,
+        allowed: bool,
+    ) -> MessageContent {
+        let result = if allowed {
+            let tool_call =
+                mcp_core::tool::ToolCall::new(&approval.tool_name, approval.arguments.clone());
+            match self
+                .dispatch_tool_call(tool_call, approval.id.clone(), None)
+                .await
+                .1
+            {
+                Ok(call) => call.result.await,
+                Err(e) => Err(e),
+            }
+        } else {
+            Ok(vec![Content::text(DECLINED_RESPONSE)])
+        };
+        MessageContent::tool_response(approval.id.clone(), result)
+    }
+
+    /// Append to the session's event log. Failing to write is logged and otherwise ignored,
+    /// so the event log never gets in the way of a run.
+    fn record_session_event(session: &Option<SessionConfig>, event: SessionEvent) {
+        let Some(session_file) = session
+            .as_ref()
+            .and_then(|s| session::storage::get_path(s.id.clone()).ok())
+        else {
+            return;
+        };
+        if let Err(e) = session::events::record_event(&session_file, &event) {
+            error!("Failed to write session event: {}", e);
+        }
+    }
+
+    /// Run approval modes as auto while the session holds an unexpired elevation, logging
+    /// each call that skips confirmation to the session's audit log
+    fn apply_session_elevation(
+        session: &Option<SessionConfig>,
+        mode: String,
+        requests: &[ToolRequest],
+    ) -> String {
+        if mode != "approve" && mode != "smart_approve" {
+            return mode;
+        }
+        let Some(session_file) = session
+            .as_ref()
+            .and_then(|s| session::storage::get_path(s.id.clone()).ok())
+        else {
+            return mode;
+        };
+        match session::elevation::active_elevation(&session_file) {
+            Ok(Some(_)) => {
+                for request in requests {
+                    if let Ok(tool_call) = &request.tool_call {
+                        if let Err(e) = session::elevation::record_audit_event(
+                            &session_file,
+                            session::AuditEventKind::ElevatedToolCall,
+                            Some(tool_call.name.clone()),
+                        ) {
+                            error!("Failed to write audit log: {}", e);
+                        }
+                    }
+                }
+                "auto".to_string()
+            }
+            Ok(None) => mode,
+            Err(e) => {
+                error!("Failed to read session elevation: {}", e);
+                mode
+            }
+        }
+    }
+
     /// Handle auto-compaction logic and return compacted messages if needed
     async fn handle_auto_compaction(
         &self,
@@ -865,7 +1223,16 @@ impl Agent {
         .await?;
 
         if compact_result.compacted {
-            let compacted_messages = compact_result.messages;
+            let mut compacted_messages = compact_result.messages;
+            if let Some(session_config) = session {
+                if let Ok(session_file) = session::storage::get_path(session_config.id.clone()) {
+                    compacted_messages = session::pins::reinclude_pins(
+                        &session_file,
+                        &session_config.working_dir,
+                        compacted_messages,
+                    );
+                }
+            }
 
             // Get threshold from config to include in message
             let config = crate::config::Config::global();
@@ -895,8 +1262,93 @@ impl Agent {
         unfixed_conversation: Conversation,
         session: Option<SessionConfig>,
         cancel_token: Option<CancellationToken>,
+    ) -> Result<BoxStream<'_, Result<AgentEvent>>> {
+        if let Some(session) = &session {
+            self.extension_manager
+                .write()
+                .await
+                .set_working_dir(session.working_dir.clone())
+                .await;
+            if self
+                .started_sessions
+                .lock()
+                .await
+                .insert(session.id.session_id(), session.working_dir.clone())
+                .is_none()
+            {
+                hooks::fire(HookEvent::new(
+                    HookKind::OnSessionStart,
+                    Some(&session.id),
+                    Some(&session.working_dir),
+                    serde_json::json!({ "schedule_id": session.schedule_id }),
+                ));
+            }
+        }
+
+        let stream = self
+            .reply_with_compaction(unfixed_conversation, session.clone(), cancel_token)
+            .await?;
+        Ok(Self::with_hooks(stream, session))
+    }
+
+    /// Pass events through unchanged, firing tool call hooks as tool responses arrive and
+    /// the turn complete hook with every message once the stream ends
+    fn with_hooks<'a>(
+        mut stream: BoxStream<'a, Result<AgentEvent>>,
+        session: Option<SessionConfig>,
+    ) -> BoxStream<'a, Result<AgentEvent>> {
+        let session_id = session.as_ref().map(|s| s.id.clone());
+        // Scheduled runs are reported by the scheduler, which knows whether they failed
+        let notify_finished = session.as_ref().is_some_and(|s| s.schedule_id.is_none());
+        let working_dir = session.map(|s| s.working_dir);
+        Box::pin(async_stream::stream! {
+            let mut turn = Vec::new();
+            let mut requests = HashMap::new();
+            while let Some(event) = stream.next().await {
+                if let Ok(AgentEvent::Message(message)) = &event {
+                    for content in &message.content {
+                        if let Some(request) = content.as_tool_request() {
+                            requests.insert(request.id.clone(), request.clone());
+                        } else if let Some(response) = content.as_tool_response() {
+                            if let Some(request) = requests.get(&response.id) {
+                                hooks::fire(HookEvent::tool_call(
+                                    session_id.as_ref(),
+                                    working_dir.as_deref(),
+                                    request,
+                                    response,
+                                ));
+                            }
+                        }
+                    }
+                    turn.push(message.clone());
+                }
+                yield event;
+            }
+            hooks::fire(HookEvent::turn_complete(
+                session_id.as_ref(),
+                working_dir.as_deref(),
+                &turn,
+            ));
+            if notify_finished {
+                let summary = turn
+                    .iter()
+                    .rev()
+                    .find(|m| m.role == Role::Assistant && !m.as_concat_text().is_empty())
+                    .map(|m| safe_truncate(&m.as_concat_text(), 200))
+                    .unwrap_or_default();
+                notifications::notify(NotificationKind::RunFinished, "goose is done", &summary);
+            }
+        })
+    }
+
+    async fn reply_with_compaction(
+        &self,
+        unfixed_conversation: Conversation,
+        session: Option<SessionConfig>,
+        cancel_token: Option<CancellationToken>,
     ) -> Result<BoxStream<'_, Result<AgentEvent>>> {
         // Handle auto-compaction before processing
+        let original_len = unfixed_conversation.len();
         let (messages, compaction_msg, _summarization_usage) = match self
             .handle_auto_compaction(unfixed_conversation.messages(), &session)
             .await?
@@ -912,6 +1364,14 @@ impl Agent {
 
         // If we compacted, yield the compaction message and history replacement event
         if let Some(compaction_msg) = compaction_msg {
+            Self::record_session_event(
+                &session,
+                SessionEvent::new(SessionEventKind::Compaction).with_detail(format!(
+                    "{} messages compacted to {}",
+                    original_len,
+                    messages.len()
+                )),
+            );
             return Ok(Box::pin(async_stream::try_stream! {
                 yield AgentEvent::Message(Message::assistant().with_text(compaction_msg));
                 yield AgentEvent::HistoryReplaced(messages.messages().clone());
@@ -941,13 +1401,40 @@ impl Agent {
             mut tools,
             mut toolshim_tools,
             mut system_prompt,
+            style,
             goose_mode,
             initial_messages,
             config,
         } = context;
+        if let Some(session_config) = &session {
+            let label = serde_json::to_string(&session_config.id).unwrap_or_default();
+            self.reply_system_prompts
+                .lock()
+                .await
+                .insert(label, system_prompt.clone());
+        }
         let reply_span = tracing::Span::current();
         self.reset_retry_attempts().await;
 
+        // Scheduled runs have just checked the provider the job runs with, which need not be
+        // the configured one
+        let scheduled = session
+            .as_ref()
+            .is_some_and(|session| session.schedule_id.is_some());
+        let connectivity = if scheduled {
+            offline::Connectivity::Online
+        } else {
+            offline::check_configured_provider().await
+        };
+        if let Some(message) = connectivity.message() {
+            return Ok(Box::pin(async_stream::try_stream! {
+                yield AgentEvent::Message(Message::assistant().with_text(message));
+            }));
+        }
+        let exemplar_prompt = self
+            .exemplar_section(session.as_ref(), messages.messages())
+            .await?;
+
         if let Some(content) = messages
             .last()
             .and_then(|msg| msg.content.first())
@@ -956,6 +1443,8 @@ impl Agent {
             debug!("user_message" = &content);
         }
 
+        let tool_context = ToolCallContext::for_session(session.as_ref());
+
         Ok(Box::pin(async_stream::try_stream! {
             let _ = reply_span.enter();
             let mut turns_taken = 0u32;
@@ -965,6 +1454,31 @@ impl Agent {
                 .unwrap_or_else(|| {
                     config.get_param("GOOSE_MAX_TURNS").unwrap_or(DEFAULT_MAX_TURNS)
                 });
+            let run_limits = session
+                .as_ref()
+                .map(|s| s.run_limits)
+                .unwrap_or_default()
+                .or(RunLimits {
+                    max_duration_seconds: config.get_param(MAX_RUN_SECONDS_KEY).ok(),
+                    max_tool_calls: config.get_param(MAX_TOOL_CALLS_KEY).ok(),
+                    max_tokens: None,
+                });
+            let run_started = std::time::Instant::now();
+            let mut tool_calls_made = 0u32;
+            let mut tokens_used = 0u64;
+            let mut last_model = messages
+                .messages()
+                .iter()
+                .rev()
+                .find_map(|m| m.usage.as_ref().map(|u| u.model.clone()));
+            let mut anomalies = AnomalyMonitor::new(
+                AnomalyConfig::load(),
+                session.as_ref().map(|s| s.id.clone()),
+            );
+            let mut loop_guard = LoopGuard::new(LoopGuardConfig::load());
+            let critique_config = session.as_ref().and_then(|s| s.critique.clone());
+            let mut critique_rounds = 0u32;
+            let mut paused = false;
 
             loop {
                 if is_token_cancelled(&cancel_token) {
@@ -983,23 +1497,64 @@ impl Agent {
 
                 turns_taken += 1;
                 if turns_taken > max_turns {
+                    Self::record_session_event(
+                        &session,
+                        SessionEvent::new(SessionEventKind::LimitReached)
+                            .with_detail(format!("{} turns", max_turns)),
+                    );
                     yield AgentEvent::Message(Message::assistant().with_text(
                         "I've reached the maximum number of actions I can do without user input. Would you like me to continue?"
                     ));
                     break;
                 }
+                let limit_reached = if let Some(seconds) = run_limits
+                    .max_duration_seconds
+                    .filter(|seconds| run_started.elapsed().as_secs() >= *seconds)
+                {
+                    Some(format!("{} seconds", seconds))
+                } else {
+                    run_limits
+                        .max_tool_calls
+                        .filter(|max| tool_calls_made >= *max)
+                        .map(|max| format!("{} tool calls", max))
+                        .or_else(|| {
+                            run_limits
+                                .max_tokens
+                                .filter(|max| tokens_used >= u64::from(*max))
+                                .map(|max| format!("{} tokens", max))
+                        })
+                };
+                if let Some(limit) = limit_reached {
+                    Self::record_session_event(
+                        &session,
+                        SessionEvent::new(SessionEventKind::LimitReached).with_detail(limit.clone()),
+                    );
+                    yield AgentEvent::Message(Message::assistant().with_text(format!(
+                        "I've reached this run's limit of {} without user input. Would you like me to continue?",
+                        limit
+                    )));
+                    break;
+                }
 
+                let mut request_prompt = system_prompt.clone();
+                request_prompt.push_str(&exemplar_prompt);
+                let mut request_messages = messages.messages().clone();
+                self.intercept_provider_request(&mut request_prompt, &mut request_messages).await?;
+                self.add_attached_resources(&session, &mut request_messages).await;
                 let mut stream = Self::stream_response_from_provider(
                     self.provider().await?,
-                    &system_prompt,
-                    messages.messages(),
+                    &request_prompt,
+                    &request_messages,
                     &tools,
                     &toolshim_tools,
                 ).await?;
 
                 let mut added_message = false;
+                // Text of a reply without tool calls, which ends the run, as (message id, text)
+                let mut final_answer: Option<(Option<String>, String)> = None;
                 let mut messages_to_add = Vec::new();
                 let mut tools_updated = false;
+                let mut last_response_id: Option<String> = None;
 
                 while let Some(next) = stream.next().await {
                     if is_token_cancelled(&cancel_token) {
@@ -1036,8 +1591,47 @@ impl Agent {
                                         .await?;
                                 }
                             }
+                            if let Some(ref usage) = usage {
+                                analytics::record(AnalyticsEvent::ModelUsed { model: usage.model.clone() });
+                                Self::record_session_event(
+                                    &session,
+                                    SessionEvent::new(SessionEventKind::Usage).with_usage(TokenUsage {
+                                        model: usage.model.clone(),
+                                        input_tokens: usage.usage.input_tokens.unwrap_or(0).max(0) as i64,
+                                        output_tokens: usage.usage.output_tokens.unwrap_or(0).max(0) as i64,
+                                    }),
+                                );
+                                if let Some(previous) = last_model.as_ref().filter(|m| **m != usage.model) {
+                                    Self::record_session_event(
+                                        &session,
+                                        SessionEvent::new(SessionEventKind::ModelSwitch)
+                                            .with_detail(format!("{} -> {}", previous, usage.model)),
+                                    );
+                                }
+                                last_model = Some(usage.model.clone());
+                                tokens_used += usage.usage.total_tokens.unwrap_or(0).max(0) as u64;
+                                if let Some(alert) = usage.usage.total_tokens.and_then(|tokens| anomalies.observe_usage(tokens as i64)) {
+                                    anomaly::raise(alert);
+                                }
+                            }
+
+                            // Keep the usage on the message it paid for. Streams may send it in a
+                            // chunk of its own, which then joins the streamed message by id.
+                            let response = match (response, usage.as_ref()) {
+                                (Some(response), Some(usage)) => Some(response.with_usage(usage.into())),
+                                (None, Some(usage)) => {
+                                    if let Some(id) = &last_response_id {
+                                        yield AgentEvent::Message(
+                                            Message::assistant().with_id(id.clone()).with_usage(usage.into()),
+                                        );
+                                    }
+                                    None
+                                }
+                                (response, None) => response,
+                            };
 
                             if let Some(response) = response {
+                                last_response_id = response.id.clone();
                                 let ToolCategorizeResult {
                                     frontend_requests,
                                     remaining_requests,
@@ -1054,7 +1648,15 @@ impl Agent {
                                 tokio::task::yield_now().await;
 
                                 let num_tool_requests = frontend_requests.len() + remaining_requests.len();
+                                tool_calls_made += num_tool_requests as u32;
                                 if num_tool_requests == 0 {
+                                    // Streamed replies arrive in chunks sharing a message id
+                                    if final_answer.as_ref().is_none_or(|(id, _)| *id != filtered_response.id) {
+                                        final_answer = Some((filtered_response.id.clone(), String::new()));
+                                    }
+                                    if let Some((_, text)) = final_answer.as_mut() {
+                                        text.push_str(&filtered_response.as_concat_text());
+                                    }
                                     continue;
                                 }
 
@@ -1071,7 +1673,7 @@ impl Agent {
                                     yield AgentEvent::Message(msg);
                                 }
 
-                                let mode = goose_mode.clone();
+                                let mode = Self::apply_session_elevation(&session, goose_mode.clone(), &remaining_requests);
                                 if mode.as_str() == "chat" {
                                     // Skip all tool calls in chat mode
                                     for request in remaining_requests {
@@ -1096,7 +1698,8 @@ impl Agent {
                                     let mut tool_futures = self.handle_approved_and_denied_tools(
                                         &permission_check_result,
                                         message_tool_response.clone(),
-                                        cancel_token.clone()
+                                        cancel_token.clone(),
+                                        &tool_context,
                                     ).await?;
 
                                     let tool_futures_arc = Arc::new(Mutex::new(tool_futures));
@@ -1108,6 +1711,7 @@ impl Agent {
                                         &mut permission_manager,
                                         message_tool_response.clone(),
                                         cancel_token.clone(),
+                                        &tool_context,
                                     );
 
                                     while let Some(msg) = tool_approval_stream.try_next().await? {
@@ -1119,6 +1723,39 @@ impl Agent {
                                         futures_lock.drain(..).collect::<Vec<_>>()
                                     };
 
+                                    let tool_name = |id: &str| {
+                                        remaining_requests
+                                            .iter()
+                                            .find(|r| r.id == id)
+                                            .and_then(|r| r.tool_call.as_ref().ok())
+                                            .map(|call| call.name.clone())
+                                            .unwrap_or_default()
+                                    };
+                                    for request in &permission_check_result.denied {
+                                        Self::record_session_event(
+                                            &session,
+                                            SessionEvent::new(SessionEventKind::PermissionDecision)
+                                                .with_tool(&request.id, tool_name(&request.id))
+                                                .with_detail("denied by permission settings"),
+                                        );
+                                    }
+                                    for request in &permission_check_result.needs_approval {
+                                        let approved = tool_futures.iter().any(|(id, _)| *id == request.id);
+                                        Self::record_session_event(
+                                            &session,
+                                            SessionEvent::new(SessionEventKind::PermissionDecision)
+                                                .with_tool(&request.id, tool_name(&request.id))
+                                                .with_detail(if approved { "approved by user" } else { "denied by user" }),
+                                        );
+                                    }
+                                    for (request_id, _) in &tool_futures {
+                                        Self::record_session_event(
+                                            &session,
+                                            SessionEvent::new(SessionEventKind::ToolStarted)
+                                                .with_tool(request_id, tool_name(request_id)),
+                                        );
+                                    }
+
                                     let with_id = tool_futures
                                         .into_iter()
                                         .map(|(request_id, stream)| {
@@ -1134,7 +1771,20 @@ impl Agent {
                                             break;
                                         }
                                         match item {
-                                            ToolStreamItem::Result(output) => {
+                                            ToolStreamItem::Result(mut output) => {
+                                                if let Some(request) = remaining_requests.iter().find(|r| r.id == request_id) {
+                                                    self.intercept_tool_result(request, &mut output).await?;
+                                                }
+                                                let outcome = match &output {
+                                                    Ok(_) => "succeeded".to_string(),
+                                                    Err(e) => format!("failed: {}", e.message),
+                                                };
+                                                Self::record_session_event(
+                                                    &session,
+                                                    SessionEvent::new(SessionEventKind::ToolFinished)
+                                                        .with_tool(&request_id, tool_name(&request_id))
+                                                        .with_detail(outcome),
+                                                );
                                                 if enable_extension_request_ids.contains(&request_id)
                                                     && output.is_err()
                                                 {
@@ -1157,15 +1807,54 @@ impl Agent {
                                     }
                                 }
 
-                                let final_message_tool_resp = message_tool_response.lock().await.clone();
+                                let mut final_message_tool_resp = message_tool_response.lock().await.clone();
+                                let mut detected_loop = None;
+                                for request in &remaining_requests {
+                                    let Ok(call) = &request.tool_call else {
+                                        continue;
+                                    };
+                                    let failed = final_message_tool_resp
+                                        .content
+                                        .iter()
+                                        .filter_map(|c| c.as_tool_response())
+                                        .find(|r| r.id == request.id)
+                                        .is_some_and(|r| r.tool_result.is_err());
+                                    if let Some(detection) = loop_guard.observe(&call.name, &call.arguments, failed) {
+                                        detected_loop = Some(detection);
+                                    }
+                                }
+                                if let (Some(detection), LoopAction::Reflect) = (&detected_loop, loop_guard.action()) {
+                                    final_message_tool_resp = final_message_tool_resp.with_text(detection.reflection_prompt());
+                                }
                                 yield AgentEvent::Message(final_message_tool_resp.clone());
 
                                 added_message = true;
                                 messages_to_add.push(response);
                                 messages_to_add.push(final_message_tool_resp);
+
+                                if let Some(detection) = detected_loop {
+                                    let action = match loop_guard.action() {
+                                        LoopAction::Pause => "paused",
+                                        _ => "asked the model to reflect",
+                                    };
+                                    warn!("Agent loop detected, {}: {}", action, detection.describe());
+                                    anomaly::raise(anomalies.loop_alert(&detection));
+                                    Self::record_session_event(
+                                        &session,
+                                        SessionEvent::new(SessionEventKind::Intervention)
+                                            .with_detail(format!("{}: {}", action, detection.describe())),
+                                    );
+                                    if loop_guard.action() == LoopAction::Pause {
+                                        let message = Message::assistant().with_text(detection.pause_message());
+                                        messages_to_add.push(message.clone());
+                                        yield AgentEvent::Message(message);
+                                        paused = true;
+                                    }
+                                }
                             }
                         }
                         Err(ProviderError::ContextLengthExceeded(_)) => {
+                            analytics::record(AnalyticsEvent::Error { category: "context_length_exceeded".to_string() });
                             yield AgentEvent::Message(Message::assistant().with_context_length_exceeded(
                                     "The context length of the model has been exceeded. Please start a new session and try again.",
                                 ));
@@ -1173,6 +1862,7 @@ impl Agent {
                         }
                         Err(e) => {
                             error!("Error: {}", e);
+                            analytics::record(AnalyticsEvent::Error { category: e.category().to_string() });
                             yield AgentEvent::Message(Message::assistant().with_text(
                                     format!("Ran into this error: {e}.\n\nPlease retry if you think this is a transient or recoverable error.")
                                 ));
@@ -1181,7 +1871,7 @@ impl Agent {
                     }
                 }
                 if tools_updated {
-                    (tools, toolshim_tools, system_prompt) = self.prepare_tools_and_prompt().await?;
+                    (tools, toolshim_tools, system_prompt) = self.prepare_tools_and_prompt(style.as_deref()).await?;
                 }
                 if !added_message {
                     if let Some(final_output_tool) = self.final_output_tool.lock().await.as_ref() {
@@ -1198,10 +1888,43 @@ impl Agent {
                         }
                     }
 
+                    if let (Some(config), Some((_, answer))) = (critique_config.as_ref(), final_answer.take()) {
+                        if critique_rounds < config.max_rounds {
+                            critique_rounds += 1;
+                            match critique::review(self.provider().await?, config, messages.messages(), &answer).await {
+                                Ok(review) => {
+                                    Self::record_session_event(
+                                        &session,
+                                        SessionEvent::new(SessionEventKind::Critique).with_detail(format!(
+                                            "{}: {}",
+                                            if review.passed { "passed" } else { "needs revision" },
+                                            review.text.trim()
+                                        )),
+                                    );
+                                    if !review.passed && config.include_in_context {
+                                        let request = Message::user().with_text(review.revision_request());
+                                        messages.push(Message::assistant().with_text(answer));
+                                        messages.push(request.clone());
+                                        yield AgentEvent::Message(request);
+                                        continue;
+                                    }
+                                }
+                                Err(e) => warn!("Failed to critique the final answer: {}", e),
+                            }
+                        }
+                    }
+
                     match self.handle_retry_logic(&mut messages, &session, &initial_messages).await {
                         Ok(should_retry) => {
                             if should_retry {
                                 info!("Retry logic triggered, restarting agent loop");
+                                Self::record_session_event(
+                                    &session,
+                                    SessionEvent::new(SessionEventKind::Retry).with_detail(format!(
+                                        "attempt {}",
+                                        self.get_retry_attempts().await
+                                    )),
+                                );
                                 continue;
                             }
                         }
@@ -1216,6 +1939,9 @@ impl Agent {
                 }
 
                 messages.extend(messages_to_add);
+                if paused {
+                    break;
+                }
 
                 tokio::task::yield_now().await;
             }
@@ -1241,6 +1967,12 @@ impl Agent {
     }
 
     pub async fn update_provider(&self, provider: Arc<dyn Provider>) -> Result<()> {
+        self.extension_manager
+            .read()
+            .await
+            .sampling()
+            .set_provider(provider.clone())
+            .await;
         let mut current_provider = self.provider.lock().await;
         *current_provider = Some(provider.clone());
 
@@ -1265,6 +1997,12 @@ impl Agent {
             .await
     }
 
+    /// Use the style preset `style` for sessions that don't pick their own
+    pub async fn set_style(&self, style: Option<String>) {
+        let mut prompt_manager = self.prompt_manager.lock().await;
+        prompt_manager.set_style(style);
+    }
+
     /// Override the system prompt with a custom template
     pub async fn override_system_prompt(&self, template: String) {
         let mut prompt_manager = self.prompt_manager.lock().await;
@@ -1347,6 +2085,7 @@ impl Agent {
             extension_manager.suggest_disable_extensions_prompt().await,
             Some(model_name),
             false,
+            None,
         );
 
         let recipe_prompt = prompt_manager.get_recipe_prompt().await;
@@ -1456,6 +2195,10 @@ impl Agent {
             goose_provider: Some(provider_name.clone()),
             goose_model: Some(model_name.clone()),
             temperature: Some(model_config.temperature.unwrap_or(0.0)),
+            max_turns: None,
+            max_duration_seconds: None,
+            max_tool_calls: None,
+            style: None,
         };
 
         let recipe = Recipe::builder()
@@ -1505,7 +2248,7 @@ mod tests {
 
         let prompt_manager = agent.prompt_manager.lock().await;
         let system_prompt =
-            prompt_manager.build_system_prompt(vec![], None, Value::Null, None, false);
+            prompt_manager.build_system_prompt(vec![], None, Value::Null, None, false, None);
 
         let final_output_tool_ref = agent.final_output_tool.lock().await;
         let final_output_tool_system_prompt =
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::Config;
+use crate::permission::RiskCategory;
+
+/// How long a tool confirmation waits for an answer before it is denied
+pub const DEFAULT_APPROVAL_TIMEOUT_SECS: u64 = 600;
+
+/// A tool call waiting for a user to approve or deny it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingApproval {
+    /// The tool request id; answer with this id
+    pub id: String,
+    /// Session whose reply is waiting on the confirmation, if the reply has one
+    #[serde(default)]
+    pub session_id: Option<String>,
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+    /// Risk classification of the call, for UIs to color-code
+    pub risk: RiskCategory,
+    /// The prompt shown to the user
+    pub prompt: Option<String>,
+    /// Unix timestamp (seconds) when the confirmation was requested
+    pub requested_at: i64,
+    /// Unix timestamp (seconds) after which the call is denied
+    pub expires_at: i64,
+}
+
+/// Tool confirmations the agent is currently blocked on, so clients that did not start
+/// the reply stream can still see and answer them.
+#[derive(Default)]
+pub struct ApprovalRegistry {
+    pending: Mutex<HashMap<String, PendingApproval>>,
+}
+
+impl ApprovalRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configured wait before a pending confirmation is denied
+    pub fn timeout() -> Duration {
+        let secs = Config::global()
+            .get_param::<u64>("GOOSE_APPROVAL_TIMEOUT")
+            .unwrap_or(DEFAULT_APPROVAL_TIMEOUT_SECS);
+        Duration::from_secs(secs)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn register(
+        &self,
+        id: String,
+        session_id: Option<String>,
+        tool_name: String,
+        arguments: serde_json::Value,
+        risk: RiskCategory,
+        prompt: Option<String>,
+        timeout: Duration,
+    ) -> PendingApproval {
+        let requested_at = chrono::Utc::now().timestamp();
+        let approval = PendingApproval {
+            id: id.clone(),
+            session_id,
+            tool_name,
+            arguments,
+            risk,
+            prompt,
+            requested_at,
+            expires_at: requested_at + timeout.as_secs() as i64,
+        };
+        self.pending.lock().unwrap().insert(id, approval.clone());
+        approval
+    }
+
+    pub fn remove(&self, id: &str) -> Option<PendingApproval> {
+        self.pending.lock().unwrap().remove(id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<PendingApproval> {
+        let now = chrono::Utc::now().timestamp();
+        self.pending
+            .lock()
+            .unwrap()
+            .get(id)
+            .filter(|a| a.expires_at > now)
+            .cloned()
+    }
+
+    /// Unexpired confirmations, oldest first, of `session_id` or of every session
+    pub fn list(&self, session_id: Option<&str>) -> Vec<PendingApproval> {
+        let now = chrono::Utc::now().timestamp();
+        let mut pending: Vec<_> = self
+            .pending
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|a| a.expires_at > now)
+            .filter(|a| session_id.is_none() || a.session_id.as_deref() == session_id)
+            .cloned()
+            .collect();
+        pending.sort_by_key(|a| a.requested_at);
+        pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_register_list_remove() {
+        let registry = ApprovalRegistry::new();
+        registry.register(
+            "req1".to_string(),
+            Some("alpha".to_string()),
+            "developer__shell".to_string(),
+            json!({"command": "rm -rf build"}),
+            RiskCategory::Destructive,
+            None,
+            Duration::from_secs(60),
+        );
+        registry.register(
+            "req2".to_string(),
+            Some("alpha".to_string()),
+            "developer__shell".to_string(),
+            json!({}),
+            RiskCategory::ReadOnly,
+            None,
+            Duration::from_secs(0),
+        );
+
+        // A zero timeout is already expired
+        let pending = registry.list(None);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, "req1");
+        assert!(registry.get("req2").is_none());
+
+        assert!(registry.remove("req1").is_some());
+        assert!(registry.list(None).is_empty());
+    }
+
+    #[test]
+    fn test_list_is_filtered_by_session() {
+        let registry = ApprovalRegistry::new();
+        for (id, session) in [("req1", "alpha"), ("req2", "beta")] {
+            registry.register(
+                id.to_string(),
+                Some(session.to_string()),
+                "developer__shell".to_string(),
+                json!({}),
+                RiskCategory::Destructive,
+                None,
+                Duration::from_secs(60),
+            );
+        }
+
+        let alpha = registry.list(Some("alpha"));
+        assert_eq!(alpha.len(), 1);
+        assert_eq!(alpha[0].id, "req1");
+        assert_eq!(registry.list(Some("gamma")).len(), 0);
+        assert_eq!(registry.list(None).len(), 2);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::agents::types::CritiqueConfig;
+use crate::conversation::message::Message;
+use crate::providers::base::Provider;
+use anyhow::Result;
+use rmcp::model::Role;
+use std::sync::Arc;
+
+const CRITIQUE_SYSTEM_PROMPT: &str = "You review an AI assistant's answer to a task against a \
+    list of criteria. Start your reply with a line containing only PASS if the answer meets every \
+    criterion, or REVISE if it does not. Then explain briefly which criteria are unmet and what \
+    should change. Judge only what the answer says; do not answer the task yourself.";
+
+/// The model's review of its own answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Review {
+    pub passed: bool,
+    pub text: String,
+}
+
+impl Review {
+    /// A review passes unless its first line asks for a revision, so an unclear verdict never
+    /// keeps a run going
+    fn parse(text: String) -> Self {
+        let verdict = text
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or_default()
+            .trim()
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_uppercase();
+        Review {
+            passed: verdict != "REVISE",
+            text,
+        }
+    }
+
+    /// Message asking the model to revise its answer, when the critique goes into the context
+    pub fn revision_request(&self) -> String {
+        format!(
+            "A review of your answer against the task criteria found problems:\n\n{}\n\n\
+             Revise your answer to address them.",
+            self.text.trim()
+        )
+    }
+}
+
+/// The task the answer should solve: the last thing the user typed
+fn task(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .rev()
+        .find(|m| m.role == Role::User && !m.is_tool_response())
+        .map(|m| m.as_concat_text())
+        .unwrap_or_default()
+}
+
+fn critique_request(config: &CritiqueConfig, messages: &[Message], answer: &str) -> Message {
+    let criteria = config
+        .criteria
+        .iter()
+        .map(|criterion| format!("- {}", criterion))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Message::user().with_text(format!(
+        "Task:\n{}\n\nCriteria:\n{}\n\nAnswer:\n{}",
+        task(messages),
+        criteria,
+        answer
+    ))
+}
+
+/// Ask the model to review `answer`, the text of a run's last reply, against the recipe's
+/// criteria. The review is a separate completion, so it never enters the conversation itself.
+pub async fn review(
+    provider: Arc<dyn Provider>,
+    config: &CritiqueConfig,
+    messages: &[Message],
+    answer: &str,
+) -> Result<Review> {
+    let request = critique_request(config, messages, answer);
+    let (response, _usage) = provider
+        .complete(CRITIQUE_SYSTEM_PROMPT, &[request], &[])
+        .await?;
+    Ok(Review::parse(response.as_concat_text()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdict_parsing() {
+        assert!(Review::parse("PASS\nAll criteria met.".to_string()).passed);
+        assert!(!Review::parse("\n**REVISE**\nNo tests were added.".to_string()).passed);
+        assert!(Review::parse("Looks fine to me".to_string()).passed);
+    }
+
+    #[test]
+    fn test_request_uses_last_prompt_and_criteria() {
+        let config = CritiqueConfig {
+            criteria: vec!["Mentions the file changed".to_string()],
+            include_in_context: false,
+            max_rounds: 1,
+        };
+        let messages = vec![
+            Message::user().with_text("first task"),
+            Message::assistant().with_text("done"),
+            Message::user().with_text("fix the typo in README"),
+        ];
+        let request = critique_request(&config, &messages, "Fixed it.").as_concat_text();
+        assert!(request.contains("Task:\nfix the typo in README"));
+        assert!(request.contains("- Mentions the file changed"));
+        assert!(request.ends_with("Answer:\nFixed it."));
+    }
+}
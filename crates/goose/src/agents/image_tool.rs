@@ -0,0 +1,171 @@
+//! Image generation tool handler for the Goose agent
+//!
+//! Images are made by the configured image provider and kept as artifacts of the session
+//! the reply belongs to, so the conversation only holds a reference to them.
+
+use std::sync::Arc;
+
+use base64::Engine;
+use mcp_core::ToolResult;
+use rmcp::model::{Content, ErrorCode, ErrorData};
+
+use crate::config::Config;
+use crate::providers::image_generation::{
+    configured_image_provider, ImageGenerationProvider, ImageRequest, IMAGE_PROVIDER_CONFIG_KEY,
+    MAX_IMAGES_PER_REQUEST,
+};
+use crate::session::attachments;
+
+use super::types::ToolCallContext;
+use super::Agent;
+
+const DEFAULT_IMAGE_SIZE: u32 = 1024;
+
+impl Agent {
+    /// Use `provider` for the image generation tool instead of the one in the config
+    pub async fn set_image_provider(&self, provider: Option<Arc<dyn ImageGenerationProvider>>) {
+        *self.image_provider.lock().await = provider;
+    }
+
+    /// Whether the image generation tool should be offered
+    pub(super) async fn image_generation_available(&self) -> bool {
+        self.image_provider.lock().await.is_some()
+            || Config::global()
+                .get_param::<String>(IMAGE_PROVIDER_CONFIG_KEY)
+                .is_ok()
+    }
+
+    async fn image_provider(&self) -> Result<Arc<dyn ImageGenerationProvider>, ErrorData> {
+        if let Some(provider) = self.image_provider.lock().await.clone() {
+            return Ok(provider);
+        }
+        match configured_image_provider(Config::global()) {
+            Ok(Some(provider)) => Ok(Arc::from(provider)),
+            Ok(None) => Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "No image provider is configured, set {} to openai, stability or local",
+                    IMAGE_PROVIDER_CONFIG_KEY
+                ),
+                None,
+            )),
+            Err(e) => Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to set up the image provider: {}", e),
+                None,
+            )),
+        }
+    }
+
+    /// Handle image generation tool calls, keeping the images with the session of `context`
+    pub async fn handle_generate_image(
+        &self,
+        arguments: serde_json::Value,
+        context: &ToolCallContext,
+    ) -> ToolResult<Vec<Content>> {
+        let prompt = arguments
+            .get("prompt")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                ErrorData::new(
+                    ErrorCode::INVALID_PARAMS,
+                    "Missing 'prompt' parameter".to_string(),
+                    None,
+                )
+            })?;
+        let dimension = |key: &str| {
+            arguments
+                .get(key)
+                .and_then(|v| v.as_u64())
+                .map(|v| v.min(u32::MAX as u64) as u32)
+                .unwrap_or(DEFAULT_IMAGE_SIZE)
+        };
+        let count = arguments
+            .get("count")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1)
+            .max(1);
+        let name = arguments
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("image");
+
+        let provider = self.image_provider().await?;
+        let max_images = provider.max_images().min(MAX_IMAGES_PER_REQUEST);
+        if count > max_images as u64 {
+            return Err(ErrorData::new(
+                ErrorCode::INVALID_PARAMS,
+                format!(
+                    "{} makes at most {} image(s) per request, asked for {}",
+                    provider.name(),
+                    max_images,
+                    count
+                ),
+                None,
+            ));
+        }
+        // Providers only make some sizes, so the closest one they support is used
+        let (width, height) = provider.image_size(dimension("width"), dimension("height"));
+        let request = ImageRequest {
+            prompt: prompt.to_string(),
+            width,
+            height,
+            count: count as u32,
+        };
+        let images = provider.generate_images(&request).await.map_err(|e| {
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Image generation failed: {}", e),
+                None,
+            )
+        })?;
+        if images.is_empty() {
+            return Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("{} returned no images", provider.name()),
+                None,
+            ));
+        }
+
+        // Without a session there is nowhere to keep the images, so they are returned inline
+        let Some(session_file) = context.session_file.as_ref() else {
+            return Ok(images
+                .iter()
+                .map(|image| {
+                    Content::image(
+                        base64::engine::general_purpose::STANDARD.encode(&image.data),
+                        image.mime_type.clone(),
+                    )
+                })
+                .collect());
+        };
+
+        let mut lines = Vec::new();
+        for image in &images {
+            let description = image.revised_prompt.as_deref().unwrap_or(prompt);
+            let info = attachments::save_artifact(
+                session_file,
+                &format!("{}.png", name),
+                &image.data,
+                provider.name(),
+                Some(description.to_string()),
+            )
+            .map_err(|e| {
+                ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to store the generated image: {}", e),
+                    None,
+                )
+            })?;
+            lines.push(format!("- artifact '{}' ({} bytes)", info.name, info.size));
+        }
+        Ok(vec![Content::text(format!(
+            "Generated {} {}x{} image(s) with {}, kept with the session as:\n{}",
+            images.len(),
+            width,
+            height,
+            provider.name(),
+            lines.join("\n")
+        ))])
+    }
+}
@@ -0,0 +1,96 @@
+//! Interceptors let crates embedding goose hook into the agent loop without forking it.
+//!
+//! An [`Interceptor`] registered with [`Agent::add_interceptor`](super::Agent::add_interceptor)
+//! sees every request before it goes to the provider and every tool result before it joins
+//! the conversation, and may rewrite either. Returning an error stops the reply and surfaces
+//! the error to the caller, which is how a policy blocks a request outright.
+//!
+//! Interceptors run in the order they were added, each seeing the previous one's changes.
+//! Secrets are handled after every interceptor has run, by the provider itself (see
+//! [`secret_scanner`](crate::providers::secret_scanner)).
+
+use crate::conversation::message::{Message, ToolRequest};
+use anyhow::Result;
+use async_trait::async_trait;
+use mcp_core::ToolResult;
+use rmcp::model::Content;
+
+/// What is about to be sent to the provider. Changes apply to this call only; the
+/// conversation the agent keeps is left as it was.
+pub struct ProviderRequest<'a> {
+    pub system_prompt: &'a mut String,
+    pub messages: &'a mut Vec<Message>,
+}
+
+#[async_trait]
+pub trait Interceptor: Send + Sync {
+    async fn before_provider_call(&self, _request: ProviderRequest<'_>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once per tool call with its result, before the result is recorded
+    async fn after_tool_result(
+        &self,
+        _request: &ToolRequest,
+        _result: &mut ToolResult<Vec<Content>>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::Agent;
+    use mcp_core::ToolCall;
+    use std::sync::Arc;
+
+    struct Redact;
+
+    #[async_trait]
+    impl Interceptor for Redact {
+        async fn before_provider_call(&self, request: ProviderRequest<'_>) -> Result<()> {
+            for message in request.messages.iter_mut() {
+                let text = message.as_concat_text().replace("hunter2", "[redacted]");
+                *message = Message::user().with_text(text);
+            }
+            Ok(())
+        }
+
+        async fn after_tool_result(
+            &self,
+            _request: &ToolRequest,
+            result: &mut ToolResult<Vec<Content>>,
+        ) -> Result<()> {
+            if let Ok(content) = result {
+                content.push(Content::text("checked by policy"));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptors_rewrite_requests_and_results() {
+        let agent = Agent::new();
+        agent.add_interceptor(Arc::new(Redact)).await;
+
+        let mut system_prompt = "be helpful".to_string();
+        let mut messages = vec![Message::user().with_text("my password is hunter2")];
+        agent
+            .intercept_provider_request(&mut system_prompt, &mut messages)
+            .await
+            .unwrap();
+        assert_eq!(messages[0].as_concat_text(), "my password is [redacted]");
+
+        let request = ToolRequest {
+            id: "1".to_string(),
+            tool_call: Ok(ToolCall::new("shell", serde_json::json!({}))),
+        };
+        let mut result = Ok(vec![Content::text("ok")]);
+        agent
+            .intercept_tool_result(&request, &mut result)
+            .await
+            .unwrap();
+        assert_eq!(result.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,329 @@
+//! Notices when the agent is stuck: making the same failing tool call again and again,
+//! repeating one call over and over, or flipping back and forth between two calls. What
+//! happens then is configured under `GOOSE_LOOP_GUARD`:
+//!
+//! ```yaml
+//! GOOSE_LOOP_GUARD:
+//!   action: reflect          # reflect (the default), pause or off
+//!   repeated_failures: 3     # the same call failing this many times in a row
+//!   repeated_calls: 4        # the same call made this many times in a row
+//!   oscillations: 3          # A, B, A, B, ... repeated this many times
+//! ```
+//!
+//! `reflect` adds a note to the tool results asking the model to step back and try another
+//! approach; `pause` ends the run so the user can weigh in. Either way the loop is also
+//! raised as an alert, see [`crate::anomaly`].
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+pub const LOOP_GUARD_KEY: &str = "GOOSE_LOOP_GUARD";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoopAction {
+    #[default]
+    Reflect,
+    Pause,
+    Off,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoopGuardConfig {
+    pub action: LoopAction,
+    pub repeated_failures: usize,
+    pub repeated_calls: usize,
+    pub oscillations: usize,
+}
+
+impl Default for LoopGuardConfig {
+    fn default() -> Self {
+        Self {
+            action: LoopAction::default(),
+            repeated_failures: 3,
+            repeated_calls: 4,
+            oscillations: 3,
+        }
+    }
+}
+
+impl LoopGuardConfig {
+    pub fn load() -> Self {
+        Config::global()
+            .get_param(LOOP_GUARD_KEY)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopDetection {
+    /// The same call, with the same arguments, failed this many times in a row
+    RepeatedFailure { tool_name: String, times: usize },
+    /// The same call, with the same arguments, was made this many times in a row
+    RepeatedCall { tool_name: String, times: usize },
+    /// Calls alternated between these two tools this many times
+    Oscillation {
+        first: String,
+        second: String,
+        times: usize,
+    },
+}
+
+impl LoopDetection {
+    pub fn describe(&self) -> String {
+        match self {
+            LoopDetection::RepeatedFailure { tool_name, times } => format!(
+                "{} failed {} times in a row with the same arguments",
+                tool_name, times
+            ),
+            LoopDetection::RepeatedCall { tool_name, times } => format!(
+                "{} was called {} times in a row with the same arguments",
+                tool_name, times
+            ),
+            LoopDetection::Oscillation {
+                first,
+                second,
+                times,
+            } => format!(
+                "calls alternated between {} and {} {} times without progress",
+                first, second, times
+            ),
+        }
+    }
+
+    /// Note for the model, added after the tool results that revealed the loop
+    pub fn reflection_prompt(&self) -> String {
+        format!(
+            "It looks like you are going in circles: {}. Stop and reflect before making another \
+             tool call. State what you expected to happen and why it didn't, then try a \
+             different approach. If you need information only the user has, ask for it.",
+            self.describe()
+        )
+    }
+
+    /// Shown to the user when the run is paused
+    pub fn pause_message(&self) -> String {
+        format!(
+            "I've paused because it looks like I'm stuck: {}. How would you like me to proceed?",
+            self.describe()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ObservedCall {
+    name: String,
+    arguments: serde_json::Value,
+    failed: bool,
+}
+
+impl ObservedCall {
+    fn same_call(&self, other: &ObservedCall) -> bool {
+        self.name == other.name && self.arguments == other.arguments
+    }
+}
+
+/// Tool calls of one run, checked for loops as they finish
+#[derive(Debug)]
+pub struct LoopGuard {
+    config: LoopGuardConfig,
+    history: VecDeque<ObservedCall>,
+}
+
+impl LoopGuard {
+    pub fn new(config: LoopGuardConfig) -> Self {
+        Self {
+            config,
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn action(&self) -> LoopAction {
+        self.config.action
+    }
+
+    fn window(&self) -> usize {
+        self.config
+            .repeated_failures
+            .max(self.config.repeated_calls)
+            .max(self.config.oscillations * 2)
+    }
+
+    /// Record a finished tool call. When this completes a loop, the loop is returned and
+    /// the history starts over, so the same loop isn't reported again on the next call.
+    pub fn observe(
+        &mut self,
+        name: &str,
+        arguments: &serde_json::Value,
+        failed: bool,
+    ) -> Option<LoopDetection> {
+        if self.config.action == LoopAction::Off {
+            return None;
+        }
+        self.history.push_back(ObservedCall {
+            name: name.to_string(),
+            arguments: arguments.clone(),
+            failed,
+        });
+        while self.history.len() > self.window() {
+            self.history.pop_front();
+        }
+
+        let detection = self
+            .repeated_failure()
+            .or_else(|| self.repeated_call())
+            .or_else(|| self.oscillation());
+        if detection.is_some() {
+            self.history.clear();
+        }
+        detection
+    }
+
+    fn repeated_failure(&self) -> Option<LoopDetection> {
+        let times = self.config.repeated_failures;
+        if times == 0 || self.history.len() < times {
+            return None;
+        }
+        let last = self.history.back()?;
+        let repeated = self
+            .history
+            .iter()
+            .rev()
+            .take(times)
+            .all(|call| call.failed && call.same_call(last));
+        repeated.then(|| LoopDetection::RepeatedFailure {
+            tool_name: last.name.clone(),
+            times,
+        })
+    }
+
+    fn repeated_call(&self) -> Option<LoopDetection> {
+        let times = self.config.repeated_calls;
+        if times == 0 || self.history.len() < times {
+            return None;
+        }
+        let last = self.history.back()?;
+        let repeated = self
+            .history
+            .iter()
+            .rev()
+            .take(times)
+            .all(|call| call.same_call(last));
+        repeated.then(|| LoopDetection::RepeatedCall {
+            tool_name: last.name.clone(),
+            times,
+        })
+    }
+
+    fn oscillation(&self) -> Option<LoopDetection> {
+        let times = self.config.oscillations;
+        let length = times * 2;
+        if times == 0 || self.history.len() < length {
+            return None;
+        }
+        let recent: Vec<&ObservedCall> = self.history.iter().rev().take(length).collect();
+        let (b, a) = (recent[0], recent[1]);
+        if a.same_call(b) {
+            return None;
+        }
+        let alternating = recent
+            .iter()
+            .enumerate()
+            .all(|(i, call)| call.same_call(if i % 2 == 0 { b } else { a }));
+        alternating.then(|| LoopDetection::Oscillation {
+            first: a.name.clone(),
+            second: b.name.clone(),
+            times,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_repeated_failure_is_detected_once() {
+        let mut guard = LoopGuard::new(LoopGuardConfig::default());
+        let args = json!({"command": "cargo build"});
+        assert!(guard.observe("shell", &args, true).is_none());
+        assert!(guard.observe("shell", &args, true).is_none());
+        let detection = guard.observe("shell", &args, true).unwrap();
+        assert_eq!(
+            detection,
+            LoopDetection::RepeatedFailure {
+                tool_name: "shell".to_string(),
+                times: 3
+            }
+        );
+        assert!(guard.observe("shell", &args, true).is_none());
+    }
+
+    #[test]
+    fn test_success_or_new_arguments_break_the_streak() {
+        let mut guard = LoopGuard::new(LoopGuardConfig::default());
+        let args = json!({"command": "cargo build"});
+        guard.observe("shell", &args, true);
+        guard.observe("shell", &args, false);
+        guard.observe("shell", &args, true);
+        assert!(guard
+            .observe("shell", &json!({"command": "cargo check"}), true)
+            .is_none());
+        assert!(guard.observe("shell", &args, true).is_none());
+    }
+
+    #[test]
+    fn test_repeated_call() {
+        let mut guard = LoopGuard::new(LoopGuardConfig::default());
+        let args = json!({"command": "ls"});
+        for _ in 0..3 {
+            assert!(guard.observe("shell", &args, false).is_none());
+        }
+        assert!(guard
+            .observe("shell", &json!({"command": "pwd"}), false)
+            .is_none());
+        for _ in 0..3 {
+            assert!(guard.observe("shell", &args, false).is_none());
+        }
+        let detection = guard.observe("shell", &args, false).unwrap();
+        assert_eq!(
+            detection,
+            LoopDetection::RepeatedCall {
+                tool_name: "shell".to_string(),
+                times: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_oscillation() {
+        let mut guard = LoopGuard::new(LoopGuardConfig::default());
+        let read = json!({"path": "a.rs"});
+        let write = json!({"path": "a.rs", "text": "x"});
+        for _ in 0..2 {
+            assert!(guard.observe("read", &read, false).is_none());
+            assert!(guard.observe("write", &write, false).is_none());
+        }
+        assert!(guard.observe("read", &read, false).is_none());
+        let detection = guard.observe("write", &write, false).unwrap();
+        assert!(matches!(
+            detection,
+            LoopDetection::Oscillation { times: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_off_detects_nothing() {
+        let mut guard = LoopGuard::new(LoopGuardConfig {
+            action: LoopAction::Off,
+            ..Default::default()
+        });
+        let args = json!({});
+        for _ in 0..10 {
+            assert!(guard.observe("shell", &args, true).is_none());
+        }
+    }
+}
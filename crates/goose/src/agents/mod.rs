@@ -1,16 +1,27 @@
 mod agent;
+pub mod approvals;
 mod context;
+pub mod critique;
+pub mod elicitation;
 pub mod extension;
+pub mod extension_audit;
 pub mod extension_manager;
 pub mod final_output_tool;
+pub mod hooks;
+mod image_tool;
+pub mod interceptor;
 mod large_response_handler;
+pub mod loop_guard;
+pub mod native_plugin;
 pub mod platform_tools;
+pub mod prompt_analysis;
 pub mod prompt_manager;
 mod recipe_tools;
 mod reply_parts;
 pub mod retry;
 mod router_tool_selector;
 mod router_tools;
+pub mod sampling;
 mod schedule_tool;
 pub mod sub_recipe_manager;
 pub mod subagent;
@@ -19,14 +30,22 @@ pub mod subagent_handler;
 mod subagent_task_config;
 pub mod todo_tools;
 mod tool_execution;
+pub mod tool_recorder;
 mod tool_route_manager;
 mod tool_router_index_manager;
 pub mod types;
+pub mod wasm_runtime;
+pub mod workspace;
 
-pub use agent::{Agent, AgentEvent};
+pub use agent::{with_reply_provider, Agent, AgentEvent};
+pub use approvals::PendingApproval;
 pub use extension::ExtensionConfig;
 pub use extension_manager::ExtensionManager;
+pub use interceptor::{Interceptor, ProviderRequest};
 pub use prompt_manager::PromptManager;
 pub use subagent::{SubAgent, SubAgentProgress, SubAgentStatus};
 pub use subagent_task_config::TaskConfig;
-pub use types::{FrontendTool, RetryConfig, SessionConfig, SuccessCheck};
+pub use tool_recorder::ToolRecorder;
+pub use types::{
+    CritiqueConfig, FrontendTool, RetryConfig, RunLimits, SessionConfig, SuccessCheck,
+};
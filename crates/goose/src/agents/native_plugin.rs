@@ -0,0 +1,485 @@
+//! Native plugins: tools shipped as a shared library (.so/.dylib/.dll) and loaded into the
+//! goose process, instead of running as a separate MCP server.
+//!
+//! A plugin exports four functions with C linkage. Everything crossing the boundary is a
+//! NUL-terminated UTF-8 JSON string, so plugins can be written in any language that
+//! produces a C ABI library:
+//!
+//! ```c
+//! // Pick an ABI version in [host_min, host_max], or return 0 if none is supported
+//! uint32_t goose_plugin_negotiate(uint32_t host_min, uint32_t host_max);
+//! // A PluginManifest as JSON, for the negotiated version
+//! char *goose_plugin_manifest(void);
+//! // A CallToolResult as JSON; `arguments` is a JSON object
+//! char *goose_plugin_call_tool(const char *name, const char *arguments);
+//! // Free a string returned by the plugin
+//! void goose_plugin_free(char *ptr);
+//! ```
+//!
+//! Calls may arrive from several threads at once, so exported functions must be thread safe.
+//!
+//! A plugin runs with all of goose's privileges, so nothing about it is taken on its word.
+//! Before a library is loaded for the first time the user approves it with
+//! `goose extension grant-plugin`, which shows the capabilities its manifest declares. The
+//! approval is kept in the user config against the library's SHA-256, so a changed library
+//! has to be approved again, and goose loads a private copy of exactly the bytes it checked.
+
+use super::extension::{ExtensionError, ExtensionResult};
+use crate::config::Config;
+use mcp_client::client::{Error, McpClientTrait};
+use rmcp::model::{
+    CallToolResult, ErrorCode, ErrorData, GetPromptResult, Implementation, InitializeResult,
+    ListPromptsResult, ListResourcesResult, ListToolsResult, ProtocolVersion, ReadResourceResult,
+    ServerCapabilities, ServerNotification, Tool,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+/// Oldest plugin ABI this goose can load
+pub const MIN_PLUGIN_ABI_VERSION: u32 = 1;
+/// Newest plugin ABI this goose can load
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// User config key mapping a library's SHA-256 to the [`PluginGrant`] the user approved
+pub const PLUGIN_GRANTS_KEY: &str = "GOOSE_PLUGIN_GRANTS";
+
+type NegotiateFn = unsafe extern "C" fn(u32, u32) -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *mut c_char;
+type CallToolFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+/// What a plugin may do beyond computing on its inputs. A plugin runs with all of goose's
+/// privileges, so these are a declaration the user must approve, not a sandbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    Filesystem,
+    Network,
+    /// Spawning other programs
+    Process,
+}
+
+/// Returned by `goose_plugin_manifest`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    pub abi_version: u32,
+    pub description: Option<String>,
+    /// Added to the system prompt, like MCP server instructions
+    pub instructions: Option<String>,
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+    pub tools: Vec<Tool>,
+}
+
+/// A library the user approved, and the capabilities they accepted for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginGrant {
+    /// Where the library was when it was approved, for display only
+    pub path: String,
+    pub capabilities: Vec<PluginCapability>,
+}
+
+fn plugin_error(message: impl Into<String>) -> ExtensionError {
+    ExtensionError::SetupError(message.into())
+}
+
+/// The library's bytes and their SHA-256, read once so what is checked is what gets loaded
+fn read_library(path: &Path) -> ExtensionResult<(Vec<u8>, String)> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| plugin_error(format!("failed to read {}: {}", path.display(), e)))?;
+    let digest = format!("{:x}", Sha256::digest(&bytes));
+    Ok((bytes, digest))
+}
+
+fn load_grants(config: &Config) -> HashMap<String, PluginGrant> {
+    // Only the user's own config can approve native code; project and team layers can't
+    config.get_user_param(PLUGIN_GRANTS_KEY).unwrap_or_default()
+}
+
+/// Record that the user approved the library at `path` with these capabilities
+pub fn grant_plugin(
+    config: &Config,
+    path: &Path,
+    capabilities: &[PluginCapability],
+) -> ExtensionResult<()> {
+    let (_, digest) = read_library(path)?;
+    let mut grants = load_grants(config);
+    grants.insert(
+        digest,
+        PluginGrant {
+            path: path.display().to_string(),
+            capabilities: capabilities.to_vec(),
+        },
+    );
+    let grants = serde_json::to_value(grants)
+        .map_err(|e| plugin_error(format!("failed to save plugin grant: {}", e)))?;
+    config
+        .set_param(PLUGIN_GRANTS_KEY, grants)
+        .map_err(|e| plugin_error(format!("failed to save plugin grant: {}", e)))
+}
+
+/// Pick the ABI version to talk to the plugin with
+fn negotiate(negotiate_fn: NegotiateFn) -> ExtensionResult<u32> {
+    // SAFETY: the symbol was resolved with this signature; it takes and returns plain integers
+    let version = unsafe { negotiate_fn(MIN_PLUGIN_ABI_VERSION, PLUGIN_ABI_VERSION) };
+    if (MIN_PLUGIN_ABI_VERSION..=PLUGIN_ABI_VERSION).contains(&version) {
+        Ok(version)
+    } else {
+        Err(plugin_error(format!(
+            "plugin does not support ABI versions {}-{} (answered {}); it needs a different goose version",
+            MIN_PLUGIN_ABI_VERSION, PLUGIN_ABI_VERSION, version
+        )))
+    }
+}
+
+/// Check the manifest matches the negotiated ABI and asks for nothing the user has not granted
+fn validate_manifest(
+    manifest: &PluginManifest,
+    abi_version: u32,
+    granted: &[PluginCapability],
+) -> ExtensionResult<()> {
+    if manifest.abi_version != abi_version {
+        return Err(plugin_error(format!(
+            "plugin manifest is for ABI {} but {} was negotiated",
+            manifest.abi_version, abi_version
+        )));
+    }
+    let missing: Vec<_> = manifest
+        .capabilities
+        .iter()
+        .filter(|c| !granted.contains(c))
+        .map(|c| serde_json::to_string(c).unwrap_or_default())
+        .collect();
+    if !missing.is_empty() {
+        return Err(plugin_error(format!(
+            "plugin '{}' needs capabilities that were not granted: {}",
+            manifest.name,
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+struct PluginLibrary {
+    call_tool: CallToolFn,
+    free: FreeFn,
+    // Keeps the function pointers above valid; dropped after them
+    _library: libloading::Library,
+    // Holds the copy the library was loaded from; dropped last
+    _copy: tempfile::TempDir,
+}
+
+impl PluginLibrary {
+    /// Copy a plugin-owned string and hand it back to the plugin to free
+    fn take_string(&self, ptr: *mut c_char) -> Option<String> {
+        if ptr.is_null() {
+            return None;
+        }
+        // SAFETY: the plugin ABI requires a NUL-terminated string that stays valid until freed
+        let value = unsafe { CStr::from_ptr(ptr) }
+            .to_string_lossy()
+            .into_owned();
+        // SAFETY: `ptr` came from this plugin and is freed exactly once
+        unsafe { (self.free)(ptr) };
+        Some(value)
+    }
+
+    fn call_tool(&self, name: &str, arguments: &Value) -> Result<String, String> {
+        let name = CString::new(name).map_err(|e| e.to_string())?;
+        let arguments = CString::new(arguments.to_string()).map_err(|e| e.to_string())?;
+        // SAFETY: both arguments are valid C strings that outlive the call
+        let result = unsafe { (self.call_tool)(name.as_ptr(), arguments.as_ptr()) };
+        self.take_string(result)
+            .ok_or_else(|| "plugin returned no result".to_string())
+    }
+}
+
+/// A loaded plugin, exposed to the extension manager like any MCP client
+pub struct NativePluginClient {
+    library: Arc<PluginLibrary>,
+    manifest: PluginManifest,
+    info: InitializeResult,
+}
+
+impl NativePluginClient {
+    /// Load the library at `path` if the user approved it, agree on an ABI version and
+    /// check its manifest asks for no more than they granted
+    pub fn load(path: &Path) -> ExtensionResult<Self> {
+        let (bytes, digest) = read_library(path)?;
+        let grant = load_grants(Config::global())
+            .remove(&digest)
+            .ok_or_else(|| {
+                plugin_error(format!(
+                "plugin {} has not been approved; review it with `goose extension grant-plugin {}`",
+                path.display(),
+                path.display()
+            ))
+            })?;
+        Self::open(path, bytes, &grant.capabilities)
+    }
+
+    /// Load the library at `path` without checking for a grant, to show the user what it
+    /// declares before they approve it. This runs the library's code.
+    pub fn inspect(path: &Path) -> ExtensionResult<PluginManifest> {
+        let (bytes, _) = read_library(path)?;
+        let all = [
+            PluginCapability::Filesystem,
+            PluginCapability::Network,
+            PluginCapability::Process,
+        ];
+        Ok(Self::open(path, bytes, &all)?.manifest)
+    }
+
+    fn open(path: &Path, bytes: Vec<u8>, granted: &[PluginCapability]) -> ExtensionResult<Self> {
+        // Load a private copy so the library can't be swapped after it was checked
+        let copy = tempfile::tempdir()
+            .map_err(|e| plugin_error(format!("failed to stage plugin: {}", e)))?;
+        let file_name = path.file_name().unwrap_or_else(|| "plugin".as_ref());
+        let copy_path = copy.path().join(file_name);
+        std::fs::write(&copy_path, bytes)
+            .map_err(|e| plugin_error(format!("failed to stage plugin: {}", e)))?;
+
+        // SAFETY: loading runs the library's initializers; the user approved these exact
+        // bytes, or asked to inspect them
+        let library = unsafe { libloading::Library::new(&copy_path) }
+            .map_err(|e| plugin_error(format!("failed to load {}: {}", path.display(), e)))?;
+
+        fn symbol<T: Copy>(library: &libloading::Library, name: &[u8]) -> ExtensionResult<T> {
+            // SAFETY: the caller names the type the plugin ABI defines for this symbol
+            unsafe { library.get::<T>(name) }.map(|s| *s).map_err(|e| {
+                plugin_error(format!(
+                    "not a goose plugin, missing {}: {}",
+                    String::from_utf8_lossy(&name[..name.len() - 1]),
+                    e
+                ))
+            })
+        }
+        let negotiate_fn: NegotiateFn = symbol(&library, b"goose_plugin_negotiate\0")?;
+        let manifest_fn: ManifestFn = symbol(&library, b"goose_plugin_manifest\0")?;
+        let library = PluginLibrary {
+            call_tool: symbol(&library, b"goose_plugin_call_tool\0")?,
+            free: symbol(&library, b"goose_plugin_free\0")?,
+            _library: library,
+            _copy: copy,
+        };
+
+        let abi_version = negotiate(negotiate_fn)?;
+        // SAFETY: resolved with the ABI's signature; takes no arguments
+        let manifest = library
+            .take_string(unsafe { manifest_fn() })
+            .ok_or_else(|| plugin_error("plugin returned no manifest"))?;
+        let manifest: PluginManifest = serde_json::from_str(&manifest)
+            .map_err(|e| plugin_error(format!("invalid plugin manifest: {}", e)))?;
+        validate_manifest(&manifest, abi_version, granted)?;
+
+        let info = InitializeResult {
+            protocol_version: ProtocolVersion::default(),
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: Implementation {
+                name: manifest.name.clone(),
+                version: manifest.version.clone(),
+            },
+            instructions: manifest.instructions.clone(),
+        };
+        Ok(Self {
+            library: Arc::new(library),
+            manifest,
+            info,
+        })
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+}
+
+fn unsupported(what: &str) -> Error {
+    Error::McpError(ErrorData::new(
+        ErrorCode::METHOD_NOT_FOUND,
+        format!("native plugins do not provide {}", what),
+        None,
+    ))
+}
+
+#[async_trait::async_trait]
+impl McpClientTrait for NativePluginClient {
+    async fn list_resources(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListResourcesResult, Error> {
+        Ok(ListResourcesResult {
+            resources: Vec::new(),
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        _uri: &str,
+        _cancel_token: CancellationToken,
+    ) -> Result<ReadResourceResult, Error> {
+        Err(unsupported("resources"))
+    }
+
+    async fn subscribe_resource(
+        &self,
+        _uri: &str,
+        _cancel_token: CancellationToken,
+    ) -> Result<(), Error> {
+        Err(unsupported("resources"))
+    }
+
+    async fn unsubscribe_resource(
+        &self,
+        _uri: &str,
+        _cancel_token: CancellationToken,
+    ) -> Result<(), Error> {
+        Err(unsupported("resources"))
+    }
+
+    async fn list_tools(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListToolsResult, Error> {
+        Ok(ListToolsResult {
+            tools: self.manifest.tools.clone(),
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+        cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        let library = self.library.clone();
+        let name = name.to_string();
+        let call = tokio::task::spawn_blocking(move || library.call_tool(&name, &arguments));
+
+        // Native code cannot be interrupted; a cancelled call finishes in the background
+        let output = tokio::select! {
+            result = call => result.map_err(|e| e.to_string()).and_then(|r| r),
+            _ = cancel_token.cancelled() => return Err(Error::Cancelled { reason: None }),
+        };
+        output
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+            .map_err(|e| {
+                Error::McpError(ErrorData::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("plugin '{}' failed: {}", self.manifest.name, e),
+                    None,
+                ))
+            })
+    }
+
+    async fn list_prompts(
+        &self,
+        _next_cursor: Option<String>,
+        _cancel_token: CancellationToken,
+    ) -> Result<ListPromptsResult, Error> {
+        Ok(ListPromptsResult {
+            prompts: Vec::new(),
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        _name: &str,
+        _arguments: Value,
+        _cancel_token: CancellationToken,
+    ) -> Result<GetPromptResult, Error> {
+        Err(unsupported("prompts"))
+    }
+
+    async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+        // Plugins have no way to send notifications; the sender is dropped straight away
+        mpsc::channel(1).1
+    }
+
+    fn get_info(&self) -> Option<&InitializeResult> {
+        Some(&self.info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn future_plugin(_min: u32, _max: u32) -> u32 {
+        0
+    }
+
+    unsafe extern "C" fn current_plugin(_min: u32, max: u32) -> u32 {
+        max.min(PLUGIN_ABI_VERSION)
+    }
+
+    fn manifest(capabilities: Vec<PluginCapability>) -> PluginManifest {
+        serde_json::from_value(serde_json::json!({
+            "name": "wordcount",
+            "version": "0.1.0",
+            "abi_version": 1,
+            "capabilities": capabilities,
+            "tools": [{
+                "name": "count_words",
+                "description": "Count the words in a file",
+                "inputSchema": {"type": "object"}
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate(current_plugin).unwrap(), PLUGIN_ABI_VERSION);
+        let err = negotiate(future_plugin).unwrap_err().to_string();
+        assert!(err.contains("different goose version"));
+    }
+
+    #[test]
+    fn test_grants_follow_the_library_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )
+        .unwrap();
+        let library = dir.path().join("libwordcount.so");
+        std::fs::write(&library, b"version one").unwrap();
+        grant_plugin(&config, &library, &[PluginCapability::Network]).unwrap();
+
+        let (_, digest) = read_library(&library).unwrap();
+        let grant = load_grants(&config).remove(&digest).unwrap();
+        assert_eq!(grant.capabilities, vec![PluginCapability::Network]);
+
+        std::fs::write(&library, b"version two").unwrap();
+        let (_, changed) = read_library(&library).unwrap();
+        assert!(!load_grants(&config).contains_key(&changed));
+    }
+
+    #[test]
+    fn test_manifest_capabilities_must_be_granted() {
+        let manifest = manifest(vec![PluginCapability::Filesystem]);
+        assert_eq!(manifest.tools[0].name, "count_words");
+        assert!(validate_manifest(&manifest, 1, &[PluginCapability::Filesystem]).is_ok());
+
+        let err = validate_manifest(&manifest, 1, &[PluginCapability::Network])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("filesystem"));
+        assert!(validate_manifest(&manifest, 2, &[PluginCapability::Filesystem]).is_err());
+    }
+}
@@ -8,6 +8,7 @@ pub const PLATFORM_SEARCH_AVAILABLE_EXTENSIONS_TOOL_NAME: &str =
     "platform__search_available_extensions";
 pub const PLATFORM_MANAGE_EXTENSIONS_TOOL_NAME: &str = "platform__manage_extensions";
 pub const PLATFORM_MANAGE_SCHEDULE_TOOL_NAME: &str = "platform__manage_schedule";
+pub const PLATFORM_GENERATE_IMAGE_TOOL_NAME: &str = "platform__generate_image";
 
 pub fn read_resource_tool() -> Tool {
     Tool::new(
@@ -154,3 +155,35 @@ pub fn manage_schedule_tool() -> Tool {
         open_world_hint: Some(false),
     })
 }
+
+pub fn generate_image_tool() -> Tool {
+    Tool::new(
+        PLATFORM_GENERATE_IMAGE_TOOL_NAME.to_string(),
+        indoc! {r#"
+            Generate images from a text description, for mockups, illustrations and diagrams in
+            design and documentation work.
+
+            The images are kept with the session as artifacts the user can download, and the
+            result names them. Describe the subject, style and composition in the prompt.
+        "#}
+        .to_string(),
+        object!({
+            "type": "object",
+            "required": ["prompt"],
+            "properties": {
+                "prompt": {"type": "string", "description": "Description of the image to generate"},
+                "name": {"type": "string", "description": "File name for the image, without extension (e.g. 'homepage-hero')"},
+                "width": {"type": "integer", "description": "Width in pixels, the closest size the image provider supports is used", "default": 1024},
+                "height": {"type": "integer", "description": "Height in pixels, the closest size the image provider supports is used", "default": 1024},
+                "count": {"type": "integer", "description": "Number of images to generate, at most 4 and only 1 with dall-e-3", "default": 1}
+            }
+        }),
+    )
+    .annotate(ToolAnnotations {
+        title: Some("Generate an image".to_string()),
+        read_only_hint: Some(false),
+        destructive_hint: Some(false),
+        idempotent_hint: Some(false),
+        open_world_hint: Some(true),
+    })
+}
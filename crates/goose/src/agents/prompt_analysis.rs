@@ -0,0 +1,336 @@
+//! Breaks the assembled system prompt down by where each part comes from, so users can see
+//! what their context is spent on before the conversation even starts.
+
+use rmcp::model::Tool;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+use super::extension::ExtensionInfo;
+use super::Agent;
+use crate::token_counter::create_async_token_counter;
+
+/// Heading the developer extension puts before the .goosehints it loaded
+const GLOBAL_HINTS_HEADING: &str = "### Global Hints";
+const PROJECT_HINTS_HEADING: &str = "### Project Hints";
+/// Heading the memory extension puts before the memories it loaded
+const MEMORIES_HEADING: &str = "**Here are the user's currently saved memories:**";
+
+/// Sources at least this large get a suggestion
+const SUGGESTION_THRESHOLD_TOKENS: usize = 1_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptSourceKind {
+    /// The system prompt template itself
+    Base,
+    /// Instructions an extension ships with
+    Extension,
+    /// .goosehints files, loaded by the developer extension
+    Hints,
+    /// Saved memories, loaded by the memory extension
+    Memories,
+    /// Additional instructions from recipes, the mode, the response language and the style
+    Instructions,
+    /// Definitions of the tools an extension offers
+    Tools,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PromptSource {
+    pub kind: PromptSourceKind,
+    /// Extension the source belongs to, when it belongs to one
+    pub extension: Option<String>,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PromptAnalysis {
+    /// Tokens sent before the first message: the system prompt plus tool definitions
+    pub total_tokens: usize,
+    pub system_prompt_tokens: usize,
+    pub tool_tokens: usize,
+    /// Largest first
+    pub sources: Vec<PromptSource>,
+    /// What to disable or trim to win back the most context, largest saving first
+    pub suggestions: Vec<String>,
+}
+
+/// Split extension instructions into what the extension ships with, the hints it loaded
+/// and the memories it loaded
+fn split_instructions(instructions: &str) -> (&str, &str, &str) {
+    let hints_start = [GLOBAL_HINTS_HEADING, PROJECT_HINTS_HEADING]
+        .iter()
+        .filter_map(|heading| instructions.find(heading))
+        .min();
+    if let Some(start) = hints_start {
+        let (own, hints) = instructions.split_at(start);
+        return (own, hints, "");
+    }
+    match instructions.find(MEMORIES_HEADING) {
+        Some(start) => {
+            let (own, memories) = instructions.split_at(start);
+            (own, "", memories)
+        }
+        None => (instructions, "", ""),
+    }
+}
+
+/// Tool tokens per extension; tools are named `<extension>__<tool>`, and frontend tools
+/// have no prefix
+fn group_tools(tools: &[Tool]) -> BTreeMap<String, Vec<Tool>> {
+    let mut groups: BTreeMap<String, Vec<Tool>> = BTreeMap::new();
+    for tool in tools {
+        let extension = tool
+            .name
+            .split_once("__")
+            .map(|(extension, _)| extension.to_string())
+            .unwrap_or_else(|| "frontend".to_string());
+        groups.entry(extension).or_default().push(tool.clone());
+    }
+    groups
+}
+
+fn suggestions(sources: &[PromptSource], router_enabled: bool) -> Vec<String> {
+    let mut per_extension: BTreeMap<&str, usize> = BTreeMap::new();
+    for source in sources {
+        if let (Some(extension), PromptSourceKind::Extension | PromptSourceKind::Tools) =
+            (&source.extension, source.kind)
+        {
+            *per_extension.entry(extension.as_str()).or_default() += source.tokens;
+        }
+    }
+
+    let mut suggestions: Vec<(usize, String)> = per_extension
+        .into_iter()
+        .filter(|(extension, tokens)| {
+            *tokens >= SUGGESTION_THRESHOLD_TOKENS && !["frontend", "platform"].contains(extension)
+        })
+        .map(|(extension, tokens)| {
+            (
+                tokens,
+                format!(
+                    "Disable the {} extension when you don't need it to save about {} tokens",
+                    extension, tokens
+                ),
+            )
+        })
+        .collect();
+
+    for source in sources {
+        if source.tokens < SUGGESTION_THRESHOLD_TOKENS {
+            continue;
+        }
+        match source.kind {
+            PromptSourceKind::Hints => suggestions.push((
+                source.tokens,
+                format!(
+                    "Trim your .goosehints files, including the files they reference, to save up to {} tokens",
+                    source.tokens
+                ),
+            )),
+            PromptSourceKind::Memories => suggestions.push((
+                source.tokens,
+                format!(
+                    "Remove memories you no longer need to save up to {} tokens",
+                    source.tokens
+                ),
+            )),
+            _ => {}
+        }
+    }
+
+    let tool_tokens: usize = sources
+        .iter()
+        .filter(|source| source.kind == PromptSourceKind::Tools)
+        .map(|source| source.tokens)
+        .sum();
+    if !router_enabled && tool_tokens >= 4 * SUGGESTION_THRESHOLD_TOKENS {
+        suggestions.push((
+            tool_tokens,
+            format!(
+                "Set GOOSE_ENABLE_ROUTER to send only the tools relevant to each request instead of {} tokens of tool definitions",
+                tool_tokens
+            ),
+        ));
+    }
+
+    suggestions.sort_by(|a, b| b.0.cmp(&a.0));
+    suggestions.into_iter().map(|(_, text)| text).collect()
+}
+
+/// Break `system_prompt` down into the parts it was assembled from. Token counts of the parts
+/// are counted separately, so the base is what is left of the total once they are taken off.
+fn analyze(
+    system_prompt: &str,
+    extensions: &[ExtensionInfo],
+    additional_instructions: &[String],
+    tool_tokens: BTreeMap<String, usize>,
+    router_enabled: bool,
+    count_tokens: impl Fn(&str) -> usize,
+) -> PromptAnalysis {
+    let system_prompt_tokens = count_tokens(system_prompt);
+    let mut sources = Vec::new();
+    for extension in extensions {
+        let (own, hints, memories) = split_instructions(&extension.instructions);
+        for (kind, text) in [
+            (PromptSourceKind::Extension, own),
+            (PromptSourceKind::Hints, hints),
+            (PromptSourceKind::Memories, memories),
+        ] {
+            if !text.trim().is_empty() {
+                sources.push(PromptSource {
+                    kind,
+                    extension: Some(extension.name.clone()),
+                    tokens: count_tokens(text),
+                });
+            }
+        }
+    }
+    let instructions_tokens: usize = additional_instructions
+        .iter()
+        .map(|instruction| count_tokens(instruction))
+        .sum();
+    if instructions_tokens > 0 {
+        sources.push(PromptSource {
+            kind: PromptSourceKind::Instructions,
+            extension: None,
+            tokens: instructions_tokens,
+        });
+    }
+    let parts_tokens: usize = sources.iter().map(|source| source.tokens).sum();
+    sources.push(PromptSource {
+        kind: PromptSourceKind::Base,
+        extension: None,
+        tokens: system_prompt_tokens.saturating_sub(parts_tokens),
+    });
+
+    let tool_tokens_total: usize = tool_tokens.values().sum();
+    sources.extend(
+        tool_tokens
+            .into_iter()
+            .map(|(extension, tokens)| PromptSource {
+                kind: PromptSourceKind::Tools,
+                extension: Some(extension),
+                tokens,
+            }),
+    );
+    sources.sort_by(|a, b| b.tokens.cmp(&a.tokens));
+
+    PromptAnalysis {
+        total_tokens: system_prompt_tokens + tool_tokens_total,
+        system_prompt_tokens,
+        tool_tokens: tool_tokens_total,
+        suggestions: suggestions(&sources, router_enabled),
+        sources,
+    }
+}
+
+impl Agent {
+    /// Analyze the system prompt and tools the next request would be sent with
+    pub async fn analyze_prompt(&self) -> anyhow::Result<PromptAnalysis> {
+        let (tools, _toolshim_tools, system_prompt) = self.prepare_tools_and_prompt(None).await?;
+        let router_enabled = self.tool_route_manager.is_router_enabled().await;
+
+        let mut extensions = self
+            .extension_manager
+            .read()
+            .await
+            .get_extensions_info()
+            .await;
+        if let Some(frontend_instructions) = self.frontend_instructions.lock().await.clone() {
+            extensions.push(ExtensionInfo::new(
+                "frontend",
+                &frontend_instructions,
+                false,
+            ));
+        }
+        let additional_instructions = self
+            .prompt_manager
+            .lock()
+            .await
+            .additional_instructions(None);
+
+        let token_counter = create_async_token_counter()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create token counter: {}", e))?;
+        let tool_tokens = group_tools(&tools)
+            .into_iter()
+            .map(|(extension, tools)| (extension, token_counter.count_tokens_for_tools(&tools)))
+            .collect();
+
+        Ok(analyze(
+            &system_prompt,
+            &extensions,
+            &additional_instructions,
+            tool_tokens,
+            router_enabled,
+            |text| token_counter.count_tokens(text),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+
+    #[test]
+    fn test_split_instructions() {
+        let developer = "Edit files.\n### Project Hints\nUse tabs.";
+        assert_eq!(
+            split_instructions(developer),
+            ("Edit files.\n", "### Project Hints\nUse tabs.", "")
+        );
+        let memory = format!("Remember things.\n\n{}\n- likes tea", MEMORIES_HEADING);
+        let (own, hints, memories) = split_instructions(&memory);
+        assert_eq!(own, "Remember things.\n\n");
+        assert_eq!(hints, "");
+        assert!(memories.ends_with("- likes tea"));
+    }
+
+    #[test]
+    fn test_analyze_attributes_tokens_and_suggests() {
+        let hints = "hint ".repeat(1_500);
+        let extensions = vec![
+            ExtensionInfo::new(
+                "developer",
+                &format!("Edit files.\n{}\n{}", GLOBAL_HINTS_HEADING, hints),
+                false,
+            ),
+            ExtensionInfo::new("jira", "Track issues.", false),
+        ];
+        let system_prompt = format!(
+            "You are goose. {} {} Be concise.",
+            extensions[0].instructions, extensions[1].instructions
+        );
+        let tool_tokens =
+            BTreeMap::from([("developer".to_string(), 400), ("jira".to_string(), 1_200)]);
+
+        let analysis = analyze(
+            &system_prompt,
+            &extensions,
+            &["Be concise.".to_string()],
+            tool_tokens,
+            false,
+            words,
+        );
+
+        assert_eq!(analysis.tool_tokens, 1_600);
+        assert_eq!(analysis.total_tokens, words(&system_prompt) + 1_600);
+        assert_eq!(analysis.sources[0].kind, PromptSourceKind::Hints);
+        assert_eq!(analysis.sources[0].tokens, 1_503);
+        let base = analysis
+            .sources
+            .iter()
+            .find(|source| source.kind == PromptSourceKind::Base)
+            .unwrap();
+        assert_eq!(base.tokens, 3);
+        assert_eq!(analysis.suggestions.len(), 2);
+        assert!(analysis.suggestions[0].contains(".goosehints"));
+        assert!(analysis.suggestions[1].contains("jira"));
+    }
+}
@@ -5,11 +5,13 @@ use std::collections::HashMap;
 use crate::agents::extension::ExtensionInfo;
 use crate::agents::router_tools::llm_search_tool_prompt;
 use crate::providers::base::get_current_model;
-use crate::{config::Config, prompt_template, utils::sanitize_unicode_tags};
+use crate::{config::Config, i18n, prompt_template, style, utils::sanitize_unicode_tags};
 
 pub struct PromptManager {
     system_prompt_override: Option<String>,
     system_prompt_extras: Vec<String>,
+    /// Style preset chosen for the agent, e.g. by a recipe
+    style: Option<String>,
     current_date_timestamp: String,
 }
 
@@ -24,6 +26,7 @@ impl PromptManager {
         PromptManager {
             system_prompt_override: None,
             system_prompt_extras: Vec::new(),
+            style: None,
             // Use the fixed current date time so that prompt cache can be used.
             current_date_timestamp: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         }
@@ -34,6 +37,11 @@ impl PromptManager {
         self.system_prompt_extras.push(instruction);
     }
 
+    /// Use the style preset `style` unless the session picks another
+    pub fn set_style(&mut self, style: Option<String>) {
+        self.style = style;
+    }
+
     /// Override the system prompt with custom text
     pub fn set_system_prompt_override(&mut self, template: String) {
         self.system_prompt_override = Some(template);
@@ -58,10 +66,44 @@ impl PromptManager {
         "system.md"
     }
 
+    /// Instructions appended to the system prompt after the template: recipe and caller
+    /// extras, the current mode, the response language and the style preset, where the
+    /// session's `session_style` wins over the agent's
+    pub fn additional_instructions(&self, session_style: Option<&str>) -> Vec<String> {
+        let mut system_prompt_extras = self.system_prompt_extras.clone();
+        let config = Config::global();
+        let goose_mode = config.get_param("GOOSE_MODE").unwrap_or("auto".to_string());
+        if goose_mode == "chat" {
+            system_prompt_extras.push(
+                "Right now you are in the chat only mode, no access to any tool use and system."
+                    .to_string(),
+            );
+        } else {
+            system_prompt_extras
+                .push("Right now you are *NOT* in the chat only mode and have access to tool use and system.".to_string());
+        }
+        if let Some(language) = i18n::response_language() {
+            system_prompt_extras.push(format!(
+                "Respond to the user in {}, unless they ask for another language. Keep code, file paths, commands and tool arguments as they are.",
+                language
+            ));
+        }
+        let style = session_style.or(self.style.as_deref());
+        if let Some(instructions) = style::style_instructions(style) {
+            system_prompt_extras.push(instructions);
+        }
+
+        system_prompt_extras
+            .into_iter()
+            .map(|extra| sanitize_unicode_tags(&extra))
+            .collect()
+    }
+
     /// Build the final system prompt
     ///
     /// * `extensions_info` – extension information for each extension/MCP
     /// * `frontend_instructions` – instructions for the "frontend" tool
+    /// * `session_style` – the style preset the session picked, if any
     pub fn build_system_prompt(
         &self,
         extensions_info: Vec<ExtensionInfo>,
@@ -69,6 +111,7 @@ impl PromptManager {
         suggest_disable_extensions_prompt: Value,
         model_name: Option<&str>,
         router_enabled: bool,
+        session_style: Option<&str>,
     ) -> String {
         let mut context: HashMap<&str, Value> = HashMap::new();
         let mut extensions_info = extensions_info.clone();
@@ -138,23 +181,7 @@ impl PromptManager {
                 .expect("Prompt should render")
         };
 
-        let mut system_prompt_extras = self.system_prompt_extras.clone();
-        let config = Config::global();
-        let goose_mode = config.get_param("GOOSE_MODE").unwrap_or("auto".to_string());
-        if goose_mode == "chat" {
-            system_prompt_extras.push(
-                "Right now you are in the chat only mode, no access to any tool use and system."
-                    .to_string(),
-            );
-        } else {
-            system_prompt_extras
-                .push("Right now you are *NOT* in the chat only mode and have access to tool use and system.".to_string());
-        }
-
-        let sanitized_system_prompt_extras: Vec<String> = system_prompt_extras
-            .into_iter()
-            .map(|extra| sanitize_unicode_tags(&extra))
-            .collect();
+        let sanitized_system_prompt_extras = self.additional_instructions(session_style);
 
         if sanitized_system_prompt_extras.is_empty() {
             base_prompt
@@ -235,8 +262,14 @@ mod tests {
         let malicious_override = "System prompt\u{E0041}\u{E0042}\u{E0043}with hidden text";
         manager.set_system_prompt_override(malicious_override.to_string());
 
-        let result =
-            manager.build_system_prompt(vec![], None, Value::String("".to_string()), None, false);
+        let result = manager.build_system_prompt(
+            vec![],
+            None,
+            Value::String("".to_string()),
+            None,
+            false,
+            None,
+        );
 
         assert!(!result.contains('\u{E0041}'));
         assert!(!result.contains('\u{E0042}'));
@@ -245,14 +278,46 @@ mod tests {
         assert!(result.contains("with hidden text"));
     }
 
+    #[test]
+    fn test_session_style_wins_over_agent_style() {
+        let mut manager = PromptManager::new();
+        manager.set_style(Some("terse".to_string()));
+        let result = manager.build_system_prompt(
+            vec![],
+            None,
+            Value::String("".to_string()),
+            None,
+            false,
+            None,
+        );
+        assert!(result.contains("Keep answers short"));
+
+        let result = manager.build_system_prompt(
+            vec![],
+            None,
+            Value::String("".to_string()),
+            None,
+            false,
+            Some("code-only"),
+        );
+        assert!(result.contains("Answer with code alone"));
+        assert!(!result.contains("Keep answers short"));
+    }
+
     #[test]
     fn test_build_system_prompt_sanitizes_extras() {
         let mut manager = PromptManager::new();
         let malicious_extra = "Extra instruction\u{E0041}\u{E0042}\u{E0043}hidden";
         manager.add_system_prompt_extra(malicious_extra.to_string());
 
-        let result =
-            manager.build_system_prompt(vec![], None, Value::String("".to_string()), None, false);
+        let result = manager.build_system_prompt(
+            vec![],
+            None,
+            Value::String("".to_string()),
+            None,
+            false,
+            None,
+        );
 
         assert!(!result.contains('\u{E0041}'));
         assert!(!result.contains('\u{E0042}'));
@@ -268,8 +333,14 @@ mod tests {
         manager.add_system_prompt_extra("Second\u{E0042}instruction".to_string());
         manager.add_system_prompt_extra("Third\u{E0043}instruction".to_string());
 
-        let result =
-            manager.build_system_prompt(vec![], None, Value::String("".to_string()), None, false);
+        let result = manager.build_system_prompt(
+            vec![],
+            None,
+            Value::String("".to_string()),
+            None,
+            false,
+            None,
+        );
 
         assert!(!result.contains('\u{E0041}'));
         assert!(!result.contains('\u{E0042}'));
@@ -285,8 +356,14 @@ mod tests {
         let legitimate_unicode = "Instruction with 世界 and 🌍 emojis";
         manager.add_system_prompt_extra(legitimate_unicode.to_string());
 
-        let result =
-            manager.build_system_prompt(vec![], None, Value::String("".to_string()), None, false);
+        let result = manager.build_system_prompt(
+            vec![],
+            None,
+            Value::String("".to_string()),
+            None,
+            false,
+            None,
+        );
 
         assert!(result.contains("世界"));
         assert!(result.contains("🌍"));
@@ -309,6 +386,7 @@ mod tests {
             Value::String("".to_string()),
             None,
             false,
+            None,
         );
 
         assert!(!result.contains('\u{E0041}'));
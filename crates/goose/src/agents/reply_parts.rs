@@ -8,6 +8,7 @@ use futures::stream::StreamExt;
 use super::super::agents::Agent;
 use crate::conversation::message::{Message, MessageContent, ToolRequest};
 use crate::conversation::Conversation;
+use crate::notifications::{self, NotificationKind, NotificationsConfig};
 use crate::providers::base::{stream_from_single_message, MessageStream, Provider, ProviderUsage};
 use crate::providers::errors::ProviderError;
 use crate::providers::toolshim::{
@@ -32,8 +33,12 @@ async fn toolshim_postprocess(
 }
 
 impl Agent {
-    /// Prepares tools and system prompt for a provider request
-    pub async fn prepare_tools_and_prompt(&self) -> anyhow::Result<(Vec<Tool>, Vec<Tool>, String)> {
+    /// Prepares tools and system prompt for a provider request, in the style preset the
+    /// session picked if there is one
+    pub async fn prepare_tools_and_prompt(
+        &self,
+        session_style: Option<&str>,
+    ) -> anyhow::Result<(Vec<Tool>, Vec<Tool>, String)> {
         // Get router enabled status
         let router_enabled = self.tool_route_manager.is_router_enabled().await;
 
@@ -67,6 +72,7 @@ impl Agent {
             extension_manager.suggest_disable_extensions_prompt().await,
             Some(model_name),
             router_enabled,
+            session_style,
         );
 
         // Handle toolshim if enabled
@@ -105,6 +111,37 @@ impl Agent {
             })
     }
 
+    /// Put the content of the resources attached to the session in front of the first message
+    /// of a request. It is delimited as data in a user message rather than added to the system
+    /// prompt, since it comes from outside and is read again for every request.
+    pub(crate) async fn add_attached_resources(
+        &self,
+        session: &Option<crate::agents::types::SessionConfig>,
+        messages: &mut [Message],
+    ) {
+        let Some(attached) = session
+            .as_ref()
+            .and_then(|session| session::storage::get_path(session.id.clone()).ok())
+            .and_then(|path| session::storage::read_metadata(&path).ok())
+            .map(|metadata| metadata.attached_resources)
+            .filter(|attached| !attached.is_empty())
+        else {
+            return;
+        };
+        let Some(text) = self
+            .extension_manager
+            .read()
+            .await
+            .attached_resources_text(&attached)
+            .await
+        else {
+            return;
+        };
+        if let Some(first) = messages.first_mut() {
+            first.content.insert(0, MessageContent::text(text));
+        }
+    }
+
     /// Generate a response from the LLM provider
     /// Handles toolshim transformations if needed
     pub(crate) async fn generate_response_from_provider(
@@ -266,6 +303,7 @@ impl Agent {
             role: response.role.clone(),
             created: response.created,
             content: filtered_content,
+            usage: response.usage.clone(),
         };
 
         // Categorize tool requests
@@ -315,8 +353,20 @@ impl Agent {
                 _ => a.or(b),
             }
         };
+        let tokens_before = metadata.accumulated_total_tokens.unwrap_or(0);
         metadata.accumulated_total_tokens =
             accumulate(metadata.accumulated_total_tokens, usage.usage.total_tokens);
+        let tokens_after = metadata.accumulated_total_tokens.unwrap_or(0);
+        if NotificationsConfig::load().crosses_budget(tokens_before, tokens_after) {
+            notifications::notify(
+                NotificationKind::BudgetReached,
+                "goose token budget reached",
+                &format!(
+                    "Session {} has used {} tokens",
+                    metadata.description, tokens_after
+                ),
+            );
+        }
         metadata.accumulated_input_tokens =
             accumulate(metadata.accumulated_input_tokens, usage.usage.input_tokens);
         metadata.accumulated_output_tokens = accumulate(
@@ -0,0 +1,402 @@
+//! MCP sampling: extensions asking goose's configured provider for a completion, so their
+//! servers can hand reasoning back to the host model. The user approves every request, each
+//! answer is held to the request's `max_tokens`, and an extension with a budget spends from it.
+
+use async_trait::async_trait;
+use etcetera::{choose_app_strategy, AppStrategy};
+use mcp_client::client::SamplingHandler;
+use rmcp::model::{
+    Content, CreateMessageRequestParam, CreateMessageResult, ErrorCode, ErrorData, Role,
+    SamplingMessage,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{oneshot, Mutex};
+use utoipa::ToSchema;
+
+use crate::agents::approvals::{ApprovalRegistry, PendingApproval};
+use crate::config::{ExtensionConfigManager, APP_STRATEGY};
+use crate::conversation::message::Message;
+use crate::notifications::{self, NotificationKind};
+use crate::permission::RiskCategory;
+use crate::providers::base::Provider;
+use crate::token_counter::create_async_token_counter;
+
+/// A sampling request waiting for the user, and where to send their answer
+struct PendingSample {
+    approval: PendingApproval,
+    answer: oneshot::Sender<bool>,
+}
+
+/// Sampling state shared by the samplers of every extension
+#[derive(Default)]
+pub struct SamplingContext {
+    provider: Mutex<Option<Arc<dyn Provider>>>,
+    /// Tokens spent or reserved by in-flight requests, by extension
+    tokens_used: Mutex<HashMap<String, i32>>,
+    /// Where `tokens_used` is kept across restarts; nowhere when unset
+    usage_path: Option<PathBuf>,
+    pending: std::sync::Mutex<HashMap<String, PendingSample>>,
+}
+
+/// An extension's sampling budget and how much of it it spent
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SamplingUsage {
+    pub extension: String,
+    pub token_budget: Option<i32>,
+    /// Tokens spent on sampling, across restarts
+    pub tokens_used: i32,
+}
+
+impl SamplingContext {
+    /// A context that keeps what extensions spent in the goose data dir
+    pub fn persistent() -> Self {
+        let usage_path = choose_app_strategy(APP_STRATEGY.clone())
+            .map(|strategy| strategy.data_dir().join("sampling_usage.json"))
+            .ok();
+        Self::with_usage_path(usage_path)
+    }
+
+    fn with_usage_path(usage_path: Option<PathBuf>) -> Self {
+        let tokens_used = usage_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|usage| serde_json::from_str(&usage).ok())
+            .unwrap_or_default();
+        Self {
+            tokens_used: Mutex::new(tokens_used),
+            usage_path,
+            ..Self::default()
+        }
+    }
+
+    /// Answer sampling requests with `provider` from now on
+    pub async fn set_provider(&self, provider: Arc<dyn Provider>) {
+        *self.provider.lock().await = Some(provider);
+    }
+
+    /// Budget and spending of every extension that has a budget or has sampled
+    pub async fn usage(&self) -> Vec<SamplingUsage> {
+        let budgets = ExtensionConfigManager::get_sampling_budgets();
+        let tokens_used = self.tokens_used.lock().await;
+        let mut extensions: Vec<&String> = budgets.keys().chain(tokens_used.keys()).collect();
+        extensions.sort();
+        extensions.dedup();
+        extensions
+            .into_iter()
+            .map(|extension| SamplingUsage {
+                extension: extension.clone(),
+                token_budget: budgets.get(extension).copied(),
+                tokens_used: tokens_used.get(extension).copied().unwrap_or(0),
+            })
+            .collect()
+    }
+
+    /// Sampling requests waiting for the user, oldest first
+    pub fn pending_approvals(&self) -> Vec<PendingApproval> {
+        let now = chrono::Utc::now().timestamp();
+        let mut pending: Vec<_> = self
+            .pending
+            .lock()
+            .unwrap()
+            .values()
+            .map(|sample| sample.approval.clone())
+            .filter(|approval| approval.expires_at > now)
+            .collect();
+        pending.sort_by_key(|approval| approval.requested_at);
+        pending
+    }
+
+    /// Allow or deny the sampling request `id`. Returns false if there is no such request.
+    pub fn answer(&self, id: &str, allowed: bool) -> bool {
+        match self.pending.lock().unwrap().remove(id) {
+            Some(sample) => sample.answer.send(allowed).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Set `tokens` aside for `extension`, refusing when that would take it over `budget`.
+    /// Checking and reserving under one lock keeps concurrent requests from overspending.
+    async fn reserve(
+        &self,
+        extension: &str,
+        budget: Option<i32>,
+        tokens: i32,
+    ) -> Result<(), ErrorData> {
+        let mut tokens_used = self.tokens_used.lock().await;
+        let used = tokens_used.entry(extension.to_string()).or_default();
+        check_budget(extension, budget, *used, tokens)?;
+        *used += tokens;
+        Ok(())
+    }
+
+    /// Replace the `reserved` tokens of `extension` with the `spent` ones and save the result
+    async fn settle(&self, extension: &str, reserved: i32, spent: i32) {
+        let mut tokens_used = self.tokens_used.lock().await;
+        let used = tokens_used.entry(extension.to_string()).or_default();
+        *used = (*used - reserved + spent).max(0);
+        if let Some(path) = &self.usage_path {
+            let saved = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| {
+                    std::fs::write(path, serde_json::to_vec(&*tokens_used).unwrap_or_default())
+                });
+            if let Err(e) = saved {
+                tracing::warn!("Failed to save sampling usage: {}", e);
+            }
+        }
+    }
+
+    /// Ask the user whether `extension` may make the completion request `params`, denying it
+    /// when nobody answers in time
+    async fn ask_user(&self, extension: &str, params: &CreateMessageRequestParam) -> bool {
+        let id = format!("sampling_{}", uuid::Uuid::new_v4());
+        let timeout = ApprovalRegistry::timeout();
+        let requested_at = chrono::Utc::now().timestamp();
+        let (answer, answered) = oneshot::channel();
+        let approval = PendingApproval {
+            id: id.clone(),
+            session_id: None,
+            tool_name: format!("{}__sampling", extension),
+            arguments: serde_json::to_value(params).unwrap_or_default(),
+            risk: RiskCategory::Network,
+            prompt: Some(format!(
+                "The {} extension would like to ask the model for a completion of up to {} tokens. Allow?",
+                extension, params.max_tokens
+            )),
+            requested_at,
+            expires_at: requested_at + timeout.as_secs() as i64,
+        };
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(id.clone(), PendingSample { approval, answer });
+        notifications::notify(
+            NotificationKind::ApprovalNeeded,
+            "goose needs approval",
+            &format!("The {} extension would like a completion", extension),
+        );
+
+        let allowed = tokio::time::timeout(timeout, answered).await;
+        self.pending.lock().unwrap().remove(&id);
+        matches!(allowed, Ok(Ok(true)))
+    }
+}
+
+fn refused(message: String) -> ErrorData {
+    ErrorData::new(ErrorCode::INVALID_REQUEST, message, None)
+}
+
+/// Refuse a request from `extension` for up to `tokens` when it would take it over `budget`
+fn check_budget(
+    extension: &str,
+    budget: Option<i32>,
+    tokens_used: i32,
+    tokens: i32,
+) -> Result<(), ErrorData> {
+    match budget {
+        Some(budget) if tokens_used.saturating_add(tokens) > budget => Err(refused(format!(
+            "The {} extension has {} of its sampling budget of {} tokens left, not the {} it asked for",
+            extension,
+            (budget - tokens_used).max(0),
+            budget,
+            tokens
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn to_message(sampling_message: &SamplingMessage) -> Message {
+    let message = match sampling_message.role {
+        Role::User => Message::user(),
+        Role::Assistant => Message::assistant(),
+    };
+    if let Some(text) = sampling_message.content.as_text() {
+        message.with_text(text.text.clone())
+    } else if let Some(image) = sampling_message.content.as_image() {
+        message.with_image(image.data.clone(), image.mime_type.clone())
+    } else {
+        message
+    }
+}
+
+/// Answers the sampling requests of one extension
+pub struct ExtensionSampler {
+    extension: String,
+    context: Arc<SamplingContext>,
+}
+
+impl ExtensionSampler {
+    pub fn new(extension: &str, context: Arc<SamplingContext>) -> Self {
+        Self {
+            extension: extension.to_string(),
+            context,
+        }
+    }
+}
+
+#[async_trait]
+impl SamplingHandler for ExtensionSampler {
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        let budget = ExtensionConfigManager::get_sampling_budgets()
+            .get(&self.extension)
+            .copied();
+        let reserved = i32::try_from(params.max_tokens).unwrap_or(i32::MAX);
+        self.context
+            .reserve(&self.extension, budget, reserved)
+            .await?;
+
+        let result = self.sample(params).await;
+        let spent = result.as_ref().map_or(0, |(_, tokens)| *tokens);
+        self.context.settle(&self.extension, reserved, spent).await;
+        result.map(|(result, _)| result)
+    }
+}
+
+impl ExtensionSampler {
+    /// Ask the user, then the provider, for the completion; with the tokens it took
+    async fn sample(
+        &self,
+        params: CreateMessageRequestParam,
+    ) -> Result<(CreateMessageResult, i32), ErrorData> {
+        if !self.context.ask_user(&self.extension, &params).await {
+            return Err(refused(format!(
+                "The user did not allow the {} extension's completion request",
+                self.extension
+            )));
+        }
+
+        let provider = self.context.provider.lock().await.clone().ok_or_else(|| {
+            ErrorData::new(ErrorCode::INTERNAL_ERROR, "No provider configured", None)
+        })?;
+        let messages: Vec<Message> = params.messages.iter().map(to_message).collect();
+        let system = format!(
+            "{}\n\nAnswer in at most {} tokens.",
+            params.system_prompt.unwrap_or_default(),
+            params.max_tokens
+        );
+        let (response, usage) = provider
+            .complete(system.trim_start(), &messages, &[])
+            .await
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
+
+        // Providers don't take a per-request limit, so a longer answer is cut to it here
+        let mut text = response.as_concat_text();
+        let mut stop_reason = "endTurn";
+        let counter = create_async_token_counter()
+            .await
+            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e, None))?;
+        if let Some(truncated) = counter.truncate_to_tokens(&text, params.max_tokens as usize) {
+            text = truncated;
+            stop_reason = "maxTokens";
+        }
+
+        let tokens = usage.usage.total_tokens.unwrap_or(0);
+        tracing::info!(
+            extension = %self.extension,
+            tokens,
+            "answered sampling request"
+        );
+
+        Ok((
+            CreateMessageResult {
+                model: usage.model,
+                stop_reason: Some(stop_reason.to_string()),
+                message: SamplingMessage {
+                    role: Role::Assistant,
+                    content: Content::text(text),
+                },
+            },
+            tokens,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_must_fit_the_budget() {
+        assert!(check_budget("researcher", None, 1_000_000, 1_000).is_ok());
+        assert!(check_budget("researcher", Some(1_000), 500, 500).is_ok());
+        let error = check_budget("researcher", Some(1_000), 600, 500).unwrap_err();
+        assert!(error
+            .message
+            .contains("400 of its sampling budget of 1000 tokens"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_reservations_cannot_overspend() {
+        let context = Arc::new(SamplingContext::default());
+        let reservations = (0..4).map(|_| {
+            let context = Arc::clone(&context);
+            tokio::spawn(async move { context.reserve("researcher", Some(1_000), 400).await })
+        });
+        let granted = futures::future::join_all(reservations)
+            .await
+            .into_iter()
+            .filter(|result| matches!(result, Ok(Ok(()))))
+            .count();
+        assert_eq!(granted, 2);
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_kept_across_restarts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sampling_usage.json");
+
+        let context = SamplingContext::with_usage_path(Some(path.clone()));
+        context.reserve("researcher", None, 500).await.unwrap();
+        context.settle("researcher", 500, 120).await;
+
+        let restarted = SamplingContext::with_usage_path(Some(path));
+        assert_eq!(
+            restarted.tokens_used.lock().await.get("researcher"),
+            Some(&120)
+        );
+    }
+
+    #[test]
+    fn test_answers_reach_the_waiting_request() {
+        let context = SamplingContext::default();
+        let (answer, mut answered) = oneshot::channel();
+        let approval = PendingApproval {
+            id: "sampling_1".to_string(),
+            session_id: None,
+            tool_name: "researcher__sampling".to_string(),
+            arguments: serde_json::Value::Null,
+            risk: RiskCategory::Network,
+            prompt: None,
+            requested_at: 0,
+            expires_at: i64::MAX,
+        };
+        context
+            .pending
+            .lock()
+            .unwrap()
+            .insert("sampling_1".to_string(), PendingSample { approval, answer });
+
+        assert_eq!(context.pending_approvals().len(), 1);
+        assert!(!context.answer("sampling_2", true));
+        assert!(context.answer("sampling_1", true));
+        assert_eq!(answered.try_recv(), Ok(true));
+        assert!(context.pending_approvals().is_empty());
+    }
+
+    #[test]
+    fn test_sampling_messages_become_goose_messages() {
+        let message = to_message(&SamplingMessage {
+            role: Role::Assistant,
+            content: Content::text("Summarize the diff"),
+        });
+        assert_eq!(message.role, Role::Assistant);
+        assert_eq!(message.as_concat_text(), "Summarize the diff");
+    }
+}
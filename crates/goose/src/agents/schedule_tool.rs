@@ -69,6 +69,13 @@ impl Agent {
     ) -> ToolResult<Vec<Content>> {
         match scheduler.list_scheduled_jobs().await {
             Ok(jobs) => {
+                let jobs: Vec<_> = jobs
+                    .into_iter()
+                    .map(|mut job| {
+                        job.environment = job.environment.redacted();
+                        job
+                    })
+                    .collect();
                 let jobs_json = serde_json::to_string_pretty(&jobs).map_err(|e| {
                     ErrorData::new(
                         ErrorCode::INTERNAL_ERROR,
@@ -187,6 +194,9 @@ impl Agent {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some(execution_mode.to_string()),
+            run_at: None,
+            depends_on: Vec::new(),
+            environment: Default::default(),
         };
 
         match scheduler.add_scheduled_job(job).await {
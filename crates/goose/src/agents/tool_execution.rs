@@ -5,11 +5,13 @@ use async_stream::try_stream;
 use futures::stream::{self, BoxStream};
 use futures::{Stream, StreamExt};
 use tokio::sync::Mutex;
+use tokio::time::timeout;
 use tokio_util::sync::CancellationToken;
 
 use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
-use crate::permission::Permission;
+use crate::notifications::{self, NotificationKind};
+use crate::permission::{classify_tool_call, Permission};
 use mcp_core::ToolResult;
 use rmcp::model::{Content, ServerNotification};
 
@@ -30,6 +32,8 @@ impl From<ToolResult<Vec<Content>>> for ToolCallResult {
 }
 
 use super::agent::{tool_stream, ToolStream};
+use super::approvals::ApprovalRegistry;
+use crate::agents::types::ToolCallContext;
 use crate::agents::Agent;
 use crate::conversation::message::{Message, ToolRequest};
 
@@ -54,49 +58,82 @@ impl Agent {
         permission_manager: &'a mut PermissionManager,
         message_tool_response: Arc<Mutex<Message>>,
         cancellation_token: Option<CancellationToken>,
+        context: &'a ToolCallContext,
     ) -> BoxStream<'a, anyhow::Result<Message>> {
         try_stream! {
             for request in tool_requests {
                 if let Ok(tool_call) = request.tool_call.clone() {
+                    let prompt = "Goose would like to call the above tool. Allow? (y/n):".to_string();
                     let confirmation = Message::user().with_tool_confirmation_request(
                         request.id.clone(),
                         tool_call.name.clone(),
                         tool_call.arguments.clone(),
-                        Some("Goose would like to call the above tool. Allow? (y/n):".to_string()),
+                        Some(prompt.clone()),
                     );
                     yield confirmation;
 
-                    let mut rx = self.confirmation_rx.lock().await;
-                    while let Some((req_id, confirmation)) = rx.recv().await {
-                        if req_id == request.id {
-                            if confirmation.permission == Permission::AllowOnce || confirmation.permission == Permission::AlwaysAllow {
-                                let (req_id, tool_result) = self.dispatch_tool_call(tool_call.clone(), request.id.clone(), cancellation_token.clone()).await;
-                                let mut futures = tool_futures.lock().await;
-
-                                futures.push((req_id, match tool_result {
-                                    Ok(result) => tool_stream(
-                                        result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
-                                        result.result,
-                                    ),
-                                    Err(e) => tool_stream(
-                                        Box::new(stream::empty()),
-                                        futures::future::ready(Err(e)),
-                                    ),
-                                }));
-
-                                if confirmation.permission == Permission::AlwaysAllow {
-                                    permission_manager.update_user_permission(&tool_call.name, PermissionLevel::AlwaysAllow);
+                    let approval_timeout = ApprovalRegistry::timeout();
+                    self.approvals.register(
+                        request.id.clone(),
+                        context.session_id.clone(),
+                        tool_call.name.clone(),
+                        tool_call.arguments.clone(),
+                        classify_tool_call(&tool_call.name, &tool_call.arguments, false),
+                        Some(prompt),
+                        approval_timeout,
+                    );
+                    notifications::notify(
+                        NotificationKind::ApprovalNeeded,
+                        "goose needs approval",
+                        &format!("goose would like to call {}", tool_call.name),
+                    );
+
+                    let answer = {
+                        let mut rx = self.confirmation_rx.lock().await;
+                        timeout(approval_timeout, async {
+                            while let Some((req_id, confirmation)) = rx.recv().await {
+                                if req_id == request.id {
+                                    return Some(confirmation);
                                 }
-                            } else {
-                                // User declined - add declined response
-                                let mut response = message_tool_response.lock().await;
-                                *response = response.clone().with_tool_response(
-                                    request.id.clone(),
-                                    Ok(vec![Content::text(DECLINED_RESPONSE)]),
-                                );
                             }
-                            break; // Exit the loop once the matching `req_id` is found
+                            None
+                        })
+                        .await
+                        .ok()
+                        .flatten()
+                    };
+                    self.approvals.remove(&request.id);
+
+                    // Nobody answered in time, so the call is denied
+                    let permission = answer
+                        .map(|confirmation| confirmation.permission)
+                        .unwrap_or(Permission::DenyOnce);
+
+                    if permission == Permission::AllowOnce || permission == Permission::AlwaysAllow {
+                        let (req_id, tool_result) = self.dispatch_tool_call_in_session(tool_call.clone(), request.id.clone(), cancellation_token.clone(), context).await;
+                        let mut futures = tool_futures.lock().await;
+
+                        futures.push((req_id, match tool_result {
+                            Ok(result) => tool_stream(
+                                result.notification_stream.unwrap_or_else(|| Box::new(stream::empty())),
+                                result.result,
+                            ),
+                            Err(e) => tool_stream(
+                                Box::new(stream::empty()),
+                                futures::future::ready(Err(e)),
+                            ),
+                        }));
+
+                        if permission == Permission::AlwaysAllow {
+                            permission_manager.update_user_permission(&tool_call.name, PermissionLevel::AlwaysAllow);
                         }
+                    } else {
+                        // User declined - add declined response
+                        let mut response = message_tool_response.lock().await;
+                        *response = response.clone().with_tool_response(
+                            request.id.clone(),
+                            Ok(vec![Content::text(DECLINED_RESPONSE)]),
+                        );
                     }
                 }
             }
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use mcp_core::{ToolCall, ToolResult};
+use rmcp::model::{Content, ErrorCode, ErrorData};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::tool_execution::ToolCallResult;
+
+/// A single recorded tool invocation and what it returned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolRecord {
+    pub name: String,
+    pub arguments: serde_json::Value,
+    pub result: Result<Vec<Content>, ErrorData>,
+}
+
+/// Records tool I/O during a live run and replays it later so tests and benchmarks can
+/// exercise the agent loop without touching real extensions. This is the tool-side
+/// counterpart of [`crate::providers::testprovider::TestProvider`].
+///
+/// Calls are keyed by tool name and arguments. Identical calls are replayed in the order
+/// they were recorded, so a tool that returns different output over time still replays
+/// deterministically.
+pub struct ToolRecorder {
+    replaying: bool,
+    records: Arc<Mutex<HashMap<String, VecDeque<ToolRecord>>>>,
+    file_path: PathBuf,
+}
+
+impl ToolRecorder {
+    pub fn new_recording(file_path: impl Into<PathBuf>) -> Self {
+        Self {
+            replaying: false,
+            records: Arc::new(Mutex::new(HashMap::new())),
+            file_path: file_path.into(),
+        }
+    }
+
+    pub fn new_replaying(file_path: impl Into<PathBuf>) -> Result<Self> {
+        let file_path = file_path.into();
+        let records = Self::load_records(&file_path)?;
+
+        Ok(Self {
+            replaying: true,
+            records: Arc::new(Mutex::new(records)),
+            file_path,
+        })
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replaying
+    }
+
+    fn hash_call(name: &str, arguments: &serde_json::Value) -> String {
+        let serialized = serde_json::to_string(&(name, arguments)).unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(serialized.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn load_records(file_path: &Path) -> Result<HashMap<String, VecDeque<ToolRecord>>> {
+        if !file_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content = fs::read_to_string(file_path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save_records(&self) -> Result<()> {
+        if self.replaying {
+            return Ok(());
+        }
+        if let Some(parent) = self.file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let records = self.records.lock().unwrap();
+        let content = serde_json::to_string_pretty(&*records)?;
+        fs::write(&self.file_path, content)?;
+        Ok(())
+    }
+
+    pub fn get_record_count(&self) -> usize {
+        self.records.lock().unwrap().values().map(|v| v.len()).sum()
+    }
+
+    /// Return the next recorded result for this call, consuming it.
+    pub fn replay(&self, tool_call: &ToolCall) -> ToolResult<Vec<Content>> {
+        let hash = Self::hash_call(&tool_call.name, &tool_call.arguments);
+        let mut records = self.records.lock().unwrap();
+        match records.get_mut(&hash).and_then(|queue| queue.pop_front()) {
+            Some(record) => record.result,
+            None => Err(ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!(
+                    "No recorded tool result found for '{}' (input hash: {})",
+                    tool_call.name, hash
+                ),
+                None,
+            )),
+        }
+    }
+
+    /// Wrap a live tool result so its output is captured once the call completes.
+    pub fn record(&self, tool_call: &ToolCall, result: ToolCallResult) -> ToolCallResult {
+        let hash = Self::hash_call(&tool_call.name, &tool_call.arguments);
+        let name = tool_call.name.clone();
+        let arguments = tool_call.arguments.clone();
+        let records = self.records.clone();
+        let inner = result.result;
+
+        ToolCallResult {
+            notification_stream: result.notification_stream,
+            result: Box::new(Box::pin(async move {
+                let output = inner.await;
+                records
+                    .lock()
+                    .unwrap()
+                    .entry(hash)
+                    .or_default()
+                    .push_back(ToolRecord {
+                        name,
+                        arguments,
+                        result: output.clone(),
+                    });
+                output
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}_{}.json", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_in_order() {
+        let path = temp_path("tool_records");
+        let call = ToolCall::new("developer__shell", json!({"command": "date"}));
+
+        let recorder = ToolRecorder::new_recording(&path);
+        for output in ["first", "second"] {
+            let live = ToolCallResult::from(Ok(vec![Content::text(output)]));
+            let result = recorder.record(&call, live).result.await.unwrap();
+            assert_eq!(result[0].as_text().unwrap().text, output);
+        }
+        assert_eq!(recorder.get_record_count(), 2);
+        recorder.save_records().unwrap();
+
+        let replayer = ToolRecorder::new_replaying(&path).unwrap();
+        assert!(replayer.is_replaying());
+        let first = replayer.replay(&call).unwrap();
+        let second = replayer.replay(&call).unwrap();
+        assert_eq!(first[0].as_text().unwrap().text, "first");
+        assert_eq!(second[0].as_text().unwrap().text, "second");
+        assert!(replayer.replay(&call).is_err());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_replay_unknown_call() {
+        let path = temp_path("tool_records_missing");
+        let replayer = ToolRecorder::new_replaying(&path).unwrap();
+        let err = replayer
+            .replay(&ToolCall::new("weather", json!({"city": "Paris"})))
+            .unwrap_err();
+        assert!(err.message.contains("No recorded tool result found"));
+    }
+}
@@ -78,6 +78,74 @@ pub struct FrontendTool {
     pub tool: Tool,
 }
 
+/// Review of the agent's final answer against recipe-defined criteria before a run ends
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CritiqueConfig {
+    /// What a good answer has to satisfy
+    pub criteria: Vec<String>,
+    /// Give the critique back to the model so it can revise its answer. Off by default, in
+    /// which case the critique is only recorded in the session's event log.
+    #[serde(default)]
+    pub include_in_context: bool,
+    /// Reviews per run, so a model that never satisfies its critic still finishes
+    #[serde(default = "default_critique_rounds")]
+    pub max_rounds: u32,
+}
+
+fn default_critique_rounds() -> u32 {
+    1
+}
+
+/// Limits on a single run beyond the turn limit, so autonomous tasks end cleanly instead
+/// of running forever. They are checked between turns, so a batch of tool calls that is
+/// already running gets to finish.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RunLimits {
+    /// Wall-clock time allowed for the run, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration_seconds: Option<u64>,
+    /// Tool calls allowed in the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_calls: Option<u32>,
+    /// Tokens, input and output together, the run may spend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+impl RunLimits {
+    /// Limits left unset here fall back to `other`
+    pub fn or(self, other: RunLimits) -> RunLimits {
+        RunLimits {
+            max_duration_seconds: self.max_duration_seconds.or(other.max_duration_seconds),
+            max_tool_calls: self.max_tool_calls.or(other.max_tool_calls),
+            max_tokens: self.max_tokens.or(other.max_tokens),
+        }
+    }
+}
+
+/// The session a tool call is made in, for the tools and extensions that keep state per session
+#[derive(Debug, Clone, Default)]
+pub struct ToolCallContext {
+    /// Name of the session's file, which is how extensions tell sessions apart
+    pub session_id: Option<String>,
+    /// The session's file, for platform tools that keep artifacts with it
+    pub session_file: Option<PathBuf>,
+}
+
+impl ToolCallContext {
+    pub fn for_session(session: Option<&SessionConfig>) -> Self {
+        let session_file =
+            session.and_then(|session| session::storage::get_path(session.id.clone()).ok());
+        let session_id = session_file
+            .as_ref()
+            .and_then(|path| Some(path.file_stem()?.to_string_lossy().to_string()));
+        Self {
+            session_id,
+            session_file,
+        }
+    }
+}
+
 /// Session configuration for an agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionConfig {
@@ -91,6 +159,12 @@ pub struct SessionConfig {
     pub execution_mode: Option<String>,
     /// Maximum number of turns (iterations) allowed without user input
     pub max_turns: Option<u32>,
+    /// Time and tool call limits for each run
+    #[serde(default)]
+    pub run_limits: RunLimits,
+    /// Review of the final answer of each run, from the session's recipe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critique: Option<CritiqueConfig>,
     /// Retry configuration for automated validation and recovery
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry_config: Option<RetryConfig>,
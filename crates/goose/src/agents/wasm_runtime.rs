@@ -0,0 +1,303 @@
+//! Sandboxed extensions: an MCP server compiled to a WASI (preview 1) `.wasm` module and run
+//! inside goose with wasmtime. The module speaks MCP over stdin/stdout exactly like a stdio
+//! extension, but it can only see the directories, network and environment it is granted.
+
+use super::extension::{ExtensionError, ExtensionResult, ProcessExit};
+use mcp_client::client::{Error, McpClient, McpClientTrait, SamplingHandler};
+use once_cell::sync::Lazy;
+use rmcp::model::{
+    CallToolResult, GetPromptResult, InitializeResult, ListPromptsResult, ListResourcesResult,
+    ListToolsResult, ReadResourceResult, Root, ServerNotification,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use wasmtime::{Engine, Linker, Module, Store};
+use wasmtime_wasi::pipe::{AsyncReadStream, AsyncWriteStream, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{AsyncStdinStream, AsyncStdoutStream, DirPerms, FilePerms, WasiCtxBuilder};
+
+const PIPE_CAPACITY: usize = 64 * 1024;
+const MAX_STDERR_BYTES: usize = 64 * 1024;
+/// How often a running guest yields back to the async runtime, so it can be dropped mid-loop
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+static ENGINE: Lazy<Result<Engine, String>> = Lazy::new(|| {
+    let mut config = wasmtime::Config::new();
+    config.async_support(true);
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config).map_err(|e| e.to_string())?;
+    let ticker = engine.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(EPOCH_TICK);
+        ticker.increment_epoch();
+    });
+    Ok(engine)
+});
+
+fn engine() -> ExtensionResult<&'static Engine> {
+    ENGINE
+        .as_ref()
+        .map_err(|e| ExtensionError::SetupError(format!("failed to start wasm runtime: {}", e)))
+}
+
+/// Everything a wasm extension is allowed to touch. Nothing else is reachable from the guest.
+#[derive(Debug, Clone, Default)]
+pub struct WasmSandbox {
+    /// Host directories mounted at the same path in the guest
+    pub dirs: Vec<PathBuf>,
+    /// Host directories the guest may read but not modify
+    pub read_only_dirs: Vec<PathBuf>,
+    pub allow_network: bool,
+    pub envs: HashMap<String, String>,
+}
+
+impl WasmSandbox {
+    fn build_context(
+        &self,
+        stdin: tokio::io::DuplexStream,
+        stdout: tokio::io::DuplexStream,
+        stderr: MemoryOutputPipe,
+    ) -> ExtensionResult<WasiP1Ctx> {
+        let mut builder = WasiCtxBuilder::new();
+        builder
+            .stdin(AsyncStdinStream::new(AsyncReadStream::new(stdin)))
+            .stdout(AsyncStdoutStream::new(AsyncWriteStream::new(
+                PIPE_CAPACITY,
+                stdout,
+            )))
+            .stderr(stderr);
+
+        let mounts = self
+            .dirs
+            .iter()
+            .map(|dir| (dir, DirPerms::all(), FilePerms::all()))
+            .chain(
+                self.read_only_dirs
+                    .iter()
+                    .map(|dir| (dir, DirPerms::READ, FilePerms::READ)),
+            );
+        for (dir, dir_perms, file_perms) in mounts {
+            let dir = dir.canonicalize().map_err(|e| {
+                ExtensionError::ConfigError(format!(
+                    "cannot mount {} into wasm extension: {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+            let guest_path = dir.to_string_lossy().into_owned();
+            builder
+                .preopened_dir(&dir, guest_path, dir_perms, file_perms)
+                .map_err(|e| ExtensionError::SetupError(e.to_string()))?;
+        }
+
+        if self.allow_network {
+            builder.inherit_network().allow_ip_name_lookup(true);
+        }
+        for (key, value) in &self.envs {
+            builder.env(key, value);
+        }
+        Ok(builder.build_p1())
+    }
+}
+
+async fn run_guest(module: Module, ctx: WasiP1Ctx) -> anyhow::Result<()> {
+    let engine = module.engine();
+    let mut store = Store::new(engine, ctx);
+    store.epoch_deadline_async_yield_and_update(1);
+    let mut linker: Linker<WasiP1Ctx> = Linker::new(engine);
+    preview1::add_to_linker_async(&mut linker, |ctx| ctx)?;
+    let instance = linker.instantiate_async(&mut store, &module).await?;
+    let start = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+    start.call_async(&mut store, ()).await
+}
+
+/// An MCP client connected to a wasm guest. Dropping it stops the guest.
+pub struct WasmClient {
+    client: McpClient,
+    guest: JoinHandle<()>,
+}
+
+impl Drop for WasmClient {
+    fn drop(&mut self) {
+        self.guest.abort();
+    }
+}
+
+impl WasmClient {
+    /// Compile the module at `path` and start it as an MCP server
+    pub async fn load(
+        path: &Path,
+        sandbox: &WasmSandbox,
+        timeout: Duration,
+    ) -> ExtensionResult<Self> {
+        let engine = engine()?;
+        let path = path.to_path_buf();
+        // Compiling is CPU bound and can take a while for large modules
+        let module = tokio::task::spawn_blocking(move || Module::from_file(engine, &path))
+            .await?
+            .map_err(|e| ExtensionError::SetupError(format!("invalid wasm module: {}", e)))?;
+        Self::start(module, sandbox, timeout).await
+    }
+
+    async fn start(
+        module: Module,
+        sandbox: &WasmSandbox,
+        timeout: Duration,
+    ) -> ExtensionResult<Self> {
+        let (guest_stdin, host_stdin) = tokio::io::duplex(PIPE_CAPACITY);
+        let (host_stdout, guest_stdout) = tokio::io::duplex(PIPE_CAPACITY);
+        let stderr = MemoryOutputPipe::new(MAX_STDERR_BYTES);
+        let ctx = sandbox.build_context(guest_stdin, guest_stdout, stderr.clone())?;
+
+        let guest = tokio::spawn(async move {
+            if let Err(e) = run_guest(module, ctx).await {
+                tracing::warn!("wasm extension exited: {:#}", e);
+            }
+        });
+
+        match McpClient::connect((host_stdout, host_stdin), timeout).await {
+            Ok(client) => Ok(Self { client, guest }),
+            Err(error) => {
+                guest.abort();
+                let _ = guest.await;
+                let stderr = String::from_utf8_lossy(&stderr.contents()).into_owned();
+                Err(ProcessExit::new(stderr, error).into())
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl McpClientTrait for WasmClient {
+    async fn list_resources(
+        &self,
+        next_cursor: Option<String>,
+        cancel_token: CancellationToken,
+    ) -> Result<ListResourcesResult, Error> {
+        self.client.list_resources(next_cursor, cancel_token).await
+    }
+
+    async fn read_resource(
+        &self,
+        uri: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<ReadResourceResult, Error> {
+        self.client.read_resource(uri, cancel_token).await
+    }
+
+    async fn subscribe_resource(
+        &self,
+        uri: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<(), Error> {
+        self.client.subscribe_resource(uri, cancel_token).await
+    }
+
+    async fn unsubscribe_resource(
+        &self,
+        uri: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<(), Error> {
+        self.client.unsubscribe_resource(uri, cancel_token).await
+    }
+
+    async fn set_sampling_handler(&self, handler: Arc<dyn SamplingHandler>) {
+        self.client.set_sampling_handler(handler).await
+    }
+
+    async fn set_roots(&self, roots: Vec<Root>) {
+        self.client.set_roots(roots).await
+    }
+
+    async fn list_tools(
+        &self,
+        next_cursor: Option<String>,
+        cancel_token: CancellationToken,
+    ) -> Result<ListToolsResult, Error> {
+        self.client.list_tools(next_cursor, cancel_token).await
+    }
+
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+        cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        self.client.call_tool(name, arguments, cancel_token).await
+    }
+
+    async fn list_prompts(
+        &self,
+        next_cursor: Option<String>,
+        cancel_token: CancellationToken,
+    ) -> Result<ListPromptsResult, Error> {
+        self.client.list_prompts(next_cursor, cancel_token).await
+    }
+
+    async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: Value,
+        cancel_token: CancellationToken,
+    ) -> Result<GetPromptResult, Error> {
+        self.client.get_prompt(name, arguments, cancel_token).await
+    }
+
+    async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
+        self.client.subscribe().await
+    }
+
+    fn get_info(&self) -> Option<&InitializeResult> {
+        self.client.get_info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    // Writes to stderr and exits without ever answering initialize
+    const NOT_AN_MCP_SERVER: &str = r#"
+        (module
+          (import "wasi_snapshot_preview1" "fd_write"
+            (func $fd_write (param i32 i32 i32 i32) (result i32)))
+          (memory (export "memory") 1)
+          (data (i32.const 16) "no tools here\n")
+          (func (export "_start")
+            (i32.store (i32.const 0) (i32.const 16))
+            (i32.store (i32.const 4) (i32.const 14))
+            (drop (call $fd_write (i32.const 2) (i32.const 0) (i32.const 1) (i32.const 8)))))
+    "#;
+
+    #[tokio::test]
+    async fn test_guest_exit_reports_stderr() {
+        let module = Module::new(engine().unwrap(), NOT_AN_MCP_SERVER).unwrap();
+        let err = WasmClient::start(module, &WasmSandbox::default(), Duration::from_secs(5))
+            .await
+            .err()
+            .expect("guest exits before initializing");
+        assert!(err.to_string().contains("no tools here"));
+    }
+
+    #[test]
+    fn test_missing_dir_is_rejected() {
+        let dir = tempdir().unwrap();
+        let sandbox = WasmSandbox {
+            read_only_dirs: vec![dir.path().join("missing")],
+            ..Default::default()
+        };
+        let (a, b) = tokio::io::duplex(1);
+        let err = sandbox
+            .build_context(a, b, MemoryOutputPipe::new(1))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("cannot mount"));
+    }
+}
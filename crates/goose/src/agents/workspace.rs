@@ -0,0 +1,233 @@
+//! The session's working directory as the workspace extensions operate in. Every extension is
+//! told about it as its MCP root, and with `GOOSE_CONFINE_TO_WORKSPACE: true` tool calls whose
+//! path arguments point outside of it are refused before they reach the extension. Shell
+//! commands are checked for the paths they spell out, which keeps a model from wandering off by
+//! mistake but is no sandbox: a command can still build a path at runtime.
+
+use rmcp::model::Root;
+use serde_json::Value;
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::Config;
+
+pub const CONFINE_TO_WORKSPACE_CONFIG_KEY: &str = "GOOSE_CONFINE_TO_WORKSPACE";
+
+/// Argument names file-oriented tools take paths in
+const PATH_ARGUMENTS: &[&str] = &[
+    "path",
+    "paths",
+    "file",
+    "files",
+    "filename",
+    "filepath",
+    "directory",
+    "dir",
+    "cwd",
+];
+/// Argument names ending like this hold paths too, e.g. `file_path` or `output_dir`
+const PATH_ARGUMENT_SUFFIXES: &[&str] = &["_path", "_paths", "_file", "_dir", "_directory"];
+/// Argument names shell-like tools take command lines in
+const COMMAND_ARGUMENTS: &[&str] = &["command", "cmd", "script"];
+
+pub fn confinement_enabled() -> bool {
+    Config::global()
+        .get_param(CONFINE_TO_WORKSPACE_CONFIG_KEY)
+        .unwrap_or(false)
+}
+
+/// The roots extensions are given for `workspace`
+pub fn roots(workspace: &Path) -> Vec<Root> {
+    match url::Url::from_directory_path(workspace) {
+        Ok(uri) => vec![Root {
+            uri: uri.to_string(),
+            name: workspace
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string()),
+        }],
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Remove `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Absolute form of `path` with symlinks resolved as far as the path exists, so a link inside
+/// the workspace can't lead out of it
+fn resolve(path: &Path) -> PathBuf {
+    let normalized = normalize(path);
+    let mut existing = normalized.clone();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        match existing.file_name() {
+            Some(name) => {
+                missing.push(name.to_os_string());
+                existing.pop();
+            }
+            None => break,
+        }
+    }
+    let mut resolved = existing.canonicalize().unwrap_or(existing);
+    resolved.extend(missing.into_iter().rev());
+    resolved
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Whether `path`, relative paths taken from `workspace`, lies within `workspace`
+pub fn is_within(path: &str, workspace: &Path) -> bool {
+    let path = path.strip_prefix("file://").unwrap_or(path);
+    let workspace = resolve(workspace);
+    resolve(&workspace.join(expand_home(path))).starts_with(&workspace)
+}
+
+fn is_path_argument(name: &str) -> bool {
+    PATH_ARGUMENTS.contains(&name)
+        || PATH_ARGUMENT_SUFFIXES
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// The words of `command` that name a path outside of its working directory: absolute ones,
+/// ones under the home directory and ones that climb with `..`
+fn command_paths(command: &str) -> impl Iterator<Item = &str> {
+    command
+        .split(|c: char| {
+            c.is_whitespace()
+                || matches!(
+                    c,
+                    ';' | '|' | '&' | '<' | '>' | '(' | ')' | '=' | '\'' | '"' | '`'
+                )
+        })
+        .filter(|word| {
+            word.starts_with('/')
+                || word.starts_with("~/")
+                || word.starts_with("file://")
+                || word.split('/').any(|part| part == "..")
+        })
+}
+
+fn collect_paths<'a>(arguments: &'a Value, paths: &mut Vec<&'a str>) {
+    match arguments {
+        Value::Object(map) => {
+            for (name, value) in map {
+                match value {
+                    Value::String(command) if COMMAND_ARGUMENTS.contains(&name.as_str()) => {
+                        paths.extend(command_paths(command))
+                    }
+                    Value::String(path) if is_path_argument(name) => paths.push(path),
+                    Value::Array(items) if is_path_argument(name) => {
+                        paths.extend(items.iter().filter_map(Value::as_str))
+                    }
+                    _ => collect_paths(value, paths),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_paths(item, paths)),
+        _ => {}
+    }
+}
+
+/// The first path argument in `arguments` that points outside of `workspace`
+pub fn path_outside<'a>(arguments: &'a Value, workspace: &Path) -> Option<&'a str> {
+    let mut paths = Vec::new();
+    collect_paths(arguments, &mut paths);
+    paths.into_iter().find(|path| {
+        // Arguments named like paths sometimes hold URLs, which are not ours to confine
+        let is_url = path.contains("://") && !path.starts_with("file://");
+        !path.is_empty() && !is_url && !is_within(path, workspace)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_paths_must_stay_in_the_workspace() {
+        let dir = tempdir().unwrap();
+        let workspace = dir.path().join("project");
+        std::fs::create_dir_all(workspace.join("src")).unwrap();
+
+        assert!(is_within("src/main.rs", &workspace));
+        assert!(is_within(
+            workspace.join("new/file.rs").to_str().unwrap(),
+            &workspace
+        ));
+        assert!(!is_within("../secrets.txt", &workspace));
+        assert!(!is_within("src/../../secrets.txt", &workspace));
+        assert!(!is_within("/etc/passwd", &workspace));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_cannot_lead_out() {
+        let dir = tempdir().unwrap();
+        let workspace = dir.path().join("project");
+        std::fs::create_dir_all(&workspace).unwrap();
+        std::os::unix::fs::symlink(dir.path(), workspace.join("escape")).unwrap();
+
+        assert!(!is_within("escape/secrets.txt", &workspace));
+    }
+
+    #[test]
+    fn test_path_outside_finds_path_arguments() {
+        let workspace = tempdir().unwrap();
+        let inside = json!({"command": "view", "path": "README.md"});
+        assert_eq!(path_outside(&inside, workspace.path()), None);
+
+        let outside = json!({"edits": [{"file_path": "/etc/hosts", "text": "/etc/passwd"}]});
+        assert_eq!(path_outside(&outside, workspace.path()), Some("/etc/hosts"));
+
+        let url = json!({"path": "https://example.com/docs"});
+        assert_eq!(path_outside(&url, workspace.path()), None);
+    }
+
+    #[test]
+    fn test_shell_commands_are_checked_for_paths() {
+        let workspace = tempdir().unwrap();
+        let inside = json!({"command": "cargo test && cat src/lib.rs | grep fn > out.txt"});
+        assert_eq!(path_outside(&inside, workspace.path()), None);
+
+        let absolute = json!({"command": "cat /etc/passwd"});
+        assert_eq!(
+            path_outside(&absolute, workspace.path()),
+            Some("/etc/passwd")
+        );
+
+        let climbing = json!({"command": "cd ../other && rm -rf build"});
+        assert_eq!(path_outside(&climbing, workspace.path()), Some("../other"));
+
+        let redirected = json!({"cmd": "echo hi >~/.bashrc"});
+        assert_eq!(
+            path_outside(&redirected, workspace.path()),
+            Some("~/.bashrc")
+        );
+    }
+
+    #[test]
+    fn test_roots_point_at_the_workspace() {
+        let workspace = tempdir().unwrap();
+        let roots = roots(workspace.path());
+        assert_eq!(roots.len(), 1);
+        assert!(roots[0].uri.starts_with("file://"));
+    }
+}
@@ -0,0 +1,227 @@
+//! Opt-in usage analytics. Nothing is recorded unless GOOSE_ANALYTICS_ENABLED is set.
+//! Events stay in a local file, and are only sent anywhere when
+//! GOOSE_ANALYTICS_ENDPOINT is also configured. Events never carry message content,
+//! file paths, session ids or anything else that identifies a user.
+
+use crate::config::{Config, APP_STRATEGY};
+use anyhow::Result;
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+/// Config flag that turns analytics on; off unless explicitly set to true
+pub const ANALYTICS_ENABLED_KEY: &str = "GOOSE_ANALYTICS_ENABLED";
+/// Optional URL that also receives each event as JSON
+pub const ANALYTICS_ENDPOINT_KEY: &str = "GOOSE_ANALYTICS_ENDPOINT";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnalyticsEvent {
+    /// A goose feature was used, e.g. "desktop_session" or "elevation"
+    FeatureUsed { feature: String },
+    /// Something failed, reduced to a coarse category such as "rate_limit"
+    Error { category: String },
+    /// A model produced a response
+    ModelUsed { model: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedEvent {
+    timestamp: i64,
+    version: String,
+    #[serde(flatten)]
+    event: AnalyticsEvent,
+}
+
+/// Counts of everything recorded so far
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    pub enabled: bool,
+    /// Whether events are also sent to GOOSE_ANALYTICS_ENDPOINT
+    pub remote: bool,
+    pub total_events: usize,
+    /// Unix timestamp (seconds) of the oldest recorded event
+    pub since: Option<i64>,
+    pub features: BTreeMap<String, usize>,
+    pub errors: BTreeMap<String, usize>,
+    pub models: BTreeMap<String, usize>,
+}
+
+pub struct Analytics {
+    enabled: bool,
+    endpoint: Option<String>,
+    path: PathBuf,
+}
+
+impl Analytics {
+    pub fn new<P: AsRef<Path>>(path: P, enabled: bool, endpoint: Option<String>) -> Self {
+        Self {
+            enabled,
+            endpoint,
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Analytics as configured, stored in the goose data dir
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let path = choose_app_strategy(APP_STRATEGY.clone())?
+            .data_dir()
+            .join("analytics.jsonl");
+        Ok(Self::new(
+            path,
+            config.get_param(ANALYTICS_ENABLED_KEY).unwrap_or(false),
+            config.get_param(ANALYTICS_ENDPOINT_KEY).ok(),
+        ))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn record(&self, event: AnalyticsEvent) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let recorded = RecordedEvent {
+            timestamp: chrono::Utc::now().timestamp(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            event,
+        };
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&recorded)?)?;
+
+        if let Some(endpoint) = &self.endpoint {
+            // Sending is best effort; the local record is what /stats reports
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                let endpoint = endpoint.clone();
+                handle.spawn(async move {
+                    let result = reqwest::Client::new()
+                        .post(&endpoint)
+                        .json(&recorded)
+                        .send()
+                        .await
+                        .and_then(|r| r.error_for_status());
+                    if let Err(e) = result {
+                        tracing::debug!("Failed to send analytics event: {}", e);
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> Result<UsageStats> {
+        let mut stats = UsageStats {
+            enabled: self.enabled,
+            remote: self.enabled && self.endpoint.is_some(),
+            ..Default::default()
+        };
+        if !self.path.exists() {
+            return Ok(stats);
+        }
+
+        for line in BufReader::new(fs::File::open(&self.path)?).lines() {
+            let Ok(recorded) = serde_json::from_str::<RecordedEvent>(&line?) else {
+                continue;
+            };
+            stats.total_events += 1;
+            stats.since = Some(
+                stats
+                    .since
+                    .map_or(recorded.timestamp, |since| since.min(recorded.timestamp)),
+            );
+            let (counts, key) = match recorded.event {
+                AnalyticsEvent::FeatureUsed { feature } => (&mut stats.features, feature),
+                AnalyticsEvent::Error { category } => (&mut stats.errors, category),
+                AnalyticsEvent::ModelUsed { model } => (&mut stats.models, model),
+            };
+            *counts.entry(key).or_default() += 1;
+        }
+        Ok(stats)
+    }
+
+    /// The file records are kept in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Delete everything recorded locally
+    pub fn clear(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Record an event with the global config. A no-op unless analytics are enabled, and
+/// never fails the caller.
+pub fn record(event: AnalyticsEvent) {
+    let result = Analytics::from_config(Config::global()).and_then(|a| a.record(event));
+    if let Err(e) = result {
+        tracing::debug!("Failed to record analytics event: {}", e);
+    }
+}
+
+pub fn record_feature(feature: &str) {
+    record(AnalyticsEvent::FeatureUsed {
+        feature: feature.to_string(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_disabled_records_nothing() -> Result<()> {
+        let dir = tempdir()?;
+        let analytics = Analytics::new(dir.path().join("analytics.jsonl"), false, None);
+        analytics.record(AnalyticsEvent::FeatureUsed {
+            feature: "desktop_session".to_string(),
+        })?;
+        assert!(!dir.path().join("analytics.jsonl").exists());
+        assert_eq!(analytics.stats()?.total_events, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_aggregate_events() -> Result<()> {
+        let dir = tempdir()?;
+        let analytics = Analytics::new(dir.path().join("analytics.jsonl"), true, None);
+        for model in ["gpt-4o", "gpt-4o", "claude-sonnet-4"] {
+            analytics.record(AnalyticsEvent::ModelUsed {
+                model: model.to_string(),
+            })?;
+        }
+        analytics.record(AnalyticsEvent::Error {
+            category: "rate_limit".to_string(),
+        })?;
+
+        let stats = analytics.stats()?;
+        assert!(stats.enabled);
+        assert!(!stats.remote);
+        assert_eq!(stats.total_events, 4);
+        assert!(stats.since.is_some());
+        assert_eq!(stats.models.get("gpt-4o"), Some(&2));
+        assert_eq!(stats.errors.get("rate_limit"), Some(&1));
+        assert!(stats.features.is_empty());
+
+        analytics.clear()?;
+        assert_eq!(analytics.stats()?.total_events, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,291 @@
+//! Flags runs that look like they've gone wrong: a response using far more tokens than the
+//! ones before it, or the agent going in circles. Loops are found by the agent's
+//! [`LoopGuard`](crate::agents::loop_guard), tuned under `GOOSE_LOOP_GUARD`, and raised here
+//! as alerts.
+//!
+//! Alerts are kept in the goose data dir, served from `GET /alerts`, and shown through the
+//! `anomaly` entry of `GOOSE_NOTIFICATIONS`. Token spikes can be tuned under
+//! `GOOSE_ANOMALIES`:
+//!
+//! ```yaml
+//! GOOSE_ANOMALIES:
+//!   token_spike_factor: 3.0    # a response using this many times the average so far
+//! ```
+
+use crate::agents::loop_guard::LoopDetection;
+use crate::config::{Config, APP_STRATEGY};
+use crate::notifications::{self, NotificationKind};
+use anyhow::Result;
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+pub const ANOMALIES_KEY: &str = "GOOSE_ANOMALIES";
+
+/// Responses seen before token usage is compared against their average
+const MIN_RESPONSES_FOR_SPIKE: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyConfig {
+    pub token_spike_factor: f64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            token_spike_factor: 3.0,
+        }
+    }
+}
+
+impl AnomalyConfig {
+    pub fn load() -> Self {
+        Config::global()
+            .get_param(ANOMALIES_KEY)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    TokenSpike,
+    /// The same tool call failing several times in a row
+    RepeatedFailures,
+    /// Any other loop the agent's loop guard noticed
+    Looping,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub kind: AlertKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub message: String,
+}
+
+/// Watches one run for anomalies
+#[derive(Debug)]
+pub struct AnomalyMonitor {
+    config: AnomalyConfig,
+    session_id: Option<String>,
+    response_tokens: Vec<i64>,
+}
+
+impl AnomalyMonitor {
+    pub fn new(config: AnomalyConfig, session_id: Option<String>) -> Self {
+        Self {
+            config,
+            session_id,
+            response_tokens: Vec::new(),
+        }
+    }
+
+    fn alert(&self, kind: AlertKind, message: String) -> Alert {
+        Alert {
+            timestamp: chrono::Utc::now().timestamp(),
+            kind,
+            session_id: self.session_id.clone(),
+            message,
+        }
+    }
+
+    /// A model response used `tokens` in total
+    pub fn observe_usage(&mut self, tokens: i64) -> Option<Alert> {
+        let seen = self.response_tokens.len();
+        let average = self.response_tokens.iter().sum::<i64>() as f64 / seen.max(1) as f64;
+        self.response_tokens.push(tokens);
+        if seen < MIN_RESPONSES_FOR_SPIKE || average <= 0.0 {
+            return None;
+        }
+        let factor = tokens as f64 / average;
+        (factor >= self.config.token_spike_factor).then(|| {
+            self.alert(
+                AlertKind::TokenSpike,
+                format!(
+                    "A response used {} tokens, {:.1}x the average of {:.0}",
+                    tokens, factor, average
+                ),
+            )
+        })
+    }
+
+    /// The alert for a loop the loop guard found
+    pub fn loop_alert(&self, detection: &LoopDetection) -> Alert {
+        let kind = match detection {
+            LoopDetection::RepeatedFailure { .. } => AlertKind::RepeatedFailures,
+            _ => AlertKind::Looping,
+        };
+        self.alert(kind, detection.describe())
+    }
+}
+
+fn alerts_path() -> Result<PathBuf> {
+    Ok(choose_app_strategy(APP_STRATEGY.clone())?
+        .data_dir()
+        .join("alerts.jsonl"))
+}
+
+fn append_alert(path: &Path, alert: &Alert) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(alert)?)?;
+    Ok(())
+}
+
+fn load_alerts(path: &Path, since: Option<i64>) -> Result<Vec<Alert>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut alerts = Vec::new();
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let Ok(alert) = serde_json::from_str::<Alert>(&line?) else {
+            continue;
+        };
+        if since.is_none_or(|since| alert.timestamp >= since) {
+            alerts.push(alert);
+        }
+    }
+    alerts.reverse();
+    Ok(alerts)
+}
+
+/// Keep the alert and notify the user as configured. Never fails the caller.
+pub fn raise(alert: Alert) {
+    tracing::warn!("Anomaly detected: {}", alert.message);
+    if let Err(e) = alerts_path().and_then(|path| append_alert(&path, &alert)) {
+        tracing::error!("Failed to record alert: {}", e);
+    }
+    notifications::notify(
+        NotificationKind::Anomaly,
+        "goose noticed a problem",
+        &alert.message,
+    );
+}
+
+/// Alerts raised at or after `since` (Unix seconds), most recent first
+pub fn read_alerts(since: Option<i64>) -> Result<Vec<Alert>> {
+    load_alerts(&alerts_path()?, since)
+}
+
+fn remove_alerts_in(path: &Path, matches: impl Fn(&Alert) -> bool, dry_run: bool) -> Result<usize> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let mut kept = Vec::new();
+    let mut removed = 0;
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let line = line?;
+        match serde_json::from_str::<Alert>(&line) {
+            Ok(alert) if matches(&alert) => removed += 1,
+            _ => kept.push(line),
+        }
+    }
+    if removed > 0 && !dry_run {
+        let temp_file = path.with_extension("tmp");
+        let content: String = kept.iter().map(|line| format!("{}\n", line)).collect();
+        fs::write(&temp_file, content)?;
+        fs::rename(&temp_file, path)?;
+    }
+    Ok(removed)
+}
+
+/// Remove the alerts `matches` picks, returning how many there were. With `dry_run` they
+/// are only counted.
+pub fn remove_alerts(matches: impl Fn(&Alert) -> bool, dry_run: bool) -> Result<usize> {
+    remove_alerts_in(&alerts_path()?, matches, dry_run)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_remove_alerts() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("alerts.jsonl");
+        for session_id in ["s1", "s2", "s1"] {
+            let alert = Alert {
+                timestamp: 0,
+                kind: AlertKind::Looping,
+                session_id: Some(session_id.to_string()),
+                message: "looping".to_string(),
+            };
+            append_alert(&path, &alert)?;
+        }
+        let in_s1 = |alert: &Alert| alert.session_id.as_deref() == Some("s1");
+
+        assert_eq!(remove_alerts_in(&path, in_s1, true)?, 2);
+        assert_eq!(load_alerts(&path, None)?.len(), 3);
+        assert_eq!(remove_alerts_in(&path, in_s1, false)?, 2);
+        let left = load_alerts(&path, None)?;
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].session_id.as_deref(), Some("s2"));
+        Ok(())
+    }
+
+    fn monitor() -> AnomalyMonitor {
+        AnomalyMonitor::new(AnomalyConfig::default(), Some("s1".to_string()))
+    }
+
+    #[test]
+    fn test_token_spike() {
+        let mut monitor = monitor();
+        for tokens in [1000, 1200, 800] {
+            assert!(monitor.observe_usage(tokens).is_none());
+        }
+        assert!(monitor.observe_usage(2000).is_none());
+        let alert = monitor
+            .observe_usage(5000)
+            .expect("spike should be flagged");
+        assert_eq!(alert.kind, AlertKind::TokenSpike);
+        assert_eq!(alert.session_id.as_deref(), Some("s1"));
+    }
+
+    #[test]
+    fn test_loop_alerts() {
+        let alert = monitor().loop_alert(&LoopDetection::RepeatedFailure {
+            tool_name: "shell".to_string(),
+            times: 3,
+        });
+        assert_eq!(alert.kind, AlertKind::RepeatedFailures);
+        assert_eq!(alert.session_id.as_deref(), Some("s1"));
+
+        let alert = monitor().loop_alert(&LoopDetection::RepeatedCall {
+            tool_name: "shell".to_string(),
+            times: 4,
+        });
+        assert_eq!(alert.kind, AlertKind::Looping);
+        assert!(alert.message.contains("4 times"));
+    }
+
+    #[test]
+    fn test_alerts_are_read_newest_first() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("alerts.jsonl");
+        assert!(load_alerts(&path, None)?.is_empty());
+
+        let mut first = monitor().alert(AlertKind::Looping, "first".to_string());
+        first.timestamp = 100;
+        let mut second = monitor().alert(AlertKind::TokenSpike, "second".to_string());
+        second.timestamp = 200;
+        append_alert(&path, &first)?;
+        append_alert(&path, &second)?;
+
+        let alerts = load_alerts(&path, None)?;
+        assert_eq!(alerts[0].message, "second");
+        assert_eq!(alerts[1].message, "first");
+        assert_eq!(load_alerts(&path, Some(150))?.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,712 @@
+//! Backing up everything a user would miss after losing a machine into one zip: sessions,
+//! config (secrets left out), recipes, memories and schedules.
+//!
+//! The archive carries a `manifest.json` listing every file with its SHA-256, and restore
+//! checks every selected file against it before writing anything, so a damaged archive
+//! never leaves a half-restored state behind. Sessions from every GOOSE_SESSION_ROOTS root
+//! are backed up, and each restored session goes back to the root its working directory
+//! places it in.
+
+use crate::config::team::{export_config, import_config};
+use crate::config::{Config, APP_STRATEGY};
+use crate::session::{self, SessionMetadata, SessionRoot};
+use anyhow::{anyhow, bail, Context, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use utoipa::ToSchema;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_PATH: &str = "manifest.json";
+const CONFIG_PATH: &str = "config/config.yaml";
+const SCHEDULES_PATH: &str = "schedules/schedules.json";
+const SESSIONS_PREFIX: &str = "sessions";
+/// Largest manifest read, so a crafted archive cannot exhaust memory before anything is checked
+const MAX_MANIFEST_BYTES: u64 = 16 * 1024 * 1024;
+/// Largest total size of the files one restore reads, which are held in memory until verified
+const MAX_RESTORE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupCategory {
+    Sessions,
+    Config,
+    Recipes,
+    Memories,
+    Schedules,
+}
+
+impl BackupCategory {
+    pub const ALL: [BackupCategory; 5] = [
+        BackupCategory::Sessions,
+        BackupCategory::Config,
+        BackupCategory::Recipes,
+        BackupCategory::Memories,
+        BackupCategory::Schedules,
+    ];
+}
+
+impl FromStr for BackupCategory {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        serde_json::from_value(serde_json::Value::String(s.to_lowercase())).map_err(|_| {
+            anyhow!(
+                "Unknown backup category '{}'; expected sessions, config, recipes, memories or schedules",
+                s
+            )
+        })
+    }
+}
+
+/// Where each category lives on disk
+#[derive(Debug, Clone)]
+pub struct BackupLocations {
+    /// The default session directory
+    pub sessions: PathBuf,
+    /// Extra session roots from GOOSE_SESSION_ROOTS
+    pub session_roots: Vec<SessionRoot>,
+    pub recipes: PathBuf,
+    pub memories: PathBuf,
+    pub schedules_file: PathBuf,
+    pub scheduled_recipes: PathBuf,
+}
+
+impl BackupLocations {
+    pub fn from_app_dirs() -> Result<Self> {
+        let strategy = choose_app_strategy(APP_STRATEGY.clone())?;
+        Ok(Self {
+            sessions: session::ensure_session_dir()?,
+            session_roots: session::configured_session_roots(),
+            recipes: strategy.in_config_dir("recipes"),
+            memories: strategy.in_config_dir("memory"),
+            schedules_file: strategy.data_dir().join("schedules.json"),
+            scheduled_recipes: strategy.data_dir().join("scheduled_recipes"),
+        })
+    }
+
+    /// Directories copied whole for a category, with the archive prefix each is stored under.
+    /// Where two directories hold the same file, the earlier one wins.
+    fn dirs(&self, category: BackupCategory) -> Vec<(&'static str, &Path)> {
+        match category {
+            BackupCategory::Sessions => std::iter::once(self.sessions.as_path())
+                .chain(self.session_roots.iter().map(|root| root.path.as_path()))
+                .map(|dir| (SESSIONS_PREFIX, dir))
+                .collect(),
+            BackupCategory::Recipes => vec![("recipes", self.recipes.as_path())],
+            BackupCategory::Memories => vec![("memories", self.memories.as_path())],
+            BackupCategory::Schedules => vec![(
+                "schedules/scheduled_recipes",
+                self.scheduled_recipes.as_path(),
+            )],
+            BackupCategory::Config => vec![],
+        }
+    }
+
+    /// The directory a restored session goes to: wherever it already is, or else the root
+    /// its working directory places it in
+    fn session_dir(&self, name: &str, working_dir: &Path) -> PathBuf {
+        let file_name = format!("{}.jsonl", name);
+        std::iter::once(&self.sessions)
+            .chain(self.session_roots.iter().map(|root| &root.path))
+            .find(|dir| dir.join(&file_name).exists())
+            .cloned()
+            .or_else(|| session::storage::placement_dir(&self.session_roots, working_dir))
+            .unwrap_or_else(|| self.sessions.clone())
+    }
+
+    /// Local path an archive entry restores to; None for entries handled specially.
+    /// `session_dirs` maps each restored session to its directory, which its sidecar files
+    /// (`<name>.pins.json`, `<name>.attachments/...`) follow.
+    fn restore_path(
+        &self,
+        entry: &BackupEntry,
+        session_dirs: &BTreeMap<String, PathBuf>,
+    ) -> Option<PathBuf> {
+        if entry.path == SCHEDULES_PATH {
+            return Some(self.schedules_file.clone());
+        }
+        if entry.category == BackupCategory::Sessions {
+            let rest = entry
+                .path
+                .strip_prefix(SESSIONS_PREFIX)?
+                .strip_prefix('/')?;
+            let first = rest.split('/').next().unwrap_or(rest);
+            let dir = session_dirs
+                .iter()
+                .filter(|(name, _)| {
+                    first
+                        .strip_prefix(name.as_str())
+                        .is_some_and(|suffix| suffix.starts_with('.'))
+                })
+                .max_by_key(|(name, _)| name.len())
+                .map(|(_, dir)| dir.as_path())
+                .unwrap_or(&self.sessions);
+            return Some(dir.join(rest));
+        }
+        self.dirs(entry.category)
+            .into_iter()
+            .find_map(|(prefix, dir)| {
+                entry
+                    .path
+                    .strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_prefix('/'))
+                    .map(|rest| dir.join(rest))
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BackupEntry {
+    /// Path inside the archive, `/`-separated
+    pub path: String,
+    pub category: BackupCategory,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub goose_version: String,
+    pub created_at: String,
+    pub entries: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RestoreSummary {
+    /// Files written, including the config
+    pub restored: usize,
+    /// Files left alone because they already exist and overwrite was off
+    pub skipped: usize,
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub(crate) fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Where `goose backup create` keeps archives unless given another path, which is also where
+/// purges look for copies of the data they remove
+pub fn backups_dir() -> Result<PathBuf> {
+    Ok(choose_app_strategy(APP_STRATEGY.clone())?
+        .data_dir()
+        .join("backups"))
+}
+
+/// Reject archive paths that could escape the directory they restore into
+fn is_safe_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Write a backup of `categories` as a zip to `writer`
+pub fn create_backup<W: Write + Seek>(
+    writer: W,
+    config: &Config,
+    locations: &BackupLocations,
+    categories: &[BackupCategory],
+) -> Result<BackupManifest> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(writer);
+    let mut entries = Vec::new();
+
+    let mut add = |zip: &mut ZipWriter<W>, path: String, category, bytes: &[u8]| -> Result<()> {
+        zip.start_file(path.as_str(), options)?;
+        zip.write_all(bytes)?;
+        entries.push(BackupEntry {
+            path,
+            category,
+            size: bytes.len() as u64,
+            sha256: sha256_hex(bytes),
+        });
+        Ok(())
+    };
+
+    for &category in categories {
+        if category == BackupCategory::Config {
+            let yaml = export_config(config)?;
+            add(&mut zip, CONFIG_PATH.to_string(), category, yaml.as_bytes())?;
+        }
+        if category == BackupCategory::Schedules && locations.schedules_file.exists() {
+            let bytes = fs::read(&locations.schedules_file)?;
+            add(&mut zip, SCHEDULES_PATH.to_string(), category, &bytes)?;
+        }
+        let mut added = HashSet::new();
+        for (prefix, dir) in locations.dirs(category) {
+            let mut files = Vec::new();
+            collect_files(dir, &mut files)?;
+            files.sort();
+            for file in files {
+                let relative = file.strip_prefix(dir)?.to_string_lossy().replace('\\', "/");
+                let path = format!("{}/{}", prefix, relative);
+                if !added.insert(path.clone()) {
+                    continue;
+                }
+                let bytes = fs::read(&file)
+                    .with_context(|| format!("Failed to read {}", file.display()))?;
+                add(&mut zip, path, category, &bytes)?;
+            }
+        }
+    }
+
+    let manifest = BackupManifest {
+        format_version: FORMAT_VERSION,
+        goose_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        entries,
+    };
+    zip.start_file(MANIFEST_PATH, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
+    Ok(manifest)
+}
+
+/// Read an entry, failing once it decompresses to more than `max_bytes`
+fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    path: &str,
+    max_bytes: u64,
+) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    archive
+        .by_name(path)
+        .with_context(|| format!("{} is missing from the backup", path))?
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > max_bytes {
+        bail!(
+            "{} is larger than the backup says; the backup is damaged",
+            path
+        );
+    }
+    Ok(bytes)
+}
+
+fn read_manifest_entry<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Result<BackupManifest> {
+    serde_json::from_slice(&read_entry(archive, MANIFEST_PATH, MAX_MANIFEST_BYTES)?)
+        .context("Backup manifest is corrupt")
+}
+
+/// The working directory recorded in the first line of a session file
+fn session_working_dir(bytes: &[u8]) -> Option<PathBuf> {
+    let first_line = bytes.split(|b| *b == b'\n').next()?;
+    serde_json::from_slice::<SessionMetadata>(first_line)
+        .ok()
+        .map(|metadata| metadata.working_dir)
+}
+
+/// Read the manifest of a backup without restoring anything
+pub fn read_manifest<R: Read + Seek>(reader: R) -> Result<BackupManifest> {
+    let mut archive = ZipArchive::new(reader).context("Not a goose backup archive")?;
+    let manifest = read_manifest_entry(&mut archive)?;
+    if manifest.format_version > FORMAT_VERSION {
+        bail!(
+            "Backup format {} is newer than this goose supports ({})",
+            manifest.format_version,
+            FORMAT_VERSION
+        );
+    }
+    Ok(manifest)
+}
+
+/// Rewrite the backup at `path` without the entries `remove` picks, returning their paths;
+/// with `dry_run` the archive is left as it is
+pub fn remove_entries(
+    path: &Path,
+    remove: impl Fn(&BackupEntry) -> bool,
+    dry_run: bool,
+) -> Result<Vec<String>> {
+    let mut archive =
+        ZipArchive::new(fs::File::open(path)?).context("Not a goose backup archive")?;
+    let mut manifest = read_manifest_entry(&mut archive)?;
+    let (removed, kept): (Vec<BackupEntry>, Vec<BackupEntry>) =
+        manifest.entries.drain(..).partition(|entry| remove(entry));
+    let removed: Vec<String> = removed.into_iter().map(|entry| entry.path).collect();
+    if dry_run || removed.is_empty() {
+        return Ok(removed);
+    }
+
+    // Written next to the original and moved over it, so a failure leaves the old archive
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let temp = tempfile::NamedTempFile::new_in(dir)?;
+    let mut zip = ZipWriter::new(temp.reopen()?);
+    for entry in &kept {
+        zip.raw_copy_file(archive.by_name(&entry.path)?)?;
+    }
+    manifest.entries = kept;
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(MANIFEST_PATH, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
+    temp.persist(path)?;
+    Ok(removed)
+}
+
+/// Restore the selected categories from a backup. Existing files are kept unless
+/// `overwrite` is set; the config is always merged over the current one.
+pub fn restore_backup<R: Read + Seek>(
+    reader: R,
+    config: &Config,
+    locations: &BackupLocations,
+    categories: &[BackupCategory],
+    overwrite: bool,
+) -> Result<RestoreSummary> {
+    let mut archive = ZipArchive::new(reader).context("Not a goose backup archive")?;
+    let manifest = read_manifest_entry(&mut archive)?;
+    let selected: Vec<&BackupEntry> = manifest
+        .entries
+        .iter()
+        .filter(|e| categories.contains(&e.category))
+        .collect();
+    let total = selected
+        .iter()
+        .fold(0u64, |total, entry| total.saturating_add(entry.size));
+    if total > MAX_RESTORE_BYTES {
+        bail!(
+            "The selected backup files add up to {} bytes, more than the {} a restore reads",
+            total,
+            MAX_RESTORE_BYTES
+        );
+    }
+
+    // Verify everything first so a bad archive changes nothing
+    let mut files = Vec::new();
+    let mut session_dirs = BTreeMap::new();
+    for entry in selected {
+        if !is_safe_path(&entry.path) {
+            bail!("Backup contains an unsafe path: {}", entry.path);
+        }
+        let bytes = read_entry(&mut archive, &entry.path, entry.size)?;
+        if sha256_hex(&bytes) != entry.sha256 {
+            bail!(
+                "Checksum mismatch for {}; the backup is damaged",
+                entry.path
+            );
+        }
+        let session_name = entry
+            .path
+            .strip_prefix(SESSIONS_PREFIX)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .and_then(|rest| rest.strip_suffix(".jsonl"))
+            .filter(|name| entry.category == BackupCategory::Sessions && !name.contains('/'));
+        if let Some(name) = session_name {
+            let working_dir = session_working_dir(&bytes).unwrap_or_default();
+            session_dirs.insert(name.to_string(), locations.session_dir(name, &working_dir));
+        }
+        files.push((entry, bytes));
+    }
+
+    let mut summary = RestoreSummary::default();
+    for (entry, bytes) in files {
+        if entry.path == CONFIG_PATH {
+            let content = String::from_utf8(bytes).context("Backed up config is not UTF-8")?;
+            import_config(config, &content, false)?;
+            summary.restored += 1;
+            continue;
+        }
+
+        let Some(target) = locations.restore_path(entry, &session_dirs) else {
+            tracing::warn!("Skipping unrecognised backup entry {}", entry.path);
+            continue;
+        };
+        if target.exists() && !overwrite {
+            summary.skipped += 1;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, bytes)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+        summary.restored += 1;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    fn locations(root: &Path) -> BackupLocations {
+        BackupLocations {
+            sessions: root.join("sessions"),
+            session_roots: Vec::new(),
+            recipes: root.join("recipes"),
+            memories: root.join("memory"),
+            schedules_file: root.join("schedules.json"),
+            scheduled_recipes: root.join("scheduled_recipes"),
+        }
+    }
+
+    fn config(root: &Path) -> Config {
+        Config::new_with_file_secrets(root.join("config.yaml"), root.join("secrets.yaml")).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_with_selective_restore() -> Result<()> {
+        let source = tempdir()?;
+        let from = locations(source.path());
+        fs::create_dir_all(from.sessions.join("nested"))?;
+        fs::write(from.sessions.join("a.jsonl"), "{}\n")?;
+        fs::write(from.sessions.join("nested/b.jsonl"), "{}\n")?;
+        fs::create_dir_all(&from.memories)?;
+        fs::write(from.memories.join("prefs.txt"), "likes tabs")?;
+        fs::write(&from.schedules_file, "[]")?;
+        let source_config = config(source.path());
+        source_config.set_param("GOOSE_MODEL", Value::String("gpt-4o".to_string()))?;
+        source_config.set_secret("OPENAI_API_KEY", Value::String("sk-secret".to_string()))?;
+
+        let mut archive = Cursor::new(Vec::new());
+        let manifest = create_backup(&mut archive, &source_config, &from, &BackupCategory::ALL)?;
+        assert_eq!(manifest.entries.len(), 5);
+        assert!(manifest
+            .entries
+            .iter()
+            .any(|e| e.path == "sessions/nested/b.jsonl"));
+
+        let target = tempdir()?;
+        let to = locations(target.path());
+        let target_config = config(target.path());
+        fs::create_dir_all(&to.sessions)?;
+        fs::write(to.sessions.join("a.jsonl"), "newer")?;
+
+        archive.set_position(0);
+        let summary = restore_backup(
+            &mut archive,
+            &target_config,
+            &to,
+            &[BackupCategory::Sessions, BackupCategory::Config],
+            false,
+        )?;
+        assert_eq!(
+            summary,
+            RestoreSummary {
+                restored: 2,
+                skipped: 1
+            }
+        );
+        assert_eq!(fs::read_to_string(to.sessions.join("a.jsonl"))?, "newer");
+        assert!(to.sessions.join("nested/b.jsonl").exists());
+        assert!(!to.memories.exists());
+        assert_eq!(target_config.get_param::<String>("GOOSE_MODEL")?, "gpt-4o");
+        assert!(target_config
+            .get_secret::<String>("OPENAI_API_KEY")
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_damaged_archive_restores_nothing() -> Result<()> {
+        let source = tempdir()?;
+        let from = locations(source.path());
+        fs::create_dir_all(&from.sessions)?;
+        fs::write(from.sessions.join("a.jsonl"), "{}\n")?;
+        fs::write(from.sessions.join("b.jsonl"), "{}\n")?;
+        let source_config = config(source.path());
+
+        let mut archive = Cursor::new(Vec::new());
+        let mut manifest = create_backup(
+            &mut archive,
+            &source_config,
+            &from,
+            &[BackupCategory::Sessions],
+        )?;
+
+        // Rewrite the archive with a tampered checksum for the second session
+        manifest.entries[1].sha256 = "0".repeat(64);
+        archive.set_position(0);
+        let mut original = ZipArchive::new(&mut archive)?;
+        let mut tampered = ZipWriter::new(Cursor::new(Vec::new()));
+        for entry in &manifest.entries {
+            tampered.start_file(entry.path.as_str(), SimpleFileOptions::default())?;
+            tampered.write_all(&read_entry(&mut original, &entry.path, entry.size)?)?;
+        }
+        tampered.start_file(MANIFEST_PATH, SimpleFileOptions::default())?;
+        tampered.write_all(&serde_json::to_vec(&manifest)?)?;
+        let mut tampered = tampered.finish()?;
+        tampered.set_position(0);
+
+        let target = tempdir()?;
+        let to = locations(target.path());
+        let err = restore_backup(
+            &mut tampered,
+            &config(target.path()),
+            &to,
+            &BackupCategory::ALL,
+            true,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(!to.sessions.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_entries_rewrites_archive() -> Result<()> {
+        let source = tempdir()?;
+        let from = locations(source.path());
+        fs::create_dir_all(&from.sessions)?;
+        fs::write(from.sessions.join("a.jsonl"), "{}\n")?;
+        fs::write(from.sessions.join("b.jsonl"), "{}\n")?;
+        let path = source.path().join("backup.zip");
+        create_backup(
+            fs::File::create(&path)?,
+            &config(source.path()),
+            &from,
+            &[BackupCategory::Sessions],
+        )?;
+
+        let is_a = |entry: &BackupEntry| entry.path == "sessions/a.jsonl";
+        assert_eq!(remove_entries(&path, is_a, true)?, vec!["sessions/a.jsonl"]);
+        assert_eq!(read_manifest(fs::File::open(&path)?)?.entries.len(), 2);
+
+        assert_eq!(
+            remove_entries(&path, is_a, false)?,
+            vec!["sessions/a.jsonl"]
+        );
+        let manifest = read_manifest(fs::File::open(&path)?)?;
+        let paths: Vec<&str> = manifest.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["sessions/b.jsonl"]);
+
+        let target = tempdir()?;
+        let to = locations(target.path());
+        restore_backup(
+            fs::File::open(&path)?,
+            &config(target.path()),
+            &to,
+            &BackupCategory::ALL,
+            false,
+        )?;
+        assert!(!to.sessions.join("a.jsonl").exists());
+        assert!(to.sessions.join("b.jsonl").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sessions_restore_to_their_roots() -> Result<()> {
+        let source = tempdir()?;
+        let mut from = locations(source.path());
+        from.session_roots = vec![SessionRoot {
+            path: source.path().join("shared"),
+            working_dirs: vec![PathBuf::from("/work/shared-app")],
+        }];
+        fs::create_dir_all(&from.sessions)?;
+        fs::create_dir_all(&from.session_roots[0].path)?;
+        fs::write(from.sessions.join("mine.jsonl"), "{}\n")?;
+        fs::write(
+            from.session_roots[0].path.join("team.jsonl"),
+            "{\"working_dir\":\"/work/shared-app/api\",\"description\":\"\",\"message_count\":0}\n",
+        )?;
+        fs::write(from.session_roots[0].path.join("team.pins.json"), "[]")?;
+
+        let mut archive = Cursor::new(Vec::new());
+        create_backup(
+            &mut archive,
+            &config(source.path()),
+            &from,
+            &[BackupCategory::Sessions],
+        )?;
+
+        let target = tempdir()?;
+        let mut to = locations(target.path());
+        to.session_roots = vec![SessionRoot {
+            path: target.path().join("shared"),
+            working_dirs: vec![PathBuf::from("/work/shared-app")],
+        }];
+        archive.set_position(0);
+        restore_backup(
+            &mut archive,
+            &config(target.path()),
+            &to,
+            &[BackupCategory::Sessions],
+            false,
+        )?;
+        assert!(to.sessions.join("mine.jsonl").exists());
+        assert!(to.session_roots[0].path.join("team.jsonl").exists());
+        assert!(to.session_roots[0].path.join("team.pins.json").exists());
+        assert!(!to.sessions.join("team.jsonl").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_entries_larger_than_the_manifest_says_are_rejected() -> Result<()> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let bytes = vec![b'a'; 4096];
+        zip.start_file("sessions/big.jsonl", SimpleFileOptions::default())?;
+        zip.write_all(&bytes)?;
+        let manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            goose_version: String::new(),
+            created_at: String::new(),
+            entries: vec![BackupEntry {
+                path: "sessions/big.jsonl".to_string(),
+                category: BackupCategory::Sessions,
+                size: 16,
+                sha256: sha256_hex(&bytes),
+            }],
+        };
+        zip.start_file(MANIFEST_PATH, SimpleFileOptions::default())?;
+        zip.write_all(&serde_json::to_vec(&manifest)?)?;
+        let mut archive = zip.finish()?;
+        archive.set_position(0);
+
+        let target = tempdir()?;
+        let err = restore_backup(
+            &mut archive,
+            &config(target.path()),
+            &locations(target.path()),
+            &BackupCategory::ALL,
+            true,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("larger than the backup says"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsafe_paths() {
+        assert!(is_safe_path("sessions/a.jsonl"));
+        assert!(!is_safe_path("sessions/../../etc/passwd"));
+        assert!(!is_safe_path("/etc/passwd"));
+        assert!(!is_safe_path(""));
+    }
+
+    #[test]
+    fn test_parse_category() {
+        assert_eq!(
+            "Sessions".parse::<BackupCategory>().unwrap(),
+            BackupCategory::Sessions
+        );
+        assert!("logs".parse::<BackupCategory>().is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! History of goose-bench results, kept in SQLite like the scheduler's jobs so regressions
+//! in task success can be followed across models, suites and commits.
+//!
+//! Every evaluation goose-bench finishes is recorded as one row in `bench.db` in the data
+//! directory. `trend` groups those rows into time (or commit) buckets per model and suite.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use etcetera::{choose_app_strategy, AppStrategy};
+use rusqlite::{params_from_iter, types::Value, Connection};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::APP_STRATEGY;
+
+/// Environment variable naming the commit being benchmarked, for when goose-bench does not
+/// run inside the repository it is testing
+pub const BENCH_COMMIT_ENV: &str = "GOOSE_BENCH_COMMIT";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS bench_results (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    suite TEXT NOT NULL,
+    eval TEXT NOT NULL,
+    git_commit TEXT,
+    passed INTEGER NOT NULL,
+    score REAL,
+    errors INTEGER NOT NULL DEFAULT 0,
+    metrics TEXT NOT NULL DEFAULT '{}'
+);
+CREATE INDEX IF NOT EXISTS bench_results_recorded_at ON bench_results (recorded_at);
+";
+
+/// The outcome of one evaluation in a benchmark run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub recorded_at: DateTime<Utc>,
+    pub provider: String,
+    pub model: String,
+    /// The selector without its last component, e.g. `core:developer`
+    pub suite: String,
+    pub eval: String,
+    pub commit: Option<String>,
+    pub passed: bool,
+    pub score: Option<f64>,
+    pub errors: usize,
+    /// Every metric the evaluation reported, by name
+    pub metrics: serde_json::Value,
+}
+
+/// How results are grouped over time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendBucket {
+    #[default]
+    Day,
+    Week,
+    /// One bucket per benchmarked commit, ordered by when it was first benchmarked
+    Commit,
+}
+
+impl TrendBucket {
+    fn expression(self) -> &'static str {
+        match self {
+            TrendBucket::Day => "strftime('%Y-%m-%d', recorded_at, 'unixepoch')",
+            TrendBucket::Week => "strftime('%G-W%V', recorded_at, 'unixepoch')",
+            TrendBucket::Commit => "IFNULL(git_commit, 'unknown')",
+        }
+    }
+}
+
+/// Which results to aggregate; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct TrendQuery {
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub suite: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub bucket: TrendBucket,
+}
+
+/// Aggregated results of one model on one suite within a bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    /// The day (`2025-01-31`), ISO week (`2025-W04`) or commit of the bucket
+    pub bucket: String,
+    pub provider: String,
+    pub model: String,
+    pub suite: String,
+    pub evaluations: usize,
+    pub passed: usize,
+    pub success_rate: f64,
+    /// Mean of the scores reported in the bucket, if any were
+    pub mean_score: Option<f64>,
+    pub errors: usize,
+    /// Unix timestamp (seconds) of the first and last result in the bucket
+    pub first_recorded: i64,
+    pub last_recorded: i64,
+}
+
+pub struct BenchResultStore {
+    db: Mutex<Connection>,
+}
+
+impl BenchResultStore {
+    /// The store in the data directory
+    pub fn open_default() -> Result<Self> {
+        let path: PathBuf = choose_app_strategy(APP_STRATEGY.clone())?
+            .data_dir()
+            .join("bench.db");
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Self::open(Connection::open(path)?)
+    }
+
+    fn open(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            db: Mutex::new(connection),
+        })
+    }
+
+    pub fn record(&self, record: &BenchRecord) -> Result<()> {
+        self.db.lock().unwrap().execute(
+            "INSERT INTO bench_results
+                 (recorded_at, provider, model, suite, eval, git_commit, passed, score, errors, metrics)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            rusqlite::params![
+                record.recorded_at.timestamp(),
+                record.provider,
+                record.model,
+                record.suite,
+                record.eval,
+                record.commit,
+                record.passed,
+                record.score,
+                record.errors as i64,
+                record.metrics.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Results matching `query`, one point per bucket, provider, model and suite, oldest
+    /// bucket first
+    pub fn trend(&self, query: &TrendQuery) -> Result<Vec<TrendPoint>> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        for (column, value) in [
+            ("provider", &query.provider),
+            ("model", &query.model),
+            ("suite", &query.suite),
+        ] {
+            if let Some(value) = value {
+                values.push(Value::Text(value.clone()));
+                conditions.push(format!("{} = ?{}", column, values.len()));
+            }
+        }
+        if let Some(since) = query.since {
+            values.push(Value::Integer(since.timestamp()));
+            conditions.push(format!("recorded_at >= ?{}", values.len()));
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let sql = format!(
+            "SELECT {} AS bucket, provider, model, suite, COUNT(*), SUM(passed), AVG(score),
+                    SUM(errors), MIN(recorded_at), MAX(recorded_at)
+             FROM bench_results {}
+             GROUP BY bucket, provider, model, suite
+             ORDER BY MIN(recorded_at), provider, model, suite",
+            query.bucket.expression(),
+            filter
+        );
+        let db = self.db.lock().unwrap();
+        let mut statement = db.prepare(&sql)?;
+        let points = statement
+            .query_map(params_from_iter(values), |row| {
+                let evaluations: i64 = row.get(4)?;
+                let passed: i64 = row.get(5)?;
+                Ok(TrendPoint {
+                    bucket: row.get(0)?,
+                    provider: row.get(1)?,
+                    model: row.get(2)?,
+                    suite: row.get(3)?,
+                    evaluations: evaluations as usize,
+                    passed: passed as usize,
+                    success_rate: if evaluations == 0 {
+                        0.0
+                    } else {
+                        passed as f64 / evaluations as f64
+                    },
+                    mean_score: row.get(6)?,
+                    errors: row.get::<_, i64>(7)? as usize,
+                    first_recorded: row.get(8)?,
+                    last_recorded: row.get(9)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(points)
+    }
+}
+
+/// The commit being benchmarked: `GOOSE_BENCH_COMMIT` if set, otherwise the HEAD of the
+/// repository goose-bench was started in
+pub fn current_commit() -> Option<String> {
+    if let Ok(commit) = std::env::var(BENCH_COMMIT_ENV) {
+        if !commit.trim().is_empty() {
+            return Some(commit.trim().to_string());
+        }
+    }
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!commit.is_empty()).then_some(commit)
+}
+
+/// Split an eval selector such as `core:developer:create_file` into its suite and eval
+pub fn split_selector(selector: &str) -> (String, String) {
+    match selector.rsplit_once(':') {
+        Some((suite, eval)) => (suite.to_string(), eval.to_string()),
+        None => (selector.to_string(), selector.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(day: u32, model: &str, commit: &str, passed: bool, score: f64) -> BenchRecord {
+        BenchRecord {
+            recorded_at: Utc.with_ymd_and_hms(2025, 3, day, 12, 0, 0).unwrap(),
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            suite: "core:developer".to_string(),
+            eval: "create_file".to_string(),
+            commit: Some(commit.to_string()),
+            passed,
+            score: Some(score),
+            errors: 0,
+            metrics: serde_json::json!({ "score": score }),
+        }
+    }
+
+    #[test]
+    fn test_trend_buckets() -> Result<()> {
+        let store = BenchResultStore::open(Connection::open_in_memory()?)?;
+        store.record(&record(3, "gpt-4o", "abc123", true, 1.0))?;
+        store.record(&record(3, "gpt-4o", "abc123", false, 0.5))?;
+        store.record(&record(4, "gpt-4o", "def456", false, 0.0))?;
+        store.record(&record(4, "gpt-4o-mini", "def456", true, 1.0))?;
+
+        let by_day = store.trend(&TrendQuery {
+            model: Some("gpt-4o".to_string()),
+            ..Default::default()
+        })?;
+        assert_eq!(by_day.len(), 2);
+        assert_eq!(by_day[0].bucket, "2025-03-03");
+        assert_eq!(by_day[0].evaluations, 2);
+        assert_eq!(by_day[0].passed, 1);
+        assert_eq!(by_day[0].success_rate, 0.5);
+        assert_eq!(by_day[0].mean_score, Some(0.75));
+        assert_eq!(by_day[1].success_rate, 0.0);
+
+        let by_commit = store.trend(&TrendQuery {
+            bucket: TrendBucket::Commit,
+            since: Some(Utc.with_ymd_and_hms(2025, 3, 4, 0, 0, 0).unwrap()),
+            ..Default::default()
+        })?;
+        let buckets: Vec<(&str, &str)> = by_commit
+            .iter()
+            .map(|point| (point.bucket.as_str(), point.model.as_str()))
+            .collect();
+        assert_eq!(
+            buckets,
+            vec![("def456", "gpt-4o"), ("def456", "gpt-4o-mini")]
+        );
+
+        // ISO weeks run Monday to Sunday and may start in the previous year
+        let store = BenchResultStore::open(Connection::open_in_memory()?)?;
+        for day in [(2024, 12, 29), (2024, 12, 30), (2025, 1, 5)] {
+            store.record(&BenchRecord {
+                recorded_at: Utc.with_ymd_and_hms(day.0, day.1, day.2, 12, 0, 0).unwrap(),
+                ..record(3, "gpt-4o", "abc123", true, 1.0)
+            })?;
+        }
+        let by_week = store.trend(&TrendQuery {
+            bucket: TrendBucket::Week,
+            ..Default::default()
+        })?;
+        let buckets: Vec<(&str, usize)> = by_week
+            .iter()
+            .map(|point| (point.bucket.as_str(), point.evaluations))
+            .collect();
+        assert_eq!(buckets, vec![("2024-W52", 1), ("2025-W01", 2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_selector() {
+        assert_eq!(
+            split_selector("core:developer:create_file"),
+            ("core:developer".to_string(), "create_file".to_string())
+        );
+        assert_eq!(
+            split_selector("example"),
+            ("example".to_string(), "example".to_string())
+        );
+    }
+}
@@ -1,15 +1,19 @@
 use etcetera::{choose_app_strategy, AppStrategy, AppStrategyArgs};
 use fs2::FileExt;
-use keyring::Entry;
 use once_cell::sync::{Lazy, OnceCell};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::secrets::{
+    FallbackSecretStore, FileSecretStore, KeyringSecretStore, SecretStore, KEYRING_SERVICE,
+};
 
 pub static APP_STRATEGY: Lazy<AppStrategyArgs> = Lazy::new(|| AppStrategyArgs {
     top_level_domain: "Block".to_string(),
@@ -17,8 +21,21 @@ pub static APP_STRATEGY: Lazy<AppStrategyArgs> = Lazy::new(|| AppStrategyArgs {
     app_name: "goose".to_string(),
 });
 
-const KEYRING_SERVICE: &str = "goose";
-const KEYRING_USERNAME: &str = "secrets";
+/// Directories whose .goose/config.yaml the user has agreed to load, kept in the user config
+pub const TRUSTED_PROJECTS_CONFIG_KEY: &str = "GOOSE_TRUSTED_PROJECTS";
+
+/// The only keys a trusted project config may set. Anything that starts processes, talks to
+/// other hosts or loosens policy (extensions, hooks, provider hosts, GOOSE_MODE, allowlists,
+/// version pins, session roots) is only ever read from the user's own layers.
+pub const PROJECT_CONFIG_KEYS: &[&str] = &[
+    "GOOSE_MODEL",
+    "GOOSE_TEMPERATURE",
+    "GOOSE_MAX_TURNS",
+    "GOOSE_CONTEXT_LIMIT",
+    "GOOSE_AUTO_COMPACT_THRESHOLD",
+    "GOOSE_STYLE",
+    "GOOSE_RESPONSE_LANGUAGE",
+];
 
 #[cfg(test)]
 const TEST_KEYRING_SERVICE: &str = "goose-test";
@@ -57,6 +74,32 @@ impl From<keyring::Error> for ConfigError {
     }
 }
 
+/// Where a configuration value came from, lowest precedence first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// Machine-wide defaults, e.g. /etc/goose/config.yaml
+    System,
+    /// The team config last synced from GOOSE_TEAM_CONFIG
+    Team,
+    /// The user's config.yaml
+    User,
+    /// .goose/config.yaml in the current directory or one of its parents, once trusted
+    Project,
+    /// An environment variable
+    Env,
+    /// A value passed with a single request
+    Override,
+}
+
+/// The value a key resolves to and the layer it was taken from
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EffectiveValue {
+    pub key: String,
+    pub value: Value,
+    pub source: ConfigSource,
+}
+
 /// Configuration management for Goose.
 ///
 /// This module provides a flexible configuration system that supports:
@@ -68,8 +111,16 @@ impl From<keyring::Error> for ConfigError {
 /// - Secure secret storage in system keyring
 ///
 /// Configuration values are loaded with the following precedence:
-/// 1. Environment variables (exact key match)
-/// 2. Configuration file (~/.config/goose/config.yaml by default)
+/// 1. Per-request overrides (see [`Config::get_param_with_overrides`])
+/// 2. Environment variables (exact key match)
+/// 3. Project config (.goose/config.yaml in the working directory or a parent), only once
+///    the user has trusted its directory and only for [`PROJECT_CONFIG_KEYS`]
+/// 4. Configuration file (~/.config/goose/config.yaml by default)
+/// 5. Team config synced from GOOSE_TEAM_CONFIG (~/.config/goose/team-config.yaml)
+/// 6. System defaults (/etc/goose/config.yaml, or $GOOSE_SYSTEM_CONFIG)
+///
+/// Only the user configuration file is ever written; the other layers are
+/// read-only.
 ///
 /// Secrets are loaded with the following precedence:
 /// 1. Environment variables (exact key match)
@@ -105,12 +156,10 @@ impl From<keyring::Error> for ConfigError {
 /// For Goose-specific configuration, consider prefixing with "goose_" to avoid conflicts.
 pub struct Config {
     config_path: PathBuf,
-    secrets: SecretStorage,
-}
-
-enum SecretStorage {
-    Keyring { service: String },
-    File { path: PathBuf },
+    secrets: Box<dyn SecretStore>,
+    system_path: Option<PathBuf>,
+    team_path: Option<PathBuf>,
+    project_path: Option<PathBuf>,
 }
 
 // Global instance
@@ -129,21 +178,44 @@ impl Default for Config {
 
         let config_path = config_dir.join("config.yaml");
 
-        let secrets = match env::var("GOOSE_DISABLE_KEYRING") {
-            Ok(_) => SecretStorage::File {
-                path: config_dir.join("secrets.yaml"),
-            },
-            Err(_) => SecretStorage::Keyring {
-                service: KEYRING_SERVICE.to_string(),
-            },
+        let secrets_path = config_dir.join("secrets.yaml");
+        let secrets: Box<dyn SecretStore> = match env::var("GOOSE_DISABLE_KEYRING") {
+            Ok(_) => Box::new(FileSecretStore::new(secrets_path)),
+            Err(_) => Box::new(FallbackSecretStore::new(KEYRING_SERVICE, secrets_path)),
         };
         Config {
             config_path,
             secrets,
+            system_path: Some(default_system_config_path()),
+            team_path: Some(config_dir.join("team-config.yaml")),
+            project_path: find_project_config(),
         }
     }
 }
 
+fn default_system_config_path() -> PathBuf {
+    if let Ok(path) = env::var("GOOSE_SYSTEM_CONFIG") {
+        return PathBuf::from(path);
+    }
+    if cfg!(windows) {
+        let program_data =
+            env::var("PROGRAMDATA").unwrap_or_else(|_| "C:\\ProgramData".to_string());
+        PathBuf::from(program_data)
+            .join("goose")
+            .join("config.yaml")
+    } else {
+        PathBuf::from("/etc/goose/config.yaml")
+    }
+}
+
+// The nearest .goose/config.yaml walking up from the working directory
+fn find_project_config() -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(".goose").join("config.yaml"))
+        .find(|path| path.is_file())
+}
+
 impl Config {
     /// Get the global configuration instance.
     ///
@@ -160,9 +232,10 @@ impl Config {
     pub fn new<P: AsRef<Path>>(config_path: P, service: &str) -> Result<Self, ConfigError> {
         Ok(Config {
             config_path: config_path.as_ref().to_path_buf(),
-            secrets: SecretStorage::Keyring {
-                service: service.to_string(),
-            },
+            secrets: Box::new(KeyringSecretStore::new(service)),
+            system_path: None,
+            team_path: None,
+            project_path: None,
         })
     }
 
@@ -176,12 +249,112 @@ impl Config {
     ) -> Result<Self, ConfigError> {
         Ok(Config {
             config_path: config_path.as_ref().to_path_buf(),
-            secrets: SecretStorage::File {
-                path: secrets_path.as_ref().to_path_buf(),
-            },
+            secrets: Box::new(FileSecretStore::new(secrets_path)),
+            system_path: None,
+            team_path: None,
+            project_path: None,
         })
     }
 
+    /// Add read-only system and project layers beneath and above the user config. The project
+    /// layer is only read once its directory is trusted, see [`Config::trust_project`].
+    pub fn with_layers(
+        mut self,
+        system_path: Option<PathBuf>,
+        project_path: Option<PathBuf>,
+    ) -> Self {
+        self.system_path = system_path;
+        self.project_path = project_path;
+        self
+    }
+
+    /// Use `path` as the synced team config layer
+    pub fn with_team_config(mut self, path: Option<PathBuf>) -> Self {
+        self.team_path = path;
+        self
+    }
+
+    /// Where the synced team config is cached, if this config has a team layer
+    pub fn team_config_path(&self) -> Option<&Path> {
+        self.team_path.as_deref()
+    }
+
+    /// Paths of the read-only layers that are in use, if any
+    pub fn layer_paths(&self) -> Vec<(ConfigSource, PathBuf)> {
+        let mut layers = Vec::new();
+        if let Some(path) = &self.system_path {
+            layers.push((ConfigSource::System, path.clone()));
+        }
+        if let Some(path) = &self.team_path {
+            layers.push((ConfigSource::Team, path.clone()));
+        }
+        if let Some(path) = self.trusted_project_path() {
+            layers.push((ConfigSource::Project, path.to_path_buf()));
+        }
+        layers
+    }
+
+    // The directory a .goose/config.yaml belongs to
+    fn project_dir(path: &Path) -> Option<PathBuf> {
+        let dir = path.parent()?.parent()?;
+        dir.canonicalize().ok()
+    }
+
+    fn trusted_projects(&self) -> Vec<String> {
+        self.get_user_param(TRUSTED_PROJECTS_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+
+    /// The project config, if there is one and the user has trusted its directory
+    pub fn trusted_project_path(&self) -> Option<&Path> {
+        let path = self.project_path.as_deref()?;
+        let dir = Self::project_dir(path)?;
+        let dir = dir.to_string_lossy();
+        self.trusted_projects()
+            .iter()
+            .any(|trusted| *trusted == dir)
+            .then_some(path)
+    }
+
+    /// The project config, if there is one that is ignored because its directory isn't trusted
+    pub fn untrusted_project_path(&self) -> Option<&Path> {
+        let path = self.project_path.as_deref()?;
+        match self.trusted_project_path() {
+            Some(_) => None,
+            None => Some(path),
+        }
+    }
+
+    /// Trust the directory of the project config, so that its allowed keys are loaded
+    pub fn trust_project(&self) -> Result<(), ConfigError> {
+        let Some(dir) = self.project_path.as_deref().and_then(Self::project_dir) else {
+            return Ok(());
+        };
+        let dir = dir.to_string_lossy().to_string();
+        let mut trusted = self.trusted_projects();
+        if !trusted.contains(&dir) {
+            trusted.push(dir);
+            self.set_param(TRUSTED_PROJECTS_CONFIG_KEY, serde_json::to_value(trusted)?)?;
+        }
+        Ok(())
+    }
+
+    // The allowed keys of the project layer, or nothing if it isn't trusted
+    fn project_layer(&self) -> HashMap<String, Value> {
+        let Some(path) = self.trusted_project_path() else {
+            return HashMap::new();
+        };
+        let mut values = Self::read_layer(Some(path));
+        values.retain(|key, _| {
+            let allowed = PROJECT_CONFIG_KEYS.contains(&key.as_str());
+            if !allowed {
+                tracing::warn!("Ignoring {} in project config {:?}", key, path);
+            }
+            allowed
+        });
+        values
+    }
+
     /// Check if this config already exists
     pub fn exists(&self) -> bool {
         self.config_path.exists()
@@ -476,35 +649,20 @@ impl Config {
         Ok(())
     }
 
-    // Load current secrets from the keyring
+    // Load current secrets from the secret store
     pub fn load_secrets(&self) -> Result<HashMap<String, Value>, ConfigError> {
-        match &self.secrets {
-            SecretStorage::Keyring { service } => {
-                let entry = Entry::new(service, KEYRING_USERNAME)?;
-
-                match entry.get_password() {
-                    Ok(content) => {
-                        let values: HashMap<String, Value> = serde_json::from_str(&content)?;
-                        Ok(values)
-                    }
-                    Err(keyring::Error::NoEntry) => Ok(HashMap::new()),
-                    Err(e) => Err(ConfigError::KeyringError(e.to_string())),
-                }
-            }
-            SecretStorage::File { path } => {
-                if path.exists() {
-                    let file_content = std::fs::read_to_string(path)?;
-                    let yaml_value: serde_yaml::Value = serde_yaml::from_str(&file_content)?;
-                    let json_value: Value = serde_json::to_value(yaml_value)?;
-                    match json_value {
-                        Value::Object(map) => Ok(map.into_iter().collect()),
-                        _ => Ok(HashMap::new()),
-                    }
-                } else {
-                    Ok(HashMap::new())
-                }
-            }
-        }
+        self.secrets.load()
+    }
+
+    /// The store holding secrets for this config
+    pub fn secret_store(&self) -> &dyn SecretStore {
+        self.secrets.as_ref()
+    }
+
+    /// Store secrets somewhere other than the default for this config
+    pub fn with_secret_store(mut self, secrets: Box<dyn SecretStore>) -> Self {
+        self.secrets = secrets;
+        self
     }
 
     /// Parse an environment variable value into a JSON Value.
@@ -575,21 +733,109 @@ impl Config {
     /// - The value cannot be deserialized into the requested type
     /// - There is an error reading the config file
     pub fn get_param<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<T, ConfigError> {
-        // First check environment variables (convert to uppercase)
-        let env_key = key.to_uppercase();
-        if let Ok(val) = env::var(&env_key) {
-            let value = Self::parse_env_value(&val)?;
-            return Ok(serde_json::from_value(value)?);
+        self.get_param_with_overrides(key, &HashMap::new())
+    }
+
+    /// Get a configuration value, letting `overrides` win over every other layer
+    pub fn get_param_with_overrides<T: for<'de> Deserialize<'de>>(
+        &self,
+        key: &str,
+        overrides: &HashMap<String, Value>,
+    ) -> Result<T, ConfigError> {
+        let (value, _) = self
+            .resolve(key, overrides)?
+            .ok_or_else(|| ConfigError::NotFound(key.to_string()))?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Get a value from the user config file alone, ignoring every other layer. Anything that
+    /// modifies a value and writes it back has to start from this, so that values from the
+    /// read-only layers are never copied into the user config.
+    pub fn get_user_param<T: for<'de> Deserialize<'de>>(
+        &self,
+        key: &str,
+    ) -> Result<T, ConfigError> {
+        let value = self
+            .load_values()?
+            .remove(key)
+            .ok_or_else(|| ConfigError::NotFound(key.to_string()))?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Find the value for `key` in the highest-precedence layer that has it
+    pub fn resolve(
+        &self,
+        key: &str,
+        overrides: &HashMap<String, Value>,
+    ) -> Result<Option<(Value, ConfigSource)>, ConfigError> {
+        if let Some(value) = overrides.get(key) {
+            return Ok(Some((value.clone(), ConfigSource::Override)));
         }
 
-        // Load current values from file
-        let values = self.load_values()?;
+        // Environment variables are checked in uppercase
+        if let Ok(val) = env::var(key.to_uppercase()) {
+            return Ok(Some((Self::parse_env_value(&val)?, ConfigSource::Env)));
+        }
 
-        // Then check our stored values
-        values
-            .get(key)
-            .ok_or_else(|| ConfigError::NotFound(key.to_string()))
-            .and_then(|v| Ok(serde_json::from_value(v.clone())?))
+        if let Some(value) = self.project_layer().remove(key) {
+            return Ok(Some((value, ConfigSource::Project)));
+        }
+        if let Some(value) = self.load_values()?.remove(key) {
+            return Ok(Some((value, ConfigSource::User)));
+        }
+        if let Some(value) = Self::read_layer(self.team_path.as_deref()).remove(key) {
+            return Ok(Some((value, ConfigSource::Team)));
+        }
+        if let Some(value) = Self::read_layer(self.system_path.as_deref()).remove(key) {
+            return Ok(Some((value, ConfigSource::System)));
+        }
+        Ok(None)
+    }
+
+    /// Effective value and source for every key set in a config file or override,
+    /// plus `extra_keys` (useful for keys that may only be set in the environment)
+    pub fn effective_values(
+        &self,
+        overrides: &HashMap<String, Value>,
+        extra_keys: &[&str],
+    ) -> Result<Vec<EffectiveValue>, ConfigError> {
+        let mut keys: BTreeSet<String> = extra_keys.iter().map(|k| k.to_string()).collect();
+        keys.extend(Self::read_layer(self.system_path.as_deref()).into_keys());
+        keys.extend(Self::read_layer(self.team_path.as_deref()).into_keys());
+        keys.extend(self.load_values()?.into_keys());
+        keys.extend(self.project_layer().into_keys());
+        keys.extend(overrides.keys().cloned());
+
+        let mut effective = Vec::new();
+        for key in keys {
+            if let Some((value, source)) = self.resolve(&key, overrides)? {
+                effective.push(EffectiveValue { key, value, source });
+            }
+        }
+        Ok(effective)
+    }
+
+    // Read a read-only layer; a missing or unreadable file contributes nothing
+    fn read_layer(path: Option<&Path>) -> HashMap<String, Value> {
+        let Some(path) = path.filter(|p| p.exists()) else {
+            return HashMap::new();
+        };
+        let parsed = std::fs::read_to_string(path)
+            .map_err(ConfigError::from)
+            .and_then(|content| {
+                if content.trim().is_empty() {
+                    return Ok(HashMap::new());
+                }
+                let yaml_value: serde_yaml::Value = serde_yaml::from_str(&content)?;
+                match serde_json::to_value(yaml_value)? {
+                    Value::Object(map) => Ok(map.into_iter().collect()),
+                    _ => Ok(HashMap::new()),
+                }
+            });
+        parsed.unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable config layer {:?}: {}", path, e);
+            HashMap::new()
+        })
     }
 
     /// Set a configuration value in the config file (non-secret).
@@ -685,19 +931,7 @@ impl Config {
     pub fn set_secret(&self, key: &str, value: Value) -> Result<(), ConfigError> {
         let mut values = self.load_secrets()?;
         values.insert(key.to_string(), value);
-
-        match &self.secrets {
-            SecretStorage::Keyring { service } => {
-                let json_value = serde_json::to_string(&values)?;
-                let entry = Entry::new(service, KEYRING_USERNAME)?;
-                entry.set_password(&json_value)?;
-            }
-            SecretStorage::File { path } => {
-                let yaml_value = serde_yaml::to_string(&values)?;
-                std::fs::write(path, yaml_value)?;
-            }
-        };
-        Ok(())
+        self.secrets.save(&values)
     }
 
     /// Delete a secret from the system keyring.
@@ -713,19 +947,7 @@ impl Config {
     pub fn delete_secret(&self, key: &str) -> Result<(), ConfigError> {
         let mut values = self.load_secrets()?;
         values.remove(key);
-
-        match &self.secrets {
-            SecretStorage::Keyring { service } => {
-                let json_value = serde_json::to_string(&values)?;
-                let entry = Entry::new(service, KEYRING_USERNAME)?;
-                entry.set_password(&json_value)?;
-            }
-            SecretStorage::File { path } => {
-                let yaml_value = serde_yaml::to_string(&values)?;
-                std::fs::write(path, yaml_value)?;
-            }
-        };
-        Ok(())
+        self.secrets.save(&values)
     }
 }
 
@@ -786,6 +1008,8 @@ pub fn load_init_config_from_workspace() -> Result<HashMap<String, Value>, Confi
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::secrets::KEYRING_USERNAME;
+    use keyring::Entry;
     use serial_test::serial;
     use tempfile::NamedTempFile;
 
@@ -1463,4 +1687,99 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[serial]
+    fn test_layered_resolution() -> Result<(), ConfigError> {
+        let dir = tempfile::tempdir().unwrap();
+        let system = dir.path().join("system.yaml");
+        let project = dir.path().join("project.yaml");
+        std::fs::write(&system, "LAYER_MODEL: system-model\nLAYER_ONLY_SYSTEM: 1\n")?;
+        std::fs::write(&project, "LAYER_MODEL: project-model\n")?;
+
+        let config = Config::new(dir.path().join("config.yaml"), TEST_KEYRING_SERVICE)?
+            .with_layers(Some(system), Some(project));
+        config.set_param("LAYER_MODEL", Value::String("user-model".to_string()))?;
+        config.set_param("LAYER_ONLY_USER", Value::Bool(true))?;
+
+        // The user file beats system defaults
+        assert_eq!(
+            config.resolve("LAYER_MODEL", &HashMap::new())?,
+            Some((Value::String("user-model".into()), ConfigSource::User))
+        );
+        let only_system: i64 = config.get_param("LAYER_ONLY_SYSTEM")?;
+        assert_eq!(only_system, 1);
+
+        std::env::set_var("LAYER_MODEL", "env-model");
+        let model: String = config.get_param("LAYER_MODEL")?;
+        assert_eq!(model, "env-model");
+
+        let overrides = HashMap::from([("LAYER_MODEL".to_string(), Value::from("request-model"))]);
+        let model: String = config.get_param_with_overrides("LAYER_MODEL", &overrides)?;
+        assert_eq!(model, "request-model");
+
+        let sources: Vec<_> = config
+            .effective_values(&HashMap::new(), &[])?
+            .into_iter()
+            .map(|e| (e.key, e.source))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("LAYER_MODEL".to_string(), ConfigSource::Env),
+                ("LAYER_ONLY_SYSTEM".to_string(), ConfigSource::System),
+                ("LAYER_ONLY_USER".to_string(), ConfigSource::User),
+            ]
+        );
+        std::env::remove_var("LAYER_MODEL");
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_project_layer_needs_trust_and_allowed_keys() -> Result<(), ConfigError> {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("repo");
+        std::fs::create_dir_all(project_dir.join(".goose"))?;
+        let project = project_dir.join(".goose").join("config.yaml");
+        std::fs::write(
+            &project,
+            "GOOSE_MODEL: project-model\nGOOSE_MODE: auto\nextensions: {}\n",
+        )?;
+
+        let config = Config::new(dir.path().join("config.yaml"), TEST_KEYRING_SERVICE)?
+            .with_layers(None, Some(project.clone()));
+        config.set_param("GOOSE_MODEL", Value::from("user-model"))?;
+        config.set_param("GOOSE_MODE", Value::from("approve"))?;
+
+        // Until the directory is trusted the project config is ignored entirely
+        assert_eq!(config.untrusted_project_path(), Some(project.as_path()));
+        let model: String = config.get_param("GOOSE_MODEL")?;
+        assert_eq!(model, "user-model");
+
+        config.trust_project()?;
+        assert_eq!(config.trusted_project_path(), Some(project.as_path()));
+        assert_eq!(
+            config.resolve("GOOSE_MODEL", &HashMap::new())?,
+            Some((Value::from("project-model"), ConfigSource::Project))
+        );
+        // Keys outside the allowlist never come from a project, trusted or not
+        assert_eq!(
+            config.resolve("GOOSE_MODE", &HashMap::new())?,
+            Some((Value::from("approve"), ConfigSource::User))
+        );
+        assert!(config.resolve("extensions", &HashMap::new())?.is_none());
+
+        // Writes start from the user layer, so project values are not copied into it
+        let mut mode: String = config.get_user_param("GOOSE_MODEL")?;
+        mode.push_str("-2");
+        config.set_param("GOOSE_MODEL", Value::from(mode))?;
+        assert_eq!(
+            config.load_values()?.get("GOOSE_MODEL"),
+            Some(&Value::from("user-model-2"))
+        );
+
+        Ok(())
+    }
 }
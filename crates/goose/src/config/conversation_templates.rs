@@ -0,0 +1,199 @@
+use crate::agents::ExtensionConfig;
+use crate::config::{ExtensionConfigManager, APP_STRATEGY};
+use anyhow::{anyhow, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+pub fn templates_dir() -> PathBuf {
+    choose_app_strategy(APP_STRATEGY.clone())
+        .expect("goose requires a home dir")
+        .config_dir()
+        .join("templates")
+}
+
+/// A saved conversation starter: an opening prompt, files to attach to it, and the
+/// extensions to run with. Lighter than a recipe and personal to the user.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    /// The first user message of the conversation
+    pub prompt: String,
+    /// Files whose contents are attached to the prompt when the template is started
+    #[serde(default)]
+    pub context: Vec<String>,
+    /// Names of configured extensions to enable; empty means use the usual set
+    #[serde(default)]
+    pub extensions: Vec<String>,
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Template names may only contain letters, numbers, '-' and '_'"
+        ));
+    }
+    Ok(())
+}
+
+impl ConversationTemplate {
+    pub fn save(&self) -> Result<()> {
+        self.save_in(&templates_dir())
+    }
+
+    pub fn save_in(&self, dir: &Path) -> Result<()> {
+        validate_name(&self.name)?;
+        std::fs::create_dir_all(dir)?;
+        let file_path = dir.join(format!("{}.json", self.name));
+        std::fs::write(file_path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(name: &str) -> Result<Self> {
+        Self::load_from(&templates_dir(), name)
+    }
+
+    pub fn load_from(dir: &Path, name: &str) -> Result<Self> {
+        validate_name(name)?;
+        let file_path = dir.join(format!("{}.json", name));
+        let content = std::fs::read_to_string(&file_path)
+            .map_err(|_| anyhow!("Template '{}' not found", name))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn remove(name: &str) -> Result<()> {
+        validate_name(name)?;
+        let file_path = templates_dir().join(format!("{}.json", name));
+        if file_path.exists() {
+            std::fs::remove_file(file_path)?;
+        }
+        Ok(())
+    }
+
+    /// The opening message with the contents of every context file appended
+    pub fn render_prompt(&self) -> Result<String> {
+        self.render_prompt_with(|path| Ok(std::fs::read_to_string(path)?))
+    }
+
+    /// The opening message with every context file appended as `read` returns it, which lets
+    /// callers turn documents such as PDFs into text first
+    pub fn render_prompt_with(&self, read: impl Fn(&Path) -> Result<String>) -> Result<String> {
+        let mut prompt = self.prompt.clone();
+        for path in &self.context {
+            let content = read(Path::new(path))
+                .map_err(|e| anyhow!("Failed to read context file {}: {}", path, e))?;
+            prompt.push_str(&format!("\n\nContents of {}:\n```\n{}\n```", path, content));
+        }
+        Ok(prompt)
+    }
+
+    /// Resolve the template's extension names against the user's configured extensions
+    pub fn extension_configs(&self) -> Result<Vec<ExtensionConfig>> {
+        let configured = ExtensionConfigManager::get_all()?;
+        self.extensions
+            .iter()
+            .map(|name| {
+                configured
+                    .iter()
+                    .find(|entry| entry.config.name() == *name || entry.config.key() == *name)
+                    .map(|entry| entry.config.clone())
+                    .ok_or_else(|| anyhow!("Extension '{}' is not configured", name))
+            })
+            .collect()
+    }
+}
+
+pub fn list_templates() -> Result<Vec<ConversationTemplate>> {
+    load_templates(&templates_dir())
+}
+
+pub fn load_templates(dir: &Path) -> Result<Vec<ConversationTemplate>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut templates: Vec<ConversationTemplate> = std::fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "json").then_some(path)
+        })
+        .map(|path| {
+            let content = std::fs::read_to_string(&path)?;
+            serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))
+        })
+        .collect::<Result<_>>()?;
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn template(name: &str) -> ConversationTemplate {
+        ConversationTemplate {
+            name: name.to_string(),
+            description: None,
+            prompt: "Review this code".to_string(),
+            context: Vec::new(),
+            extensions: vec!["developer".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_save_load_list() -> Result<()> {
+        let dir = tempdir()?;
+        template("review").save_in(dir.path())?;
+        template("debug").save_in(dir.path())?;
+
+        let loaded = ConversationTemplate::load_from(dir.path(), "review")?;
+        assert_eq!(loaded.prompt, "Review this code");
+        assert_eq!(loaded.extensions, vec!["developer".to_string()]);
+
+        let names: Vec<_> = load_templates(dir.path())?
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["debug", "review"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_name() {
+        let dir = tempdir().unwrap();
+        assert!(template("../escape").save_in(dir.path()).is_err());
+        assert!(ConversationTemplate::load_from(dir.path(), "a/b").is_err());
+    }
+
+    #[test]
+    fn test_render_prompt_attaches_context() -> Result<()> {
+        let dir = tempdir()?;
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "remember the milk")?;
+
+        let mut t = template("notes");
+        t.context = vec![file.to_string_lossy().to_string()];
+        let prompt = t.render_prompt()?;
+        assert!(prompt.starts_with("Review this code"));
+        assert!(prompt.contains("remember the milk"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_prompt_with_converts_context() -> Result<()> {
+        let mut t = template("report");
+        t.context = vec!["report.pdf".to_string()];
+        let prompt = t.render_prompt_with(|path| Ok(format!("[page 1] of {}", path.display())))?;
+        assert!(prompt.contains("Contents of report.pdf:\n```\n[page 1] of report.pdf"));
+        Ok(())
+    }
+}
@@ -0,0 +1,271 @@
+//! Enterprise allowlist of extensions. When an admin installs a policy file, only the
+//! extensions it lists can be enabled; everything else is refused by the extension manager.
+//!
+//! The policy is YAML, signed with Ed25519 like goose releases: the base64 signature over the
+//! file's bytes sits next to it in `<policy>.sig`.
+//!
+//! ```yaml
+//! extensions:
+//!   - name: developer                # an entry with only a name allows the builtin extension
+//!   - name: github
+//!     cmd: /opt/mcp/github-server    # stdio extensions must run exactly this command
+//!   - name: jira
+//!     type: streamable_http
+//!     uri: https://mcp.corp.example/jira
+//!   - name: scanner
+//!     path: /opt/mcp/scanner.wasm    # wasm and plugin extensions must load exactly this file
+//! ```
+//!
+//! An entry only matches extensions of one type: the `type` it names, or else the type its
+//! other fields imply (`stdio` for `cmd`, `sse` or `streamable_http` for `uri`, `wasm` or
+//! `plugin` for `path`, `builtin` without any). Frontend and inline Python extensions have
+//! nothing to pin, so they are only allowed by an entry naming their `type`.
+//!
+//! The policy is read from [`SYSTEM_POLICY_PATH`] if it exists, otherwise from the path in the
+//! `GOOSE_EXTENSION_POLICY` environment variable. The public key is built in through
+//! `GOOSE_POLICY_PUBLIC_KEY`, or read from `extension-policy.pub` beside the policy. A policy
+//! that is present but can't be verified blocks every extension rather than none.
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::agents::ExtensionConfig;
+use crate::config::extensions::name_to_key;
+
+/// Environment variable pointing at a policy, used when there is none at the system path
+pub const POLICY_PATH_ENV: &str = "GOOSE_EXTENSION_POLICY";
+
+/// Where admins install the policy; only writable by administrators on managed machines
+#[cfg(windows)]
+pub const SYSTEM_POLICY_PATH: &str = r"C:\ProgramData\goose\extension-policy.yaml";
+#[cfg(not(windows))]
+pub const SYSTEM_POLICY_PATH: &str = "/etc/goose/extension-policy.yaml";
+
+/// Base64 Ed25519 public key policies are signed with, set by enterprise builds
+const POLICY_PUBLIC_KEY: Option<&str> = option_env!("GOOSE_POLICY_PUBLIC_KEY");
+
+const PUBLIC_KEY_FILE: &str = "extension-policy.pub";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct AllowedExtension {
+    pub name: String,
+    /// Type the extension must have, as in its config, e.g. `stdio` or `streamable_http`
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    /// Command a stdio extension must run
+    #[serde(default)]
+    pub cmd: Option<String>,
+    /// Endpoint an SSE or streamable HTTP extension must connect to
+    #[serde(default)]
+    pub uri: Option<String>,
+    /// Module or library a wasm or plugin extension must load
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl AllowedExtension {
+    fn permits(&self, config: &ExtensionConfig) -> bool {
+        if name_to_key(&self.name) != config.key() {
+            return false;
+        }
+        if self
+            .kind
+            .as_deref()
+            .is_some_and(|kind| kind != config.type_name())
+        {
+            return false;
+        }
+        let pins = |required: &Option<String>, actual: &str| required.as_deref() == Some(actual);
+        let unpinned = self.cmd.is_none() && self.uri.is_none() && self.path.is_none();
+        match config {
+            ExtensionConfig::Stdio { cmd, .. } => pins(&self.cmd, cmd),
+            ExtensionConfig::Sse { uri, .. } | ExtensionConfig::StreamableHttp { uri, .. } => {
+                pins(&self.uri, uri)
+            }
+            ExtensionConfig::Wasm { path, .. } | ExtensionConfig::Plugin { path, .. } => {
+                pins(&self.path, path)
+            }
+            ExtensionConfig::Builtin { .. } => unpinned,
+            ExtensionConfig::Frontend { .. } | ExtensionConfig::InlinePython { .. } => {
+                unpinned && self.kind.is_some()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExtensionPolicy {
+    #[serde(default)]
+    pub extensions: Vec<AllowedExtension>,
+}
+
+impl ExtensionPolicy {
+    /// Parse `policy` after checking `signature` (base64 Ed25519) over its bytes
+    pub fn verify(policy: &[u8], signature: &str, public_key: &str) -> Result<Self> {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let public_key = engine
+            .decode(public_key.trim())
+            .context("Invalid extension policy public key")?;
+        let signature = engine
+            .decode(signature.trim())
+            .context("Extension policy signature is not valid base64")?;
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(policy, &signature)
+            .map_err(|_| anyhow!("Extension policy signature does not match"))?;
+        Ok(serde_yaml::from_slice(policy)?)
+    }
+
+    /// Why `config` may not be enabled, or None if the policy allows it
+    pub fn check(&self, config: &ExtensionConfig) -> Option<String> {
+        if self
+            .extensions
+            .iter()
+            .any(|allowed| allowed.permits(config))
+        {
+            return None;
+        }
+        let listed = self
+            .extensions
+            .iter()
+            .any(|allowed| name_to_key(&allowed.name) == config.key());
+        Some(if listed {
+            format!(
+                "extension '{}' is allowed by your organization's policy only with a different type, command, endpoint or path",
+                config.name()
+            )
+        } else {
+            format!(
+                "extension '{}' is not on your organization's list of allowed extensions",
+                config.name()
+            )
+        })
+    }
+}
+
+fn policy_path() -> Option<PathBuf> {
+    let system = PathBuf::from(SYSTEM_POLICY_PATH);
+    if system.exists() {
+        return Some(system);
+    }
+    std::env::var_os(POLICY_PATH_ENV)
+        .map(PathBuf::from)
+        .filter(|path| !path.as_os_str().is_empty())
+}
+
+fn public_key(policy_path: &Path) -> Result<String> {
+    if let Some(key) = POLICY_PUBLIC_KEY {
+        return Ok(key.to_string());
+    }
+    let path = policy_path.with_file_name(PUBLIC_KEY_FILE);
+    std::fs::read_to_string(&path).with_context(|| {
+        format!(
+            "No public key to verify the policy with at {}",
+            path.display()
+        )
+    })
+}
+
+fn load_from(path: &Path) -> Result<ExtensionPolicy> {
+    let policy = std::fs::read(path)
+        .with_context(|| format!("Failed to read extension policy {}", path.display()))?;
+    let mut signature_path = path.as_os_str().to_owned();
+    signature_path.push(".sig");
+    let signature = std::fs::read_to_string(&signature_path)
+        .context("Extension policy is not signed: missing .sig file")?;
+    ExtensionPolicy::verify(&policy, &signature, &public_key(path)?)
+}
+
+/// Check `config` against the installed policy, if there is one. Returns why the extension
+/// may not be enabled; with a policy that fails verification nothing may be.
+pub fn check_extension(config: &ExtensionConfig) -> Option<String> {
+    let path = policy_path()?;
+    match load_from(&path) {
+        Ok(policy) => policy.check(config),
+        Err(e) => {
+            tracing::error!("Extension policy {} rejected: {:#}", path.display(), e);
+            Some(format!(
+                "the extension policy at {} could not be verified, so no extensions may be enabled ({:#})",
+                path.display(),
+                e
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    const POLICY: &str =
+        "extensions:\n  - name: developer\n  - name: github\n    cmd: /opt/mcp/github\n";
+
+    fn stdio(name: &str, cmd: &str) -> ExtensionConfig {
+        ExtensionConfig::Stdio {
+            name: name.to_string(),
+            cmd: cmd.to_string(),
+            args: vec![],
+            envs: Default::default(),
+            env_keys: vec![],
+            timeout: None,
+            description: None,
+            bundled: None,
+            available_tools: vec![],
+        }
+    }
+
+    fn signed(policy: &str) -> (String, String) {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        (
+            engine.encode(key_pair.sign(policy.as_bytes())),
+            engine.encode(key_pair.public_key()),
+        )
+    }
+
+    #[test]
+    fn test_signature_is_required() {
+        let (signature, public_key) = signed(POLICY);
+        assert!(ExtensionPolicy::verify(POLICY.as_bytes(), &signature, &public_key).is_ok());
+
+        let tampered = POLICY.replace("developer", "anything");
+        assert!(ExtensionPolicy::verify(tampered.as_bytes(), &signature, &public_key).is_err());
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let policy: ExtensionPolicy = serde_yaml::from_str(POLICY).unwrap();
+        assert!(policy.check(&ExtensionConfig::default()).is_none());
+        assert!(policy.check(&stdio("GitHub", "/opt/mcp/github")).is_none());
+
+        let wrong_cmd = policy.check(&stdio("github", "npx")).unwrap();
+        assert!(wrong_cmd.contains("different type, command"));
+        let unlisted = policy.check(&stdio("slack", "npx")).unwrap();
+        assert!(unlisted.contains("not on your organization's list"));
+    }
+
+    #[test]
+    fn test_allowlist_matches_type() {
+        let policy: ExtensionPolicy = serde_yaml::from_str(POLICY).unwrap();
+        // A name-only entry allows the builtin extension, not anything else by that name
+        assert!(policy.check(&stdio("developer", "/tmp/evil")).is_some());
+        let remote =
+            ExtensionConfig::streamable_http("developer", "https://mcp.example.com", "", 300_u64);
+        assert!(policy.check(&remote).is_some());
+
+        let policy: ExtensionPolicy = serde_yaml::from_str(
+            "extensions:\n  - name: jira\n    type: sse\n    uri: https://mcp.corp.example/jira\n",
+        )
+        .unwrap();
+        let sse = ExtensionConfig::sse("jira", "https://mcp.corp.example/jira", "", 300_u64);
+        assert!(policy.check(&sse).is_none());
+        let http =
+            ExtensionConfig::streamable_http("jira", "https://mcp.corp.example/jira", "", 300_u64);
+        assert!(policy.check(&http).is_some());
+    }
+}
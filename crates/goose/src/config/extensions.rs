@@ -10,6 +10,59 @@ pub const DEFAULT_EXTENSION_TIMEOUT: u64 = 300;
 pub const DEFAULT_EXTENSION_DESCRIPTION: &str = "";
 pub const DEFAULT_DISPLAY_NAME: &str = "Developer";
 const EXTENSIONS_CONFIG_KEY: &str = "extensions";
+/// Tools turned off within enabled extensions, by extension name:
+/// `GOOSE_DISABLED_TOOLS: { developer: [shell] }`
+pub const DISABLED_TOOLS_CONFIG_KEY: &str = "GOOSE_DISABLED_TOOLS";
+/// Names and docs the model sees for tools, by extension name and then tool name:
+///
+/// ```yaml
+/// GOOSE_TOOL_OVERRIDES:
+///   github:
+///     search_code:
+///       name: find_code
+///       description: Search code across the organization's repositories.
+///       parameters:
+///         q: GitHub code search query, e.g. "parse_config repo:block/goose"
+/// ```
+pub const TOOL_OVERRIDES_CONFIG_KEY: &str = "GOOSE_TOOL_OVERRIDES";
+/// How a tool name that more than one extension could claim is resolved
+pub const TOOL_COLLISION_POLICY_CONFIG_KEY: &str = "GOOSE_TOOL_COLLISION_POLICY";
+/// Tokens each extension may spend on completions it requests from the configured provider
+/// (MCP sampling), by extension name: `GOOSE_SAMPLING_BUDGETS: { researcher: 50000 }`. The user
+/// approves every request regardless; extensions without a budget are only limited by that.
+pub const SAMPLING_BUDGETS_CONFIG_KEY: &str = "GOOSE_SAMPLING_BUDGETS";
+/// Secrets from the keyring passed to one extension's process only, by extension name and
+/// then environment variable: `GOOSE_EXTENSION_SECRETS: { github: { GITHUB_TOKEN: github_pat } }`.
+/// The variables are removed from every other extension's environment. Only the user config
+/// can set this, and an extension only gets secrets in its own namespace (see
+/// [`is_extension_secret`]).
+pub const EXTENSION_SECRETS_CONFIG_KEY: &str = "GOOSE_EXTENSION_SECRETS";
+
+/// What the model sees for one tool in place of what its MCP server ships
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub struct ToolOverride {
+    /// Alias the tool is offered and called by, without the extension prefix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Descriptions of the tool's parameters, by parameter name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub parameters: HashMap<String, String>,
+}
+
+/// Tools are offered as `<extension>__<tool>`, so when extension names themselves contain `__`
+/// one name can be claimed by two extensions, e.g. `git__hub__status` by `git__hub` (tool
+/// `status`) and by `git` (tool `hub__status`)
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCollisionPolicy {
+    /// The extension with the longest name gets the tool
+    #[default]
+    LongestName,
+    /// No extension gets the tool: it is not offered, and calls to it are refused
+    Drop,
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct ExtensionEntry {
@@ -25,6 +78,38 @@ pub fn name_to_key(name: &str) -> String {
         .to_lowercase()
 }
 
+/// Whether `secret` is in the keyring namespace of `extension`: its name starts with the
+/// extension's key and an underscore, e.g. `github_pat` for `github`
+pub fn is_extension_secret(extension: &str, secret: &str) -> bool {
+    let prefix = format!("{}_", name_to_key(extension));
+    secret.len() > prefix.len() && secret.to_lowercase().starts_with(&prefix)
+}
+
+/// Drop the secrets mapped into an extension from outside its namespace
+fn scope_extension_secrets(
+    secrets: HashMap<String, HashMap<String, String>>,
+) -> HashMap<String, HashMap<String, String>> {
+    secrets
+        .into_iter()
+        .map(|(extension, mut envs)| {
+            envs.retain(|var, secret| {
+                let allowed = is_extension_secret(&extension, secret);
+                if !allowed {
+                    tracing::warn!(
+                        "Not passing secret '{}' to extension '{}' as {}: it is outside the \
+                         extension's namespace",
+                        secret,
+                        extension,
+                        var
+                    );
+                }
+                allowed
+            });
+            (extension, envs)
+        })
+        .collect()
+}
+
 pub struct ExtensionConfigManager;
 
 impl ExtensionConfigManager {
@@ -35,6 +120,18 @@ impl ExtensionConfigManager {
             .unwrap_or_else(|_| HashMap::new()))
     }
 
+    /// Extensions as the user config alone has them, the starting point for any change
+    fn get_user_extensions_map() -> HashMap<String, ExtensionEntry> {
+        Config::global()
+            .get_user_param(EXTENSIONS_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+
+    /// A map from the user config alone, the starting point for any change to it
+    fn get_user_map<T: serde::de::DeserializeOwned>(key: &str) -> HashMap<String, T> {
+        Config::global().get_user_param(key).unwrap_or_default()
+    }
+
     fn save_extensions_map(extensions: HashMap<String, ExtensionEntry>) -> Result<()> {
         let config = Config::global();
         config.set_param(EXTENSIONS_CONFIG_KEY, serde_json::to_value(extensions)?)?;
@@ -50,20 +147,27 @@ impl ExtensionConfigManager {
     }
 
     pub fn set(entry: ExtensionEntry) -> Result<()> {
-        let mut extensions = Self::get_extensions_map()?;
+        let mut extensions = Self::get_user_extensions_map();
         let key = entry.config.key();
         extensions.insert(key, entry);
         Self::save_extensions_map(extensions)
     }
 
     pub fn remove(key: &str) -> Result<()> {
-        let mut extensions = Self::get_extensions_map()?;
+        let mut extensions = Self::get_user_extensions_map();
         extensions.remove(key);
         Self::save_extensions_map(extensions)
     }
 
     pub fn set_enabled(key: &str, enabled: bool) -> Result<()> {
-        let mut extensions = Self::get_extensions_map()?;
+        let mut extensions = Self::get_user_extensions_map();
+        // An extension from the team or system config is copied into the user config to
+        // record the change, without copying any of the others
+        if !extensions.contains_key(key) {
+            if let Some(entry) = Self::get_extensions_map()?.remove(key) {
+                extensions.insert(key.to_string(), entry);
+            }
+        }
         if let Some(entry) = extensions.get_mut(key) {
             entry.enabled = enabled;
             Self::save_extensions_map(extensions)?;
@@ -85,4 +189,122 @@ impl ExtensionConfigManager {
         let extensions = Self::get_extensions_map()?;
         Ok(extensions.get(key).map(|e| e.enabled).unwrap_or(false))
     }
+
+    /// Tools disabled within each extension, by extension name; tool names are unprefixed
+    pub fn get_disabled_tools() -> HashMap<String, Vec<String>> {
+        Config::global()
+            .get_param(DISABLED_TOOLS_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+
+    /// Tool overrides by extension name and then tool name, both as the MCP server names them
+    pub fn get_tool_overrides() -> HashMap<String, HashMap<String, ToolOverride>> {
+        Config::global()
+            .get_param(TOOL_OVERRIDES_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+
+    pub fn get_tool_collision_policy() -> ToolCollisionPolicy {
+        Config::global()
+            .get_param(TOOL_COLLISION_POLICY_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+
+    /// Sampling token budgets by extension name
+    pub fn get_sampling_budgets() -> HashMap<String, i32> {
+        Config::global()
+            .get_param(SAMPLING_BUDGETS_CONFIG_KEY)
+            .unwrap_or_default()
+    }
+
+    /// Limit what `extension` may spend on sampling, or lift its limit with `None`
+    pub fn set_sampling_budget(extension: &str, token_budget: Option<i32>) -> Result<()> {
+        let mut budgets: HashMap<String, i32> = Self::get_user_map(SAMPLING_BUDGETS_CONFIG_KEY);
+        match token_budget {
+            Some(budget) => budgets.insert(extension.to_string(), budget),
+            None => budgets.remove(extension),
+        };
+        Config::global().set_param(SAMPLING_BUDGETS_CONFIG_KEY, serde_json::to_value(budgets)?)?;
+        Ok(())
+    }
+
+    /// Secret names by extension name and then the environment variable they are passed in,
+    /// from the user config only and limited to each extension's namespace
+    pub fn get_extension_secrets() -> HashMap<String, HashMap<String, String>> {
+        scope_extension_secrets(Self::get_user_map(EXTENSION_SECRETS_CONFIG_KEY))
+    }
+
+    /// Replace the secrets passed to `extension`; an empty map passes none
+    pub fn set_extension_secrets(extension: &str, secrets: HashMap<String, String>) -> Result<()> {
+        if let Some(secret) = secrets
+            .values()
+            .find(|secret| !is_extension_secret(extension, secret))
+        {
+            return Err(anyhow::anyhow!(
+                "Secret '{}' is outside the namespace of extension '{}'; its name has to start \
+                 with '{}_'",
+                secret,
+                extension,
+                name_to_key(extension)
+            ));
+        }
+        let mut all_secrets: HashMap<String, HashMap<String, String>> =
+            Self::get_user_map(EXTENSION_SECRETS_CONFIG_KEY);
+        if secrets.is_empty() {
+            all_secrets.remove(extension);
+        } else {
+            all_secrets.insert(extension.to_string(), secrets);
+        }
+        Config::global().set_param(
+            EXTENSION_SECRETS_CONFIG_KEY,
+            serde_json::to_value(all_secrets)?,
+        )?;
+        Ok(())
+    }
+
+    /// Replace the tools disabled within `extension`; an empty list turns them all back on
+    pub fn set_disabled_tools(extension: &str, tools: Vec<String>) -> Result<()> {
+        let mut disabled: HashMap<String, Vec<String>> =
+            Self::get_user_map(DISABLED_TOOLS_CONFIG_KEY);
+        let mut tools = tools;
+        tools.sort();
+        tools.dedup();
+        if tools.is_empty() {
+            disabled.remove(extension);
+        } else {
+            disabled.insert(extension.to_string(), tools);
+        }
+        Config::global().set_param(DISABLED_TOOLS_CONFIG_KEY, serde_json::to_value(disabled)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_secret_namespace() {
+        assert!(is_extension_secret("github", "github_pat"));
+        assert!(is_extension_secret("GitHub", "GITHUB_TOKEN"));
+        assert!(!is_extension_secret("github", "github_"));
+        assert!(!is_extension_secret("github", "openai_api_key"));
+        assert!(!is_extension_secret("git", "github_pat"));
+    }
+
+    #[test]
+    fn test_scope_drops_secrets_of_other_extensions() {
+        let secrets = HashMap::from([(
+            "github".to_string(),
+            HashMap::from([
+                ("GITHUB_TOKEN".to_string(), "github_pat".to_string()),
+                ("OPENAI_API_KEY".to_string(), "openai_api_key".to_string()),
+            ]),
+        )]);
+        let scoped = scope_extension_secrets(secrets);
+        assert_eq!(
+            scoped["github"],
+            HashMap::from([("GITHUB_TOKEN".to_string(), "github_pat".to_string())])
+        );
+    }
 }
@@ -1,16 +1,25 @@
 pub mod base;
+pub mod conversation_templates;
 pub mod custom_providers;
 mod experiments;
+pub mod extension_policy;
 pub mod extensions;
 pub mod permission;
+pub mod secrets;
+pub mod settings;
 pub mod signup_openrouter;
+pub mod team;
 
 pub use crate::agents::ExtensionConfig;
-pub use base::{Config, ConfigError, APP_STRATEGY};
+pub use base::{Config, ConfigError, ConfigSource, EffectiveValue, APP_STRATEGY};
+pub use conversation_templates::ConversationTemplate;
 pub use custom_providers::CustomProviderConfig;
 pub use experiments::ExperimentManager;
-pub use extensions::{ExtensionConfigManager, ExtensionEntry};
+pub use extensions::{
+    is_extension_secret, ExtensionConfigManager, ExtensionEntry, ToolCollisionPolicy, ToolOverride,
+};
 pub use permission::PermissionManager;
+pub use settings::GooseSettings;
 pub use signup_openrouter::configure_openrouter;
 
 pub use extensions::DEFAULT_DISPLAY_NAME;
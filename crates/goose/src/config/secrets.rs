@@ -0,0 +1,475 @@
+//! Where secrets live. The OS keychain (macOS Keychain, Windows Credential Manager or the
+//! Secret Service/libsecret on Linux) is used when it is available, with a YAML file as
+//! the fallback for machines that have none.
+
+use super::base::{Config, ConfigError};
+use keyring::Entry;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use utoipa::ToSchema;
+
+pub(crate) const KEYRING_SERVICE: &str = "goose";
+pub(crate) const KEYRING_USERNAME: &str = "secrets";
+
+/// Words a credential's name ends with, e.g. GITHUB_TOKEN
+const SECRET_SUFFIXES: &[&str] = &["API_KEY", "TOKEN", "SECRET", "PASSWORD", "PASSWD", "PAT"];
+/// Words that mark a credential wherever they appear, e.g. AWS_SECRET_ACCESS_KEY
+const SECRET_WORDS: &[&str] = &[
+    "API_KEY",
+    "APIKEY",
+    "ACCESS_KEY",
+    "PRIVATE_KEY",
+    "CLIENT_SECRET",
+];
+
+/// Keys that hold credentials by naming convention, e.g. OPENAI_API_KEY or
+/// openai-api-key-2
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_uppercase().replace(['-', '.'], "_");
+    // A trailing number names another credential of the same kind
+    let base = key
+        .trim_end_matches(|c: char| c.is_ascii_digit())
+        .trim_end_matches('_');
+    let words = format!("_{}_", base);
+    SECRET_SUFFIXES
+        .iter()
+        .any(|suffix| words.ends_with(&format!("_{}_", suffix)))
+        || SECRET_WORDS
+            .iter()
+            .any(|word| words.contains(&format!("_{}_", word)))
+}
+
+/// Config keys providers declare secret, whatever they are called
+pub fn provider_secret_keys() -> Vec<String> {
+    crate::providers::providers()
+        .into_iter()
+        .flat_map(|p| p.config_keys)
+        .filter(|k| k.secret)
+        .map(|k| k.name)
+        .collect()
+}
+
+/// Storage for all secrets as one map, read and written as a whole
+pub trait SecretStore: Send + Sync {
+    /// Short name for diagnostics, e.g. "keyring"
+    fn name(&self) -> &'static str;
+
+    fn load(&self) -> Result<HashMap<String, Value>, ConfigError>;
+
+    fn save(&self, values: &HashMap<String, Value>) -> Result<(), ConfigError>;
+
+    /// Move secrets out of any older storage this store replaces. Returns how many moved.
+    fn migrate_legacy(&self) -> Result<usize, ConfigError> {
+        Ok(0)
+    }
+}
+
+/// Secrets kept as a single JSON entry in the OS keychain
+pub struct KeyringSecretStore {
+    service: String,
+}
+
+impl KeyringSecretStore {
+    pub fn new(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+        }
+    }
+
+    fn try_load(&self) -> Result<HashMap<String, Value>, keyring::Error> {
+        let entry = Entry::new(&self.service, KEYRING_USERNAME)?;
+        match entry.get_password() {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| keyring::Error::BadEncoding(e.to_string().into_bytes())),
+            Err(keyring::Error::NoEntry) => Ok(HashMap::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn try_save(&self, values: &HashMap<String, Value>) -> Result<(), keyring::Error> {
+        let json_value = serde_json::to_string(values)
+            .map_err(|e| keyring::Error::BadEncoding(e.to_string().into_bytes()))?;
+        Entry::new(&self.service, KEYRING_USERNAME)?.set_password(&json_value)
+    }
+}
+
+impl SecretStore for KeyringSecretStore {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn load(&self) -> Result<HashMap<String, Value>, ConfigError> {
+        Ok(self.try_load()?)
+    }
+
+    fn save(&self, values: &HashMap<String, Value>) -> Result<(), ConfigError> {
+        Ok(self.try_save(values)?)
+    }
+}
+
+/// Secrets kept in a plain YAML file, for machines without a usable keychain
+pub struct FileSecretStore {
+    path: PathBuf,
+}
+
+impl FileSecretStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl SecretStore for FileSecretStore {
+    fn name(&self) -> &'static str {
+        "file"
+    }
+
+    fn load(&self) -> Result<HashMap<String, Value>, ConfigError> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+        let file_content = std::fs::read_to_string(&self.path)?;
+        let yaml_value: serde_yaml::Value = serde_yaml::from_str(&file_content)?;
+        match serde_json::to_value(yaml_value)? {
+            Value::Object(map) => Ok(map.into_iter().collect()),
+            _ => Ok(HashMap::new()),
+        }
+    }
+
+    fn save(&self, values: &HashMap<String, Value>) -> Result<(), ConfigError> {
+        let yaml_value = serde_yaml::to_string(values)?;
+        let temp_path = self.path.with_extension("tmp");
+        std::fs::write(&temp_path, yaml_value)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+// The keychain exists but cannot be reached, e.g. no Secret Service on a headless Linux box
+fn keyring_unavailable(error: &keyring::Error) -> bool {
+    matches!(
+        error,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
+
+/// The keychain, falling back to a file when the platform has no usable keychain
+pub struct FallbackSecretStore {
+    keyring: KeyringSecretStore,
+    file: FileSecretStore,
+    warned: AtomicBool,
+}
+
+impl FallbackSecretStore {
+    pub fn new<P: AsRef<Path>>(service: &str, fallback_path: P) -> Self {
+        Self {
+            keyring: KeyringSecretStore::new(service),
+            file: FileSecretStore::new(fallback_path),
+            warned: AtomicBool::new(false),
+        }
+    }
+
+    fn warn_fallback(&self, error: &keyring::Error) {
+        if !self.warned.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                "System keyring unavailable ({}), storing secrets in {}",
+                error,
+                self.file.path.display()
+            );
+        }
+    }
+}
+
+impl SecretStore for FallbackSecretStore {
+    fn name(&self) -> &'static str {
+        match self.keyring.try_load() {
+            Err(e) if keyring_unavailable(&e) => self.file.name(),
+            _ => self.keyring.name(),
+        }
+    }
+
+    fn load(&self) -> Result<HashMap<String, Value>, ConfigError> {
+        match self.keyring.try_load() {
+            Err(e) if keyring_unavailable(&e) => {
+                self.warn_fallback(&e);
+                self.file.load()
+            }
+            result => Ok(result?),
+        }
+    }
+
+    fn save(&self, values: &HashMap<String, Value>) -> Result<(), ConfigError> {
+        match self.keyring.try_save(values) {
+            Err(e) if keyring_unavailable(&e) => {
+                self.warn_fallback(&e);
+                self.file.save(values)
+            }
+            result => Ok(result?),
+        }
+    }
+
+    /// Once the keychain works, move secrets saved to the fallback file into it
+    fn migrate_legacy(&self) -> Result<usize, ConfigError> {
+        let mut values = match self.keyring.try_load() {
+            Err(e) if keyring_unavailable(&e) => return Ok(0),
+            result => result?,
+        };
+        let legacy = self.file.load()?;
+        if legacy.is_empty() {
+            return Ok(0);
+        }
+
+        let mut moved = 0;
+        for (key, value) in legacy {
+            // Anything already in the keychain is newer than the file
+            if !values.contains_key(&key) {
+                values.insert(key, value);
+                moved += 1;
+            }
+        }
+        self.keyring.try_save(&values)?;
+        std::fs::remove_file(&self.file.path)?;
+        Ok(moved)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretMigrationReport {
+    /// Secret store now in use
+    pub store: String,
+    /// Top-level config.yaml keys moved into the secret store
+    pub config_keys: Vec<String>,
+    /// Extension env vars moved out of config.yaml, as extension/VAR
+    pub extension_envs: Vec<String>,
+    /// Plaintext values left in config.yaml because the secret store, or another extension,
+    /// already holds a different value under the same name
+    pub conflicts: Vec<String>,
+    /// Secrets moved from the fallback file into the keychain
+    pub from_fallback_file: usize,
+}
+
+/// Secrets share one namespace, so a plaintext value can only move when its name is free or
+/// already holds the same value. Returns whether the plaintext copy can be dropped.
+fn claim_secret(
+    secrets: &HashMap<String, Value>,
+    moved: &mut HashMap<String, Value>,
+    name: &str,
+    value: &Value,
+) -> bool {
+    match secrets.get(name).or_else(|| moved.get(name)) {
+        Some(existing) => existing == value,
+        None => {
+            moved.insert(name.to_string(), value.clone());
+            true
+        }
+    }
+}
+
+/// Move plaintext credentials out of config.yaml and into the secret store.
+///
+/// Top-level keys are moved when a provider declares them secret or they follow the
+/// credential naming convention. Extension `envs` entries that look like credentials
+/// become `env_keys`, which extensions already read from the secret store. A value never
+/// replaces a different one already in the store; it stays in config.yaml and is reported
+/// as a conflict. Run on request with `goose config migrate-secrets`.
+pub fn migrate_plaintext_secrets(config: &Config) -> Result<SecretMigrationReport, ConfigError> {
+    let mut report = SecretMigrationReport {
+        from_fallback_file: config.secret_store().migrate_legacy()?,
+        ..Default::default()
+    };
+
+    let provider_secrets = provider_secret_keys();
+
+    let mut secrets = config.secret_store().load()?;
+    let mut values = config.load_values()?;
+    let mut moved: HashMap<String, Value> = HashMap::new();
+
+    let keys: Vec<String> = values
+        .keys()
+        .filter(|k| is_secret_key(k) || provider_secrets.contains(*k))
+        .cloned()
+        .collect();
+    for key in keys {
+        if claim_secret(&secrets, &mut moved, &key, &values[&key]) {
+            values.remove(&key);
+            report.config_keys.push(key);
+        } else {
+            report.conflicts.push(key);
+        }
+    }
+
+    if let Some(Value::Object(extensions)) = values.get_mut("extensions") {
+        for (ext_key, entry) in extensions.iter_mut() {
+            let Some(entry) = entry.as_object_mut() else {
+                continue;
+            };
+            let Some(envs) = entry.get_mut("envs").and_then(Value::as_object_mut) else {
+                continue;
+            };
+            let secret_envs: Vec<String> =
+                envs.keys().filter(|k| is_secret_key(k)).cloned().collect();
+            let mut taken = Vec::new();
+            for name in secret_envs {
+                if claim_secret(&secrets, &mut moved, &name, &envs[&name]) {
+                    envs.remove(&name);
+                    taken.push(name);
+                } else {
+                    report.conflicts.push(format!("{}/{}", ext_key, name));
+                }
+            }
+            if taken.is_empty() {
+                continue;
+            }
+
+            let env_keys = entry
+                .entry("env_keys")
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(env_keys) = env_keys {
+                for name in &taken {
+                    if !env_keys.iter().any(|k| k.as_str() == Some(name)) {
+                        env_keys.push(Value::String(name.clone()));
+                    }
+                }
+            }
+            report.extension_envs.extend(
+                taken
+                    .into_iter()
+                    .map(|name| format!("{}/{}", ext_key, name)),
+            );
+        }
+    }
+
+    if !moved.is_empty() {
+        // Write the secrets first so a failure never loses them
+        secrets.extend(moved);
+        config.secret_store().save(&secrets)?;
+    }
+    if !report.config_keys.is_empty() || !report.extension_envs.is_empty() {
+        config.save_values(values)?;
+    }
+
+    report.config_keys.sort();
+    report.extension_envs.sort();
+    report.conflicts.sort();
+    report.store = config.secret_store().name().to_string();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_secret_key() {
+        assert!(is_secret_key("OPENAI_API_KEY"));
+        assert!(is_secret_key("github_token"));
+        assert!(is_secret_key("OPENAI_API_KEY_2"));
+        assert!(is_secret_key("aws-secret-access-key"));
+        assert!(is_secret_key("TOKEN"));
+        assert!(!is_secret_key("GOOSE_MODEL"));
+        assert!(!is_secret_key("TOKEN_LIMIT"));
+        assert!(!is_secret_key("GOOSE_SECRET_SCAN"));
+        assert!(!is_secret_key("GOOSE_MAX_TOKENS"));
+    }
+
+    #[test]
+    fn test_migrate_plaintext_secrets() -> Result<(), ConfigError> {
+        let dir = tempdir()?;
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )?;
+        config.set_param("GOOSE_MODEL", json!("gpt-4o"))?;
+        config.set_param("MIGRATETEST_API_KEY", json!("sk-plain"))?;
+        config.set_param(
+            "extensions",
+            json!({
+                "github": {
+                    "type": "stdio",
+                    "name": "github",
+                    "cmd": "github-mcp",
+                    "args": [],
+                    "envs": {"GITHUB_TOKEN": "ghp-plain", "LOG_LEVEL": "info"},
+                    "enabled": true
+                }
+            }),
+        )?;
+
+        let report = migrate_plaintext_secrets(&config)?;
+        assert_eq!(report.config_keys, vec!["MIGRATETEST_API_KEY"]);
+        assert_eq!(report.extension_envs, vec!["github/GITHUB_TOKEN"]);
+        assert_eq!(report.store, "file");
+
+        let values = config.load_values()?;
+        assert!(!values.contains_key("MIGRATETEST_API_KEY"));
+        assert_eq!(values["GOOSE_MODEL"], json!("gpt-4o"));
+        let github = &values["extensions"]["github"];
+        assert_eq!(github["envs"], json!({"LOG_LEVEL": "info"}));
+        assert_eq!(github["env_keys"], json!(["GITHUB_TOKEN"]));
+
+        let secrets = config.load_secrets()?;
+        assert_eq!(secrets["MIGRATETEST_API_KEY"], json!("sk-plain"));
+        assert_eq!(secrets["GITHUB_TOKEN"], json!("ghp-plain"));
+
+        // Nothing left to move the second time
+        let again = migrate_plaintext_secrets(&config)?;
+        assert!(again.config_keys.is_empty() && again.extension_envs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_plaintext_secrets_never_overwrites() -> Result<(), ConfigError> {
+        let dir = tempdir()?;
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )?;
+        config.set_secret("MIGRATETEST_API_KEY", json!("sk-stored"))?;
+        config.set_param("MIGRATETEST_API_KEY", json!("sk-plain"))?;
+        let extension = |token: &str| {
+            json!({
+                "type": "stdio",
+                "name": "github",
+                "cmd": "github-mcp",
+                "args": [],
+                "envs": {"GITHUB_TOKEN": token},
+                "enabled": true
+            })
+        };
+        config.set_param(
+            "extensions",
+            json!({"github": extension("ghp-work"), "github_personal": extension("ghp-home")}),
+        )?;
+
+        let report = migrate_plaintext_secrets(&config)?;
+        assert!(report.config_keys.is_empty());
+        assert_eq!(report.extension_envs, vec!["github/GITHUB_TOKEN"]);
+        assert_eq!(
+            report.conflicts,
+            vec!["MIGRATETEST_API_KEY", "github_personal/GITHUB_TOKEN"]
+        );
+
+        let secrets = config.load_secrets()?;
+        assert_eq!(secrets["MIGRATETEST_API_KEY"], json!("sk-stored"));
+        assert_eq!(secrets["GITHUB_TOKEN"], json!("ghp-work"));
+        let values = config.load_values()?;
+        assert_eq!(values["MIGRATETEST_API_KEY"], json!("sk-plain"));
+        assert_eq!(
+            values["extensions"]["github_personal"]["envs"],
+            json!({"GITHUB_TOKEN": "ghp-home"})
+        );
+        Ok(())
+    }
+}
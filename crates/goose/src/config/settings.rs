@@ -0,0 +1,322 @@
+use super::base::{Config, ConfigError};
+use crate::permission::RiskCategory;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+pub const GOOSE_MODES: &[&str] = &["auto", "approve", "smart_approve", "chat"];
+
+/// Config keys covered by [`GooseSettings`], in field order
+pub const SETTINGS_KEYS: &[&str] = &[
+    "GOOSE_PROVIDER",
+    "GOOSE_MODEL",
+    "GOOSE_MODE",
+    "GOOSE_TEMPERATURE",
+    "GOOSE_MAX_TURNS",
+    "GOOSE_MAX_RUN_SECONDS",
+    "GOOSE_MAX_TOOL_CALLS",
+    "GOOSE_APPROVAL_TIMEOUT",
+    "GOOSE_AUTO_COMPACT_THRESHOLD",
+    "GOOSE_AUTO_APPROVE_CATEGORIES",
+    "GOOSE_ANALYTICS_ENABLED",
+    "GOOSE_PREFER_LOCAL_OCR",
+];
+
+/// The typed subset of config.yaml that clients can read and edit. Every field is
+/// optional so the same shape works for reads and partial updates.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", deny_unknown_fields)]
+pub struct GooseSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_model: Option<String>,
+    /// One of auto, approve, smart_approve or chat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_mode: Option<String>,
+    /// Sampling temperature between 0 and 2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_temperature: Option<f32>,
+    /// Turns the agent may take without user input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_max_turns: Option<u32>,
+    /// Wall-clock seconds a run may take unless its session or recipe sets a limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_max_run_seconds: Option<u64>,
+    /// Tool calls a run may make unless its session or recipe sets a limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_max_tool_calls: Option<u32>,
+    /// Seconds a tool confirmation waits before it is denied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_approval_timeout: Option<u64>,
+    /// Fraction of the context window that triggers auto-compaction, above 0 and at most 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_auto_compact_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_auto_approve_categories: Option<Vec<RiskCategory>>,
+    /// Record anonymous usage events locally; off unless set to true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_analytics_enabled: Option<bool>,
+    /// Have the developer extension read images with a local tesseract install instead of
+    /// sending them to the model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goose_prefer_local_ocr: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct SettingsValidationError {
+    /// The config key or secret name that failed
+    pub field: String,
+    pub message: String,
+}
+
+impl SettingsValidationError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl GooseSettings {
+    /// Read the current settings. Values that do not match their type are left out
+    /// rather than failing the whole read.
+    pub fn load(config: &Config) -> Result<Self, ConfigError> {
+        let mut values = Map::new();
+        for key in SETTINGS_KEYS {
+            let Ok(value) = config.get_param::<Value>(key) else {
+                continue;
+            };
+            let entry = Map::from_iter([(key.to_string(), value)]);
+            match serde_json::from_value::<GooseSettings>(Value::Object(entry.clone())) {
+                Ok(_) => values.extend(entry),
+                Err(e) => tracing::warn!("Ignoring invalid config value for {}: {}", key, e),
+            }
+        }
+        Ok(serde_json::from_value(Value::Object(values))?)
+    }
+
+    pub fn validate(&self) -> Vec<SettingsValidationError> {
+        let mut errors = Vec::new();
+
+        if let Some(provider) = &self.goose_provider {
+            if !crate::providers::providers()
+                .iter()
+                .any(|p| p.name == *provider)
+            {
+                errors.push(SettingsValidationError::new(
+                    "GOOSE_PROVIDER",
+                    format!("unknown provider '{}'", provider),
+                ));
+            }
+        }
+        if self
+            .goose_model
+            .as_ref()
+            .is_some_and(|m| m.trim().is_empty())
+        {
+            errors.push(SettingsValidationError::new(
+                "GOOSE_MODEL",
+                "model cannot be empty",
+            ));
+        }
+        if let Some(mode) = &self.goose_mode {
+            if !GOOSE_MODES.contains(&mode.as_str()) {
+                errors.push(SettingsValidationError::new(
+                    "GOOSE_MODE",
+                    format!("mode must be one of: {}", GOOSE_MODES.join(", ")),
+                ));
+            }
+        }
+        if self
+            .goose_temperature
+            .is_some_and(|t| !(0.0..=2.0).contains(&t))
+        {
+            errors.push(SettingsValidationError::new(
+                "GOOSE_TEMPERATURE",
+                "temperature must be between 0 and 2",
+            ));
+        }
+        if self.goose_max_turns == Some(0) {
+            errors.push(SettingsValidationError::new(
+                "GOOSE_MAX_TURNS",
+                "max turns must be at least 1",
+            ));
+        }
+        if self.goose_max_run_seconds == Some(0) {
+            errors.push(SettingsValidationError::new(
+                "GOOSE_MAX_RUN_SECONDS",
+                "max run time must be at least 1 second",
+            ));
+        }
+        if self.goose_max_tool_calls == Some(0) {
+            errors.push(SettingsValidationError::new(
+                "GOOSE_MAX_TOOL_CALLS",
+                "max tool calls must be at least 1",
+            ));
+        }
+        if self.goose_approval_timeout == Some(0) {
+            errors.push(SettingsValidationError::new(
+                "GOOSE_APPROVAL_TIMEOUT",
+                "approval timeout must be at least 1 second",
+            ));
+        }
+        if self
+            .goose_auto_compact_threshold
+            .is_some_and(|t| t <= 0.0 || t > 1.0)
+        {
+            errors.push(SettingsValidationError::new(
+                "GOOSE_AUTO_COMPACT_THRESHOLD",
+                "threshold must be above 0 and at most 1",
+            ));
+        }
+
+        errors
+    }
+
+    /// Write every field that is set, leaving the others untouched
+    pub fn apply(&self, config: &Config) -> Result<(), ConfigError> {
+        if let Value::Object(values) = serde_json::to_value(self)? {
+            for (key, value) in values {
+                config.set_param(&key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Secret names are stored as env-style keys, e.g. OPENAI_API_KEY
+pub fn validate_secrets(secrets: &HashMap<String, String>) -> Vec<SettingsValidationError> {
+    let mut errors = Vec::new();
+    for (name, value) in secrets {
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if !valid_name {
+            errors.push(SettingsValidationError::new(
+                name,
+                "secret names may only contain A-Z, 0-9 and _",
+            ));
+        } else if SETTINGS_KEYS.contains(&name.as_str()) {
+            errors.push(SettingsValidationError::new(
+                name,
+                "this is a regular setting, not a secret",
+            ));
+        } else if value.is_empty() {
+            errors.push(SettingsValidationError::new(name, "secret cannot be empty"));
+        }
+    }
+    errors.sort_by(|a, b| a.field.cmp(&b.field));
+    errors
+}
+
+/// Store secrets in the secret store (the keyring unless disabled) and drop any copy
+/// that was previously saved in plaintext config.
+pub fn store_secrets(
+    config: &Config,
+    secrets: &HashMap<String, String>,
+) -> Result<(), ConfigError> {
+    let plaintext = config.load_values()?;
+    for (name, value) in secrets {
+        config.set_secret(name, Value::String(value.clone()))?;
+        if plaintext.contains_key(name) {
+            config.delete(name)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_settings_keys_match_fields() {
+        let settings = GooseSettings {
+            goose_provider: Some(String::new()),
+            goose_model: Some(String::new()),
+            goose_mode: Some(String::new()),
+            goose_temperature: Some(0.0),
+            goose_max_turns: Some(1),
+            goose_max_run_seconds: Some(1),
+            goose_max_tool_calls: Some(1),
+            goose_approval_timeout: Some(1),
+            goose_auto_compact_threshold: Some(0.5),
+            goose_auto_approve_categories: Some(Vec::new()),
+            goose_analytics_enabled: Some(false),
+            goose_prefer_local_ocr: Some(false),
+        };
+        let value = serde_json::to_value(&settings).unwrap();
+        let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        let mut expected: Vec<_> = SETTINGS_KEYS.iter().map(|k| k.to_string()).collect();
+        keys.sort();
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn test_validate() {
+        let settings = GooseSettings {
+            goose_mode: Some("yolo".to_string()),
+            goose_temperature: Some(3.0),
+            goose_max_turns: Some(0),
+            goose_max_tool_calls: Some(0),
+            goose_auto_compact_threshold: Some(0.8),
+            ..Default::default()
+        };
+        let fields: Vec<_> = settings.validate().into_iter().map(|e| e.field).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "GOOSE_MODE",
+                "GOOSE_TEMPERATURE",
+                "GOOSE_MAX_TURNS",
+                "GOOSE_MAX_TOOL_CALLS"
+            ]
+        );
+
+        let secrets = HashMap::from([
+            ("OPENAI_API_KEY".to_string(), "sk-test".to_string()),
+            ("bad-name".to_string(), "x".to_string()),
+            ("GOOSE_MODEL".to_string(), "gpt-4o".to_string()),
+        ]);
+        let fields: Vec<_> = validate_secrets(&secrets)
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(fields, vec!["GOOSE_MODEL", "bad-name"]);
+    }
+
+    #[test]
+    fn test_apply_load_and_store_secrets() -> Result<(), ConfigError> {
+        let config_file = NamedTempFile::new().unwrap();
+        let secrets_file = NamedTempFile::new().unwrap();
+        let config = Config::new_with_file_secrets(config_file.path(), secrets_file.path())?;
+        config.set_param("GOOSE_MAX_TURNS", Value::String("many".to_string()))?;
+        config.set_param("TEST_API_KEY", Value::String("plaintext".to_string()))?;
+
+        GooseSettings {
+            goose_mode: Some("approve".to_string()),
+            goose_temperature: Some(0.5),
+            ..Default::default()
+        }
+        .apply(&config)?;
+
+        let loaded = GooseSettings::load(&config)?;
+        assert_eq!(loaded.goose_mode.as_deref(), Some("approve"));
+        assert_eq!(loaded.goose_temperature, Some(0.5));
+        // The invalid value is skipped instead of failing the load
+        assert_eq!(loaded.goose_max_turns, None);
+
+        let secrets = HashMap::from([("TEST_API_KEY".to_string(), "sk-test".to_string())]);
+        store_secrets(&config, &secrets)?;
+        let stored: String = config.get_secret("TEST_API_KEY")?;
+        assert_eq!(stored, "sk-test");
+        assert!(!config.load_values()?.contains_key("TEST_API_KEY"));
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+//! Moving config between machines: export/import of the user config, and syncing a
+//! shared team config that sits beneath each user's own settings.
+
+use super::base::{Config, ConfigError, TRUSTED_PROJECTS_CONFIG_KEY};
+use super::secrets::{is_secret_key, provider_secret_keys};
+use crate::agents::native_plugin::PLUGIN_GRANTS_KEY;
+use crate::governance::LEGAL_HOLDS_KEY;
+use crate::session::storage::SESSION_ROOTS_KEY;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path};
+use tokio::process::Command;
+use utoipa::ToSchema;
+
+/// Config key naming where the team config comes from
+pub const TEAM_CONFIG_KEY: &str = "GOOSE_TEAM_CONFIG";
+
+const DEFAULT_GIT_CONFIG_PATH: &str = "config.yaml";
+
+/// Settings about this machine rather than how goose is set up. They are left out of
+/// exports and kept when an import replaces the config.
+const MACHINE_KEYS: &[&str] = &[
+    TEAM_CONFIG_KEY,
+    TRUSTED_PROJECTS_CONFIG_KEY,
+    SESSION_ROOTS_KEY,
+    LEGAL_HOLDS_KEY,
+    // Approving native code is up to the user at this machine
+    PLUGIN_GRANTS_KEY,
+];
+
+/// Whether a key holds a credential, by naming convention or because a provider says so
+fn is_secret(key: &str, provider_secrets: &[String]) -> bool {
+    is_secret_key(key) || provider_secrets.iter().any(|k| k == key)
+}
+
+/// Drop credentials from extension `envs`; extensions read those from the secret store
+/// through `env_keys` instead
+fn strip_extension_secrets(extensions: &mut Value) {
+    let Value::Object(extensions) = extensions else {
+        return;
+    };
+    for entry in extensions.values_mut() {
+        if let Some(Value::Object(envs)) = entry.get_mut("envs") {
+            envs.retain(|name, _| !is_secret_key(name));
+        }
+    }
+}
+
+/// Where a team config is fetched from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TeamConfigSource {
+    /// A YAML file served over https
+    Http(String),
+    /// A file inside a git repository, written as `git+<repo url>[#<path>]`
+    Git { repo: String, path: String },
+}
+
+/// The team config can add extensions, which run commands on this machine, so it is only
+/// fetched over transports that authenticate the server
+fn is_unencrypted(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("git://")
+}
+
+/// Whether `path` stays inside the directory it is joined to
+fn is_contained(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+impl TeamConfigSource {
+    pub fn parse(source: &str) -> Result<Self> {
+        let source = source.trim();
+        if let Some(rest) = source.strip_prefix("git+") {
+            let (repo, path) = match rest.split_once('#') {
+                Some((repo, path)) => (repo, path),
+                None => (rest, DEFAULT_GIT_CONFIG_PATH),
+            };
+            if repo.is_empty() || is_unencrypted(repo) || path.is_empty() || !is_contained(path) {
+                bail!("Invalid git team config source: {}", source);
+            }
+            Ok(Self::Git {
+                repo: repo.to_string(),
+                path: path.to_string(),
+            })
+        } else if source.starts_with("https://") {
+            Ok(Self::Http(source.to_string()))
+        } else {
+            bail!(
+                "Team config source must be an https URL or git+<repo>[#<path>], got: {}",
+                source
+            )
+        }
+    }
+
+    async fn fetch(&self) -> Result<String> {
+        match self {
+            Self::Http(url) => {
+                let response = reqwest::get(url).await?.error_for_status()?;
+                Ok(response.text().await?)
+            }
+            Self::Git { repo, path } => {
+                let checkout = tempfile::tempdir()?;
+                let status = Command::new("git")
+                    .args(["clone", "--depth", "1", "--quiet", "--", repo])
+                    .arg(checkout.path())
+                    .status()
+                    .await
+                    .context("Failed to run git")?;
+                if !status.success() {
+                    bail!("Failed to clone {}", repo);
+                }
+                let file = checkout
+                    .path()
+                    .join(path)
+                    .canonicalize()
+                    .with_context(|| format!("{} not found in {}", path, repo))?;
+                // A symlink in the repository could still point outside the checkout
+                if !file.starts_with(checkout.path().canonicalize()?) {
+                    bail!("{} is outside of {}", path, repo);
+                }
+                Ok(std::fs::read_to_string(file)?)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSyncResult {
+    pub source: String,
+    /// Number of keys the team config sets
+    pub keys: usize,
+    /// Unix timestamp (seconds) of the sync
+    pub synced_at: i64,
+}
+
+fn parse_mapping(content: &str) -> Result<HashMap<String, Value>> {
+    if content.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+    let yaml: serde_yaml::Value = serde_yaml::from_str(content)?;
+    match serde_json::to_value(yaml)? {
+        Value::Object(map) => Ok(map.into_iter().collect()),
+        _ => Err(anyhow!("Config must be a YAML mapping of keys to values")),
+    }
+}
+
+/// Fetch the team config named by GOOSE_TEAM_CONFIG (or `source`, which is then saved
+/// as the new source) and cache it as the team layer.
+pub async fn sync_team_config(config: &Config, source: Option<&str>) -> Result<TeamSyncResult> {
+    let source = match source {
+        Some(source) => {
+            TeamConfigSource::parse(source)?;
+            config.set_param(TEAM_CONFIG_KEY, Value::String(source.to_string()))?;
+            source.to_string()
+        }
+        None => config
+            .get_param::<String>(TEAM_CONFIG_KEY)
+            .map_err(|_| anyhow!("No team config source set; set {}", TEAM_CONFIG_KEY))?,
+    };
+    let cache_path = config
+        .team_config_path()
+        .ok_or_else(|| anyhow!("This config has no team layer"))?;
+
+    let content = TeamConfigSource::parse(&source)?.fetch().await?;
+    let values = parse_mapping(&content)?;
+    let provider_secrets = provider_secret_keys();
+    if let Some(key) = values.keys().find(|k| is_secret(k, &provider_secrets)) {
+        bail!("Team config must not contain secrets, found {}", key);
+    }
+
+    let temp_path = cache_path.with_extension("tmp");
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, cache_path)?;
+
+    Ok(TeamSyncResult {
+        source,
+        keys: values.len(),
+        synced_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// The user config as YAML, without secrets or settings that only make sense on this machine
+pub fn export_config(config: &Config) -> Result<String, ConfigError> {
+    let provider_secrets = provider_secret_keys();
+    let mut values: std::collections::BTreeMap<_, _> = config
+        .load_values()?
+        .into_iter()
+        .filter(|(key, _)| {
+            !is_secret(key, &provider_secrets) && !MACHINE_KEYS.contains(&key.as_str())
+        })
+        .collect();
+    if let Some(extensions) = values.get_mut("extensions") {
+        strip_extension_secrets(extensions);
+    }
+    Ok(serde_yaml::to_string(&values)?)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub values: usize,
+    pub secrets: usize,
+}
+
+/// Import exported YAML into the user config, merging over existing values unless
+/// `replace` is set, in which case only this machine's own settings are kept. Credential
+/// keys are moved to the secret store.
+pub fn import_config(config: &Config, content: &str, replace: bool) -> Result<ImportSummary> {
+    let provider_secrets = provider_secret_keys();
+    let (secrets, values): (HashMap<_, _>, HashMap<_, _>) = parse_mapping(content)?
+        .into_iter()
+        .filter(|(key, _)| !MACHINE_KEYS.contains(&key.as_str()))
+        .partition(|(key, _)| is_secret(key, &provider_secrets));
+
+    let mut merged = config.load_values()?;
+    if replace {
+        merged.retain(|key, _| MACHINE_KEYS.contains(&key.as_str()));
+    }
+    let summary = ImportSummary {
+        values: values.len(),
+        secrets: secrets.len(),
+    };
+    merged.extend(values);
+    config.save_values(merged)?;
+
+    for (key, value) in secrets {
+        config.set_secret(&key, value)?;
+    }
+    Ok(summary)
+}
+
+pub fn import_config_file(config: &Config, path: &Path, replace: bool) -> Result<ImportSummary> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    import_config(config, &content, replace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            TeamConfigSource::parse("https://example.com/goose.yaml").unwrap(),
+            TeamConfigSource::Http("https://example.com/goose.yaml".to_string())
+        );
+        assert_eq!(
+            TeamConfigSource::parse("git+https://github.com/acme/goose-config.git#team/goose.yaml")
+                .unwrap(),
+            TeamConfigSource::Git {
+                repo: "https://github.com/acme/goose-config.git".to_string(),
+                path: "team/goose.yaml".to_string(),
+            }
+        );
+        assert_eq!(
+            TeamConfigSource::parse("git+git@github.com:acme/goose-config.git").unwrap(),
+            TeamConfigSource::Git {
+                repo: "git@github.com:acme/goose-config.git".to_string(),
+                path: DEFAULT_GIT_CONFIG_PATH.to_string(),
+            }
+        );
+        assert!(TeamConfigSource::parse("/etc/goose.yaml").is_err());
+        assert!(TeamConfigSource::parse("http://example.com/goose.yaml").is_err());
+        assert!(TeamConfigSource::parse("git+http://example.com/goose-config.git").is_err());
+        assert!(TeamConfigSource::parse("git+git://example.com/goose-config.git").is_err());
+        assert!(TeamConfigSource::parse("git+https://x.git#../secrets").is_err());
+        assert!(TeamConfigSource::parse("git+https://x.git#team/../../secrets").is_err());
+        assert!(TeamConfigSource::parse("git+https://x.git#/home/me/.ssh/id_rsa").is_err());
+    }
+
+    #[test]
+    fn test_export_import_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let source = Config::new_with_file_secrets(
+            dir.path().join("source.yaml"),
+            dir.path().join("source-secrets.yaml"),
+        )?;
+        source.set_param("GOOSE_MODEL", Value::String("gpt-4o".to_string()))?;
+        source.set_param(
+            "TEAMTEST_OPENAI_API_KEY",
+            Value::String("sk-leaked".to_string()),
+        )?;
+        source.set_param(
+            "TEAMTEST_OPENAI_API_KEY_2",
+            Value::String("sk-leaked-2".to_string()),
+        )?;
+        source.set_param(
+            "extensions",
+            serde_json::json!({
+                "github": {"envs": {"GITHUB_TOKEN": "ghp-leaked", "LOG_LEVEL": "debug"}}
+            }),
+        )?;
+
+        let exported = export_config(&source)?;
+        assert!(exported.contains("GOOSE_MODEL"));
+        assert!(exported.contains("LOG_LEVEL"));
+        assert!(!exported.contains("sk-leaked"));
+        assert!(!exported.contains("ghp-leaked"));
+
+        let target = Config::new_with_file_secrets(
+            dir.path().join("target.yaml"),
+            dir.path().join("target-secrets.yaml"),
+        )?;
+        target.set_param("GOOSE_MODE", Value::String("approve".to_string()))?;
+        target.set_param(
+            TEAM_CONFIG_KEY,
+            Value::String("https://example.com/goose.yaml".to_string()),
+        )?;
+        let summary = import_config(
+            &target,
+            &format!("{}TEAMTEST_ANTHROPIC_API_KEY: sk-imported\n", exported),
+            false,
+        )?;
+        assert_eq!(
+            summary,
+            ImportSummary {
+                values: 2,
+                secrets: 1
+            }
+        );
+
+        let values = target.load_values()?;
+        assert_eq!(values.get("GOOSE_MODEL"), Some(&Value::from("gpt-4o")));
+        assert_eq!(values.get("GOOSE_MODE"), Some(&Value::from("approve")));
+        assert!(!values.contains_key("TEAMTEST_ANTHROPIC_API_KEY"));
+        let secret: String = target.get_secret("TEAMTEST_ANTHROPIC_API_KEY")?;
+        assert_eq!(secret, "sk-imported");
+
+        import_config(&target, "GOOSE_PROVIDER: openai\n", true)?;
+        let values = target.load_values()?;
+        assert!(!values.contains_key("GOOSE_MODE"));
+        assert!(values.contains_key(TEAM_CONFIG_KEY));
+        Ok(())
+    }
+}
@@ -268,6 +268,10 @@ mod tests {
             accumulated_total_tokens: Some(100),
             accumulated_input_tokens: Some(50),
             accumulated_output_tokens: Some(50),
+            checksum: None,
+            style: None,
+            attached_resources: Vec::new(),
+            comparison_id: None,
         }
     }
 
@@ -3,7 +3,7 @@ use crate::conversation::Conversation;
 use crate::utils::safe_truncate;
 use anyhow::{anyhow, Result};
 use rmcp::model::{RawContent, ResourceContents, Role};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::DerefMut;
 use tracing::{debug, warn};
 
@@ -134,6 +134,45 @@ fn truncate_message_content(message: &Message, max_content_size: usize) -> Resul
     Ok(new_message)
 }
 
+/// The full output of each tool response that `truncated` only holds a shortened copy of, as
+/// `(tool call id, output)` pairs, so callers can keep it outside the context
+pub fn truncated_tool_results(
+    original: &[Message],
+    truncated: &[Message],
+) -> Vec<(String, String)> {
+    let shortened: HashMap<&str, String> = tool_outputs(truncated).collect();
+    tool_outputs(original)
+        .filter(|(id, output)| shortened.get(id).is_some_and(|short| short != output))
+        .map(|(id, output)| (id.to_string(), output))
+        .collect()
+}
+
+/// Tool call id and text output of every successful tool response in `messages`
+fn tool_outputs(messages: &[Message]) -> impl Iterator<Item = (&str, String)> {
+    messages
+        .iter()
+        .flat_map(|message| message.content.iter())
+        .filter_map(|content| {
+            let MessageContent::ToolResponse(tool_response) = content else {
+                return None;
+            };
+            let result = tool_response.tool_result.as_ref().ok()?;
+            let output = result
+                .iter()
+                .filter_map(|item| match &item.raw {
+                    RawContent::Text(text_content) => Some(text_content.text.as_str()),
+                    RawContent::Resource(resource) => match &resource.resource {
+                        ResourceContents::TextResourceContents { text, .. } => Some(text.as_str()),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some((tool_response.id.as_str(), output))
+        })
+}
+
 /// Estimates token count for a message using a simple heuristic
 fn estimate_message_tokens(message: &Message, estimate_fn: &dyn Fn(&str) -> usize) -> usize {
     let mut total_tokens = 10; // Base overhead for message structure
@@ -497,6 +536,12 @@ mod tests {
             context_limit
         );
 
+        // The full output is reported for the shortened tool response only
+        let full_outputs = truncated_tool_results(&messages, truncated_messages.messages());
+        assert_eq!(full_outputs.len(), 1);
+        assert_eq!(full_outputs[0].0, "tool1");
+        assert_eq!(full_outputs[0].1.len(), 50000);
+
         Ok(())
     }
 
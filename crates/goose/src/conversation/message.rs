@@ -11,6 +11,7 @@ use std::fmt;
 use utoipa::ToSchema;
 
 use crate::conversation::tool_result_serde;
+use crate::providers::base::ProviderUsage;
 use crate::utils::sanitize_unicode_tags;
 
 /// Custom deserializer for MessageContent that sanitizes Unicode Tags in text content
@@ -374,6 +375,30 @@ pub struct Message {
     pub created: i64,
     #[serde(deserialize_with = "deserialize_sanitized_content")]
     pub content: Vec<MessageContent>,
+    /// Provider usage for the request that produced this message, on assistant messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<MessageUsage>,
+}
+
+/// Tokens a provider reported for one completion request
+#[derive(ToSchema, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageUsage {
+    pub model: String,
+    pub input_tokens: Option<i32>,
+    pub output_tokens: Option<i32>,
+    pub total_tokens: Option<i32>,
+}
+
+impl From<&ProviderUsage> for MessageUsage {
+    fn from(usage: &ProviderUsage) -> Self {
+        MessageUsage {
+            model: usage.model.clone(),
+            input_tokens: usage.usage.input_tokens,
+            output_tokens: usage.usage.output_tokens,
+            total_tokens: usage.usage.total_tokens,
+        }
+    }
 }
 
 impl fmt::Debug for Message {
@@ -400,6 +425,7 @@ impl Message {
             role,
             created,
             content,
+            usage: None,
         }
     }
     pub fn debug(&self) -> String {
@@ -413,6 +439,7 @@ impl Message {
             role: Role::User,
             created: Utc::now().timestamp(),
             content: Vec::new(),
+            usage: None,
         }
     }
 
@@ -423,6 +450,7 @@ impl Message {
             role: Role::Assistant,
             created: Utc::now().timestamp(),
             content: Vec::new(),
+            usage: None,
         }
     }
 
@@ -431,6 +459,11 @@ impl Message {
         self
     }
 
+    pub fn with_usage(mut self, usage: MessageUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
     /// Add any MessageContent to the message
     pub fn with_content(mut self, content: MessageContent) -> Self {
         self.content.push(content);
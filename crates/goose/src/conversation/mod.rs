@@ -46,6 +46,10 @@ impl Conversation {
             .last_mut()
             .filter(|m| m.id.is_some() && m.id == message.id)
         {
+            // Streaming providers may report usage in a chunk of its own
+            if message.usage.is_some() {
+                last.usage = message.usage.clone();
+            }
             match (last.content.last_mut(), message.content.last()) {
                 (Some(MessageContent::Text(ref mut last)), Some(MessageContent::Text(new)))
                     if message.content.len() == 1 =>
@@ -360,7 +364,7 @@ pub fn debug_conversation_fix(
 
 #[cfg(test)]
 mod tests {
-    use crate::conversation::message::Message;
+    use crate::conversation::message::{Message, MessageUsage};
     use crate::conversation::{debug_conversation_fix, fix_conversation, Conversation};
     use mcp_core::tool::ToolCall;
     use rmcp::model::Role;
@@ -384,6 +388,29 @@ mod tests {
         (fixed.messages().clone(), issues)
     }
 
+    #[test]
+    fn test_push_joins_streamed_usage() {
+        let usage = MessageUsage {
+            model: "gpt-4o".to_string(),
+            input_tokens: Some(1200),
+            output_tokens: Some(30),
+            total_tokens: Some(1230),
+        };
+        let mut conversation = Conversation::empty();
+        conversation.push(Message::assistant().with_id("msg-1").with_text("Hello"));
+        conversation.push(Message::assistant().with_id("msg-1").with_text(" there"));
+        conversation.push(
+            Message::assistant()
+                .with_id("msg-1")
+                .with_usage(usage.clone()),
+        );
+
+        assert_eq!(conversation.len(), 1);
+        let message = conversation.last().unwrap();
+        assert_eq!(message.as_concat_text(), "Hello there");
+        assert_eq!(message.usage, Some(usage));
+    }
+
     #[test]
     fn test_valid_conversation() {
         let all_messages = vec![
@@ -0,0 +1,289 @@
+//! A zip of everything useful for a bug report, with credentials scrubbed out:
+//! version info, the config, recent logs and the metadata of one session.
+
+use crate::config::secrets::is_secret_key;
+use crate::config::{Config, APP_STRATEGY};
+use crate::session;
+use anyhow::Result;
+use etcetera::{choose_app_strategy, AppStrategy};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Only the end of each log is kept; that is where the failure is
+const MAX_LOG_BYTES: u64 = 512 * 1024;
+const DEFAULT_MAX_LOG_FILES: usize = 5;
+
+// Credentials that can show up in logs without going through the secret store
+static SECRET_PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    vec![
+        (
+            Regex::new(r"(?i)\b(bearer)\s+[A-Za-z0-9._~+/=-]+").unwrap(),
+            "$1 [REDACTED]",
+        ),
+        (
+            Regex::new(r"\b(sk|pk|ghp|gho|xox[abp])[-_][A-Za-z0-9_-]{12,}").unwrap(),
+            REDACTED,
+        ),
+        (
+            Regex::new(
+                r#"(?i)("?[A-Za-z0-9_-]*(?:api[_-]?key|token|secret|password)"?\s*[:=]\s*"?)[^\s",}]+"#,
+            )
+            .unwrap(),
+            "${1}[REDACTED]",
+        ),
+    ]
+});
+
+pub struct BundleOptions {
+    /// Session whose metadata is included, usually the one that failed
+    pub session_file: Option<PathBuf>,
+    /// Where logs are read from; defaults to goose's log directory
+    pub log_dir: Option<PathBuf>,
+    pub max_log_files: usize,
+}
+
+impl Default for BundleOptions {
+    fn default() -> Self {
+        Self {
+            session_file: None,
+            log_dir: None,
+            max_log_files: DEFAULT_MAX_LOG_FILES,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct VersionInfo {
+    goose_version: &'static str,
+    os: &'static str,
+    arch: &'static str,
+    created_at: String,
+}
+
+/// Root of the CLI and server log directories
+pub fn log_dir() -> Result<PathBuf> {
+    let strategy = choose_app_strategy(APP_STRATEGY.clone())?;
+    Ok(strategy
+        .in_state_dir("logs")
+        .unwrap_or_else(|| strategy.in_data_dir("logs")))
+}
+
+/// Replace known secret values and anything shaped like a credential
+pub fn scrub(text: &str, secrets: &[String]) -> String {
+    let mut scrubbed = text.to_string();
+    for secret in secrets {
+        // Short values would redact ordinary words
+        if secret.len() >= 8 {
+            scrubbed = scrubbed.replace(secret.as_str(), REDACTED);
+        }
+    }
+    for (pattern, replacement) in SECRET_PATTERNS.iter() {
+        scrubbed = pattern.replace_all(&scrubbed, *replacement).into_owned();
+    }
+    scrubbed
+}
+
+/// Every secret value goose knows of, so they can be scrubbed wherever they appear
+fn known_secrets(config: &Config) -> Vec<String> {
+    let mut secrets: Vec<String> = config
+        .load_secrets()
+        .unwrap_or_default()
+        .into_values()
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect();
+    secrets.extend(
+        std::env::vars()
+            .filter(|(key, _)| is_secret_key(key))
+            .map(|(_, value)| value),
+    );
+    // Longest first so a secret containing another is replaced whole
+    secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    secrets
+}
+
+fn redact_config(config: &Config) -> Result<String> {
+    let mut values = config.load_values()?;
+    for (key, value) in values.iter_mut() {
+        if is_secret_key(key) {
+            *value = Value::String(REDACTED.to_string());
+        }
+    }
+    // Extension env values are often credentials under names that don't look like it
+    if let Some(Value::Object(extensions)) = values.get_mut("extensions") {
+        for extension in extensions.values_mut() {
+            if let Some(Value::Object(envs)) = extension.get_mut("envs") {
+                for value in envs.values_mut() {
+                    *value = Value::String(REDACTED.to_string());
+                }
+            }
+        }
+    }
+    let values: std::collections::BTreeMap<_, _> = values.into_iter().collect();
+    Ok(serde_yaml::to_string(&values)?)
+}
+
+fn recent_logs(dir: &Path, limit: usize) -> Vec<PathBuf> {
+    fn collect(dir: &Path, files: &mut Vec<(std::time::SystemTime, PathBuf)>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect(&path, files);
+            } else if path.extension().is_some_and(|e| e == "log") {
+                if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                    files.push((modified, path));
+                }
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    collect(dir, &mut files);
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files
+        .into_iter()
+        .take(limit)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+fn read_tail(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    if len > MAX_LOG_BYTES {
+        file.seek(SeekFrom::Start(len - MAX_LOG_BYTES))?;
+    }
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Build the diagnostics zip in memory
+pub fn build_bundle(config: &Config, options: &BundleOptions) -> Result<Vec<u8>> {
+    let secrets = known_secrets(config);
+    let file_options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+
+    let version = VersionInfo {
+        goose_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    zip.start_file("version.json", file_options)?;
+    zip.write_all(serde_json::to_string_pretty(&version)?.as_bytes())?;
+
+    let config_yaml = match redact_config(config) {
+        Ok(yaml) => scrub(&yaml, &secrets),
+        Err(e) => format!("# Failed to read config: {}\n", e),
+    };
+    zip.start_file("config.yaml", file_options)?;
+    zip.write_all(config_yaml.as_bytes())?;
+
+    if let Some(session_file) = &options.session_file {
+        let metadata = session::read_metadata(session_file)?;
+        zip.start_file("session.json", file_options)?;
+        zip.write_all(scrub(&serde_json::to_string_pretty(&metadata)?, &secrets).as_bytes())?;
+    }
+
+    let log_dir = match &options.log_dir {
+        Some(dir) => dir.clone(),
+        None => log_dir()?,
+    };
+    for path in recent_logs(&log_dir, options.max_log_files) {
+        let name = path
+            .strip_prefix(&log_dir)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let content = match read_tail(&path) {
+            Ok(content) => scrub(&content, &secrets),
+            Err(e) => format!("Failed to read log: {}\n", e),
+        };
+        zip.start_file(format!("logs/{}", name), file_options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use zip::ZipArchive;
+
+    #[test]
+    fn test_scrub() {
+        let secrets = vec!["hunter2-but-longer".to_string(), "short".to_string()];
+        let scrubbed = scrub(
+            "password hunter2-but-longer, short words stay\n\
+             Authorization: Bearer abc.def.ghi\n\
+             {\"OPENAI_API_KEY\": \"sk-proj-abcdefghijklmnop\", \"model\": \"gpt-4o\"}",
+            &secrets,
+        );
+        assert!(!scrubbed.contains("hunter2"));
+        assert!(scrubbed.contains("short words stay"));
+        assert!(!scrubbed.contains("abc.def.ghi"));
+        assert!(!scrubbed.contains("sk-proj"));
+        assert!(scrubbed.contains("gpt-4o"));
+    }
+
+    #[test]
+    fn test_bundle_contents() -> Result<()> {
+        let dir = tempdir()?;
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )?;
+        config.set_param("GOOSE_MODEL", Value::String("gpt-4o".to_string()))?;
+        config.set_param(
+            "BUNDLETEST_API_KEY",
+            Value::String("plaintext-credential".to_string()),
+        )?;
+        config.set_secret(
+            "BUNDLETEST_TOKEN",
+            Value::String("stored-credential".to_string()),
+        )?;
+
+        let logs = dir.path().join("logs/cli/2025-01-01");
+        fs::create_dir_all(&logs)?;
+        fs::write(
+            logs.join("goose.log"),
+            "calling provider with stored-credential\n",
+        )?;
+
+        let bytes = build_bundle(
+            &config,
+            &BundleOptions {
+                log_dir: Some(dir.path().join("logs")),
+                ..Default::default()
+            },
+        )?;
+        let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+        let mut read = |name: &str| -> Result<String> {
+            let mut content = String::new();
+            archive.by_name(name)?.read_to_string(&mut content)?;
+            Ok(content)
+        };
+
+        assert!(read("version.json")?.contains(env!("CARGO_PKG_VERSION")));
+        let config_yaml = read("config.yaml")?;
+        assert!(config_yaml.contains("gpt-4o"));
+        assert!(!config_yaml.contains("plaintext-credential"));
+        let log = read("logs/cli/2025-01-01/goose.log")?;
+        assert!(log.contains("calling provider with [REDACTED]"));
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+//! Environment checks behind `goose doctor`. Each check says what is wrong and how
+//! to fix it, since most first-run problems are configuration rather than bugs.
+
+use crate::config::{Config, ExtensionConfig, ExtensionEntry};
+use crate::conversation::message::Message;
+use crate::model::ModelConfig;
+use crate::providers::errors::ProviderError;
+use crate::session;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(60);
+/// Any well-known HTTPS host works; only its Date header is used
+const CLOCK_CHECK_URL: &str = "https://www.cloudflare.com";
+
+const MIN_FREE_BYTES: u64 = 100 * 1024 * 1024;
+const LOW_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+/// Signed provider requests (Bedrock, Vertex AI) are rejected beyond a few minutes of skew
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+const WARN_CLOCK_SKEW_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// The check could not run, e.g. there was nothing to check
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What to do about a warning or failure
+    pub fix: Option<String>,
+}
+
+impl CheckResult {
+    fn new(name: &str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn pass(name: &str, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, message)
+    }
+
+    fn skip(name: &str, message: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Skip, message)
+    }
+
+    fn warn(name: &str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, message).with_fix(fix)
+    }
+
+    fn fail(name: &str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, message).with_fix(fix)
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    /// False when any check failed
+    pub ok: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+/// Run every check. Checks never error; problems are reported as results.
+pub async fn run_checks(config: &Config) -> DoctorReport {
+    let mut checks = vec![check_provider(config).await];
+    checks.extend(check_extensions(config).await);
+    checks.push(check_disk_space());
+    checks.push(check_clock().await);
+    checks.push(check_tokenizer().await);
+
+    DoctorReport {
+        ok: !checks.iter().any(|c| c.status == CheckStatus::Fail),
+        checks,
+    }
+}
+
+async fn check_provider(config: &Config) -> CheckResult {
+    const NAME: &str = "provider";
+    let (Ok(provider_name), Ok(model)) = (
+        config.get_param::<String>("GOOSE_PROVIDER"),
+        config.get_param::<String>("GOOSE_MODEL"),
+    ) else {
+        return CheckResult::fail(
+            NAME,
+            "No provider and model configured",
+            "Run `goose configure` to choose a provider and model",
+        );
+    };
+
+    let Some(metadata) = crate::providers::providers()
+        .into_iter()
+        .find(|p| p.name == provider_name)
+    else {
+        return CheckResult::fail(
+            NAME,
+            format!("Unknown provider '{}'", provider_name),
+            "Run `goose configure` and pick one of the listed providers",
+        );
+    };
+    let missing: Vec<_> = metadata
+        .config_keys
+        .iter()
+        .filter(|key| key.required && key.default.is_none())
+        .filter(|key| config.get(&key.name, key.secret).is_err())
+        .map(|key| key.name.clone())
+        .collect();
+    if !missing.is_empty() {
+        return CheckResult::fail(
+            NAME,
+            format!("{} is missing {}", provider_name, missing.join(", ")),
+            format!(
+                "Run `goose configure` or set {} in your environment",
+                missing.join(", ")
+            ),
+        );
+    }
+
+    let provider = match ModelConfig::new(&model)
+        .map_err(anyhow::Error::from)
+        .and_then(|model_config| crate::providers::create(&provider_name, model_config))
+    {
+        Ok(provider) => provider,
+        Err(e) => {
+            return CheckResult::fail(
+                NAME,
+                format!("Could not create {}: {}", provider_name, e),
+                "Run `goose configure` to check the provider settings",
+            )
+        }
+    };
+    let response = tokio::time::timeout(
+        PROVIDER_TIMEOUT,
+        provider.complete(
+            "You are a connectivity check.",
+            &[Message::user().with_text("Reply with OK.")],
+            &[],
+        ),
+    )
+    .await;
+
+    let target = format!("{} ({})", provider_name, model);
+    match response {
+        Ok(Ok(_)) => CheckResult::pass(NAME, format!("{} responded", target)),
+        Ok(Err(ProviderError::Authentication(e))) => CheckResult::fail(
+            NAME,
+            format!("{} rejected the credentials: {}", target, e),
+            "Your API key is invalid or expired; update it with `goose configure`",
+        ),
+        Ok(Err(ProviderError::RateLimitExceeded(e))) => CheckResult::warn(
+            NAME,
+            format!("{} is rate limiting requests: {}", target, e),
+            "Wait a minute, or check your plan's rate limits with the provider",
+        ),
+        Ok(Err(ProviderError::RequestFailed(e))) if e.contains("404") => CheckResult::fail(
+            NAME,
+            format!("{} does not know this model: {}", target, e),
+            "Choose a model your account can access with `goose configure`",
+        ),
+        Ok(Err(e)) => CheckResult::fail(
+            NAME,
+            format!("{} failed: {}", target, e),
+            "Check your network and the provider host settings in `goose configure`",
+        ),
+        Err(_) => CheckResult::fail(
+            NAME,
+            format!(
+                "{} did not respond within {}s",
+                target,
+                PROVIDER_TIMEOUT.as_secs()
+            ),
+            "Check your network connection, proxy settings and the provider's status page",
+        ),
+    }
+}
+
+async fn check_extensions(config: &Config) -> Vec<CheckResult> {
+    let extensions: HashMap<String, ExtensionEntry> =
+        config.get_param("extensions").unwrap_or_default();
+    let mut entries: Vec<_> = extensions.into_values().filter(|e| e.enabled).collect();
+    entries.sort_by_key(|e| e.config.name());
+
+    let mut results = Vec::new();
+    for entry in entries {
+        let name = format!("extension:{}", entry.config.name());
+        let result = match &entry.config {
+            ExtensionConfig::Stdio { cmd, .. } => match which::which(cmd) {
+                Ok(path) => CheckResult::pass(&name, format!("{} found at {}", cmd, path.display())),
+                Err(_) => CheckResult::fail(
+                    &name,
+                    format!("Command '{}' was not found", cmd),
+                    format!(
+                        "Install {} or put it on your PATH, or disable the extension with `goose configure`",
+                        cmd
+                    ),
+                ),
+            },
+            ExtensionConfig::Sse { uri, .. } | ExtensionConfig::StreamableHttp { uri, .. } => {
+                check_reachable(&name, uri).await
+            }
+            _ => continue,
+        };
+        results.push(result);
+    }
+    results
+}
+
+async fn check_reachable(name: &str, uri: &str) -> CheckResult {
+    let client = match reqwest::Client::builder().timeout(NETWORK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return CheckResult::skip(name, format!("Could not build HTTP client: {}", e)),
+    };
+    // Any HTTP response means the server is up; MCP endpoints often reject a bare GET
+    match client.get(uri).send().await {
+        Ok(response) => CheckResult::pass(
+            name,
+            format!("{} is reachable ({})", uri, response.status()),
+        ),
+        Err(e) => CheckResult::fail(
+            name,
+            format!("Could not reach {}: {}", uri, e),
+            "Check that the MCP server is running and the URL is correct",
+        ),
+    }
+}
+
+fn check_disk_space() -> CheckResult {
+    const NAME: &str = "disk_space";
+    let dir = match session::ensure_session_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            return CheckResult::fail(
+                NAME,
+                format!("Cannot create the session directory: {}", e),
+                "Check that your home directory exists and is writable",
+            )
+        }
+    };
+    match fs2::available_space(&dir) {
+        Ok(available) => disk_space_result(available),
+        Err(e) => CheckResult::skip(NAME, format!("Could not read free space: {}", e)),
+    }
+}
+
+fn disk_space_result(available: u64) -> CheckResult {
+    const NAME: &str = "disk_space";
+    let megabytes = available / (1024 * 1024);
+    let fix = "Free up disk space, or remove old sessions with `goose session remove`";
+    if available < MIN_FREE_BYTES {
+        CheckResult::fail(
+            NAME,
+            format!("Only {} MB free for sessions", megabytes),
+            fix,
+        )
+    } else if available < LOW_FREE_BYTES {
+        CheckResult::warn(NAME, format!("{} MB free for sessions", megabytes), fix)
+    } else {
+        CheckResult::pass(NAME, format!("{} MB free for sessions", megabytes))
+    }
+}
+
+async fn check_clock() -> CheckResult {
+    const NAME: &str = "clock";
+    let client = match reqwest::Client::builder().timeout(NETWORK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return CheckResult::skip(NAME, format!("Could not build HTTP client: {}", e)),
+    };
+    let date = match client.head(CLOCK_CHECK_URL).send().await {
+        Ok(response) => response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|d| d.to_str().ok())
+            .and_then(|d| chrono::DateTime::parse_from_rfc2822(d).ok()),
+        Err(e) => {
+            return CheckResult::skip(NAME, format!("Could not reach {}: {}", CLOCK_CHECK_URL, e))
+        }
+    };
+    match date {
+        Some(date) => clock_skew_result(chrono::Utc::now().timestamp() - date.timestamp()),
+        None => CheckResult::skip(NAME, "No server time to compare against"),
+    }
+}
+
+fn clock_skew_result(skew_secs: i64) -> CheckResult {
+    const NAME: &str = "clock";
+    let fix = "Turn on automatic time sync in your system settings";
+    let message = format!("System clock is off by {}s", skew_secs);
+    if skew_secs.abs() > MAX_CLOCK_SKEW_SECS {
+        CheckResult::fail(NAME, message, fix)
+    } else if skew_secs.abs() > WARN_CLOCK_SKEW_SECS {
+        CheckResult::warn(NAME, message, fix)
+    } else {
+        CheckResult::pass(NAME, "System clock is in sync")
+    }
+}
+
+async fn check_tokenizer() -> CheckResult {
+    const NAME: &str = "tokenizer";
+    match tokio::task::spawn_blocking(crate::token_counter::tokenizer_available).await {
+        Ok(Ok(())) => CheckResult::pass(NAME, "Tokenizer loaded"),
+        Ok(Err(e)) => CheckResult::fail(
+            NAME,
+            e,
+            "Reinstall goose; the bundled tokenizer data is missing or corrupt",
+        ),
+        Err(e) => CheckResult::fail(
+            NAME,
+            format!("Tokenizer failed to load: {}", e),
+            "Reinstall goose; the bundled tokenizer data is missing or corrupt",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_thresholds() {
+        assert_eq!(
+            disk_space_result(10 * 1024 * 1024).status,
+            CheckStatus::Fail
+        );
+        assert_eq!(
+            disk_space_result(500 * 1024 * 1024).status,
+            CheckStatus::Warn
+        );
+        assert_eq!(
+            disk_space_result(50 * LOW_FREE_BYTES).status,
+            CheckStatus::Pass
+        );
+
+        assert_eq!(clock_skew_result(3).status, CheckStatus::Pass);
+        assert_eq!(clock_skew_result(-120).status, CheckStatus::Warn);
+        let result = clock_skew_result(900);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.fix.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_missing_stdio_command() {
+        let dir = tempdir().unwrap();
+        let config = Config::new_with_file_secrets(
+            dir.path().join("config.yaml"),
+            dir.path().join("secrets.yaml"),
+        )
+        .unwrap();
+        config
+            .set_param(
+                "extensions",
+                serde_json::json!({
+                    "broken": {
+                        "enabled": true,
+                        "type": "stdio",
+                        "name": "broken",
+                        "cmd": "goose-doctor-no-such-command",
+                        "args": []
+                    },
+                    "disabled": {
+                        "enabled": false,
+                        "type": "stdio",
+                        "name": "disabled",
+                        "cmd": "goose-doctor-no-such-command",
+                        "args": []
+                    }
+                }),
+            )
+            .unwrap();
+
+        let results = check_extensions(&config).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "extension:broken");
+        assert_eq!(results[0].status, CheckStatus::Fail);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod bundle;
+pub mod checks;
+
+pub use bundle::{build_bundle, scrub, BundleOptions};
+pub use checks::{run_checks, CheckResult, CheckStatus, DoctorReport};
@@ -0,0 +1,577 @@
+//! Periodic digests of recent sessions: what was worked on, how many tokens it took and what
+//! it cost, and which tool calls failed along the way.
+//!
+//! Digests are off until `GOOSE_DIGEST` is configured with somewhere to send them:
+//!
+//! ```yaml
+//! GOOSE_DIGEST:
+//!   every_hours: 24                # how often to report, and how far back each digest looks
+//!   format: markdown               # markdown (the default) or html
+//!   output_dir: ~/goose-digests    # write each digest to a file here
+//!   webhook_url: https://hooks.example.com/goose   # and/or POST it here
+//! ```
+//!
+//! The webhook receives `{"text": ..., "format": ...}`, which chat tools such as Slack accept
+//! as is. Tokens are those used during the digest's period, and costs are estimated with the
+//! prices of the models that used them.
+
+use crate::config::{Config, APP_STRATEGY};
+use crate::providers::pricing::get_all_pricing;
+use crate::session::events::EventFilter;
+use crate::session::info::SortOrder;
+use crate::session::{self, SessionEventKind, SessionInfo};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const DIGEST_KEY: &str = "GOOSE_DIGEST";
+
+/// Failures listed per session before the rest are only counted
+const MAX_FAILURES_SHOWN: usize = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl DigestFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            DigestFormat::Markdown => "md",
+            DigestFormat::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestConfig {
+    pub every_hours: u64,
+    pub format: DigestFormat,
+    pub output_dir: Option<PathBuf>,
+    pub webhook_url: Option<String>,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            every_hours: 24,
+            format: DigestFormat::default(),
+            output_dir: None,
+            webhook_url: None,
+        }
+    }
+}
+
+impl DigestConfig {
+    pub fn load() -> Self {
+        Config::global().get_param(DIGEST_KEY).unwrap_or_default()
+    }
+
+    /// Digests are only generated when they have somewhere to go
+    pub fn is_enabled(&self) -> bool {
+        self.every_hours > 0 && (self.output_dir.is_some() || self.webhook_url.is_some())
+    }
+
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(self.every_hours.max(1) * 60 * 60)
+    }
+}
+
+/// Input and output tokens used by each model
+type ModelUsage = HashMap<String, (i64, i64)>;
+
+/// Price per input and output token of each model
+type ModelPricing = HashMap<String, (f64, f64)>;
+
+/// A tool call that failed during a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestFailure {
+    pub tool_name: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestSession {
+    pub id: String,
+    pub description: String,
+    pub working_dir: String,
+    pub message_count: usize,
+    /// Tokens used during the digest's period
+    pub tokens: i64,
+    /// Estimated cost of those tokens, None when no model's price is known
+    pub cost: Option<f64>,
+    pub failures: Vec<DigestFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Digest {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub sessions: Vec<DigestSession>,
+}
+
+impl Digest {
+    pub fn total_tokens(&self) -> i64 {
+        self.sessions.iter().map(|s| s.tokens).sum()
+    }
+
+    /// None when no session's cost could be estimated
+    pub fn total_cost(&self) -> Option<f64> {
+        let costs: Vec<f64> = self.sessions.iter().filter_map(|s| s.cost).collect();
+        (!costs.is_empty()).then(|| costs.iter().sum())
+    }
+
+    pub fn total_failures(&self) -> usize {
+        self.sessions.iter().map(|s| s.failures.len()).sum()
+    }
+
+    pub fn render(&self, format: DigestFormat) -> String {
+        match format {
+            DigestFormat::Markdown => self.render_markdown(),
+            DigestFormat::Html => self.render_html(),
+        }
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "goose digest: {} to {}",
+            self.since.format("%Y-%m-%d %H:%M"),
+            self.until.format("%Y-%m-%d %H:%M UTC")
+        )
+    }
+
+    fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} session(s), {} tokens",
+            self.sessions.len(),
+            self.total_tokens()
+        );
+        if let Some(cost) = self.total_cost() {
+            let _ = write!(summary, ", about ${:.2}", cost);
+        }
+        let _ = write!(summary, ", {} failed tool call(s)", self.total_failures());
+        summary
+    }
+
+    pub fn render_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n{}\n", self.title(), self.summary());
+        if self.sessions.is_empty() {
+            out.push_str("\nNo sessions in this period.\n");
+            return out;
+        }
+        for session in &self.sessions {
+            let _ = write!(
+                out,
+                "\n## {}\n\n- Directory: `{}`\n- Messages: {}\n- Tokens: {}\n",
+                session.description, session.working_dir, session.message_count, session.tokens
+            );
+            if let Some(cost) = session.cost {
+                let _ = writeln!(out, "- Cost: about ${:.2}", cost);
+            }
+            if !session.failures.is_empty() {
+                let _ = writeln!(out, "- Failures:");
+                for failure in session.failures.iter().take(MAX_FAILURES_SHOWN) {
+                    let _ = writeln!(out, "  - `{}`: {}", failure.tool_name, failure.detail);
+                }
+                if session.failures.len() > MAX_FAILURES_SHOWN {
+                    let _ = writeln!(
+                        out,
+                        "  - and {} more",
+                        session.failures.len() - MAX_FAILURES_SHOWN
+                    );
+                }
+            }
+        }
+        out
+    }
+
+    pub fn render_html(&self) -> String {
+        let title = escape_html(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n<body>\n<h1>{title}</h1>\n<p>{}</p>\n",
+            escape_html(&self.summary())
+        );
+        if self.sessions.is_empty() {
+            out.push_str("<p>No sessions in this period.</p>\n");
+        }
+        for session in &self.sessions {
+            let _ = write!(
+                out,
+                "<h2>{}</h2>\n<ul>\n<li>Directory: <code>{}</code></li>\n<li>Messages: {}</li>\n<li>Tokens: {}</li>\n",
+                escape_html(&session.description),
+                escape_html(&session.working_dir),
+                session.message_count,
+                session.tokens
+            );
+            if let Some(cost) = session.cost {
+                let _ = writeln!(out, "<li>Cost: about ${:.2}</li>", cost);
+            }
+            if !session.failures.is_empty() {
+                out.push_str("<li>Failures:<ul>\n");
+                for failure in session.failures.iter().take(MAX_FAILURES_SHOWN) {
+                    let _ = writeln!(
+                        out,
+                        "<li><code>{}</code>: {}</li>",
+                        escape_html(&failure.tool_name),
+                        escape_html(&failure.detail)
+                    );
+                }
+                if session.failures.len() > MAX_FAILURES_SHOWN {
+                    let _ = writeln!(
+                        out,
+                        "<li>and {} more</li>",
+                        session.failures.len() - MAX_FAILURES_SHOWN
+                    );
+                }
+                out.push_str("</ul></li>\n");
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn modified_at(session: &SessionInfo) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(&session.modified, "%Y-%m-%d %H:%M:%S UTC")
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+/// Sessions with a description that were modified in `since..until`, most recent first
+fn sessions_between(
+    sessions: Vec<SessionInfo>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<SessionInfo> {
+    sessions
+        .into_iter()
+        .filter(|session| !session.metadata.description.is_empty())
+        .filter(|session| modified_at(session).is_some_and(|at| at >= since && at < until))
+        .collect()
+}
+
+fn failures(
+    session: &SessionInfo,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<DigestFailure> {
+    let filter = EventFilter {
+        kind: Some(SessionEventKind::ToolFinished),
+        since: Some(since.timestamp_millis()),
+        ..Default::default()
+    };
+    session::events::read_events(&PathBuf::from(&session.path), &filter)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|event| event.timestamp < until.timestamp_millis())
+        .filter_map(|event| {
+            let detail = event.detail?.strip_prefix("failed: ")?.to_string();
+            Some(DigestFailure {
+                tool_name: event.tool_name.unwrap_or_default(),
+                detail,
+            })
+        })
+        .collect()
+}
+
+/// Tokens each model used in the session during `since..until`, from its usage events
+fn usage_between(session: &SessionInfo, since: DateTime<Utc>, until: DateTime<Utc>) -> ModelUsage {
+    let filter = EventFilter {
+        kind: Some(SessionEventKind::Usage),
+        since: Some(since.timestamp_millis()),
+        ..Default::default()
+    };
+    let mut usage = ModelUsage::new();
+    for event in session::events::read_events(&PathBuf::from(&session.path), &filter)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|event| event.timestamp < until.timestamp_millis())
+    {
+        if let Some(tokens) = event.usage {
+            let (input, output) = usage.entry(tokens.model).or_default();
+            *input += tokens.input_tokens;
+            *output += tokens.output_tokens;
+        }
+    }
+    usage
+}
+
+/// Price per input and output token of each of `models` whose price is known
+async fn pricing_of<'a>(models: impl IntoIterator<Item = &'a String>) -> ModelPricing {
+    let all_pricing = get_all_pricing().await;
+    models
+        .into_iter()
+        .filter_map(|model| {
+            let pricing = all_pricing
+                .values()
+                .find_map(|provider_models| provider_models.get(model))?;
+            Some((model.clone(), (pricing.input_cost, pricing.output_cost)))
+        })
+        .collect()
+}
+
+/// Estimated cost of `usage`, None when none of its models has a known price
+fn cost_of(usage: &ModelUsage, pricing: &ModelPricing) -> Option<f64> {
+    let costs: Vec<f64> = usage
+        .iter()
+        .filter_map(|(model, (input, output))| {
+            let (input_cost, output_cost) = pricing.get(model)?;
+            Some(*input as f64 * input_cost + *output as f64 * output_cost)
+        })
+        .collect();
+    (!costs.is_empty()).then(|| costs.iter().sum())
+}
+
+fn summarize(
+    sessions: Vec<(SessionInfo, ModelUsage)>,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    pricing: &ModelPricing,
+) -> Digest {
+    let sessions = sessions
+        .into_iter()
+        .map(|(session, usage)| {
+            let metadata = &session.metadata;
+            DigestSession {
+                id: session.id.clone(),
+                description: metadata.description.clone(),
+                working_dir: metadata.working_dir.to_string_lossy().into_owned(),
+                message_count: metadata.message_count,
+                tokens: usage.values().map(|(input, output)| input + output).sum(),
+                cost: cost_of(&usage, pricing),
+                failures: failures(&session, since, until),
+            }
+        })
+        .collect();
+    Digest {
+        since,
+        until,
+        sessions,
+    }
+}
+
+/// Digest of the sessions worked on in `since..until`
+pub async fn build_digest(since: DateTime<Utc>, until: DateTime<Utc>) -> Result<Digest> {
+    let sessions: Vec<_> = sessions_between(
+        session::get_valid_sorted_sessions(SortOrder::Descending)?,
+        since,
+        until,
+    )
+    .into_iter()
+    .map(|session| {
+        let usage = usage_between(&session, since, until);
+        (session, usage)
+    })
+    .collect();
+    let pricing = pricing_of(sessions.iter().flat_map(|(_, usage)| usage.keys())).await;
+    Ok(summarize(sessions, since, until, &pricing))
+}
+
+/// Write the digest to the output directory and post it to the webhook, whichever are set
+pub async fn deliver(config: &DigestConfig, digest: &Digest) -> Result<()> {
+    let text = digest.render(config.format);
+    if let Some(dir) = &config.output_dir {
+        let dir = expand_home(dir);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!(
+            "goose-digest-{}.{}",
+            digest.until.format("%Y%m%d-%H%M"),
+            config.format.extension()
+        ));
+        fs::write(&path, &text)?;
+        tracing::info!("Wrote session digest to {}", path.display());
+    }
+    if let Some(url) = &config.webhook_url {
+        reqwest::Client::new()
+            .post(url)
+            .json(&serde_json::json!({ "text": text, "format": config.format }))
+            .send()
+            .await?
+            .error_for_status()?;
+    }
+    Ok(())
+}
+
+fn expand_home(path: &std::path::Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// When the last digest was generated, kept so a restart doesn't report twice
+fn last_run_path() -> Result<PathBuf> {
+    Ok(choose_app_strategy(APP_STRATEGY.clone())?
+        .data_dir()
+        .join("digest_last_run"))
+}
+
+fn read_last_run() -> Option<DateTime<Utc>> {
+    let text = fs::read_to_string(last_run_path().ok()?).ok()?;
+    DateTime::parse_from_rfc3339(text.trim())
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+fn write_last_run(at: DateTime<Utc>) -> Result<()> {
+    let path = last_run_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, at.to_rfc3339())?;
+    Ok(())
+}
+
+/// Generate and deliver a digest every configured period, for as long as the process runs.
+/// Returns straight away when digests aren't configured.
+pub async fn run_scheduled() {
+    let config = DigestConfig::load();
+    if !config.is_enabled() {
+        return;
+    }
+    let period = chrono::Duration::from_std(config.period()).unwrap_or(chrono::Duration::days(1));
+    loop {
+        let now = Utc::now();
+        let last_run = read_last_run().unwrap_or(now - period);
+        let due = last_run + period;
+        if due > now {
+            let wait = (due - now).to_std().unwrap_or(Duration::ZERO);
+            tokio::time::sleep(wait).await;
+            continue;
+        }
+
+        match build_digest(last_run, now).await {
+            Ok(digest) => {
+                if let Err(e) = deliver(&config, &digest).await {
+                    tracing::warn!("Failed to deliver session digest: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to build session digest: {}", e),
+        }
+        // A digest that failed to go out isn't retried until the next period
+        if let Err(e) = write_last_run(now) {
+            tracing::warn!("Failed to record session digest time: {}", e);
+            tokio::time::sleep(config.period()).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionMetadata;
+
+    fn session_info(id: &str, description: &str, modified: &str) -> SessionInfo {
+        let metadata = SessionMetadata {
+            description: description.to_string(),
+            // Lifetime totals, which the digest must not report
+            accumulated_total_tokens: Some(1_000_000),
+            ..Default::default()
+        };
+        SessionInfo {
+            id: id.to_string(),
+            path: format!("/nonexistent/{}.jsonl", id),
+            modified: modified.to_string(),
+            metadata,
+        }
+    }
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_sessions_modified_in_the_period() {
+        let sessions = vec![
+            session_info("recent", "Fix <login> bug", "2025-03-02 09:00:00 UTC"),
+            session_info("untitled", "", "2025-03-02 10:00:00 UTC"),
+            session_info("old", "Old work", "2025-02-20 09:00:00 UTC"),
+        ];
+        let selected = sessions_between(
+            sessions,
+            at("2025-03-01T12:00:00Z"),
+            at("2025-03-02T12:00:00Z"),
+        );
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].id, "recent");
+    }
+
+    #[test]
+    fn test_usage_and_cost_of_the_period() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut session = session_info("recent", "Fix <login> bug", "2025-03-02 09:00:00 UTC");
+        session.path = dir
+            .path()
+            .join("recent.jsonl")
+            .to_string_lossy()
+            .into_owned();
+        let since = at("2025-03-01T12:00:00Z");
+        let until = at("2025-03-02T12:00:00Z");
+        for (at, model, tokens) in [
+            (since - chrono::Duration::hours(1), "gpt-4o", 10_000),
+            (since + chrono::Duration::hours(1), "gpt-4o", 500),
+            (since + chrono::Duration::hours(2), "unpriced-model", 100),
+            (until, "gpt-4o", 10_000),
+        ] {
+            let mut event = session::SessionEvent::new(SessionEventKind::Usage).with_usage(
+                session::TokenUsage {
+                    model: model.to_string(),
+                    input_tokens: tokens,
+                    output_tokens: tokens,
+                },
+            );
+            event.timestamp = at.timestamp_millis();
+            session::events::record_event(&PathBuf::from(&session.path), &event).unwrap();
+        }
+
+        let usage = usage_between(&session, since, until);
+        assert_eq!(usage["gpt-4o"], (500, 500));
+        assert_eq!(usage["unpriced-model"], (100, 100));
+
+        let pricing = ModelPricing::from([("gpt-4o".to_string(), (0.001, 0.002))]);
+        let digest = summarize(vec![(session, usage)], since, until, &pricing);
+        assert_eq!(digest.total_tokens(), 1200);
+        assert!((digest.total_cost().unwrap() - 1.5).abs() < 1e-9);
+
+        let markdown = digest.render(DigestFormat::Markdown);
+        assert!(markdown.contains("## Fix <login> bug"));
+        assert!(markdown.contains("about $1.50"));
+
+        let html = digest.render(DigestFormat::Html);
+        assert!(html.contains("<h2>Fix &lt;login&gt; bug</h2>"));
+    }
+
+    #[test]
+    fn test_digest_without_pricing_or_sessions() {
+        let digest = summarize(
+            Vec::new(),
+            at("2025-03-01T12:00:00Z"),
+            at("2025-03-02T12:00:00Z"),
+            &ModelPricing::new(),
+        );
+        assert_eq!(digest.total_cost(), None);
+        let markdown = digest.render_markdown();
+        assert!(markdown.contains("0 session(s), 0 tokens, 0 failed tool call(s)"));
+        assert!(markdown.contains("No sessions in this period."));
+    }
+}
@@ -0,0 +1,345 @@
+//! Running goose inside another Rust program, without the CLI or goosed.
+//!
+//! [`GooseBuilder`] wires an [`Agent`] to a provider, extensions and optional session
+//! storage; the resulting [`Goose`] keeps the conversation between calls:
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use goose::embed::GooseBuilder;
+//!
+//! let goose = GooseBuilder::new()
+//!     .provider_name("openai", "gpt-4o")
+//!     .instructions("Answer in one sentence.")
+//!     .build()
+//!     .await?;
+//! let reply = goose.run("What is the capital of France?").await?;
+//! println!("{}", reply.as_concat_text());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Use [`Goose::stream`] instead of [`Goose::run`] to see messages, tool calls and
+//! notifications as they happen.
+
+use crate::agents::{Agent, AgentEvent, ExtensionConfig, Interceptor, SessionConfig};
+use crate::config::Config;
+use crate::conversation::message::Message;
+use crate::conversation::Conversation;
+use crate::model::ModelConfig;
+use crate::providers::{self, base::Provider};
+use crate::session;
+use anyhow::{anyhow, Result};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use rmcp::model::Role;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+/// Where a conversation is saved between runs
+#[derive(Debug, Clone, Default)]
+pub enum SessionStorage {
+    /// Kept in memory only and lost when the [`Goose`] is dropped
+    #[default]
+    Memory,
+    /// A named session in the goose session directory, shared with the CLI and desktop app
+    Named(String),
+    /// A session file at this path
+    File(PathBuf),
+}
+
+enum ProviderChoice {
+    /// GOOSE_PROVIDER and GOOSE_MODEL from the goose config
+    Configured,
+    Named {
+        provider: String,
+        model: String,
+    },
+    Instance(Arc<dyn Provider>),
+}
+
+pub struct GooseBuilder {
+    provider: ProviderChoice,
+    extensions: Vec<ExtensionConfig>,
+    instructions: Vec<String>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    storage: SessionStorage,
+    working_dir: Option<PathBuf>,
+}
+
+impl Default for GooseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GooseBuilder {
+    /// Start from the provider and model in the user's goose config, no extensions and an
+    /// in-memory session
+    pub fn new() -> Self {
+        Self {
+            provider: ProviderChoice::Configured,
+            extensions: Vec::new(),
+            instructions: Vec::new(),
+            interceptors: Vec::new(),
+            storage: SessionStorage::Memory,
+            working_dir: None,
+        }
+    }
+
+    pub fn provider(mut self, provider: Arc<dyn Provider>) -> Self {
+        self.provider = ProviderChoice::Instance(provider);
+        self
+    }
+
+    /// Create a provider by name, e.g. ("anthropic", "claude-sonnet-4-0"), with credentials
+    /// from the goose config or environment
+    pub fn provider_name(mut self, provider: &str, model: &str) -> Self {
+        self.provider = ProviderChoice::Named {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        };
+        self
+    }
+
+    pub fn extension(mut self, extension: ExtensionConfig) -> Self {
+        self.extensions.push(extension);
+        self
+    }
+
+    /// Extra text appended to the system prompt
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions.push(instructions.into());
+        self
+    }
+
+    pub fn interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Save the conversation so it can be resumed; an existing session is picked up
+    pub fn storage(mut self, storage: SessionStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Directory recorded with the session (defaults to the current directory)
+    pub fn working_dir(mut self, working_dir: impl Into<PathBuf>) -> Self {
+        self.working_dir = Some(working_dir.into());
+        self
+    }
+
+    pub async fn build(self) -> Result<Goose> {
+        let provider = match self.provider {
+            ProviderChoice::Instance(provider) => provider,
+            ProviderChoice::Named { provider, model } => {
+                providers::create(&provider, ModelConfig::new(&model)?)?
+            }
+            ProviderChoice::Configured => {
+                let config = Config::global();
+                let provider: String = config.get_param("GOOSE_PROVIDER").map_err(|_| {
+                    anyhow!(
+                        "No provider configured; run `goose configure` or set one on the builder"
+                    )
+                })?;
+                let model: String = config.get_param("GOOSE_MODEL").map_err(|_| {
+                    anyhow!("No model configured; run `goose configure` or set one on the builder")
+                })?;
+                providers::create(&provider, ModelConfig::new(&model)?)?
+            }
+        };
+
+        let agent = Agent::new();
+        agent.update_provider(provider).await?;
+        for extension in self.extensions {
+            let name = extension.name();
+            agent
+                .add_extension(extension)
+                .await
+                .map_err(|e| anyhow!("Failed to start extension {}: {}", name, e))?;
+        }
+        for instructions in self.instructions {
+            agent.extend_system_prompt(instructions).await;
+        }
+        for interceptor in self.interceptors {
+            agent.add_interceptor(interceptor).await;
+        }
+
+        let working_dir = match self.working_dir {
+            Some(dir) => dir,
+            None => std::env::current_dir()?,
+        };
+        let session_file = match self.storage {
+            SessionStorage::Memory => None,
+            SessionStorage::Named(name) => {
+                Some(session::get_path_for_working_dir(&name, &working_dir)?)
+            }
+            SessionStorage::File(path) => Some(path),
+        };
+        let conversation = match &session_file {
+            Some(path) if path.exists() => session::read_messages(path)?,
+            _ => Conversation::empty(),
+        };
+        let session = session_file.as_ref().map(|path| SessionConfig {
+            id: session::Identifier::Path(path.clone()),
+            working_dir: working_dir.clone(),
+            schedule_id: None,
+            execution_mode: None,
+            max_turns: None,
+            run_limits: Default::default(),
+            critique: None,
+            retry_config: None,
+        });
+
+        Ok(Goose {
+            agent,
+            conversation: Mutex::new(conversation),
+            session,
+            session_file,
+            working_dir,
+        })
+    }
+}
+
+/// An agent with its conversation, built by [`GooseBuilder`]
+pub struct Goose {
+    agent: Agent,
+    conversation: Mutex<Conversation>,
+    session: Option<SessionConfig>,
+    session_file: Option<PathBuf>,
+    working_dir: PathBuf,
+}
+
+impl Goose {
+    /// The underlying agent, for anything the builder doesn't cover
+    pub fn agent(&self) -> &Agent {
+        &self.agent
+    }
+
+    pub async fn conversation(&self) -> Conversation {
+        self.conversation.lock().await.clone()
+    }
+
+    /// Send a message and stream the agent's events. The conversation, and the session file
+    /// if there is one, are updated once the stream finishes; dropping it early discards the
+    /// exchange.
+    pub async fn stream(
+        &self,
+        message: Message,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<BoxStream<'_, Result<AgentEvent>>> {
+        let mut conversation = self.conversation.lock().await.clone();
+        conversation.push(message);
+
+        let mut events = self
+            .agent
+            .reply(conversation.clone(), self.session.clone(), cancel_token)
+            .await?;
+
+        Ok(Box::pin(async_stream::try_stream! {
+            while let Some(event) = events.next().await {
+                let event = event?;
+                match &event {
+                    AgentEvent::Message(message) => conversation.push(message.clone()),
+                    AgentEvent::HistoryReplaced(messages) => {
+                        conversation = Conversation::new_unvalidated(messages.clone());
+                    }
+                    _ => {}
+                }
+                yield event;
+            }
+
+            if let Some(path) = &self.session_file {
+                session::persist_messages(
+                    path,
+                    &conversation,
+                    Some(self.agent.provider().await?),
+                    Some(self.working_dir.clone()),
+                )
+                .await?;
+            }
+            *self.conversation.lock().await = conversation;
+        }))
+    }
+
+    /// Send a text message and wait for the agent to finish, returning its last reply
+    pub async fn run(&self, prompt: &str) -> Result<Message> {
+        let start = self.conversation.lock().await.len();
+        let mut events = self.stream(Message::user().with_text(prompt), None).await?;
+        while let Some(event) = events.next().await {
+            event?;
+        }
+        drop(events);
+
+        let conversation = self.conversation.lock().await;
+        conversation
+            .iter()
+            .skip(start)
+            .rev()
+            .find(|message| message.role == Role::Assistant)
+            .cloned()
+            .ok_or_else(|| anyhow!("The agent finished without replying"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::base::{ProviderMetadata, ProviderUsage, Usage};
+    use crate::providers::errors::ProviderError;
+    use rmcp::model::Tool;
+
+    struct EchoProvider {
+        model_config: ModelConfig,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for EchoProvider {
+        fn metadata() -> ProviderMetadata {
+            ProviderMetadata::empty()
+        }
+
+        fn get_model_config(&self) -> ModelConfig {
+            self.model_config.clone()
+        }
+
+        async fn complete(
+            &self,
+            _system: &str,
+            messages: &[Message],
+            _tools: &[Tool],
+        ) -> Result<(Message, ProviderUsage), ProviderError> {
+            let reply = format!(
+                "{} messages, last: {}",
+                messages.len(),
+                messages
+                    .last()
+                    .map(|m| m.as_concat_text())
+                    .unwrap_or_default()
+            );
+            Ok((
+                Message::assistant().with_text(reply),
+                ProviderUsage::new("echo".to_string(), Usage::default()),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_conversation_carries_across_runs() -> Result<()> {
+        let goose = GooseBuilder::new()
+            .provider(Arc::new(EchoProvider {
+                model_config: ModelConfig::new("echo")?,
+            }))
+            .build()
+            .await?;
+
+        let first = goose.run("hello").await?;
+        assert_eq!(first.as_concat_text(), "1 messages, last: hello");
+        let second = goose.run("again").await?;
+        assert_eq!(second.as_concat_text(), "3 messages, last: again");
+        assert_eq!(goose.conversation().await.len(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,356 @@
+//! Exemplars are past exchanges someone marked as a good example: a prompt and the answer
+//! goose gave. When a new session's task is similar to an exemplar's prompt, by embedding
+//! similarity, the exemplar is added to the system prompt as an example of the output
+//! expected, which keeps answers consistent in style across a team.
+//!
+//! ```yaml
+//! GOOSE_EXEMPLARS:
+//!   enabled: true                  # inject exemplars into sessions (the default)
+//!   max_examples: 2                # at most this many per session
+//!   min_similarity: 0.75           # cosine similarity an exemplar needs to be used
+//!   dir: ~/team/goose-exemplars    # shared location; defaults to the goose data dir
+//! ```
+//!
+//! Exemplars are only matched by embeddings, so marking and injecting them needs a provider
+//! that supports embeddings.
+
+use crate::config::{Config, APP_STRATEGY};
+use crate::conversation::message::Message;
+use crate::providers::base::Provider;
+use anyhow::{anyhow, Result};
+use etcetera::{choose_app_strategy, AppStrategy};
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+pub const EXEMPLARS_KEY: &str = "GOOSE_EXEMPLARS";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExemplarsConfig {
+    pub enabled: bool,
+    pub max_examples: usize,
+    pub min_similarity: f32,
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for ExemplarsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_examples: 2,
+            min_similarity: 0.75,
+            dir: None,
+        }
+    }
+}
+
+impl ExemplarsConfig {
+    pub fn load() -> Self {
+        Config::global()
+            .get_param(EXEMPLARS_KEY)
+            .unwrap_or_default()
+    }
+
+    fn store_path(&self) -> Result<PathBuf> {
+        let dir = match &self.dir {
+            Some(dir) => match (dir.strip_prefix("~"), dirs::home_dir()) {
+                (Ok(rest), Some(home)) => home.join(rest),
+                _ => dir.clone(),
+            },
+            None => choose_app_strategy(APP_STRATEGY.clone())?.data_dir(),
+        };
+        Ok(dir.join("exemplars.json"))
+    }
+}
+
+/// A past exchange kept as an example of a good answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Exemplar {
+    pub id: String,
+    /// What the user asked
+    pub prompt: String,
+    /// The answer that was marked as a good example
+    pub response: String,
+    /// Session the exchange was taken from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_session: Option<String>,
+    /// Who marked it, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Unix timestamp (seconds) when it was marked
+    pub created: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredExemplar {
+    #[serde(flatten)]
+    exemplar: Exemplar,
+    /// Embedding of the prompt
+    embedding: Vec<f32>,
+}
+
+fn read_store(path: &Path) -> Result<Vec<StoredExemplar>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_store(path: &Path, exemplars: &[StoredExemplar]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_file = path.with_extension("tmp");
+    fs::write(&temp_file, serde_json::to_string_pretty(exemplars)?)?;
+    fs::rename(&temp_file, path)?;
+    Ok(())
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// The stored exemplars closest to `embedding`, most similar first
+fn most_similar(
+    exemplars: Vec<StoredExemplar>,
+    embedding: &[f32],
+    config: &ExemplarsConfig,
+) -> Vec<Exemplar> {
+    let mut scored: Vec<(f32, Exemplar)> = exemplars
+        .into_iter()
+        .map(|stored| {
+            (
+                cosine_similarity(&stored.embedding, embedding),
+                stored.exemplar,
+            )
+        })
+        .filter(|(score, _)| *score >= config.min_similarity)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .take(config.max_examples)
+        .map(|(_, exemplar)| exemplar)
+        .collect()
+}
+
+fn is_prompt(message: &Message) -> bool {
+    message.role == Role::User && !message.is_tool_response()
+}
+
+/// The prompt at `index` and the last text answer given to it, if `index` is a prompt
+/// that got an answer
+pub fn exchange_at(messages: &[Message], index: usize) -> Option<(String, String)> {
+    let prompt = messages.get(index).filter(|m| is_prompt(m))?;
+    let answer = messages[index + 1..]
+        .iter()
+        .take_while(|m| !is_prompt(m))
+        .filter(|m| m.role == Role::Assistant)
+        .map(|m| m.as_concat_text())
+        .filter(|text| !text.trim().is_empty())
+        .last()?;
+    Some((prompt.as_concat_text(), answer))
+}
+
+async fn embed(provider: &Arc<dyn Provider>, text: &str) -> Result<Vec<f32>> {
+    if !provider.supports_embeddings() {
+        return Err(anyhow!("The current provider does not support embeddings"));
+    }
+    provider
+        .create_embeddings(vec![text.to_string()])
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow!("The provider returned no embedding"))
+}
+
+/// Keep an exchange as an exemplar, with the embedding of its prompt for matching later
+pub async fn add_exemplar(
+    provider: &Arc<dyn Provider>,
+    prompt: String,
+    response: String,
+    source_session: Option<String>,
+    author: Option<String>,
+) -> Result<Exemplar> {
+    let embedding = embed(provider, &prompt).await?;
+    let exemplar = Exemplar {
+        id: uuid::Uuid::new_v4().to_string(),
+        prompt,
+        response,
+        source_session,
+        author,
+        created: chrono::Utc::now().timestamp(),
+    };
+    let path = ExemplarsConfig::load().store_path()?;
+    let mut exemplars = read_store(&path)?;
+    exemplars.push(StoredExemplar {
+        exemplar: exemplar.clone(),
+        embedding,
+    });
+    write_store(&path, &exemplars)?;
+    Ok(exemplar)
+}
+
+/// Every exemplar, in the order they were marked
+pub fn list_exemplars() -> Result<Vec<Exemplar>> {
+    let path = ExemplarsConfig::load().store_path()?;
+    Ok(read_store(&path)?.into_iter().map(|s| s.exemplar).collect())
+}
+
+/// Remove an exemplar by id. Returns false if no exemplar had that id.
+pub fn remove_exemplar(id: &str) -> Result<bool> {
+    let path = ExemplarsConfig::load().store_path()?;
+    let mut exemplars = read_store(&path)?;
+    let before = exemplars.len();
+    exemplars.retain(|stored| stored.exemplar.id != id);
+    if exemplars.len() == before {
+        return Ok(false);
+    }
+    write_store(&path, &exemplars)?;
+    Ok(true)
+}
+
+/// Remove the exemplars `matches` picks, with their embeddings, returning their ids. With
+/// `dry_run` they are only listed.
+pub fn remove_exemplars(matches: impl Fn(&Exemplar) -> bool, dry_run: bool) -> Result<Vec<String>> {
+    let path = ExemplarsConfig::load().store_path()?;
+    let (removed, kept): (Vec<StoredExemplar>, Vec<StoredExemplar>) = read_store(&path)?
+        .into_iter()
+        .partition(|stored| matches(&stored.exemplar));
+    if !removed.is_empty() && !dry_run {
+        write_store(&path, &kept)?;
+    }
+    Ok(removed
+        .into_iter()
+        .map(|stored| stored.exemplar.id)
+        .collect())
+}
+
+fn render_section(exemplars: &[Exemplar]) -> String {
+    if exemplars.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "\n\n# Examples\n\nThese past answers were marked as good examples for similar tasks. \
+         Follow their style and level of detail where it fits; they are not part of this \
+         conversation.\n",
+    );
+    for (i, exemplar) in exemplars.iter().enumerate() {
+        section.push_str(&format!(
+            "\n## Example {}\n\nRequest:\n{}\n\nAnswer:\n{}\n",
+            i + 1,
+            exemplar.prompt.trim(),
+            exemplar.response.trim()
+        ));
+    }
+    section
+}
+
+/// System prompt section with the exemplars most relevant to the session's task, taken to
+/// be its first prompt. Empty when there are none, and never fails the caller.
+pub async fn prompt_section(provider: &Arc<dyn Provider>, messages: &[Message]) -> String {
+    let config = ExemplarsConfig::load();
+    if !config.enabled || config.max_examples == 0 || !provider.supports_embeddings() {
+        return String::new();
+    }
+    let Some(task) = messages.iter().find(|m| is_prompt(m)) else {
+        return String::new();
+    };
+    let stored = match config.store_path().and_then(|path| read_store(&path)) {
+        Ok(stored) if !stored.is_empty() => stored,
+        Ok(_) => return String::new(),
+        Err(e) => {
+            tracing::warn!("Failed to read exemplars: {}", e);
+            return String::new();
+        }
+    };
+    match embed(provider, &task.as_concat_text()).await {
+        Ok(embedding) => render_section(&most_similar(stored, &embedding, &config)),
+        Err(e) => {
+            tracing::debug!("Skipping exemplars, the task could not be embedded: {}", e);
+            String::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn stored(id: &str, embedding: Vec<f32>) -> StoredExemplar {
+        StoredExemplar {
+            exemplar: Exemplar {
+                id: id.to_string(),
+                prompt: format!("prompt {}", id),
+                response: format!("response {}", id),
+                source_session: None,
+                author: None,
+                created: 0,
+            },
+            embedding,
+        }
+    }
+
+    #[test]
+    fn test_most_similar_ranks_and_filters() {
+        let config = ExemplarsConfig::default();
+        let exemplars = vec![
+            stored("far", vec![0.0, 1.0]),
+            stored("close", vec![0.9, 0.1]),
+            stored("exact", vec![1.0, 0.0]),
+            stored("near", vec![0.8, 0.3]),
+        ];
+        let ids: Vec<String> = most_similar(exemplars, &[1.0, 0.0], &config)
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec!["exact", "close"]);
+    }
+
+    #[test]
+    fn test_exchange_takes_the_final_answer() {
+        let messages = vec![
+            Message::user().with_text("summarize the PR"),
+            Message::assistant().with_text("Let me look."),
+            Message::user().with_tool_response("call_1", Ok(vec![])),
+            Message::assistant().with_text("The PR adds exemplars."),
+            Message::user().with_text("thanks"),
+        ];
+        assert_eq!(
+            exchange_at(&messages, 0),
+            Some((
+                "summarize the PR".to_string(),
+                "The PR adds exemplars.".to_string()
+            ))
+        );
+        assert_eq!(exchange_at(&messages, 1), None);
+        assert_eq!(exchange_at(&messages, 4), None);
+    }
+
+    #[test]
+    fn test_store_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("exemplars.json");
+        assert!(read_store(&path)?.is_empty());
+        write_store(&path, &[stored("a", vec![1.0])])?;
+        let read = read_store(&path)?;
+        assert_eq!(read[0].exemplar.id, "a");
+        assert_eq!(read[0].embedding, vec![1.0]);
+        assert!(render_section(&[read[0].exemplar.clone()]).contains("Request:\nprompt a"));
+        Ok(())
+    }
+}
@@ -0,0 +1,906 @@
+//! Data-governance requests: exporting everything goose keeps about a project, or about
+//! the user when no project is given, into one portable zip, and legal holds that keep
+//! that data from being deleted until the hold is released.
+//!
+//! A project is a working directory. Its sessions are the ones started in it or below it,
+//! and its memories are the ones the memory extension keeps in its `.goose/memory`. Without
+//! a project every session and the global memories are covered. Holds are kept in the
+//! config; held memories are additionally marked with a `memory.hold` file next to the
+//! memory directory, which the memory extension checks before removing anything.
+//!
+//! A hold covers the sessions in its scope when it was placed and any started there since.
+//! Held sessions are never removed, and when the history of one is rewritten rather than
+//! extended (an edited or retried message, a cleared chat) the version it replaces is kept
+//! in a `.held.jsonl` sidecar, so the export still has everything that was recorded.
+//!
+//! `purge` deletes the same data, plus the exemplars and alerts that came from the purged
+//! sessions and their copies in goose's backups and sync backend, skipping whatever a hold
+//! covers. Tool statistics and usage analytics record no session or project, so only a purge
+//! of all data removes them. Every purge can be run as a dry run first to report what it would
+//! remove; the dry run's confirmation token is what a purge of all data is confirmed with.
+
+use crate::analytics::Analytics;
+use crate::backup::{self, collect_files, sha256_hex, BackupCategory};
+use crate::config::{Config, APP_STRATEGY};
+use crate::session::sync::{self, SyncConfig};
+use crate::tool_stats::ToolStats;
+use crate::{anomaly, exemplars, session};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub const LEGAL_HOLDS_KEY: &str = "GOOSE_LEGAL_HOLDS";
+const MANIFEST_PATH: &str = "manifest.json";
+const COPY_CHUNK: usize = 64 * 1024;
+
+/// What an exported file is a record of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordKind {
+    /// A session's messages, or data kept alongside them such as branches and manifests
+    Session,
+    /// The audit trail of a session
+    Audit,
+    /// A file attached to a session
+    Attachment,
+    Memory,
+    /// Tool call statistics or usage analytics
+    Usage,
+}
+
+impl RecordKind {
+    /// The kind of a file kept for a session, from its name relative to the session dir
+    fn of_session_file(relative: &str) -> Self {
+        if relative.ends_with(".audit.jsonl") {
+            RecordKind::Audit
+        } else if relative.contains(".attachments/") {
+            RecordKind::Attachment
+        } else {
+            RecordKind::Session
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExportEntry {
+    /// Path inside the archive, `/`-separated
+    pub path: String,
+    pub kind: RecordKind,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExportManifest {
+    pub goose_version: String,
+    pub created_at: DateTime<Utc>,
+    /// The project the export covers; None when it covers all of the user's data
+    pub project: Option<String>,
+    /// The hold placed on the exported data, if one was
+    pub hold_id: Option<String>,
+    pub sessions: Vec<String>,
+    pub entries: Vec<ExportEntry>,
+}
+
+/// Data that must not be deleted until the hold is released
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LegalHold {
+    pub id: String,
+    pub project: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Sessions that existed when the hold was placed
+    pub sessions: Vec<String>,
+    /// The memory directory the hold freezes
+    pub memory_dir: String,
+}
+
+impl LegalHold {
+    /// Whether the hold covers session `name`, kept in `session_file`: any session in its
+    /// scope, whether or not it existed when the hold was placed
+    pub fn covers_session(&self, name: &str, session_file: &Path) -> bool {
+        if self.sessions.iter().any(|session| session == name) {
+            return true;
+        }
+        match &self.project {
+            Some(project) => session::read_metadata(session_file)
+                .is_ok_and(|metadata| metadata.working_dir.starts_with(project)),
+            None => true,
+        }
+    }
+}
+
+/// The memory directory of `project`, or the global one
+fn memory_dir(project: Option<&Path>) -> Result<PathBuf> {
+    Ok(match project {
+        Some(project) => project.join(".goose").join("memory"),
+        None => choose_app_strategy(APP_STRATEGY.clone())?.in_config_dir("memory"),
+    })
+}
+
+/// The marker that freezes the memories in `memory_dir`; it lists the holds covering them
+fn memory_hold_marker(memory_dir: &Path) -> PathBuf {
+    memory_dir.with_extension("hold")
+}
+
+/// Sessions in scope of `project`, skipping the `.jsonl` sidecars of other sessions
+fn scoped_sessions(project: Option<&Path>) -> Result<Vec<(String, PathBuf)>> {
+    let all = session::list_sessions()?;
+    let names: HashSet<&str> = all.iter().map(|(name, _)| name.as_str()).collect();
+    let mut sessions: Vec<(String, PathBuf)> = all
+        .iter()
+        .filter(|(name, _)| {
+            name.split_once('.')
+                .is_none_or(|(owner, _)| !names.contains(owner))
+        })
+        .filter(|(_, path)| match project {
+            Some(project) => session::read_metadata(path)
+                .is_ok_and(|metadata| metadata.working_dir.starts_with(project)),
+            None => true,
+        })
+        .cloned()
+        .collect();
+    sessions.sort();
+    Ok(sessions)
+}
+
+/// Files and directories kept next to a session: audit trail, branches, manifests,
+/// attachments
+fn sidecars(name: &str, session_file: &Path) -> Result<Vec<PathBuf>> {
+    let Some(dir) = session_file.parent() else {
+        return Ok(Vec::new());
+    };
+    let prefix = format!("{}.", name);
+    let mut sidecars = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_sidecar = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .is_some_and(|file_name| file_name.starts_with(&prefix));
+        if is_sidecar && path != session_file {
+            sidecars.push(path);
+        }
+    }
+    sidecars.sort();
+    Ok(sidecars)
+}
+
+/// The session file followed by every file kept next to it
+fn session_files(name: &str, session_file: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![session_file.to_path_buf()];
+    for sidecar in sidecars(name, session_file)? {
+        if sidecar.is_dir() {
+            collect_files(&sidecar, &mut files)?;
+        } else {
+            files.push(sidecar);
+        }
+    }
+    files[1..].sort();
+    Ok(files)
+}
+
+fn write_archive<W: Write + Seek>(
+    writer: W,
+    sessions: &[(String, PathBuf)],
+    memory_dir: &Path,
+    project: Option<&Path>,
+    hold: Option<&LegalHold>,
+) -> Result<ExportManifest> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(writer);
+    let mut entries = Vec::new();
+
+    // Files are copied a chunk at a time, so no session or attachment is ever held in memory
+    // whole, however large the export
+    let mut buffer = vec![0; COPY_CHUNK];
+    let mut add = |zip: &mut ZipWriter<W>, path: String, kind, file: &Path| -> Result<()> {
+        let mut source =
+            fs::File::open(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        zip.start_file(path.as_str(), options)?;
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+        loop {
+            let read = source.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            zip.write_all(&buffer[..read])?;
+            size += read as u64;
+        }
+        entries.push(ExportEntry {
+            path,
+            kind,
+            size,
+            sha256: hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        });
+        Ok(())
+    };
+
+    for (name, session_file) in sessions {
+        let dir = session_file.parent().unwrap_or(Path::new(""));
+        for file in session_files(name, session_file)? {
+            let relative = file.strip_prefix(dir)?.to_string_lossy().replace('\\', "/");
+            let kind = RecordKind::of_session_file(&relative);
+            add(&mut zip, format!("sessions/{}", relative), kind, &file)?;
+        }
+    }
+
+    let mut memories = Vec::new();
+    collect_files(memory_dir, &mut memories)?;
+    memories.sort();
+    for file in memories {
+        let relative = file
+            .strip_prefix(memory_dir)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        add(
+            &mut zip,
+            format!("memories/{}", relative),
+            RecordKind::Memory,
+            &file,
+        )?;
+    }
+
+    let manifest = ExportManifest {
+        goose_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        project: project.map(|project| project.display().to_string()),
+        hold_id: hold.map(|hold| hold.id.clone()),
+        sessions: sessions.iter().map(|(name, _)| name.clone()).collect(),
+        entries,
+    };
+    zip.start_file(MANIFEST_PATH, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
+    Ok(manifest)
+}
+
+/// Write every session, audit record and memory of `project` (or of the user) as a zip to
+/// `writer`, noting `hold` in the manifest when the export was made for one
+pub fn export_all<W: Write + Seek>(
+    writer: W,
+    project: Option<&Path>,
+    hold: Option<&LegalHold>,
+) -> Result<ExportManifest> {
+    let sessions = scoped_sessions(project)?;
+    write_archive(writer, &sessions, &memory_dir(project)?, project, hold)
+}
+
+pub fn holds() -> Vec<LegalHold> {
+    Config::global()
+        .get_param(LEGAL_HOLDS_KEY)
+        .unwrap_or_default()
+}
+
+// The user config's own list, which changes start from so that other layers aren't copied in
+fn user_holds() -> Vec<LegalHold> {
+    Config::global()
+        .get_user_param(LEGAL_HOLDS_KEY)
+        .unwrap_or_default()
+}
+
+fn save_holds(holds: &[LegalHold]) -> Result<()> {
+    Config::global().set_param(LEGAL_HOLDS_KEY, serde_json::to_value(holds)?)?;
+    Ok(())
+}
+
+/// Rewrite the marker for `memory_dir` to list `hold_ids`, removing it when there are none
+fn write_memory_marker(memory_dir: &Path, hold_ids: &[&str]) -> Result<()> {
+    let marker = memory_hold_marker(memory_dir);
+    if hold_ids.is_empty() {
+        if marker.exists() {
+            fs::remove_file(&marker)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = marker.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&marker, format!("{}\n", hold_ids.join("\n")))?;
+    Ok(())
+}
+
+fn sync_memory_marker(holds: &[LegalHold], memory_dir: &str) -> Result<()> {
+    let hold_ids: Vec<&str> = holds
+        .iter()
+        .filter(|hold| hold.memory_dir == memory_dir)
+        .map(|hold| hold.id.as_str())
+        .collect();
+    write_memory_marker(Path::new(memory_dir), &hold_ids)
+}
+
+/// Freeze the sessions and memories of `project` (or of the user) against deletion
+pub fn place_hold(project: Option<&Path>, reason: Option<String>) -> Result<LegalHold> {
+    let hold = LegalHold {
+        id: uuid::Uuid::new_v4().to_string(),
+        project: project.map(|project| project.display().to_string()),
+        reason,
+        created_at: Utc::now(),
+        sessions: scoped_sessions(project)?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect(),
+        memory_dir: memory_dir(project)?.display().to_string(),
+    };
+    let mut all = user_holds();
+    all.push(hold.clone());
+    sync_memory_marker(&all, &hold.memory_dir)?;
+    save_holds(&all)?;
+    Ok(hold)
+}
+
+/// Release the hold with `id`, returning whether there was one
+pub fn release_hold(id: &str) -> Result<bool> {
+    let mut all = user_holds();
+    let Some(index) = all.iter().position(|hold| hold.id == id) else {
+        return Ok(false);
+    };
+    let released = all.remove(index);
+    sync_memory_marker(&all, &released.memory_dir)?;
+    save_holds(&all)?;
+    Ok(true)
+}
+
+/// The hold keeping session `name`, kept in `session_file`, from being deleted, if any
+pub fn session_hold(name: &str, session_file: &Path) -> Option<LegalHold> {
+    holds()
+        .into_iter()
+        .find(|hold| hold.covers_session(name, session_file))
+}
+
+/// Where the versions of a held session that were rewritten are kept
+fn superseded_path(session_file: &Path) -> PathBuf {
+    session_file.with_extension("held.jsonl")
+}
+
+/// Append the saved version of `session_file` to its `.held.jsonl` sidecar unless
+/// `messages`, the lines about to replace its messages, keep every one of them. Returns
+/// whether it was kept.
+fn keep_superseded(session_file: &Path, messages: &[String]) -> Result<bool> {
+    if !session_file.exists() {
+        return Ok(false);
+    }
+    let saved = fs::read_to_string(session_file)?;
+    // The first line is the metadata, which changes on every save
+    let saved_messages: Vec<&str> = saved
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let extended = saved_messages.len() <= messages.len()
+        && saved_messages
+            .iter()
+            .zip(messages)
+            .all(|(saved, message)| *saved == message.as_str());
+    if extended {
+        return Ok(false);
+    }
+
+    let mut superseded = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(superseded_path(session_file))?;
+    superseded.write_all(saved.as_bytes())?;
+    if !saved.ends_with('\n') {
+        superseded.write_all(b"\n")?;
+    }
+    superseded.sync_all()?;
+    Ok(true)
+}
+
+/// Called before `session_file` is overwritten with `messages` (serialized, one per line):
+/// when the session is held and the new history drops or changes anything that was saved,
+/// the saved version is kept next to it first
+pub fn preserve_held_history(session_file: &Path, messages: &[String]) -> Result<()> {
+    let Some(name) = session_file.file_stem().and_then(|stem| stem.to_str()) else {
+        return Ok(());
+    };
+    if session_hold(name, session_file).is_some() && keep_superseded(session_file, messages)? {
+        tracing::info!(
+            "Kept the replaced history of held session {} in {}",
+            name,
+            superseded_path(session_file).display()
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PurgedFile {
+    pub path: String,
+    pub kind: RecordKind,
+    pub size: u64,
+}
+
+/// What a purge removed, or would remove when it was a dry run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub project: Option<String>,
+    pub sessions: Vec<String>,
+    pub files: Vec<PurgedFile>,
+    /// Exemplars taken from the purged sessions, removed with their embeddings
+    pub exemplars: Vec<String>,
+    /// Number of alerts raised by the purged sessions
+    pub alerts: usize,
+    /// Sessions kept because a legal hold covers them
+    pub held_sessions: Vec<String>,
+    /// Whether the memories were kept because a legal hold covers them
+    pub memories_held: bool,
+    /// Backup archives in goose's backups directory that purged data was removed from
+    pub backups: Vec<String>,
+    /// Sessions whose copies were removed from the sync backend
+    pub synced: Vec<String>,
+    /// Set on dry runs; a purge of all data has to be confirmed with it
+    pub confirmation_token: Option<String>,
+}
+
+impl PurgeReport {
+    pub fn bytes(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// Hash of what the purge covers, so a confirmation only holds for the data that was
+    /// reported. Sizes are left out since sessions grow while they are in use.
+    fn scope_token(&self) -> String {
+        let mut scope = vec![self.project.clone().unwrap_or_default()];
+        scope.extend(self.sessions.iter().cloned());
+        scope.extend(self.files.iter().map(|file| file.path.clone()));
+        scope.extend(self.backups.iter().cloned());
+        scope.extend(self.synced.iter().cloned());
+        sha256_hex(scope.join("\n").as_bytes())
+    }
+}
+
+fn purged_file(path: &Path, kind: RecordKind) -> Result<PurgedFile> {
+    Ok(PurgedFile {
+        path: path.display().to_string(),
+        kind,
+        size: fs::metadata(path)?.len(),
+    })
+}
+
+/// Remove the files of `sessions` not in `held`, and the memories in `memory_dir` unless
+/// they are held; with `dry_run` nothing is removed
+fn purge_files(
+    sessions: &[(String, PathBuf)],
+    held: &HashSet<String>,
+    memory_dir: &Path,
+    dry_run: bool,
+    report: &mut PurgeReport,
+) -> Result<()> {
+    for (name, session_file) in sessions {
+        if held.contains(name) {
+            report.held_sessions.push(name.clone());
+            continue;
+        }
+        let dir = session_file.parent().unwrap_or(Path::new(""));
+        for file in session_files(name, session_file)? {
+            let relative = file.strip_prefix(dir)?.to_string_lossy().replace('\\', "/");
+            report
+                .files
+                .push(purged_file(&file, RecordKind::of_session_file(&relative))?);
+        }
+        if !dry_run {
+            // The session file goes last so an interrupted purge leaves it listed
+            for sidecar in sidecars(name, session_file)? {
+                if sidecar.is_dir() {
+                    fs::remove_dir_all(&sidecar)?;
+                } else {
+                    fs::remove_file(&sidecar)?;
+                }
+            }
+            fs::remove_file(session_file)?;
+        }
+        report.sessions.push(name.clone());
+    }
+
+    if memory_hold_marker(memory_dir).exists() {
+        report.memories_held = true;
+        return Ok(());
+    }
+    let mut memories = Vec::new();
+    collect_files(memory_dir, &mut memories)?;
+    memories.sort();
+    for file in &memories {
+        report.files.push(purged_file(file, RecordKind::Memory)?);
+    }
+    if !dry_run && memory_dir.exists() {
+        fs::remove_dir_all(memory_dir)?;
+    }
+    Ok(())
+}
+
+/// Remove tool statistics and usage analytics
+fn purge_usage(dry_run: bool, report: &mut PurgeReport) -> Result<()> {
+    let stats = ToolStats::from_default_path()?;
+    let analytics = Analytics::from_config(Config::global())?;
+    for path in [stats.path(), analytics.path()] {
+        if path.exists() {
+            report.files.push(purged_file(path, RecordKind::Usage)?);
+        }
+    }
+    if !dry_run {
+        stats.clear()?;
+        analytics.clear()?;
+    }
+    Ok(())
+}
+
+/// Drop `sessions`, and the memories when `memories` is set, from every backup archive in
+/// `backups_dir`, returning the archives that held any of them
+fn purge_backups(
+    backups_dir: &Path,
+    sessions: &[String],
+    memories: bool,
+    dry_run: bool,
+) -> Result<Vec<String>> {
+    let sessions: HashSet<&str> = sessions.iter().map(String::as_str).collect();
+    let mut archives = Vec::new();
+    collect_files(backups_dir, &mut archives)?;
+    archives.sort();
+
+    let mut purged = Vec::new();
+    for archive in archives {
+        if let Err(e) = fs::File::open(&archive)
+            .map_err(Into::into)
+            .and_then(backup::read_manifest)
+        {
+            tracing::warn!(
+                "Skipping {}, which is not a goose backup: {}",
+                archive.display(),
+                e
+            );
+            continue;
+        }
+        let removed = backup::remove_entries(
+            &archive,
+            |entry| match entry.category {
+                BackupCategory::Sessions => {
+                    entry.path.strip_prefix("sessions/").is_some_and(|file| {
+                        let owner = file.split_once('.').map_or(file, |(owner, _)| owner);
+                        sessions.contains(owner)
+                    })
+                }
+                BackupCategory::Memories => memories,
+                _ => false,
+            },
+            dry_run,
+        )?;
+        if !removed.is_empty() {
+            purged.push(archive.display().to_string());
+        }
+    }
+    Ok(purged)
+}
+
+/// Delete every session, attachment, audit record, memory, exemplar and alert of `project`
+/// (or of the user) along with their backed up and synced copies, except what a legal hold
+/// covers. With `dry_run` nothing is deleted, and the report says what would be and carries
+/// the token to confirm the purge with.
+pub async fn purge(project: Option<&Path>, dry_run: bool) -> Result<PurgeReport> {
+    let sessions = scoped_sessions(project)?;
+    let holds = holds();
+    let held: HashSet<String> = holds
+        .iter()
+        .flat_map(|hold| hold.sessions.iter().cloned())
+        .chain(
+            sessions
+                .iter()
+                .filter(|(name, path)| holds.iter().any(|hold| hold.covers_session(name, path)))
+                .map(|(name, _)| name.clone()),
+        )
+        .collect();
+    let mut report = PurgeReport {
+        dry_run,
+        project: project.map(|project| project.display().to_string()),
+        ..Default::default()
+    };
+    purge_files(
+        &sessions,
+        &held,
+        &memory_dir(project)?,
+        dry_run,
+        &mut report,
+    )?;
+
+    // Without a project everything goes, including exemplars and alerts from sessions
+    // that no longer exist, but never those tied to a held session
+    let purged: HashSet<&str> = report.sessions.iter().map(String::as_str).collect();
+    let in_scope = |session: Option<&str>| match session {
+        Some(session) if held.contains(session) => false,
+        Some(session) => project.is_none() || purged.contains(session),
+        None => project.is_none(),
+    };
+    report.exemplars = exemplars::remove_exemplars(
+        |exemplar| in_scope(exemplar.source_session.as_deref()),
+        dry_run,
+    )?;
+    report.alerts = anomaly::remove_alerts(|alert| in_scope(alert.session_id.as_deref()), dry_run)?;
+    if project.is_none() {
+        purge_usage(dry_run, &mut report)?;
+    }
+    report.backups = purge_backups(
+        &backup::backups_dir()?,
+        &report.sessions,
+        project.is_none() && !report.memories_held,
+        dry_run,
+    )?;
+    let config = Config::global();
+    if let Some(sync_config) = SyncConfig::load(config)? {
+        let backend = sync_config.backend(config)?;
+        report.synced =
+            sync::purge_remote(backend.as_ref(), |name| in_scope(Some(name)), dry_run).await?;
+    }
+
+    if dry_run {
+        report.confirmation_token = Some(report.scope_token());
+    }
+    Ok(report)
+}
+
+/// Whether `token` confirms purging `project` (or all data) as it stands now
+pub async fn confirms_purge(project: Option<&Path>, token: &str) -> Result<bool> {
+    let preview = purge(project, true).await?;
+    Ok(preview.confirmation_token.as_deref() == Some(token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+    use tempfile::tempdir;
+    use zip::ZipArchive;
+
+    #[test]
+    fn test_export_includes_sidecars_and_memories() -> Result<()> {
+        let root = tempdir()?;
+        let sessions_dir = root.path().join("sessions");
+        fs::create_dir_all(sessions_dir.join("20250101_1.attachments"))?;
+        fs::write(sessions_dir.join("20250101_1.jsonl"), "{}\n")?;
+        fs::write(sessions_dir.join("20250101_1.audit.jsonl"), "{}\n")?;
+        fs::write(sessions_dir.join("20250101_1.branches.json"), "[]")?;
+        fs::write(sessions_dir.join("20250101_1.attachments/a.png"), "png")?;
+        fs::write(sessions_dir.join("20250101_10.jsonl"), "{}\n")?;
+        let memories = root.path().join("memory");
+        fs::create_dir_all(&memories)?;
+        fs::write(memories.join("project.txt"), "uses tabs")?;
+
+        let sessions = vec![(
+            "20250101_1".to_string(),
+            sessions_dir.join("20250101_1.jsonl"),
+        )];
+        let mut archive = Cursor::new(Vec::new());
+        let manifest = write_archive(&mut archive, &sessions, &memories, None, None)?;
+
+        let paths: Vec<(&str, RecordKind)> = manifest
+            .entries
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.kind))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("sessions/20250101_1.jsonl", RecordKind::Session),
+                (
+                    "sessions/20250101_1.attachments/a.png",
+                    RecordKind::Attachment
+                ),
+                ("sessions/20250101_1.audit.jsonl", RecordKind::Audit),
+                ("sessions/20250101_1.branches.json", RecordKind::Session),
+                ("memories/project.txt", RecordKind::Memory),
+            ]
+        );
+
+        let mut zip = ZipArchive::new(archive)?;
+        let mut content = String::new();
+        zip.by_name("memories/project.txt")?
+            .read_to_string(&mut content)?;
+        assert_eq!(content, "uses tabs");
+        assert_eq!(manifest.entries[4].sha256, sha256_hex(b"uses tabs"));
+        assert!(zip.by_name(MANIFEST_PATH).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_skips_held_data() -> Result<()> {
+        let root = tempdir()?;
+        let sessions_dir = root.path().join("sessions");
+        fs::create_dir_all(sessions_dir.join("gone.attachments"))?;
+        fs::write(sessions_dir.join("gone.jsonl"), "{}\n")?;
+        fs::write(sessions_dir.join("gone.audit.jsonl"), "{}\n")?;
+        fs::write(sessions_dir.join("gone.attachments/a.png"), "png")?;
+        fs::write(sessions_dir.join("kept.jsonl"), "{}\n")?;
+        let memories = root.path().join("memory");
+        fs::create_dir_all(&memories)?;
+        fs::write(memories.join("project.txt"), "uses tabs")?;
+        write_memory_marker(&memories, &["hold"])?;
+
+        let sessions = vec![
+            ("gone".to_string(), sessions_dir.join("gone.jsonl")),
+            ("kept".to_string(), sessions_dir.join("kept.jsonl")),
+        ];
+        let held = HashSet::from(["kept".to_string()]);
+
+        let mut dry_run = PurgeReport::default();
+        purge_files(&sessions, &held, &memories, true, &mut dry_run)?;
+        assert_eq!(dry_run.sessions, vec!["gone"]);
+        assert_eq!(dry_run.held_sessions, vec!["kept"]);
+        assert!(dry_run.memories_held);
+        let kinds: Vec<RecordKind> = dry_run.files.iter().map(|file| file.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                RecordKind::Session,
+                RecordKind::Attachment,
+                RecordKind::Audit
+            ]
+        );
+        assert_eq!(dry_run.bytes(), 3 + 3 + 3);
+        assert!(sessions_dir.join("gone.jsonl").exists());
+
+        let mut report = PurgeReport::default();
+        purge_files(&sessions, &held, &memories, false, &mut report)?;
+        assert_eq!(report.files, dry_run.files);
+        let mut left: Vec<String> = fs::read_dir(&sessions_dir)?
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["kept.jsonl"]);
+        assert!(memories.join("project.txt").exists());
+
+        write_memory_marker(&memories, &[])?;
+        let mut report = PurgeReport::default();
+        purge_files(&[], &held, &memories, false, &mut report)?;
+        assert_eq!(report.files.len(), 1);
+        assert!(!memories.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_removes_backed_up_copies() -> Result<()> {
+        let root = tempdir()?;
+        let locations = backup::BackupLocations {
+            sessions: root.path().join("sessions"),
+            session_roots: Vec::new(),
+            recipes: root.path().join("recipes"),
+            memories: root.path().join("memory"),
+            schedules_file: root.path().join("schedules.json"),
+            scheduled_recipes: root.path().join("scheduled_recipes"),
+        };
+        fs::create_dir_all(locations.sessions.join("gone.attachments"))?;
+        fs::write(locations.sessions.join("gone.jsonl"), "{}\n")?;
+        fs::write(locations.sessions.join("gone.attachments/a.png"), "png")?;
+        fs::write(locations.sessions.join("kept.jsonl"), "{}\n")?;
+        fs::create_dir_all(&locations.memories)?;
+        fs::write(locations.memories.join("project.txt"), "uses tabs")?;
+
+        let backups = root.path().join("backups");
+        fs::create_dir_all(&backups)?;
+        fs::write(backups.join("notes.txt"), "not a backup")?;
+        let archive = backups.join("goose-backup.zip");
+        let config =
+            Config::new_with_file_secrets(root.path().join("c.yaml"), root.path().join("s.yaml"))?;
+        backup::create_backup(
+            fs::File::create(&archive)?,
+            &config,
+            &locations,
+            &[BackupCategory::Sessions, BackupCategory::Memories],
+        )?;
+
+        let purged = vec!["gone".to_string()];
+        let reported = purge_backups(&backups, &purged, false, true)?;
+        assert_eq!(reported, vec![archive.display().to_string()]);
+        assert_eq!(
+            backup::read_manifest(fs::File::open(&archive)?)?
+                .entries
+                .len(),
+            4
+        );
+
+        purge_backups(&backups, &purged, false, false)?;
+        let manifest = backup::read_manifest(fs::File::open(&archive)?)?;
+        let paths: Vec<&str> = manifest.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["sessions/kept.jsonl", "memories/project.txt"]);
+
+        assert!(purge_backups(&backups, &purged, false, false)?.is_empty());
+        purge_backups(&backups, &[], true, false)?;
+        let manifest = backup::read_manifest(fs::File::open(&archive)?)?;
+        assert_eq!(manifest.entries.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirmation_token_follows_scope() {
+        let report = PurgeReport {
+            sessions: vec!["gone".to_string()],
+            ..Default::default()
+        };
+        let mut grown = report.clone();
+        grown.sessions.push("new".to_string());
+        assert_eq!(report.scope_token(), report.clone().scope_token());
+        assert_ne!(report.scope_token(), grown.scope_token());
+    }
+
+    #[test]
+    fn test_rewritten_history_is_kept() -> Result<()> {
+        let root = tempdir()?;
+        let session_file = root.path().join("held.jsonl");
+        fs::write(&session_file, "{\"metadata\":1}\n\"first\"\n\"second\"\n")?;
+        let lines = |messages: &[&str]| -> Vec<String> {
+            messages.iter().map(|m| format!("\"{}\"", m)).collect()
+        };
+
+        // Growing the history keeps nothing extra
+        assert!(!keep_superseded(
+            &session_file,
+            &lines(&["first", "second", "third"])
+        )?);
+        assert!(!superseded_path(&session_file).exists());
+
+        assert!(keep_superseded(
+            &session_file,
+            &lines(&["first", "edited"])
+        )?);
+        assert!(keep_superseded(&session_file, &[])?);
+        assert_eq!(
+            fs::read_to_string(superseded_path(&session_file))?,
+            "{\"metadata\":1}\n\"first\"\n\"second\"\n".repeat(2)
+        );
+        assert_eq!(
+            superseded_path(&session_file),
+            root.path().join("held.held.jsonl")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_holds_cover_sessions_started_later() {
+        let hold = LegalHold {
+            id: "hold".to_string(),
+            project: None,
+            reason: None,
+            created_at: Utc::now(),
+            sessions: vec!["old".to_string()],
+            memory_dir: "memory".to_string(),
+        };
+        assert!(hold.covers_session("new", Path::new("new.jsonl")));
+
+        let project_hold = LegalHold {
+            project: Some("/work/project".to_string()),
+            ..hold
+        };
+        assert!(project_hold.covers_session("old", Path::new("old.jsonl")));
+        // Sessions it can't place in the project aren't covered
+        assert!(!project_hold.covers_session("new", Path::new("missing.jsonl")));
+    }
+
+    #[test]
+    fn test_memory_marker_follows_holds() -> Result<()> {
+        let root = tempdir()?;
+        let memories = root.path().join("project/.goose/memory");
+        let marker = memory_hold_marker(&memories);
+        assert_eq!(marker, root.path().join("project/.goose/memory.hold"));
+
+        write_memory_marker(&memories, &["first", "second"])?;
+        assert_eq!(fs::read_to_string(&marker)?, "first\nsecond\n");
+        write_memory_marker(&memories, &[])?;
+        assert!(!marker.exists());
+        Ok(())
+    }
+}
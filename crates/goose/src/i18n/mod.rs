@@ -0,0 +1,225 @@
+//! Translations for the strings goose shows to people, and the language the agent answers in.
+//!
+//! Two settings control this, and they are independent of each other:
+//!
+//! ```yaml
+//! GOOSE_LOCALE: es               # CLI output and API error messages
+//! GOOSE_RESPONSE_LANGUAGE: es    # added to the system prompt; a code or a name like "Spanish"
+//! ```
+//!
+//! Without `GOOSE_LOCALE` the locale comes from `LC_ALL`, `LC_MESSAGES` or `LANG`. Catalogs
+//! live in `locales/<code>.yaml` as flat `key: text` maps; a key missing from a catalog falls
+//! back to English, and `{name}` placeholders are filled in by [`t_with`].
+
+use crate::config::Config;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+pub const LOCALE_KEY: &str = "GOOSE_LOCALE";
+pub const RESPONSE_LANGUAGE_KEY: &str = "GOOSE_RESPONSE_LANGUAGE";
+
+const DEFAULT_LOCALE: &str = "en";
+
+/// Locale code, English name and catalog source for every supported locale
+const LOCALES: &[(&str, &str, &str)] = &[
+    ("en", "English", include_str!("locales/en.yaml")),
+    ("de", "German", include_str!("locales/de.yaml")),
+    ("es", "Spanish", include_str!("locales/es.yaml")),
+    ("fr", "French", include_str!("locales/fr.yaml")),
+    ("ja", "Japanese", include_str!("locales/ja.yaml")),
+    ("pt", "Portuguese", include_str!("locales/pt.yaml")),
+    ("zh", "Chinese", include_str!("locales/zh.yaml")),
+];
+
+static CATALOGS: Lazy<HashMap<&'static str, HashMap<String, String>>> = Lazy::new(|| {
+    LOCALES
+        .iter()
+        .map(|(code, _, source)| {
+            let catalog = serde_yaml::from_str(source)
+                .unwrap_or_else(|e| panic!("Invalid {} locale catalog: {}", code, e));
+            (*code, catalog)
+        })
+        .collect()
+});
+
+/// Codes of the locales goose has translations for
+pub fn available_locales() -> Vec<&'static str> {
+    LOCALES.iter().map(|(code, _, _)| *code).collect()
+}
+
+/// Reduce a locale like `pt_BR.UTF-8` or `zh-Hans` to a supported code, if there is one
+pub fn normalize_locale(locale: &str) -> Option<&'static str> {
+    let language = locale
+        .split(['.', '@'])
+        .next()?
+        .split(['_', '-'])
+        .next()?
+        .trim()
+        .to_lowercase();
+    if language == "c" || language == "posix" {
+        return Some(DEFAULT_LOCALE);
+    }
+    LOCALES
+        .iter()
+        .map(|(code, _, _)| *code)
+        .find(|code| *code == language)
+}
+
+/// The locale for CLI output: `GOOSE_LOCALE`, then the usual locale environment variables
+pub fn current_locale() -> &'static str {
+    let configured = Config::global().get_param::<String>(LOCALE_KEY).ok();
+    configured
+        .into_iter()
+        .chain(
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok()),
+        )
+        .filter(|locale| !locale.is_empty())
+        .find_map(|locale| normalize_locale(&locale))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Pick the best supported locale from an `Accept-Language` header, honouring `q` weights
+pub fn negotiate_locale(accept_language: &str) -> Option<&'static str> {
+    let mut candidates: Vec<(f32, &str)> = accept_language
+        .split(',')
+        .filter_map(|part| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let quality = pieces
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.parse().ok())
+                .unwrap_or(1.0);
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((quality, tag))
+        })
+        .collect();
+    // Stable sort, so equally weighted tags keep the client's order
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    candidates
+        .into_iter()
+        .find_map(|(_, tag)| normalize_locale(tag))
+}
+
+/// Look up `key` in `locale`, falling back to English and then to the key itself
+pub fn translate(locale: &str, key: &str, args: &[(&str, &str)]) -> String {
+    let lookup = |code: &str| CATALOGS.get(code).and_then(|catalog| catalog.get(key));
+    let template = normalize_locale(locale)
+        .and_then(lookup)
+        .or_else(|| lookup(DEFAULT_LOCALE))
+        .map(String::as_str)
+        .unwrap_or(key);
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// `key` in the current locale
+pub fn t(key: &str) -> String {
+    translate(current_locale(), key, &[])
+}
+
+/// `key` in the current locale with its `{name}` placeholders filled in
+pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
+    translate(current_locale(), key, args)
+}
+
+/// The language the agent should answer in, from `GOOSE_RESPONSE_LANGUAGE`. Supported
+/// locale codes become language names; anything else is used as written.
+pub fn response_language() -> Option<String> {
+    let configured = Config::global()
+        .get_param::<String>(RESPONSE_LANGUAGE_KEY)
+        .ok()?;
+    response_language_name(&configured)
+}
+
+fn response_language_name(configured: &str) -> Option<String> {
+    let configured = configured.trim();
+    if configured.is_empty() {
+        return None;
+    }
+    let name = LOCALES
+        .iter()
+        .find(|(code, _, _)| configured.eq_ignore_ascii_case(code))
+        .map(|(_, name, _)| name.to_string())
+        .unwrap_or_else(|| configured.to_string());
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_catalog_translates_english() {
+        let english = &CATALOGS[DEFAULT_LOCALE];
+        for code in available_locales() {
+            let catalog = &CATALOGS[code];
+            for key in catalog.keys() {
+                assert!(
+                    english.contains_key(key),
+                    "{} has unknown key {}",
+                    code,
+                    key
+                );
+            }
+            for (key, text) in english {
+                let Some(translated) = catalog.get(key) else {
+                    panic!("{} has no translation for {}", code, key);
+                };
+                for placeholder in text.split('{').skip(1).filter_map(|s| s.split_once('}')) {
+                    let placeholder = format!("{{{}}}", placeholder.0);
+                    assert!(
+                        translated.contains(&placeholder),
+                        "{} {} is missing {}",
+                        code,
+                        key,
+                        placeholder
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_locale() {
+        assert_eq!(normalize_locale("pt_BR.UTF-8"), Some("pt"));
+        assert_eq!(normalize_locale("zh-Hans"), Some("zh"));
+        assert_eq!(normalize_locale("DE"), Some("de"));
+        assert_eq!(normalize_locale("C.UTF-8"), Some("en"));
+        assert_eq!(normalize_locale("ko_KR"), None);
+    }
+
+    #[test]
+    fn test_translate_falls_back_and_fills_placeholders() {
+        assert_eq!(translate("fr", "cli.context", &[]), "Contexte");
+        assert_eq!(
+            translate("ko", "cli.closing_session", &[]),
+            "Closing session."
+        );
+        assert_eq!(translate("es", "no.such.key", &[]), "no.such.key");
+        assert_eq!(
+            translate("de", "api.agent_unavailable", &[("error", "boom")]),
+            "Agent konnte nicht abgerufen werden: boom"
+        );
+    }
+
+    #[test]
+    fn test_negotiate_locale() {
+        assert_eq!(negotiate_locale("fr-CH, fr;q=0.9, en;q=0.8"), Some("fr"));
+        assert_eq!(negotiate_locale("ko;q=1.0, ja;q=0.5, en;q=0.7"), Some("en"));
+        assert_eq!(negotiate_locale("ko, *;q=0.5"), None);
+        assert_eq!(negotiate_locale("es;q=0, de"), Some("de"));
+    }
+
+    #[test]
+    fn test_response_language_name() {
+        assert_eq!(response_language_name("ES").as_deref(), Some("Spanish"));
+        assert_eq!(
+            response_language_name("Brazilian Portuguese").as_deref(),
+            Some("Brazilian Portuguese")
+        );
+        assert_eq!(response_language_name("  "), None);
+    }
+}
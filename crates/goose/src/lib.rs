@@ -1,21 +1,37 @@
 pub mod agents;
+pub mod analytics;
+pub mod anomaly;
+pub mod backup;
+pub mod bench_results;
 pub mod config;
 pub mod context_mgmt;
 pub mod conversation;
+pub mod diagnostics;
+pub mod digest;
+pub mod embed;
+pub mod exemplars;
+pub mod governance;
+pub mod i18n;
 pub mod model;
+pub mod notifications;
 pub mod oauth;
+pub mod offline;
 pub mod permission;
 pub mod prompt_template;
 pub mod providers;
 pub mod recipe;
 pub mod recipe_deeplink;
+pub mod schedule_calendar;
 pub mod scheduler;
 pub mod scheduler_factory;
 pub mod scheduler_trait;
 pub mod session;
+pub mod sqlite_scheduler;
+pub mod style;
 pub mod temporal_scheduler;
 pub mod token_counter;
 pub mod tool_monitor;
+pub mod tool_stats;
 pub mod tracing;
 pub mod utils;
 
@@ -72,6 +72,9 @@ pub struct ModelConfig {
     pub max_tokens: Option<i32>,
     pub toolshim: bool,
     pub toolshim_model: Option<String>,
+    /// Sampling seed, sent to providers that support deterministic sampling
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,6 +96,7 @@ impl ModelConfig {
         let temperature = Self::parse_temperature()?;
         let toolshim = Self::parse_toolshim()?;
         let toolshim_model = Self::parse_toolshim_model()?;
+        let seed = Self::parse_seed()?;
 
         Ok(Self {
             model_name,
@@ -101,6 +105,7 @@ impl ModelConfig {
             max_tokens: None,
             toolshim,
             toolshim_model,
+            seed,
         })
     }
 
@@ -187,6 +192,19 @@ impl ModelConfig {
         }
     }
 
+    fn parse_seed() -> Result<Option<u64>, ConfigError> {
+        match std::env::var("GOOSE_SEED") {
+            Ok(val) => val.parse::<u64>().map(Some).map_err(|_| {
+                ConfigError::InvalidValue(
+                    "GOOSE_SEED".to_string(),
+                    val,
+                    "must be a non-negative integer".to_string(),
+                )
+            }),
+            Err(_) => Ok(None),
+        }
+    }
+
     fn get_model_specific_limit(model_name: &str) -> Option<usize> {
         MODEL_SPECIFIC_LIMITS
             .iter()
@@ -231,6 +249,11 @@ impl ModelConfig {
         self
     }
 
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
     pub fn context_limit(&self) -> usize {
         self.context_limit.unwrap_or(DEFAULT_CONTEXT_LIMIT)
     }
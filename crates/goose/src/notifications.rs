@@ -0,0 +1,192 @@
+//! Desktop notifications for things worth looking up from other work for: a run finishing,
+//! a tool call waiting for approval, a session passing its token budget, or a run that looks
+//! like it has gone wrong (see [`crate::anomaly`]).
+//!
+//! Each kind of event is configured separately under `GOOSE_NOTIFICATIONS`:
+//!
+//! ```yaml
+//! GOOSE_NOTIFICATIONS:
+//!   run_finished: desktop      # desktop, bell or off (the default)
+//!   approval_needed: desktop
+//!   budget_reached: bell
+//!   budget_tokens: 500000      # session token total that counts as the budget
+//!   anomaly: desktop
+//! ```
+//!
+//! `desktop` uses the platform's own notifications (`osascript` on macOS, `notify-send` on
+//! Linux, a PowerShell balloon on Windows) and rings the terminal bell if that fails.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+pub const NOTIFICATIONS_KEY: &str = "GOOSE_NOTIFICATIONS";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyMethod {
+    Desktop,
+    Bell,
+    #[default]
+    Off,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    RunFinished,
+    ApprovalNeeded,
+    BudgetReached,
+    Anomaly,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    pub run_finished: NotifyMethod,
+    pub approval_needed: NotifyMethod,
+    pub budget_reached: NotifyMethod,
+    pub budget_tokens: Option<i32>,
+    pub anomaly: NotifyMethod,
+}
+
+impl NotificationsConfig {
+    pub fn load() -> Self {
+        Config::global()
+            .get_param(NOTIFICATIONS_KEY)
+            .unwrap_or_default()
+    }
+
+    pub fn method(&self, kind: NotificationKind) -> NotifyMethod {
+        match kind {
+            NotificationKind::RunFinished => self.run_finished,
+            NotificationKind::ApprovalNeeded => self.approval_needed,
+            NotificationKind::BudgetReached => self.budget_reached,
+            NotificationKind::Anomaly => self.anomaly,
+        }
+    }
+
+    /// Whether a session going from `before` to `after` tokens crossed the budget
+    pub fn crosses_budget(&self, before: i32, after: i32) -> bool {
+        self.budget_tokens
+            .is_some_and(|budget| before < budget && after >= budget)
+    }
+}
+
+/// Notify the user as configured for `kind`. Never blocks and never fails; a notification
+/// that can't be shown is only logged.
+pub fn notify(kind: NotificationKind, title: &str, body: &str) {
+    match NotificationsConfig::load().method(kind) {
+        NotifyMethod::Off => {}
+        NotifyMethod::Bell => bell(),
+        NotifyMethod::Desktop => {
+            let title = title.to_string();
+            let body = body.to_string();
+            std::thread::spawn(move || {
+                if let Err(e) = show_desktop_notification(&title, &body) {
+                    tracing::debug!("Desktop notification failed, ringing the bell: {}", e);
+                    bell();
+                }
+            });
+        }
+    }
+}
+
+fn bell() {
+    let mut stderr = std::io::stderr();
+    let _ = stderr.write_all(b"\x07");
+    let _ = stderr.flush();
+}
+
+fn notification_command(title: &str, body: &str) -> Command {
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        ));
+        command
+    } else if cfg!(windows) {
+        let script = format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; \
+             $n.Icon = [System.Drawing.SystemIcons]::Information; \
+             $n.Visible = $true; \
+             $n.ShowBalloonTip(5000, {}, {}, 'Info'); \
+             Start-Sleep -Seconds 6; $n.Dispose()",
+            powershell_string(title),
+            powershell_string(body)
+        );
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=goose", title, body]);
+        command
+    }
+}
+
+fn show_desktop_notification(title: &str, body: &str) -> std::io::Result<()> {
+    let status = notification_command(title, body)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("exited with {}", status)))
+    }
+}
+
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn powershell_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_to_off() {
+        let config: NotificationsConfig =
+            serde_json::from_value(serde_json::json!({ "approval_needed": "desktop" })).unwrap();
+        assert_eq!(
+            config.method(NotificationKind::ApprovalNeeded),
+            NotifyMethod::Desktop
+        );
+        assert_eq!(
+            config.method(NotificationKind::RunFinished),
+            NotifyMethod::Off
+        );
+        assert_eq!(
+            config.method(NotificationKind::BudgetReached),
+            NotifyMethod::Off
+        );
+    }
+
+    #[test]
+    fn test_budget_is_crossed_once() {
+        let config = NotificationsConfig {
+            budget_tokens: Some(1000),
+            ..Default::default()
+        };
+        assert!(!config.crosses_budget(0, 999));
+        assert!(config.crosses_budget(999, 1000));
+        assert!(config.crosses_budget(500, 1500));
+        assert!(!config.crosses_budget(1000, 2000));
+        assert!(!NotificationsConfig::default().crosses_budget(0, i32::MAX));
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(applescript_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(powershell_string("it's"), "'it''s'");
+    }
+}
@@ -0,0 +1,358 @@
+//! Working without a network. Before a reply goose checks that the provider's host, or the
+//! proxy set in `HTTPS_PROXY` and friends, can be reached, so an outage fails in seconds
+//! with a clear message instead of a string of request timeouts. Providers on this machine,
+//! like a local Ollama, are never checked and keep working. Scheduled runs that come due
+//! while offline are queued and run once the provider each of them uses is reachable again.
+//!
+//! ```yaml
+//! GOOSE_OFFLINE:
+//!   mode: auto                 # auto (the default) checks before each reply; on treats the
+//!                              # network as down; off skips the check
+//!   probe_timeout_seconds: 3
+//! ```
+
+use crate::config::{Config, APP_STRATEGY};
+use anyhow::Result;
+use etcetera::{choose_app_strategy, AppStrategy};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use url::Url;
+use utoipa::ToSchema;
+
+pub const OFFLINE_KEY: &str = "GOOSE_OFFLINE";
+
+/// How long a probe result is reused before the host is checked again
+const PROBE_CACHE: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineMode {
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OfflineConfig {
+    pub mode: OfflineMode,
+    pub probe_timeout_seconds: u64,
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            mode: OfflineMode::default(),
+            probe_timeout_seconds: 3,
+        }
+    }
+}
+
+impl OfflineConfig {
+    pub fn load() -> Self {
+        Config::global().get_param(OFFLINE_KEY).unwrap_or_default()
+    }
+}
+
+/// Whether the configured provider can be used right now
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Connectivity {
+    /// The provider's host answered, or it isn't checked
+    Online,
+    /// The provider runs on this machine, so the network doesn't matter
+    Local,
+    Offline {
+        reason: String,
+    },
+}
+
+impl Connectivity {
+    pub fn is_offline(&self) -> bool {
+        matches!(self, Connectivity::Offline { .. })
+    }
+
+    /// What to tell the user when a reply can't be started
+    pub fn message(&self) -> Option<String> {
+        match self {
+            Connectivity::Offline { reason } => Some(format!(
+                "goose is offline: {}. Your session is saved; send your message again once \
+                 you're connected, or switch to a local provider such as Ollama.",
+                reason
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// The URL of the provider's API, from its `*_HOST` or `*_ENDPOINT` setting
+pub fn provider_endpoint(provider_name: &str) -> Option<Url> {
+    let metadata = crate::providers::providers()
+        .into_iter()
+        .find(|p| p.name == provider_name)?;
+    let key = metadata
+        .config_keys
+        .iter()
+        .find(|key| key.name.ends_with("_HOST") || key.name.ends_with("_ENDPOINT"))?;
+    let config = Config::global();
+    let value = config
+        .get_param::<String>(&key.name)
+        .or_else(|_| config.get_secret::<String>(&key.name))
+        .ok()
+        .or_else(|| key.default.clone())?;
+    let value = if value.contains("://") {
+        value
+    } else {
+        format!("https://{}", value)
+    };
+    Url::parse(&value).ok()
+}
+
+fn is_local(url: &Url) -> bool {
+    match url.host_str() {
+        Some("localhost") => true,
+        Some(host) => host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback() || ip.is_unspecified()),
+        None => false,
+    }
+}
+
+/// The proxy requests to `url` go through, from the variables the HTTP client follows:
+/// `HTTPS_PROXY` or `HTTP_PROXY` by scheme, then `ALL_PROXY`, unless `NO_PROXY` exempts
+/// the host. `env` looks a variable up; lowercase names are honored too.
+fn proxy_for(url: &Url, env: impl Fn(&str) -> Option<String>) -> Option<Url> {
+    let var = |name: &str| {
+        env(name)
+            .or_else(|| env(&name.to_lowercase()))
+            .filter(|value| !value.trim().is_empty())
+    };
+    let host = url.host_str()?;
+    if let Some(no_proxy) = var("NO_PROXY") {
+        let exempt = no_proxy
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .any(|entry| {
+                let domain = entry.trim_start_matches('*').trim_start_matches('.');
+                entry == "*" || host == domain || host.ends_with(&format!(".{}", domain))
+            });
+        if exempt {
+            return None;
+        }
+    }
+    let scheme_proxy = if url.scheme() == "https" {
+        "HTTPS_PROXY"
+    } else {
+        "HTTP_PROXY"
+    };
+    let proxy = var(scheme_proxy).or_else(|| var("ALL_PROXY"))?;
+    let proxy = if proxy.contains("://") {
+        proxy
+    } else {
+        format!("http://{}", proxy)
+    };
+    Url::parse(&proxy).ok()
+}
+
+async fn probe(url: &Url, timeout: Duration) -> Connectivity {
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Connectivity::Online;
+    };
+    match tokio::time::timeout(timeout, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Connectivity::Online,
+        Ok(Err(e)) => Connectivity::Offline {
+            reason: format!("can't reach {}: {}", host, e),
+        },
+        Err(_) => Connectivity::Offline {
+            reason: format!("{} did not answer within {}s", host, timeout.as_secs()),
+        },
+    }
+}
+
+static LAST_PROBE: Lazy<Mutex<Option<(String, Instant, Connectivity)>>> =
+    Lazy::new(|| Mutex::new(None));
+
+/// Whether `provider_name` can be used, reusing a recent check of the same provider
+pub async fn check_provider(provider_name: &str) -> Connectivity {
+    let config = OfflineConfig::load();
+    if config.mode == OfflineMode::Off {
+        return Connectivity::Online;
+    }
+    let Some(endpoint) = provider_endpoint(provider_name) else {
+        // Providers without a host setting (CLI wrappers, cloud SDKs) can't be checked
+        return Connectivity::Online;
+    };
+    if is_local(&endpoint) {
+        return Connectivity::Local;
+    }
+    if config.mode == OfflineMode::On {
+        return Connectivity::Offline {
+            reason: format!(
+                "offline mode is on and {} is not a local provider",
+                provider_name
+            ),
+        };
+    }
+
+    let cached = LAST_PROBE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(name, at, _)| name == provider_name && at.elapsed() < PROBE_CACHE)
+        .map(|(_, _, status)| status.clone());
+    if let Some(status) = cached {
+        return status;
+    }
+    // Behind a proxy the provider's host may not be reachable directly, only the proxy is
+    let target = proxy_for(&endpoint, |name| std::env::var(name).ok()).unwrap_or(endpoint);
+    let status = probe(&target, Duration::from_secs(config.probe_timeout_seconds)).await;
+    *LAST_PROBE.lock().unwrap() = Some((provider_name.to_string(), Instant::now(), status.clone()));
+    status
+}
+
+/// Connectivity of the provider set in `GOOSE_PROVIDER`
+pub async fn check_configured_provider() -> Connectivity {
+    match Config::global().get_param::<String>("GOOSE_PROVIDER") {
+        Ok(provider_name) => check_provider(&provider_name).await,
+        Err(_) => Connectivity::Online,
+    }
+}
+
+/// Whether the most recent check found the provider unreachable, without checking again.
+/// Lets optional network work, like naming a session, be skipped instead of timing out.
+pub fn recently_offline() -> bool {
+    LAST_PROBE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|(_, at, status)| at.elapsed() < PROBE_CACHE && status.is_offline())
+}
+
+fn queue_path() -> Result<PathBuf> {
+    Ok(choose_app_strategy(APP_STRATEGY.clone())?
+        .data_dir()
+        .join("offline_queue.json"))
+}
+
+fn read_queue(path: &Path) -> BTreeSet<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write_queue(path: &Path, queue: &BTreeSet<String>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string(queue)?)?;
+    Ok(())
+}
+
+/// Remember that the scheduled job `job_id` came due while offline. A job is queued once
+/// however many runs it missed.
+pub fn queue_scheduled_run(job_id: &str) -> Result<()> {
+    let path = queue_path()?;
+    let mut queue = read_queue(&path);
+    if queue.insert(job_id.to_string()) {
+        write_queue(&path, &queue)?;
+    }
+    Ok(())
+}
+
+/// Ids of the jobs waiting for their provider to be reachable
+pub fn queued_runs() -> Result<Vec<String>> {
+    Ok(read_queue(&queue_path()?).into_iter().collect())
+}
+
+/// Take `job_ids` off the queue, leaving the other jobs waiting
+pub fn dequeue_runs(job_ids: &[String]) -> Result<()> {
+    let path = queue_path()?;
+    let mut queue = read_queue(&path);
+    let before = queue.len();
+    queue.retain(|job_id| !job_ids.contains(job_id));
+    if queue.len() != before {
+        write_queue(&path, &queue)?;
+    }
+    Ok(())
+}
+
+pub fn has_queued_runs() -> bool {
+    queue_path().is_ok_and(|path| !read_queue(&path).is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_hosts() {
+        let local = |url: &str| is_local(&Url::parse(url).unwrap());
+        assert!(local("http://localhost:11434"));
+        assert!(local("http://127.0.0.1:8080"));
+        assert!(local("http://[::1]:4000"));
+        assert!(!local("https://api.openai.com"));
+        assert!(!local("http://10.0.0.5:11434"));
+    }
+
+    #[test]
+    fn test_proxy_from_environment() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let openai = Url::parse("https://api.openai.com/v1").unwrap();
+        let proxy = |vars| proxy_for(&openai, env(vars)).map(|url| url.to_string());
+
+        assert_eq!(proxy(&[]), None);
+        assert_eq!(
+            proxy(&[("https_proxy", "proxy.corp:3128")]),
+            Some("http://proxy.corp:3128/".to_string())
+        );
+        assert_eq!(
+            proxy(&[
+                ("HTTP_PROXY", "http://plain:80"),
+                ("ALL_PROXY", "http://all:8080")
+            ]),
+            Some("http://all:8080/".to_string())
+        );
+        assert_eq!(
+            proxy(&[
+                ("HTTPS_PROXY", "http://proxy.corp:3128"),
+                ("NO_PROXY", "localhost, .openai.com")
+            ]),
+            None
+        );
+        assert_eq!(
+            proxy(&[
+                ("HTTPS_PROXY", "http://proxy.corp:3128"),
+                ("NO_PROXY", "notopenai.com")
+            ]),
+            Some("http://proxy.corp:3128/".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_host_fails_fast() {
+        // Port 9 (discard) on a reserved TEST-NET address never answers
+        let url = Url::parse("http://192.0.2.1:9").unwrap();
+        let started = Instant::now();
+        let status = probe(&url, Duration::from_millis(200)).await;
+        assert!(status.is_offline());
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(status.message().unwrap().contains("goose is offline"));
+    }
+}
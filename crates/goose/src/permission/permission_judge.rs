@@ -3,6 +3,7 @@ use crate::config::permission::PermissionLevel;
 use crate::config::PermissionManager;
 use crate::conversation::message::{Message, MessageContent, ToolRequest};
 use crate::conversation::Conversation;
+use crate::permission::risk::{auto_approved_categories, classify_tool_call};
 use crate::prompt_template::render_global_file;
 use crate::providers::base::Provider;
 use chrono::Utc;
@@ -176,6 +177,11 @@ pub async fn check_tool_permissions(
     let mut denied = vec![];
     let mut llm_detect_candidates = vec![];
     let mut extension_request_ids = vec![];
+    let auto_approved = if mode == "smart_approve" {
+        auto_approved_categories()
+    } else {
+        Vec::new()
+    };
 
     for request in candidate_requests {
         if let Ok(tool_call) = request.tool_call.clone() {
@@ -204,6 +210,17 @@ pub async fn check_tool_permissions(
                         needs_approval.push(request.clone());
                     }
                     "smart_approve" => {
+                        // Categories the user trusts run without asking, per call
+                        let risk = classify_tool_call(
+                            &tool_call.name,
+                            &tool_call.arguments,
+                            tools_with_readonly_annotation.contains(&tool_call.name),
+                        );
+                        if auto_approved.contains(&risk) {
+                            approved.push(request.clone());
+                            continue;
+                        }
+
                         if let Some(level) =
                             permission_manager.get_smart_approve_permission(&tool_call.name)
                         {
@@ -0,0 +1,390 @@
+use crate::config::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Config key holding the categories that smart approve runs without asking
+pub const AUTO_APPROVE_CATEGORIES_KEY: &str = "GOOSE_AUTO_APPROVE_CATEGORIES";
+
+/// How much damage a tool call could do, used to decide what can run without asking
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskCategory {
+    /// Only reads local state
+    ReadOnly,
+    /// Changes local state in a way that can be undone, like editing a file
+    ReversibleWrite,
+    /// Deletes data or makes changes that are hard to undo
+    Destructive,
+    /// Talks to the network
+    Network,
+}
+
+const DESTRUCTIVE_COMMANDS: &[&str] = &[
+    "rm ",
+    "rm\t",
+    "rmdir",
+    "shred",
+    "mkfs",
+    "dd ",
+    "truncate",
+    "git push",
+    "git reset --hard",
+    "git clean",
+    "git checkout --",
+    "drop table",
+    "drop database",
+    "kill ",
+    "pkill",
+    "chmod -r",
+    "chown -r",
+    "sudo ",
+    "-delete",
+    "-exec",
+    "-execdir",
+    "-ok ",
+    "-okdir",
+];
+
+const NETWORK_COMMANDS: &[&str] = &["curl ", "wget ", "ssh ", "scp ", "rsync ", "nc ", "ftp "];
+
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "ls",
+    "cat",
+    "head",
+    "tail",
+    "grep",
+    "rg",
+    "find",
+    "pwd",
+    "echo",
+    "wc",
+    "which",
+    "file",
+    "stat",
+    "tree",
+    "diff",
+    "git status",
+    "git log",
+    "git diff",
+    "git show",
+];
+
+const DESTRUCTIVE_NAME_HINTS: &[&str] = &["delete", "remove", "drop", "destroy", "kill", "purge"];
+const NETWORK_NAME_HINTS: &[&str] = &[
+    "fetch", "http", "web", "browser", "download", "upload", "request",
+];
+const READ_ONLY_NAME_HINTS: &[&str] = &["read", "list", "get", "search", "view", "show", "find"];
+/// Tools that only read, whose names don't say so
+const READ_ONLY_TOOLS: &[&str] = &["developer__ocr"];
+
+fn matches_any(haystack: &str, needles: &[&str]) -> bool {
+    needles.iter().any(|needle| haystack.contains(needle))
+}
+
+// Only match at the start of a word so "terraform " does not look like "rm "
+fn contains_command(command: &str, needles: &[&str]) -> bool {
+    needles.iter().any(|needle| {
+        command.match_indices(needle).any(|(i, _)| {
+            command[..i]
+                .chars()
+                .next_back()
+                .is_none_or(|c| c.is_whitespace() || matches!(c, ';' | '|' | '&' | '(' | '`'))
+        })
+    })
+}
+
+fn is_read_only_command(part: &str) -> bool {
+    let part = part.trim();
+    READ_ONLY_COMMANDS
+        .iter()
+        .any(|ro| part == *ro || part.starts_with(&format!("{} ", ro)))
+}
+
+// Whether a pipeline hands its output to a command that could do anything with it, e.g.
+// `cat urls | xargs rm` or `curl ... | sh`. `||` is a condition, not a pipe.
+fn pipes_into_writer(command: &str) -> bool {
+    command
+        .split(['\n', ';', '&'])
+        .flat_map(|list| list.split("||"))
+        .any(|pipeline| {
+            pipeline
+                .split('|')
+                .skip(1)
+                .any(|part| !is_read_only_command(part))
+        })
+}
+
+fn classify_shell_command(command: &str) -> RiskCategory {
+    let command = command.to_lowercase();
+    // Command substitution and redirection can run or overwrite anything, whatever the
+    // command around them looks like
+    let substitutes = command.contains("$(") || command.contains('`');
+    let redirects = command.contains('>') || command.contains("<(");
+    if contains_command(&command, DESTRUCTIVE_COMMANDS)
+        || substitutes
+        || redirects
+        || pipes_into_writer(&command)
+    {
+        RiskCategory::Destructive
+    } else if contains_command(&command, NETWORK_COMMANDS) || command.contains("://") {
+        RiskCategory::Network
+    } else if command
+        .split(['|', ';', '&', '\n'])
+        .filter(|part| !part.trim().is_empty())
+        .all(is_read_only_command)
+    {
+        RiskCategory::ReadOnly
+    } else {
+        RiskCategory::ReversibleWrite
+    }
+}
+
+/// Classify a tool call by risk.
+///
+/// `read_only_hint` is the tool's own read-only annotation, which wins when present. Shell
+/// commands and text editor calls are inspected directly; other tools are classified by name.
+pub fn classify_tool_call(
+    tool_name: &str,
+    arguments: &Value,
+    read_only_hint: bool,
+) -> RiskCategory {
+    if read_only_hint || READ_ONLY_TOOLS.contains(&tool_name) {
+        return RiskCategory::ReadOnly;
+    }
+
+    // Tool names are prefixed with their extension, e.g. developer__shell
+    let short_name = tool_name
+        .rsplit("__")
+        .next()
+        .unwrap_or(tool_name)
+        .to_lowercase();
+
+    if short_name == "shell" {
+        if let Some(command) = arguments.get("command").and_then(Value::as_str) {
+            return classify_shell_command(command);
+        }
+        return RiskCategory::Destructive;
+    }
+
+    // An interactive terminal runs whatever it is given, like the shell; only reading its
+    // output is safe
+    if short_name == "terminal" {
+        return match arguments.get("action").and_then(Value::as_str) {
+            Some("read") => RiskCategory::ReadOnly,
+            _ => RiskCategory::Destructive,
+        };
+    }
+
+    // A background process runs its command like the shell would; checking on it is safe
+    if short_name == "background_process" {
+        return match arguments.get("action").and_then(Value::as_str) {
+            Some("output") | Some("list") => RiskCategory::ReadOnly,
+            Some("start") => match arguments.get("command").and_then(Value::as_str) {
+                Some(command) => classify_shell_command(command),
+                None => RiskCategory::Destructive,
+            },
+            _ => RiskCategory::Destructive,
+        };
+    }
+
+    // Running tests runs the project's code and whatever its test scripts do, like the shell
+    if short_name == "run_tests" {
+        return RiskCategory::Destructive;
+    }
+
+    if short_name == "text_editor" {
+        return match arguments.get("command").and_then(Value::as_str) {
+            Some("view") => RiskCategory::ReadOnly,
+            _ => RiskCategory::ReversibleWrite,
+        };
+    }
+
+    if matches_any(&short_name, DESTRUCTIVE_NAME_HINTS) {
+        RiskCategory::Destructive
+    } else if matches_any(&short_name, NETWORK_NAME_HINTS) {
+        RiskCategory::Network
+    } else if matches_any(&short_name, READ_ONLY_NAME_HINTS) {
+        RiskCategory::ReadOnly
+    } else {
+        RiskCategory::ReversibleWrite
+    }
+}
+
+/// Categories the user allows smart approve to run without asking. Nothing is
+/// auto-approved by category unless configured.
+pub fn auto_approved_categories() -> Vec<RiskCategory> {
+    Config::global()
+        .get_param(AUTO_APPROVE_CATEGORIES_KEY)
+        .unwrap_or_default()
+}
+
+pub fn set_auto_approved_categories(categories: &[RiskCategory]) -> Result<(), ConfigError> {
+    Config::global().set_param(
+        AUTO_APPROVE_CATEGORIES_KEY,
+        serde_json::to_value(categories)?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_classify_shell_commands() {
+        let shell = |command: &str| {
+            classify_tool_call("developer__shell", &json!({ "command": command }), false)
+        };
+        assert_eq!(shell("ls -la"), RiskCategory::ReadOnly);
+        assert_eq!(shell("git status && git diff"), RiskCategory::ReadOnly);
+        assert_eq!(shell("rm -rf target"), RiskCategory::Destructive);
+        assert_eq!(shell("echo hi > notes.txt"), RiskCategory::Destructive);
+        assert_eq!(shell("curl https://example.com"), RiskCategory::Network);
+        assert_eq!(shell("cargo build"), RiskCategory::ReversibleWrite);
+        assert_eq!(
+            shell("git add . && terraform plan"),
+            RiskCategory::ReversibleWrite
+        );
+        assert_eq!(shell("ls | grep foo | wc -l"), RiskCategory::ReadOnly);
+        assert_eq!(shell("ls || echo none"), RiskCategory::ReadOnly);
+    }
+
+    #[test]
+    fn test_find_actions_are_destructive() {
+        let shell = |command: &str| {
+            classify_tool_call("developer__shell", &json!({ "command": command }), false)
+        };
+        assert_eq!(
+            shell("find . -name '*.log' -exec rm {} \\;"),
+            RiskCategory::Destructive
+        );
+        assert_eq!(
+            shell("find . -execdir shred -u {} +"),
+            RiskCategory::Destructive
+        );
+        assert_eq!(
+            shell("find /tmp -name x -delete"),
+            RiskCategory::Destructive
+        );
+        assert_eq!(shell("find . -name '*.rs'"), RiskCategory::ReadOnly);
+    }
+
+    #[test]
+    fn test_command_substitution_is_destructive() {
+        let shell = |command: &str| {
+            classify_tool_call("developer__shell", &json!({ "command": command }), false)
+        };
+        assert_eq!(shell("echo $(rm -rf ~)"), RiskCategory::Destructive);
+        assert_eq!(shell("ls `make clean`"), RiskCategory::Destructive);
+    }
+
+    #[test]
+    fn test_redirections_are_destructive() {
+        let shell = |command: &str| {
+            classify_tool_call("developer__shell", &json!({ "command": command }), false)
+        };
+        assert_eq!(shell("cat a >> b"), RiskCategory::Destructive);
+        assert_eq!(shell("grep x file 2>/dev/null"), RiskCategory::Destructive);
+        assert_eq!(shell("diff <(ls a) <(ls b)"), RiskCategory::Destructive);
+    }
+
+    #[test]
+    fn test_pipes_into_writers_are_destructive() {
+        let shell = |command: &str| {
+            classify_tool_call("developer__shell", &json!({ "command": command }), false)
+        };
+        assert_eq!(
+            shell("cat files.txt | xargs touch"),
+            RiskCategory::Destructive
+        );
+        assert_eq!(shell("echo 'drop' | sh"), RiskCategory::Destructive);
+        assert_eq!(shell("ls | tee listing.txt"), RiskCategory::Destructive);
+    }
+
+    #[test]
+    fn test_classify_by_name_and_hint() {
+        assert_eq!(
+            classify_tool_call("developer__text_editor", &json!({"command": "view"}), false),
+            RiskCategory::ReadOnly
+        );
+        assert_eq!(
+            classify_tool_call(
+                "developer__text_editor",
+                &json!({"command": "write"}),
+                false
+            ),
+            RiskCategory::ReversibleWrite
+        );
+        assert_eq!(
+            classify_tool_call("github__delete_branch", &json!({}), false),
+            RiskCategory::Destructive
+        );
+        assert_eq!(
+            classify_tool_call("computercontroller__web_scrape", &json!({}), false),
+            RiskCategory::Network
+        );
+        assert_eq!(
+            classify_tool_call("github__delete_branch", &json!({}), true),
+            RiskCategory::ReadOnly
+        );
+    }
+
+    #[test]
+    fn test_classify_terminal() {
+        let terminal =
+            |arguments: Value| classify_tool_call("developer__terminal", &arguments, false);
+        assert_eq!(
+            terminal(json!({"action": "start", "command": "ls"})),
+            RiskCategory::Destructive
+        );
+        assert_eq!(
+            terminal(json!({"action": "send", "session_id": "1", "input": "y\n"})),
+            RiskCategory::Destructive
+        );
+        assert_eq!(
+            terminal(json!({"action": "read", "session_id": "1"})),
+            RiskCategory::ReadOnly
+        );
+    }
+
+    #[test]
+    fn test_classify_background_process() {
+        let process = |arguments: Value| {
+            classify_tool_call("developer__background_process", &arguments, false)
+        };
+        assert_eq!(
+            process(json!({"action": "start", "command": "rm -rf build && npm run dev"})),
+            RiskCategory::Destructive
+        );
+        assert_eq!(
+            process(json!({"action": "start", "command": "tail -f server.log"})),
+            classify_shell_command("tail -f server.log")
+        );
+        assert_eq!(
+            process(json!({"action": "stop", "process_id": "proc-1"})),
+            RiskCategory::Destructive
+        );
+        assert_eq!(
+            process(json!({"action": "output", "process_id": "proc-1"})),
+            RiskCategory::ReadOnly
+        );
+        assert_eq!(process(json!({"action": "list"})), RiskCategory::ReadOnly);
+    }
+
+    #[test]
+    fn test_classify_ocr() {
+        assert_eq!(
+            classify_tool_call("developer__ocr", &json!({"path": "/tmp/scan.png"}), false),
+            RiskCategory::ReadOnly
+        );
+    }
+
+    #[test]
+    fn test_classify_run_tests() {
+        assert_eq!(
+            classify_tool_call("developer__run_tests", &json!({"filter": "cart"}), false),
+            RiskCategory::Destructive
+        );
+    }
+}
@@ -340,6 +340,12 @@ pub trait Provider: Send + Sync {
     /// Get the model config from the provider
     fn get_model_config(&self) -> ModelConfig;
 
+    /// The name the provider was created under, e.g. `openai`. Only providers made by
+    /// [`create`](super::create) know it.
+    fn get_provider_name(&self) -> Option<String> {
+        None
+    }
+
     fn retry_config(&self) -> RetryConfig {
         RetryConfig::default()
     }
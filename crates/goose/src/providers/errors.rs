@@ -28,6 +28,22 @@ pub enum ProviderError {
     NotImplemented(String),
 }
 
+impl ProviderError {
+    /// Coarse category without any of the error detail, for usage analytics
+    pub fn category(&self) -> &'static str {
+        match self {
+            ProviderError::Authentication(_) => "authentication",
+            ProviderError::ContextLengthExceeded(_) => "context_length_exceeded",
+            ProviderError::RateLimitExceeded(_) => "rate_limit",
+            ProviderError::ServerError(_) => "server_error",
+            ProviderError::RequestFailed(_) => "request_failed",
+            ProviderError::ExecutionError(_) => "execution_error",
+            ProviderError::UsageError(_) => "usage_error",
+            ProviderError::NotImplemented(_) => "not_implemented",
+        }
+    }
+}
+
 impl From<anyhow::Error> for ProviderError {
     fn from(error: anyhow::Error) -> Self {
         if let Some(reqwest_err) = error.downcast_ref::<reqwest::Error>() {
@@ -19,6 +19,7 @@ use super::{
     openrouter::OpenRouterProvider,
     provider_registry::ProviderRegistry,
     sagemaker_tgi::SageMakerTgiProvider,
+    secret_scanner::SecretScanProvider,
     snowflake::SnowflakeProvider,
     venice::VeniceProvider,
     xai::XaiProvider,
@@ -87,15 +88,17 @@ pub fn refresh_custom_providers() -> Result<()> {
     Ok(())
 }
 
+/// Create a provider by name, checking what is sent to it for secrets
 pub fn create(name: &str, model: ModelConfig) -> Result<Arc<dyn Provider>> {
     let config = crate::config::Config::global();
 
-    if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
+    let provider = if let Ok(lead_model_name) = config.get_param::<String>("GOOSE_LEAD_MODEL") {
         tracing::info!("Creating lead/worker provider from environment variables");
-        return create_lead_worker_from_env(name, &model, &lead_model_name);
-    }
-
-    REGISTRY.read().unwrap().create(name, model)
+        create_lead_worker_from_env(name, &model, &lead_model_name)?
+    } else {
+        REGISTRY.read().unwrap().create(name, model)?
+    };
+    Ok(SecretScanProvider::wrap(name, provider))
 }
 
 fn create_lead_worker_from_env(
@@ -540,6 +540,7 @@ where
                         role: Role::Assistant,
                         created: chrono::Utc::now().timestamp(),
                         content: contents,
+                        usage: None,
                     }),
                     usage,
                 )
@@ -550,6 +551,7 @@ where
                         role: Role::Assistant,
                         created: chrono::Utc::now().timestamp(),
                         content: vec![MessageContent::text(text)],
+                        usage: None,
                     }),
                     if chunk.choices[0].finish_reason.is_some() {
                         usage
@@ -646,6 +648,13 @@ pub fn create_request(
         }
     }
 
+    if let Some(seed) = model_config.seed {
+        payload
+            .as_object_mut()
+            .unwrap()
+            .insert("seed".to_string(), json!(seed));
+    }
+
     // o1 models use max_completion_tokens instead of max_tokens
     if let Some(tokens) = model_config.max_tokens {
         let key = if is_ox_model {
@@ -1077,6 +1086,7 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            seed: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1098,6 +1108,18 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_create_request_with_seed() -> anyhow::Result<()> {
+        let model_config = ModelConfig::new_or_fail("gpt-4o").with_seed(Some(42));
+        let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
+        assert_eq!(request.get("seed"), Some(&json!(42)));
+
+        let model_config = model_config.with_seed(None);
+        let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
+        assert!(request.get("seed").is_none());
+        Ok(())
+    }
+
     #[test]
     fn test_create_request_o1_default() -> anyhow::Result<()> {
         // Test default medium reasoning effort for O1 model
@@ -1108,6 +1130,7 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            seed: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
@@ -1140,6 +1163,7 @@ mod tests {
             max_tokens: Some(1024),
             toolshim: false,
             toolshim_model: None,
+            seed: None,
         };
         let request = create_request(&model_config, "system", &[], &[], &ImageFormat::OpenAi)?;
         let obj = request.as_object().unwrap();
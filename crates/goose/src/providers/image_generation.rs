@@ -0,0 +1,456 @@
+use super::api_client::{ApiClient, AuthMethod};
+use super::errors::ProviderError;
+use super::ollama::NoAuth;
+use super::utils::map_http_error_to_provider_error;
+use crate::config::Config;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Which image generation backend to use: `openai`, `stability` or `local`
+pub const IMAGE_PROVIDER_CONFIG_KEY: &str = "GOOSE_IMAGE_PROVIDER";
+/// Model or engine for the image provider, when it has a choice
+pub const IMAGE_MODEL_CONFIG_KEY: &str = "GOOSE_IMAGE_MODEL";
+/// Host of a local Stable Diffusion web UI started with `--api`
+pub const LOCAL_IMAGE_HOST_CONFIG_KEY: &str = "GOOSE_IMAGE_LOCAL_HOST";
+
+const IMAGE_TIMEOUT: Duration = Duration::from_secs(300);
+pub const MAX_IMAGES_PER_REQUEST: u32 = 4;
+const MIN_IMAGE_SIZE: u32 = 64;
+const MAX_IMAGE_SIZE: u32 = 2048;
+
+/// Sizes the OpenAI models accept, which reject anything else
+const DALL_E_2_SIZES: &[(u32, u32)] = &[(256, 256), (512, 512), (1024, 1024)];
+const DALL_E_3_SIZES: &[(u32, u32)] = &[(1024, 1024), (1792, 1024), (1024, 1792)];
+const GPT_IMAGE_SIZES: &[(u32, u32)] = &[(1024, 1024), (1536, 1024), (1024, 1536)];
+/// Sizes the SDXL engines accept
+const SDXL_SIZES: &[(u32, u32)] = &[
+    (1024, 1024),
+    (1152, 896),
+    (896, 1152),
+    (1216, 832),
+    (832, 1216),
+    (1344, 768),
+    (768, 1344),
+    (1536, 640),
+    (640, 1536),
+];
+
+/// The size in `sizes` with the aspect ratio closest to `width`x`height`, and of those the one
+/// closest in area
+fn closest_size(sizes: &[(u32, u32)], width: u32, height: u32) -> (u32, u32) {
+    let ratio = (width.max(1) as f64 / height.max(1) as f64).ln();
+    let area = width as f64 * height as f64;
+    let distance = |&(w, h): &(u32, u32)| {
+        let ratio_distance = ((w as f64 / h as f64).ln() - ratio).abs();
+        let area_distance = ((w as f64 * h as f64) - area).abs();
+        (ratio_distance, area_distance)
+    };
+    sizes
+        .iter()
+        .copied()
+        .min_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap())
+        .unwrap_or((width, height))
+}
+
+/// `size` clamped to what a Stable Diffusion model can make, in steps of `step` pixels
+fn free_size(size: u32, step: u32) -> u32 {
+    (size.clamp(MIN_IMAGE_SIZE, MAX_IMAGE_SIZE) / step) * step
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageRequest {
+    pub prompt: String,
+    pub width: u32,
+    pub height: u32,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratedImage {
+    /// Encoded image, PNG for every provider
+    pub data: Vec<u8>,
+    pub mime_type: String,
+    /// The prompt the provider actually used, when it rewrote the one it was given
+    pub revised_prompt: Option<String>,
+}
+
+/// A service that turns text prompts into images
+#[async_trait]
+pub trait ImageGenerationProvider: Send + Sync {
+    /// Name shown to the user, such as `openai`
+    fn name(&self) -> &str;
+
+    /// The size this provider makes when asked for `width`x`height`, since most only make a few
+    fn image_size(&self, width: u32, height: u32) -> (u32, u32) {
+        (free_size(width, 64), free_size(height, 64))
+    }
+
+    /// How many images one request may ask for
+    fn max_images(&self) -> u32 {
+        MAX_IMAGES_PER_REQUEST
+    }
+
+    async fn generate_images(
+        &self,
+        request: &ImageRequest,
+    ) -> Result<Vec<GeneratedImage>, ProviderError>;
+}
+
+fn decode_png(encoded: &str) -> Result<GeneratedImage, ProviderError> {
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| ProviderError::RequestFailed(format!("Image was not valid base64: {}", e)))?;
+    Ok(GeneratedImage {
+        data,
+        mime_type: "image/png".to_string(),
+        revised_prompt: None,
+    })
+}
+
+async fn post(client: &ApiClient, path: &str, payload: &Value) -> Result<Value, ProviderError> {
+    let response = client
+        .api_post(path, payload)
+        .await
+        .map_err(|e| ProviderError::RequestFailed(e.to_string()))?;
+    if !response.status.is_success() {
+        return Err(map_http_error_to_provider_error(
+            response.status,
+            response.payload,
+        ));
+    }
+    response
+        .payload
+        .ok_or_else(|| ProviderError::RequestFailed("Empty response".to_string()))
+}
+
+/// OpenAI's images API
+pub struct OpenAiImageProvider {
+    api_client: ApiClient,
+    model: String,
+}
+
+impl OpenAiImageProvider {
+    pub const DEFAULT_MODEL: &'static str = "gpt-image-1";
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let api_key: String = config.get_secret("OPENAI_API_KEY")?;
+        let host: String = config
+            .get_param("OPENAI_HOST")
+            .unwrap_or_else(|_| "https://api.openai.com".to_string());
+        let model = config
+            .get_param(IMAGE_MODEL_CONFIG_KEY)
+            .unwrap_or_else(|_| Self::DEFAULT_MODEL.to_string());
+        let api_client =
+            ApiClient::with_timeout(host, AuthMethod::BearerToken(api_key), IMAGE_TIMEOUT)?;
+        Ok(Self { api_client, model })
+    }
+
+    fn payload(&self, request: &ImageRequest) -> Value {
+        let mut payload = json!({
+            "model": self.model,
+            "prompt": request.prompt,
+            "n": request.count,
+            "size": format!("{}x{}", request.width, request.height),
+        });
+        // gpt-image models always return base64 and reject the parameter
+        if self.model.starts_with("dall-e") {
+            payload["response_format"] = json!("b64_json");
+        }
+        payload
+    }
+}
+
+#[async_trait]
+impl ImageGenerationProvider for OpenAiImageProvider {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn image_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let sizes = match self.model.as_str() {
+            "dall-e-2" => DALL_E_2_SIZES,
+            "dall-e-3" => DALL_E_3_SIZES,
+            _ => GPT_IMAGE_SIZES,
+        };
+        closest_size(sizes, width, height)
+    }
+
+    fn max_images(&self) -> u32 {
+        // dall-e-3 makes one image per request and fails on anything more
+        if self.model == "dall-e-3" {
+            1
+        } else {
+            MAX_IMAGES_PER_REQUEST
+        }
+    }
+
+    async fn generate_images(
+        &self,
+        request: &ImageRequest,
+    ) -> Result<Vec<GeneratedImage>, ProviderError> {
+        let response = post(
+            &self.api_client,
+            "v1/images/generations",
+            &self.payload(request),
+        )
+        .await?;
+        response["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|image| {
+                let encoded = image["b64_json"].as_str().ok_or_else(|| {
+                    ProviderError::RequestFailed("Response had no image data".to_string())
+                })?;
+                Ok(GeneratedImage {
+                    revised_prompt: image["revised_prompt"].as_str().map(String::from),
+                    ..decode_png(encoded)?
+                })
+            })
+            .collect()
+    }
+}
+
+/// Stability AI's text-to-image API
+pub struct StabilityImageProvider {
+    api_client: ApiClient,
+    engine: String,
+}
+
+impl StabilityImageProvider {
+    pub const DEFAULT_ENGINE: &'static str = "stable-diffusion-xl-1024-v1-0";
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let api_key: String = config.get_secret("STABILITY_API_KEY")?;
+        let host: String = config
+            .get_param("STABILITY_HOST")
+            .unwrap_or_else(|_| "https://api.stability.ai".to_string());
+        let engine = config
+            .get_param(IMAGE_MODEL_CONFIG_KEY)
+            .unwrap_or_else(|_| Self::DEFAULT_ENGINE.to_string());
+        let api_client =
+            ApiClient::with_timeout(host, AuthMethod::BearerToken(api_key), IMAGE_TIMEOUT)?
+                .with_header("Accept", "application/json")?;
+        Ok(Self { api_client, engine })
+    }
+}
+
+#[async_trait]
+impl ImageGenerationProvider for StabilityImageProvider {
+    fn name(&self) -> &str {
+        "stability"
+    }
+
+    fn image_size(&self, width: u32, height: u32) -> (u32, u32) {
+        if self.engine.contains("xl") {
+            closest_size(SDXL_SIZES, width, height)
+        } else {
+            (free_size(width, 64), free_size(height, 64))
+        }
+    }
+
+    async fn generate_images(
+        &self,
+        request: &ImageRequest,
+    ) -> Result<Vec<GeneratedImage>, ProviderError> {
+        let path = format!("v1/generation/{}/text-to-image", self.engine);
+        let payload = json!({
+            "text_prompts": [{ "text": request.prompt }],
+            "width": request.width,
+            "height": request.height,
+            "samples": request.count,
+        });
+        let response = post(&self.api_client, &path, &payload).await?;
+        response["artifacts"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|artifact| artifact["base64"].as_str())
+            .map(decode_png)
+            .collect()
+    }
+}
+
+/// A local Stable Diffusion web UI (AUTOMATIC1111 or a compatible fork) run with `--api`
+pub struct LocalImageProvider {
+    api_client: ApiClient,
+}
+
+impl LocalImageProvider {
+    pub const DEFAULT_HOST: &'static str = "http://127.0.0.1:7860";
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let host: String = config
+            .get_param(LOCAL_IMAGE_HOST_CONFIG_KEY)
+            .unwrap_or_else(|_| Self::DEFAULT_HOST.to_string());
+        let api_client =
+            ApiClient::with_timeout(host, AuthMethod::Custom(Box::new(NoAuth)), IMAGE_TIMEOUT)?;
+        Ok(Self { api_client })
+    }
+}
+
+#[async_trait]
+impl ImageGenerationProvider for LocalImageProvider {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn image_size(&self, width: u32, height: u32) -> (u32, u32) {
+        (free_size(width, 8), free_size(height, 8))
+    }
+
+    async fn generate_images(
+        &self,
+        request: &ImageRequest,
+    ) -> Result<Vec<GeneratedImage>, ProviderError> {
+        let payload = json!({
+            "prompt": request.prompt,
+            "width": request.width,
+            "height": request.height,
+            "batch_size": request.count,
+        });
+        let response = post(&self.api_client, "sdapi/v1/txt2img", &payload).await?;
+        response["images"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|image| image.as_str())
+            .map(decode_png)
+            .collect()
+    }
+}
+
+/// The image provider named by `GOOSE_IMAGE_PROVIDER`, or `None` when it isn't set
+pub fn configured_image_provider(
+    config: &Config,
+) -> Result<Option<Box<dyn ImageGenerationProvider>>> {
+    let Ok(name) = config.get_param::<String>(IMAGE_PROVIDER_CONFIG_KEY) else {
+        return Ok(None);
+    };
+    let provider: Box<dyn ImageGenerationProvider> = match name.as_str() {
+        "openai" => Box::new(OpenAiImageProvider::from_config(config)?),
+        "stability" => Box::new(StabilityImageProvider::from_config(config)?),
+        "local" => Box::new(LocalImageProvider::from_config(config)?),
+        other => {
+            return Err(anyhow!(
+                "Unknown image provider '{}', expected openai, stability or local",
+                other
+            ))
+        }
+    };
+    Ok(Some(provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn request() -> ImageRequest {
+        ImageRequest {
+            prompt: "a lighthouse at dusk".to_string(),
+            width: 1024,
+            height: 1024,
+            count: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openai_images_are_decoded() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/images/generations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{ "b64_json": "iVBORw0K", "revised_prompt": "a red lighthouse" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = OpenAiImageProvider {
+            api_client: ApiClient::with_timeout(
+                server.uri(),
+                AuthMethod::BearerToken("key".to_string()),
+                IMAGE_TIMEOUT,
+            )
+            .unwrap(),
+            model: OpenAiImageProvider::DEFAULT_MODEL.to_string(),
+        };
+        let images = provider.generate_images(&request()).await.unwrap();
+        assert_eq!(images.len(), 1);
+        assert!(images[0].data.starts_with(b"\x89PNG"));
+        assert_eq!(
+            images[0].revised_prompt.as_deref(),
+            Some("a red lighthouse")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_errors_are_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/sdapi/v1/txt2img"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let provider = LocalImageProvider {
+            api_client: ApiClient::with_timeout(
+                server.uri(),
+                AuthMethod::Custom(Box::new(NoAuth)),
+                IMAGE_TIMEOUT,
+            )
+            .unwrap(),
+        };
+        assert!(matches!(
+            provider.generate_images(&request()).await,
+            Err(ProviderError::ServerError(_))
+        ));
+    }
+
+    fn openai(model: &str) -> OpenAiImageProvider {
+        OpenAiImageProvider {
+            api_client: ApiClient::new(
+                "https://api.openai.com".to_string(),
+                AuthMethod::BearerToken("key".to_string()),
+            )
+            .unwrap(),
+            model: model.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_dall_e_asks_for_base64() {
+        assert_eq!(
+            openai("dall-e-3").payload(&request())["response_format"],
+            "b64_json"
+        );
+    }
+
+    #[test]
+    fn test_sizes_map_to_what_the_model_supports() {
+        assert_eq!(openai("dall-e-3").image_size(1920, 1080), (1792, 1024));
+        assert_eq!(openai("dall-e-3").image_size(512, 512), (1024, 1024));
+        assert_eq!(openai("dall-e-2").image_size(300, 300), (256, 256));
+        assert_eq!(openai("gpt-image-1").image_size(800, 1200), (1024, 1536));
+
+        let local = LocalImageProvider {
+            api_client: ApiClient::new(
+                LocalImageProvider::DEFAULT_HOST.to_string(),
+                AuthMethod::Custom(Box::new(NoAuth)),
+            )
+            .unwrap(),
+        };
+        assert_eq!(local.image_size(10, 5000), (64, 2048));
+        assert_eq!(local.image_size(700, 515), (696, 512));
+    }
+
+    #[test]
+    fn test_dall_e_3_makes_one_image_per_request() {
+        assert_eq!(openai("dall-e-3").max_images(), 1);
+        assert_eq!(openai("gpt-image-1").max_images(), MAX_IMAGES_PER_REQUEST);
+    }
+}
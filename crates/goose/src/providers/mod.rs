@@ -17,6 +17,7 @@ pub mod gemini_cli;
 pub mod githubcopilot;
 pub mod google;
 pub mod groq;
+pub mod image_generation;
 pub mod lead_worker;
 pub mod litellm;
 pub mod oauth;
@@ -27,6 +28,7 @@ pub mod pricing;
 pub mod provider_registry;
 mod retry;
 pub mod sagemaker_tgi;
+pub mod secret_scanner;
 pub mod snowflake;
 pub mod testprovider;
 pub mod toolshim;
@@ -127,8 +127,8 @@ impl OllamaProvider {
     }
 }
 
-// No authentication provider for Ollama
-struct NoAuth;
+// No authentication provider for Ollama and other local servers
+pub(super) struct NoAuth;
 
 #[async_trait]
 impl super::api_client::AuthProvider for NoAuth {
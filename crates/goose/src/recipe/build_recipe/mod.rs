@@ -1,4 +1,5 @@
 use crate::recipe::read_recipe_file_content::RecipeFile;
+use crate::recipe::scaffold;
 use crate::recipe::template_recipe::{parse_recipe_content, render_recipe_content_with_params};
 use crate::recipe::{
     Recipe, RecipeParameter, RecipeParameterRequirement, BUILT_IN_RECIPE_DIR_PARAM,
@@ -22,6 +23,20 @@ pub fn render_recipe_template<F>(
     params: Vec<(String, String)>,
     user_prompt_fn: Option<F>,
 ) -> Result<(String, Vec<String>)>
+where
+    F: Fn(&str, &str) -> Result<String, anyhow::Error>,
+{
+    let (rendered_content, missing_params, _) =
+        render_recipe_template_with_params(recipe_file, params, user_prompt_fn)?;
+    Ok((rendered_content, missing_params))
+}
+
+/// Like `render_recipe_template`, also returning the parameter values it was rendered with
+fn render_recipe_template_with_params<F>(
+    recipe_file: RecipeFile,
+    params: Vec<(String, String)>,
+    user_prompt_fn: Option<F>,
+) -> Result<(String, Vec<String>, HashMap<String, String>)>
 where
     F: Fn(&str, &str) -> Result<String, anyhow::Error>,
 {
@@ -44,15 +59,21 @@ where
         String::new()
     };
 
-    Ok((rendered_content, missing_params))
+    Ok((rendered_content, missing_params, params_for_template))
 }
 
 pub fn validate_recipe_parameters(
     recipe_file_content: &str,
     recipe_dir_str: &str,
 ) -> Result<Option<Vec<RecipeParameter>>> {
-    let (raw_recipe, template_variables) =
+    let (raw_recipe, mut template_variables) =
         parse_recipe_content(recipe_file_content, recipe_dir_str.to_string())?;
+    if let Some(scaffold) = &raw_recipe.scaffold {
+        template_variables.extend(scaffold::template_variables(
+            scaffold,
+            Path::new(recipe_dir_str),
+        )?);
+    }
     let recipe_parameters = raw_recipe.parameters;
     validate_optional_parameters(&recipe_parameters)?;
     validate_parameters_in_template(&recipe_parameters, &template_variables)?;
@@ -68,8 +89,8 @@ where
     F: Fn(&str, &str) -> Result<String, anyhow::Error>,
 {
     let recipe_parent_dir = recipe_file.parent_dir.clone();
-    let (rendered_content, missing_params) =
-        render_recipe_template(recipe_file, params.clone(), user_prompt_fn)
+    let (rendered_content, missing_params, params_for_template) =
+        render_recipe_template_with_params(recipe_file, params.clone(), user_prompt_fn)
             .map_err(|source| RecipeError::TemplateRendering { source })?;
 
     if !missing_params.is_empty() {
@@ -90,6 +111,11 @@ where
         }
     }
 
+    if let Some(ref mut scaffold) = recipe.scaffold {
+        scaffold::expand_sources(scaffold, &recipe_parent_dir, &params_for_template)
+            .map_err(|source| RecipeError::TemplateRendering { source })?;
+    }
+
     Ok(recipe)
 }
 
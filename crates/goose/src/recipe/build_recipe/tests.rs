@@ -351,6 +351,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_recipe_expands_scaffold_sources() {
+        let instructions_and_parameters = r##"
+                "instructions": "Start the {{ service }} service",
+                "scaffold": {
+                    "destination": "{{ service }}",
+                    "files": [
+                        { "path": "README.md", "content": "# {{ service }}" },
+                        { "path": "src/main.rs", "source": "main.rs" }
+                    ]
+                },
+                "parameters": [
+                    {
+                        "key": "service",
+                        "input_type": "string",
+                        "requirement": "required",
+                        "description": "Name of the service"
+                    },
+                    {
+                        "key": "port",
+                        "input_type": "number",
+                        "requirement": "optional",
+                        "description": "Port the service listens on",
+                        "default": "8080"
+                    }
+                ]"##;
+        let (temp_dir, recipe_file) = setup_recipe_file(instructions_and_parameters);
+        std::fs::write(
+            temp_dir.path().join("main.rs"),
+            "// listens on {{ port }}\n",
+        )
+        .unwrap();
+
+        let params = vec![("service".to_string(), "billing".to_string())];
+        let recipe = build_recipe_from_template(recipe_file, params, NO_USER_PROMPT).unwrap();
+
+        let scaffold = recipe.scaffold.unwrap();
+        assert_eq!(scaffold.destination.as_deref(), Some("billing"));
+        assert_eq!(scaffold.files[0].content.as_deref(), Some("# billing"));
+        assert_eq!(scaffold.files[1].path, "src/main.rs");
+        assert_eq!(
+            scaffold.files[1].content.as_deref(),
+            Some("// listens on 8080\n")
+        );
+        assert!(scaffold.files[1].source.is_none());
+    }
+
     mod sub_recipe_path_resolution {
         use super::*;
 
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::fmt;
 
 use crate::agents::extension::ExtensionConfig;
-use crate::agents::types::RetryConfig;
+use crate::agents::types::{CritiqueConfig, RetryConfig, RunLimits};
 use crate::utils::contains_unicode_tags;
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
@@ -12,6 +12,7 @@ use utoipa::ToSchema;
 
 pub mod build_recipe;
 pub mod read_recipe_file_content;
+pub mod scaffold;
 pub mod template_recipe;
 
 pub const BUILT_IN_RECIPE_DIR_PARAM: &str = "recipe_dir";
@@ -40,6 +41,7 @@ fn default_version() -> String {
 /// * `parameters` - Additional parameters for the Recipe
 /// * `response` - Response configuration including JSON schema validation
 /// * `retry` - Retry configuration for automated validation and recovery
+/// * `scaffold` - Templated file tree created in the working directory before the run
 /// # Example
 ///
 ///
@@ -69,6 +71,9 @@ fn default_version() -> String {
 ///     response: None,
 ///     sub_recipes: None,
 ///     retry: None,
+///     critique: None,
+///     artifacts: None,
+///     scaffold: None,
 /// };
 ///
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -115,6 +120,15 @@ pub struct Recipe {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub critique: Option<CritiqueConfig>, // review of the final answer before a run ends
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<Vec<Artifact>>, // files the run produces, kept once it finishes
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scaffold: Option<Scaffold>, // files created before the run starts
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -136,6 +150,85 @@ pub struct Settings {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+
+    /// Turns allowed per run without user input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+
+    /// Wall-clock time allowed per run, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duration_seconds: Option<u64>,
+
+    /// Tool calls allowed per run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tool_calls: Option<u32>,
+
+    /// Style preset for the recipe's sessions, e.g. `terse`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+}
+
+impl Settings {
+    /// The time and tool call limits for each of the recipe's runs
+    pub fn run_limits(&self) -> RunLimits {
+        RunLimits {
+            max_duration_seconds: self.max_duration_seconds,
+            max_tool_calls: self.max_tool_calls,
+            max_tokens: None,
+        }
+    }
+}
+
+/// A file a run leaves behind, collected into the run's session when it finishes
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct Artifact {
+    /// Relative to the run's working directory. A `*` in the file name matches any characters,
+    /// as in `reports/*.pdf`.
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A tree of files the recipe creates in the working directory before its run starts, such as a
+/// new service laid out from a team's template
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct Scaffold {
+    /// Directory the files are created in, relative to the working directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(default)]
+    pub on_conflict: ScaffoldConflict,
+    pub files: Vec<ScaffoldFile>,
+}
+
+/// A file of a scaffold, or a whole directory of them when `source` names one. Paths, inline
+/// content and the text files read from `source` are all templates over the recipe's
+/// parameters; wrap text that has to keep its braces, such as `${{ secrets.TOKEN }}` in a CI
+/// workflow, in `{% raw %}...{% endraw %}`. Files that are not UTF-8 text are copied as they are.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct ScaffoldFile {
+    /// Relative to the scaffold's destination; the directory to copy into when `source` is one
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// File or directory to copy, relative to the recipe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Content of a file read from `source` that is not text, written without rendering
+    #[serde(skip)]
+    pub binary_content: Option<Vec<u8>>,
+}
+
+/// What to do when a scaffold file already exists
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScaffoldConflict {
+    /// Create nothing and report the files that are in the way
+    #[default]
+    Fail,
+    /// Keep the existing files and create the rest
+    Skip,
+    Overwrite,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
@@ -251,6 +344,9 @@ pub struct RecipeBuilder {
     response: Option<Response>,
     sub_recipes: Option<Vec<SubRecipe>>,
     retry: Option<RetryConfig>,
+    critique: Option<CritiqueConfig>,
+    artifacts: Option<Vec<Artifact>>,
+    scaffold: Option<Scaffold>,
 }
 
 impl Recipe {
@@ -303,6 +399,9 @@ impl Recipe {
             response: None,
             sub_recipes: None,
             retry: None,
+            critique: None,
+            artifacts: None,
+            scaffold: None,
         }
     }
     pub fn from_content(content: &str) -> Result<Self> {
@@ -419,6 +518,24 @@ impl RecipeBuilder {
         self
     }
 
+    /// Sets the critique of the final answer for the Recipe
+    pub fn critique(mut self, critique: CritiqueConfig) -> Self {
+        self.critique = Some(critique);
+        self
+    }
+
+    /// Sets the files collected from a run of the Recipe
+    pub fn artifacts(mut self, artifacts: Vec<Artifact>) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
+    /// Sets the files created before a run of the Recipe starts
+    pub fn scaffold(mut self, scaffold: Scaffold) -> Self {
+        self.scaffold = Some(scaffold);
+        self
+    }
+
     /// Builds the Recipe instance
     ///
     /// Returns an error if any required fields are missing
@@ -445,6 +562,9 @@ impl RecipeBuilder {
             response: self.response,
             sub_recipes: self.sub_recipes,
             retry: self.retry,
+            critique: self.critique,
+            artifacts: self.artifacts,
+            scaffold: self.scaffold,
         })
     }
 }
@@ -737,6 +857,32 @@ sub_recipes:
         assert_eq!(activities, vec!["activity1", "activity2"]);
     }
 
+    #[test]
+    fn test_from_content_with_run_limits() {
+        let content = r#"
+version: 1.0.0
+title: Nightly triage
+description: Triage new issues
+instructions: Label new issues
+settings:
+  max_turns: 20
+  max_duration_seconds: 600
+  max_tool_calls: 50
+"#;
+
+        let recipe = Recipe::from_content(content).unwrap();
+        let settings = recipe.settings.unwrap();
+        assert_eq!(settings.max_turns, Some(20));
+        assert_eq!(
+            settings.run_limits(),
+            RunLimits {
+                max_duration_seconds: Some(600),
+                max_tool_calls: Some(50),
+                max_tokens: None,
+            }
+        );
+    }
+
     #[test]
     fn test_from_content_with_nested_recipe_yaml() {
         let content = r#"name: test_recipe
@@ -784,6 +930,9 @@ isGlobal: true"#;
             response: None,
             sub_recipes: None,
             retry: None,
+            critique: None,
+            artifacts: None,
+            scaffold: None,
         };
 
         assert!(!recipe.check_for_security_warnings());
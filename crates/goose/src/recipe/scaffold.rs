@@ -0,0 +1,448 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use minijinja::{Environment, UndefinedBehavior};
+
+use crate::recipe::{Scaffold, ScaffoldConflict, ScaffoldFile};
+
+/// What creating a scaffold did, with paths relative to its root
+#[derive(Debug, Default)]
+pub struct ScaffoldReport {
+    pub root: PathBuf,
+    pub created: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl ScaffoldReport {
+    /// What was created, for the agent to carry on from
+    pub fn describe(&self) -> String {
+        let mut text = format!(
+            "The recipe's scaffold has been created in {}. Build on these files rather than \
+             creating them again.",
+            self.root.display()
+        );
+        for (label, files) in [
+            ("Created", &self.created),
+            ("Overwritten", &self.overwritten),
+            ("Already existed and were left as they were", &self.skipped),
+        ] {
+            if !files.is_empty() {
+                text.push_str(&format!("\n{}:\n- {}", label, files.join("\n- ")));
+            }
+        }
+        text
+    }
+}
+
+fn template_env() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_keep_trailing_newline(true);
+    env
+}
+
+/// `path` as a path that stays inside the directory it is joined to
+fn relative_path(path: &str) -> Result<PathBuf> {
+    let relative = Path::new(path);
+    if path.trim().is_empty()
+        || !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(anyhow!(
+            "Scaffold path '{}' must be relative and may not contain '..'",
+            path
+        ));
+    }
+    Ok(relative.to_path_buf())
+}
+
+/// The files a scaffold entry's `source` stands for, as paths relative to it and their content
+fn read_source(source: &Path) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let read = |path: &Path| {
+        fs::read(path)
+            .map_err(|e| anyhow!("Failed to read scaffold template {}: {}", path.display(), e))
+    };
+    if source.is_file() {
+        return Ok(vec![(PathBuf::new(), read(source)?)]);
+    }
+    if !source.is_dir() {
+        return Err(anyhow!(
+            "Scaffold template {} does not exist",
+            source.display()
+        ));
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![source.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                let relative = path.strip_prefix(source)?.to_path_buf();
+                files.push((relative, read(&path)?));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// Replace the scaffold's `source` entries with the files they name, read relative to
+/// `recipe_dir` and rendered with `params` like the recipe itself. Files that are not text are
+/// kept as they are.
+pub fn expand_sources(
+    scaffold: &mut Scaffold,
+    recipe_dir: &Path,
+    params: &HashMap<String, String>,
+) -> Result<()> {
+    let env = template_env();
+    let mut files = Vec::new();
+    for file in scaffold.files.drain(..) {
+        let Some(source) = &file.source else {
+            files.push(file);
+            continue;
+        };
+        let source = recipe_dir.join(relative_path(source)?);
+        let single_file = source.is_file();
+        for (relative, bytes) in read_source(&source)? {
+            let path = if single_file {
+                file.path.clone()
+            } else {
+                let relative = env.render_str(&relative.to_string_lossy(), params)?;
+                Path::new(&file.path)
+                    .join(relative)
+                    .to_string_lossy()
+                    .to_string()
+            };
+            let (content, binary_content) = match String::from_utf8(bytes) {
+                Ok(text) => {
+                    let content = env.render_str(&text, params).map_err(|e| {
+                        anyhow!(
+                            "Failed to render scaffold template {}: {}",
+                            source.join(&relative).display(),
+                            e
+                        )
+                    })?;
+                    (Some(content), None)
+                }
+                Err(e) => (None, Some(e.into_bytes())),
+            };
+            files.push(ScaffoldFile {
+                path,
+                content,
+                source: None,
+                binary_content,
+            });
+        }
+    }
+    scaffold.files = files;
+    Ok(())
+}
+
+/// The parameters used by the files a scaffold reads from `recipe_dir`, which the recipe has to
+/// declare like the ones it uses itself
+pub fn template_variables(scaffold: &Scaffold, recipe_dir: &Path) -> Result<HashSet<String>> {
+    let env = template_env();
+    let mut variables = HashSet::new();
+    for source in scaffold
+        .files
+        .iter()
+        .filter_map(|file| file.source.as_ref())
+    {
+        for (relative, bytes) in read_source(&recipe_dir.join(relative_path(source)?))? {
+            let mut templates = vec![relative.to_string_lossy().into_owned()];
+            if let Ok(text) = String::from_utf8(bytes) {
+                templates.push(text);
+            }
+            for template in &templates {
+                variables.extend(env.template_from_str(template)?.undeclared_variables(false));
+            }
+        }
+    }
+    Ok(variables)
+}
+
+/// Where `relative` lands under `root`, refusing paths that go through a symlink so a file
+/// can't be written outside of `root` by way of one
+fn target_path(root: &Path, relative: &Path) -> Result<PathBuf> {
+    let mut target = root.to_path_buf();
+    for component in relative.components() {
+        target.push(component);
+        if fs::symlink_metadata(&target).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+            return Err(anyhow!(
+                "Refusing to write the scaffold through the symlink {}",
+                target.display()
+            ));
+        }
+    }
+    Ok(target)
+}
+
+/// Create the scaffold's files under `working_dir`, after expanding its sources
+pub fn materialize(scaffold: &Scaffold, working_dir: &Path) -> Result<ScaffoldReport> {
+    let working_dir = working_dir.canonicalize().map_err(|e| {
+        anyhow!(
+            "Failed to resolve working directory {}: {}",
+            working_dir.display(),
+            e
+        )
+    })?;
+    let destination = match &scaffold.destination {
+        Some(destination) => relative_path(destination)?,
+        None => PathBuf::new(),
+    };
+    let root = working_dir.join(&destination);
+
+    let mut files = Vec::new();
+    for file in &scaffold.files {
+        let content = match (&file.content, &file.binary_content) {
+            (Some(content), _) => content.as_bytes(),
+            (None, Some(bytes)) => bytes.as_slice(),
+            (None, None) => {
+                return Err(anyhow!(
+                    "Scaffold file '{}' has no content; sources have to be expanded first",
+                    file.path
+                ))
+            }
+        };
+        let target = target_path(&working_dir, &destination.join(relative_path(&file.path)?))?;
+        files.push((file.path.clone(), target, content));
+    }
+
+    let existing: Vec<&str> = files
+        .iter()
+        .filter(|(_, target, _)| target.exists())
+        .map(|(path, _, _)| path.as_str())
+        .collect();
+    if scaffold.on_conflict == ScaffoldConflict::Fail && !existing.is_empty() {
+        return Err(anyhow!(
+            "Nothing was created because these files already exist in {}: {}. Set the \
+             scaffold's on_conflict to skip or overwrite to create the others anyway.",
+            root.display(),
+            existing.join(", ")
+        ));
+    }
+
+    let mut report = ScaffoldReport {
+        root: root.clone(),
+        ..Default::default()
+    };
+    for (path, target, content) in files {
+        if target.is_dir() {
+            return Err(anyhow!(
+                "Scaffold file '{}' is a directory in {}",
+                path,
+                root.display()
+            ));
+        }
+        let exists = target.exists();
+        if exists && scaffold.on_conflict == ScaffoldConflict::Skip {
+            report.skipped.push(path);
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+            // Checked again once the directories exist, in case one was swapped for a link
+            if !parent.canonicalize()?.starts_with(&working_dir) {
+                return Err(anyhow!(
+                    "Scaffold file '{}' would be created outside of {}",
+                    path,
+                    working_dir.display()
+                ));
+            }
+        }
+        target_path(&working_dir, target.strip_prefix(&working_dir)?)?;
+        fs::write(&target, content)?;
+        if exists {
+            report.overwritten.push(path);
+        } else {
+            report.created.push(path);
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaffold(on_conflict: ScaffoldConflict) -> Scaffold {
+        Scaffold {
+            destination: Some("billing".to_string()),
+            on_conflict,
+            files: vec![
+                ScaffoldFile {
+                    path: "README.md".to_string(),
+                    content: Some("# billing\n".to_string()),
+                    source: None,
+                    binary_content: None,
+                },
+                ScaffoldFile {
+                    path: "src/main.rs".to_string(),
+                    content: Some("fn main() {}\n".to_string()),
+                    source: None,
+                    binary_content: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_materialize_creates_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = materialize(&scaffold(ScaffoldConflict::Fail), dir.path()).unwrap();
+        assert_eq!(report.created, vec!["README.md", "src/main.rs"]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("billing/src/main.rs")).unwrap(),
+            "fn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("billing")).unwrap();
+        fs::write(dir.path().join("billing/README.md"), "mine").unwrap();
+
+        let err = materialize(&scaffold(ScaffoldConflict::Fail), dir.path()).unwrap_err();
+        assert!(err.to_string().contains("README.md"));
+        assert!(!dir.path().join("billing/src/main.rs").exists());
+
+        let report = materialize(&scaffold(ScaffoldConflict::Skip), dir.path()).unwrap();
+        assert_eq!(report.skipped, vec!["README.md"]);
+        assert_eq!(report.created, vec!["src/main.rs"]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("billing/README.md")).unwrap(),
+            "mine"
+        );
+
+        let report = materialize(&scaffold(ScaffoldConflict::Overwrite), dir.path()).unwrap();
+        assert_eq!(report.overwritten, vec!["README.md", "src/main.rs"]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("billing/README.md")).unwrap(),
+            "# billing\n"
+        );
+    }
+
+    #[test]
+    fn test_paths_stay_inside_the_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut escaping = scaffold(ScaffoldConflict::Overwrite);
+        escaping.files[0].path = "../outside.txt".to_string();
+        assert!(materialize(&escaping, dir.path()).is_err());
+        assert!(!dir.path().parent().unwrap().join("outside.txt").exists());
+    }
+
+    #[test]
+    fn test_expand_sources_renders_template_directory() {
+        let recipe_dir = tempfile::tempdir().unwrap();
+        let template = recipe_dir.path().join("templates/service");
+        fs::create_dir_all(template.join("src")).unwrap();
+        fs::write(
+            template.join("Cargo.toml"),
+            "[package]\nname = \"{{ name }}\"\n",
+        )
+        .unwrap();
+        fs::write(template.join("src/{{ name }}.rs"), "// {{ name }}\n").unwrap();
+
+        let mut scaffold = Scaffold {
+            destination: None,
+            on_conflict: ScaffoldConflict::Fail,
+            files: vec![ScaffoldFile {
+                path: "billing".to_string(),
+                content: None,
+                source: Some("templates/service".to_string()),
+                binary_content: None,
+            }],
+        };
+        let variables = template_variables(&scaffold, recipe_dir.path()).unwrap();
+        assert_eq!(variables, HashSet::from(["name".to_string()]));
+
+        let params = HashMap::from([("name".to_string(), "billing".to_string())]);
+        expand_sources(&mut scaffold, recipe_dir.path(), &params).unwrap();
+        let files: Vec<(&str, &str)> = scaffold
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), f.content.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                ("billing/Cargo.toml", "[package]\nname = \"billing\"\n"),
+                ("billing/src/billing.rs", "// billing\n"),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_refuses_to_write_through_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("billing")).unwrap();
+
+        let err = materialize(&scaffold(ScaffoldConflict::Overwrite), dir.path()).unwrap_err();
+        assert!(err.to_string().contains("symlink"));
+        assert!(!outside.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn test_sources_stay_inside_the_recipe_dir() {
+        let recipe_dir = tempfile::tempdir().unwrap();
+        let mut scaffold = Scaffold {
+            destination: None,
+            on_conflict: ScaffoldConflict::Fail,
+            files: vec![ScaffoldFile {
+                path: "stolen".to_string(),
+                content: None,
+                source: Some("../../etc/passwd".to_string()),
+                binary_content: None,
+            }],
+        };
+        assert!(template_variables(&scaffold, recipe_dir.path()).is_err());
+        assert!(expand_sources(&mut scaffold, recipe_dir.path(), &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_binary_files_and_raw_blocks() {
+        let recipe_dir = tempfile::tempdir().unwrap();
+        let template = recipe_dir.path().join("templates");
+        fs::create_dir_all(&template).unwrap();
+        let png = [0x89, b'P', b'N', b'G', 0xff, 0x00, b'{', b'{'];
+        fs::write(template.join("logo.png"), png).unwrap();
+        fs::write(
+            template.join("ci.yml"),
+            "name: {{ name }}\ntoken: {% raw %}${{ secrets.TOKEN }}{% endraw %}\n",
+        )
+        .unwrap();
+
+        let mut scaffold = Scaffold {
+            destination: None,
+            on_conflict: ScaffoldConflict::Fail,
+            files: vec![ScaffoldFile {
+                path: ".".to_string(),
+                content: None,
+                source: Some("templates".to_string()),
+                binary_content: None,
+            }],
+        };
+        let variables = template_variables(&scaffold, recipe_dir.path()).unwrap();
+        assert_eq!(variables, HashSet::from(["name".to_string()]));
+        let params = HashMap::from([("name".to_string(), "billing".to_string())]);
+        expand_sources(&mut scaffold, recipe_dir.path(), &params).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        materialize(&scaffold, dir.path()).unwrap();
+        assert_eq!(fs::read(dir.path().join("logo.png")).unwrap(), png);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("ci.yml")).unwrap(),
+            "name: billing\ntoken: ${{ secrets.TOKEN }}\n"
+        );
+    }
+}
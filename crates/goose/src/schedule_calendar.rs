@@ -0,0 +1,405 @@
+//! Schedules on a calendar. Blackout windows stop scheduled runs for a stretch of time, such
+//! as a deploy freeze, and the iCal feed shows upcoming runs, recent runs and blackouts in any
+//! calendar app.
+//!
+//! ```yaml
+//! GOOSE_SCHEDULE_BLACKOUTS:
+//!   - id: q4-freeze
+//!     start: 2025-12-19T00:00:00Z
+//!     end: 2026-01-05T00:00:00Z
+//!     reason: Holiday deploy freeze
+//!     schedules: [deploy-notes]   # leave out to cover every schedule
+//! GOOSE_SCHEDULE_ICAL_TOKEN: <random string>  # lets calendar apps subscribe without the
+//!                                             # server's secret key
+//! ```
+//!
+//! A run that comes due inside a window is skipped, not postponed, and jobs that follow it
+//! wait for its next run. Running a schedule by hand ignores blackouts.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::Config;
+use crate::scheduler::{next_cron_run, ScheduledJob, SchedulerError};
+use crate::scheduler_trait::SchedulerTrait;
+
+pub const BLACKOUTS_KEY: &str = "GOOSE_SCHEDULE_BLACKOUTS";
+pub const ICAL_TOKEN_KEY: &str = "GOOSE_SCHEDULE_ICAL_TOKEN";
+
+/// How far ahead the feed lists upcoming runs
+const HORIZON: Duration = Duration::days(14);
+/// Most upcoming runs listed per schedule, so a once-a-minute job doesn't flood the feed
+const MAX_UPCOMING_PER_JOB: usize = 50;
+/// Most past runs listed per schedule
+const MAX_HISTORY_PER_JOB: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BlackoutWindow {
+    pub id: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Schedules the window applies to; empty means all of them
+    #[serde(default)]
+    pub schedules: Vec<String>,
+}
+
+impl BlackoutWindow {
+    pub fn validate(&self) -> Result<()> {
+        if self.id.trim().is_empty() {
+            bail!("a blackout window needs an id");
+        }
+        if self.end <= self.start {
+            bail!("blackout window '{}' ends before it starts", self.id);
+        }
+        Ok(())
+    }
+
+    /// Whether runs of `schedule_id` are blocked at `at`
+    pub fn covers(&self, schedule_id: &str, at: DateTime<Utc>) -> bool {
+        self.start <= at
+            && at < self.end
+            && (self.schedules.is_empty() || self.schedules.iter().any(|id| id == schedule_id))
+    }
+
+    fn label(&self) -> &str {
+        self.reason.as_deref().unwrap_or(&self.id)
+    }
+}
+
+pub fn blackouts() -> Vec<BlackoutWindow> {
+    Config::global()
+        .get_param(BLACKOUTS_KEY)
+        .unwrap_or_default()
+}
+
+// The user config's own list, which changes start from so that other layers aren't copied in
+fn user_blackouts() -> Vec<BlackoutWindow> {
+    Config::global()
+        .get_user_param(BLACKOUTS_KEY)
+        .unwrap_or_default()
+}
+
+fn save_blackouts(windows: &[BlackoutWindow]) -> Result<()> {
+    Config::global().set_param(BLACKOUTS_KEY, serde_json::to_value(windows)?)?;
+    Ok(())
+}
+
+/// Add `window`, failing if it is invalid or its id is taken
+pub fn add_blackout(window: BlackoutWindow) -> Result<()> {
+    window.validate()?;
+    let mut windows = user_blackouts();
+    if windows.iter().any(|existing| existing.id == window.id) {
+        bail!("a blackout window with id '{}' already exists", window.id);
+    }
+    windows.push(window);
+    windows.sort_by_key(|window| window.start);
+    save_blackouts(&windows)
+}
+
+/// Remove the window with `id`, returning whether there was one
+pub fn remove_blackout(id: &str) -> Result<bool> {
+    let mut windows = user_blackouts();
+    let before = windows.len();
+    windows.retain(|window| window.id != id);
+    if windows.len() == before {
+        return Ok(false);
+    }
+    save_blackouts(&windows)?;
+    Ok(true)
+}
+
+/// The window keeping `schedule_id` from running at `at`, if any
+pub fn active_blackout(schedule_id: &str, at: DateTime<Utc>) -> Option<BlackoutWindow> {
+    blackouts()
+        .into_iter()
+        .find(|window| window.covers(schedule_id, at))
+}
+
+/// Whether a scheduled run of `schedule_id` due at `at` is to be skipped for a blackout
+/// window, logging the window if so. Runs started by hand are not held back.
+pub fn blacked_out(schedule_id: &str, at: DateTime<Utc>) -> bool {
+    let Some(window) = active_blackout(schedule_id, at) else {
+        return false;
+    };
+    tracing::info!(
+        "Skipping run of job '{}': blackout window '{}' runs until {}",
+        schedule_id,
+        window.id,
+        window.end.to_rfc3339()
+    );
+    true
+}
+
+/// The token a calendar app can pass instead of the secret key, if the feed is enabled
+pub fn feed_token() -> Option<String> {
+    Config::global()
+        .get_param::<String>(ICAL_TOKEN_KEY)
+        .ok()
+        .filter(|token| !token.is_empty())
+}
+
+/// A finished run of a schedule, from its session
+#[derive(Debug, Clone)]
+pub struct PastRun {
+    pub schedule_id: String,
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub description: String,
+}
+
+/// When `job` runs between `from` and `until`. Chained jobs have no times of their own.
+fn upcoming_runs(
+    job: &ScheduledJob,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<DateTime<Utc>> {
+    if job.paused || !job.depends_on.is_empty() {
+        return Vec::new();
+    }
+    if let Some(run_at) = job.run_at {
+        return if run_at >= from && run_at < until && job.last_run.is_none() {
+            vec![run_at]
+        } else {
+            Vec::new()
+        };
+    }
+    let mut runs = Vec::new();
+    let mut after = from;
+    while runs.len() < MAX_UPCOMING_PER_JOB {
+        match next_cron_run(&job.cron, after) {
+            Ok(next) if next < until => {
+                runs.push(next);
+                after = next;
+            }
+            _ => break,
+        }
+    }
+    runs
+}
+
+fn ical_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// Writes content lines, folded at 75 octets as RFC 5545 asks
+struct Calendar(String);
+
+impl Calendar {
+    fn line(&mut self, line: &str) {
+        let mut width = 0;
+        for c in line.chars() {
+            if width + c.len_utf8() > 75 {
+                self.0.push_str("\r\n ");
+                width = 1;
+            }
+            self.0.push(c);
+            width += c.len_utf8();
+        }
+        self.0.push_str("\r\n");
+    }
+
+    fn event(&mut self, uid: &str, stamp: DateTime<Utc>, fields: &[(&str, String)]) {
+        self.line("BEGIN:VEVENT");
+        self.line(&format!("UID:{}@goose", uid));
+        self.line(&format!("DTSTAMP:{}", ical_time(stamp)));
+        for (name, value) in fields {
+            self.line(&format!("{}:{}", name, value));
+        }
+        self.line("END:VEVENT");
+    }
+}
+
+/// An iCalendar document with the upcoming runs of `jobs`, `history` and `windows`
+pub fn calendar(
+    jobs: &[ScheduledJob],
+    history: &[PastRun],
+    windows: &[BlackoutWindow],
+    now: DateTime<Utc>,
+) -> String {
+    let mut cal = Calendar(String::new());
+    cal.line("BEGIN:VCALENDAR");
+    cal.line("VERSION:2.0");
+    cal.line("PRODID:-//goose//schedules//EN");
+    cal.line("CALSCALE:GREGORIAN");
+    cal.line("X-WR-CALNAME:goose schedules");
+
+    for job in jobs {
+        for run in upcoming_runs(job, now, now + HORIZON) {
+            let blackout = windows.iter().find(|window| window.covers(&job.id, run));
+            let mut fields = vec![
+                ("DTSTART", ical_time(run)),
+                ("SUMMARY", escape_text(&format!("goose: {}", job.id))),
+                (
+                    "DESCRIPTION",
+                    escape_text(&format!("Recipe: {}", job.source)),
+                ),
+            ];
+            if let Some(window) = blackout {
+                fields[1].1 = escape_text(&format!("goose: {} (skipped)", job.id));
+                fields[2].1 = escape_text(&format!("Skipped for blackout: {}", window.label()));
+                fields.push(("STATUS", "CANCELLED".to_string()));
+            }
+            cal.event(&format!("run-{}-{}", job.id, run.timestamp()), now, &fields);
+        }
+    }
+
+    for run in history {
+        cal.event(
+            &format!("session-{}", run.session_id),
+            now,
+            &[
+                ("DTSTART", ical_time(run.started_at)),
+                (
+                    "SUMMARY",
+                    escape_text(&format!("goose: {} ran", run.schedule_id)),
+                ),
+                (
+                    "DESCRIPTION",
+                    escape_text(&format!("Session {}: {}", run.session_id, run.description)),
+                ),
+            ],
+        );
+    }
+
+    for window in windows {
+        let covered = if window.schedules.is_empty() {
+            "All schedules".to_string()
+        } else {
+            window.schedules.join(", ")
+        };
+        cal.event(
+            &format!("blackout-{}", window.id),
+            now,
+            &[
+                ("DTSTART", ical_time(window.start)),
+                ("DTEND", ical_time(window.end)),
+                (
+                    "SUMMARY",
+                    escape_text(&format!("goose blackout: {}", window.label())),
+                ),
+                ("DESCRIPTION", escape_text(&format!("Paused: {}", covered))),
+                ("TRANSP", "TRANSPARENT".to_string()),
+            ],
+        );
+    }
+
+    cal.line("END:VCALENDAR");
+    cal.0
+}
+
+/// When a session started, from its id: the local time it was created
+fn session_started_at(session_id: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(session_id, "%Y%m%d_%H%M%S")
+        .ok()?
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|started_at| started_at.with_timezone(&Utc))
+}
+
+/// The iCal feed for every schedule `scheduler` knows about
+pub async fn export_ical(scheduler: &dyn SchedulerTrait) -> Result<String, SchedulerError> {
+    let jobs = scheduler.list_scheduled_jobs().await?;
+    let mut history = Vec::new();
+    for job in &jobs {
+        for (session_id, metadata) in scheduler.sessions(&job.id, MAX_HISTORY_PER_JOB).await? {
+            let Some(started_at) = session_started_at(&session_id) else {
+                continue;
+            };
+            history.push(PastRun {
+                schedule_id: job.id.clone(),
+                session_id,
+                started_at,
+                description: metadata.description,
+            });
+        }
+    }
+    Ok(calendar(&jobs, &history, &blackouts(), Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn job(id: &str, cron: &str) -> ScheduledJob {
+        ScheduledJob {
+            id: id.to_string(),
+            source: format!("/recipes/{}.yaml", id),
+            cron: cron.to_string(),
+            last_run: None,
+            currently_running: false,
+            paused: false,
+            current_session_id: None,
+            process_start_time: None,
+            execution_mode: None,
+            run_at: None,
+            depends_on: Vec::new(),
+            environment: Default::default(),
+        }
+    }
+
+    fn freeze() -> BlackoutWindow {
+        BlackoutWindow {
+            id: "freeze".to_string(),
+            start: Utc.with_ymd_and_hms(2025, 1, 2, 0, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap(),
+            reason: Some("Deploy freeze".to_string()),
+            schedules: vec!["deploy".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_blackout_covers() {
+        let window = freeze();
+        let during = Utc.with_ymd_and_hms(2025, 1, 2, 9, 0, 0).unwrap();
+        assert!(window.covers("deploy", during));
+        assert!(!window.covers("report", during));
+        assert!(!window.covers("deploy", window.end));
+
+        let mut backwards = freeze();
+        backwards.end = backwards.start;
+        assert!(backwards.validate().is_err());
+    }
+
+    #[test]
+    fn test_calendar() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap();
+        let history = vec![PastRun {
+            schedule_id: "deploy".to_string(),
+            session_id: "20241231_090000".to_string(),
+            started_at: Utc.with_ymd_and_hms(2024, 12, 31, 9, 0, 0).unwrap(),
+            description: "Notes, for the deploy".to_string(),
+        }];
+        let ical = calendar(&[job("deploy", "0 9 * * *")], &history, &[freeze()], now);
+
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("SUMMARY:goose: deploy\r\n").count(), 13);
+        assert!(ical.contains("DTSTART:20250102T090000Z\r\nSUMMARY:goose: deploy (skipped)"));
+        assert!(ical.contains("DESCRIPTION:Session 20241231_090000: Notes\\, for the deploy"));
+        assert!(ical.contains("DTEND:20250103T000000Z"));
+        assert!(ical.split("\r\n").all(|line| line.len() <= 75));
+    }
+
+    #[test]
+    fn test_session_ids_are_local_time() {
+        let started_at = session_started_at("20241231_090000").unwrap();
+        assert_eq!(
+            started_at.with_timezone(&Local).naive_local(),
+            NaiveDateTime::parse_from_str("20241231_090000", "%Y%m%d_%H%M%S").unwrap()
+        );
+        assert_eq!(session_started_at("not-a-date"), None);
+    }
+}
@@ -2,24 +2,30 @@ use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use croner::Cron;
 use etcetera::{choose_app_strategy, AppStrategy};
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use tokio_cron_scheduler::{job::JobId, Job, JobScheduler as TokioJobScheduler};
 
+use crate::agents::extension::Envs;
 use crate::agents::AgentEvent;
 use crate::agents::{Agent, SessionConfig};
-use crate::config::{self, Config};
+use crate::config::{self, Config, ExtensionConfigManager};
 use crate::conversation::message::Message;
 use crate::conversation::Conversation;
+use crate::notifications::{self, NotificationKind};
+use crate::offline::{self, Connectivity};
 use crate::providers::base::Provider as GooseProvider; // Alias to avoid conflict in test section
 use crate::providers::create;
-use crate::recipe::Recipe;
+use crate::recipe::{scaffold, Recipe};
+use crate::schedule_calendar;
 use crate::scheduler_trait::SchedulerTrait;
 use crate::session;
 use crate::session::storage::SessionMetadata;
@@ -63,6 +69,23 @@ pub fn normalize_cron_expression(src: &str) -> String {
     parts.join(" ")
 }
 
+/// The first time after `after` that `cron` fires, in UTC like the legacy scheduler
+pub fn next_cron_run(cron: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>, SchedulerError> {
+    let normalized = normalize_cron_expression(cron);
+    // croner takes at most six fields, so drop the year the normalized form ends with
+    let parts: Vec<&str> = normalized.split_whitespace().collect();
+    let pattern = if parts.len() == 7 {
+        parts[..6].join(" ")
+    } else {
+        normalized
+    };
+    Cron::new(&pattern)
+        .with_seconds_optional()
+        .parse()
+        .and_then(|cron| cron.find_next_occurrence(&after, false))
+        .map_err(|e| SchedulerError::CronParseError(format!("{}: {}", cron, e)))
+}
+
 pub fn get_default_scheduler_storage_path() -> Result<PathBuf, io::Error> {
     let strategy = choose_app_strategy(config::APP_STRATEGY.clone())
         .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e.to_string()))?;
@@ -94,6 +117,7 @@ pub enum SchedulerError {
     AgentSetupError(String),
     PersistError(String),
     CronParseError(String),
+    InvalidJob(String),
     SchedulerInternalError(String),
     AnyhowError(anyhow::Error),
 }
@@ -108,6 +132,7 @@ impl std::fmt::Display for SchedulerError {
             SchedulerError::AgentSetupError(e) => write!(f, "Agent setup error: {}", e),
             SchedulerError::PersistError(e) => write!(f, "Failed to persist schedules: {}", e),
             SchedulerError::CronParseError(e) => write!(f, "Invalid cron string: {}", e),
+            SchedulerError::InvalidJob(e) => write!(f, "Invalid job: {}", e),
             SchedulerError::SchedulerInternalError(e) => {
                 write!(f, "Scheduler internal error: {}", e)
             }
@@ -160,6 +185,196 @@ pub struct ScheduledJob {
     pub process_start_time: Option<DateTime<Utc>>,
     #[serde(default)]
     pub execution_mode: Option<String>, // "foreground" or "background"
+    /// Run once at this time instead of on a cron schedule
+    #[serde(default)]
+    pub run_at: Option<DateTime<Utc>>,
+    /// Run once all of these jobs have succeeded since this job last ran, instead of on a
+    /// cron schedule
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub environment: JobEnvironment,
+}
+
+/// Where a job runs and with what, in place of the settings of the process running the
+/// scheduler. Anything left unset is inherited as before.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, utoipa::ToSchema)]
+pub struct JobEnvironment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    /// Environment variables for the job's extensions. Their values are moved to the secret
+    /// store when the job is added, leaving only their names in `env_keys`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Names of the job's environment variables whose values are in the secret store
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_keys: Vec<String>,
+    /// Configured extensions to enable, by name, on top of the recipe's
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Secret the values of a job's environment variables are kept under
+fn env_secret_key(job_id: &str) -> String {
+    format!("GOOSE_SCHEDULE_ENV/{}", job_id)
+}
+
+impl JobEnvironment {
+    pub fn is_inherited(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Move the values of the environment variables to the secret store, so that schedule
+    /// storage only ever holds their names
+    pub(crate) fn store_env(&mut self, job_id: &str) -> Result<(), SchedulerError> {
+        if self.env.is_empty() {
+            return Ok(());
+        }
+        let env = std::mem::take(&mut self.env);
+        Config::global()
+            .set_secret(&env_secret_key(job_id), serde_json::to_value(&env)?)
+            .map_err(|e| {
+                SchedulerError::PersistError(format!(
+                    "Failed to store the job's environment variables: {}",
+                    e
+                ))
+            })?;
+        self.env_keys.extend(env.into_keys());
+        self.env_keys.sort();
+        self.env_keys.dedup();
+        Ok(())
+    }
+
+    /// The environment variables to run the job with, with their values read back from the
+    /// secret store
+    fn load_env(&self, job_id: &str) -> Result<HashMap<String, String>, String> {
+        // Jobs added before values were moved to the secret store still have them here
+        let mut env = self.env.clone();
+        if !self.env_keys.is_empty() {
+            let stored: HashMap<String, String> = Config::global()
+                .get_secret(&env_secret_key(job_id))
+                .map_err(|e| format!("Failed to read the job's environment variables: {}", e))?;
+            env.extend(stored);
+        }
+        Ok(env)
+    }
+
+    /// This environment with only the names of its variables, for showing to clients
+    pub fn redacted(&self) -> Self {
+        let mut redacted = self.clone();
+        redacted
+            .env_keys
+            .extend(std::mem::take(&mut redacted.env).into_keys());
+        redacted.env_keys.sort();
+        redacted.env_keys.dedup();
+        redacted
+    }
+
+    /// The directory the job runs in, checked to exist
+    fn resolve_working_dir(&self) -> Result<PathBuf, String> {
+        match &self.working_dir {
+            Some(dir) => {
+                let dir = PathBuf::from(dir);
+                if dir.is_dir() {
+                    Ok(dir)
+                } else {
+                    Err(format!(
+                        "Working directory {} does not exist",
+                        dir.display()
+                    ))
+                }
+            }
+            None => std::env::current_dir()
+                .map_err(|e| format!("Failed to get current directory for job execution: {}", e)),
+        }
+    }
+}
+
+/// Drop the stored values of a removed job's environment variables
+pub(crate) fn remove_env(job: &ScheduledJob) {
+    if job.environment.env_keys.is_empty() {
+        return;
+    }
+    if let Err(e) = Config::global().delete_secret(&env_secret_key(&job.id)) {
+        tracing::warn!(
+            "Failed to remove the environment variables of job '{}': {}",
+            job.id,
+            e
+        );
+    }
+}
+
+/// How a job's latest run ended
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobOutcome {
+    Succeeded,
+    Failed,
+    /// Not run because a job it depends on failed or was skipped, or because it came due
+    /// during a blackout window
+    Skipped,
+}
+
+/// A job in the dependency graph, with the jobs it waits for
+#[derive(Clone, Serialize, Debug, utoipa::ToSchema)]
+pub struct JobNode {
+    pub id: String,
+    pub depends_on: Vec<String>,
+    pub last_outcome: Option<JobOutcome>,
+}
+
+/// Order `nodes` so every job comes after the jobs it depends on, keeping ids in order among
+/// jobs that are ready at the same time. Jobs caught in a cycle, or waiting on a job that isn't
+/// there, go last.
+pub fn order_by_dependencies(mut nodes: Vec<JobNode>) -> Vec<JobNode> {
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+    let mut ordered: Vec<JobNode> = Vec::with_capacity(nodes.len());
+    loop {
+        let ready = nodes.iter().position(|node| {
+            node.depends_on
+                .iter()
+                .all(|dependency| ordered.iter().any(|done| &done.id == dependency))
+        });
+        match ready {
+            Some(index) => ordered.push(nodes.remove(index)),
+            None => break,
+        }
+    }
+    ordered.append(&mut nodes);
+    ordered
+}
+
+impl ScheduledJob {
+    /// Fail for one-shot and chained jobs, which only some backends can run
+    pub(crate) fn require_cron(&self, backend: &str) -> Result<(), SchedulerError> {
+        if self.run_at.is_some() || !self.depends_on.is_empty() {
+            return Err(SchedulerError::SchedulerInternalError(format!(
+                "the {} scheduler only runs cron schedules; set GOOSE_SCHEDULER_TYPE=sqlite for \
+                 one-shot and chained jobs",
+                backend
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn notify_job_finished(job_id: &str, error: Option<&str>) {
+    match error {
+        None => notifications::notify(
+            NotificationKind::RunFinished,
+            "goose schedule finished",
+            &format!("Scheduled job '{}' completed", job_id),
+        ),
+        Some(error) => notifications::notify(
+            NotificationKind::RunFinished,
+            "goose schedule failed",
+            &format!("Scheduled job '{}' failed: {}", job_id, error),
+        ),
+    }
 }
 
 async fn persist_jobs_from_arc(
@@ -176,6 +391,89 @@ async fn persist_jobs_from_arc(
     Ok(())
 }
 
+/// Copy a job's recipe into the scheduled recipes directory, so later edits to the original
+/// don't change what runs. Returns the path of the copy.
+pub(crate) fn store_recipe(original_job_spec: &ScheduledJob) -> Result<String, SchedulerError> {
+    let original_recipe_path = Path::new(&original_job_spec.source);
+    if !original_recipe_path.exists() {
+        return Err(SchedulerError::RecipeLoadError(format!(
+            "Original recipe file not found: {}",
+            original_job_spec.source
+        )));
+    }
+    if !original_recipe_path.is_file() {
+        return Err(SchedulerError::RecipeLoadError(format!(
+            "Original recipe source is not a file: {}",
+            original_job_spec.source
+        )));
+    }
+
+    let scheduled_recipes_dir = get_default_scheduled_recipes_dir()?;
+    let original_extension = original_recipe_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("yaml");
+
+    let destination_filename = format!("{}.{}", original_job_spec.id, original_extension);
+    let destination_recipe_path = scheduled_recipes_dir.join(destination_filename);
+
+    tracing::info!(
+        "Copying recipe from {} to {}",
+        original_recipe_path.display(),
+        destination_recipe_path.display()
+    );
+    fs::copy(original_recipe_path, &destination_recipe_path).map_err(|e| {
+        SchedulerError::StorageError(io::Error::new(
+            e.kind(),
+            format!(
+                "Failed to copy recipe from {} to {}: {}",
+                original_job_spec.source,
+                destination_recipe_path.display(),
+                e
+            ),
+        ))
+    })?;
+
+    Ok(destination_recipe_path.to_string_lossy().into_owned())
+}
+
+/// Sessions created by the schedule `sched_id`, newest first
+pub(crate) fn sessions_for_schedule(
+    sched_id: &str,
+    limit: usize,
+) -> Result<Vec<(String, SessionMetadata)>, SchedulerError> {
+    let all_session_files = session::storage::list_sessions()
+        .map_err(|e| SchedulerError::StorageError(io::Error::other(e)))?;
+
+    let mut schedule_sessions: Vec<(String, SessionMetadata)> = Vec::new();
+
+    for (session_name, session_path) in all_session_files {
+        match session::storage::read_metadata(&session_path) {
+            Ok(metadata) => {
+                // metadata is not mutable here, and SessionMetadata is original
+                if metadata.schedule_id.as_deref() == Some(sched_id) {
+                    schedule_sessions.push((session_name, metadata)); // Keep the tuple
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to read metadata for session file {}: {}. Skipping.",
+                    session_path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    schedule_sessions.sort_by(|a, b| b.0.cmp(&a.0)); // Sort by session_name (timestamp string)
+
+    // Keep the tuple, just take the limit
+    let result_sessions: Vec<(String, SessionMetadata)> =
+        schedule_sessions.into_iter().take(limit).collect();
+
+    Ok(result_sessions) // Return the Vec of tuples
+}
+
 pub struct Scheduler {
     internal_scheduler: TokioJobScheduler,
     jobs: Arc<Mutex<JobsMap>>,
@@ -205,6 +503,7 @@ impl Scheduler {
             .start()
             .await
             .map_err(|e| SchedulerError::SchedulerInternalError(e.to_string()))?;
+        tokio::spawn(run_queued_when_online(Arc::downgrade(&arc_self)));
 
         Ok(arc_self)
     }
@@ -213,53 +512,15 @@ impl Scheduler {
         &self,
         original_job_spec: ScheduledJob,
     ) -> Result<(), SchedulerError> {
+        original_job_spec.require_cron("legacy")?;
         let mut jobs_guard = self.jobs.lock().await;
         if jobs_guard.contains_key(&original_job_spec.id) {
             return Err(SchedulerError::JobIdExists(original_job_spec.id.clone()));
         }
 
-        let original_recipe_path = Path::new(&original_job_spec.source);
-        if !original_recipe_path.exists() {
-            return Err(SchedulerError::RecipeLoadError(format!(
-                "Original recipe file not found: {}",
-                original_job_spec.source
-            )));
-        }
-        if !original_recipe_path.is_file() {
-            return Err(SchedulerError::RecipeLoadError(format!(
-                "Original recipe source is not a file: {}",
-                original_job_spec.source
-            )));
-        }
-
-        let scheduled_recipes_dir = get_default_scheduled_recipes_dir()?;
-        let original_extension = original_recipe_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("yaml");
-
-        let destination_filename = format!("{}.{}", original_job_spec.id, original_extension);
-        let destination_recipe_path = scheduled_recipes_dir.join(destination_filename);
-
-        tracing::info!(
-            "Copying recipe from {} to {}",
-            original_recipe_path.display(),
-            destination_recipe_path.display()
-        );
-        fs::copy(original_recipe_path, &destination_recipe_path).map_err(|e| {
-            SchedulerError::StorageError(io::Error::new(
-                e.kind(),
-                format!(
-                    "Failed to copy recipe from {} to {}: {}",
-                    original_job_spec.source,
-                    destination_recipe_path.display(),
-                    e
-                ),
-            ))
-        })?;
-
         let mut stored_job = original_job_spec.clone();
-        stored_job.source = destination_recipe_path.to_string_lossy().into_owned();
+        stored_job.source = store_recipe(&original_job_spec)?;
+        stored_job.environment.store_env(&stored_job.id)?;
         stored_job.current_session_id = None;
         stored_job.process_start_time = None;
         tracing::info!("Updated job source path to: {}", stored_job.source);
@@ -310,6 +571,10 @@ impl Scheduler {
                     return;
                 }
 
+                if schedule_calendar::blacked_out(&task_job_id, Utc::now()) {
+                    return;
+                }
+
                 let current_time = Utc::now();
                 let mut needs_persist = false;
                 {
@@ -383,6 +648,7 @@ impl Scheduler {
                 match result {
                     Ok(Ok(_session_id)) => {
                         tracing::info!("Scheduled job '{}' completed successfully", &task_job_id);
+                        notify_job_finished(&task_job_id, None);
                     }
                     Ok(Err(e)) => {
                         tracing::error!(
@@ -390,6 +656,7 @@ impl Scheduler {
                             &e.job_id,
                             e.error
                         );
+                        notify_job_finished(&e.job_id, Some(&e.error));
                     }
                     Err(join_error) if join_error.is_cancelled() => {
                         tracing::info!("Scheduled job '{}' was cancelled/killed", &task_job_id);
@@ -488,6 +755,10 @@ impl Scheduler {
                         return;
                     }
 
+                    if schedule_calendar::blacked_out(&task_job_id, Utc::now()) {
+                        return;
+                    }
+
                     let current_time = Utc::now();
                     let mut needs_persist = false;
                     {
@@ -564,6 +835,7 @@ impl Scheduler {
                                 "Scheduled job '{}' completed successfully",
                                 &task_job_id
                             );
+                            notify_job_finished(&task_job_id, None);
                         }
                         Ok(Err(e)) => {
                             tracing::error!(
@@ -571,6 +843,7 @@ impl Scheduler {
                                 &e.job_id,
                                 e.error
                             );
+                            notify_job_finished(&e.job_id, Some(&e.error));
                         }
                         Err(join_error) if join_error.is_cancelled() => {
                             tracing::info!("Scheduled job '{}' was cancelled/killed", &task_job_id);
@@ -637,6 +910,7 @@ impl Scheduler {
             if recipe_path.exists() {
                 fs::remove_file(recipe_path).map_err(SchedulerError::StorageError)?;
             }
+            remove_env(&scheduled_job);
 
             self.persist_jobs_to_storage_with_guard(&jobs_guard).await?;
             Ok(())
@@ -650,39 +924,8 @@ impl Scheduler {
         sched_id: &str,
         limit: usize,
     ) -> Result<Vec<(String, SessionMetadata)>, SchedulerError> {
-        // Changed return type
-        let all_session_files = session::storage::list_sessions()
-            .map_err(|e| SchedulerError::StorageError(io::Error::other(e)))?;
-
-        let mut schedule_sessions: Vec<(String, SessionMetadata)> = Vec::new();
-
-        for (session_name, session_path) in all_session_files {
-            match session::storage::read_metadata(&session_path) {
-                Ok(metadata) => {
-                    // metadata is not mutable here, and SessionMetadata is original
-                    if metadata.schedule_id.as_deref() == Some(sched_id) {
-                        schedule_sessions.push((session_name, metadata)); // Keep the tuple
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        "Failed to read metadata for session file {}: {}. Skipping.",
-                        session_path.display(),
-                        e
-                    );
-                }
-            }
-        }
-
-        schedule_sessions.sort_by(|a, b| b.0.cmp(&a.0)); // Sort by session_name (timestamp string)
-
-        // Keep the tuple, just take the limit
-        let result_sessions: Vec<(String, SessionMetadata)> =
-            schedule_sessions.into_iter().take(limit).collect();
-
-        Ok(result_sessions) // Return the Vec of tuples
+        sessions_for_schedule(sched_id, limit)
     }
-
     pub async fn run_now(&self, sched_id: &str) -> Result<String, SchedulerError> {
         let job_to_run: ScheduledJob = {
             let mut jobs_guard = self.jobs.lock().await;
@@ -869,6 +1112,10 @@ impl Scheduler {
                             return;
                         }
 
+                        if schedule_calendar::blacked_out(&task_job_id, Utc::now()) {
+                            return;
+                        }
+
                         let current_time = Utc::now();
                         let mut needs_persist = false;
                         {
@@ -950,6 +1197,7 @@ impl Scheduler {
                                     "Scheduled job '{}' completed successfully",
                                     &task_job_id
                                 );
+                                notify_job_finished(&task_job_id, None);
                             }
                             Ok(Err(e)) => {
                                 tracing::error!(
@@ -957,6 +1205,7 @@ impl Scheduler {
                                     &e.job_id,
                                     e.error
                                 );
+                                notify_job_finished(&e.job_id, Some(&e.error));
                             }
                             Err(join_error) if join_error.is_cancelled() => {
                                 tracing::info!(
@@ -1058,17 +1307,103 @@ impl Scheduler {
     }
 }
 
+/// How often runs queued while offline are retried
+const OFFLINE_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Run the jobs that came due while offline, once the provider can be reached again
+pub(crate) async fn run_queued_when_online<S: SchedulerTrait + 'static>(scheduler: Weak<S>) {
+    let mut interval = tokio::time::interval(OFFLINE_RETRY_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(scheduler) = scheduler.upgrade() else {
+            return;
+        };
+        if !offline::has_queued_runs() {
+            continue;
+        }
+        let (jobs, queued) = match (
+            scheduler.list_scheduled_jobs().await,
+            offline::queued_runs(),
+        ) {
+            (Ok(jobs), Ok(queued)) => (jobs, queued),
+            (Err(e), _) => {
+                tracing::error!("Failed to list jobs to run queued runs: {}", e);
+                continue;
+            }
+            (_, Err(e)) => {
+                tracing::error!("Failed to read runs queued while offline: {}", e);
+                continue;
+            }
+        };
+
+        // Each job waits for the provider it runs with, not the configured one
+        let mut dequeued = Vec::new();
+        let mut ready = Vec::new();
+        for job_id in queued {
+            let Some(job) = jobs.iter().find(|job| job.id == job_id) else {
+                // Removed while its run was waiting
+                dequeued.push(job_id);
+                continue;
+            };
+            let provider_name = job
+                .environment
+                .provider
+                .clone()
+                .or_else(|| Config::global().get_param::<String>("GOOSE_PROVIDER").ok());
+            let online = match provider_name {
+                Some(provider_name) => !offline::check_provider(&provider_name).await.is_offline(),
+                None => true,
+            };
+            if online {
+                dequeued.push(job_id.clone());
+                ready.push(job_id);
+            }
+        }
+        if let Err(e) = offline::dequeue_runs(&dequeued) {
+            tracing::error!("Failed to update runs queued while offline: {}", e);
+            continue;
+        }
+        for job_id in ready {
+            tracing::info!("Running job '{}', queued while offline", job_id);
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                if let Err(e) = scheduler.run_now(&job_id).await {
+                    tracing::warn!("Queued run of job '{}' failed: {}", job_id, e);
+                }
+            });
+        }
+    }
+}
+
 #[derive(Debug)]
-struct JobExecutionError {
-    job_id: String,
-    error: String,
+pub(crate) struct JobExecutionError {
+    pub(crate) job_id: String,
+    pub(crate) error: String,
 }
 
-async fn run_scheduled_job_internal(
+pub(crate) async fn run_scheduled_job_internal(
     job: ScheduledJob,
     provider_override: Option<Arc<dyn GooseProvider>>, // New optional parameter
     jobs_arc: Option<Arc<Mutex<JobsMap>>>,
     job_id: Option<String>,
+) -> std::result::Result<String, JobExecutionError> {
+    run_scheduled_job_in_session(
+        job,
+        session::generate_session_id(),
+        provider_override,
+        jobs_arc,
+        job_id,
+    )
+    .await
+}
+
+/// Runs `job` in the session `session_id`, for callers that need to know it before the run ends
+pub(crate) async fn run_scheduled_job_in_session(
+    job: ScheduledJob,
+    session_id_for_return: String,
+    provider_override: Option<Arc<dyn GooseProvider>>,
+    jobs_arc: Option<Arc<Mutex<JobsMap>>>,
+    job_id: Option<String>,
 ) -> std::result::Result<String, JobExecutionError> {
     tracing::info!("Executing job: {} (Source: {})", job.id, job.source);
 
@@ -1116,13 +1451,41 @@ async fn run_scheduled_job_internal(
 
     let agent: Agent = Agent::new();
 
+    let working_dir = job
+        .environment
+        .resolve_working_dir()
+        .map_err(|error| JobExecutionError {
+            job_id: job.id.clone(),
+            error,
+        })?;
+    agent
+        .extension_manager
+        .write()
+        .await
+        .set_process_environment(
+            Some(working_dir.clone()),
+            Envs::new(
+                job.environment
+                    .load_env(&job.id)
+                    .map_err(|error| JobExecutionError {
+                        job_id: job.id.clone(),
+                        error,
+                    })?,
+            ),
+        );
+
     let agent_provider: Arc<dyn GooseProvider>; // Use the aliased GooseProvider
 
     if let Some(provider) = provider_override {
         agent_provider = provider;
     } else {
         let global_config = Config::global();
-        let provider_name: String = match global_config.get_param("GOOSE_PROVIDER") {
+        let provider_name: String = match job
+            .environment
+            .provider
+            .clone()
+            .map_or_else(|| global_config.get_param("GOOSE_PROVIDER"), Ok)
+        {
             Ok(name) => name,
             Err(_) => return Err(JobExecutionError {
                 job_id: job.id.clone(),
@@ -1131,8 +1494,25 @@ async fn run_scheduled_job_internal(
                         .to_string(),
             }),
         };
+        if let Connectivity::Offline { reason } = offline::check_provider(&provider_name).await {
+            if let Err(e) = offline::queue_scheduled_run(&job.id) {
+                tracing::error!("Failed to queue job '{}' for later: {}", job.id, e);
+            }
+            return Err(JobExecutionError {
+                job_id: job.id.clone(),
+                error: format!(
+                    "Offline ({}); the run will start once the provider is reachable",
+                    reason
+                ),
+            });
+        }
         let model_name: String =
-            match global_config.get_param("GOOSE_MODEL") {
+            match job
+                .environment
+                .model
+                .clone()
+                .map_or_else(|| global_config.get_param("GOOSE_MODEL"), Ok)
+            {
                 Ok(name) => name,
                 Err(_) => return Err(JobExecutionError {
                     job_id: job.id.clone(),
@@ -1166,6 +1546,30 @@ async fn run_scheduled_job_internal(
                 })?;
         }
     }
+    for name in &job.environment.extensions {
+        let extension = match ExtensionConfigManager::get_config_by_name(name) {
+            Ok(Some(extension)) => extension,
+            Ok(None) => {
+                return Err(JobExecutionError {
+                    job_id: job.id.clone(),
+                    error: format!("Extension '{}' is not configured", name),
+                })
+            }
+            Err(e) => {
+                return Err(JobExecutionError {
+                    job_id: job.id.clone(),
+                    error: format!("Failed to read extension '{}': {}", name, e),
+                })
+            }
+        };
+        agent
+            .add_extension(extension)
+            .await
+            .map_err(|e| JobExecutionError {
+                job_id: job.id.clone(),
+                error: format!("Failed to add extension '{}': {}", name, e),
+            })?;
+    }
 
     if let Err(e) = agent.update_provider(agent_provider).await {
         return Err(JobExecutionError {
@@ -1175,12 +1579,26 @@ async fn run_scheduled_job_internal(
     }
     tracing::info!("Agent configured with provider for job '{}'", job.id);
 
+    let recipe_settings = recipe.settings;
+    if let Some(style) = recipe_settings.as_ref().and_then(|s| s.style.clone()) {
+        agent.set_style(Some(style)).await;
+    }
+
+    if let Some(mut scaffold) = recipe.scaffold {
+        let recipe_dir = recipe_path.parent().unwrap_or(Path::new("."));
+        let report = scaffold::expand_sources(&mut scaffold, recipe_dir, &HashMap::new())
+            .and_then(|_| scaffold::materialize(&scaffold, &working_dir))
+            .map_err(|e| JobExecutionError {
+                job_id: job.id.clone(),
+                error: format!("Failed to create the recipe's scaffold: {}", e),
+            })?;
+        agent.extend_system_prompt(report.describe()).await;
+    }
+
     // Log the execution mode
     let execution_mode = job.execution_mode.as_deref().unwrap_or("background");
     tracing::info!("Job '{}' running in {} mode", job.id, execution_mode);
 
-    let session_id_for_return = session::generate_session_id();
-
     // Update the job with the session ID if we have access to the jobs arc
     if let (Some(jobs_arc), Some(job_id_str)) = (jobs_arc.as_ref(), job_id.as_ref()) {
         let mut jobs_guard = jobs_arc.lock().await;
@@ -1205,22 +1623,17 @@ async fn run_scheduled_job_internal(
         let mut all_session_messages =
             Conversation::new_unvalidated(vec![Message::user().with_text(prompt_text.clone())]);
 
-        let current_dir = match std::env::current_dir() {
-            Ok(cd) => cd,
-            Err(e) => {
-                return Err(JobExecutionError {
-                    job_id: job.id.clone(),
-                    error: format!("Failed to get current directory for job execution: {}", e),
-                });
-            }
-        };
-
         let session_config = SessionConfig {
             id: crate::session::storage::Identifier::Name(session_id_for_return.clone()),
-            working_dir: current_dir.clone(),
+            working_dir: working_dir.clone(),
             schedule_id: Some(job.id.clone()),
             execution_mode: job.execution_mode.clone(),
-            max_turns: None,
+            max_turns: recipe_settings.as_ref().and_then(|s| s.max_turns),
+            run_limits: recipe_settings
+                .as_ref()
+                .map(|s| s.run_limits())
+                .unwrap_or_default(),
+            critique: recipe.critique.clone(),
             retry_config: None,
         };
 
@@ -1288,7 +1701,7 @@ async fn run_scheduled_job_internal(
                             e
                         );
                         let fallback_metadata = crate::session::storage::SessionMetadata {
-                            working_dir: current_dir.clone(),
+                            working_dir: working_dir.clone(),
                             description: String::new(),
                             schedule_id: Some(job.id.clone()),
                             message_count: all_session_messages.len(),
@@ -1298,6 +1711,10 @@ async fn run_scheduled_job_internal(
                             accumulated_total_tokens: None,
                             accumulated_input_tokens: None,
                             accumulated_output_tokens: None,
+                            checksum: None,
+                            style: None,
+                            attached_resources: Vec::new(),
+                            comparison_id: None,
                         };
                         if let Err(e_fb) = crate::session::storage::save_messages_with_metadata(
                             &session_file_path,
@@ -1323,7 +1740,7 @@ async fn run_scheduled_job_internal(
             job.source
         );
         let metadata = crate::session::storage::SessionMetadata {
-            working_dir: std::env::current_dir().unwrap_or_default(),
+            working_dir: working_dir.clone(),
             description: "Empty job - no prompt".to_string(),
             schedule_id: Some(job.id.clone()),
             message_count: 0,
@@ -1342,6 +1759,16 @@ async fn run_scheduled_job_internal(
         }
     }
 
+    if let Some(artifacts) = &recipe.artifacts {
+        match session::attachments::collect_artifacts(&session_file_path, &working_dir, artifacts) {
+            Ok(collected) => {
+                tracing::info!("[Job {}] Collected {} artifacts", job.id, collected.len())
+            }
+            Err(e) => tracing::error!("[Job {}] Failed to collect artifacts: {}", job.id, e),
+        }
+    }
+
+    agent.end_session(&session_id_for_return).await;
     tracing::info!("Finished job: {}", job.id);
     Ok(session_id_for_return)
 }
@@ -1450,6 +1877,9 @@ mod tests {
             response: None,
             sub_recipes: None,
             retry: None,
+            critique: None,
+            artifacts: None,
+            scaffold: None,
         };
         let mut recipe_file = File::create(&recipe_filename)?;
         writeln!(
@@ -1470,6 +1900,9 @@ mod tests {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some("background".to_string()), // Default for test
+            run_at: None,
+            depends_on: Vec::new(),
+            environment: Default::default(),
         };
 
         let mock_model_config = ModelConfig::new_or_fail("test_model");
@@ -1522,6 +1955,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_job_environment() {
+        let job: ScheduledJob = serde_json::from_str(
+            r#"{"id": "nightly", "source": "/recipes/nightly.yaml", "cron": "0 2 * * *",
+                "last_run": null}"#,
+        )
+        .unwrap();
+        assert!(job.environment.is_inherited());
+
+        let environment = JobEnvironment {
+            working_dir: Some("/nonexistent/goose/checkout".to_string()),
+            provider: Some("ollama".to_string()),
+            ..Default::default()
+        };
+        assert!(!environment.is_inherited());
+        assert!(environment.resolve_working_dir().is_err());
+        let json = serde_json::to_value(&environment).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"working_dir": "/nonexistent/goose/checkout", "provider": "ollama"})
+        );
+
+        let environment = JobEnvironment {
+            env: HashMap::from([
+                ("TOKEN".to_string(), "s3cret".to_string()),
+                ("REGION".to_string(), "eu".to_string()),
+            ]),
+            ..Default::default()
+        }
+        .redacted();
+        assert!(environment.env.is_empty());
+        assert_eq!(environment.env_keys, vec!["REGION", "TOKEN"]);
+        assert!(!serde_json::to_string(&environment)
+            .unwrap()
+            .contains("s3cret"));
+    }
 }
 
 #[async_trait]
@@ -4,10 +4,12 @@ use std::sync::Arc;
 use crate::config::Config;
 use crate::scheduler::{Scheduler, SchedulerError};
 use crate::scheduler_trait::SchedulerTrait;
+use crate::sqlite_scheduler::SqliteScheduler;
 use crate::temporal_scheduler::TemporalScheduler;
 
 pub enum SchedulerType {
     Legacy,
+    Sqlite,
     Temporal,
 }
 
@@ -27,6 +29,7 @@ impl SchedulerType {
                 );
                 match scheduler_type.to_lowercase().as_str() {
                     "temporal" => SchedulerType::Temporal,
+                    "sqlite" => SchedulerType::Sqlite,
                     "legacy" => SchedulerType::Legacy,
                     _ => {
                         tracing::warn!(
@@ -51,7 +54,8 @@ impl SchedulerType {
 pub struct SchedulerFactory;
 
 impl SchedulerFactory {
-    /// Create a scheduler instance based on configuration
+    /// Create a scheduler instance based on configuration. `storage_path` is the legacy
+    /// scheduler's JSON file; the SQLite scheduler keeps `schedules.db` beside it.
     pub async fn create(storage_path: PathBuf) -> Result<Arc<dyn SchedulerTrait>, SchedulerError> {
         let scheduler_type = SchedulerType::from_config();
 
@@ -61,6 +65,12 @@ impl SchedulerFactory {
                 let scheduler = Scheduler::new(storage_path).await?;
                 Ok(scheduler as Arc<dyn SchedulerTrait>)
             }
+            SchedulerType::Sqlite => {
+                tracing::info!("Creating SQLite scheduler");
+                let scheduler =
+                    SqliteScheduler::new(storage_path.with_file_name("schedules.db")).await?;
+                Ok(scheduler as Arc<dyn SchedulerTrait>)
+            }
             SchedulerType::Temporal => {
                 tracing::info!("Attempting to create Temporal scheduler");
                 match TemporalScheduler::new().await {
@@ -141,6 +151,14 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_scheduler_type_sqlite() {
+        with_vars([("GOOSE_SCHEDULER_TYPE", Some("SQLite"))], || {
+            let scheduler_type = SchedulerType::from_config();
+            assert!(matches!(scheduler_type, SchedulerType::Sqlite));
+        });
+    }
+
     #[test]
     fn test_scheduler_type_unknown() {
         // Test that with unknown scheduler type, we default to Legacy
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
-use crate::scheduler::{ScheduledJob, SchedulerError};
+use crate::scheduler::{order_by_dependencies, JobNode, ScheduledJob, SchedulerError};
 use crate::session::storage::SessionMetadata;
 
 /// Common trait for all scheduler implementations
@@ -44,4 +44,19 @@ pub trait SchedulerTrait: Send + Sync {
         &self,
         sched_id: &str,
     ) -> Result<Option<(String, DateTime<Utc>)>, SchedulerError>;
+
+    /// Every job with the jobs it depends on, each listed after its dependencies
+    async fn dependency_graph(&self) -> Result<Vec<JobNode>, SchedulerError> {
+        let nodes = self
+            .list_scheduled_jobs()
+            .await?
+            .into_iter()
+            .map(|job| JobNode {
+                id: job.id,
+                depends_on: job.depends_on,
+                last_outcome: None,
+            })
+            .collect();
+        Ok(order_by_dependencies(nodes))
+    }
 }
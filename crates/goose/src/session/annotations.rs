@@ -0,0 +1,138 @@
+use super::storage::{get_path, lock_session, Identifier};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+/// A human note attached to one message in a session, used for review and eval curation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    /// Unique identifier for the annotation
+    pub id: String,
+    /// Index of the annotated message within the session
+    pub message_index: usize,
+    /// Who wrote the annotation, if known
+    pub author: Option<String>,
+    /// Free-text comment
+    pub comment: Option<String>,
+    /// Numeric rating, on whatever scale the reviewer uses
+    pub rating: Option<i32>,
+    /// Labels such as "hallucination" or "good-example"
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Unix timestamp (seconds) when the annotation was created
+    pub created: i64,
+}
+
+/// Annotations are kept next to the session file so the session format itself is unchanged
+fn annotations_path(session_file: &Path) -> Result<PathBuf> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(secure_path.with_extension("annotations.json"))
+}
+
+/// Read every annotation for a session, in the order they were added
+pub fn read_annotations(session_file: &Path) -> Result<Vec<Annotation>> {
+    let path = annotations_path(session_file)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn write_annotations(session_file: &Path, annotations: &[Annotation]) -> Result<()> {
+    let path = annotations_path(session_file)?;
+    let temp_file = path.with_extension("tmp");
+    fs::write(&temp_file, serde_json::to_string_pretty(annotations)?)?;
+    fs::rename(&temp_file, &path)?;
+    Ok(())
+}
+
+/// Append an annotation to a session, waiting for anyone else holding the session
+pub fn add_annotation(session_file: &Path, annotation: Annotation) -> Result<()> {
+    let _lock = lock_session(session_file)?;
+    let mut annotations = read_annotations(session_file)?;
+    annotations.push(annotation);
+    write_annotations(session_file, &annotations)
+}
+
+/// Remove an annotation by id. Returns false if no annotation had that id.
+pub fn delete_annotation(session_file: &Path, annotation_id: &str) -> Result<bool> {
+    let _lock = lock_session(session_file)?;
+    let mut annotations = read_annotations(session_file)?;
+    let before = annotations.len();
+    annotations.retain(|a| a.id != annotation_id);
+    if annotations.len() == before {
+        return Ok(false);
+    }
+    write_annotations(session_file, &annotations)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn annotation(id: &str, message_index: usize) -> Annotation {
+        Annotation {
+            id: id.to_string(),
+            message_index,
+            author: None,
+            comment: Some("looks right".to_string()),
+            rating: Some(5),
+            labels: vec!["good-example".to_string()],
+            created: 0,
+        }
+    }
+
+    #[test]
+    fn test_add_read_delete_annotations() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("annotated.jsonl");
+
+        assert!(read_annotations(&session_file)?.is_empty());
+
+        add_annotation(&session_file, annotation("a", 0))?;
+        add_annotation(&session_file, annotation("b", 3))?;
+        let annotations = read_annotations(&session_file)?;
+        assert_eq!(annotations.len(), 2);
+        assert_eq!(annotations[1].message_index, 3);
+        assert!(dir.path().join("annotated.annotations.json").exists());
+
+        assert!(delete_annotation(&session_file, "a")?);
+        assert!(!delete_annotation(&session_file, "missing")?);
+        let annotations = read_annotations(&session_file)?;
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].id, "b");
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_adds_are_all_kept() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("busy.jsonl");
+
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let session_file = session_file.clone();
+                std::thread::spawn(move || {
+                    add_annotation(&session_file, annotation(&i.to_string(), i))
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap()?;
+        }
+
+        let mut ids: Vec<_> = read_annotations(&session_file)?
+            .into_iter()
+            .map(|a| a.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["0", "1", "2", "3", "4", "5", "6", "7"]);
+        Ok(())
+    }
+}
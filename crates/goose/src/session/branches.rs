@@ -0,0 +1,98 @@
+use super::storage::{get_path, Identifier};
+use crate::conversation::message::Message;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+/// Why messages were cut from a session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BranchReason {
+    /// An earlier user message was edited
+    #[default]
+    Edit,
+    /// The last assistant turn was generated again
+    Retry,
+    /// A run was started again with the settings in its manifest
+    Replay,
+}
+
+/// Messages cut from a session by an edit or a retry, kept so they can be recovered
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscardedBranch {
+    /// Unique identifier for the branch
+    pub id: String,
+    /// Index in the session where the branch started
+    pub message_index: usize,
+    /// The messages from that index on, as they were before being replaced
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub reason: BranchReason,
+    /// Unix timestamp (seconds) when the branch was discarded
+    pub created: i64,
+}
+
+/// Branches are kept next to the session file so the session format itself is unchanged
+fn branches_path(session_file: &Path) -> Result<PathBuf> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(secure_path.with_extension("branches.json"))
+}
+
+/// Read every discarded branch for a session, oldest first
+pub fn read_branches(session_file: &Path) -> Result<Vec<DiscardedBranch>> {
+    let path = branches_path(session_file)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Keep `branch` with the session's other discarded branches
+pub fn save_branch(session_file: &Path, branch: DiscardedBranch) -> Result<()> {
+    let mut branches = read_branches(session_file)?;
+    branches.push(branch);
+    let path = branches_path(session_file)?;
+    let temp_file = path.with_extension("tmp");
+    fs::write(&temp_file, serde_json::to_string_pretty(&branches)?)?;
+    fs::rename(&temp_file, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_save_and_read_branches() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("edited.jsonl");
+
+        assert!(read_branches(&session_file)?.is_empty());
+
+        for (id, index) in [("first", 2), ("second", 0)] {
+            save_branch(
+                &session_file,
+                DiscardedBranch {
+                    id: id.to_string(),
+                    message_index: index,
+                    messages: vec![Message::user().with_text("original prompt")],
+                    reason: BranchReason::Edit,
+                    created: 0,
+                },
+            )?;
+        }
+
+        let branches = read_branches(&session_file)?;
+        assert_eq!(branches.len(), 2);
+        assert_eq!(branches[0].id, "first");
+        assert_eq!(branches[1].message_index, 0);
+        assert_eq!(branches[1].messages[0].as_concat_text(), "original prompt");
+        assert!(dir.path().join("edited.branches.json").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use super::storage::{get_path, Identifier};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// Longest elevation that can be granted in one go
+pub const MAX_ELEVATION: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// A time-boxed grant letting the agent act without asking in one session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Elevation {
+    /// Unix timestamp (seconds) when the grant was made
+    pub granted_at: i64,
+    /// Unix timestamp (seconds) when the session reverts to asking
+    pub expires_at: i64,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    ElevationGranted,
+    ElevationRevoked,
+    ElevationExpired,
+    /// A tool call that ran without confirmation because the session was elevated
+    ElevatedToolCall,
+}
+
+/// One entry of a session's append-only audit log
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEvent {
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub kind: AuditEventKind,
+    pub detail: Option<String>,
+}
+
+fn sidecar_path(session_file: &Path, extension: &str) -> Result<PathBuf> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(secure_path.with_extension(extension))
+}
+
+/// Append an event to the session's audit log
+pub fn record_audit_event(
+    session_file: &Path,
+    kind: AuditEventKind,
+    detail: Option<String>,
+) -> Result<()> {
+    let event = AuditEvent {
+        timestamp: chrono::Utc::now().timestamp(),
+        kind,
+        detail,
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(sidecar_path(session_file, "audit.jsonl")?)?;
+    writeln!(file, "{}", serde_json::to_string(&event)?)?;
+    Ok(())
+}
+
+pub fn read_audit_log(session_file: &Path) -> Result<Vec<AuditEvent>> {
+    let path = sidecar_path(session_file, "audit.jsonl")?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    BufReader::new(fs::File::open(path)?)
+        .lines()
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// Elevate a session for `duration`, capped at [`MAX_ELEVATION`]. Replaces any current grant.
+pub fn grant_elevation(
+    session_file: &Path,
+    duration: Duration,
+    reason: Option<String>,
+) -> Result<Elevation> {
+    let duration = duration.min(MAX_ELEVATION);
+    let granted_at = chrono::Utc::now().timestamp();
+    let elevation = Elevation {
+        granted_at,
+        expires_at: granted_at + duration.as_secs() as i64,
+        reason: reason.clone(),
+    };
+    fs::write(
+        sidecar_path(session_file, "elevation.json")?,
+        serde_json::to_string(&elevation)?,
+    )?;
+
+    let minutes = duration.as_secs() / 60;
+    let detail = match reason {
+        Some(reason) => format!("{} minutes: {}", minutes, reason),
+        None => format!("{} minutes", minutes),
+    };
+    record_audit_event(session_file, AuditEventKind::ElevationGranted, Some(detail))?;
+    Ok(elevation)
+}
+
+/// End an elevation early. Returns false if the session was not elevated.
+pub fn revoke_elevation(session_file: &Path) -> Result<bool> {
+    if active_elevation(session_file)?.is_none() {
+        return Ok(false);
+    }
+    fs::remove_file(sidecar_path(session_file, "elevation.json")?)?;
+    record_audit_event(session_file, AuditEventKind::ElevationRevoked, None)?;
+    Ok(true)
+}
+
+/// The session's current elevation, if it has not expired. An expired grant is
+/// cleared and logged the first time it is noticed.
+pub fn active_elevation(session_file: &Path) -> Result<Option<Elevation>> {
+    let path = sidecar_path(session_file, "elevation.json")?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let elevation: Elevation = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    if elevation.expires_at > chrono::Utc::now().timestamp() {
+        return Ok(Some(elevation));
+    }
+
+    fs::remove_file(&path)?;
+    record_audit_event(session_file, AuditEventKind::ElevationExpired, None)?;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_grant_and_revoke() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("elevated.jsonl");
+
+        assert!(active_elevation(&session_file)?.is_none());
+        let elevation = grant_elevation(
+            &session_file,
+            Duration::from_secs(30 * 60),
+            Some("bulk refactor".to_string()),
+        )?;
+        assert_eq!(elevation.expires_at - elevation.granted_at, 30 * 60);
+        assert!(active_elevation(&session_file)?.is_some());
+
+        assert!(revoke_elevation(&session_file)?);
+        assert!(!revoke_elevation(&session_file)?);
+
+        let kinds: Vec<_> = read_audit_log(&session_file)?
+            .into_iter()
+            .map(|e| e.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                AuditEventKind::ElevationGranted,
+                AuditEventKind::ElevationRevoked
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_expiry_is_capped_and_logged() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("expiring.jsonl");
+
+        let elevation = grant_elevation(&session_file, Duration::from_secs(24 * 60 * 60), None)?;
+        assert_eq!(
+            elevation.expires_at - elevation.granted_at,
+            MAX_ELEVATION.as_secs() as i64
+        );
+
+        grant_elevation(&session_file, Duration::ZERO, None)?;
+        assert!(active_elevation(&session_file)?.is_none());
+        let log = read_audit_log(&session_file)?;
+        assert_eq!(log.last().unwrap().kind, AuditEventKind::ElevationExpired);
+        Ok(())
+    }
+}
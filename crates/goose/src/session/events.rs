@@ -0,0 +1,204 @@
+use super::storage::{get_path, Identifier};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEventKind {
+    ToolStarted,
+    ToolFinished,
+    /// The run was started again because its success checks failed
+    Retry,
+    /// The conversation was summarized to fit the context window
+    Compaction,
+    /// Responses started coming from a different model
+    ModelSwitch,
+    /// A tool call was allowed or denied, by the user or by permission settings
+    PermissionDecision,
+    /// The agent was found going in circles and was nudged or paused
+    Intervention,
+    /// The run stopped at its turn, time or tool call limit
+    LimitReached,
+    /// The model reviewed its final answer against the recipe's criteria
+    Critique,
+    /// A model call used tokens
+    Usage,
+}
+
+/// Tokens one model call used
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenUsage {
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+}
+
+/// One entry of a session's event log, kept alongside its messages
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    /// Unix timestamp (milliseconds)
+    pub timestamp: i64,
+    pub kind: SessionEventKind,
+    /// Id of the tool call the event is about, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Name of the tool the event is about, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Tokens used, for usage events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+}
+
+impl SessionEvent {
+    pub fn new(kind: SessionEventKind) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            kind,
+            tool_call_id: None,
+            tool_name: None,
+            detail: None,
+            usage: None,
+        }
+    }
+
+    pub fn with_tool(mut self, call_id: impl Into<String>, name: impl Into<String>) -> Self {
+        self.tool_call_id = Some(call_id.into());
+        self.tool_name = Some(name.into());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_usage(mut self, usage: TokenUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Whether the event passes every condition set in `filter`
+    pub fn matches(&self, filter: &EventFilter) -> bool {
+        if filter.kind.is_some_and(|kind| kind != self.kind) {
+            return false;
+        }
+        if filter.since.is_some_and(|since| self.timestamp < since) {
+            return false;
+        }
+        let Some(text) = filter.text.as_deref().map(str::to_lowercase) else {
+            return true;
+        };
+        [&self.tool_call_id, &self.tool_name, &self.detail]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&text))
+    }
+}
+
+/// Conditions for searching a session's events; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub kind: Option<SessionEventKind>,
+    /// Case-insensitive text to find in the tool call id, tool name or detail
+    pub text: Option<String>,
+    /// Only events at or after this Unix timestamp (milliseconds)
+    pub since: Option<i64>,
+}
+
+fn events_path(session_file: &Path) -> Result<PathBuf> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(secure_path.with_extension("events.jsonl"))
+}
+
+/// Append an event to the session's event log
+pub fn record_event(session_file: &Path, event: &SessionEvent) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(events_path(session_file)?)?;
+    writeln!(file, "{}", serde_json::to_string(event)?)?;
+    Ok(())
+}
+
+/// The session's events matching `filter`, oldest first
+pub fn read_events(session_file: &Path, filter: &EventFilter) -> Result<Vec<SessionEvent>> {
+    let path = events_path(session_file)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut events = Vec::new();
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let event: SessionEvent = serde_json::from_str(&line?)?;
+        if event.matches(filter) {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_and_search_events() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("post-mortem.jsonl");
+
+        assert!(read_events(&session_file, &EventFilter::default())?.is_empty());
+
+        let started = SessionEvent::new(SessionEventKind::ToolStarted).with_tool("call_1", "shell");
+        let finished = SessionEvent::new(SessionEventKind::ToolFinished)
+            .with_tool("call_1", "shell")
+            .with_detail("error: exit status 1");
+        let switch = SessionEvent::new(SessionEventKind::ModelSwitch).with_detail("gpt-4o -> o3");
+        for event in [&started, &finished, &switch] {
+            record_event(&session_file, event)?;
+        }
+
+        assert_eq!(
+            read_events(&session_file, &EventFilter::default())?.len(),
+            3
+        );
+
+        let shell = read_events(
+            &session_file,
+            &EventFilter {
+                text: Some("SHELL".to_string()),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(shell.len(), 2);
+
+        let failures = read_events(
+            &session_file,
+            &EventFilter {
+                kind: Some(SessionEventKind::ToolFinished),
+                text: Some("error".to_string()),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].tool_call_id.as_deref(), Some("call_1"));
+
+        let later = read_events(
+            &session_file,
+            &EventFilter {
+                since: Some(switch.timestamp + 1),
+                ..Default::default()
+            },
+        )?;
+        assert!(later.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use super::storage::{get_path, list_sessions, read_messages, Identifier};
+use crate::conversation::message::Message;
+use anyhow::Result;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedbackRating {
+    Up,
+    Down,
+}
+
+/// A thumbs up/down on a whole session or on one message in it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Feedback {
+    /// Index of the rated message, or None when rating the whole session
+    pub message_index: Option<usize>,
+    pub rating: FeedbackRating,
+    /// Optional free-text explanation
+    pub comment: Option<String>,
+    /// Unix timestamp (seconds) when the feedback was last updated
+    pub created: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportMessage {
+    role: String,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rating: Option<FeedbackRating>,
+}
+
+/// One line of the JSONL export, in the chat format used by most fine-tuning and eval tools
+#[derive(Debug, Serialize)]
+struct ExportRecord {
+    session_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rating: Option<FeedbackRating>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    messages: Vec<ExportMessage>,
+}
+
+fn feedback_path(session_file: &Path) -> Result<PathBuf> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(secure_path.with_extension("feedback.json"))
+}
+
+/// Read all feedback recorded for a session
+pub fn read_feedback(session_file: &Path) -> Result<Vec<Feedback>> {
+    let path = feedback_path(session_file)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Record feedback, replacing any earlier feedback for the same message (or the session)
+pub fn record_feedback(session_file: &Path, feedback: Feedback) -> Result<()> {
+    let mut entries = read_feedback(session_file)?;
+    entries.retain(|f| f.message_index != feedback.message_index);
+    entries.push(feedback);
+
+    let path = feedback_path(session_file)?;
+    let temp_file = path.with_extension("tmp");
+    fs::write(&temp_file, serde_json::to_string_pretty(&entries)?)?;
+    fs::rename(&temp_file, &path)?;
+    Ok(())
+}
+
+fn export_record(session_id: String, messages: &[Message], feedback: &[Feedback]) -> ExportRecord {
+    let session_feedback = feedback.iter().find(|f| f.message_index.is_none());
+    let messages = messages
+        .iter()
+        .enumerate()
+        .filter_map(|(i, message)| {
+            let content = message.as_concat_text();
+            if content.is_empty() {
+                return None;
+            }
+            let role = match message.role {
+                Role::User => "user",
+                Role::Assistant => "assistant",
+            };
+            Some(ExportMessage {
+                role: role.to_string(),
+                content,
+                rating: feedback
+                    .iter()
+                    .find(|f| f.message_index == Some(i))
+                    .map(|f| f.rating),
+            })
+        })
+        .collect();
+
+    ExportRecord {
+        session_id,
+        rating: session_feedback.map(|f| f.rating),
+        comment: session_feedback.and_then(|f| f.comment.clone()),
+        messages,
+    }
+}
+
+/// Export every session that has feedback as JSONL, one session per line.
+///
+/// With a rating filter, only sessions rated that way as a whole are included, which is
+/// the usual way to pull out good sessions for few-shot examples.
+pub fn export_feedback_jsonl(rating: Option<FeedbackRating>) -> Result<String> {
+    let mut output = String::new();
+    for (session_id, path) in list_sessions()? {
+        let feedback = read_feedback(&path)?;
+        if feedback.is_empty() {
+            continue;
+        }
+        if let Some(rating) = rating {
+            let session_rating = feedback
+                .iter()
+                .find(|f| f.message_index.is_none())
+                .map(|f| f.rating);
+            if session_rating != Some(rating) {
+                continue;
+            }
+        }
+
+        let messages = read_messages(&path)?;
+        let record = export_record(session_id, messages.messages(), &feedback);
+        output.push_str(&serde_json::to_string(&record)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn feedback(message_index: Option<usize>, rating: FeedbackRating) -> Feedback {
+        Feedback {
+            message_index,
+            rating,
+            comment: None,
+            created: 0,
+        }
+    }
+
+    #[test]
+    fn test_record_feedback_replaces_previous() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("rated.jsonl");
+
+        record_feedback(&session_file, feedback(Some(1), FeedbackRating::Down))?;
+        record_feedback(&session_file, feedback(None, FeedbackRating::Up))?;
+        record_feedback(&session_file, feedback(Some(1), FeedbackRating::Up))?;
+
+        let entries = read_feedback(&session_file)?;
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|f| f.rating == FeedbackRating::Up));
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_record() {
+        let messages = vec![
+            Message::user().with_text("What is 2 + 2?"),
+            Message::assistant().with_text("4"),
+        ];
+        let mut session_feedback = feedback(None, FeedbackRating::Up);
+        session_feedback.comment = Some("concise".to_string());
+        let entries = vec![session_feedback, feedback(Some(1), FeedbackRating::Up)];
+
+        let record = export_record("s1".to_string(), &messages, &entries);
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["rating"], "up");
+        assert_eq!(json["comment"], "concise");
+        assert_eq!(json["messages"][0]["role"], "user");
+        assert!(json["messages"][0].get("rating").is_none());
+        assert_eq!(json["messages"][1]["content"], "4");
+        assert_eq!(json["messages"][1]["rating"], "up");
+    }
+}
@@ -0,0 +1,184 @@
+use super::storage::ensure_session_dir;
+use crate::agents::PendingApproval;
+use anyhow::Result;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// How long a handoff token can be redeemed for
+pub const HANDOFF_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Letters and digits that can't be mistaken for each other when typed from another screen
+const TOKEN_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const TOKEN_LENGTH: usize = 8;
+
+/// A live session offered to another client, redeemed once with its token
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Handoff {
+    /// Single-use code, e.g. `K3PQ-X2MA`
+    pub token: String,
+    pub session_id: String,
+    /// The session file to continue
+    #[schema(value_type = String)]
+    pub session_path: PathBuf,
+    #[schema(value_type = String)]
+    pub working_dir: PathBuf,
+    /// Tool calls the session was waiting on when it was handed off
+    pub pending_approvals: Vec<PendingApproval>,
+    /// Unix timestamp (seconds) after which the token no longer works
+    pub expires_at: i64,
+}
+
+/// Outstanding handoffs live in one file shared by every goose process of the user, so a
+/// token issued by the desktop server can be redeemed by the CLI
+fn handoffs_path() -> Result<PathBuf> {
+    Ok(ensure_session_dir()?.join("handoffs.json"))
+}
+
+fn read_handoffs(path: &Path) -> Result<HashMap<String, Handoff>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn write_handoffs(path: &Path, handoffs: &HashMap<String, Handoff>) -> Result<()> {
+    let temp_file = path.with_extension("tmp");
+    fs::write(&temp_file, serde_json::to_string_pretty(handoffs)?)?;
+    fs::rename(&temp_file, path)?;
+    Ok(())
+}
+
+fn generate_token() -> String {
+    let mut rng = rand::thread_rng();
+    (0..TOKEN_LENGTH)
+        .map(|_| TOKEN_ALPHABET[rng.gen_range(0..TOKEN_ALPHABET.len())] as char)
+        .collect()
+}
+
+/// Tokens are shown as `XXXX-XXXX` but accepted in any case, with or without the dash
+fn normalize_token(token: &str) -> String {
+    token
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// `XXXXXXXX` as `XXXX-XXXX`, for showing to people
+pub fn display_token(token: &str) -> String {
+    let (first, second) = token.split_at(token.len() / 2);
+    format!("{}-{}", first, second)
+}
+
+fn issue(
+    path: &Path,
+    session_id: String,
+    session_path: PathBuf,
+    working_dir: PathBuf,
+    pending_approvals: Vec<PendingApproval>,
+) -> Result<Handoff> {
+    let now = chrono::Utc::now().timestamp();
+    let mut handoffs = read_handoffs(path)?;
+    handoffs.retain(|_, handoff| handoff.expires_at > now);
+
+    let token = std::iter::repeat_with(generate_token)
+        .find(|token| !handoffs.contains_key(token))
+        .expect("token generation is endless");
+    let handoff = Handoff {
+        token: token.clone(),
+        session_id,
+        session_path,
+        working_dir,
+        pending_approvals,
+        expires_at: now + HANDOFF_TTL.as_secs() as i64,
+    };
+    handoffs.insert(token, handoff.clone());
+    write_handoffs(path, &handoffs)?;
+    Ok(handoff)
+}
+
+fn redeem(path: &Path, token: &str) -> Result<Option<Handoff>> {
+    let now = chrono::Utc::now().timestamp();
+    let mut handoffs = read_handoffs(path)?;
+    handoffs.retain(|_, handoff| handoff.expires_at > now);
+    let handoff = handoffs.remove(&normalize_token(token));
+    write_handoffs(path, &handoffs)?;
+    Ok(handoff)
+}
+
+/// Offer a session to another client for [`HANDOFF_TTL`]
+pub fn create_handoff(
+    session_id: String,
+    session_path: PathBuf,
+    working_dir: PathBuf,
+    pending_approvals: Vec<PendingApproval>,
+) -> Result<Handoff> {
+    issue(
+        &handoffs_path()?,
+        session_id,
+        session_path,
+        working_dir,
+        pending_approvals,
+    )
+}
+
+/// Take the handoff for `token`. Each token works once; unknown and expired tokens give None.
+pub fn redeem_handoff(token: &str) -> Result<Option<Handoff>> {
+    redeem(&handoffs_path()?, token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_handoff_is_redeemed_once() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("handoffs.json");
+
+        let handoff = issue(
+            &path,
+            "20250101_1".to_string(),
+            dir.path().join("20250101_1.jsonl"),
+            dir.path().to_path_buf(),
+            Vec::new(),
+        )?;
+        assert_eq!(handoff.token.len(), TOKEN_LENGTH);
+
+        let typed = display_token(&handoff.token).to_lowercase();
+        let redeemed = redeem(&path, &typed)?.expect("token should be redeemable");
+        assert_eq!(redeemed.session_id, "20250101_1");
+        assert!(redeem(&path, &handoff.token)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_expired_handoff_is_dropped() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("handoffs.json");
+
+        let mut handoff = issue(
+            &path,
+            "old".to_string(),
+            dir.path().join("old.jsonl"),
+            dir.path().to_path_buf(),
+            Vec::new(),
+        )?;
+        handoff.expires_at = chrono::Utc::now().timestamp() - 1;
+        write_handoffs(
+            &path,
+            &HashMap::from([(handoff.token.clone(), handoff.clone())]),
+        )?;
+
+        assert!(redeem(&path, &handoff.token)?.is_none());
+        assert!(read_handoffs(&path)?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use super::storage::{get_path, Identifier, SessionMetadata};
+use crate::config::{Config, ConfigError};
+use anyhow::Result;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use once_cell::sync::OnceCell;
+use rand::RngCore;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+use utoipa::ToSchema;
+
+/// Keychain entry holding the key session checksums are signed with. Without the key a
+/// checksum can't be recomputed, so editing the session file and its checksum together is
+/// still detected.
+const INTEGRITY_KEY_SECRET: &str = "GOOSE_SESSION_INTEGRITY_KEY";
+
+static INTEGRITY_KEY: OnceCell<Option<Vec<u8>>> = OnceCell::new();
+
+fn load_or_create_key(config: &Config) -> Result<Vec<u8>> {
+    match config.get_secret::<String>(INTEGRITY_KEY_SECRET) {
+        Ok(encoded) => Ok(base64::engine::general_purpose::STANDARD.decode(encoded)?),
+        Err(ConfigError::NotFound(_)) => {
+            let mut key = vec![0u8; 32];
+            rand::thread_rng().fill_bytes(&mut key);
+            let encoded = base64::engine::general_purpose::STANDARD.encode(&key);
+            config.set_secret(INTEGRITY_KEY_SECRET, Value::String(encoded))?;
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The key session checksums are signed with, created on first use. Sessions are saved without
+/// a checksum when the keychain can't be used.
+pub fn integrity_key() -> Option<&'static [u8]> {
+    INTEGRITY_KEY
+        .get_or_init(|| match load_or_create_key(Config::global()) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                tracing::warn!(
+                    "Session checksums are off, the signing key is unavailable: {}",
+                    e
+                );
+                None
+            }
+        })
+        .as_deref()
+}
+
+/// The HMAC of a message line, chained to the one of the message before it. The last one in
+/// the chain is the session checksum, so changing, removing or reordering any message
+/// changes it.
+pub fn chain_hash(key: &[u8], previous: &str, line: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(previous.as_bytes());
+    mac.update(b"\n");
+    mac.update(line.as_bytes());
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// Hash of each message line, in order, each chained to the one before
+pub fn message_hashes<'a>(key: &[u8], lines: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut previous = String::new();
+    lines
+        .into_iter()
+        .map(|line| {
+            previous = chain_hash(key, &previous, line);
+            previous.clone()
+        })
+        .collect()
+}
+
+/// Checksum of a session whose messages are serialized as `lines`
+pub fn session_checksum<'a>(key: &[u8], lines: impl IntoIterator<Item = &'a str>) -> String {
+    message_hashes(key, lines).pop().unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum IntegrityStatus {
+    /// The messages are as goose last saved them
+    Valid,
+    /// The messages changed after goose last saved them
+    Modified,
+    /// The session was saved without a checksum, or the signing key is unavailable, so it
+    /// can't be checked
+    Unsigned,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub status: IntegrityStatus,
+    /// Checksum recorded when the session was last saved
+    pub expected_checksum: Option<String>,
+    /// Checksum of the messages as they are now
+    pub actual_checksum: String,
+    pub message_count: usize,
+    /// Hash of each message, chained to the hash of the one before
+    pub message_hashes: Vec<String>,
+}
+
+/// Check a session's messages against the checksum saved in its metadata
+pub fn verify_session(session_file: &Path) -> Result<IntegrityReport> {
+    verify_with_key(session_file, integrity_key())
+}
+
+fn verify_with_key(session_file: &Path, key: Option<&[u8]>) -> Result<IntegrityReport> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    let file = fs::File::open(&secure_path)?;
+    let mut lines = io::BufReader::new(file).lines();
+
+    let mut message_lines = Vec::new();
+    let mut expected_checksum = None;
+    if let Some(first) = lines.next() {
+        let first = first?;
+        match serde_json::from_str::<SessionMetadata>(&first) {
+            Ok(metadata) => expected_checksum = metadata.checksum,
+            Err(_) => message_lines.push(first),
+        }
+    }
+    for line in lines {
+        message_lines.push(line?);
+    }
+
+    let message_hashes = match key {
+        Some(key) => message_hashes(key, message_lines.iter().map(String::as_str)),
+        None => Vec::new(),
+    };
+    let actual_checksum = message_hashes.last().cloned().unwrap_or_default();
+    let status = match (&expected_checksum, key) {
+        (Some(expected), Some(_)) if *expected == actual_checksum => IntegrityStatus::Valid,
+        (Some(_), Some(_)) => IntegrityStatus::Modified,
+        _ => IntegrityStatus::Unsigned,
+    };
+    Ok(IntegrityReport {
+        status,
+        expected_checksum,
+        actual_checksum,
+        message_count: message_lines.len(),
+        message_hashes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::message::Message;
+    use tempfile::tempdir;
+
+    const KEY: &[u8] = b"test signing key";
+
+    fn write_signed(session_file: &Path, key: &[u8], messages: &[Message]) -> Result<()> {
+        let lines = messages
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut metadata = SessionMetadata::new(session_file.parent().unwrap().to_path_buf());
+        metadata.checksum = Some(session_checksum(key, lines.iter().map(String::as_str)));
+        let mut content = serde_json::to_string(&metadata)?;
+        for line in lines {
+            content.push('\n');
+            content.push_str(&line);
+        }
+        fs::write(session_file, content)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampering_is_detected() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("audited.jsonl");
+        let messages = [
+            Message::user().with_text("transfer 10 dollars"),
+            Message::assistant().with_text("done"),
+        ];
+        write_signed(&session_file, KEY, &messages)?;
+
+        let report = verify_with_key(&session_file, Some(KEY))?;
+        assert_eq!(report.status, IntegrityStatus::Valid);
+        assert_eq!(report.message_count, 2);
+        assert_eq!(report.message_hashes.len(), 2);
+
+        let content = fs::read_to_string(&session_file)?;
+        fs::write(&session_file, content.replace("10 dollars", "1000 dollars"))?;
+        let report = verify_with_key(&session_file, Some(KEY))?;
+        assert_eq!(report.status, IntegrityStatus::Modified);
+        assert_ne!(
+            report.expected_checksum.as_deref(),
+            Some(report.actual_checksum.as_str())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_checksum_needs_the_key() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("forged.jsonl");
+        // Rewriting the messages and their checksum without the key doesn't pass
+        write_signed(
+            &session_file,
+            b"someone else's key",
+            &[Message::user().with_text("transfer 1000 dollars")],
+        )?;
+        assert_eq!(
+            verify_with_key(&session_file, Some(KEY))?.status,
+            IntegrityStatus::Modified
+        );
+        assert_eq!(
+            verify_with_key(&session_file, None)?.status,
+            IntegrityStatus::Unsigned
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sessions_without_checksum_are_unsigned() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("legacy.jsonl");
+        fs::write(
+            &session_file,
+            "{\"description\":\"old\",\"message_count\":0}\n",
+        )?;
+        assert_eq!(
+            verify_with_key(&session_file, Some(KEY))?.status,
+            IntegrityStatus::Unsigned
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_chain_depends_on_order() {
+        assert_ne!(
+            session_checksum(KEY, ["a", "b"]),
+            session_checksum(KEY, ["b", "a"])
+        );
+        assert_ne!(
+            session_checksum(KEY, ["a"]),
+            session_checksum(b"other", ["a"])
+        );
+        assert_eq!(session_checksum(KEY, std::iter::empty()), "");
+    }
+}
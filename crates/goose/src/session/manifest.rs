@@ -0,0 +1,263 @@
+use super::storage::{get_path, Identifier};
+use crate::agents::Agent;
+use crate::conversation::message::Message;
+use anyhow::Result;
+use rmcp::model::Role;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+/// An extension that was loaded for a run, with the version its server reported
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ExtensionVersion {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Everything about a run that can change how it behaves, so a later run can be compared
+/// against it or started again with the same settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RunManifest {
+    pub run_id: String,
+    /// Unix timestamp (seconds) when the run started
+    pub created: i64,
+    pub goose_version: String,
+    /// Name of the provider the run used, empty if it wasn't made through `providers::create`
+    pub provider: String,
+    pub model: String,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<i32>,
+    pub context_limit: Option<usize>,
+    pub toolshim: bool,
+    pub toolshim_model: Option<String>,
+    /// Sampling seed, for providers that support one
+    pub seed: Option<u64>,
+    /// SHA-256 of the rendered system prompt, including extension instructions; unset if the
+    /// run stopped before its first request was prepared
+    pub system_prompt_hash: Option<String>,
+    /// Number of messages the run started from
+    pub message_count: usize,
+    /// SHA-256 of the text of each user prompt the run started from, in order
+    pub prompt_hashes: Vec<String>,
+    pub extensions: Vec<ExtensionVersion>,
+    /// The run this one replayed, if it was started from another run's manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_of: Option<String>,
+}
+
+fn sha256(text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Hashes of the prompts a user typed, skipping tool results sent back in the user role
+pub fn prompt_hashes(messages: &[Message]) -> Vec<String> {
+    messages
+        .iter()
+        .filter(|message| message.role == Role::User && !message.is_tool_response())
+        .map(|message| sha256(&message.as_concat_text()))
+        .collect()
+}
+
+impl RunManifest {
+    /// The manifest of a run in `session` that started at `created` from `messages`. Taken
+    /// once the run is over, from the provider and system prompt the run actually used.
+    pub async fn capture(
+        agent: &Agent,
+        session: &Identifier,
+        run_id: &str,
+        created: i64,
+        messages: &[Message],
+    ) -> Result<Self> {
+        let provider = agent.provider().await?;
+        let model_config = provider.get_model_config();
+        let system_prompt = agent.take_system_prompt(session).await;
+        let extensions = agent
+            .extension_versions()
+            .await
+            .into_iter()
+            .map(|(name, version)| ExtensionVersion { name, version })
+            .collect();
+        Ok(Self {
+            run_id: run_id.to_string(),
+            created,
+            goose_version: env!("CARGO_PKG_VERSION").to_string(),
+            provider: provider.get_provider_name().unwrap_or_default(),
+            model: model_config.model_name,
+            temperature: model_config.temperature,
+            max_tokens: model_config.max_tokens,
+            context_limit: model_config.context_limit,
+            toolshim: model_config.toolshim,
+            toolshim_model: model_config.toolshim_model,
+            seed: model_config.seed,
+            system_prompt_hash: system_prompt.as_deref().map(sha256),
+            message_count: messages.len(),
+            prompt_hashes: prompt_hashes(messages),
+            extensions,
+            replay_of: None,
+        })
+    }
+
+    /// What differs between this run and `other`, one line per difference
+    pub fn differences(&self, other: &RunManifest) -> Vec<String> {
+        fn shown<T: ToString>(value: &Option<T>, unset: &str) -> String {
+            value
+                .as_ref()
+                .map(|value| value.to_string())
+                .unwrap_or_else(|| unset.to_string())
+        }
+        fn loaded(extensions: &[ExtensionVersion], name: &str) -> String {
+            match extensions.iter().find(|extension| extension.name == name) {
+                Some(extension) => shown(&extension.version, "unknown version"),
+                None => "not loaded".to_string(),
+            }
+        }
+
+        let mut fields = vec![
+            (
+                "goose version".to_string(),
+                self.goose_version.clone(),
+                other.goose_version.clone(),
+            ),
+            (
+                "provider".to_string(),
+                self.provider.clone(),
+                other.provider.clone(),
+            ),
+            ("model".to_string(), self.model.clone(), other.model.clone()),
+            (
+                "temperature".to_string(),
+                shown(&self.temperature, "default"),
+                shown(&other.temperature, "default"),
+            ),
+            (
+                "seed".to_string(),
+                shown(&self.seed, "none"),
+                shown(&other.seed, "none"),
+            ),
+            (
+                "system prompt".to_string(),
+                shown(&self.system_prompt_hash, "unknown"),
+                shown(&other.system_prompt_hash, "unknown"),
+            ),
+        ];
+        let mut names: Vec<&str> = self
+            .extensions
+            .iter()
+            .chain(&other.extensions)
+            .map(|extension| extension.name.as_str())
+            .collect();
+        names.sort();
+        names.dedup();
+        for name in names {
+            fields.push((
+                format!("extension {}", name),
+                loaded(&self.extensions, name),
+                loaded(&other.extensions, name),
+            ));
+        }
+
+        fields
+            .into_iter()
+            .filter(|(_, before, after)| before != after)
+            .map(|(what, before, after)| format!("{}: {} -> {}", what, before, after))
+            .collect()
+    }
+}
+
+/// Manifests are kept next to the session file, like discarded branches
+fn manifests_path(session_file: &Path) -> Result<PathBuf> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(secure_path.with_extension("manifests.json"))
+}
+
+/// Manifests of every run in a session, oldest first
+pub fn read_manifests(session_file: &Path) -> Result<Vec<RunManifest>> {
+    let path = manifests_path(session_file)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// Keep `manifest` with the manifests of the session's earlier runs
+pub fn save_manifest(session_file: &Path, manifest: RunManifest) -> Result<()> {
+    let mut manifests = read_manifests(session_file)?;
+    manifests.push(manifest);
+    let path = manifests_path(session_file)?;
+    let temp_file = path.with_extension("tmp");
+    fs::write(&temp_file, serde_json::to_string_pretty(&manifests)?)?;
+    fs::rename(&temp_file, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn manifest(run_id: &str) -> RunManifest {
+        RunManifest {
+            run_id: run_id.to_string(),
+            created: 0,
+            goose_version: "1.0.0".to_string(),
+            provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            temperature: Some(0.0),
+            max_tokens: None,
+            context_limit: None,
+            toolshim: false,
+            toolshim_model: None,
+            seed: Some(7),
+            system_prompt_hash: Some("abc".to_string()),
+            message_count: 1,
+            prompt_hashes: prompt_hashes(&[Message::user().with_text("hello")]),
+            extensions: vec![ExtensionVersion {
+                name: "developer".to_string(),
+                version: Some("1.0.0".to_string()),
+            }],
+            replay_of: None,
+        }
+    }
+
+    #[test]
+    fn test_save_and_read_manifests() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("reproducible.jsonl");
+        assert!(read_manifests(&session_file)?.is_empty());
+
+        save_manifest(&session_file, manifest("first"))?;
+        save_manifest(&session_file, manifest("second"))?;
+        let manifests = read_manifests(&session_file)?;
+        assert_eq!(manifests.len(), 2);
+        assert_eq!(manifests[1].run_id, "second");
+        assert_eq!(manifests[0].prompt_hashes, vec![sha256("hello")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_differences() {
+        let before = manifest("before");
+        let mut after = manifest("after");
+        assert!(before.differences(&after).is_empty());
+
+        after.model = "gpt-4o-mini".to_string();
+        after.extensions[0].version = Some("1.1.0".to_string());
+        after.extensions.push(ExtensionVersion {
+            name: "memory".to_string(),
+            version: None,
+        });
+        assert_eq!(
+            before.differences(&after),
+            vec![
+                "model: gpt-4o -> gpt-4o-mini",
+                "extension developer: 1.0.0 -> 1.1.0",
+                "extension memory: not loaded -> unknown version",
+            ]
+        );
+    }
+}
@@ -1,12 +1,33 @@
+pub mod annotations;
+pub mod attachments;
+pub mod branches;
+pub mod elevation;
+pub mod events;
+pub mod feedback;
+pub mod handoff;
 pub mod info;
+pub mod integrity;
+pub mod manifest;
+pub mod pins;
 pub mod storage;
+pub mod sync;
 
 // Re-export common session types and functions
 pub use storage::{
-    ensure_session_dir, generate_description, generate_description_with_schedule_id,
-    generate_session_id, get_most_recent_session, get_path, list_sessions, persist_messages,
-    persist_messages_with_schedule_id, read_messages, read_metadata, update_metadata, Identifier,
-    SessionMetadata,
+    configured_session_roots, ensure_session_dir, generate_description,
+    generate_description_with_schedule_id, generate_session_id, get_most_recent_session, get_path,
+    get_path_for_working_dir, list_sessions, lock_session, persist_messages,
+    persist_messages_with_schedule_id, read_messages, read_metadata, session_dirs, update_metadata,
+    Identifier, ResourceAttachment, SessionLock, SessionMetadata, SessionRoot, SESSION_ROOTS_KEY,
 };
 
+pub use annotations::Annotation;
+pub use branches::{BranchReason, DiscardedBranch};
+pub use elevation::{AuditEvent, AuditEventKind, Elevation};
+pub use events::{SessionEvent, SessionEventKind, TokenUsage};
+pub use feedback::{Feedback, FeedbackRating};
+pub use handoff::Handoff;
 pub use info::{get_valid_sorted_sessions, SessionInfo};
+pub use integrity::{IntegrityReport, IntegrityStatus};
+pub use manifest::{ExtensionVersion, RunManifest};
+pub use pins::{Pin, PinnedItem};
@@ -0,0 +1,263 @@
+use super::storage::{get_path, Identifier};
+use crate::conversation::message::{Message, MessageContent};
+use crate::conversation::Conversation;
+use crate::utils::safe_truncate;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use utoipa::ToSchema;
+
+/// Pinned files are read fresh every time they are re-included, up to this many characters
+const MAX_PINNED_FILE_CHARS: usize = 50_000;
+
+/// Something the session must keep in context after compaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PinnedItem {
+    /// A file inside the session's working directory, relative to it
+    File {
+        path: String,
+    },
+    Note {
+        text: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Pin {
+    pub id: String,
+    #[serde(flatten)]
+    pub item: PinnedItem,
+    /// Unix timestamp (seconds) when the item was pinned
+    pub created: i64,
+}
+
+/// Whether `path` stays inside the directory it is relative to, going by its text alone.
+/// Symlinks are only resolved when the file is read.
+pub fn is_relative_inside(path: &str) -> bool {
+    Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// The pinned file at `path`, which must still be inside `working_dir` once symlinks are
+/// resolved; a pin must not become a way to read arbitrary files into the conversation
+fn pinned_file(working_dir: &Path, path: &str) -> Result<PathBuf> {
+    if !is_relative_inside(path) {
+        anyhow::bail!("it is not inside the working directory");
+    }
+    let root = working_dir.canonicalize()?;
+    let file = root.join(path).canonicalize()?;
+    if !file.starts_with(&root) {
+        anyhow::bail!("it is not inside the working directory");
+    }
+    Ok(file)
+}
+
+/// Pins are kept next to the session file, like discarded branches
+fn pins_path(session_file: &Path) -> Result<PathBuf> {
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    Ok(secure_path.with_extension("pins.json"))
+}
+
+/// Pins of a session, oldest first
+pub fn read_pins(session_file: &Path) -> Result<Vec<Pin>> {
+    let path = pins_path(session_file)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+fn write_pins(session_file: &Path, pins: &[Pin]) -> Result<()> {
+    let path = pins_path(session_file)?;
+    let temp_file = path.with_extension("tmp");
+    fs::write(&temp_file, serde_json::to_string_pretty(pins)?)?;
+    fs::rename(&temp_file, &path)?;
+    Ok(())
+}
+
+/// Pin `item` to the session; pinning the same item twice returns the existing pin
+pub fn add_pin(session_file: &Path, item: PinnedItem) -> Result<Pin> {
+    let mut pins = read_pins(session_file)?;
+    if let Some(existing) = pins.iter().find(|pin| pin.item == item) {
+        return Ok(existing.clone());
+    }
+    let pin = Pin {
+        id: uuid::Uuid::new_v4().to_string(),
+        item,
+        created: chrono::Utc::now().timestamp(),
+    };
+    pins.push(pin.clone());
+    write_pins(session_file, &pins)?;
+    Ok(pin)
+}
+
+/// Unpin the item with `id`, returning whether there was one
+pub fn remove_pin(session_file: &Path, id: &str) -> Result<bool> {
+    let mut pins = read_pins(session_file)?;
+    let before = pins.len();
+    pins.retain(|pin| pin.id != id);
+    if pins.len() == before {
+        return Ok(false);
+    }
+    write_pins(session_file, &pins)?;
+    Ok(true)
+}
+
+/// The pinned items rendered for the model; files are read as they are now
+fn render_pins(pins: &[Pin], working_dir: &Path) -> String {
+    let mut text = String::from(
+        "The user pinned these items to the session so they stay in context. They were \
+         re-included after the conversation was summarized:",
+    );
+    for pin in pins {
+        match &pin.item {
+            PinnedItem::File { path } => {
+                let content = pinned_file(working_dir, path)
+                    .and_then(|file| Ok(fs::read_to_string(file)?))
+                    .map(|content| safe_truncate(&content, MAX_PINNED_FILE_CHARS))
+                    .unwrap_or_else(|e| format!("(could not be read: {})", e));
+                text.push_str(&format!("\n\n## File: {}\n\n```\n{}\n```", path, content));
+            }
+            PinnedItem::Note { text: note } => {
+                text.push_str(&format!("\n\n## Note\n\n{}", note));
+            }
+        }
+    }
+    text
+}
+
+/// Add the session's pinned items to `messages` after compaction replaced them with a
+/// summary. They go into the first message, which is the summary the model reads first.
+pub fn reinclude_pins(
+    session_file: &Path,
+    working_dir: &Path,
+    messages: Conversation,
+) -> Conversation {
+    let pins = match read_pins(session_file) {
+        Ok(pins) => pins,
+        Err(e) => {
+            tracing::warn!("Failed to read pinned items: {}", e);
+            return messages;
+        }
+    };
+    if pins.is_empty() || messages.is_empty() {
+        return messages;
+    }
+    let mut messages = messages.messages().clone();
+    messages[0]
+        .content
+        .push(MessageContent::text(render_pins(&pins, working_dir)));
+    Conversation::new_unvalidated(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_pin_and_unpin() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("pinned.jsonl");
+        assert!(read_pins(&session_file)?.is_empty());
+
+        let file = add_pin(
+            &session_file,
+            PinnedItem::File {
+                path: "src/main.rs".to_string(),
+            },
+        )?;
+        let again = add_pin(
+            &session_file,
+            PinnedItem::File {
+                path: "src/main.rs".to_string(),
+            },
+        )?;
+        assert_eq!(file.id, again.id);
+        add_pin(
+            &session_file,
+            PinnedItem::Note {
+                text: "Never touch the migrations".to_string(),
+            },
+        )?;
+        assert_eq!(read_pins(&session_file)?.len(), 2);
+
+        assert!(remove_pin(&session_file, &file.id)?);
+        assert!(!remove_pin(&session_file, &file.id)?);
+        let pins = read_pins(&session_file)?;
+        assert_eq!(pins.len(), 1);
+        assert!(matches!(pins[0].item, PinnedItem::Note { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pins_are_reincluded_in_the_summary() -> Result<()> {
+        let dir = tempdir()?;
+        let session_file = dir.path().join("compacted.jsonl");
+        fs::write(dir.path().join("plan.md"), "1. fix the parser")?;
+        add_pin(
+            &session_file,
+            PinnedItem::File {
+                path: "plan.md".to_string(),
+            },
+        )?;
+        add_pin(
+            &session_file,
+            PinnedItem::File {
+                path: "missing.md".to_string(),
+            },
+        )?;
+
+        let summarized = Conversation::new_unvalidated(vec![
+            Message::user().with_text("Summary of the conversation"),
+            Message::assistant().with_text("I summarized our conversation."),
+        ]);
+        let messages = reinclude_pins(&session_file, dir.path(), summarized);
+        assert_eq!(messages.len(), 2);
+        let summary = messages.messages()[0].as_concat_text();
+        assert!(summary.starts_with("Summary of the conversation"));
+        assert!(summary.contains("## File: plan.md"));
+        assert!(summary.contains("1. fix the parser"));
+        assert!(summary.contains("## File: missing.md\n\n```\n(could not be read"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pinned_files_stay_in_the_working_dir() -> Result<()> {
+        let dir = tempdir()?;
+        let working_dir = dir.path().join("project");
+        fs::create_dir(&working_dir)?;
+        fs::write(dir.path().join("secret.txt"), "hunter2")?;
+        fs::write(working_dir.join("notes.txt"), "keep this")?;
+
+        assert!(is_relative_inside("src/./main.rs"));
+        assert!(!is_relative_inside("../secret.txt"));
+        assert!(!is_relative_inside("/etc/passwd"));
+        assert!(pinned_file(&working_dir, "notes.txt").is_ok());
+        assert!(pinned_file(&working_dir, "../secret.txt").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.path().join("secret.txt"), working_dir.join("link"))?;
+            assert!(pinned_file(&working_dir, "link").is_err());
+        }
+
+        let pins: Vec<Pin> = ["notes.txt", "../secret.txt"]
+            .iter()
+            .map(|path| Pin {
+                id: path.to_string(),
+                item: PinnedItem::File {
+                    path: path.to_string(),
+                },
+                created: 0,
+            })
+            .collect();
+        let text = render_pins(&pins, &working_dir);
+        assert!(text.contains("keep this"));
+        assert!(!text.contains("hunter2"));
+        Ok(())
+    }
+}
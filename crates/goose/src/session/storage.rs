@@ -5,8 +5,11 @@
 // - Backup creation
 // Additional debug logging can be added if needed for troubleshooting.
 
+use super::integrity;
 use crate::conversation::message::Message;
 use crate::conversation::Conversation;
+use crate::governance;
+use crate::offline;
 use crate::providers::base::Provider;
 use crate::utils::safe_truncate;
 use anyhow::Result;
@@ -39,6 +42,14 @@ fn get_current_working_dir() -> PathBuf {
         .expect("could not determine the current working directory")
 }
 
+/// A resource of an extension attached to a session, whose current content goes with every
+/// request of the session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ResourceAttachment {
+    pub extension: String,
+    pub uri: String,
+}
+
 /// Metadata for a session, stored as the first line in the session file
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct SessionMetadata {
@@ -64,6 +75,19 @@ pub struct SessionMetadata {
     pub accumulated_input_tokens: Option<i32>,
     /// The number of output tokens used in the session. Accumulated across all messages.
     pub accumulated_output_tokens: Option<i32>,
+    /// Hash chain over the messages as last saved, to detect edits made outside goose.
+    /// Sessions saved before checksums were recorded have none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// Style preset the session picked, overriding the recipe's and the configured default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    /// Extension resources attached to the session
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attached_resources: Vec<ResourceAttachment>,
+    /// Model comparison this session is one run of; its other runs share the id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparison_id: Option<String>,
 }
 
 // Custom deserializer to handle old sessions without working_dir
@@ -84,6 +108,14 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: Option<i32>,
             accumulated_output_tokens: Option<i32>,
             working_dir: Option<PathBuf>,
+            #[serde(default)]
+            checksum: Option<String>,
+            #[serde(default)]
+            style: Option<String>,
+            #[serde(default)]
+            attached_resources: Vec<ResourceAttachment>,
+            #[serde(default)]
+            comparison_id: Option<String>,
         }
 
         let helper = Helper::deserialize(deserializer)?;
@@ -105,6 +137,10 @@ impl<'de> Deserialize<'de> for SessionMetadata {
             accumulated_input_tokens: helper.accumulated_input_tokens,
             accumulated_output_tokens: helper.accumulated_output_tokens,
             working_dir,
+            checksum: helper.checksum,
+            style: helper.style,
+            attached_resources: helper.attached_resources,
+            comparison_id: helper.comparison_id,
         })
     }
 }
@@ -129,6 +165,10 @@ impl SessionMetadata {
             accumulated_total_tokens: None,
             accumulated_input_tokens: None,
             accumulated_output_tokens: None,
+            checksum: None,
+            style: None,
+            attached_resources: Vec::new(),
+            comparison_id: None,
         }
     }
 }
@@ -142,30 +182,38 @@ impl Default for SessionMetadata {
 // The single app name used for all Goose applications
 const APP_NAME: &str = "goose";
 
+/// Logs kept next to a session file that share its `.jsonl` extension, e.g. `<name>.audit.jsonl`
+const JSONL_SIDECARS: &[&str] = &["audit", "events", "held"];
+
+/// Whether a `.jsonl` file stem names a sidecar log rather than a session
+fn is_jsonl_sidecar(stem: &str) -> bool {
+    stem.rsplit_once('.')
+        .is_some_and(|(_, suffix)| JSONL_SIDECARS.contains(&suffix))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Identifier {
     Name(String),
     Path(PathBuf),
 }
 
+impl Identifier {
+    /// The id clients know the session by: its name, or its file name without the extension
+    pub fn session_id(&self) -> String {
+        match self {
+            Identifier::Name(name) => name.clone(),
+            Identifier::Path(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
 pub fn get_path(id: Identifier) -> Result<PathBuf> {
     let path = match id {
         Identifier::Name(name) => {
-            // Validate session name for security
-            if name.is_empty() || name.len() > 255 {
-                return Err(anyhow::anyhow!("Invalid session name length"));
-            }
-
-            // Check for path traversal attempts
-            if name.contains("..") || name.contains('/') || name.contains('\\') {
-                return Err(anyhow::anyhow!("Invalid characters in session name"));
-            }
-
-            let session_dir = ensure_session_dir().map_err(|e| {
-                tracing::error!("Failed to create session directory: {}", e);
-                anyhow::anyhow!("Failed to access session directory")
-            })?;
-            session_dir.join(format!("{}.jsonl", name))
+            return get_path_for_working_dir(&name, &get_current_working_dir());
         }
         Identifier::Path(path) => {
             // In test mode, allow temporary directory paths
@@ -179,19 +227,25 @@ pub fn get_path(id: Identifier) -> Result<PathBuf> {
                 }
             }
 
-            // Validate that the path is within allowed directories
-            let session_dir = ensure_session_dir().map_err(|e| {
+            // Validate that the path is within one of the session roots
+            let session_dirs = session_dirs().map_err(|e| {
                 tracing::error!("Failed to create session directory: {}", e);
                 anyhow::anyhow!("Failed to access session directory")
             })?;
 
             // Handle path validation with Windows-compatible logic
-            let is_path_allowed = validate_path_within_session_dir(&path, &session_dir)?;
+            let mut is_path_allowed = false;
+            for session_dir in &session_dirs {
+                if validate_path_within_session_dir(&path, session_dir)? {
+                    is_path_allowed = true;
+                    break;
+                }
+            }
             if !is_path_allowed {
                 tracing::warn!(
-                    "Attempted access outside session directory: {:?} not within {:?}",
+                    "Attempted access outside session directories: {:?} not within {:?}",
                     path,
-                    session_dir
+                    session_dirs
                 );
                 return Err(anyhow::anyhow!("Path not allowed"));
             }
@@ -210,6 +264,40 @@ pub fn get_path(id: Identifier) -> Result<PathBuf> {
     Ok(path)
 }
 
+/// Path of the named session for a session running in `working_dir`. An existing session is
+/// found in whichever root holds it; a new one is placed by the GOOSE_SESSION_ROOTS rules.
+pub fn get_path_for_working_dir(name: &str, working_dir: &Path) -> Result<PathBuf> {
+    // Validate session name for security
+    if name.is_empty() || name.len() > 255 {
+        return Err(anyhow::anyhow!("Invalid session name length"));
+    }
+
+    // Check for path traversal attempts
+    if name.contains("..") || name.contains('/') || name.contains('\\') {
+        return Err(anyhow::anyhow!("Invalid characters in session name"));
+    }
+    if is_jsonl_sidecar(name) {
+        return Err(anyhow::anyhow!("Invalid session name"));
+    }
+
+    let session_dir = ensure_session_dir().map_err(|e| {
+        tracing::error!("Failed to create session directory: {}", e);
+        anyhow::anyhow!("Failed to access session directory")
+    })?;
+    let roots = usable_roots(configured_session_roots());
+
+    let file_name = format!("{}.jsonl", name);
+    let existing = std::iter::once(&session_dir)
+        .chain(roots.iter().map(|root| &root.path))
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.exists());
+    Ok(existing.unwrap_or_else(|| {
+        placement_dir(&roots, working_dir)
+            .unwrap_or(session_dir)
+            .join(file_name)
+    }))
+}
+
 /// Validate that a path is within the session directory, with Windows-compatible logic
 ///
 /// This function handles Windows-specific path issues like:
@@ -334,49 +422,130 @@ pub fn ensure_session_dir() -> Result<PathBuf> {
     Ok(data_dir)
 }
 
+/// Config key listing extra session roots, e.g.
+///
+/// ```yaml
+/// GOOSE_SESSION_ROOTS:
+///   - path: /mnt/shared/goose-sessions
+///     working_dirs: [/home/me/projects/shared-app]
+/// ```
+pub const SESSION_ROOTS_KEY: &str = "GOOSE_SESSION_ROOTS";
+
+/// A directory holding sessions in addition to the default data dir
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRoot {
+    /// Directory the session files live in
+    pub path: PathBuf,
+    /// New sessions started in or below one of these directories are stored in this root
+    #[serde(default)]
+    pub working_dirs: Vec<PathBuf>,
+}
+
+/// The session roots from config, or none if the key is unset or malformed
+pub fn configured_session_roots() -> Vec<SessionRoot> {
+    match crate::config::Config::global().get_param::<Vec<SessionRoot>>(SESSION_ROOTS_KEY) {
+        Ok(roots) => roots,
+        Err(crate::config::ConfigError::NotFound(_)) => Vec::new(),
+        Err(e) => {
+            tracing::warn!("Ignoring invalid {}: {}", SESSION_ROOTS_KEY, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Roots whose directory exists or can be created; an unmounted drive is skipped, not fatal
+fn usable_roots(roots: Vec<SessionRoot>) -> Vec<SessionRoot> {
+    roots
+        .into_iter()
+        .filter(|root| match fs::create_dir_all(&root.path) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Skipping session root {:?}: {}", root.path, e);
+                false
+            }
+        })
+        .collect()
+}
+
+/// Every directory sessions are read from, the default data dir first
+pub fn session_dirs() -> Result<Vec<PathBuf>> {
+    let mut dirs = vec![ensure_session_dir()?];
+    for root in usable_roots(configured_session_roots()) {
+        if !dirs.contains(&root.path) {
+            dirs.push(root.path);
+        }
+    }
+    Ok(dirs)
+}
+
+/// The root a new session in `working_dir` belongs in, preferring the most specific match
+pub(crate) fn placement_dir(roots: &[SessionRoot], working_dir: &Path) -> Option<PathBuf> {
+    roots
+        .iter()
+        .flat_map(|root| {
+            root.working_dirs
+                .iter()
+                .filter(|dir| working_dir.starts_with(dir))
+                .map(move |dir| (dir.components().count(), &root.path))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, path)| path.clone())
+}
+
 /// Get the path to the most recently modified session file
 pub fn get_most_recent_session() -> Result<PathBuf> {
-    let session_dir = ensure_session_dir()?;
-    let mut entries = fs::read_dir(&session_dir)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
-        .collect::<Vec<_>>();
+    let mut entries = list_sessions()?;
 
     if entries.is_empty() {
         return Err(anyhow::anyhow!("No session files found"));
     }
 
     // Sort by modification time, most recent first
-    entries.sort_by(|a, b| {
-        b.metadata()
+    let modified = |path: &Path| {
+        path.metadata()
             .and_then(|m| m.modified())
             .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-            .cmp(
-                &a.metadata()
-                    .and_then(|m| m.modified())
-                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH),
-            )
-    });
+    };
+    entries.sort_by(|(_, a), (_, b)| modified(b).cmp(&modified(a)));
 
-    Ok(entries[0].path())
+    Ok(entries.swap_remove(0).1)
 }
 
-/// List all available session files
+/// List all available session files across every session root
 pub fn list_sessions() -> Result<Vec<(String, PathBuf)>> {
-    let session_dir = ensure_session_dir()?;
-    let entries = fs::read_dir(&session_dir)?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
+    list_sessions_in(&session_dirs()?)
+}
 
-            if path.extension().is_some_and(|ext| ext == "jsonl") {
-                let name = path.file_stem()?.to_string_lossy().to_string();
-                Some((name, path))
-            } else {
-                None
+/// Sessions in `dirs`; when a name appears in more than one, the earlier directory wins
+fn list_sessions_in(dirs: &[PathBuf]) -> Result<Vec<(String, PathBuf)>> {
+    let mut seen = std::collections::HashSet::new();
+    let mut entries = Vec::new();
+    for (index, dir) in dirs.iter().enumerate() {
+        let read = match fs::read_dir(dir) {
+            Ok(read) => read,
+            // Only the default dir is required; extra roots may be temporarily unavailable
+            Err(e) if index > 0 => {
+                tracing::warn!("Failed to read session root {:?}: {}", dir, e);
+                continue;
             }
-        })
-        .collect::<Vec<_>>();
+            Err(e) => return Err(e.into()),
+        };
+        for entry in read.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if !path.extension().is_some_and(|ext| ext == "jsonl") {
+                continue;
+            }
+            let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            if is_jsonl_sidecar(&name) {
+                continue;
+            }
+            if seen.insert(name.clone()) {
+                entries.push((name, path));
+            }
+        }
+    }
 
     Ok(entries)
 }
@@ -466,6 +635,11 @@ pub fn read_messages_with_truncation(
     let mut corrupted_lines = Vec::new();
     let mut line_number = 1;
     let mut message_count = 0;
+    // Checked against the checksum in the metadata once every line has been read
+    let mut expected_checksum = None;
+    let integrity_key = integrity::integrity_key();
+    let mut checksum = String::new();
+    let mut read_every_line = true;
 
     // Read the first line as metadata or create default if empty/missing
     if let Some(line_result) = lines.next() {
@@ -478,9 +652,13 @@ pub fn read_messages_with_truncation(
                 }
 
                 // Try to parse as metadata, but if it fails, treat it as a message
-                if let Ok(_metadata) = serde_json::from_str::<SessionMetadata>(&line) {
+                if let Ok(metadata) = serde_json::from_str::<SessionMetadata>(&line) {
                     // Metadata successfully parsed, continue with the rest of the lines as messages
+                    expected_checksum = metadata.checksum;
                 } else {
+                    if let Some(key) = integrity_key {
+                        checksum = integrity::chain_hash(key, &checksum, &line);
+                    }
                     // This is not metadata, it's a message
                     match parse_message_with_truncation(&line, max_content_size) {
                         Ok(message) => {
@@ -531,11 +709,16 @@ pub fn read_messages_with_truncation(
                 "[SESSION] Message count limit reached, stopping at {}",
                 MAX_MESSAGE_COUNT
             );
+            read_every_line = false;
             break;
         }
 
         match line_result {
             Ok(line) => {
+                if let Some(key) = integrity_key {
+                    checksum = integrity::chain_hash(key, &checksum, &line);
+                }
+
                 // Security check: line length
                 if line.len() > MAX_LINE_LENGTH {
                     tracing::warn!("Line {} exceeds length limit", line_number);
@@ -590,6 +773,15 @@ pub fn read_messages_with_truncation(
         line_number += 1;
     }
 
+    if let (Some(expected), Some(_)) = (expected_checksum, integrity_key) {
+        if read_every_line && expected != checksum {
+            tracing::warn!(
+                "Session {:?} does not match its checksum; it was modified outside goose",
+                session_file
+            );
+        }
+    }
+
     // If we found corrupted lines, create a backup and log the issues
     if !corrupted_lines.is_empty() {
         println!(
@@ -1088,7 +1280,8 @@ pub async fn persist_messages_with_schedule_id(
 
     // Check if we need to update the description (after 1st or 3rd user message)
     match provider {
-        Some(provider) if user_message_count < 4 => {
+        // Naming the session needs the provider; offline, save without renaming
+        Some(provider) if user_message_count < 4 && !offline::recently_offline() => {
             //generate_description is responsible for writing the messages
             generate_description_with_schedule_id(
                 &secure_path,
@@ -1125,6 +1318,35 @@ pub async fn persist_messages_with_schedule_id(
     }
 }
 
+/// Exclusive hold on a session for a read-modify-write, released when dropped. It locks a
+/// `.lock` file next to the session, since saving replaces the session file itself.
+pub struct SessionLock {
+    file: fs::File,
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        let _ = fs2::FileExt::unlock(&self.file);
+    }
+}
+
+/// Wait until no one else holds `session_file`, then hold it until the lock is dropped
+pub fn lock_session(session_file: &Path) -> Result<SessionLock> {
+    use fs2::FileExt;
+
+    let secure_path = get_path(Identifier::Path(session_file.to_path_buf()))?;
+    if let Some(parent) = secure_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(secure_path.with_extension("lock"))?;
+    file.lock_exclusive()?;
+    Ok(SessionLock { file })
+}
+
 /// Write messages to a session file with the provided metadata using secure atomic operations
 ///
 /// This function uses atomic file operations to prevent corruption:
@@ -1157,6 +1379,21 @@ pub fn save_messages_with_metadata(
         return Err(anyhow::anyhow!("Too many messages to save"));
     }
 
+    let lines = messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| {
+            serde_json::to_string(message).map_err(|e| {
+                tracing::error!("Failed to serialize message {}: {}", i, e);
+                anyhow::anyhow!("Failed to write session message")
+            })
+        })
+        .collect::<Result<Vec<String>>>()?;
+    governance::preserve_held_history(&secure_path, &lines)?;
+    let mut metadata = metadata.clone();
+    metadata.checksum = integrity::integrity_key()
+        .map(|key| integrity::session_checksum(key, lines.iter().map(String::as_str)));
+
     // Create a temporary file in the same directory to ensure atomic move
     let temp_file = secure_path.with_extension("tmp");
 
@@ -1208,13 +1445,9 @@ pub fn save_messages_with_metadata(
         })?;
         writeln!(writer)?;
 
-        // Write all messages with progress tracking
-        for (i, message) in messages.iter().enumerate() {
-            serde_json::to_writer(&mut writer, &message).map_err(|e| {
-                tracing::error!("Failed to serialize message {}: {}", i, e);
-                anyhow::anyhow!("Failed to write session message")
-            })?;
-            writeln!(writer)?;
+        // Write all messages, exactly as they were hashed
+        for line in &lines {
+            writeln!(writer, "{}", line)?;
         }
 
         // Ensure all data is written to disk
@@ -1910,6 +2143,63 @@ mod tests {
         assert!(!normalized_existing.as_os_str().is_empty());
     }
 
+    #[test]
+    fn test_session_root_placement() {
+        let roots = vec![
+            SessionRoot {
+                path: PathBuf::from("/mnt/shared/sessions"),
+                working_dirs: vec![PathBuf::from("/home/me/projects")],
+            },
+            SessionRoot {
+                path: PathBuf::from("/mnt/team/sessions"),
+                working_dirs: vec![PathBuf::from("/home/me/projects/team-app")],
+            },
+        ];
+
+        assert_eq!(
+            placement_dir(&roots, Path::new("/home/me/projects/team-app/src")),
+            Some(PathBuf::from("/mnt/team/sessions"))
+        );
+        assert_eq!(
+            placement_dir(&roots, Path::new("/home/me/projects/other")),
+            Some(PathBuf::from("/mnt/shared/sessions"))
+        );
+        assert_eq!(
+            placement_dir(&roots, Path::new("/home/me/projects-old")),
+            None
+        );
+        assert_eq!(placement_dir(&[], Path::new("/home/me")), None);
+    }
+
+    #[test]
+    fn test_list_sessions_across_roots() -> Result<()> {
+        let default_dir = tempdir()?;
+        let shared_dir = tempdir()?;
+        fs::write(default_dir.path().join("a.jsonl"), "{}\n")?;
+        fs::write(default_dir.path().join("notes.txt"), "")?;
+        fs::write(default_dir.path().join("a.audit.jsonl"), "{}\n")?;
+        fs::write(default_dir.path().join("a.events.jsonl"), "{}\n")?;
+        fs::write(shared_dir.path().join("a.jsonl"), "{}\n")?;
+        fs::write(shared_dir.path().join("b.jsonl"), "{}\n")?;
+        let missing = shared_dir.path().join("unmounted");
+
+        let mut sessions = list_sessions_in(&[
+            default_dir.path().to_path_buf(),
+            shared_dir.path().to_path_buf(),
+            missing,
+        ])?;
+        sessions.sort();
+
+        assert_eq!(
+            sessions,
+            vec![
+                ("a".to_string(), default_dir.path().join("a.jsonl")),
+                ("b".to_string(), shared_dir.path().join("b.jsonl")),
+            ]
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_save_session_parameter() -> Result<()> {
         let dir = tempdir()?;
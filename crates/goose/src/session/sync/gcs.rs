@@ -0,0 +1,109 @@
+use super::{header_value, SyncBackend};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderName;
+use reqwest::StatusCode;
+
+const API: &str = "https://storage.googleapis.com";
+
+/// Google Cloud Storage through its JSON API
+pub struct GcsBackend {
+    pub(super) bucket: String,
+    pub(super) prefix: String,
+    /// OAuth access token, e.g. from `gcloud auth print-access-token`
+    pub(super) token: String,
+    pub(super) client: reqwest::Client,
+}
+
+impl GcsBackend {
+    fn object_name(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    /// Upload an object, only over the given generation when there is one
+    async fn upload(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        if_generation: Option<&str>,
+    ) -> Result<reqwest::Response> {
+        let mut url = format!(
+            "{}/upload/storage/v1/b/{}/o?uploadType=media&name={}",
+            API,
+            self.bucket,
+            urlencoding::encode(&self.object_name(key))
+        );
+        if let Some(generation) = if_generation {
+            url.push_str(&format!(
+                "&ifGenerationMatch={}",
+                urlencoding::encode(generation)
+            ));
+        }
+        Ok(self
+            .client
+            .post(url)
+            .bearer_auth(&self.token)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(body)
+            .send()
+            .await?)
+    }
+}
+
+#[async_trait]
+impl SyncBackend for GcsBackend {
+    async fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, String)>> {
+        let url = format!(
+            "{}/storage/v1/b/{}/o/{}?alt=media",
+            API,
+            self.bucket,
+            urlencoding::encode(&self.object_name(key))
+        );
+        let response = self.client.get(url).bearer_auth(&self.token).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        let generation = header_value(&response, HeaderName::from_static("x-goog-generation"))?;
+        Ok(Some((response.bytes().await?.to_vec(), generation)))
+    }
+
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        self.upload(key, body, None).await?.error_for_status()?;
+        Ok(())
+    }
+
+    async fn put_if_version(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        version: Option<&str>,
+    ) -> Result<bool> {
+        // Generation 0 matches only an object that does not exist yet
+        let response = self.upload(key, body, Some(version.unwrap_or("0"))).await?;
+        if response.status() == StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let url = format!(
+            "{}/storage/v1/b/{}/o/{}",
+            API,
+            self.bucket,
+            urlencoding::encode(&self.object_name(key))
+        );
+        let response = self
+            .client
+            .delete(url)
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        if response.status() != StatusCode::NOT_FOUND {
+            response.error_for_status()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,548 @@
+//! Optional syncing of session files to cloud storage, so the same history shows up on
+//! every machine.
+//!
+//! The remote side holds each version of a session as `sessions/<name>/<hash>.jsonl` plus a
+//! `manifest.json` recording the current content hash of every session. Session objects are
+//! never overwritten, and the manifest is only replaced if it still has the version (ETag) it
+//! was read at, so two machines syncing at once cannot lose each other's changes; the one
+//! that loses the race starts over from the new manifest. Locally, `session_sync.json`
+//! remembers the hash each session had when it was last synced, which is what tells a local
+//! edit apart from a remote one. When both sides changed, the newer copy keeps the session's
+//! name and the other is kept as a separate `<name>_conflict_<timestamp>` session, so nothing
+//! is lost. Deleting a session locally does not delete it remotely; `purge_remote` does, for
+//! data governance purges.
+
+mod gcs;
+mod s3;
+mod webdav;
+
+pub use gcs::GcsBackend;
+pub use s3::S3Backend;
+pub use webdav::WebDavBackend;
+
+use crate::config::{Config, ConfigError, APP_STRATEGY};
+use crate::session;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Config key holding the sync backend settings
+pub const SESSION_SYNC_KEY: &str = "GOOSE_SESSION_SYNC";
+
+const MANIFEST_KEY: &str = "manifest.json";
+const STATE_FILE: &str = "session_sync.json";
+/// Syncs that lose the race for the manifest this many times in a row give up
+const MAX_SYNC_ATTEMPTS: usize = 3;
+
+/// Object storage that sessions are synced to. Keys are `/`-separated relative paths.
+#[async_trait]
+pub trait SyncBackend: Send + Sync {
+    /// Fetch an object, or None if it does not exist
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_versioned(key).await?.map(|(body, _)| body))
+    }
+    /// Fetch an object with its version, the ETag or generation the backend assigned it
+    async fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, String)>>;
+    /// Create or replace an object
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()>;
+    /// Replace an object only if it is still at `version`, or create it only if it does not
+    /// exist when `version` is None. Returns false when another writer got there first.
+    async fn put_if_version(&self, key: &str, body: Vec<u8>, version: Option<&str>)
+        -> Result<bool>;
+    /// Remove an object; removing one that does not exist is not an error
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Settings under GOOSE_SESSION_SYNC, e.g. `{backend: s3, bucket: my-bucket, region: us-east-1}`.
+/// Credentials are read from the secret store rather than config.yaml.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum SyncConfig {
+    /// Amazon S3 or an S3-compatible store, using GOOSE_SYNC_AWS_ACCESS_KEY_ID and
+    /// GOOSE_SYNC_AWS_SECRET_ACCESS_KEY (and optionally GOOSE_SYNC_AWS_SESSION_TOKEN)
+    S3 {
+        bucket: String,
+        region: String,
+        #[serde(default)]
+        prefix: String,
+        /// Custom endpoint for S3-compatible stores such as MinIO; uses path-style URLs
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    /// Google Cloud Storage, using the OAuth access token in GOOSE_SYNC_GCS_TOKEN
+    Gcs {
+        bucket: String,
+        #[serde(default)]
+        prefix: String,
+    },
+    /// A WebDAV folder, using GOOSE_SYNC_WEBDAV_PASSWORD when a username is set
+    Webdav {
+        url: String,
+        #[serde(default)]
+        username: Option<String>,
+    },
+}
+
+/// A header a backend needs from a response, such as the ETag of an object
+fn header_value(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Result<String> {
+    response
+        .headers()
+        .get(&name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .with_context(|| format!("The sync backend sent no {} header", name))
+}
+
+fn secret(config: &Config, key: &str) -> Result<String> {
+    config
+        .get_secret::<String>(key)
+        .with_context(|| format!("{} is not set", key))
+}
+
+impl SyncConfig {
+    /// The configured sync settings, or None when sync is not set up
+    pub fn load(config: &Config) -> Result<Option<Self>> {
+        match config.get_param::<Self>(SESSION_SYNC_KEY) {
+            Ok(settings) => Ok(Some(settings)),
+            Err(ConfigError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Invalid {}", SESSION_SYNC_KEY)),
+        }
+    }
+
+    pub fn backend(&self, config: &Config) -> Result<Box<dyn SyncBackend>> {
+        Ok(match self {
+            Self::S3 {
+                bucket,
+                region,
+                prefix,
+                endpoint,
+            } => Box::new(S3Backend {
+                bucket: bucket.clone(),
+                region: region.clone(),
+                prefix: prefix.clone(),
+                endpoint: endpoint.clone(),
+                access_key_id: secret(config, "GOOSE_SYNC_AWS_ACCESS_KEY_ID")?,
+                secret_access_key: secret(config, "GOOSE_SYNC_AWS_SECRET_ACCESS_KEY")?,
+                session_token: config.get_secret("GOOSE_SYNC_AWS_SESSION_TOKEN").ok(),
+                client: reqwest::Client::new(),
+            }),
+            Self::Gcs { bucket, prefix } => Box::new(GcsBackend {
+                bucket: bucket.clone(),
+                prefix: prefix.clone(),
+                token: secret(config, "GOOSE_SYNC_GCS_TOKEN")?,
+                client: reqwest::Client::new(),
+            }),
+            Self::Webdav { url, username } => Box::new(WebDavBackend {
+                url: url.trim_end_matches('/').to_string(),
+                password: match username {
+                    Some(_) => Some(secret(config, "GOOSE_SYNC_WEBDAV_PASSWORD")?),
+                    None => None,
+                },
+                username: username.clone(),
+                client: reqwest::Client::new(),
+            }),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RemoteEntry {
+    hash: String,
+    modified: DateTime<Utc>,
+    device: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    sessions: BTreeMap<String, RemoteEntry>,
+}
+
+/// Hash of each session as of the last sync from this machine
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    synced: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct SyncReport {
+    pub uploaded: Vec<String>,
+    pub downloaded: Vec<String>,
+    /// Sessions changed on both sides, with the name the losing copy was kept under
+    pub conflicts: Vec<(String, String)>,
+}
+
+/// What to do with one session
+#[derive(Debug, PartialEq)]
+enum Action {
+    Nothing,
+    Upload,
+    Download,
+    /// Both sides changed; `remote_wins` says which copy keeps the name
+    Conflict {
+        remote_wins: bool,
+    },
+}
+
+fn decide(
+    local: Option<(&str, DateTime<Utc>)>,
+    base: Option<&str>,
+    remote: Option<&RemoteEntry>,
+) -> Action {
+    match (local, remote) {
+        (None, None) => Action::Nothing,
+        (Some(_), None) => Action::Upload,
+        (None, Some(_)) => Action::Download,
+        (Some((hash, _)), Some(remote)) if hash == remote.hash => Action::Nothing,
+        (Some(_), Some(remote)) if base == Some(remote.hash.as_str()) => Action::Upload,
+        (Some((hash, _)), Some(_)) if base == Some(hash) => Action::Download,
+        (Some((_, modified)), Some(remote)) => Action::Conflict {
+            remote_wins: remote.modified > modified,
+        },
+    }
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    blake3::hash(bytes).to_hex().to_string()
+}
+
+fn device_name() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn state_path() -> Result<PathBuf> {
+    Ok(choose_app_strategy(APP_STRATEGY.clone())?
+        .data_dir()
+        .join(STATE_FILE))
+}
+
+async fn load_state(state_path: &Path) -> SyncState {
+    match tokio::fs::read(state_path).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring corrupt session sync state: {}", e);
+            SyncState::default()
+        }),
+        Err(_) => SyncState::default(),
+    }
+}
+
+async fn save_state(state_path: &Path, state: &SyncState) -> Result<()> {
+    if let Some(parent) = state_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(state_path, serde_json::to_vec_pretty(state)?).await?;
+    Ok(())
+}
+
+fn session_key(name: &str, hash: &str) -> String {
+    format!("sessions/{}/{}.jsonl", name, hash)
+}
+
+fn modified_time(path: &Path) -> DateTime<Utc> {
+    path.metadata()
+        .and_then(|m| m.modified())
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_default()
+}
+
+/// The remote manifest and its version, which is None while there is no manifest yet
+async fn load_manifest(backend: &dyn SyncBackend) -> Result<(Manifest, Option<String>)> {
+    match backend.get_versioned(MANIFEST_KEY).await? {
+        Some((bytes, version)) => Ok((
+            serde_json::from_slice(&bytes).context("Remote manifest is corrupt")?,
+            Some(version),
+        )),
+        None => Ok((Manifest::default(), None)),
+    }
+}
+
+struct Syncer<'a> {
+    backend: &'a dyn SyncBackend,
+    manifest: Manifest,
+    state: SyncState,
+    device: String,
+    report: SyncReport,
+    /// Objects of session versions the manifest stops pointing to once it is written
+    superseded: Vec<String>,
+}
+
+impl Syncer<'_> {
+    async fn upload(&mut self, name: &str, path: &Path) -> Result<()> {
+        let bytes = tokio::fs::read(path).await?;
+        let hash = content_hash(&bytes);
+        self.backend.put(&session_key(name, &hash), bytes).await?;
+        if let Some(previous) = self.manifest.sessions.get(name) {
+            self.superseded.push(session_key(name, &previous.hash));
+        }
+        self.manifest.sessions.insert(
+            name.to_string(),
+            RemoteEntry {
+                hash: hash.clone(),
+                modified: modified_time(path),
+                device: self.device.clone(),
+            },
+        );
+        self.state.synced.insert(name.to_string(), hash);
+        self.report.uploaded.push(name.to_string());
+        Ok(())
+    }
+
+    async fn fetch(&self, name: &str) -> Result<Vec<u8>> {
+        let entry = self
+            .manifest
+            .sessions
+            .get(name)
+            .with_context(|| format!("Session {} is not in the manifest", name))?;
+        self.backend
+            .get(&session_key(name, &entry.hash))
+            .await?
+            .with_context(|| format!("Session {} is in the manifest but missing remotely", name))
+    }
+
+    async fn download(&mut self, name: &str, path: &Path) -> Result<()> {
+        let bytes = self.fetch(name).await?;
+        let hash = content_hash(&bytes);
+        tokio::fs::write(path, bytes).await?;
+        self.state.synced.insert(name.to_string(), hash);
+        self.report.downloaded.push(name.to_string());
+        Ok(())
+    }
+
+    async fn resolve_conflict(&mut self, name: &str, path: &Path, remote_wins: bool) -> Result<()> {
+        let conflict_name = format!("{}_conflict_{}", name, Utc::now().format("%Y%m%d_%H%M%S"));
+        let conflict_path = path.with_file_name(format!("{}.jsonl", conflict_name));
+        if remote_wins {
+            tokio::fs::rename(path, &conflict_path).await?;
+            self.download(name, path).await?;
+        } else {
+            let remote = self.fetch(name).await?;
+            tokio::fs::write(&conflict_path, remote).await?;
+            self.upload(name, path).await?;
+        }
+        self.upload(&conflict_name, &conflict_path).await?;
+        self.report
+            .conflicts
+            .push((name.to_string(), conflict_name));
+        Ok(())
+    }
+}
+
+/// Sync every local session with the backend, in both directions
+pub async fn sync_sessions(backend: &dyn SyncBackend) -> Result<SyncReport> {
+    let state_path = state_path()?;
+    let mut report = SyncReport::default();
+    for _ in 0..MAX_SYNC_ATTEMPTS {
+        let (manifest, version) = load_manifest(backend).await?;
+        let mut syncer = Syncer {
+            backend,
+            manifest,
+            state: load_state(&state_path).await,
+            device: device_name(),
+            report: SyncReport::default(),
+            superseded: Vec::new(),
+        };
+        sync_pass(&mut syncer).await?;
+        // Downloads and conflict copies already happened locally, whether or not this
+        // pass's uploads make it into the manifest
+        report.downloaded.append(&mut syncer.report.downloaded);
+        report.conflicts.append(&mut syncer.report.conflicts);
+
+        if !syncer.report.uploaded.is_empty() {
+            let manifest = serde_json::to_vec_pretty(&syncer.manifest)?;
+            let written = backend
+                .put_if_version(MANIFEST_KEY, manifest, version.as_deref())
+                .await
+                .context("Failed to write remote manifest")?;
+            if !written {
+                tracing::info!("Another device synced at the same time, syncing again");
+                continue;
+            }
+            for key in &syncer.superseded {
+                if let Err(e) = backend.delete(key).await {
+                    tracing::warn!("Failed to delete old session version {}: {}", key, e);
+                }
+            }
+        }
+        save_state(&state_path, &syncer.state).await?;
+        report.uploaded = syncer.report.uploaded;
+        return Ok(report);
+    }
+    anyhow::bail!(
+        "The remote manifest kept changing during sync; try again once other devices finish"
+    )
+}
+
+/// Compare every local session with the manifest and move the changed ones
+async fn sync_pass(syncer: &mut Syncer<'_>) -> Result<()> {
+    let local: BTreeMap<String, PathBuf> = session::list_sessions()?.into_iter().collect();
+    let names: BTreeSet<String> = local
+        .keys()
+        .chain(syncer.manifest.sessions.keys())
+        .cloned()
+        .collect();
+
+    for name in names {
+        let local_file = match local.get(&name) {
+            Some(path) => {
+                let bytes = tokio::fs::read(path).await?;
+                Some((path.clone(), content_hash(&bytes), modified_time(path)))
+            }
+            None => None,
+        };
+        let action = decide(
+            local_file
+                .as_ref()
+                .map(|(_, hash, modified)| (hash.as_str(), *modified)),
+            syncer.state.synced.get(&name).map(String::as_str),
+            syncer.manifest.sessions.get(&name),
+        );
+
+        let result = match (action, local_file) {
+            (Action::Nothing, Some((_, hash, _))) => {
+                syncer.state.synced.insert(name.clone(), hash);
+                Ok(())
+            }
+            (Action::Nothing, None) => Ok(()),
+            (Action::Upload, Some((path, _, _))) => syncer.upload(&name, &path).await,
+            (Action::Download, Some((path, _, _))) => syncer.download(&name, &path).await,
+            (Action::Download, None) => {
+                match session::get_path(session::Identifier::Name(name.clone())) {
+                    Ok(path) => syncer.download(&name, &path).await,
+                    Err(e) => Err(e),
+                }
+            }
+            (Action::Conflict { remote_wins }, Some((path, _, _))) => {
+                syncer.resolve_conflict(&name, &path, remote_wins).await
+            }
+            (Action::Upload | Action::Conflict { .. }, None) => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to sync session {}: {}", name, e);
+        }
+    }
+    Ok(())
+}
+
+/// Delete the remote copies of the sessions `matches` picks, whichever machine uploaded them,
+/// returning their names; with `dry_run` nothing is deleted
+pub async fn purge_remote(
+    backend: &dyn SyncBackend,
+    matches: impl Fn(&str) -> bool,
+    dry_run: bool,
+) -> Result<Vec<String>> {
+    let (mut manifest, version) = load_manifest(backend).await?;
+    let names: Vec<String> = manifest
+        .sessions
+        .keys()
+        .filter(|name| matches(name))
+        .cloned()
+        .collect();
+    if dry_run || names.is_empty() {
+        return Ok(names);
+    }
+
+    // The manifest is rewritten first so an interrupted purge never lists a deleted session
+    let removed: Vec<RemoteEntry> = names
+        .iter()
+        .filter_map(|name| manifest.sessions.remove(name))
+        .collect();
+    let written = backend
+        .put_if_version(
+            MANIFEST_KEY,
+            serde_json::to_vec_pretty(&manifest)?,
+            version.as_deref(),
+        )
+        .await
+        .context("Failed to write remote manifest")?;
+    if !written {
+        anyhow::bail!("Another device synced during the purge; run it again");
+    }
+    for (name, entry) in names.iter().zip(&removed) {
+        backend.delete(&session_key(name, &entry.hash)).await?;
+    }
+
+    let state_path = state_path()?;
+    let mut state = load_state(&state_path).await;
+    state.synced.retain(|name, _| !names.contains(name));
+    save_state(&state_path, &state).await?;
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote(hash: &str, modified: i64) -> RemoteEntry {
+        RemoteEntry {
+            hash: hash.to_string(),
+            modified: DateTime::from_timestamp(modified, 0).unwrap(),
+            device: "laptop".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_decide() {
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+
+        assert_eq!(decide(Some(("a", at(0))), None, None), Action::Upload);
+        assert_eq!(decide(None, None, Some(&remote("a", 0))), Action::Download);
+        assert_eq!(
+            decide(Some(("a", at(0))), Some("old"), Some(&remote("a", 0))),
+            Action::Nothing
+        );
+        // Only the local copy moved on since the last sync
+        assert_eq!(
+            decide(Some(("new", at(0))), Some("old"), Some(&remote("old", 0))),
+            Action::Upload
+        );
+        // Only the remote copy moved on
+        assert_eq!(
+            decide(Some(("old", at(0))), Some("old"), Some(&remote("new", 0))),
+            Action::Download
+        );
+        // Both moved on: the newer one wins
+        assert_eq!(
+            decide(
+                Some(("mine", at(10))),
+                Some("old"),
+                Some(&remote("theirs", 20))
+            ),
+            Action::Conflict { remote_wins: true }
+        );
+        assert_eq!(
+            decide(Some(("mine", at(30))), None, Some(&remote("theirs", 20))),
+            Action::Conflict { remote_wins: false }
+        );
+    }
+
+    #[test]
+    fn test_config_parsing() {
+        let config: SyncConfig = serde_json::from_value(serde_json::json!({
+            "backend": "s3",
+            "bucket": "sessions",
+            "region": "eu-west-1"
+        }))
+        .unwrap();
+        assert_eq!(
+            config,
+            SyncConfig::S3 {
+                bucket: "sessions".to_string(),
+                region: "eu-west-1".to_string(),
+                prefix: String::new(),
+                endpoint: None,
+            }
+        );
+
+        let config: SyncConfig = serde_json::from_value(serde_json::json!({
+            "backend": "webdav",
+            "url": "https://dav.example.com/goose/"
+        }))
+        .unwrap();
+        assert!(matches!(config, SyncConfig::Webdav { username: None, .. }));
+    }
+}
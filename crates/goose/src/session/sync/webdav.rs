@@ -0,0 +1,103 @@
+use super::{header_value, SyncBackend};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{Method, RequestBuilder, StatusCode};
+
+/// A folder on a WebDAV server such as Nextcloud
+pub struct WebDavBackend {
+    /// Folder URL without a trailing slash
+    pub(super) url: String,
+    pub(super) username: Option<String>,
+    pub(super) password: Option<String>,
+    pub(super) client: reqwest::Client,
+}
+
+impl WebDavBackend {
+    fn request(&self, method: Method, key: &str) -> RequestBuilder {
+        let request = self.client.request(method, format!("{}/{}", self.url, key));
+        match &self.username {
+            Some(username) => request.basic_auth(username, self.password.as_ref()),
+            None => request,
+        }
+    }
+
+    /// Create the collections above `key`, which WebDAV servers do not do on PUT
+    async fn create_parents(&self, key: &str) -> Result<()> {
+        let segments: Vec<&str> = key.split('/').collect();
+        let mut path = String::new();
+        for segment in &segments[..segments.len() - 1] {
+            path = format!("{}{}/", path, segment);
+            let status = self
+                .request(Method::from_bytes(b"MKCOL")?, &path)
+                .send()
+                .await?
+                .status();
+            // 405 means the collection already exists
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                anyhow::bail!("MKCOL {} failed: {}", path, status);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SyncBackend for WebDavBackend {
+    async fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, String)>> {
+        let response = self.request(Method::GET, key).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        let etag = header_value(&response, ETAG)?;
+        Ok(Some((response.bytes().await?.to_vec(), etag)))
+    }
+
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        let response = self
+            .request(Method::PUT, key)
+            .body(body.clone())
+            .send()
+            .await?;
+        // 409 Conflict is how WebDAV reports a missing parent collection
+        if response.status() == StatusCode::CONFLICT {
+            self.create_parents(key).await?;
+            self.request(Method::PUT, key)
+                .body(body)
+                .send()
+                .await?
+                .error_for_status()?;
+            return Ok(());
+        }
+        response.error_for_status()?;
+        Ok(())
+    }
+
+    async fn put_if_version(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        version: Option<&str>,
+    ) -> Result<bool> {
+        let request = self.request(Method::PUT, key).body(body);
+        let request = match version {
+            Some(etag) => request.header(IF_MATCH, etag),
+            None => request.header(IF_NONE_MATCH, "*"),
+        };
+        let response = request.send().await?;
+        if response.status() == StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
+        }
+        response.error_for_status()?;
+        Ok(true)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        let response = self.request(Method::DELETE, key).send().await?;
+        if response.status() != StatusCode::NOT_FOUND {
+            response.error_for_status()?;
+        }
+        Ok(())
+    }
+}
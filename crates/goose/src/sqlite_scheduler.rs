@@ -0,0 +1,884 @@
+//! A scheduler that keeps its jobs in SQLite, chosen with `GOOSE_SCHEDULER_TYPE=sqlite`.
+//! Besides cron schedules it runs one-shot jobs at a set time (`run_at`) and chained jobs
+//! (`depends_on`), which start once every job they follow has finished successfully since
+//! their own last run. When a job fails, the jobs downstream of it are skipped until it next
+//! succeeds.
+//!
+//! The database sits next to the legacy `schedules.json`, as `schedules.db`. Each job's next
+//! run time is stored with it, so a run that came due while goose was stopped starts once as
+//! soon as it is back.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex as StdMutex, Weak};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, Row};
+use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
+
+use crate::schedule_calendar;
+use crate::scheduler::{
+    next_cron_run, notify_job_finished, order_by_dependencies, remove_env, run_queued_when_online,
+    run_scheduled_job_in_session, sessions_for_schedule, store_recipe, JobEnvironment, JobNode,
+    JobOutcome, ScheduledJob, SchedulerError,
+};
+use crate::scheduler_trait::SchedulerTrait;
+use crate::session;
+use crate::session::storage::SessionMetadata;
+
+/// How often the table is checked for jobs that have come due
+const TICK: Duration = Duration::from_secs(1);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    source TEXT NOT NULL,
+    cron TEXT NOT NULL DEFAULT '',
+    run_at INTEGER,
+    execution_mode TEXT,
+    paused INTEGER NOT NULL DEFAULT 0,
+    last_run INTEGER,
+    next_run INTEGER,
+    last_outcome TEXT,
+    last_finished INTEGER,
+    environment TEXT
+);
+CREATE INDEX IF NOT EXISTS jobs_next_run ON jobs (next_run);
+CREATE TABLE IF NOT EXISTS job_dependencies (
+    job_id TEXT NOT NULL,
+    depends_on TEXT NOT NULL,
+    PRIMARY KEY (job_id, depends_on)
+);
+CREATE INDEX IF NOT EXISTS job_dependencies_depends_on ON job_dependencies (depends_on);
+";
+
+/// Columns added to `jobs` since it was first created, with their definitions
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("last_outcome", "TEXT"),
+    ("last_finished", "INTEGER"),
+    ("environment", "TEXT"),
+];
+
+/// Bring a database created by an earlier version up to `SCHEMA`, which has already created
+/// any missing tables. The first version kept a single dependency in `jobs.depends_on`.
+fn migrate(db: &Connection) -> rusqlite::Result<()> {
+    let columns = db
+        .prepare("SELECT name FROM pragma_table_info('jobs')")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (column, definition) in ADDED_COLUMNS {
+        if !columns.iter().any(|c| c == column) {
+            db.execute_batch(&format!(
+                "ALTER TABLE jobs ADD COLUMN {} {}",
+                column, definition
+            ))?;
+        }
+    }
+    if columns.iter().any(|c| c == "depends_on") {
+        db.execute_batch(
+            "BEGIN;
+             INSERT OR IGNORE INTO job_dependencies (job_id, depends_on)
+                 SELECT id, depends_on FROM jobs WHERE depends_on IS NOT NULL;
+             DROP INDEX IF EXISTS jobs_depends_on;
+             ALTER TABLE jobs DROP COLUMN depends_on;
+             COMMIT;",
+        )?;
+    }
+    Ok(())
+}
+
+const COLUMNS: &str = "id, source, cron, run_at, execution_mode, paused, last_run, environment";
+
+impl From<rusqlite::Error> for SchedulerError {
+    fn from(err: rusqlite::Error) -> Self {
+        SchedulerError::PersistError(err.to_string())
+    }
+}
+
+fn timestamp(seconds: Option<i64>) -> Option<DateTime<Utc>> {
+    seconds.and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+}
+
+fn job_from_row(row: &Row) -> rusqlite::Result<ScheduledJob> {
+    Ok(ScheduledJob {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        cron: row.get(2)?,
+        run_at: timestamp(row.get(3)?),
+        depends_on: Vec::new(),
+        execution_mode: row.get(4)?,
+        paused: row.get(5)?,
+        last_run: timestamp(row.get(6)?),
+        // A job that can't tell where and with what it runs mustn't quietly run elsewhere
+        environment: match row.get::<_, Option<String>>(7)? {
+            Some(json) => serde_json::from_str(&json).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(7, Type::Text, Box::new(e))
+            })?,
+            None => JobEnvironment::default(),
+        },
+        currently_running: false,
+        current_session_id: None,
+        process_start_time: None,
+    })
+}
+
+fn outcome_name(outcome: JobOutcome) -> &'static str {
+    match outcome {
+        JobOutcome::Succeeded => "succeeded",
+        JobOutcome::Failed => "failed",
+        JobOutcome::Skipped => "skipped",
+    }
+}
+
+fn parse_outcome(name: &str) -> Option<JobOutcome> {
+    match name {
+        "succeeded" => Some(JobOutcome::Succeeded),
+        "failed" => Some(JobOutcome::Failed),
+        "skipped" => Some(JobOutcome::Skipped),
+        _ => None,
+    }
+}
+
+/// The jobs `id` waits for
+fn dependencies(db: &Connection, id: &str) -> rusqlite::Result<Vec<String>> {
+    db.prepare_cached(
+        "SELECT depends_on FROM job_dependencies WHERE job_id = ?1 ORDER BY depends_on",
+    )?
+    .query_map(params![id], |row| row.get(0))?
+    .collect()
+}
+
+/// The jobs that wait for `id`
+fn dependents(db: &Connection, id: &str) -> rusqlite::Result<Vec<String>> {
+    db.prepare_cached("SELECT job_id FROM job_dependencies WHERE depends_on = ?1 ORDER BY job_id")?
+        .query_map(params![id], |row| row.get(0))?
+        .collect()
+}
+
+/// Jobs matching `filter`, a WHERE and/or ORDER BY clause, with the jobs each depends on
+fn select_jobs(
+    db: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> rusqlite::Result<Vec<ScheduledJob>> {
+    let mut jobs = db
+        .prepare(&format!("SELECT {} FROM jobs {}", COLUMNS, filter))?
+        .query_map(params, job_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for job in &mut jobs {
+        job.depends_on = dependencies(db, &job.id)?;
+    }
+    Ok(jobs)
+}
+
+/// Whether every job `id` depends on has succeeded since `id` last started
+fn dependencies_met(db: &Connection, id: &str) -> rusqlite::Result<bool> {
+    let unmet: i64 = db.query_row(
+        "SELECT COUNT(*) FROM job_dependencies d
+             JOIN jobs dependency ON dependency.id = d.depends_on
+             JOIN jobs job ON job.id = d.job_id
+         WHERE d.job_id = ?1
+             AND (dependency.last_outcome IS NOT 'succeeded'
+                  OR dependency.last_finished <= IFNULL(job.last_run, -1))",
+        params![id],
+        |row| row.get(0),
+    )?;
+    Ok(unmet == 0)
+}
+
+/// Jobs have exactly one trigger: a cron expression, a run-at time or jobs to follow
+fn validate(job: &ScheduledJob) -> Result<(), SchedulerError> {
+    if job.depends_on.contains(&job.id) {
+        return Err(SchedulerError::InvalidJob(format!(
+            "job '{}' can't run after itself",
+            job.id
+        )));
+    }
+    let triggers = [
+        !job.cron.trim().is_empty(),
+        job.run_at.is_some(),
+        !job.depends_on.is_empty(),
+    ];
+    match triggers.iter().filter(|set| **set).count() {
+        1 => Ok(()),
+        0 => Err(SchedulerError::InvalidJob(
+            "a job needs a cron expression, a time to run at or a job to run after".to_string(),
+        )),
+        _ => Err(SchedulerError::InvalidJob(
+            "give only one of a cron expression, a time to run at or a job to run after"
+                .to_string(),
+        )),
+    }
+}
+
+/// When a newly added `job` first runs. Chained jobs have no time of their own.
+fn first_run(
+    job: &ScheduledJob,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, SchedulerError> {
+    match job.run_at {
+        Some(run_at) => Ok(Some(run_at)),
+        None if !job.depends_on.is_empty() => Ok(None),
+        None => next_cron_run(&job.cron, now).map(Some),
+    }
+}
+
+struct RunningJob {
+    abort: AbortHandle,
+    started_at: DateTime<Utc>,
+    session_id: String,
+}
+
+pub struct SqliteScheduler {
+    db: StdMutex<Connection>,
+    running: Mutex<HashMap<String, RunningJob>>,
+}
+
+impl SqliteScheduler {
+    pub async fn new(db_path: PathBuf) -> Result<Arc<Self>, SchedulerError> {
+        if let Some(parent) = db_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let scheduler = Arc::new(Self::open(Connection::open(&db_path)?)?);
+        tracing::info!("SQLite scheduler using {}", db_path.display());
+        tokio::spawn(run_due_jobs(Arc::downgrade(&scheduler)));
+        tokio::spawn(run_queued_when_online(Arc::downgrade(&scheduler)));
+        Ok(scheduler)
+    }
+
+    fn open(connection: Connection) -> Result<Self, SchedulerError> {
+        connection.execute_batch(SCHEMA)?;
+        migrate(&connection)?;
+        Ok(Self {
+            db: StdMutex::new(connection),
+            running: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn find_job(&self, id: &str) -> Result<Option<ScheduledJob>, SchedulerError> {
+        Ok(select_jobs(&self.db.lock().unwrap(), "WHERE id = ?1", params![id])?.pop())
+    }
+
+    fn get_job(&self, id: &str) -> Result<ScheduledJob, SchedulerError> {
+        self.find_job(id)?
+            .ok_or_else(|| SchedulerError::JobNotFound(id.to_string()))
+    }
+
+    fn insert(
+        &self,
+        job: &ScheduledJob,
+        next_run: Option<DateTime<Utc>>,
+    ) -> Result<(), SchedulerError> {
+        let environment = serde_json::to_string(&job.environment)?;
+        let mut db = self.db.lock().unwrap();
+        let tx = db.transaction()?;
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO jobs
+                 (id, source, cron, run_at, execution_mode, paused, next_run, environment)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                job.id,
+                job.source,
+                job.cron,
+                job.run_at.map(|t| t.timestamp()),
+                job.execution_mode,
+                job.paused,
+                next_run.map(|t| t.timestamp()),
+                environment,
+            ],
+        )?;
+        if inserted == 0 {
+            return Err(SchedulerError::JobIdExists(job.id.clone()));
+        }
+        for dependency in &job.depends_on {
+            tx.execute(
+                "INSERT OR IGNORE INTO job_dependencies (job_id, depends_on) VALUES (?1, ?2)",
+                params![job.id, dependency],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Jobs that are due at `now`, moving each one's next run past `now`
+    fn take_due(&self, now: DateTime<Utc>) -> Result<Vec<ScheduledJob>, SchedulerError> {
+        let db = self.db.lock().unwrap();
+        let due = select_jobs(
+            &db,
+            "WHERE paused = 0 AND next_run <= ?1 ORDER BY next_run, id",
+            params![now.timestamp()],
+        )?;
+        for job in &due {
+            // One-shot and chained jobs wait to be triggered again
+            let next_run = if job.run_at.is_none() && job.depends_on.is_empty() {
+                match next_cron_run(&job.cron, now) {
+                    Ok(next_run) => Some(next_run.timestamp()),
+                    Err(e) => {
+                        tracing::error!("Job '{}' has a bad schedule: {}", job.id, e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            db.execute(
+                "UPDATE jobs SET last_run = ?1, next_run = ?2 WHERE id = ?3",
+                params![now.timestamp(), next_run, job.id],
+            )?;
+        }
+        Ok(due)
+    }
+
+    /// Record how a run of `id` ended. After a success, dependents whose other dependencies
+    /// have also succeeded become due; after a failure everything downstream of `id` is
+    /// skipped, and the skipped ids are returned. A run skipped for a blackout leaves its
+    /// dependents to wait for the next one.
+    fn finish(
+        &self,
+        id: &str,
+        outcome: JobOutcome,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<String>, SchedulerError> {
+        let db = self.db.lock().unwrap();
+        db.execute(
+            "UPDATE jobs SET last_outcome = ?1, last_finished = ?2 WHERE id = ?3",
+            params![outcome_name(outcome), now.timestamp(), id],
+        )?;
+        if outcome == JobOutcome::Skipped {
+            return Ok(Vec::new());
+        }
+
+        let mut skipped: Vec<String> = Vec::new();
+        let mut pending = dependents(&db, id)?;
+        while let Some(dependent) = pending.pop() {
+            if outcome == JobOutcome::Succeeded {
+                if dependencies_met(&db, &dependent)? {
+                    db.execute(
+                        "UPDATE jobs SET next_run = ?1 WHERE id = ?2",
+                        params![now.timestamp(), dependent],
+                    )?;
+                }
+            } else if !skipped.contains(&dependent) {
+                db.execute(
+                    "UPDATE jobs SET last_outcome = ?1, last_finished = ?2, next_run = NULL
+                     WHERE id = ?3",
+                    params![
+                        outcome_name(JobOutcome::Skipped),
+                        now.timestamp(),
+                        dependent
+                    ],
+                )?;
+                pending.extend(dependents(&db, &dependent)?);
+                skipped.push(dependent);
+            }
+        }
+        Ok(skipped)
+    }
+
+    async fn execute(&self, job: ScheduledJob) -> Result<String, SchedulerError> {
+        let session_id = session::generate_session_id();
+        let task = tokio::spawn(run_scheduled_job_in_session(
+            job.clone(),
+            session_id.clone(),
+            None,
+            None,
+            None,
+        ));
+        self.running.lock().await.insert(
+            job.id.clone(),
+            RunningJob {
+                abort: task.abort_handle(),
+                started_at: Utc::now(),
+                session_id,
+            },
+        );
+        let result = task.await;
+        self.running.lock().await.remove(&job.id);
+
+        let outcome = if matches!(result, Ok(Ok(_))) {
+            JobOutcome::Succeeded
+        } else {
+            JobOutcome::Failed
+        };
+        match self.finish(&job.id, outcome, Utc::now()) {
+            Ok(skipped) if !skipped.is_empty() => tracing::warn!(
+                "Skipping {} because '{}' did not succeed",
+                skipped.join(", "),
+                job.id
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to record how job '{}' ended: {}", job.id, e),
+        }
+
+        let error = match result {
+            Ok(Ok(session_id)) => {
+                tracing::info!("Scheduled job '{}' completed successfully", job.id);
+                notify_job_finished(&job.id, None);
+                return Ok(session_id);
+            }
+            Ok(Err(e)) => e.error,
+            Err(e) if e.is_cancelled() => {
+                tracing::info!("Scheduled job '{}' was cancelled/killed", job.id);
+                return Err(SchedulerError::AnyhowError(anyhow!(
+                    "Job '{}' was successfully cancelled",
+                    job.id
+                )));
+            }
+            Err(e) => e.to_string(),
+        };
+        tracing::error!("Scheduled job '{}' execution failed: {}", job.id, error);
+        notify_job_finished(&job.id, Some(&error));
+        Err(SchedulerError::AnyhowError(anyhow!(
+            "Failed to execute job '{}': {}",
+            job.id,
+            error
+        )))
+    }
+
+    async fn ensure_not_running(&self, id: &str, action: &str) -> Result<(), SchedulerError> {
+        if self.running.lock().await.contains_key(id) {
+            return Err(SchedulerError::AnyhowError(anyhow!(
+                "Cannot {} schedule '{}' while it's currently running",
+                action,
+                id
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Start jobs as they come due, for as long as the scheduler exists
+async fn run_due_jobs(scheduler: Weak<SqliteScheduler>) {
+    let mut interval = tokio::time::interval(TICK);
+    loop {
+        interval.tick().await;
+        let Some(scheduler) = scheduler.upgrade() else {
+            return;
+        };
+        let due = match scheduler.take_due(Utc::now()) {
+            Ok(due) => due,
+            Err(e) => {
+                tracing::error!("Failed to read due jobs: {}", e);
+                continue;
+            }
+        };
+        for job in due {
+            if scheduler.running.lock().await.contains_key(&job.id) {
+                tracing::info!("Skipping run of job '{}', which is still running", job.id);
+                continue;
+            }
+            let now = Utc::now();
+            if schedule_calendar::blacked_out(&job.id, now) {
+                if let Err(e) = scheduler.finish(&job.id, JobOutcome::Skipped, now) {
+                    tracing::error!("Failed to record skipping job '{}': {}", job.id, e);
+                }
+                continue;
+            }
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                let _ = scheduler.execute(job).await;
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl SchedulerTrait for SqliteScheduler {
+    async fn add_scheduled_job(&self, job: ScheduledJob) -> Result<(), SchedulerError> {
+        validate(&job)?;
+        if self.find_job(&job.id)?.is_some() {
+            return Err(SchedulerError::JobIdExists(job.id));
+        }
+        // Dependencies must already exist, so the graph can't contain a cycle
+        for dependency in &job.depends_on {
+            self.get_job(dependency)?;
+        }
+        let next_run = first_run(&job, Utc::now())?;
+
+        let mut stored_job = job.clone();
+        stored_job.source = store_recipe(&job)?;
+        stored_job.environment.store_env(&stored_job.id)?;
+        stored_job.last_run = None;
+        self.insert(&stored_job, next_run)
+    }
+
+    async fn list_scheduled_jobs(&self) -> Result<Vec<ScheduledJob>, SchedulerError> {
+        let mut jobs = select_jobs(&self.db.lock().unwrap(), "ORDER BY id", [])?;
+        let running = self.running.lock().await;
+        for job in &mut jobs {
+            if let Some(run) = running.get(&job.id) {
+                job.currently_running = true;
+                job.current_session_id = Some(run.session_id.clone());
+                job.process_start_time = Some(run.started_at);
+            }
+        }
+        Ok(jobs)
+    }
+
+    async fn remove_scheduled_job(&self, id: &str) -> Result<(), SchedulerError> {
+        let job = self.get_job(id)?;
+        let dependents = dependents(&self.db.lock().unwrap(), id)?;
+        if !dependents.is_empty() {
+            return Err(SchedulerError::AnyhowError(anyhow!(
+                "Cannot remove schedule '{}': {} run after it",
+                id,
+                dependents.join(", ")
+            )));
+        }
+
+        let db = self.db.lock().unwrap();
+        db.execute(
+            "DELETE FROM job_dependencies WHERE job_id = ?1",
+            params![id],
+        )?;
+        db.execute("DELETE FROM jobs WHERE id = ?1", params![id])?;
+        let recipe_path = std::path::Path::new(&job.source);
+        if recipe_path.exists() {
+            fs::remove_file(recipe_path)?;
+        }
+        remove_env(&job);
+        Ok(())
+    }
+
+    async fn pause_schedule(&self, id: &str) -> Result<(), SchedulerError> {
+        self.get_job(id)?;
+        self.ensure_not_running(id, "pause").await?;
+        self.db
+            .lock()
+            .unwrap()
+            .execute("UPDATE jobs SET paused = 1 WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    async fn unpause_schedule(&self, id: &str) -> Result<(), SchedulerError> {
+        let job = self.get_job(id)?;
+        let db = self.db.lock().unwrap();
+        db.execute("UPDATE jobs SET paused = 0 WHERE id = ?1", params![id])?;
+        // Cron runs missed while paused are skipped rather than made up
+        if job.run_at.is_none() && job.depends_on.is_empty() {
+            let next_run = next_cron_run(&job.cron, Utc::now())?;
+            db.execute(
+                "UPDATE jobs SET next_run = ?1 WHERE id = ?2",
+                params![next_run.timestamp(), id],
+            )?;
+        }
+        Ok(())
+    }
+
+    async fn run_now(&self, id: &str) -> Result<String, SchedulerError> {
+        let job = self.get_job(id)?;
+        self.db.lock().unwrap().execute(
+            "UPDATE jobs SET last_run = ?1 WHERE id = ?2",
+            params![Utc::now().timestamp(), id],
+        )?;
+        self.execute(job).await
+    }
+
+    async fn sessions(
+        &self,
+        sched_id: &str,
+        limit: usize,
+    ) -> Result<Vec<(String, SessionMetadata)>, SchedulerError> {
+        sessions_for_schedule(sched_id, limit)
+    }
+
+    /// Puts the job on `new_cron`, replacing a run-at time or dependencies it had
+    async fn update_schedule(
+        &self,
+        sched_id: &str,
+        new_cron: String,
+    ) -> Result<(), SchedulerError> {
+        self.get_job(sched_id)?;
+        self.ensure_not_running(sched_id, "edit").await?;
+        let next_run = next_cron_run(&new_cron, Utc::now())?;
+        let db = self.db.lock().unwrap();
+        db.execute(
+            "UPDATE jobs SET cron = ?1, run_at = NULL, next_run = ?2 WHERE id = ?3",
+            params![new_cron, next_run.timestamp(), sched_id],
+        )?;
+        db.execute(
+            "DELETE FROM job_dependencies WHERE job_id = ?1",
+            params![sched_id],
+        )?;
+        Ok(())
+    }
+
+    async fn kill_running_job(&self, sched_id: &str) -> Result<(), SchedulerError> {
+        self.get_job(sched_id)?;
+        match self.running.lock().await.remove(sched_id) {
+            Some(run) => {
+                tracing::info!("Killing running job '{}'", sched_id);
+                run.abort.abort();
+                Ok(())
+            }
+            None => Err(SchedulerError::AnyhowError(anyhow!(
+                "Schedule '{}' is not currently running",
+                sched_id
+            ))),
+        }
+    }
+
+    async fn get_running_job_info(
+        &self,
+        sched_id: &str,
+    ) -> Result<Option<(String, DateTime<Utc>)>, SchedulerError> {
+        self.get_job(sched_id)?;
+        Ok(self
+            .running
+            .lock()
+            .await
+            .get(sched_id)
+            .map(|run| (run.session_id.clone(), run.started_at)))
+    }
+
+    async fn dependency_graph(&self) -> Result<Vec<JobNode>, SchedulerError> {
+        let db = self.db.lock().unwrap();
+        let nodes = db
+            .prepare("SELECT id, last_outcome FROM jobs")?
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .map(|(id, outcome)| {
+                Ok(JobNode {
+                    depends_on: dependencies(&db, &id)?,
+                    last_outcome: outcome.as_deref().and_then(parse_outcome),
+                    id,
+                })
+            })
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(order_by_dependencies(nodes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn scheduler() -> SqliteScheduler {
+        SqliteScheduler::open(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn job(id: &str) -> ScheduledJob {
+        ScheduledJob {
+            id: id.to_string(),
+            source: format!("/nonexistent/{}.yaml", id),
+            cron: String::new(),
+            last_run: None,
+            currently_running: false,
+            paused: false,
+            current_session_id: None,
+            process_start_time: None,
+            execution_mode: Some("background".to_string()),
+            run_at: None,
+            depends_on: Vec::new(),
+            environment: Default::default(),
+        }
+    }
+
+    fn ids(jobs: Vec<ScheduledJob>) -> Vec<String> {
+        jobs.into_iter().map(|job| job.id).collect()
+    }
+
+    #[test]
+    fn test_next_cron_run() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 10, 0, 0).unwrap();
+        assert_eq!(
+            next_cron_run("0 9 * * *", now).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 2, 9, 0, 0).unwrap()
+        );
+        assert_eq!(
+            next_cron_run("30 15 10 * * *", now).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 1, 10, 15, 30).unwrap()
+        );
+        assert!(next_cron_run("not a cron", now).is_err());
+    }
+
+    #[test]
+    fn test_one_trigger_per_job() {
+        assert!(validate(&job("none")).is_err());
+        let mut both = job("both");
+        both.cron = "0 9 * * *".to_string();
+        both.run_at = Some(Utc::now());
+        assert!(validate(&both).is_err());
+    }
+
+    #[test]
+    fn test_one_shot_job_runs_once() {
+        let scheduler = scheduler();
+        let now = Utc::now();
+        let mut once = job("once");
+        once.run_at = Some(now);
+        scheduler
+            .insert(&once, first_run(&once, now).unwrap())
+            .unwrap();
+
+        assert!(scheduler
+            .take_due(now - chrono::Duration::seconds(1))
+            .unwrap()
+            .is_empty());
+        assert_eq!(ids(scheduler.take_due(now).unwrap()), vec!["once"]);
+        assert!(scheduler
+            .take_due(now + chrono::Duration::days(1))
+            .unwrap()
+            .is_empty());
+        assert!(scheduler.get_job("once").unwrap().last_run.is_some());
+    }
+
+    #[test]
+    fn test_cron_job_comes_due_again() {
+        let scheduler = scheduler();
+        let now = Utc::now();
+        let mut hourly = job("hourly");
+        hourly.cron = "0 0 * * * *".to_string();
+        scheduler
+            .insert(&hourly, first_run(&hourly, now).unwrap())
+            .unwrap();
+
+        let in_an_hour = now + chrono::Duration::hours(1);
+        assert_eq!(ids(scheduler.take_due(in_an_hour).unwrap()), vec!["hourly"]);
+        assert!(scheduler.take_due(in_an_hour).unwrap().is_empty());
+        let in_two_hours = now + chrono::Duration::hours(2);
+        assert_eq!(
+            ids(scheduler.take_due(in_two_hours).unwrap()),
+            vec!["hourly"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dependents_wait_for_every_dependency() {
+        let scheduler = scheduler();
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 10, 30, 0).unwrap();
+        for id in ["a", "b"] {
+            let mut hourly = job(id);
+            hourly.cron = "0 0 * * * *".to_string();
+            scheduler
+                .insert(&hourly, first_run(&hourly, now).unwrap())
+                .unwrap();
+        }
+        let mut c = job("c");
+        c.depends_on = vec!["a".to_string(), "b".to_string()];
+        scheduler.insert(&c, None).unwrap();
+        let mut d = job("d");
+        d.depends_on = vec!["c".to_string()];
+        scheduler.insert(&d, None).unwrap();
+
+        let later = now + chrono::Duration::hours(1);
+        assert_eq!(ids(scheduler.take_due(later).unwrap()), vec!["a", "b"]);
+        scheduler.finish("a", JobOutcome::Succeeded, later).unwrap();
+        assert!(scheduler.take_due(later).unwrap().is_empty());
+        scheduler.finish("b", JobOutcome::Succeeded, later).unwrap();
+        assert_eq!(ids(scheduler.take_due(later).unwrap()), vec!["c"]);
+        scheduler.finish("c", JobOutcome::Succeeded, later).unwrap();
+        assert_eq!(ids(scheduler.take_due(later).unwrap()), vec!["d"]);
+
+        // c needs fresh successes from both a and b before it runs again
+        let next = later + chrono::Duration::minutes(1);
+        scheduler.finish("a", JobOutcome::Succeeded, next).unwrap();
+        assert!(scheduler.take_due(next).unwrap().is_empty());
+        let skipped = scheduler.finish("b", JobOutcome::Failed, next).unwrap();
+        assert_eq!(skipped, vec!["c", "d"]);
+        assert!(scheduler.take_due(next).unwrap().is_empty());
+
+        let graph = scheduler.dependency_graph().await.unwrap();
+        let order: Vec<&str> = graph.iter().map(|node| node.id.as_str()).collect();
+        assert_eq!(order, vec!["a", "b", "c", "d"]);
+        assert_eq!(graph[2].depends_on, vec!["a", "b"]);
+        assert_eq!(graph[1].last_outcome, Some(JobOutcome::Failed));
+        assert_eq!(graph[3].last_outcome, Some(JobOutcome::Skipped));
+    }
+
+    #[tokio::test]
+    async fn test_blacked_out_run_leaves_dependents_alone() {
+        let scheduler = scheduler();
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 10, 30, 0).unwrap();
+        let mut a = job("a");
+        a.cron = "0 0 * * * *".to_string();
+        scheduler.insert(&a, first_run(&a, now).unwrap()).unwrap();
+        let mut b = job("b");
+        b.depends_on = vec!["a".to_string()];
+        scheduler.insert(&b, None).unwrap();
+
+        let later = now + chrono::Duration::hours(1);
+        assert_eq!(ids(scheduler.take_due(later).unwrap()), vec!["a"]);
+        assert!(scheduler
+            .finish("a", JobOutcome::Skipped, later)
+            .unwrap()
+            .is_empty());
+        assert!(scheduler.take_due(later).unwrap().is_empty());
+
+        let graph = scheduler.dependency_graph().await.unwrap();
+        assert_eq!(graph[0].last_outcome, Some(JobOutcome::Skipped));
+        assert_eq!(graph[1].last_outcome, None);
+    }
+
+    #[tokio::test]
+    async fn test_older_databases_are_migrated() {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(
+            "CREATE TABLE jobs (
+                 id TEXT PRIMARY KEY,
+                 source TEXT NOT NULL,
+                 cron TEXT NOT NULL DEFAULT '',
+                 run_at INTEGER,
+                 depends_on TEXT,
+                 execution_mode TEXT,
+                 paused INTEGER NOT NULL DEFAULT 0,
+                 last_run INTEGER,
+                 next_run INTEGER
+             );
+             CREATE INDEX jobs_depends_on ON jobs (depends_on);
+             INSERT INTO jobs (id, source, cron) VALUES ('a', 'a.yaml', '0 0 * * * *');
+             INSERT INTO jobs (id, source, depends_on) VALUES ('b', 'b.yaml', 'a');",
+        )
+        .unwrap();
+        let scheduler = SqliteScheduler::open(db).unwrap();
+
+        let jobs = scheduler.list_scheduled_jobs().await.unwrap();
+        assert_eq!(ids(jobs.clone()), vec!["a", "b"]);
+        assert_eq!(jobs[1].depends_on, vec!["a"]);
+        scheduler
+            .finish("a", JobOutcome::Succeeded, Utc::now())
+            .unwrap();
+        assert_eq!(ids(scheduler.take_due(Utc::now()).unwrap()), vec!["b"]);
+
+        // Opening an up-to-date database again changes nothing
+        let db = scheduler.db.into_inner().unwrap();
+        let scheduler = SqliteScheduler::open(db).unwrap();
+        assert_eq!(scheduler.get_job("b").unwrap().depends_on, vec!["a"]);
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_must_exist() {
+        let scheduler = scheduler();
+        let mut first = job("first");
+        first.cron = "0 0 * * * *".to_string();
+        scheduler.insert(&first, None).unwrap();
+
+        let mut orphan = job("orphan");
+        orphan.depends_on = vec!["first".to_string(), "missing".to_string()];
+        assert!(matches!(
+            scheduler.add_scheduled_job(orphan).await,
+            Err(SchedulerError::JobNotFound(_))
+        ));
+        let mut own = job("own");
+        own.depends_on = vec!["own".to_string()];
+        assert!(matches!(validate(&own), Err(SchedulerError::InvalidJob(_))));
+
+        let mut second = job("second");
+        second.depends_on = vec!["first".to_string()];
+        scheduler.insert(&second, None).unwrap();
+        assert!(scheduler.remove_scheduled_job("first").await.is_err());
+        scheduler.remove_scheduled_job("second").await.unwrap();
+        scheduler.remove_scheduled_job("first").await.unwrap();
+        assert!(scheduler.list_scheduled_jobs().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Style presets: standing instructions on how goose writes its answers, added to the
+//! system prompt of every session whether it runs in the CLI, goosed or from a recipe.
+//!
+//! ```yaml
+//! GOOSE_STYLE: terse                 # preset for sessions that don't choose one
+//! GOOSE_STYLE_PRESETS:               # your own presets, or replacements for built-in ones
+//!   reviewer: Point out risks and missing tests before anything else.
+//! ```
+//!
+//! A recipe can pick a preset in its settings, and a session can switch presets at any
+//! time; the session's choice is kept in its metadata and wins over both.
+
+use crate::config::Config;
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+pub const STYLE_KEY: &str = "GOOSE_STYLE";
+pub const STYLE_PRESETS_KEY: &str = "GOOSE_STYLE_PRESETS";
+
+const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    (
+        "terse",
+        "Keep answers short. Lead with the result, skip preamble and recaps, and only explain when asked.",
+    ),
+    (
+        "verbose",
+        "Explain your reasoning as you work, mention the alternatives you considered, and end each task with a summary of what changed and why.",
+    ),
+    (
+        "code-only",
+        "Answer with code alone wherever possible. Put any explanation that is strictly needed in code comments.",
+    ),
+    (
+        "explain-like-junior",
+        "Explain as you would to a junior developer: define terms the first time you use them, say why each step is needed, and point out common mistakes to avoid.",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct StylePreset {
+    pub name: String,
+    pub instructions: String,
+    /// Whether goose ships this preset; false for presets defined or replaced in the config
+    pub builtin: bool,
+}
+
+/// The built-in presets with `custom` layered over them, built-in names first
+fn merge_presets(custom: HashMap<String, String>) -> Vec<StylePreset> {
+    let mut custom = custom;
+    let mut presets: Vec<StylePreset> = BUILTIN_PRESETS
+        .iter()
+        .map(|(name, instructions)| match custom.remove(*name) {
+            Some(instructions) => StylePreset {
+                name: name.to_string(),
+                instructions,
+                builtin: false,
+            },
+            None => StylePreset {
+                name: name.to_string(),
+                instructions: instructions.to_string(),
+                builtin: true,
+            },
+        })
+        .collect();
+    let mut added: Vec<StylePreset> = custom
+        .into_iter()
+        .map(|(name, instructions)| StylePreset {
+            name,
+            instructions,
+            builtin: false,
+        })
+        .collect();
+    added.sort_by(|a, b| a.name.cmp(&b.name));
+    presets.extend(added);
+    presets
+}
+
+/// Every preset available, built-in ones first
+pub fn presets() -> Vec<StylePreset> {
+    merge_presets(
+        Config::global()
+            .get_param(STYLE_PRESETS_KEY)
+            .unwrap_or_default(),
+    )
+}
+
+pub fn find_preset(name: &str) -> Option<StylePreset> {
+    presets().into_iter().find(|preset| preset.name == name)
+}
+
+/// The preset sessions use when neither they nor their recipe choose one
+pub fn default_style() -> Option<String> {
+    Config::global()
+        .get_param::<String>(STYLE_KEY)
+        .ok()
+        .filter(|style| !style.trim().is_empty())
+}
+
+/// Instructions for the preset named `style`, or for the default preset when there is no
+/// name. Unknown names are logged and ignored so a removed preset never breaks a session.
+pub fn style_instructions(style: Option<&str>) -> Option<String> {
+    let name = style.map(str::to_string).or_else(default_style)?;
+    match find_preset(&name) {
+        Some(preset) => Some(preset.instructions),
+        None => {
+            tracing::warn!("Unknown style preset '{}', ignoring it", name);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_presets_replace_and_extend_builtins() {
+        let custom = HashMap::from([
+            ("terse".to_string(), "One line at most.".to_string()),
+            ("reviewer".to_string(), "Point out risks.".to_string()),
+            ("architect".to_string(), "Think in systems.".to_string()),
+        ]);
+        let presets = merge_presets(custom);
+
+        let names: Vec<&str> = presets.iter().map(|preset| preset.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "terse",
+                "verbose",
+                "code-only",
+                "explain-like-junior",
+                "architect",
+                "reviewer"
+            ]
+        );
+        assert_eq!(presets[0].instructions, "One line at most.");
+        assert!(!presets[0].builtin);
+        assert!(presets[1].builtin);
+    }
+}
@@ -545,6 +545,14 @@ impl TemporalScheduler {
             "TemporalScheduler: add_scheduled_job() called for job '{}'",
             job.id
         );
+        job.require_cron("Temporal")?;
+        if !job.environment.is_inherited() {
+            return Err(SchedulerError::SchedulerInternalError(
+                "the Temporal scheduler runs every job in the service's own environment; set \
+                 GOOSE_SCHEDULER_TYPE=legacy or sqlite to give a job its own"
+                    .to_string(),
+            ));
+        }
 
         // Normalize the cron expression to ensure it's 6-field format
         let normalized_cron = normalize_cron_expression(&job.cron);
@@ -601,6 +609,9 @@ impl TemporalScheduler {
                         current_session_id: None, // Not provided by Temporal service
                         process_start_time: None, // Not provided by Temporal service
                         execution_mode: tj.execution_mode,
+                        run_at: None,
+                        depends_on: Vec::new(),
+                        environment: Default::default(),
                     }
                 })
                 .collect();
@@ -6,7 +6,7 @@ use std::sync::Arc;
 use tiktoken_rs::CoreBPE;
 use tokio::sync::OnceCell;
 
-use crate::conversation::message::Message;
+use crate::conversation::message::{Message, ToolRequest};
 
 // Global tokenizer instance to avoid repeated initialization
 static TOKENIZER: OnceCell<Arc<CoreBPE>> = OnceCell::const_new();
@@ -64,6 +64,15 @@ impl AsyncTokenCounter {
         count
     }
 
+    /// `text` cut down to its first `max_tokens` tokens, or `None` when it already fits
+    pub fn truncate_to_tokens(&self, text: &str, max_tokens: usize) -> Option<String> {
+        let tokens = self.tokenizer.encode_with_special_tokens(text);
+        if tokens.len() <= max_tokens {
+            return None;
+        }
+        self.tokenizer.decode(tokens[..max_tokens].to_vec()).ok()
+    }
+
     /// Count tokens for tools with optimized string handling
     pub fn count_tokens_for_tools(&self, tools: &[Tool]) -> usize {
         // Token counts for different function components
@@ -126,6 +135,22 @@ impl AsyncTokenCounter {
         func_token_count
     }
 
+    /// Tokens one message takes up in a chat request, including its role framing
+    pub fn count_message_tokens(&self, message: &Message) -> usize {
+        let tokens_per_message = 4;
+        let mut num_tokens = tokens_per_message;
+        for content in &message.content {
+            if let Some(content_text) = content.as_text() {
+                num_tokens += self.count_tokens(content_text);
+            } else if let Some(tool_request) = content.as_tool_request() {
+                num_tokens += self.count_tokens(&tool_request_text(tool_request));
+            } else if let Some(tool_response_text) = content.as_tool_response_text() {
+                num_tokens += self.count_tokens(&tool_response_text);
+            }
+        }
+        num_tokens
+    }
+
     /// Count chat tokens (using cached count_tokens)
     pub fn count_chat_tokens(
         &self,
@@ -141,22 +166,7 @@ impl AsyncTokenCounter {
         }
 
         for message in messages {
-            num_tokens += tokens_per_message;
-            for content in &message.content {
-                if let Some(content_text) = content.as_text() {
-                    num_tokens += self.count_tokens(content_text);
-                } else if let Some(tool_request) = content.as_tool_request() {
-                    let tool_call = tool_request.tool_call.as_ref().unwrap();
-                    // Note: separators are tokenized with adjacent tokens, keep original for accuracy
-                    let text = format!(
-                        "{}:{}:{}",
-                        tool_request.id, tool_call.name, tool_call.arguments
-                    );
-                    num_tokens += self.count_tokens(&text);
-                } else if let Some(tool_response_text) = content.as_tool_response_text() {
-                    num_tokens += self.count_tokens(&tool_response_text);
-                }
-            }
+            num_tokens += self.count_message_tokens(message);
         }
 
         if !tools.is_empty() {
@@ -295,12 +305,7 @@ impl TokenCounter {
                 if let Some(content_text) = content.as_text() {
                     num_tokens += self.count_tokens(content_text);
                 } else if let Some(tool_request) = content.as_tool_request() {
-                    let tool_call = tool_request.tool_call.as_ref().unwrap();
-                    let text = format!(
-                        "{}:{}:{}",
-                        tool_request.id, tool_call.name, tool_call.arguments
-                    );
-                    num_tokens += self.count_tokens(&text);
+                    num_tokens += self.count_tokens(&tool_request_text(tool_request));
                 } else if let Some(tool_response_text) = content.as_tool_response_text() {
                     num_tokens += self.count_tokens(&tool_response_text);
                 } else {
@@ -354,6 +359,19 @@ async fn get_tokenizer() -> Result<Arc<CoreBPE>, String> {
 }
 
 /// Get the global tokenizer instance (blocking version for backward compatibility)
+/// What a tool request amounts to in a chat request. A call the model got wrong is sent back
+/// to it as its error, so that is what gets counted.
+fn tool_request_text(tool_request: &ToolRequest) -> String {
+    // Note: separators are tokenized with adjacent tokens, keep original for accuracy
+    match &tool_request.tool_call {
+        Ok(tool_call) => format!(
+            "{}:{}:{}",
+            tool_request.id, tool_call.name, tool_call.arguments
+        ),
+        Err(e) => format!("{}:{}", tool_request.id, e),
+    }
+}
+
 fn get_tokenizer_blocking() -> Result<Arc<CoreBPE>, String> {
     // For the blocking version, we need to handle the case where the tokenizer hasn't been initialized yet
     if let Some(tokenizer) = TOKENIZER.get() {
@@ -372,6 +390,11 @@ fn get_tokenizer_blocking() -> Result<Arc<CoreBPE>, String> {
     }
 }
 
+/// Whether the bundled tokenizer loads, for environment checks
+pub fn tokenizer_available() -> Result<(), String> {
+    get_tokenizer_blocking().map(|_| ())
+}
+
 /// Factory function for creating async token counters with proper error handling
 pub async fn create_async_token_counter() -> Result<AsyncTokenCounter, String> {
     AsyncTokenCounter::new().await
@@ -693,4 +716,18 @@ mod tests {
             "Longer text should have more tokens"
         );
     }
+
+    #[test]
+    fn test_malformed_tool_requests_are_counted() {
+        let counter = TokenCounter::new();
+        let message = Message::assistant().with_tool_request(
+            "call_1",
+            Err(rmcp::model::ErrorData::invalid_params(
+                "could not parse the arguments",
+                None,
+            )),
+        );
+        assert!(counter.count_message_tokens(&message) > 4);
+        assert!(counter.count_chat_tokens("", &[message], &[]) > 4);
+    }
 }
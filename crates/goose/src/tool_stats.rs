@@ -0,0 +1,347 @@
+//! Per-tool call counts, failures and latency across sessions, so users can see which
+//! extensions earn their place in the context window. Only tool names and timings are
+//! recorded, never arguments or results, and they stay in a local file. The file is
+//! compacted as it grows, keeping only recent calls.
+
+use crate::config::APP_STRATEGY;
+use anyhow::Result;
+use etcetera::{choose_app_strategy, AppStrategy};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+/// Once the file passes this size it is compacted; a record takes around 80 bytes
+const MAX_FILE_BYTES: u64 = 8 * 1024 * 1024;
+/// Calls kept by a compaction, the newest first, which leaves the file about half full
+const KEEP_RECORDS: usize = 50_000;
+/// Calls older than this are dropped by a compaction
+const RETENTION_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ToolCallRecord {
+    timestamp: i64,
+    tool: String,
+    duration_ms: u64,
+    success: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUsage {
+    pub tool: String,
+    pub calls: usize,
+    pub failures: usize,
+    /// Share of calls that failed, from 0 to 1
+    pub failure_rate: f64,
+    pub avg_latency_ms: u64,
+    pub p95_latency_ms: u64,
+    /// Unix timestamp (seconds) of the latest call
+    pub last_used: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtensionUsage {
+    pub extension: String,
+    pub calls: usize,
+    pub failures: usize,
+    pub failure_rate: f64,
+    pub avg_latency_ms: u64,
+    /// Tools that were called, most called first
+    pub tools: Vec<ToolUsage>,
+    /// Tools the extension currently offers that were never called
+    pub unused_tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStatsReport {
+    pub total_calls: usize,
+    /// Unix timestamp (seconds) of the oldest recorded call
+    pub since: Option<i64>,
+    /// Most called first; extensions that were never called come last
+    pub extensions: Vec<ExtensionUsage>,
+}
+
+/// Extension a tool belongs to; tools are named `<extension>__<tool>`, and frontend tools
+/// have no prefix
+fn extension_of(tool: &str) -> &str {
+    tool.split_once("__")
+        .map(|(extension, _)| extension)
+        .unwrap_or("frontend")
+}
+
+fn failure_rate(failures: usize, calls: usize) -> f64 {
+    if calls == 0 {
+        0.0
+    } else {
+        failures as f64 / calls as f64
+    }
+}
+
+fn tool_usage(tool: String, records: &[&ToolCallRecord]) -> ToolUsage {
+    let mut latencies: Vec<u64> = records.iter().map(|r| r.duration_ms).collect();
+    latencies.sort_unstable();
+    let calls = records.len();
+    let failures = records.iter().filter(|r| !r.success).count();
+    let p95_index = (calls * 95).div_ceil(100).saturating_sub(1);
+    ToolUsage {
+        tool,
+        calls,
+        failures,
+        failure_rate: failure_rate(failures, calls),
+        avg_latency_ms: latencies.iter().sum::<u64>() / calls.max(1) as u64,
+        p95_latency_ms: latencies.get(p95_index).copied().unwrap_or_default(),
+        last_used: records.iter().map(|r| r.timestamp).max(),
+    }
+}
+
+/// Aggregate `records` per extension and tool; `available_tools` are the tools offered right
+/// now, so the ones never called show up too
+fn summarize(records: &[ToolCallRecord], available_tools: &[String]) -> ToolStatsReport {
+    let mut by_tool: BTreeMap<&str, Vec<&ToolCallRecord>> = BTreeMap::new();
+    for record in records {
+        by_tool.entry(&record.tool).or_default().push(record);
+    }
+
+    let mut extensions: BTreeMap<&str, ExtensionUsage> = BTreeMap::new();
+    for (tool, tool_records) in &by_tool {
+        let usage = tool_usage(tool.to_string(), tool_records);
+        let extension = extensions
+            .entry(extension_of(tool))
+            .or_insert_with(|| ExtensionUsage {
+                extension: extension_of(tool).to_string(),
+                ..Default::default()
+            });
+        extension.calls += usage.calls;
+        extension.failures += usage.failures;
+        extension.avg_latency_ms += usage.avg_latency_ms * usage.calls as u64;
+        extension.tools.push(usage);
+    }
+    for tool in available_tools {
+        if by_tool.contains_key(tool.as_str()) {
+            continue;
+        }
+        extensions
+            .entry(extension_of(tool))
+            .or_insert_with(|| ExtensionUsage {
+                extension: extension_of(tool).to_string(),
+                ..Default::default()
+            })
+            .unused_tools
+            .push(tool.clone());
+    }
+
+    let mut extensions: Vec<ExtensionUsage> = extensions
+        .into_values()
+        .map(|mut extension| {
+            extension.failure_rate = failure_rate(extension.failures, extension.calls);
+            extension.avg_latency_ms /= extension.calls.max(1) as u64;
+            extension.tools.sort_by(|a, b| b.calls.cmp(&a.calls));
+            extension.unused_tools.sort();
+            extension
+        })
+        .collect();
+    extensions.sort_by(|a, b| b.calls.cmp(&a.calls));
+
+    ToolStatsReport {
+        total_calls: records.len(),
+        since: records.iter().map(|r| r.timestamp).min(),
+        extensions,
+    }
+}
+
+pub struct ToolStats {
+    path: PathBuf,
+}
+
+impl ToolStats {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Tool stats stored in the goose data dir
+    pub fn from_default_path() -> Result<Self> {
+        let path = choose_app_strategy(APP_STRATEGY.clone())?
+            .data_dir()
+            .join("tool_stats.jsonl");
+        Ok(Self::new(path))
+    }
+
+    pub fn record(&self, tool: &str, duration: Duration, success: bool) -> Result<()> {
+        let now = chrono::Utc::now().timestamp();
+        let record = ToolCallRecord {
+            timestamp: now,
+            tool: tool.to_string(),
+            duration_ms: duration.as_millis() as u64,
+            success,
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+        if file.metadata()?.len() > MAX_FILE_BYTES {
+            self.compact(now, KEEP_RECORDS)?;
+        }
+        Ok(())
+    }
+
+    fn read_records(&self) -> Result<Vec<ToolCallRecord>> {
+        let mut records = Vec::new();
+        if self.path.exists() {
+            for line in BufReader::new(fs::File::open(&self.path)?).lines() {
+                if let Ok(record) = serde_json::from_str::<ToolCallRecord>(&line?) {
+                    records.push(record);
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    /// Rewrite the file with at most the newest `keep` calls of the retention window.
+    /// Records are appended in order, so the newest are at the end.
+    fn compact(&self, now: i64, keep: usize) -> Result<()> {
+        let cutoff = now - RETENTION_DAYS * 24 * 60 * 60;
+        let mut records = self.read_records()?;
+        records.retain(|record| record.timestamp >= cutoff);
+        let dropped = records.len().saturating_sub(keep);
+
+        let temp_file = self.path.with_extension("jsonl.tmp");
+        {
+            let mut writer = BufWriter::new(fs::File::create(&temp_file)?);
+            for record in &records[dropped..] {
+                writeln!(writer, "{}", serde_json::to_string(record)?)?;
+            }
+            writer.flush()?;
+        }
+        fs::rename(&temp_file, &self.path)?;
+        Ok(())
+    }
+
+    /// Usage per extension and tool; `available_tools` are listed as unused when they were
+    /// never called
+    pub fn report(&self, available_tools: &[String]) -> Result<ToolStatsReport> {
+        Ok(summarize(&self.read_records()?, available_tools))
+    }
+
+    /// The file records are kept in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Delete everything recorded
+    pub fn clear(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Record a finished tool call; never fails the caller
+pub fn record(tool: &str, duration: Duration, success: bool) {
+    let result =
+        ToolStats::from_default_path().and_then(|stats| stats.record(tool, duration, success));
+    if let Err(e) = result {
+        tracing::debug!("Failed to record tool call: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_report_aggregates_per_extension_and_tool() -> Result<()> {
+        let dir = tempdir()?;
+        let stats = ToolStats::new(dir.path().join("tool_stats.jsonl"));
+        for (tool, ms, success) in [
+            ("developer__shell", 100, true),
+            ("developer__shell", 300, false),
+            ("developer__text_editor", 50, true),
+            ("jira__search", 1_000, true),
+        ] {
+            stats.record(tool, Duration::from_millis(ms), success)?;
+        }
+
+        let available = vec![
+            "developer__shell".to_string(),
+            "developer__screen_capture".to_string(),
+            "slack__post".to_string(),
+        ];
+        let report = stats.report(&available)?;
+        assert_eq!(report.total_calls, 4);
+        assert!(report.since.is_some());
+
+        let names: Vec<&str> = report
+            .extensions
+            .iter()
+            .map(|e| e.extension.as_str())
+            .collect();
+        assert_eq!(names, vec!["developer", "jira", "slack"]);
+
+        let developer = &report.extensions[0];
+        assert_eq!(developer.calls, 3);
+        assert_eq!(developer.failures, 1);
+        assert_eq!(developer.avg_latency_ms, 150);
+        assert_eq!(developer.tools[0].tool, "developer__shell");
+        assert_eq!(developer.tools[0].failure_rate, 0.5);
+        assert_eq!(developer.tools[0].p95_latency_ms, 300);
+        assert_eq!(developer.unused_tools, vec!["developer__screen_capture"]);
+
+        let slack = &report.extensions[2];
+        assert_eq!(slack.calls, 0);
+        assert!(slack.tools.is_empty());
+        assert_eq!(slack.unused_tools, vec!["slack__post"]);
+
+        stats.clear()?;
+        assert_eq!(stats.report(&[])?.total_calls, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compaction_keeps_recent_calls() -> Result<()> {
+        let dir = tempdir()?;
+        let stats = ToolStats::new(dir.path().join("tool_stats.jsonl"));
+        let now = chrono::Utc::now().timestamp();
+        let day = 24 * 60 * 60;
+        let lines: Vec<String> = [
+            ("developer__shell", now - (RETENTION_DAYS + 1) * day),
+            ("developer__shell", now - 3 * day),
+            ("jira__search", now - 2 * day),
+            ("slack__post", now - day),
+        ]
+        .iter()
+        .map(|(tool, timestamp)| {
+            serde_json::to_string(&ToolCallRecord {
+                timestamp: *timestamp,
+                tool: tool.to_string(),
+                duration_ms: 10,
+                success: true,
+            })
+            .unwrap()
+        })
+        .collect();
+        fs::write(stats.path(), format!("{}\n", lines.join("\n")))?;
+
+        stats.compact(now, 10)?;
+        assert_eq!(stats.report(&[])?.total_calls, 3);
+
+        stats.compact(now, 2)?;
+        let report = stats.report(&[])?;
+        assert_eq!(report.total_calls, 2);
+        assert_eq!(report.since, Some(now - 2 * day));
+        Ok(())
+    }
+}
@@ -864,6 +864,8 @@ mod retry_tests {
             schedule_id: None,
             execution_mode: None,
             max_turns: None,
+            run_limits: Default::default(),
+            critique: None,
             retry_config: Some(retry_config),
         };
 
@@ -1019,22 +1021,11 @@ mod max_turns_tests {
         }
     }
 
-    #[tokio::test]
-    async fn test_max_turns_limit() -> Result<()> {
-        let agent = Agent::new();
-        let provider = Arc::new(MockToolProvider::new());
-        agent.update_provider(provider).await?;
-        // The mock provider will call a non-existent tool, which will fail and allow the loop to continue
-
-        // Create session config with max_turns = 1
-        let session_config = goose::agents::SessionConfig {
-            id: Identifier::Name("test_session".to_string()),
-            working_dir: PathBuf::from("/tmp"),
-            schedule_id: None,
-            execution_mode: None,
-            max_turns: Some(1),
-            retry_config: None,
-        };
+    /// Run one reply with `session_config`, approving any tool calls, and collect the messages
+    async fn run_reply(
+        agent: &Agent,
+        session_config: goose::agents::SessionConfig,
+    ) -> Result<Vec<Message>> {
         let conversation = Conversation::new(vec![Message::user().with_text("Hello")]).unwrap();
 
         let reply_stream = agent
@@ -1067,6 +1058,38 @@ mod max_turns_tests {
                 }
             }
         }
+        Ok(responses)
+    }
+
+    fn last_text(responses: &[Message]) -> String {
+        match responses
+            .last()
+            .and_then(|response| response.content.first())
+        {
+            Some(MessageContent::Text(text_content)) => text_content.text.clone(),
+            _ => panic!("Expected text content in last message"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_turns_limit() -> Result<()> {
+        let agent = Agent::new();
+        let provider = Arc::new(MockToolProvider::new());
+        agent.update_provider(provider).await?;
+        // The mock provider will call a non-existent tool, which will fail and allow the loop to continue
+
+        // Create session config with max_turns = 1
+        let session_config = goose::agents::SessionConfig {
+            id: Identifier::Name("test_session".to_string()),
+            working_dir: PathBuf::from("/tmp"),
+            schedule_id: None,
+            execution_mode: None,
+            max_turns: Some(1),
+            run_limits: Default::default(),
+            critique: None,
+            retry_config: None,
+        };
+        let responses = run_reply(&agent, session_config).await?;
 
         assert!(
             responses.len() >= 1,
@@ -1075,15 +1098,73 @@ mod max_turns_tests {
         );
 
         // Look for the max turns message as the last response
-        let last_response = responses.last().unwrap();
-        let last_content = last_response.content.first().unwrap();
-        if let MessageContent::Text(text_content) = last_content {
-            assert!(text_content.text.contains(
-                "I've reached the maximum number of actions I can do without user input"
-            ));
-        } else {
-            panic!("Expected text content in last message");
-        }
+        assert!(last_text(&responses)
+            .contains("I've reached the maximum number of actions I can do without user input"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_tool_calls_limit() -> Result<()> {
+        let agent = Agent::new();
+        agent
+            .update_provider(Arc::new(MockToolProvider::new()))
+            .await?;
+
+        // Every turn makes one tool call, so the run stops before its second turn
+        let session_config = goose::agents::SessionConfig {
+            id: Identifier::Name("test_session_tool_calls".to_string()),
+            working_dir: PathBuf::from("/tmp"),
+            schedule_id: None,
+            execution_mode: None,
+            max_turns: None,
+            run_limits: goose::agents::RunLimits {
+                max_duration_seconds: None,
+                max_tool_calls: Some(1),
+                max_tokens: None,
+            },
+            critique: None,
+            retry_config: None,
+        };
+        let responses = run_reply(&agent, session_config).await?;
+
+        assert!(last_text(&responses).contains("this run's limit of 1 tool calls"));
+        let tool_requests = responses
+            .iter()
+            .filter(|response| {
+                matches!(
+                    response.content.first(),
+                    Some(MessageContent::ToolRequest(_))
+                )
+            })
+            .count();
+        assert_eq!(tool_requests, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_tokens_limit() -> Result<()> {
+        let agent = Agent::new();
+        agent
+            .update_provider(Arc::new(MockToolProvider::new()))
+            .await?;
+
+        // Every turn uses 15 tokens, so the budget is spent after the first turn
+        let session_config = goose::agents::SessionConfig {
+            id: Identifier::Name("test_session_tokens".to_string()),
+            working_dir: PathBuf::from("/tmp"),
+            schedule_id: None,
+            execution_mode: None,
+            max_turns: None,
+            run_limits: goose::agents::RunLimits {
+                max_tokens: Some(15),
+                ..Default::default()
+            },
+            critique: None,
+            retry_config: None,
+        };
+        let responses = run_reply(&agent, session_config).await?;
+
+        assert!(last_text(&responses).contains("this run's limit of 15 tokens"));
         Ok(())
     }
 }
@@ -363,6 +363,9 @@ impl ScheduleToolTestBuilder {
             current_session_id: None,
             process_start_time: None,
             execution_mode: Some("background".to_string()),
+            run_at: None,
+            depends_on: Vec::new(),
+            environment: Default::default(),
         };
         {
             let mut jobs = self.scheduler.jobs.lock().await;
@@ -411,5 +414,9 @@ pub fn create_test_session_metadata(message_count: usize, working_dir: &str) ->
         accumulated_total_tokens: Some(100),
         accumulated_input_tokens: Some(50),
         accumulated_output_tokens: Some(50),
+        checksum: None,
+        style: None,
+        attached_resources: Vec::new(),
+        comparison_id: None,
     }
 }
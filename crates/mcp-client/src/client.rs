@@ -1,13 +1,21 @@
+use crate::stdio::StdioTransport;
+use mcp_core::protocol::{SESSION_ENDED_TOOL_NAME, SESSION_ID_META_KEY};
 use rmcp::{
     model::{
         CallToolRequest, CallToolRequestParam, CallToolResult, CancelledNotification,
         CancelledNotificationMethod, CancelledNotificationParam, ClientCapabilities, ClientInfo,
-        ClientRequest, GetPromptRequest, GetPromptRequestParam, GetPromptResult, Implementation,
-        InitializeResult, ListPromptsRequest, ListPromptsResult, ListResourcesRequest,
-        ListResourcesResult, ListToolsRequest, ListToolsResult, LoggingMessageNotification,
-        LoggingMessageNotificationMethod, PaginatedRequestParam, ProgressNotification,
-        ProgressNotificationMethod, ProtocolVersion, ReadResourceRequest, ReadResourceRequestParam,
-        ReadResourceResult, RequestId, ServerNotification, ServerResult,
+        ClientRequest, CreateMessageRequestParam, CreateMessageResult, ErrorCode, ErrorData,
+        GetPromptRequest, GetPromptRequestParam, GetPromptResult, Implementation, InitializeResult,
+        JsonObject, ListPromptsRequest, ListPromptsResult, ListResourcesRequest,
+        ListResourcesResult, ListRootsResult, ListToolsRequest, ListToolsResult,
+        LoggingMessageNotification, LoggingMessageNotificationMethod, Meta, PaginatedRequestParam,
+        ProgressNotification, ProgressNotificationMethod, ProgressNotificationParam, ProgressToken,
+        ProtocolVersion, ReadResourceRequest, ReadResourceRequestParam, ReadResourceResult,
+        RequestId, ResourceListChangedNotification, ResourceListChangedNotificationMethod,
+        ResourceUpdatedNotification, ResourceUpdatedNotificationMethod, Root, RootsCapabilities,
+        RootsListChangedNotification, RootsListChangedNotificationMethod, ServerNotification,
+        ServerResult, SubscribeRequest, SubscribeRequestParam, UnsubscribeRequest,
+        UnsubscribeRequestParam,
     },
     service::{
         ClientInitializeError, PeerRequestOptions, RequestHandle, RunningService, ServiceRole,
@@ -15,8 +23,10 @@ use rmcp::{
     transport::IntoTransport,
     ClientHandler, Peer, RoleClient, ServiceError, ServiceExt,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::{sync::Arc, time::Duration};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::{
     mpsc::{self, Sender},
     Mutex,
@@ -41,6 +51,19 @@ pub trait McpClientTrait: Send + Sync {
         cancel_token: CancellationToken,
     ) -> Result<ReadResourceResult, Error>;
 
+    /// Ask the server to send a notification whenever the resource at `uri` changes
+    async fn subscribe_resource(
+        &self,
+        uri: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<(), Error>;
+
+    async fn unsubscribe_resource(
+        &self,
+        uri: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<(), Error>;
+
     async fn list_tools(
         &self,
         next_cursor: Option<String>,
@@ -54,6 +77,22 @@ pub trait McpClientTrait: Send + Sync {
         cancel_token: CancellationToken,
     ) -> Result<CallToolResult, Error>;
 
+    /// Call a tool on behalf of the goose session `session_id`, for servers that keep state
+    /// per session; clients that don't tell sessions apart make a plain call
+    async fn call_tool_in_session(
+        &self,
+        name: &str,
+        arguments: Value,
+        _session_id: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        self.call_tool(name, arguments, cancel_token).await
+    }
+
+    /// Tell the server that the goose session `session_id` has ended, so it can release what it
+    /// kept for it; clients that don't tell sessions apart ignore it
+    async fn end_session(&self, _session_id: &str) {}
+
     async fn list_prompts(
         &self,
         next_cursor: Option<String>,
@@ -69,17 +108,132 @@ pub trait McpClientTrait: Send + Sync {
 
     async fn subscribe(&self) -> mpsc::Receiver<ServerNotification>;
 
+    /// Answer the server's sampling requests with `handler`; clients that don't support
+    /// sampling ignore it
+    async fn set_sampling_handler(&self, _handler: Arc<dyn SamplingHandler>) {}
+
+    /// Answer the server's elicitation requests with `handler`; clients that don't support
+    /// elicitation ignore it
+    async fn set_elicitation_handler(&self, _handler: Arc<dyn ElicitationHandler>) {}
+
+    /// Tell the server which directories it may operate in; clients that don't support roots
+    /// ignore it
+    async fn set_roots(&self, _roots: Vec<Root>) {}
+
     fn get_info(&self) -> Option<&InitializeResult>;
 }
 
+/// Answers sampling requests, where a server asks the host for a completion from its model
+#[async_trait::async_trait]
+pub trait SamplingHandler: Send + Sync {
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+    ) -> Result<CreateMessageResult, ErrorData>;
+}
+
+type SamplingHandlerSlot = Arc<Mutex<Option<Arc<dyn SamplingHandler>>>>;
+
+/// Method of the request a server sends to ask the user for input while it handles a call
+pub const ELICITATION_METHOD: &str = "elicitation/create";
+
+/// What a server asks the user for: a message, and a flat JSON schema for the answer. rmcp
+/// doesn't model elicitation yet, so its messages are defined here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateElicitationRequestParam {
+    pub message: String,
+    pub requested_schema: JsonObject,
+}
+
+/// How the user responded to an elicitation request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitationAction {
+    /// They answered; the answer is in the result's `content`
+    Accept,
+    /// They refused to answer
+    Decline,
+    /// They dismissed the request without choosing
+    Cancel,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreateElicitationResult {
+    pub action: ElicitationAction,
+    /// The answer, shaped by the requested schema; only when it was accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<JsonObject>,
+}
+
+impl CreateElicitationResult {
+    pub fn accepted(content: JsonObject) -> Self {
+        Self {
+            action: ElicitationAction::Accept,
+            content: Some(content),
+        }
+    }
+
+    pub fn declined() -> Self {
+        Self {
+            action: ElicitationAction::Decline,
+            content: None,
+        }
+    }
+
+    pub fn cancelled() -> Self {
+        Self {
+            action: ElicitationAction::Cancel,
+            content: None,
+        }
+    }
+}
+
+/// Answers elicitation requests, where a server asks the user for input in the middle of a call
+#[async_trait::async_trait]
+pub trait ElicitationHandler: Send + Sync {
+    async fn create_elicitation(
+        &self,
+        params: CreateElicitationRequestParam,
+    ) -> CreateElicitationResult;
+}
+
+type ElicitationHandlerSlot = Arc<Mutex<Option<Arc<dyn ElicitationHandler>>>>;
+
+#[derive(Clone)]
 pub struct GooseClient {
     notification_handlers: Arc<Mutex<Vec<Sender<ServerNotification>>>>,
+    sampling_handler: SamplingHandlerSlot,
+    elicitation_handler: ElicitationHandlerSlot,
+    roots: Arc<Mutex<Vec<Root>>>,
 }
 
 impl GooseClient {
-    pub fn new(handlers: Arc<Mutex<Vec<Sender<ServerNotification>>>>) -> Self {
+    pub fn new(
+        handlers: Arc<Mutex<Vec<Sender<ServerNotification>>>>,
+        sampling_handler: SamplingHandlerSlot,
+        elicitation_handler: ElicitationHandlerSlot,
+        roots: Arc<Mutex<Vec<Root>>>,
+    ) -> Self {
         GooseClient {
             notification_handlers: handlers,
+            sampling_handler,
+            elicitation_handler,
+            roots,
+        }
+    }
+
+    /// Answer a server's `elicitation/create` request. rmcp can't route these to a
+    /// `ClientHandler`, so the stdio transport hands them over; without a handler the request
+    /// is declined, and the server carries on without the input.
+    pub async fn create_elicitation(
+        &self,
+        params: CreateElicitationRequestParam,
+    ) -> CreateElicitationResult {
+        let handler = self.elicitation_handler.lock().await.clone();
+        match handler {
+            Some(handler) => handler.create_elicitation(params).await,
+            None => CreateElicitationResult::declined(),
         }
     }
 }
@@ -125,10 +279,77 @@ impl ClientHandler for GooseClient {
             });
     }
 
+    async fn on_resource_updated(
+        &self,
+        params: rmcp::model::ResourceUpdatedNotificationParam,
+        context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        self.notification_handlers
+            .lock()
+            .await
+            .iter()
+            .for_each(|handler| {
+                let _ = handler.try_send(ServerNotification::ResourceUpdatedNotification(
+                    ResourceUpdatedNotification {
+                        params: params.clone(),
+                        method: ResourceUpdatedNotificationMethod,
+                        extensions: context.extensions.clone(),
+                    },
+                ));
+            });
+    }
+
+    async fn on_resource_list_changed(
+        &self,
+        context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        self.notification_handlers
+            .lock()
+            .await
+            .iter()
+            .for_each(|handler| {
+                let _ = handler.try_send(ServerNotification::ResourceListChangedNotification(
+                    ResourceListChangedNotification {
+                        method: ResourceListChangedNotificationMethod,
+                        extensions: context.extensions.clone(),
+                    },
+                ));
+            });
+    }
+
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleClient>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        let handler = self.sampling_handler.lock().await.clone();
+        match handler {
+            Some(handler) => handler.create_message(params).await,
+            None => Err(ErrorData::new(
+                ErrorCode::METHOD_NOT_FOUND,
+                "Sampling is not available",
+                None,
+            )),
+        }
+    }
+
+    async fn list_roots(
+        &self,
+        _context: rmcp::service::RequestContext<rmcp::RoleClient>,
+    ) -> Result<ListRootsResult, ErrorData> {
+        Ok(ListRootsResult {
+            roots: self.roots.lock().await.clone(),
+        })
+    }
+
     fn get_info(&self) -> ClientInfo {
+        let mut capabilities = ClientCapabilities::builder().enable_sampling().build();
+        capabilities.roots = Some(RootsCapabilities {
+            list_changed: Some(true),
+        });
         ClientInfo {
             protocol_version: ProtocolVersion::V_2025_03_26,
-            capabilities: ClientCapabilities::builder().build(),
+            capabilities,
             client_info: Implementation {
                 name: "goose".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_owned(),
@@ -141,6 +362,9 @@ impl ClientHandler for GooseClient {
 pub struct McpClient {
     client: Mutex<RunningService<RoleClient, GooseClient>>,
     notification_subscribers: Arc<Mutex<Vec<mpsc::Sender<ServerNotification>>>>,
+    sampling_handler: SamplingHandlerSlot,
+    elicitation_handler: ElicitationHandlerSlot,
+    roots: Arc<Mutex<Vec<Root>>>,
     server_info: Option<InitializeResult>,
     timeout: std::time::Duration,
 }
@@ -150,6 +374,35 @@ impl McpClient {
         transport: T,
         timeout: std::time::Duration,
     ) -> Result<Self, ClientInitializeError>
+    where
+        T: IntoTransport<RoleClient, E, A>,
+        E: std::error::Error + From<std::io::Error> + Send + Sync + 'static,
+    {
+        Self::connect_with(|_| transport, timeout).await
+    }
+
+    /// Connect to a server over its stdout and stdin. Unlike with `connect`, the server can
+    /// ask the user for input while it handles a call.
+    pub async fn connect_stdio<R, W>(
+        read: R,
+        write: W,
+        timeout: std::time::Duration,
+    ) -> Result<Self, ClientInitializeError>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        Self::connect_with(
+            |client| StdioTransport::new(read, write, client.clone()),
+            timeout,
+        )
+        .await
+    }
+
+    async fn connect_with<T, E, A>(
+        transport: impl FnOnce(&GooseClient) -> T,
+        timeout: std::time::Duration,
+    ) -> Result<Self, ClientInitializeError>
     where
         T: IntoTransport<RoleClient, E, A>,
         E: std::error::Error + From<std::io::Error> + Send + Sync + 'static,
@@ -157,7 +410,17 @@ impl McpClient {
         let notification_subscribers =
             Arc::new(Mutex::new(Vec::<mpsc::Sender<ServerNotification>>::new()));
 
-        let client = GooseClient::new(notification_subscribers.clone());
+        let sampling_handler: SamplingHandlerSlot = Arc::new(Mutex::new(None));
+        let elicitation_handler: ElicitationHandlerSlot = Arc::new(Mutex::new(None));
+        let roots = Arc::new(Mutex::new(Vec::new()));
+
+        let client = GooseClient::new(
+            notification_subscribers.clone(),
+            sampling_handler.clone(),
+            elicitation_handler.clone(),
+            roots.clone(),
+        );
+        let transport = transport(&client);
         let client: rmcp::service::RunningService<rmcp::RoleClient, GooseClient> =
             client.serve(transport).await?;
         let server_info = client.peer_info().cloned();
@@ -165,16 +428,59 @@ impl McpClient {
         Ok(Self {
             client: Mutex::new(client),
             notification_subscribers,
+            sampling_handler,
+            elicitation_handler,
+            roots,
             server_info,
             timeout,
         })
     }
 
+    async fn send_tool_call(
+        &self,
+        name: &str,
+        arguments: Value,
+        session_id: Option<&str>,
+        cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        let arguments = match arguments {
+            Value::Object(map) => Some(map),
+            _ => None,
+        };
+        let mut extensions = Extensions::new();
+        if let Some(session_id) = session_id {
+            let mut meta = Meta::new();
+            meta.0
+                .insert(SESSION_ID_META_KEY.to_string(), session_id.into());
+            extensions.insert(meta);
+        }
+        let res = self
+            .send_request(
+                ClientRequest::CallToolRequest(CallToolRequest {
+                    params: CallToolRequestParam {
+                        name: name.to_string().into(),
+                        arguments,
+                    },
+                    method: Default::default(),
+                    extensions,
+                }),
+                cancel_token,
+            )
+            .await?;
+
+        match res {
+            ServerResult::CallToolResult(result) => Ok(result),
+            _ => Err(ServiceError::UnexpectedResponse),
+        }
+    }
+
     async fn send_request(
         &self,
         request: ClientRequest,
         cancel_token: CancellationToken,
     ) -> Result<ServerResult, Error> {
+        // rmcp puts a fresh progress token in the request's `_meta`, which the server's
+        // progress notifications for it carry back
         let handle = self
             .client
             .lock()
@@ -182,29 +488,92 @@ impl McpClient {
             .send_cancellable_request(request, PeerRequestOptions::no_options())
             .await?;
 
-        await_response(handle, self.timeout, &cancel_token).await
+        let activity = self.subscribe().await;
+        await_response(handle, self.timeout, &cancel_token, activity).await
     }
 }
 
+/// How many times the idle timeout a request may run for in total, however often the server
+/// reports progress
+const MAX_TIMEOUTS_PER_REQUEST: u32 = 10;
+
+/// The progress `notification` reports for the request with `token`, if that is what it is
+fn progress_of<'a>(
+    notification: &'a ServerNotification,
+    token: &ProgressToken,
+) -> Option<&'a ProgressNotificationParam> {
+    match notification {
+        ServerNotification::ProgressNotification(progress)
+            if &progress.params.progress_token == token =>
+        {
+            Some(&progress.params)
+        }
+        _ => None,
+    }
+}
+
+/// The error for a request that ran out of time, with what the server last reported, so the
+/// caller gets the partial progress rather than only that it timed out
+fn timed_out(timeout: Duration, last_progress: Option<&ProgressNotificationParam>) -> ServiceError {
+    let Some(progress) = last_progress else {
+        return ServiceError::Timeout { timeout };
+    };
+    let done = match progress.total {
+        Some(total) => format!("{}/{}", progress.progress, total),
+        None => progress.progress.to_string(),
+    };
+    let message = match &progress.message {
+        Some(message) => format!("{} ({})", done, message),
+        None => done,
+    };
+    ServiceError::McpError(ErrorData::new(
+        ErrorCode::INTERNAL_ERROR,
+        format!(
+            "Request timed out after {:?}; last progress: {}",
+            timeout, message
+        ),
+        serde_json::to_value(progress).ok(),
+    ))
+}
+
+/// Wait for the response to `handle`. The timeout counts from the last progress the server
+/// reported for this request, matched by the progress token rmcp sends in its `_meta`, so
+/// long-running tools that keep reporting are not cut off. They are still cut off after
+/// `MAX_TIMEOUTS_PER_REQUEST` times the timeout.
 async fn await_response(
     handle: RequestHandle<RoleClient>,
     timeout: Duration,
     cancel_token: &CancellationToken,
+    mut activity: mpsc::Receiver<ServerNotification>,
 ) -> Result<<RoleClient as ServiceRole>::PeerResp, ServiceError> {
-    let receiver = handle.rx;
+    let mut receiver = handle.rx;
     let peer = handle.peer;
     let request_id = handle.id;
-    tokio::select! {
-        result = receiver => {
-            result.map_err(|_e| ServiceError::TransportClosed)?
-        }
-        _ = tokio::time::sleep(timeout) => {
-            send_cancel_message(&peer, request_id, Some("timed out".to_owned())).await?;
-            Err(ServiceError::Timeout{timeout})
-        }
-        _ = cancel_token.cancelled() => {
-            send_cancel_message(&peer, request_id, Some("operation cancelled".to_owned())).await?;
-            Err(ServiceError::Cancelled { reason: None })
+    let progress_token = handle.progress_token;
+    let cap = tokio::time::Instant::now() + timeout * MAX_TIMEOUTS_PER_REQUEST;
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+    let mut last_progress = None;
+    loop {
+        tokio::select! {
+            result = &mut receiver => {
+                return result.map_err(|_e| ServiceError::TransportClosed)?;
+            }
+            Some(notification) = activity.recv() => {
+                if let Some(progress) = progress_of(&notification, &progress_token) {
+                    last_progress = Some(progress.clone());
+                    let next = (tokio::time::Instant::now() + timeout).min(cap);
+                    deadline.as_mut().reset(next);
+                }
+            }
+            _ = &mut deadline => {
+                send_cancel_message(&peer, request_id, Some("timed out".to_owned())).await?;
+                return Err(timed_out(timeout, last_progress.as_ref()));
+            }
+            _ = cancel_token.cancelled() => {
+                send_cancel_message(&peer, request_id, Some("operation cancelled".to_owned())).await?;
+                return Err(ServiceError::Cancelled { reason: None });
+            }
         }
     }
 }
@@ -277,15 +646,17 @@ impl McpClientTrait for McpClient {
         }
     }
 
-    async fn list_tools(
+    async fn subscribe_resource(
         &self,
-        cursor: Option<String>,
+        uri: &str,
         cancel_token: CancellationToken,
-    ) -> Result<ListToolsResult, Error> {
+    ) -> Result<(), Error> {
         let res = self
             .send_request(
-                ClientRequest::ListToolsRequest(ListToolsRequest {
-                    params: Some(PaginatedRequestParam { cursor }),
+                ClientRequest::SubscribeRequest(SubscribeRequest {
+                    params: SubscribeRequestParam {
+                        uri: uri.to_string(),
+                    },
                     method: Default::default(),
                     extensions: Default::default(),
                 }),
@@ -294,27 +665,21 @@ impl McpClientTrait for McpClient {
             .await?;
 
         match res {
-            ServerResult::ListToolsResult(result) => Ok(result),
+            ServerResult::EmptyResult(_) => Ok(()),
             _ => Err(ServiceError::UnexpectedResponse),
         }
     }
 
-    async fn call_tool(
+    async fn unsubscribe_resource(
         &self,
-        name: &str,
-        arguments: Value,
+        uri: &str,
         cancel_token: CancellationToken,
-    ) -> Result<CallToolResult, Error> {
-        let arguments = match arguments {
-            Value::Object(map) => Some(map),
-            _ => None,
-        };
+    ) -> Result<(), Error> {
         let res = self
             .send_request(
-                ClientRequest::CallToolRequest(CallToolRequest {
-                    params: CallToolRequestParam {
-                        name: name.to_string().into(),
-                        arguments,
+                ClientRequest::UnsubscribeRequest(UnsubscribeRequest {
+                    params: UnsubscribeRequestParam {
+                        uri: uri.to_string(),
                     },
                     method: Default::default(),
                     extensions: Default::default(),
@@ -324,11 +689,68 @@ impl McpClientTrait for McpClient {
             .await?;
 
         match res {
-            ServerResult::CallToolResult(result) => Ok(result),
+            ServerResult::EmptyResult(_) => Ok(()),
+            _ => Err(ServiceError::UnexpectedResponse),
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        cursor: Option<String>,
+        cancel_token: CancellationToken,
+    ) -> Result<ListToolsResult, Error> {
+        let res = self
+            .send_request(
+                ClientRequest::ListToolsRequest(ListToolsRequest {
+                    params: Some(PaginatedRequestParam { cursor }),
+                    method: Default::default(),
+                    extensions: Default::default(),
+                }),
+                cancel_token,
+            )
+            .await?;
+
+        match res {
+            ServerResult::ListToolsResult(result) => Ok(result),
             _ => Err(ServiceError::UnexpectedResponse),
         }
     }
 
+    async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+        cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        self.send_tool_call(name, arguments, None, cancel_token)
+            .await
+    }
+
+    async fn call_tool_in_session(
+        &self,
+        name: &str,
+        arguments: Value,
+        session_id: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<CallToolResult, Error> {
+        self.send_tool_call(name, arguments, Some(session_id), cancel_token)
+            .await
+    }
+
+    async fn end_session(&self, session_id: &str) {
+        if let Err(e) = self
+            .send_tool_call(
+                SESSION_ENDED_TOOL_NAME,
+                Value::Null,
+                Some(session_id),
+                CancellationToken::new(),
+            )
+            .await
+        {
+            tracing::warn!("Failed to tell the server that the session ended: {}", e);
+        }
+    }
+
     async fn list_prompts(
         &self,
         cursor: Option<String>,
@@ -383,7 +805,86 @@ impl McpClientTrait for McpClient {
 
     async fn subscribe(&self) -> mpsc::Receiver<ServerNotification> {
         let (tx, rx) = mpsc::channel(16);
-        self.notification_subscribers.lock().await.push(tx);
+        let mut subscribers = self.notification_subscribers.lock().await;
+        subscribers.retain(|subscriber| !subscriber.is_closed());
+        subscribers.push(tx);
         rx
     }
+
+    async fn set_sampling_handler(&self, handler: Arc<dyn SamplingHandler>) {
+        *self.sampling_handler.lock().await = Some(handler);
+    }
+
+    async fn set_elicitation_handler(&self, handler: Arc<dyn ElicitationHandler>) {
+        *self.elicitation_handler.lock().await = Some(handler);
+    }
+
+    async fn set_roots(&self, roots: Vec<Root>) {
+        {
+            let mut current = self.roots.lock().await;
+            if *current == roots {
+                return;
+            }
+            *current = roots;
+        }
+        let notification = RootsListChangedNotification {
+            method: RootsListChangedNotificationMethod,
+            extensions: Default::default(),
+        };
+        if let Err(e) = self
+            .client
+            .lock()
+            .await
+            .send_notification(notification.into())
+            .await
+        {
+            tracing::warn!("Failed to notify the server that the roots changed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+
+    fn progress(token: u32, message: Option<&str>) -> ServerNotification {
+        ServerNotification::ProgressNotification(ProgressNotification {
+            method: ProgressNotificationMethod,
+            params: ProgressNotificationParam {
+                progress_token: ProgressToken(NumberOrString::Number(token)),
+                progress: 3.0,
+                total: Some(10.0),
+                message: message.map(str::to_string),
+            },
+            extensions: Default::default(),
+        })
+    }
+
+    #[test]
+    fn test_progress_is_matched_by_token() {
+        let token = ProgressToken(NumberOrString::Number(1));
+        assert!(progress_of(&progress(1, None), &token).is_some());
+        assert!(progress_of(&progress(2, None), &token).is_none());
+    }
+
+    #[test]
+    fn test_timeout_reports_last_progress() {
+        let timeout = Duration::from_secs(5);
+        assert!(matches!(
+            timed_out(timeout, None),
+            ServiceError::Timeout { .. }
+        ));
+
+        let notification = progress(1, Some("indexing"));
+        let token = ProgressToken(NumberOrString::Number(1));
+        let last = progress_of(&notification, &token);
+        match timed_out(timeout, last) {
+            ServiceError::McpError(error) => {
+                assert!(error.message.contains("3/10 (indexing)"));
+                assert!(error.data.is_some());
+            }
+            other => panic!("expected the partial progress, got {:?}", other),
+        }
+    }
 }
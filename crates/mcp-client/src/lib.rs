@@ -1,3 +1,4 @@
 pub mod client;
+mod stdio;
 
 pub use client::{Error, McpClient, McpClientTrait};
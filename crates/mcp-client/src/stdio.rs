@@ -0,0 +1,275 @@
+//! The transport for servers run as child processes. It speaks the same newline-delimited
+//! JSON-RPC as rmcp's own, but answers `elicitation/create` requests itself: rmcp doesn't know
+//! the method, and a message it can't parse ends its connection to the server.
+
+use crate::client::{CreateElicitationRequestParam, GooseClient, ELICITATION_METHOD};
+use rmcp::model::ErrorData;
+use rmcp::service::{RxJsonRpcMessage, TxJsonRpcMessage};
+use rmcp::transport::Transport;
+use rmcp::RoleClient;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::sync::Mutex;
+
+pub struct StdioTransport<R, W> {
+    lines: Lines<BufReader<R>>,
+    writer: Arc<Mutex<W>>,
+    client: GooseClient,
+}
+
+impl<R, W> StdioTransport<R, W>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    pub fn new(read: R, write: W, client: GooseClient) -> Self {
+        Self {
+            lines: BufReader::new(read).lines(),
+            writer: Arc::new(Mutex::new(write)),
+            client,
+        }
+    }
+
+    /// Ask the user in the background and send their answer back under the request's `id`,
+    /// so the server's other messages keep coming in while they think about it
+    fn answer_elicitation(&self, id: Value, params: Option<Value>) {
+        let client = self.client.clone();
+        let writer = Arc::clone(&self.writer);
+        tokio::spawn(async move {
+            let response = match serde_json::from_value::<CreateElicitationRequestParam>(
+                params.unwrap_or_default(),
+            ) {
+                Ok(params) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": client.create_elicitation(params).await,
+                }),
+                Err(e) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": ErrorData::invalid_params(
+                        format!("Invalid elicitation request: {}", e),
+                        None,
+                    ),
+                }),
+            };
+            if let Err(e) = write_line(&writer, &response).await {
+                tracing::warn!("Failed to answer the server's elicitation request: {}", e);
+            }
+        });
+    }
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(
+    writer: &Mutex<W>,
+    message: &Value,
+) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    let mut writer = writer.lock().await;
+    writer.write_all(&line).await?;
+    writer.flush().await
+}
+
+/// Declare elicitation support in the `initialize` request, which rmcp's `ClientCapabilities`
+/// has no field for yet
+fn with_elicitation_capability(mut message: Value) -> Value {
+    if message.get("method").and_then(Value::as_str) == Some("initialize") {
+        if let Some(capabilities) = message
+            .pointer_mut("/params/capabilities")
+            .and_then(Value::as_object_mut)
+        {
+            capabilities.insert("elicitation".to_string(), json!({}));
+        }
+    }
+    message
+}
+
+/// The id and parameters of `message` when it is an elicitation request
+fn elicitation_request(message: &Value) -> Option<(Value, Option<Value>)> {
+    if message.get("method")?.as_str()? != ELICITATION_METHOD {
+        return None;
+    }
+    let id = message.get("id")?.clone();
+    Some((id, message.get("params").cloned()))
+}
+
+impl<R, W> Transport<RoleClient> for StdioTransport<R, W>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    type Error = std::io::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let writer = Arc::clone(&self.writer);
+        let message = serde_json::to_value(item).map(with_elicitation_capability);
+        async move { write_line(&writer, &message?).await }
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<RoleClient>> {
+        loop {
+            let line = match self.lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => return None,
+                Err(e) => {
+                    tracing::error!("Error reading from the server: {}", e);
+                    return None;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message: Value = match serde_json::from_str(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!("Ignoring output from the server that isn't JSON: {}", e);
+                    continue;
+                }
+            };
+            if let Some((id, params)) = elicitation_request(&message) {
+                self.answer_elicitation(id, params);
+                continue;
+            }
+            match serde_json::from_value(message) {
+                Ok(message) => return Some(message),
+                Err(e) => tracing::warn!("Ignoring a message from the server: {}", e),
+            }
+        }
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{
+        CreateElicitationResult, ElicitationAction, ElicitationHandler, McpClient, McpClientTrait,
+    };
+    use std::time::Duration;
+    use tokio::io::{duplex, DuplexStream};
+
+    struct AwsProfilePicker;
+
+    #[async_trait::async_trait]
+    impl ElicitationHandler for AwsProfilePicker {
+        async fn create_elicitation(
+            &self,
+            params: CreateElicitationRequestParam,
+        ) -> CreateElicitationResult {
+            assert_eq!(params.message, "Which AWS profile?");
+            let mut content = serde_json::Map::new();
+            content.insert("profile".to_string(), json!("staging"));
+            CreateElicitationResult::accepted(content)
+        }
+    }
+
+    /// The server end of the pipes: reads the client's lines and writes its own
+    struct MockServer {
+        lines: Lines<BufReader<DuplexStream>>,
+        writer: DuplexStream,
+    }
+
+    impl MockServer {
+        async fn read(&mut self) -> Value {
+            let line = self.lines.next_line().await.unwrap().unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+
+        async fn write(&mut self, message: Value) {
+            let mut line = serde_json::to_vec(&message).unwrap();
+            line.push(b'\n');
+            self.writer.write_all(&line).await.unwrap();
+        }
+
+        async fn elicit(&mut self, id: u32) -> Value {
+            self.write(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": ELICITATION_METHOD,
+                "params": {
+                    "message": "Which AWS profile?",
+                    "requestedSchema": {
+                        "type": "object",
+                        "properties": { "profile": { "type": "string" } },
+                        "required": ["profile"]
+                    }
+                }
+            }))
+            .await;
+            self.read().await
+        }
+    }
+
+    async fn connect() -> (McpClient, MockServer) {
+        let (client_read, server_write) = duplex(64 * 1024);
+        let (server_read, client_write) = duplex(64 * 1024);
+        let mut server = MockServer {
+            lines: BufReader::new(server_read).lines(),
+            writer: server_write,
+        };
+        let handshake = async {
+            let initialize = server.read().await;
+            assert_eq!(initialize["method"], "initialize");
+            assert_eq!(
+                initialize["params"]["capabilities"]["elicitation"],
+                json!({})
+            );
+            server
+                .write(json!({
+                    "jsonrpc": "2.0",
+                    "id": initialize["id"],
+                    "result": {
+                        "protocolVersion": "2025-03-26",
+                        "capabilities": { "tools": {} },
+                        "serverInfo": { "name": "aws", "version": "1.0.0" }
+                    }
+                }))
+                .await;
+            assert_eq!(server.read().await["method"], "notifications/initialized");
+        };
+        let (client, ()) = tokio::join!(
+            McpClient::connect_stdio(client_read, client_write, Duration::from_secs(5)),
+            handshake
+        );
+        (client.unwrap(), server)
+    }
+
+    #[tokio::test]
+    async fn test_elicitation_round_trip() {
+        let (client, mut server) = connect().await;
+
+        // Nobody to ask yet, so the server hears a decline rather than an error
+        let declined = server.elicit(1).await;
+        assert_eq!(declined["id"], 1);
+        assert_eq!(declined["result"], json!({ "action": "decline" }));
+
+        client
+            .set_elicitation_handler(Arc::new(AwsProfilePicker))
+            .await;
+        let accepted = server.elicit(2).await;
+        assert_eq!(accepted["id"], 2);
+        let result: CreateElicitationResult =
+            serde_json::from_value(accepted["result"].clone()).unwrap();
+        assert_eq!(result.action, ElicitationAction::Accept);
+        assert_eq!(result.content.unwrap()["profile"], "staging");
+
+        server
+            .write(json!({ "jsonrpc": "2.0", "id": 3, "method": ELICITATION_METHOD }))
+            .await;
+        let invalid = server.read().await;
+        assert_eq!(invalid["id"], 3);
+        assert!(invalid["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid elicitation request"));
+    }
+}
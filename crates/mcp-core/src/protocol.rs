@@ -10,6 +10,14 @@ pub const METHOD_NOT_FOUND: i32 = -32601;
 pub const INVALID_PARAMS: i32 = -32602;
 pub const INTERNAL_ERROR: i32 = -32603;
 
+/// Key in a tool call's `_meta` holding the id of the goose session it was made in, so servers
+/// that keep state between calls can keep it apart per session
+pub const SESSION_ID_META_KEY: &str = "goose/sessionId";
+
+/// Tool that goose calls on its builtin servers, with the session in `_meta`, once a session has
+/// ended. It is never listed, and servers release whatever they kept for the session.
+pub const SESSION_ENDED_TOOL_NAME: &str = "goose/session_ended";
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeResult {
@@ -10,12 +10,12 @@ use mcp_core::{
     protocol::{
         CallToolResult, Implementation, InitializeResult, ListPromptsResult, ListResourcesResult,
         ListToolsResult, PromptsCapability, ReadResourceResult, ResourcesCapability,
-        ServerCapabilities, ToolsCapability,
+        ServerCapabilities, ToolsCapability, SESSION_ENDED_TOOL_NAME, SESSION_ID_META_KEY,
     },
 };
 use rmcp::model::{
     Content, ErrorData, GetPromptResult, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse,
-    JsonRpcVersion2_0, Prompt, PromptMessage, PromptMessageRole, RequestId, Resource,
+    JsonRpcVersion2_0, Meta, Prompt, PromptMessage, PromptMessageRole, RequestId, Resource,
     ResourceContents,
 };
 use serde_json::Value;
@@ -24,6 +24,16 @@ use tower_service::Service;
 
 use crate::{BoxError, RouterError};
 
+tokio::task_local! {
+    static SESSION_ID: Option<String>;
+}
+
+/// The goose session the tool call being handled was made in, if the client named one. Only
+/// set while the future returned by `Router::call_tool` runs.
+pub fn request_session_id() -> Option<String> {
+    SESSION_ID.try_with(|id| id.clone()).ok().flatten()
+}
+
 /// Builder for configuring and constructing capabilities
 pub struct CapabilitiesBuilder {
     tools: Option<ToolsCapability>,
@@ -102,6 +112,14 @@ pub trait Router: Send + Sync + 'static {
     fn list_prompts(&self) -> Vec<Prompt>;
     fn get_prompt(&self, prompt_name: &str) -> PromptFuture;
 
+    /// Release whatever the router keeps for `session_id`, called once goose ends the session
+    fn session_ended(
+        &self,
+        _session_id: &str,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        Box::pin(async {})
+    }
+
     // Helper method to create base response
     fn create_response(&self, id: RequestId) -> JsonRpcResponse {
         JsonRpcResponse {
@@ -181,8 +199,31 @@ pub trait Router: Send + Sync + 'static {
                 .ok_or_else(|| RouterError::InvalidParams("Missing tool name".into()))?;
 
             let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+            let session_id = req
+                .request
+                .extensions
+                .get::<Meta>()
+                .and_then(|meta| meta.0.get(SESSION_ID_META_KEY))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+
+            if name == SESSION_ENDED_TOOL_NAME {
+                if let Some(session_id) = &session_id {
+                    self.session_ended(session_id).await;
+                }
+                let mut response = self.create_response(req.id);
+                self.set_result(
+                    &mut response,
+                    CallToolResult {
+                        content: Vec::new(),
+                        is_error: None,
+                    },
+                )?;
+                return Ok(response);
+            }
 
-            let result = match self.call_tool(name, arguments, notifier).await {
+            let call = SESSION_ID.scope(session_id, self.call_tool(name, arguments, notifier));
+            let result = match call.await {
                 Ok(result) => CallToolResult {
                     content: result,
                     is_error: None,
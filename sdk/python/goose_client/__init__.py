@@ -0,0 +1,10 @@
+"""Python client for the goose server API.
+
+Everything under ``goose_client.generated`` is produced from the OpenAPI schema by
+``just generate-clients``; ``stream_reply`` covers the streaming ``/reply`` endpoint.
+"""
+
+from .generated import AuthenticatedClient, Client
+from .stream import stream_reply
+
+__all__ = ["AuthenticatedClient", "Client", "stream_reply"]
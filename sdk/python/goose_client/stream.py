@@ -0,0 +1,39 @@
+import json
+from typing import Any, Dict, Iterator, List, Optional, Union
+
+from .generated import AuthenticatedClient, Client
+
+
+def stream_reply(
+    client: Union[AuthenticatedClient, Client],
+    messages: List[Dict[str, Any]],
+    session_working_dir: str,
+    session_id: Optional[str] = None,
+    scheduled_job_id: Optional[str] = None,
+) -> Iterator[Dict[str, Any]]:
+    """POST /v1/reply and yield each MessageEvent until the server sends Finish.
+
+    Events are returned as decoded JSON objects, keyed by their ``type`` field.
+    """
+    body = {
+        "messages": messages,
+        "session_id": session_id,
+        "session_working_dir": session_working_dir,
+        "scheduled_job_id": scheduled_job_id,
+    }
+    with client.get_httpx_client().stream(
+        "POST", "/v1/reply", json=body, headers={"Accept": "text/event-stream"}, timeout=None
+    ) as response:
+        response.raise_for_status()
+        data: List[str] = []
+        for line in response.iter_lines():
+            if line.startswith("data:"):
+                data.append(line[5:].lstrip())
+                continue
+            if line or not data:
+                continue
+            event = json.loads("\n".join(data))
+            data = []
+            yield event
+            if event.get("type") == "Finish":
+                return
@@ -0,0 +1,3 @@
+export * from './generated';
+export { client } from './generated/client.gen';
+export { streamReply } from './stream';
@@ -0,0 +1,53 @@
+import { client } from './generated/client.gen';
+import type { ChatRequest, MessageEvent } from './generated/types.gen';
+
+/**
+ * POST /v1/reply and yield each event of the server-sent stream until the server sends Finish
+ * or the stream closes. Uses the base URL and headers configured on the generated client.
+ */
+export async function* streamReply(
+  body: ChatRequest,
+  signal?: AbortSignal
+): AsyncGenerator<MessageEvent> {
+  const config = client.getConfig();
+  const headers = new Headers(config.headers as HeadersInit | undefined);
+  headers.set('Content-Type', 'application/json');
+  headers.set('Accept', 'text/event-stream');
+
+  const response = await fetch(`${config.baseUrl}/v1/reply`, {
+    method: 'POST',
+    headers,
+    body: JSON.stringify(body),
+    signal,
+  });
+  if (!response.ok || !response.body) {
+    throw new Error(`reply failed: ${response.status} ${response.statusText}`);
+  }
+
+  const reader = response.body.pipeThrough(new TextDecoderStream()).getReader();
+  let buffer = '';
+  for (;;) {
+    const { value, done } = await reader.read();
+    if (done) return;
+    buffer += value;
+
+    let end: number;
+    while ((end = buffer.indexOf('\n\n')) !== -1) {
+      const frame = buffer.slice(0, end);
+      buffer = buffer.slice(end + 2);
+      const data = frame
+        .split('\n')
+        .filter((line) => line.startsWith('data:'))
+        .map((line) => line.slice(5).trimStart())
+        .join('\n');
+      if (!data) continue;
+
+      const event = JSON.parse(data) as MessageEvent;
+      yield event;
+      if (event.type === 'Finish') {
+        await reader.cancel();
+        return;
+      }
+    }
+  }
+}